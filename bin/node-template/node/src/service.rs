@@ -1,7 +1,7 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
 use futures::FutureExt;
-use node_template_runtime::{self, opaque::Block, RuntimeApi};
+use node_template_runtime::{self, opaque::Block, Balance, RuntimeApi};
 use sc_client_api::{Backend, BlockBackend};
 use sc_consensus_aura::{ImportQueueParams, SlotProportion, StartAuraParams};
 use sc_consensus_grandpa::SharedVoterState;
@@ -224,6 +224,11 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 		})
 	};
 
+	let extrinsic_fee_provider =
+		pallet_transaction_payment_rpc::ExtrinsicFeeProvider::<_, Block, Balance>::new(
+			client.clone(),
+		);
+
 	let _rpc_handlers = sc_service::spawn_tasks(sc_service::SpawnTasksParams {
 		network: network.clone(),
 		client: client.clone(),
@@ -237,6 +242,7 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 		sync_service: sync_service.clone(),
 		config,
 		telemetry: telemetry.as_mut(),
+		extrinsic_fee_provider: Some(Arc::new(extrinsic_fee_provider)),
 	})?;
 
 	if role.is_authority() {
@@ -30,8 +30,8 @@ use std::{borrow::Cow, collections::HashMap, pin::Pin, sync::Arc};
 use node_primitives::Block;
 use node_testing::bench::{BenchDb, BlockType, DatabaseType, KeyTypes};
 use sc_transaction_pool_api::{
-	ImportNotificationStream, PoolFuture, PoolStatus, ReadyTransactions, TransactionFor,
-	TransactionSource, TransactionStatusStreamFor, TxHash,
+	BlockHash, ImportNotificationStream, PoolEventStream, PoolFuture, PoolStatus,
+	ReadyTransactions, TransactionFor, TransactionSource, TransactionStatusStreamFor, TxHash,
 };
 use sp_consensus::{Environment, Proposer};
 use sp_inherents::InherentDataProvider;
@@ -290,6 +290,10 @@ impl sc_transaction_pool_api::TransactionPool for Transactions {
 		unimplemented!()
 	}
 
+	fn pool_events(&self) -> PoolEventStream<TxHash<Self>, BlockHash<Self>> {
+		unimplemented!()
+	}
+
 	fn on_broadcasted(&self, _propagations: HashMap<TxHash<Self>, Vec<String>>) {
 		unimplemented!()
 	}
@@ -301,4 +305,16 @@ impl sc_transaction_pool_api::TransactionPool for Transactions {
 	fn ready_transaction(&self, _hash: &TxHash<Self>) -> Option<Arc<Self::InPoolTransaction>> {
 		unimplemented!()
 	}
+
+	fn futures(&self) -> Vec<Arc<Self::InPoolTransaction>> {
+		unimplemented!()
+	}
+
+	fn ban_transactions(&self, _hashes: &[TxHash<Self>]) -> Vec<Arc<Self::InPoolTransaction>> {
+		unimplemented!()
+	}
+
+	fn ban_senders(&self, _senders: Vec<Vec<u8>>) -> Vec<Arc<Self::InPoolTransaction>> {
+		unimplemented!()
+	}
 }
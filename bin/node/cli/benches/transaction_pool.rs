@@ -34,7 +34,7 @@ use sc_transaction_pool::PoolLimit;
 use sc_transaction_pool_api::{TransactionPool as _, TransactionSource, TransactionStatus};
 use sp_core::{crypto::Pair, sr25519};
 use sp_keyring::Sr25519Keyring;
-use sp_runtime::{generic::BlockId, OpaqueExtrinsic};
+use sp_runtime::{generic::BlockId, OpaqueExtrinsic, Percent};
 use tokio::runtime::Handle;
 
 fn new_node(tokio_handle: Handle) -> node_cli::service::NewFullBase {
@@ -59,7 +59,13 @@ fn new_node(tokio_handle: Handle) -> node_cli::service::NewFullBase {
 			ready: PoolLimit { count: 100_000, total_bytes: 100 * 1024 * 1024 },
 			future: PoolLimit { count: 100_000, total_bytes: 100 * 1024 * 1024 },
 			reject_future_transactions: false,
+			future_limit_per_sender: None,
+			nonce_len: 4,
 			ban_time: Duration::from_secs(30 * 60),
+			replacement_priority_bump: Percent::zero(),
+			memory_budget: None,
+			persistence_path: None,
+			selective_revalidation: None,
 		},
 		network: network_config,
 		keystore: KeystoreConfig::InMemory,
@@ -70,18 +76,43 @@ fn new_node(tokio_handle: Handle) -> node_cli::service::NewFullBase {
 		chain_spec: spec,
 		wasm_method: Default::default(),
 		rpc_addr: None,
+		grpc_addr: None,
+		grpc_max_connections: Default::default(),
+		health_addr: None,
+		health_max_finality_lag: None,
 		rpc_max_connections: Default::default(),
 		rpc_cors: None,
+		rpc_cors_policy: None,
 		rpc_methods: Default::default(),
 		rpc_max_request_size: Default::default(),
 		rpc_max_response_size: Default::default(),
+		rpc_response_limits: Default::default(),
+		rpc_batch_limits: Default::default(),
 		rpc_id_provider: Default::default(),
 		rpc_max_subs_per_conn: Default::default(),
+		rpc_ping_interval: None,
+		rpc_http_compression: false,
+		rpc_call_timeout: None,
+		rpc_max_broadcasts: 64,
+		rpc_operation_cache_ttl: None,
+		rpc_operation_coalescing: true,
+		rpc_operation_max_body_chunk_size: None,
+		rpc_operation_storage_timeout: None,
+		rpc_operation_call_timeout: None,
+		rpc_operation_body_timeout: None,
+		rpc_pin_eviction: None,
+		rpc_max_pinned_blocks_per_subscription: 512,
+		rpc_max_follow_subscriptions: None,
+		rpc_qos: None,
+		rpc_audit_sink: None,
+		rpc_authenticator: None,
+		rpc_request_hooks: Default::default(),
 		rpc_port: 9944,
 		prometheus_config: None,
 		telemetry_endpoints: None,
 		default_heap_pages: None,
 		offchain_worker: OffchainWorkerConfig { enabled: true, indexing_enabled: false },
+		extrinsic_index: false,
 		force_authoring: false,
 		disable_grandpa: false,
 		dev_key_seed: Some(Sr25519Keyring::Alice.to_seed()),
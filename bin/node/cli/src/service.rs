@@ -27,7 +27,7 @@ use frame_system_rpc_runtime_api::AccountNonceApi;
 use futures::prelude::*;
 use kitchensink_runtime::RuntimeApi;
 use node_executor::ExecutorDispatch;
-use node_primitives::Block;
+use node_primitives::{Balance, Block};
 use sc_client_api::{Backend, BlockBackend};
 use sc_consensus_babe::{self, SlotProportion};
 use sc_executor::NativeElseWasmExecutor;
@@ -233,6 +233,7 @@ pub fn new_partial(
 			registry: config.prometheus_registry(),
 			telemetry: telemetry.as_ref().map(|x| x.handle()),
 			offchain_tx_pool_factory: OffchainTransactionPoolFactory::new(transaction_pool.clone()),
+			pre_verifier: Some(Arc::new(sc_consensus::CheckExtrinsicsRoot)),
 		})?;
 
 	let import_setup = (block_import, grandpa_link, babe_link);
@@ -400,6 +401,11 @@ pub fn new_full_base(
 	let prometheus_registry = config.prometheus_registry().cloned();
 	let enable_offchain_worker = config.offchain_worker.enabled;
 
+	let extrinsic_fee_provider =
+		pallet_transaction_payment_rpc::ExtrinsicFeeProvider::<_, Block, Balance>::new(
+			client.clone(),
+		);
+
 	let rpc_handlers = sc_service::spawn_tasks(sc_service::SpawnTasksParams {
 		config,
 		backend: backend.clone(),
@@ -413,6 +419,7 @@ pub fn new_full_base(
 		tx_handler_controller,
 		sync_service: sync_service.clone(),
 		telemetry: telemetry.as_mut(),
+		extrinsic_fee_provider: Some(Arc::new(extrinsic_fee_provider)),
 	})?;
 
 	if let Some(hwbench) = hwbench {
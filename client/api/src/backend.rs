@@ -33,6 +33,7 @@ use sp_state_machine::{
 	OffchainChangesCollection, StorageCollection, StorageIterator,
 };
 use sp_storage::{ChildInfo, StorageData, StorageKey};
+use sp_trie::{cache::TrieCacheHitStats, MerkleValue};
 
 use crate::{blockchain::Backend as BlockchainBackend, UsageInfo};
 
@@ -470,6 +471,23 @@ pub trait StorageProvider<Block: BlockT, B: Backend<Block>> {
 		child_info: &ChildInfo,
 		key: &StorageKey,
 	) -> sp_blockchain::Result<Option<Block::Hash>>;
+
+	/// Given a block's `Hash` and a key, return the merkle value of the closest descendant
+	/// node of the key in that block.
+	fn closest_merkle_value(
+		&self,
+		hash: Block::Hash,
+		key: &StorageKey,
+	) -> sp_blockchain::Result<Option<MerkleValue<Block::Hash>>>;
+
+	/// Given a block's `Hash`, a key and a child storage key, return the merkle value of the
+	/// closest descendant node of the key in that block.
+	fn child_closest_merkle_value(
+		&self,
+		hash: Block::Hash,
+		child_info: &ChildInfo,
+		key: &StorageKey,
+	) -> sp_blockchain::Result<Option<MerkleValue<Block::Hash>>>;
 }
 
 /// Client backend.
@@ -551,12 +569,21 @@ pub trait Backend<Block: BlockT>: AuxStore + Send + Sync {
 	/// Returns current usage statistics.
 	fn usage_info(&self) -> Option<UsageInfo>;
 
+	/// Returns the hits and misses of the shared trie cache, if the backend has one.
+	fn trie_cache_hit_stats(&self) -> Option<TrieCacheHitStats> {
+		None
+	}
+
 	/// Returns a handle to offchain storage.
 	fn offchain_storage(&self) -> Option<Self::OffchainStorage>;
 
 	/// Pin the block to keep body, justification and state available after pruning.
 	/// Number of pins are reference counted. Users need to make sure to perform
 	/// one call to [`Self::unpin_block`] per call to [`Self::pin_block`].
+	///
+	/// The guarantee also holds for blocks that are retracted from the best chain, including
+	/// the blocks of forks that are discarded when a competing block is finalized. While the
+	/// block is pinned, it can be pinned again and its state can be queried.
 	fn pin_block(&self, hash: Block::Hash) -> sp_blockchain::Result<()>;
 
 	/// Unpin the block to allow pruning.
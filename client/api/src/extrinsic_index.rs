@@ -0,0 +1,180 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Optional index of the extrinsics of the imported blocks, mapping the extrinsic hashes to
+//! the blocks including them.
+//!
+//! The index is stored in the auxiliary storage. Each extrinsic hash maps to the hashes of the
+//! blocks including the extrinsic and its index in these blocks, and each indexed block maps
+//! to the hashes of its extrinsics, so that the block is removed from the index when pruned.
+
+use crate::backend::AuxStore;
+use codec::{Decode, Encode};
+use sp_blockchain::{Error, Result};
+use sp_runtime::traits::{Block as BlockT, Hash, HashingFor};
+
+/// The key marking the index as enabled.
+const ENABLED_KEY: &[u8] = b"extrinsic_index_enabled";
+
+/// The prefix of the keys of the extrinsics.
+const EXTRINSIC_PREFIX: &[u8] = b"extrinsic_index_extrinsic";
+
+/// The prefix of the keys of the indexed blocks.
+const BLOCK_PREFIX: &[u8] = b"extrinsic_index_block";
+
+/// The hash of a block including an extrinsic, and the index of the extrinsic in the block.
+pub type ExtrinsicLocation<Hash> = (Hash, u32);
+
+fn key(prefix: &[u8], hash: &impl Encode) -> Vec<u8> {
+	let mut key = prefix.to_vec();
+	hash.encode_to(&mut key);
+	key
+}
+
+fn load<T: Decode>(store: &impl AuxStore, key: &[u8]) -> Result<Option<T>> {
+	store
+		.get_aux(key)?
+		.map(|value| {
+			T::decode(&mut &value[..]).map_err(|e| {
+				Error::Backend(format!("Error decoding the extrinsic index entry: {}", e))
+			})
+		})
+		.transpose()
+}
+
+/// Mark the index as enabled, until it is disabled.
+pub fn enable(store: &impl AuxStore) -> Result<()> {
+	store.insert_aux(&[(ENABLED_KEY, &[1u8][..])], &[])
+}
+
+/// Mark the index as disabled.
+///
+/// The blocks imported while the index is disabled are not indexed, so it is not queried
+/// until enabled again.
+pub fn disable(store: &impl AuxStore) -> Result<()> {
+	store.insert_aux(&[], &[ENABLED_KEY])
+}
+
+/// Returns `true` if the index has been enabled.
+pub fn is_enabled(store: &impl AuxStore) -> Result<bool> {
+	Ok(store.get_aux(ENABLED_KEY)?.is_some())
+}
+
+/// Add the extrinsics of the block to the index.
+///
+/// An already indexed block is left untouched.
+pub fn index_block<Block: BlockT>(
+	store: &impl AuxStore,
+	hash: Block::Hash,
+	extrinsics: &[Block::Extrinsic],
+) -> Result<()> {
+	let block_key = key(BLOCK_PREFIX, &hash);
+	if store.get_aux(&block_key)?.is_some() {
+		return Ok(())
+	}
+
+	let extrinsic_hashes = extrinsics
+		.iter()
+		.map(HashingFor::<Block>::hash_of)
+		.collect::<Vec<Block::Hash>>();
+	let mut entries = Vec::with_capacity(extrinsic_hashes.len() + 1);
+	for (index, extrinsic_hash) in extrinsic_hashes.iter().enumerate() {
+		let extrinsic_key = key(EXTRINSIC_PREFIX, extrinsic_hash);
+		let mut locations =
+			load::<Vec<ExtrinsicLocation<Block::Hash>>>(store, &extrinsic_key)?.unwrap_or_default();
+		locations.push((hash, index as u32));
+		entries.push((extrinsic_key, locations.encode()));
+	}
+	entries.push((block_key, extrinsic_hashes.encode()));
+
+	let entries = entries.iter().map(|(key, value)| (&key[..], &value[..])).collect::<Vec<_>>();
+	store.insert_aux(&entries, &[])
+}
+
+/// Remove the extrinsics of the block from the index.
+pub fn remove_block<Block: BlockT>(store: &impl AuxStore, hash: Block::Hash) -> Result<()> {
+	let block_key = key(BLOCK_PREFIX, &hash);
+	let extrinsic_hashes = match load::<Vec<Block::Hash>>(store, &block_key)? {
+		Some(extrinsic_hashes) => extrinsic_hashes,
+		None => return Ok(()),
+	};
+
+	let mut entries = Vec::new();
+	let mut removed = vec![block_key];
+	for extrinsic_hash in extrinsic_hashes {
+		let extrinsic_key = key(EXTRINSIC_PREFIX, &extrinsic_hash);
+		let mut locations =
+			load::<Vec<ExtrinsicLocation<Block::Hash>>>(store, &extrinsic_key)?.unwrap_or_default();
+		locations.retain(|(block_hash, _)| *block_hash != hash);
+		if locations.is_empty() {
+			removed.push(extrinsic_key);
+		} else {
+			entries.push((extrinsic_key, locations.encode()));
+		}
+	}
+
+	let entries = entries.iter().map(|(key, value)| (&key[..], &value[..])).collect::<Vec<_>>();
+	let removed = removed.iter().map(|key| &key[..]).collect::<Vec<_>>();
+	store.insert_aux(&entries, &removed)
+}
+
+/// Returns the indexed blocks including the extrinsic with the given hash.
+pub fn locations<Block: BlockT>(
+	store: &impl AuxStore,
+	extrinsic_hash: Block::Hash,
+) -> Result<Vec<ExtrinsicLocation<Block::Hash>>> {
+	Ok(load(store, &key(EXTRINSIC_PREFIX, &extrinsic_hash))?.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::in_mem::Backend;
+	use sp_core::H256;
+	use sp_test_primitives::{Block, Extrinsic};
+
+	#[test]
+	fn indexes_and_removes_blocks() {
+		let store = Backend::<Block>::new();
+		let xt1 = Extrinsic::IncludeData(vec![1]);
+		let xt2 = Extrinsic::IncludeData(vec![2]);
+		let xt1_hash = HashingFor::<Block>::hash_of(&xt1);
+		let xt2_hash = HashingFor::<Block>::hash_of(&xt2);
+		let (block1, block2) = (H256::repeat_byte(1), H256::repeat_byte(2));
+
+		assert!(!is_enabled(&store).unwrap());
+		enable(&store).unwrap();
+		assert!(is_enabled(&store).unwrap());
+		disable(&store).unwrap();
+		assert!(!is_enabled(&store).unwrap());
+		enable(&store).unwrap();
+
+		index_block::<Block>(&store, block1, &[xt1.clone(), xt2]).unwrap();
+		index_block::<Block>(&store, block2, &[xt1]).unwrap();
+		// indexing the block again has no effect
+		index_block::<Block>(&store, block2, &[]).unwrap();
+		assert_eq!(locations::<Block>(&store, xt1_hash).unwrap(), vec![(block1, 0), (block2, 0)]);
+		assert_eq!(locations::<Block>(&store, xt2_hash).unwrap(), vec![(block1, 1)]);
+
+		remove_block::<Block>(&store, block1).unwrap();
+		assert_eq!(locations::<Block>(&store, xt1_hash).unwrap(), vec![(block2, 0)]);
+		assert!(locations::<Block>(&store, xt2_hash).unwrap().is_empty());
+		assert!(store.get_aux(&key(EXTRINSIC_PREFIX, &xt2_hash)).unwrap().is_none());
+		assert!(store.get_aux(&key(BLOCK_PREFIX, &block1)).unwrap().is_none());
+	}
+}
@@ -23,6 +23,7 @@ pub mod backend;
 pub mod call_executor;
 pub mod client;
 pub mod execution_extensions;
+pub mod extrinsic_index;
 pub mod in_mem;
 pub mod leaves;
 pub mod notifications;
@@ -19,6 +19,7 @@
 //! Definitions of [`ValueEnum`] types.
 
 use clap::ValueEnum;
+use std::sync::Arc;
 
 /// The instantiation strategy to use in compiled mode.
 #[derive(Debug, Clone, Copy, ValueEnum)]
@@ -185,6 +186,30 @@ impl Into<sc_service::config::RpcMethods> for RpcMethods {
 	}
 }
 
+/// Selection of the `chainHead_follow` subscriptions stopped when the global limit of pinned
+/// blocks is reached.
+#[derive(Debug, Copy, Clone, PartialEq, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum RpcPinEviction {
+	/// Stop the subscriptions that pinned a block for too long.
+	Age,
+	/// Stop the subscription that pinned a block least recently.
+	Lru,
+	/// Stop the subscription releasing the most blocks from the backend.
+	MemoryWeighted,
+}
+
+impl Into<Option<Arc<dyn sc_service::config::PinEvictionPolicy>>> for RpcPinEviction {
+	fn into(self) -> Option<Arc<dyn sc_service::config::PinEvictionPolicy>> {
+		match self {
+			RpcPinEviction::Age => None,
+			RpcPinEviction::Lru => Some(Arc::new(sc_service::config::LruEviction)),
+			RpcPinEviction::MemoryWeighted =>
+				Some(Arc::new(sc_service::config::MemoryWeightedEviction)),
+		}
+	}
+}
+
 /// Database backend
 #[derive(Debug, Clone, PartialEq, Copy, clap::ValueEnum)]
 #[value(rename_all = "lower")]
@@ -17,24 +17,35 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
-	arg_enums::RpcMethods,
+	arg_enums::{RpcMethods, RpcPinEviction},
 	error::{Error, Result},
 	params::{
 		ImportParams, KeystoreParams, NetworkParams, OffchainWorkerParams, SharedParams,
 		TransactionPoolParams,
 	},
 	CliConfiguration, PrometheusParams, RuntimeParams, TelemetryParams,
-	RPC_DEFAULT_MAX_CONNECTIONS, RPC_DEFAULT_MAX_REQUEST_SIZE_MB, RPC_DEFAULT_MAX_RESPONSE_SIZE_MB,
-	RPC_DEFAULT_MAX_SUBS_PER_CONN,
+	GRPC_DEFAULT_MAX_CONNECTIONS, RPC_DEFAULT_MAX_BROADCASTS, RPC_DEFAULT_MAX_CONNECTIONS,
+	RPC_DEFAULT_MAX_PINNED_BLOCKS_PER_SUBSCRIPTION, RPC_DEFAULT_MAX_REQUEST_SIZE_MB,
+	RPC_DEFAULT_MAX_RESPONSE_SIZE_MB, RPC_DEFAULT_MAX_SUBS_PER_CONN,
+	RPC_DEFAULT_PING_INTERVAL_SECS,
 };
 use clap::Parser;
 use regex::Regex;
 use sc_service::{
-	config::{BasePath, PrometheusConfig, TransactionPoolOptions},
+	config::{
+		AuditSink, BasePath, BatchLimits, BearerTokenAuthenticator, CorsPolicy, FileAuditSink,
+		PinEvictionPolicy, PrometheusConfig, QosConfig, ResponseLimits, RpcAuthenticator,
+		TransactionPoolOptions,
+	},
 	ChainSpec, Role,
 };
 use sc_telemetry::TelemetryEndpoints;
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::{
+	net::{IpAddr, Ipv4Addr, SocketAddr},
+	path::PathBuf,
+	sync::Arc,
+	time::Duration,
+};
 
 /// The `run` command used to run a node.
 #[derive(Debug, Clone, Parser)]
@@ -87,14 +98,209 @@ pub struct RunCmd {
 	#[arg(long, default_value_t = RPC_DEFAULT_MAX_RESPONSE_SIZE_MB)]
 	pub rpc_max_response_size: u32,
 
+	/// Set the maximum RPC response payload size of the `state` and `childstate` queries in
+	/// megabytes.
+	///
+	/// Defaults to `--rpc-max-response-size`. The limit is enforced on the HTTP requests, the
+	/// WebSocket connections are limited by the largest of the response limits.
+	#[arg(long, value_name = "MB")]
+	pub rpc_max_response_size_state: Option<u32>,
+
+	/// Set the maximum RPC response payload size of the methods returning the blocks and
+	/// their headers and bodies in megabytes.
+	///
+	/// Defaults to `--rpc-max-response-size`. The limit is enforced on the HTTP requests, the
+	/// WebSocket connections are limited by the largest of the response limits.
+	#[arg(long, value_name = "MB")]
+	pub rpc_max_response_size_blocks: Option<u32>,
+
+	/// Set the maximum number of calls of an RPC batch request sent over HTTP.
+	///
+	/// HTTP only: the batches sent over the WebSocket connections are not limited.
+	#[arg(long, value_name = "COUNT")]
+	pub rpc_max_batch_calls: Option<u32>,
+
+	/// Set the maximum cumulative cost of the calls of an RPC batch request sent over HTTP.
+	///
+	/// The calls to the methods iterating the storage or executing the runtime, e.g.
+	/// `state_getKeysPaged` or `state_call`, cost 10, the other calls cost 1. HTTP only: the
+	/// batches sent over the WebSocket connections are not limited.
+	#[arg(long, value_name = "COST")]
+	pub rpc_max_batch_cost: Option<u32>,
+
 	/// Set the the maximum concurrent subscriptions per connection.
 	#[arg(long, default_value_t = RPC_DEFAULT_MAX_SUBS_PER_CONN)]
 	pub rpc_max_subscriptions_per_connection: u32,
 
+	/// Set the interval in seconds of the pings sent to the WebSocket connections.
+	///
+	/// The dead clients are detected once a ping fails, which releases their subscriptions
+	/// and the blocks pinned by their `chainHead` subscriptions.
+	#[arg(
+		long,
+		value_name = "SECONDS",
+		default_value_t = RPC_DEFAULT_PING_INTERVAL_SECS,
+		value_parser = clap::value_parser!(u64).range(1..)
+	)]
+	pub rpc_ping_interval: u64,
+
+	/// Compress the HTTP responses of the RPC servers with gzip or brotli.
+	///
+	/// The encoding is negotiated with the `Accept-Encoding` header of the requests. The
+	/// messages of the WebSocket connections are not compressed.
+	#[arg(long)]
+	pub rpc_http_compression: bool,
+
+	/// Cancel the `state` and `childstate` RPC calls iterating the storage after this many
+	/// seconds, e.g. `state_getKeysPaged`.
+	///
+	/// The calls are cancelled with an error, such that the slow reads of the database don't
+	/// hold the threads of the RPC servers. The runtime calls are not cancelled.
+	#[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+	pub rpc_call_timeout: Option<u64>,
+
+	/// Set the maximum number of active `transaction_unstable_broadcast` broadcasts.
+	///
+	/// The broadcasts started once the limit is reached are rejected.
+	#[arg(long, default_value_t = RPC_DEFAULT_MAX_BROADCASTS)]
+	pub rpc_max_broadcasts: usize,
+
+	/// Cache the results of identical `chainHead` operations for this many seconds.
+	///
+	/// The bodies, runtime calls and storage items of a block are served from the cache to all
+	/// the `chainHead_follow` subscriptions of a server. The operation cache is disabled by
+	/// default.
+	#[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+	pub rpc_operation_cache_ttl: Option<u64>,
+
+	/// Execute every `chainHead` operation, instead of sharing the result of an identical
+	/// operation of the same subscription that is in progress.
+	#[arg(long)]
+	pub no_rpc_operation_coalescing: bool,
+
+	/// Split the `chainHead_body` results larger than this many bytes of hexadecimal encoded
+	/// extrinsics into several `operationBodyItems` events.
+	///
+	/// An extrinsic larger than the limit is reported alone. The body is reported by a single
+	/// event by default.
+	#[arg(long, value_name = "BYTES", value_parser = clap::value_parser!(u64).range(1..))]
+	pub rpc_body_chunk_size: Option<u64>,
+
+	/// Stop the `chainHead_storage` operations after this many seconds, including the time
+	/// spent waiting for `chainHead_continue`.
+	///
+	/// The stopped operations are reported by the `operationTimeout` event.
+	#[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+	pub rpc_storage_timeout: Option<u64>,
+
+	/// Report the `chainHead_call` operations running for more than this many seconds by the
+	/// `operationTimeout` event.
+	///
+	/// The runtime call is not interrupted, its result is discarded once the timeout is reported.
+	#[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+	pub rpc_runtime_call_timeout: Option<u64>,
+
+	/// Report the `chainHead_body` operations taking more than this many seconds by the
+	/// `operationTimeout` event, in place of the body.
+	///
+	/// The read of the body is not interrupted.
+	#[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+	pub rpc_body_timeout: Option<u64>,
+
+	/// Select the `chainHead_follow` subscriptions stopped when the global limit of pinned
+	/// blocks is reached.
+	/// - `age`: Stops the subscriptions pinning blocks for too long.
+	/// - `lru`: Stops the subscription that pinned a block least recently.
+	/// - `memory-weighted`: Stops the subscription releasing the most blocks from the backend.
+	#[arg(
+		long,
+		value_name = "POLICY",
+		value_enum,
+		ignore_case = true,
+		default_value_t = RpcPinEviction::Age,
+		verbatim_doc_comment
+	)]
+	pub rpc_pin_eviction: RpcPinEviction,
+
+	/// Set the maximum number of blocks pinned by a `chainHead_follow` subscription.
+	///
+	/// A subscription exceeding the limit is stopped.
+	#[arg(long, default_value_t = RPC_DEFAULT_MAX_PINNED_BLOCKS_PER_SUBSCRIPTION)]
+	pub rpc_max_pinned_blocks_per_subscription: usize,
+
+	/// Set the maximum number of running `chainHead_follow` subscriptions per RPC server.
+	///
+	/// The subscriptions started once the limit is reached are rejected. The limit applies to
+	/// all the connections of the server together. The subscriptions are not limited by
+	/// default.
+	#[arg(long, value_name = "COUNT")]
+	pub rpc_max_follow_subscriptions: Option<usize>,
+
+	/// Path to a JSON file defining the quality of service tiers of the RPC servers.
+	///
+	/// Each tier has its own limits for the request rate, the `chainHead` pinned blocks,
+	/// operations and bandwidth and the access to the `archive` methods, and can be assigned
+	/// to the default listen address and to additional listen addresses.
+	///
+	/// The `methods` of a tier allow-list and deny-list the methods or namespaces (ie `author`)
+	/// exposed on its listen addresses.
+	///
+	/// The tier is selected by the listen address: the `apiKeys` of a tier only grant access
+	/// to its listen addresses and cannot select another tier. The request rate limit counts
+	/// the HTTP requests and the WebSocket handshakes, not the calls made over an established
+	/// WebSocket connection.
+	#[arg(long, value_name = "PATH")]
+	pub rpc_qos_config: Option<PathBuf>,
+
+	/// Path to a file to which an audit record is appended for every completed `chainHead`
+	/// operation.
+	///
+	/// Each record is a JSON line with the method, the cost units, the bytes served, the
+	/// duration and the quality of service tier of the operation.
+	#[arg(long, value_name = "PATH")]
+	pub rpc_audit_file: Option<PathBuf>,
+
+	/// Path to a file of the bearer tokens that may call the unsafe RPC methods, one token per
+	/// line.
+	///
+	/// The unsafe methods of the exposed addresses are then available to the requests with an
+	/// `Authorization: Bearer <token>` header, unless `--rpc-methods safe` is passed. The
+	/// WebSocket connections must be authenticated by their handshake.
+	#[arg(long, value_name = "PATH")]
+	pub rpc_auth_tokens: Option<PathBuf>,
+
 	/// Specify JSON-RPC server TCP port.
 	#[arg(long, value_name = "PORT")]
 	pub rpc_port: Option<u16>,
 
+	/// Start the gRPC server listening on this address, e.g. `127.0.0.1:9945`.
+	///
+	/// The gRPC server exposes the blocks, the storage and the submission of transactions,
+	/// see the protobuf definitions of `sc-rpc-grpc`. It is not started by default.
+	#[arg(long, value_name = "ADDR")]
+	pub grpc_addr: Option<SocketAddr>,
+
+	/// Maximum number of connections of the gRPC server.
+	///
+	/// The connections accepted once the limit is reached are closed right away.
+	#[arg(long, value_name = "COUNT", default_value_t = GRPC_DEFAULT_MAX_CONNECTIONS)]
+	pub grpc_max_connections: u32,
+
+	/// Serve the `/health`, `/ready` and `/live` endpoints on this address as well, e.g.
+	/// `0.0.0.0:9616`.
+	///
+	/// The endpoints are always served by the RPC server, the dedicated server lets the
+	/// probes reach the node without exposing the RPC server.
+	#[arg(long, value_name = "ADDR")]
+	pub health_addr: Option<SocketAddr>,
+
+	/// The maximum number of blocks between the finalized block and the best block of a
+	/// node reported as ready by the `/ready` endpoint.
+	///
+	/// The finality lag is not checked by default.
+	#[arg(long, value_name = "BLOCKS")]
+	pub health_max_finality_lag: Option<u32>,
+
 	/// Maximum number of RPC server connections.
 	#[arg(long, value_name = "COUNT", default_value_t = RPC_DEFAULT_MAX_CONNECTIONS)]
 	pub rpc_max_connections: u32,
@@ -107,6 +313,23 @@ pub struct RunCmd {
 	#[arg(long, value_name = "ORIGINS", value_parser = parse_cors)]
 	pub rpc_cors: Option<Cors>,
 
+	/// Path to a JSON file defining the per-origin CORS policy of the RPC servers.
+	///
+	/// The `rules` are evaluated in order, the first rule whose `origin` matches the origin of
+	/// the request applies. An `origin` is either `*` or `<scheme>://<host>[:<port>]`, where the
+	/// host may start with `*.` to match its subdomains and the port may be `*`, e.g.
+	/// `https://*.example.com`. Each rule lists its `allowedHeaders` and `allowedMethods`.
+	#[arg(long, value_name = "PATH", conflicts_with = "rpc_cors")]
+	pub rpc_cors_config: Option<PathBuf>,
+
+	/// Index the extrinsics of the imported blocks by hash, for `chain_getExtrinsicByHash`.
+	///
+	/// Only the blocks imported after the index is enabled are indexed. Starting the node
+	/// without this flag disables the index, and the blocks imported meanwhile are missing from
+	/// it once enabled again.
+	#[arg(long)]
+	pub extrinsic_index: bool,
+
 	/// The human-readable name for this node.
 	/// It's used as network node name.
 	#[arg(long, value_name = "NAME")]
@@ -294,6 +517,10 @@ impl CliConfiguration for RunCmd {
 		Ok(if is_authority { Role::Authority } else { Role::Full })
 	}
 
+	fn extrinsic_index(&self) -> Result<bool> {
+		Ok(self.extrinsic_index)
+	}
+
 	fn force_authoring(&self) -> Result<bool> {
 		// Imply forced authoring on --dev
 		Ok(self.shared_params.dev || self.force_authoring)
@@ -349,6 +576,22 @@ impl CliConfiguration for RunCmd {
 		Ok(Some(SocketAddr::new(interface, self.rpc_port.unwrap_or(default_listen_port))))
 	}
 
+	fn grpc_addr(&self) -> Result<Option<SocketAddr>> {
+		Ok(self.grpc_addr)
+	}
+
+	fn grpc_max_connections(&self) -> Result<u32> {
+		Ok(self.grpc_max_connections)
+	}
+
+	fn health_addr(&self) -> Result<Option<SocketAddr>> {
+		Ok(self.health_addr)
+	}
+
+	fn health_max_finality_lag(&self) -> Result<Option<u32>> {
+		Ok(self.health_max_finality_lag)
+	}
+
 	fn rpc_methods(&self) -> Result<sc_service::config::RpcMethods> {
 		Ok(self.rpc_methods.into())
 	}
@@ -361,10 +604,103 @@ impl CliConfiguration for RunCmd {
 		Ok(self.rpc_max_response_size)
 	}
 
+	fn rpc_batch_limits(&self) -> Result<BatchLimits> {
+		Ok(BatchLimits { max_calls: self.rpc_max_batch_calls, max_cost: self.rpc_max_batch_cost })
+	}
+
+	fn rpc_response_limits(&self) -> Result<ResponseLimits> {
+		Ok(ResponseLimits {
+			state_mb: self.rpc_max_response_size_state,
+			blocks_mb: self.rpc_max_response_size_blocks,
+		})
+	}
+
 	fn rpc_max_subscriptions_per_connection(&self) -> Result<u32> {
 		Ok(self.rpc_max_subscriptions_per_connection)
 	}
 
+	fn rpc_ping_interval(&self) -> Result<Option<Duration>> {
+		Ok(Some(Duration::from_secs(self.rpc_ping_interval)))
+	}
+
+	fn rpc_http_compression(&self) -> Result<bool> {
+		Ok(self.rpc_http_compression)
+	}
+
+	fn rpc_call_timeout(&self) -> Result<Option<Duration>> {
+		Ok(self.rpc_call_timeout.map(Duration::from_secs))
+	}
+
+	fn rpc_max_broadcasts(&self) -> Result<usize> {
+		Ok(self.rpc_max_broadcasts)
+	}
+
+	fn rpc_operation_cache_ttl(&self) -> Result<Option<Duration>> {
+		Ok(self.rpc_operation_cache_ttl.map(Duration::from_secs))
+	}
+
+	fn rpc_operation_coalescing(&self) -> Result<bool> {
+		Ok(!self.no_rpc_operation_coalescing)
+	}
+
+	fn rpc_operation_max_body_chunk_size(&self) -> Result<Option<usize>> {
+		Ok(self.rpc_body_chunk_size.map(|size| size as usize))
+	}
+
+	fn rpc_operation_storage_timeout(&self) -> Result<Option<Duration>> {
+		Ok(self.rpc_storage_timeout.map(Duration::from_secs))
+	}
+
+	fn rpc_operation_call_timeout(&self) -> Result<Option<Duration>> {
+		Ok(self.rpc_runtime_call_timeout.map(Duration::from_secs))
+	}
+
+	fn rpc_operation_body_timeout(&self) -> Result<Option<Duration>> {
+		Ok(self.rpc_body_timeout.map(Duration::from_secs))
+	}
+
+	fn rpc_pin_eviction(&self) -> Result<Option<Arc<dyn PinEvictionPolicy>>> {
+		Ok(self.rpc_pin_eviction.into())
+	}
+
+	fn rpc_max_pinned_blocks_per_subscription(&self) -> Result<usize> {
+		Ok(self.rpc_max_pinned_blocks_per_subscription)
+	}
+
+	fn rpc_max_follow_subscriptions(&self) -> Result<Option<usize>> {
+		Ok(self.rpc_max_follow_subscriptions)
+	}
+
+	fn rpc_cors_policy(&self) -> Result<Option<CorsPolicy>> {
+		self.rpc_cors_config
+			.as_ref()
+			.map(|path| CorsPolicy::from_file(path).map_err(|e| Error::Input(e.to_string())))
+			.transpose()
+	}
+
+	fn rpc_qos(&self) -> Result<Option<QosConfig>> {
+		self.rpc_qos_config
+			.as_ref()
+			.map(|path| QosConfig::from_file(path).map_err(|e| Error::Input(e.to_string())))
+			.transpose()
+	}
+
+	fn rpc_audit_sink(&self) -> Result<Option<Arc<dyn AuditSink>>> {
+		let Some(path) = self.rpc_audit_file.as_ref() else { return Ok(None) };
+		let sink = FileAuditSink::open(path).map_err(|e| {
+			Error::Input(format!("Failed to open the RPC audit file {}: {}", path.display(), e))
+		})?;
+		Ok(Some(Arc::new(sink)))
+	}
+
+	fn rpc_authenticator(&self) -> Result<Option<Arc<dyn RpcAuthenticator>>> {
+		let Some(path) = self.rpc_auth_tokens.as_ref() else { return Ok(None) };
+		let authenticator = BearerTokenAuthenticator::from_file(path).map_err(|e| {
+			Error::Input(format!("Failed to read the RPC tokens file {}: {}", path.display(), e))
+		})?;
+		Ok(Some(Arc::new(authenticator)))
+	}
+
 	fn transaction_pool(&self, is_dev: bool) -> Result<TransactionPoolOptions> {
 		Ok(self.pool_config.transaction_pool(is_dev))
 	}
@@ -26,14 +26,16 @@ use log::warn;
 use names::{Generator, Name};
 use sc_service::{
 	config::{
-		BasePath, Configuration, DatabaseSource, KeystoreConfig, NetworkConfiguration,
-		NodeKeyConfig, OffchainWorkerConfig, PrometheusConfig, PruningMode, Role, RpcMethods,
-		TelemetryEndpoints, TransactionPoolOptions, WasmExecutionMethod,
+		AuditSink, BasePath, BatchLimits, Configuration, CorsPolicy, DatabaseSource,
+		KeystoreConfig, NetworkConfiguration, NodeKeyConfig, OffchainWorkerConfig,
+		PinEvictionPolicy, PrometheusConfig, PruningMode, QosConfig, ResponseLimits, Role,
+		RpcAuthenticator, RpcMethods, TelemetryEndpoints, TransactionPoolOptions,
+		WasmExecutionMethod,
 	},
 	BlocksPruning, ChainSpec, TracingReceiver,
 };
 use sc_tracing::logging::LoggerBuilder;
-use std::{net::SocketAddr, path::PathBuf};
+use std::{net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
 
 /// The maximum number of characters for a node name.
 pub(crate) const NODE_NAME_MAX_LENGTH: usize = 64;
@@ -52,8 +54,16 @@ pub const RPC_DEFAULT_MAX_SUBS_PER_CONN: u32 = 1024;
 pub const RPC_DEFAULT_MAX_REQUEST_SIZE_MB: u32 = 15;
 /// The default max response size in MB.
 pub const RPC_DEFAULT_MAX_RESPONSE_SIZE_MB: u32 = 15;
+/// The default interval of the pings sent to the WebSocket connections, in seconds.
+pub const RPC_DEFAULT_PING_INTERVAL_SECS: u64 = 30;
+/// The default max number of active `transaction_unstable_broadcast` broadcasts.
+pub const RPC_DEFAULT_MAX_BROADCASTS: usize = 64;
+/// The default max number of blocks pinned per `chainHead_follow` subscription.
+pub const RPC_DEFAULT_MAX_PINNED_BLOCKS_PER_SUBSCRIPTION: usize = 512;
 /// The default number of connection..
 pub const RPC_DEFAULT_MAX_CONNECTIONS: u32 = 100;
+/// The default max number of connections of the gRPC server.
+pub const GRPC_DEFAULT_MAX_CONNECTIONS: u32 = 100;
 
 /// Default configuration values used by Substrate
 ///
@@ -295,6 +305,32 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(None)
 	}
 
+	/// Get the gRPC address.
+	///
+	/// By default this is `None`, the gRPC server is not started.
+	fn grpc_addr(&self) -> Result<Option<SocketAddr>> {
+		Ok(None)
+	}
+
+	/// Get the maximum number of gRPC server connections.
+	fn grpc_max_connections(&self) -> Result<u32> {
+		Ok(GRPC_DEFAULT_MAX_CONNECTIONS)
+	}
+
+	/// Get the address of the dedicated server of the health endpoints.
+	///
+	/// By default this is `None`, the health endpoints are served by the RPC servers only.
+	fn health_addr(&self) -> Result<Option<SocketAddr>> {
+		Ok(None)
+	}
+
+	/// Get the maximum finality lag of a ready node, in blocks.
+	///
+	/// By default this is `None`, the finality lag is not checked.
+	fn health_max_finality_lag(&self) -> Result<Option<u32>> {
+		Ok(None)
+	}
+
 	/// Returns the RPC method set to expose.
 	///
 	/// By default this is `RpcMethods::Auto` (unsafe RPCs are denied iff
@@ -315,6 +351,13 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(Some(Vec::new()))
 	}
 
+	/// Get the per-origin CORS policy of the RPC servers, which supersedes the RPC cors.
+	///
+	/// By default this is `None`.
+	fn rpc_cors_policy(&self) -> Result<Option<CorsPolicy>> {
+		Ok(None)
+	}
+
 	/// Get maximum RPC request payload size.
 	fn rpc_max_request_size(&self) -> Result<u32> {
 		Ok(RPC_DEFAULT_MAX_REQUEST_SIZE_MB)
@@ -325,11 +368,134 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(RPC_DEFAULT_MAX_RESPONSE_SIZE_MB)
 	}
 
+	/// Get the maximum RPC response payload sizes of the classes of methods.
+	///
+	/// By default the classes have no limit of their own.
+	fn rpc_response_limits(&self) -> Result<ResponseLimits> {
+		Ok(Default::default())
+	}
+
+	/// Get the limits of the RPC batch requests.
+	///
+	/// By default the batches are not limited.
+	fn rpc_batch_limits(&self) -> Result<BatchLimits> {
+		Ok(Default::default())
+	}
+
 	/// Get maximum number of subscriptions per connection.
 	fn rpc_max_subscriptions_per_connection(&self) -> Result<u32> {
 		Ok(RPC_DEFAULT_MAX_SUBS_PER_CONN)
 	}
 
+	/// Get the interval of the pings sent to the WebSocket connections of the RPC servers.
+	///
+	/// By default this is `None`, the default interval of the RPC servers.
+	fn rpc_ping_interval(&self) -> Result<Option<Duration>> {
+		Ok(None)
+	}
+
+	/// Returns `Ok(true)` if the HTTP responses of the RPC servers should be compressed.
+	///
+	/// By default this is `false`.
+	fn rpc_http_compression(&self) -> Result<bool> {
+		Ok(false)
+	}
+
+	/// Get the maximum time allowed for the RPC calls iterating the storage.
+	///
+	/// By default this is `None`, the calls are not cancelled.
+	fn rpc_call_timeout(&self) -> Result<Option<Duration>> {
+		Ok(None)
+	}
+
+	/// Get the maximum number of active `transaction_unstable_broadcast` broadcasts.
+	fn rpc_max_broadcasts(&self) -> Result<usize> {
+		Ok(RPC_DEFAULT_MAX_BROADCASTS)
+	}
+
+	/// Get the duration for which the results of identical `chainHead` operations are cached.
+	///
+	/// By default this is `None`, the operation cache is disabled.
+	fn rpc_operation_cache_ttl(&self) -> Result<Option<Duration>> {
+		Ok(None)
+	}
+
+	/// Returns `true` if the identical `chainHead` operations in progress are coalesced.
+	///
+	/// By default this is `true`.
+	fn rpc_operation_coalescing(&self) -> Result<bool> {
+		Ok(true)
+	}
+
+	/// Get the maximum number of bytes of the extrinsics reported by a `chainHead_body` event.
+	///
+	/// By default this is `None`, the body is reported by a single event.
+	fn rpc_operation_max_body_chunk_size(&self) -> Result<Option<usize>> {
+		Ok(None)
+	}
+
+	/// Get the maximum duration of a `chainHead_storage` operation.
+	///
+	/// By default this is `None`, the operations are not subject to a timeout.
+	fn rpc_operation_storage_timeout(&self) -> Result<Option<Duration>> {
+		Ok(None)
+	}
+
+	/// Get the maximum duration of a `chainHead_call` operation.
+	///
+	/// By default this is `None`, the operations are not subject to a timeout.
+	fn rpc_operation_call_timeout(&self) -> Result<Option<Duration>> {
+		Ok(None)
+	}
+
+	/// Get the maximum duration of a `chainHead_body` operation.
+	///
+	/// By default this is `None`, the operations are not subject to a timeout.
+	fn rpc_operation_body_timeout(&self) -> Result<Option<Duration>> {
+		Ok(None)
+	}
+
+	/// Get the policy selecting the `chainHead_follow` subscriptions stopped when the global
+	/// limit of pinned blocks is reached.
+	///
+	/// By default this is `None`, the subscriptions pinning blocks for too long are stopped.
+	fn rpc_pin_eviction(&self) -> Result<Option<Arc<dyn PinEvictionPolicy>>> {
+		Ok(None)
+	}
+
+	/// Get the maximum number of blocks pinned by a `chainHead_follow` subscription.
+	fn rpc_max_pinned_blocks_per_subscription(&self) -> Result<usize> {
+		Ok(RPC_DEFAULT_MAX_PINNED_BLOCKS_PER_SUBSCRIPTION)
+	}
+
+	/// Get the maximum number of running `chainHead_follow` subscriptions per RPC server.
+	///
+	/// By default this is `None`, the subscriptions are not limited.
+	fn rpc_max_follow_subscriptions(&self) -> Result<Option<usize>> {
+		Ok(None)
+	}
+
+	/// Get the quality of service tiers of the RPC servers.
+	///
+	/// By default this is `None`.
+	fn rpc_qos(&self) -> Result<Option<QosConfig>> {
+		Ok(None)
+	}
+
+	/// Get the sink of the audit records of the completed `chainHead` operations.
+	///
+	/// By default this is `None`.
+	fn rpc_audit_sink(&self) -> Result<Option<Arc<dyn AuditSink>>> {
+		Ok(None)
+	}
+
+	/// Get the authenticator of the calls to the unsafe RPC methods.
+	///
+	/// By default this is `None`.
+	fn rpc_authenticator(&self) -> Result<Option<Arc<dyn RpcAuthenticator>>> {
+		Ok(None)
+	}
+
 	/// Get the prometheus configuration (`None` if disabled)
 	///
 	/// By default this is `None`.
@@ -367,6 +533,13 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			.unwrap_or_else(|| Ok(OffchainWorkerConfig::default()))
 	}
 
+	/// Returns `Ok(true)` if the extrinsics of the imported blocks should be indexed by hash.
+	///
+	/// By default this is `false`.
+	fn extrinsic_index(&self) -> Result<bool> {
+		Ok(Default::default())
+	}
+
 	/// Returns `Ok(true)` if authoring should be forced
 	///
 	/// By default this is `false`.
@@ -493,19 +666,45 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			wasm_method: self.wasm_method()?,
 			wasm_runtime_overrides: self.wasm_runtime_overrides(),
 			rpc_addr: self.rpc_addr(DCV::rpc_listen_port())?,
+			grpc_addr: self.grpc_addr()?,
+			grpc_max_connections: self.grpc_max_connections()?,
+			health_addr: self.health_addr()?,
+			health_max_finality_lag: self.health_max_finality_lag()?,
 			rpc_methods: self.rpc_methods()?,
 			rpc_max_connections: self.rpc_max_connections()?,
 			rpc_cors: self.rpc_cors(is_dev)?,
+			rpc_cors_policy: self.rpc_cors_policy()?,
 			rpc_max_request_size: self.rpc_max_request_size()?,
 			rpc_max_response_size: self.rpc_max_response_size()?,
+			rpc_response_limits: self.rpc_response_limits()?,
+			rpc_batch_limits: self.rpc_batch_limits()?,
 			rpc_id_provider: None,
 			rpc_max_subs_per_conn: self.rpc_max_subscriptions_per_connection()?,
+			rpc_ping_interval: self.rpc_ping_interval()?,
+			rpc_http_compression: self.rpc_http_compression()?,
+			rpc_call_timeout: self.rpc_call_timeout()?,
+			rpc_max_broadcasts: self.rpc_max_broadcasts()?,
+			rpc_operation_cache_ttl: self.rpc_operation_cache_ttl()?,
+			rpc_operation_coalescing: self.rpc_operation_coalescing()?,
+			rpc_operation_max_body_chunk_size: self.rpc_operation_max_body_chunk_size()?,
+			rpc_operation_storage_timeout: self.rpc_operation_storage_timeout()?,
+			rpc_operation_call_timeout: self.rpc_operation_call_timeout()?,
+			rpc_operation_body_timeout: self.rpc_operation_body_timeout()?,
+			rpc_pin_eviction: self.rpc_pin_eviction()?,
+			rpc_max_pinned_blocks_per_subscription: self
+				.rpc_max_pinned_blocks_per_subscription()?,
+			rpc_max_follow_subscriptions: self.rpc_max_follow_subscriptions()?,
+			rpc_qos: self.rpc_qos()?,
+			rpc_audit_sink: self.rpc_audit_sink()?,
+			rpc_authenticator: self.rpc_authenticator()?,
+			rpc_request_hooks: Default::default(),
 			rpc_port: DCV::rpc_listen_port(),
 			prometheus_config: self
 				.prometheus_config(DCV::prometheus_listen_port(), &chain_spec)?,
 			telemetry_endpoints,
 			default_heap_pages: self.default_heap_pages()?,
 			offchain_worker: self.offchain_worker(&role)?,
+			extrinsic_index: self.extrinsic_index()?,
 			force_authoring: self.force_authoring()?,
 			disable_grandpa: self.disable_grandpa()?,
 			dev_key_seed: self.dev_key_seed(is_dev)?,
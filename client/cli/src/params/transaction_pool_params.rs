@@ -17,7 +17,9 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use clap::Args;
-use sc_service::config::TransactionPoolOptions;
+use sc_service::config::{SelectiveRevalidation, TransactionPoolOptions};
+use sp_runtime::Percent;
+use std::path::PathBuf;
 
 /// Parameters used to create the pool configuration.
 #[derive(Debug, Clone, Args)]
@@ -30,9 +32,51 @@ pub struct TransactionPoolParams {
 	#[arg(long, value_name = "COUNT", default_value_t = 20480)]
 	pub pool_kbytes: usize,
 
+	/// Maximum number of future transactions of a sender in the transaction pool.
+	///
+	/// The future transactions wait for the transactions of the sender with lower nonces.
+	#[arg(long, value_name = "COUNT")]
+	pub pool_future_per_sender: Option<usize>,
+
+	/// Memory budget of the transaction pool, in megabytes.
+	///
+	/// The transactions with the lowest priority, starting with the future ones, are evicted
+	/// when the estimated memory used by the pool exceeds the budget.
+	#[arg(long, value_name = "MB")]
+	pub pool_memory_budget: Option<usize>,
+
 	/// How long a transaction is banned for, if it is considered invalid. Defaults to 1800s.
 	#[arg(long, value_name = "SECONDS")]
 	pub tx_ban_seconds: Option<u64>,
+
+	/// The minimum priority increase, in percent, of a transaction replacing a ready
+	/// transaction of the same sender with the same nonce.
+	///
+	/// The replaced transaction is reported as usurped. By default any strictly higher
+	/// priority replaces the transaction.
+	#[arg(
+		long,
+		value_name = "PERCENT",
+		default_value_t = 0,
+		value_parser = clap::value_parser!(u8).range(0..=100)
+	)]
+	pub tx_replacement_priority_bump: u8,
+
+	/// Persist the transactions of the pool to the given file.
+	///
+	/// The persisted transactions are validated again and resubmitted to the pool when the
+	/// node restarts.
+	#[arg(long, value_name = "PATH")]
+	pub tx_persistence_path: Option<PathBuf>,
+
+	/// Revalidate only the ready transactions sharing a sender with the transactions of the new
+	/// blocks, and all of them every given number of blocks.
+	///
+	/// By default all the ready transactions are revalidated at every block. They are all
+	/// revalidated as well after a reorg or a runtime update. The sender of a transaction is
+	/// identified by its `(sender, nonce)` tags, with the `u32` nonces of the node runtime.
+	#[arg(long, value_name = "BLOCKS", value_parser = clap::value_parser!(u32).range(1..))]
+	pub tx_selective_revalidation_period: Option<u32>,
 }
 
 impl TransactionPoolParams {
@@ -48,6 +92,8 @@ impl TransactionPoolParams {
 		let factor = 10;
 		opts.future.count = self.pool_limit / factor;
 		opts.future.total_bytes = self.pool_kbytes * 1024 / factor;
+		opts.future_limit_per_sender = self.pool_future_per_sender;
+		opts.memory_budget = self.pool_memory_budget.map(|budget| budget * 1024 * 1024);
 
 		opts.ban_time = if let Some(ban_seconds) = self.tx_ban_seconds {
 			std::time::Duration::from_secs(ban_seconds)
@@ -57,6 +103,12 @@ impl TransactionPoolParams {
 			std::time::Duration::from_secs(30 * 60)
 		};
 
+		opts.replacement_priority_bump = Percent::from_percent(self.tx_replacement_priority_bump);
+		opts.persistence_path = self.tx_persistence_path.clone();
+		opts.selective_revalidation = self.tx_selective_revalidation_period.map(|period| {
+			SelectiveRevalidation { full_revalidation_period: period, ..Default::default() }
+		});
+
 		opts
 	}
 }
@@ -31,8 +31,9 @@ use log::{debug, trace};
 
 use sp_consensus::{error::Error as ConsensusError, BlockOrigin};
 use sp_runtime::{
-	traits::{Block as BlockT, Header as _, NumberFor},
-	Justifications,
+	codec::Encode,
+	traits::{Block as BlockT, Hash as _, HashingFor, Header as _, NumberFor},
+	Justifications, StateVersion,
 };
 
 use crate::{
@@ -100,6 +101,41 @@ pub trait Verifier<B: BlockT>: Send {
 		-> Result<BlockImportParams<B>, String>;
 }
 
+/// Verify the parts of a block which don't depend on the import of its ancestors, e.g. the
+/// signatures of its extrinsics.
+///
+/// Unlike with the [`Verifier`], the blocks are pre-verified in parallel, ahead of the import
+/// of the preceding blocks.
+pub trait PreVerifier<B: BlockT>: Send + Sync {
+	/// Pre-verify the header and the body, if any, of a block.
+	fn pre_verify(&self, header: &B::Header, body: Option<&[B::Extrinsic]>) -> Result<(), String>;
+}
+
+/// Pre-verify that the body of a block matches the extrinsics root of its header.
+///
+/// The runtime checks the extrinsics root as well, but only once the block is executed.
+pub struct CheckExtrinsicsRoot;
+
+impl<B: BlockT> PreVerifier<B> for CheckExtrinsicsRoot {
+	fn pre_verify(&self, header: &B::Header, body: Option<&[B::Extrinsic]>) -> Result<(), String> {
+		let Some(body) = body else { return Ok(()) };
+
+		let extrinsics_root = HashingFor::<B>::ordered_trie_root(
+			body.iter().map(Encode::encode).collect(),
+			StateVersion::V0,
+		);
+		if extrinsics_root != *header.extrinsics_root() {
+			return Err(format!(
+				"Extrinsics root mismatch: expected {:?}, got {:?}",
+				header.extrinsics_root(),
+				extrinsics_root,
+			))
+		}
+
+		Ok(())
+	}
+}
+
 /// Blocks import queue API.
 ///
 /// The `import_*` methods can be called in order to send elements for the import queue to verify.
@@ -16,6 +16,8 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 use futures::{
+	channel::oneshot,
+	executor::ThreadPool,
 	prelude::*,
 	task::{Context, Poll},
 };
@@ -28,13 +30,18 @@ use sp_runtime::{
 	traits::{Block as BlockT, Header as HeaderT, NumberFor},
 	Justification, Justifications,
 };
-use std::{pin::Pin, time::Duration};
+use std::{
+	panic::AssertUnwindSafe,
+	pin::Pin,
+	sync::{Arc, OnceLock},
+	time::Duration,
+};
 
 use crate::{
 	import_queue::{
 		buffered_link::{self, BufferedLinkReceiver, BufferedLinkSender},
 		import_single_block_metered, BlockImportError, BlockImportStatus, BoxBlockImport,
-		BoxJustificationImport, ImportQueue, ImportQueueService, IncomingBlock, Link,
+		BoxJustificationImport, ImportQueue, ImportQueueService, IncomingBlock, Link, PreVerifier,
 		RuntimeOrigin, Verifier, LOG_TARGET,
 	},
 	metrics::Metrics,
@@ -67,6 +74,28 @@ impl<B: BlockT> BasicQueue<B> {
 		justification_import: Option<BoxJustificationImport<B>>,
 		spawner: &impl sp_core::traits::SpawnEssentialNamed,
 		prometheus_registry: Option<&Registry>,
+	) -> Self {
+		Self::new_with_pre_verifier(
+			verifier,
+			None,
+			block_import,
+			justification_import,
+			spawner,
+			prometheus_registry,
+		)
+	}
+
+	/// Instantiate a new basic queue, with given verifier and pre-verifier.
+	///
+	/// The blocks are pre-verified on a pool of threads, in parallel to the import of the
+	/// preceding blocks, see [`PreVerifier`].
+	pub fn new_with_pre_verifier<V: 'static + Verifier<B>>(
+		verifier: V,
+		pre_verifier: Option<Arc<dyn PreVerifier<B>>>,
+		block_import: BoxBlockImport<B>,
+		justification_import: Option<BoxJustificationImport<B>>,
+		spawner: &impl sp_core::traits::SpawnEssentialNamed,
+		prometheus_registry: Option<&Registry>,
 	) -> Self {
 		let (result_sender, result_port) = buffered_link::buffered_link(100_000);
 
@@ -81,6 +110,7 @@ impl<B: BlockT> BasicQueue<B> {
 		let (future, justification_sender, block_import_sender) = BlockImportWorker::new(
 			result_sender,
 			verifier,
+			pre_verifier.map(PreVerification::new),
 			block_import,
 			justification_import,
 			metrics,
@@ -221,6 +251,7 @@ mod worker_messages {
 async fn block_import_process<B: BlockT>(
 	mut block_import: BoxBlockImport<B>,
 	mut verifier: impl Verifier<B>,
+	pre_verification: Option<PreVerification<B>>,
 	mut result_sender: BufferedLinkSender<B>,
 	mut block_import_receiver: TracingUnboundedReceiver<worker_messages::ImportBlocks<B>>,
 	metrics: Option<Metrics>,
@@ -244,6 +275,7 @@ async fn block_import_process<B: BlockT>(
 			origin,
 			blocks,
 			&mut verifier,
+			pre_verification.as_ref(),
 			delay_between_blocks,
 			metrics.clone(),
 		)
@@ -263,6 +295,7 @@ impl<B: BlockT> BlockImportWorker<B> {
 	fn new<V: 'static + Verifier<B>>(
 		result_sender: BufferedLinkSender<B>,
 		verifier: V,
+		pre_verification: Option<PreVerification<B>>,
 		block_import: BoxBlockImport<B>,
 		justification_import: Option<BoxJustificationImport<B>>,
 		metrics: Option<Metrics>,
@@ -294,6 +327,7 @@ impl<B: BlockT> BlockImportWorker<B> {
 			let block_import_process = block_import_process(
 				block_import,
 				verifier,
+				pre_verification,
 				worker.result_sender.clone(),
 				block_import_port,
 				worker.metrics.clone(),
@@ -375,6 +409,106 @@ impl<B: BlockT> BlockImportWorker<B> {
 	}
 }
 
+/// The maximum number of threads pre-verifying the blocks, shared by all the import queues.
+const MAX_PRE_VERIFICATION_THREADS: usize = 4;
+
+/// The pool of threads pre-verifying the blocks of all the import queues, `None` if it couldn't
+/// be created.
+fn pre_verification_thread_pool() -> Option<&'static ThreadPool> {
+	static THREAD_POOL: OnceLock<Option<ThreadPool>> = OnceLock::new();
+
+	THREAD_POOL
+		.get_or_init(|| {
+			let pool_size = std::thread::available_parallelism()
+				.map_or(1, |n| n.get())
+				.min(MAX_PRE_VERIFICATION_THREADS);
+			ThreadPool::builder()
+				.pool_size(pool_size)
+				.name_prefix("block-pre-verification-")
+				.create()
+				.map_err(|err| {
+					log::warn!(
+						target: LOG_TARGET,
+						"Failed to create the block pre-verification threads: {}",
+						err,
+					);
+				})
+				.ok()
+		})
+		.as_ref()
+}
+
+/// A block and the result of its pre-verification.
+type PreVerified<B> = (IncomingBlock<B>, Result<(), String>);
+
+/// A block queued for import.
+struct QueuedBlock<B: BlockT> {
+	hash: B::Hash,
+	number: Option<NumberFor<B>>,
+	/// The block, once pre-verified.
+	pre_verified: oneshot::Receiver<PreVerified<B>>,
+}
+
+impl<B: BlockT> QueuedBlock<B> {
+	/// Queue a block which isn't pre-verified.
+	fn new(block: IncomingBlock<B>) -> Self {
+		let (sender, pre_verified) = oneshot::channel();
+		let queued = Self::with_receiver(&block, pre_verified);
+		let _ = sender.send((block, Ok(())));
+		queued
+	}
+
+	fn with_receiver(
+		block: &IncomingBlock<B>,
+		pre_verified: oneshot::Receiver<PreVerified<B>>,
+	) -> Self {
+		Self { hash: block.hash, number: block.header.as_ref().map(|h| *h.number()), pre_verified }
+	}
+}
+
+/// Pre-verifies the blocks on the shared pool of threads, see [`PreVerifier`].
+struct PreVerification<B: BlockT> {
+	pre_verifier: Arc<dyn PreVerifier<B>>,
+}
+
+impl<B: BlockT> PreVerification<B> {
+	fn new(pre_verifier: Arc<dyn PreVerifier<B>>) -> Self {
+		Self { pre_verifier }
+	}
+
+	/// Start the pre-verification of the block.
+	///
+	/// The block moves to the pre-verification thread and back, so that its body isn't copied.
+	/// The pre-verification runs on the import task if the pool of threads couldn't be created.
+	fn start(&self, block: IncomingBlock<B>) -> QueuedBlock<B> {
+		let (sender, pre_verified) = oneshot::channel();
+		let queued = QueuedBlock::with_receiver(&block, pre_verified);
+		let pre_verifier = self.pre_verifier.clone();
+		let pre_verify = move || {
+			// The import of the block was cancelled.
+			if sender.is_canceled() {
+				return
+			}
+			// The missing header is reported by the import.
+			let result = match &block.header {
+				Some(header) => std::panic::catch_unwind(AssertUnwindSafe(|| {
+					pre_verifier.pre_verify(header, block.body.as_deref())
+				}))
+				.unwrap_or_else(|_| Err("Block pre-verification panicked".into())),
+				None => Ok(()),
+			};
+			let _ = sender.send((block, result));
+		};
+
+		match pre_verification_thread_pool() {
+			Some(thread_pool) => thread_pool.spawn_ok(async move { pre_verify() }),
+			None => pre_verify(),
+		}
+
+		queued
+	}
+}
+
 /// Result of [`import_many_blocks`].
 struct ImportManyBlocksResult<B: BlockT> {
 	/// The number of blocks imported successfully.
@@ -394,6 +528,7 @@ async fn import_many_blocks<B: BlockT, V: Verifier<B>>(
 	blocks_origin: BlockOrigin,
 	blocks: Vec<IncomingBlock<B>>,
 	verifier: &mut V,
+	pre_verification: Option<&PreVerification<B>>,
 	delay_between_blocks: Duration,
 	metrics: Option<Metrics>,
 ) -> ImportManyBlocksResult<B> {
@@ -413,33 +548,55 @@ async fn import_many_blocks<B: BlockT, V: Verifier<B>>(
 	let mut imported = 0;
 	let mut results = vec![];
 	let mut has_error = false;
-	let mut blocks = blocks.into_iter();
+	// The pre-verification of all the blocks starts now, ahead of their import.
+	let mut blocks = blocks
+		.into_iter()
+		.map(|block| match pre_verification {
+			Some(pre_verification) => pre_verification.start(block),
+			None => QueuedBlock::new(block),
+		})
+		.collect::<Vec<_>>()
+		.into_iter();
 
 	// Blocks in the response/drain should be in ascending order.
 	loop {
 		// Is there any block left to import?
-		let block = match blocks.next() {
-			Some(b) => b,
-			None => {
-				// No block left to import, success!
-				return ImportManyBlocksResult { block_count: count, imported, results }
-			},
-		};
+		let QueuedBlock { hash: block_hash, number: block_number, pre_verified } =
+			match blocks.next() {
+				Some(b) => b,
+				None => {
+					// No block left to import, success!
+					return ImportManyBlocksResult { block_count: count, imported, results }
+				},
+			};
 
-		let block_number = block.header.as_ref().map(|h| *h.number());
-		let block_hash = block.hash;
 		let import_result = if has_error {
 			Err(BlockImportError::Cancelled)
 		} else {
-			// The actual import.
-			import_single_block_metered(
-				import_handle,
-				blocks_origin,
-				block,
-				verifier,
-				metrics.clone(),
-			)
-			.await
+			match pre_verified.await {
+				// The actual import.
+				Ok((block, Ok(()))) =>
+					import_single_block_metered(
+						import_handle,
+						blocks_origin,
+						block,
+						verifier,
+						metrics.clone(),
+					)
+					.await,
+				Ok((block, Err(msg))) => {
+					debug!(
+						target: LOG_TARGET,
+						"Pre-verifying {:?} ({}) failed: {}",
+						block_number,
+						block_hash,
+						msg,
+					);
+					Err(BlockImportError::VerificationFailed(block.origin, msg))
+				},
+				// The pre-verification thread dropped the block.
+				Err(_) => Err(BlockImportError::Cancelled),
+			}
 		};
 
 		if let Some(metrics) = metrics.as_ref() {
@@ -502,10 +659,14 @@ mod tests {
 		block_import::{
 			BlockCheckParams, BlockImport, BlockImportParams, ImportResult, JustificationImport,
 		},
-		import_queue::Verifier,
+		import_queue::{CheckExtrinsicsRoot, Verifier},
 	};
 	use futures::{executor::block_on, Future};
-	use sp_test_primitives::{Block, BlockNumber, Hash, Header};
+	use sp_runtime::{
+		codec::Encode,
+		traits::{Hash as _, HashingFor},
+	};
+	use sp_test_primitives::{Block, BlockNumber, Extrinsic, Hash, Header};
 
 	#[async_trait::async_trait]
 	impl Verifier<Block> for () {
@@ -554,6 +715,67 @@ mod tests {
 		}
 	}
 
+	/// Rejects the blocks with the given number.
+	struct RejectNumber(BlockNumber);
+
+	impl PreVerifier<Block> for RejectNumber {
+		fn pre_verify(
+			&self,
+			header: &Header,
+			_body: Option<&[<Block as BlockT>::Extrinsic]>,
+		) -> Result<(), String> {
+			if header.number == self.0 {
+				Err("rejected".into())
+			} else {
+				Ok(())
+			}
+		}
+	}
+
+	/// Records the numbers of the pre-verified blocks.
+	#[derive(Clone, Default)]
+	struct RecordPreVerified(Arc<parking_lot::Mutex<Vec<BlockNumber>>>);
+
+	impl PreVerifier<Block> for RecordPreVerified {
+		fn pre_verify(
+			&self,
+			header: &Header,
+			_body: Option<&[<Block as BlockT>::Extrinsic]>,
+		) -> Result<(), String> {
+			self.0.lock().push(header.number);
+			Ok(())
+		}
+	}
+
+	/// Imports the first block only once the given number of blocks are pre-verified.
+	struct WaitForPreVerified(RecordPreVerified, usize);
+
+	#[async_trait::async_trait]
+	impl BlockImport<Block> for WaitForPreVerified {
+		type Error = sp_consensus::Error;
+
+		async fn check_block(
+			&mut self,
+			_block: BlockCheckParams<Block>,
+		) -> Result<ImportResult, Self::Error> {
+			Ok(ImportResult::imported(false))
+		}
+
+		async fn import_block(
+			&mut self,
+			block: BlockImportParams<Block>,
+		) -> Result<ImportResult, Self::Error> {
+			if block.header.number == 1 {
+				let started = std::time::Instant::now();
+				while (self.0).0.lock().len() < self.1 {
+					assert!(started.elapsed() < Duration::from_secs(10), "not pre-verified");
+					std::thread::sleep(Duration::from_millis(10));
+				}
+			}
+			Ok(ImportResult::imported(true))
+		}
+	}
+
 	#[derive(Debug, PartialEq)]
 	enum Event {
 		JustificationImported(Hash),
@@ -588,12 +810,100 @@ mod tests {
 		}
 	}
 
+	fn incoming_block(number: BlockNumber) -> IncomingBlock<Block> {
+		let header = Header {
+			parent_hash: Hash::random(),
+			number,
+			extrinsics_root: Hash::random(),
+			state_root: Default::default(),
+			digest: Default::default(),
+		};
+
+		IncomingBlock {
+			hash: header.hash(),
+			header: Some(header),
+			body: None,
+			indexed_body: None,
+			justifications: None,
+			origin: None,
+			allow_missing_state: false,
+			import_existing: false,
+			state: None,
+			skip_execution: false,
+		}
+	}
+
+	#[test]
+	fn cancels_import_after_failed_pre_verification() {
+		let pre_verification = PreVerification::new(Arc::new(RejectNumber(2)));
+		let blocks = (1..=3).map(incoming_block).collect::<Vec<_>>();
+		let hashes = blocks.iter().map(|block| block.hash).collect::<Vec<_>>();
+
+		let result = block_on(import_many_blocks(
+			&mut (Box::new(()) as BoxBlockImport<Block>),
+			BlockOrigin::NetworkInitialSync,
+			blocks,
+			&mut (),
+			Some(&pre_verification),
+			Duration::default(),
+			None,
+		));
+
+		assert_eq!(result.block_count, 3);
+		assert_eq!(result.imported, 1);
+		assert_eq!(result.results.iter().map(|(_, hash)| *hash).collect::<Vec<_>>(), hashes);
+		assert!(matches!(result.results[0].0, Ok(BlockImportStatus::ImportedUnknown(1, _, None))));
+		assert!(matches!(
+			&result.results[1].0,
+			Err(BlockImportError::VerificationFailed(None, msg)) if msg == "rejected"
+		));
+		assert!(matches!(result.results[2].0, Err(BlockImportError::Cancelled)));
+	}
+
+	#[test]
+	fn pre_verifies_blocks_ahead_of_their_import() {
+		let pre_verified = RecordPreVerified::default();
+		let pre_verification = PreVerification::new(Arc::new(pre_verified.clone()));
+
+		// The first block is imported once the following blocks are pre-verified.
+		let result = block_on(import_many_blocks(
+			&mut (Box::new(WaitForPreVerified(pre_verified.clone(), 3)) as BoxBlockImport<Block>),
+			BlockOrigin::NetworkInitialSync,
+			(1..=3).map(incoming_block).collect(),
+			&mut (),
+			Some(&pre_verification),
+			Duration::default(),
+			None,
+		));
+
+		assert_eq!(result.imported, 3);
+		let mut numbers = pre_verified.0.lock().clone();
+		numbers.sort();
+		assert_eq!(numbers, vec![1, 2, 3]);
+	}
+
+	#[test]
+	fn checks_extrinsics_root() {
+		let body = vec![Extrinsic::IncludeData(vec![1]), Extrinsic::IncludeData(vec![2])];
+		let mut block = incoming_block(1);
+		let header = block.header.as_mut().unwrap();
+		header.extrinsics_root = HashingFor::<Block>::ordered_trie_root(
+			body.iter().map(Encode::encode).collect(),
+			sp_runtime::StateVersion::V0,
+		);
+
+		let pre_verifier: &dyn PreVerifier<Block> = &CheckExtrinsicsRoot;
+		assert_eq!(pre_verifier.pre_verify(header, None), Ok(()));
+		assert_eq!(pre_verifier.pre_verify(header, Some(&body)), Ok(()));
+		assert!(pre_verifier.pre_verify(header, Some(&body[..1])).is_err());
+	}
+
 	#[test]
 	fn prioritizes_finality_work_over_block_import() {
 		let (result_sender, mut result_port) = buffered_link::buffered_link(100_000);
 
 		let (worker, finality_sender, block_import_sender) =
-			BlockImportWorker::new(result_sender, (), Box::new(()), Some(Box::new(())), None);
+			BlockImportWorker::new(result_sender, (), None, Box::new(()), Some(Box::new(())), None);
 		futures::pin_mut!(worker);
 
 		let import_block = |n| {
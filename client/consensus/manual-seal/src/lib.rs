@@ -808,4 +808,73 @@ mod tests {
 		let header = client.header(created_block.hash).unwrap().unwrap();
 		assert_eq!(header.number, 1);
 	}
+
+	#[tokio::test]
+	async fn manual_seal_dev_set_head() {
+		use crate::rpc::{ManualSealDev, ManualSealDevApiServer};
+
+		let builder = TestClientBuilder::new();
+		let (client, select_chain) = builder.build_with_longest_chain();
+		let client = Arc::new(client);
+		let pool_api = Arc::new(FullChainApi::new(
+			client.clone(),
+			None,
+			&sp_core::testing::TaskExecutor::new(),
+		));
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let genesis_hash = client.info().genesis_hash;
+		let pool = Arc::new(BasicPool::with_revalidation_type(
+			Options::default(),
+			true.into(),
+			pool_api,
+			None,
+			RevalidationType::Full,
+			spawner.clone(),
+			0,
+			genesis_hash,
+			genesis_hash,
+		));
+		let env = ProposerFactory::new(spawner.clone(), client.clone(), pool.clone(), None, None);
+
+		let (sink, commands_stream) = futures::channel::mpsc::channel(1024);
+		let future = run_manual_seal(ManualSealParams {
+			block_import: client.clone(),
+			env,
+			client: client.clone(),
+			pool: pool.clone(),
+			commands_stream,
+			select_chain,
+			consensus_data_provider: None,
+			create_inherent_data_providers: |_, _| async { Ok(()) },
+		});
+		std::thread::spawn(|| {
+			let rt = tokio::runtime::Runtime::new().unwrap();
+			rt.block_on(future);
+		});
+
+		let dev = ManualSealDev::new(sink);
+
+		// Build on top of the best block.
+		assert!(pool.submit_one(&BlockId::Number(0), SOURCE, uxt(Alice, 0)).await.is_ok());
+		let first = dev.create_block(false, false, None).await.unwrap();
+		let header = client.header(first.hash).unwrap().unwrap();
+		assert_eq!(header.number, 1);
+		pool.maintain(sc_transaction_pool_api::ChainEvent::NewBestBlock {
+			hash: header.hash(),
+			tree_route: None,
+		})
+		.await;
+
+		// Fork from genesis with an empty block.
+		dev.set_head(Some(genesis_hash)).unwrap();
+		let fork = dev.create_block(true, false, None).await.unwrap();
+		let fork_header = client.header(fork.hash).unwrap().unwrap();
+		assert_eq!(fork_header.number, 1);
+		assert_eq!(fork_header.parent_hash, genesis_hash);
+		assert_ne!(fork.hash, first.hash);
+
+		// The head follows the created blocks.
+		let next = dev.create_block(true, false, None).await.unwrap();
+		assert_eq!(client.header(next.hash).unwrap().unwrap().parent_hash, fork.hash);
+	}
 }
@@ -27,9 +27,11 @@ use jsonrpsee::{
 	core::{async_trait, Error as JsonRpseeError, RpcResult},
 	proc_macros::rpc,
 };
+use parking_lot::Mutex;
 use sc_consensus::ImportedAux;
 use serde::{Deserialize, Serialize};
 use sp_runtime::EncodedJustification;
+use std::sync::Arc;
 
 /// Sender passed to the authorship task to report errors or successes.
 pub type Sender<T> = Option<oneshot::Sender<std::result::Result<T, Error>>>;
@@ -85,6 +87,32 @@ pub trait ManualSealApi<Hash> {
 	) -> RpcResult<bool>;
 }
 
+/// RPC trait that exposes the manual-seal authorship task under the `dev` namespace.
+///
+/// This allows integration test frameworks to drive manual-seal and instant-seal
+/// test networks without the `engine_*` methods.
+#[rpc(client, server)]
+pub trait ManualSealDevApi<Hash> {
+	/// Instructs the manual-seal authorship task to create a new block.
+	///
+	/// The block is built on top of `parent_hash` if provided, otherwise on top of
+	/// the head set by `dev_unstable_setHead`, otherwise on top of the best block.
+	#[method(name = "dev_unstable_createBlock")]
+	async fn create_block(
+		&self,
+		create_empty: bool,
+		finalize: bool,
+		parent_hash: Option<Hash>,
+	) -> RpcResult<CreatedBlock<Hash>>;
+
+	/// Set the head on top of which `dev_unstable_createBlock` builds new blocks.
+	///
+	/// The head follows the blocks created on top of it, such that consecutive
+	/// blocks extend the same fork. Providing no hash resets the head to the best block.
+	#[method(name = "dev_unstable_setHead")]
+	fn set_head(&self, hash: Option<Hash>) -> RpcResult<()>;
+}
+
 /// A struct that implements the [`ManualSealApiServer`].
 pub struct ManualSeal<Hash> {
 	import_block_channel: mpsc::Sender<EngineCommand<Hash>>,
@@ -108,6 +136,41 @@ impl<Hash> ManualSeal<Hash> {
 	}
 }
 
+/// A struct that implements the [`ManualSealDevApiServer`].
+pub struct ManualSealDev<Hash> {
+	import_block_channel: mpsc::Sender<EngineCommand<Hash>>,
+	/// The head set by `dev_unstable_setHead`.
+	head: Arc<Mutex<Option<Hash>>>,
+}
+
+impl<Hash> ManualSealDev<Hash> {
+	/// Create new `ManualSealDev` with the given channel of the authorship task.
+	pub fn new(import_block_channel: mpsc::Sender<EngineCommand<Hash>>) -> Self {
+		Self { import_block_channel, head: Default::default() }
+	}
+}
+
+/// Instruct the authorship task to seal a new block and wait for the result.
+async fn seal_new_block<Hash>(
+	mut sink: mpsc::Sender<EngineCommand<Hash>>,
+	create_empty: bool,
+	finalize: bool,
+	parent_hash: Option<Hash>,
+) -> RpcResult<CreatedBlock<Hash>> {
+	let (sender, receiver) = oneshot::channel();
+	// NOTE: this sends a Result over the channel.
+	let command =
+		EngineCommand::SealNewBlock { create_empty, finalize, parent_hash, sender: Some(sender) };
+
+	sink.send(command).await?;
+
+	match receiver.await {
+		Ok(Ok(rx)) => Ok(rx),
+		Ok(Err(e)) => Err(e.into()),
+		Err(e) => Err(JsonRpseeError::to_call_error(e)),
+	}
+}
+
 #[async_trait]
 impl<Hash: Send + 'static> ManualSealApiServer<Hash> for ManualSeal<Hash> {
 	async fn create_block(
@@ -116,23 +179,7 @@ impl<Hash: Send + 'static> ManualSealApiServer<Hash> for ManualSeal<Hash> {
 		finalize: bool,
 		parent_hash: Option<Hash>,
 	) -> RpcResult<CreatedBlock<Hash>> {
-		let mut sink = self.import_block_channel.clone();
-		let (sender, receiver) = oneshot::channel();
-		// NOTE: this sends a Result over the channel.
-		let command = EngineCommand::SealNewBlock {
-			create_empty,
-			finalize,
-			parent_hash,
-			sender: Some(sender),
-		};
-
-		sink.send(command).await?;
-
-		match receiver.await {
-			Ok(Ok(rx)) => Ok(rx),
-			Ok(Err(e)) => Err(e.into()),
-			Err(e) => Err(JsonRpseeError::to_call_error(e)),
-		}
+		seal_new_block(self.import_block_channel.clone(), create_empty, finalize, parent_hash).await
 	}
 
 	async fn finalize_block(
@@ -148,6 +195,39 @@ impl<Hash: Send + 'static> ManualSealApiServer<Hash> for ManualSeal<Hash> {
 	}
 }
 
+#[async_trait]
+impl<Hash: Clone + Send + 'static> ManualSealDevApiServer<Hash> for ManualSealDev<Hash> {
+	async fn create_block(
+		&self,
+		create_empty: bool,
+		finalize: bool,
+		parent_hash: Option<Hash>,
+	) -> RpcResult<CreatedBlock<Hash>> {
+		let head = self.head.lock().clone();
+		let follow_head = parent_hash.is_none() && head.is_some();
+
+		let created_block = seal_new_block(
+			self.import_block_channel.clone(),
+			create_empty,
+			finalize,
+			parent_hash.or(head),
+		)
+		.await?;
+
+		// Extend the same fork with the next blocks.
+		if follow_head {
+			*self.head.lock() = Some(created_block.hash.clone());
+		}
+
+		Ok(created_block)
+	}
+
+	fn set_head(&self, hash: Option<Hash>) -> RpcResult<()> {
+		*self.head.lock() = hash;
+		Ok(())
+	}
+}
+
 /// report any errors or successes encountered by the authorship task back
 /// to the rpc
 pub fn send_result<T: std::fmt::Debug>(
@@ -45,6 +45,12 @@ pub struct PeerInfo<Block: BlockT> {
 	pub best_hash: Block::Hash,
 	/// Their best block number.
 	pub best_number: NumberFor<Block>,
+	/// The number of the last block known to be common with them.
+	pub common_number: NumberFor<Block>,
+	/// Total size in bytes of the sync responses received from them.
+	pub downloaded_bytes: u64,
+	/// Average download speed of their sync responses, in bytes per second.
+	pub download_speed: u64,
 }
 
 /// Info about a peer's known state (both full and light).
@@ -85,6 +91,15 @@ pub struct StateDownloadProgress {
 	pub size: u64,
 }
 
+/// Progress of the download of the blocks missing from the history after a warp sync.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct GapSyncProgress<Block: BlockT> {
+	/// The highest block of the gap queued for import.
+	pub best_queued_number: NumberFor<Block>,
+	/// The last block of the gap.
+	pub target: NumberFor<Block>,
+}
+
 /// Syncing status and statistics.
 #[derive(Debug, Clone)]
 pub struct SyncStatus<Block: BlockT> {
@@ -102,6 +117,8 @@ pub struct SyncStatus<Block: BlockT> {
 	pub state_sync: Option<StateDownloadProgress>,
 	/// Warp sync in progress, if any.
 	pub warp_sync: Option<WarpSyncProgress<Block>>,
+	/// Gap sync in progress, if any.
+	pub gap_sync: Option<GapSyncProgress<Block>>,
 }
 
 /// A peer did not behave as expected and should be reported.
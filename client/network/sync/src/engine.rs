@@ -702,6 +702,14 @@ where
 						self.peers.iter().map(|(id, peer)| (*id, peer.info.clone())).collect();
 					let _ = tx.send(peers_info);
 				},
+				ToServiceCommand::SyncPeersInfo(tx) => {
+					let peers_info = self
+						.peers
+						.keys()
+						.filter_map(|id| self.chain_sync.peer_info(id).map(|info| (*id, info)))
+						.collect();
+					let _ = tx.send(peers_info);
+				},
 				ToServiceCommand::OnBlockFinalized(hash, header) =>
 					self.chain_sync.on_block_finalized(&hash, *header.number()),
 			}
@@ -561,6 +561,7 @@ mod tests {
 				best_hash: Hash::random(),
 				best_number: u64::arbitrary(g),
 				state: ArbitraryPeerSyncState::arbitrary(g).0,
+				download: Default::default(),
 			};
 			ArbitraryPeerSync(ps)
 		}
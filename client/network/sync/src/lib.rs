@@ -64,10 +64,10 @@ use sc_network_common::{
 			BlockResponse, Direction, FromBlock,
 		},
 		warp::{EncodedProof, WarpProofRequest, WarpSyncParams, WarpSyncPhase, WarpSyncProgress},
-		BadPeer, ChainSync as ChainSyncT, ImportResult, Metrics, OnBlockData, OnBlockJustification,
-		OnStateData, OpaqueBlockRequest, OpaqueBlockResponse, OpaqueStateRequest,
-		OpaqueStateResponse, PeerInfo, PeerRequest, PollBlockAnnounceValidation, SyncMode,
-		SyncState, SyncStatus,
+		BadPeer, ChainSync as ChainSyncT, GapSyncProgress, ImportResult, Metrics, OnBlockData,
+		OnBlockJustification, OnStateData, OpaqueBlockRequest, OpaqueBlockResponse,
+		OpaqueStateRequest, OpaqueStateResponse, PeerInfo, PeerRequest,
+		PollBlockAnnounceValidation, SyncMode, SyncState, SyncStatus,
 	},
 };
 use sp_arithmetic::traits::Saturating;
@@ -90,6 +90,7 @@ use std::{
 	ops::Range,
 	pin::Pin,
 	sync::Arc,
+	time::{Duration, Instant},
 };
 
 pub use service::chain_sync::SyncingService;
@@ -366,6 +367,42 @@ pub struct PeerSync<B: BlockT> {
 	/// The state of syncing this peer is in for us, generally categories
 	/// into `Available` or "busy" with something as defined by `PeerSyncState`.
 	pub state: PeerSyncState<B>,
+	/// Statistics of the responses downloaded from this peer.
+	pub download: PeerDownloadStats,
+}
+
+/// Statistics of the responses downloaded from a peer.
+#[derive(Debug, Clone, Default)]
+pub struct PeerDownloadStats {
+	/// When the pending request was sent, if any.
+	request_sent_at: Option<Instant>,
+	/// Total size in bytes of the responses received.
+	downloaded_bytes: u64,
+	/// Total time spent waiting for the responses received.
+	download_time: Duration,
+}
+
+impl PeerDownloadStats {
+	/// Record that a request has been sent to the peer.
+	fn on_request(&mut self) {
+		self.request_sent_at = Some(Instant::now());
+	}
+
+	/// Record that the response of the pending request has been received.
+	fn on_response(&mut self, size: usize) {
+		if let Some(request_sent_at) = self.request_sent_at.take() {
+			self.download_time += request_sent_at.elapsed();
+		}
+		self.downloaded_bytes = self.downloaded_bytes.saturating_add(size as u64);
+	}
+
+	/// Average download speed of the responses, in bytes per second.
+	fn speed(&self) -> u64 {
+		if self.download_time.is_zero() {
+			return 0
+		}
+		(self.downloaded_bytes as f64 / self.download_time.as_secs_f64()) as u64
+	}
 }
 
 impl<B: BlockT> PeerSync<B> {
@@ -481,9 +518,13 @@ where
 		+ 'static,
 {
 	fn peer_info(&self, who: &PeerId) -> Option<PeerInfo<B>> {
-		self.peers
-			.get(who)
-			.map(|p| PeerInfo { best_hash: p.best_hash, best_number: p.best_number })
+		self.peers.get(who).map(|p| PeerInfo {
+			best_hash: p.best_hash,
+			best_number: p.best_number,
+			common_number: p.common_number,
+			downloaded_bytes: p.download.downloaded_bytes,
+			download_speed: p.download.speed(),
+		})
 	}
 
 	/// Returns the current sync status.
@@ -533,6 +574,10 @@ where
 			queued_blocks: self.queue_blocks.len() as u32,
 			state_sync: self.state_sync.as_ref().map(|s| s.progress()),
 			warp_sync: warp_sync_progress,
+			gap_sync: self.gap_sync.as_ref().map(|gap_sync| GapSyncProgress {
+				best_queued_number: gap_sync.best_queued_number,
+				target: gap_sync.target,
+			}),
 		}
 	}
 
@@ -595,6 +640,7 @@ where
 							best_hash,
 							best_number,
 							state: PeerSyncState::Available,
+							download: Default::default(),
 						},
 					);
 					return Ok(None)
@@ -639,6 +685,7 @@ where
 						best_hash,
 						best_number,
 						state,
+						download: Default::default(),
 					},
 				);
 
@@ -670,6 +717,7 @@ where
 						best_hash,
 						best_number,
 						state: PeerSyncState::Available,
+						download: Default::default(),
 					},
 				);
 				self.allowed_requests.add(&who);
@@ -1350,7 +1398,8 @@ where
 		let (tx, rx) = oneshot::channel();
 		let opaque_req = self.create_opaque_block_request(&request);
 
-		if self.peers.contains_key(&who) {
+		if let Some(peer) = self.peers.get_mut(&who) {
+			peer.download.on_request();
 			self.pending_responses
 				.insert(who, Box::pin(async move { (who, PeerRequest::Block(request), rx.await) }));
 		}
@@ -1988,7 +2037,8 @@ where
 	fn send_state_request(&mut self, who: PeerId, request: OpaqueStateRequest) {
 		let (tx, rx) = oneshot::channel();
 
-		if self.peers.contains_key(&who) {
+		if let Some(peer) = self.peers.get_mut(&who) {
+			peer.download.on_request();
 			self.pending_responses
 				.insert(who, Box::pin(async move { (who, PeerRequest::State, rx.await) }));
 		}
@@ -2016,7 +2066,8 @@ where
 	fn send_warp_sync_request(&mut self, who: PeerId, request: WarpProofRequest<B>) {
 		let (tx, rx) = oneshot::channel();
 
-		if self.peers.contains_key(&who) {
+		if let Some(peer) = self.peers.get_mut(&who) {
+			peer.download.on_request();
 			self.pending_responses
 				.insert(who, Box::pin(async move { (who, PeerRequest::WarpProof, rx.await) }));
 		}
@@ -2170,6 +2221,10 @@ where
 				.remove(&id)
 				.expect("Logic error: peer id from pending response is missing in the map.");
 
+			if let (Ok(Ok(resp)), Some(peer)) = (&response, self.peers.get_mut(&id)) {
+				peer.download.on_response(resp.len());
+			}
+
 			match response {
 				Ok(Ok(resp)) => match request {
 					PeerRequest::Block(req) => {
@@ -4188,4 +4243,23 @@ mod test {
 		sync.peer_disconnected(&peers[1]);
 		assert_eq!(sync.pending_responses.len(), 0);
 	}
+
+	#[test]
+	fn peer_download_stats_measure_the_responses() {
+		let mut stats = PeerDownloadStats::default();
+		assert_eq!(stats.speed(), 0);
+
+		stats.on_request();
+		std::thread::sleep(Duration::from_millis(10));
+		stats.on_response(1000);
+		assert_eq!(stats.downloaded_bytes, 1000);
+		assert!(stats.download_time >= Duration::from_millis(10));
+		assert!(stats.speed() > 0 && stats.speed() <= 100_000);
+
+		// The responses without a pending request don't count towards the download time.
+		let download_time = stats.download_time;
+		stats.on_response(1000);
+		assert_eq!(stats.downloaded_bytes, 2000);
+		assert_eq!(stats.download_time, download_time);
+	}
 }
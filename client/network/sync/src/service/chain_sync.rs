@@ -22,7 +22,7 @@ use libp2p::PeerId;
 use sc_consensus::{BlockImportError, BlockImportStatus, JustificationSyncLink, Link};
 use sc_network::{NetworkBlock, NetworkSyncForkRequest};
 use sc_network_common::sync::{
-	ExtendedPeerInfo, SyncEvent, SyncEventStream, SyncStatus, SyncStatusProvider,
+	ExtendedPeerInfo, PeerInfo, SyncEvent, SyncEventStream, SyncStatus, SyncStatusProvider,
 };
 use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedSender};
 use sp_runtime::traits::{Block as BlockT, NumberFor};
@@ -58,6 +58,7 @@ pub enum ToServiceCommand<B: BlockT> {
 	NumDownloadedBlocks(oneshot::Sender<usize>),
 	NumSyncRequests(oneshot::Sender<usize>),
 	PeersInfo(oneshot::Sender<Vec<(PeerId, ExtendedPeerInfo<B>)>>),
+	SyncPeersInfo(oneshot::Sender<Vec<(PeerId, PeerInfo<B>)>>),
 	OnBlockFinalized(B::Hash, B::Header),
 	// Status {
 	// 	pending_response: oneshot::Sender<SyncStatus<B>>,
@@ -142,6 +143,14 @@ impl<B: BlockT> SyncingService<B> {
 		rx.await
 	}
 
+	/// Get the state of the sync with the peers we are syncing from.
+	pub async fn sync_peers_info(&self) -> Result<Vec<(PeerId, PeerInfo<B>)>, oneshot::Canceled> {
+		let (tx, rx) = oneshot::channel();
+		let _ = self.tx.unbounded_send(ToServiceCommand::SyncPeersInfo(tx));
+
+		rx.await
+	}
+
 	/// Notify the `SyncingEngine` that a block has been finalized.
 	pub fn on_block_finalized(&self, hash: B::Hash, header: B::Header) {
 		let _ = self.tx.unbounded_send(ToServiceCommand::OnBlockFinalized(hash, header));
@@ -51,6 +51,9 @@ pub enum Error {
 	/// Invalid session keys encoding.
 	#[error("Session keys are not encoded correctly")]
 	InvalidSessionKeys,
+	/// The fee information of the extrinsics was requested but isn't available.
+	#[error("The fee information of the extrinsics is not available on this node")]
+	FeeInfoUnavailable,
 	/// Call to an unsafe RPC was denied.
 	#[error(transparent)]
 	UnsafeRpcCalled(#[from] crate::policy::UnsafeRpcError),
@@ -86,6 +89,8 @@ const POOL_NO_TAGS: i32 = POOL_INVALID_TX + 9;
 const POOL_INVALID_BLOCK_ID: i32 = POOL_INVALID_TX + 10;
 /// The pool is not accepting future transactions.
 const POOL_FUTURE_TX: i32 = POOL_INVALID_TX + 11;
+/// The sender has too many future transactions in the pool.
+const POOL_TOO_MANY_FUTURE_TXS: i32 = POOL_INVALID_TX + 12;
 
 impl From<Error> for JsonRpseeError {
 	fn from(e: Error) -> Self {
@@ -176,6 +181,13 @@ impl From<Error> for JsonRpseeError {
 					None::<()>,
 				))
 			},
+			Error::Pool(PoolError::TooManyFutureTransactions) => {
+				CallError::Custom(ErrorObject::owned(
+					POOL_TOO_MANY_FUTURE_TXS,
+					"The sender has too many future transactions in the pool",
+					None::<()>,
+				))
+			},
 			Error::UnsafeRpcCalled(e) => e.into(),
 			e => CallError::Failed(e.into()),
 		}.into()
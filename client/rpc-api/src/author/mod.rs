@@ -19,12 +19,61 @@
 //! Substrate block-author/full-node API.
 
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use sc_transaction_pool_api::TransactionStatus;
+use sc_transaction_pool_api::{PoolEvent, TransactionStatus};
+use serde::{Deserialize, Serialize};
 use sp_core::Bytes;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::traits::Block as BlockT;
+use sp_weights::Weight;
 
 pub mod error;
 pub mod hash;
 
+/// The fee and the weight of an extrinsic.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtrinsicFeeInfo {
+	/// The fee of the extrinsic, without the tip.
+	pub partial_fee: NumberOrHex,
+	/// The weight of the dispatch of the extrinsic.
+	pub weight: Weight,
+}
+
+/// Computes the fee and the weight of the extrinsics watched with
+/// `author_submitAndWatchExtrinsic`.
+///
+/// The fees are defined by the runtime, so this is left to the node. FRAME based runtimes can
+/// use the provider of `pallet-transaction-payment-rpc`.
+pub trait ExtrinsicFeeProvider<Block: BlockT>: Send + Sync {
+	/// Returns the fee and the weight of `extrinsic`, whose encoding is `len` bytes long, at the
+	/// block `at`.
+	fn fee_info(
+		&self,
+		at: Block::Hash,
+		extrinsic: &Block::Extrinsic,
+		len: u32,
+	) -> Result<ExtrinsicFeeInfo, String>;
+}
+
+/// An event of an extrinsic watched with `author_submitAndWatchExtrinsic`.
+///
+/// The status events are serialized exactly like a [`TransactionStatus`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ExtrinsicUpdate<Hash, BlockHash> {
+	/// The status of the extrinsic changed.
+	Status(TransactionStatus<Hash, BlockHash>),
+	/// The fee and the weight of the extrinsic.
+	///
+	/// Only sent when requested, once the extrinsic has been validated by the pool and before
+	/// its first status. Not sent if the runtime failed to compute the fee.
+	FeeInfo {
+		/// The fee and the weight of the extrinsic.
+		#[serde(rename = "feeInfo")]
+		fee_info: ExtrinsicFeeInfo,
+	},
+}
+
 /// Substrate authoring RPC API
 #[rpc(client, server)]
 pub trait AuthorApi<Hash, BlockHash> {
@@ -69,10 +118,25 @@ pub trait AuthorApi<Hash, BlockHash> {
 	///
 	/// See [`TransactionStatus`](sc_transaction_pool_api::TransactionStatus) for details on
 	/// transaction life cycle.
+	///
+	/// With `with_fee_info` set, the fee and the weight of the extrinsic are sent as well once it
+	/// has been validated, see [`ExtrinsicUpdate::FeeInfo`]. This is rejected by the nodes
+	/// without an [`ExtrinsicFeeProvider`].
 	#[subscription(
 		name = "author_submitAndWatchExtrinsic" => "author_extrinsicUpdate",
 		unsubscribe = "author_unwatchExtrinsic",
-		item = TransactionStatus<Hash, BlockHash>,
+		item = ExtrinsicUpdate<Hash, BlockHash>,
+	)]
+	fn watch_extrinsic(&self, bytes: Bytes, with_fee_info: Option<bool>);
+
+	/// Subscribe to the events of all the transactions of the pool.
+	///
+	/// See [`PoolEvent`](sc_transaction_pool_api::PoolEvent) for details on the events. The
+	/// events are dropped when the subscriber does not keep up with the pool.
+	#[subscription(
+		name = "author_subscribePoolEvents" => "author_poolEvent",
+		unsubscribe = "author_unsubscribePoolEvents",
+		item = PoolEvent<Hash, BlockHash>,
 	)]
-	fn watch_extrinsic(&self, bytes: Bytes);
+	fn subscribe_pool_events(&self);
 }
@@ -19,10 +19,26 @@
 //! Substrate blockchain API.
 
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use serde::{Deserialize, Serialize};
+use sp_core::Bytes;
 use sp_rpc::{list::ListOrValue, number::NumberOrHex};
 
 pub mod error;
 
+/// An extrinsic of the canon chain, found by its hash.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtrinsicDetails<Number, Hash> {
+	/// The hash of the block including the extrinsic.
+	pub block_hash: Hash,
+	/// The number of the block including the extrinsic.
+	pub block_number: Number,
+	/// The index of the extrinsic in the block.
+	pub index: u32,
+	/// The SCALE encoded extrinsic, if the body of the block isn't pruned.
+	pub extrinsic: Option<Bytes>,
+}
+
 #[rpc(client, server)]
 pub trait ChainApi<Number, Hash, Header, SignedBlock> {
 	/// Get header.
@@ -46,6 +62,14 @@ pub trait ChainApi<Number, Hash, Header, SignedBlock> {
 	#[method(name = "chain_getFinalizedHead", aliases = ["chain_getFinalisedHead"], blocking)]
 	fn finalized_head(&self) -> RpcResult<Hash>;
 
+	/// Get the block of the canon chain including the extrinsic with the given hash, and the
+	/// extrinsic.
+	///
+	/// Requires the extrinsic index, enabled with `--extrinsic-index`, which only covers the
+	/// blocks imported after the index is enabled.
+	#[method(name = "chain_getExtrinsicByHash", blocking)]
+	fn extrinsic_by_hash(&self, hash: Hash) -> RpcResult<Option<ExtrinsicDetails<Number, Hash>>>;
+
 	/// All head subscription.
 	#[subscription(
 		name = "chain_subscribeAllHeads" => "chain_allHead",
@@ -38,6 +38,9 @@ pub enum Error {
 	/// The witness compaction failed.
 	#[error("Failed to create to compact the witness")]
 	WitnessCompactionFailed,
+	/// Failed to collect the storage changes of the re-executed block.
+	#[error("Failed to collect the storage changes: {0}")]
+	StorageChangesFailed(String),
 	/// The method is marked as unsafe but unsafe flag wasn't supplied on the CLI.
 	#[error(transparent)]
 	UnsafeRpcCalled(#[from] crate::policy::UnsafeRpcError),
@@ -59,6 +62,8 @@ impl From<Error> for JsonRpseeError {
 				CallError::Custom(ErrorObject::owned(BASE_ERROR + 4, msg, None::<()>)),
 			Error::ProofExtractionFailed =>
 				CallError::Custom(ErrorObject::owned(BASE_ERROR + 5, msg, None::<()>)),
+			Error::StorageChangesFailed(_) =>
+				CallError::Custom(ErrorObject::owned(BASE_ERROR + 6, msg, None::<()>)),
 			Error::UnsafeRpcCalled(e) => e.into(),
 		}
 		.into()
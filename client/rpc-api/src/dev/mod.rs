@@ -26,6 +26,7 @@ use codec::{Decode, Encode};
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use scale_info::TypeInfo;
 use serde::{Deserialize, Serialize};
+use sp_weights::Weight;
 
 /// Statistics of a block returned by the `dev_getBlockStats` RPC.
 #[derive(Eq, PartialEq, Clone, Copy, Encode, Decode, Debug, TypeInfo, Serialize, Deserialize)]
@@ -45,6 +46,18 @@ pub struct BlockStats {
 	/// This information can also be acquired by downloading the whole block. This merely
 	/// saves some complexity on the client side.
 	pub num_extrinsics: u64,
+	/// Number of distinct keys read from the state of the parent block.
+	///
+	/// The keys read after being written by the block are not counted, their values are
+	/// served from the storage overlay.
+	pub num_storage_reads: u64,
+	/// Number of distinct keys written or removed by the block, including the child tries.
+	pub num_storage_writes: u64,
+	/// The total weight consumed by the block, across all the dispatch classes.
+	///
+	/// This is `None` if the runtime isn't built with FRAME, i.e. the block doesn't write the
+	/// `System::BlockWeight` storage item.
+	pub total_weight: Option<Weight>,
 }
 
 /// Substrate dev API.
@@ -34,4 +34,5 @@ pub mod dev;
 pub mod offchain;
 pub mod state;
 pub mod statement;
+pub mod subscription;
 pub mod system;
@@ -52,6 +52,9 @@ pub enum Error {
 	/// Call to an unsafe RPC was denied.
 	#[error(transparent)]
 	UnsafeRpcCalled(#[from] crate::policy::UnsafeRpcError),
+	/// The call has been cancelled because it ran for longer than the timeout.
+	#[error("call has been cancelled after running for longer than {:?}", .0)]
+	CallTimeout(std::time::Duration),
 }
 
 /// Base code for all state errors.
@@ -66,6 +69,9 @@ impl From<Error> for JsonRpseeError {
 			Error::InvalidCount { .. } =>
 				CallError::Custom(ErrorObject::owned(BASE_ERROR + 2, e.to_string(), None::<()>))
 					.into(),
+			Error::CallTimeout(_) =>
+				CallError::Custom(ErrorObject::owned(BASE_ERROR + 3, e.to_string(), None::<()>))
+					.into(),
 			e => Self::to_call_error(e),
 		}
 	}
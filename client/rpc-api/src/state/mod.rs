@@ -88,7 +88,8 @@ pub trait StateApi<Hash> {
 	/// NOTE: The first returned result contains the initial state of storage for all keys.
 	/// Subsequent values in the vector represent changes to the previous state (diffs).
 	/// WARNING: The time complexity of this query is O(|keys|*dist(block, hash)), and the
-	/// memory complexity is O(dist(block, hash)) -- use with caution.
+	/// memory complexity is O(dist(block, hash)) -- use with caution, or stream the changes with
+	/// `state_subscribeQueryStorage`.
 	#[method(name = "state_queryStorage", blocking)]
 	fn query_storage(
 		&self,
@@ -129,6 +130,42 @@ pub trait StateApi<Hash> {
 	)]
 	fn subscribe_storage(&self, keys: Option<Vec<StorageKey>>);
 
+	/// Stream the historical storage entries (by key) of a range of blocks, in pages.
+	///
+	/// The pages hold the change sets of at most `page_size` consecutive blocks, 100 by default,
+	/// in the format of `state_queryStorage`. The pages without changes are skipped. At most
+	/// `max_in_flight` pages, 1 by default, are queried ahead of the pages already sent.
+	/// The subscription is closed once the whole range has been sent.
+	#[subscription(
+		name = "state_subscribeQueryStorage" => "state_queryStorageChanges",
+		unsubscribe = "state_unsubscribeQueryStorage",
+		item = Vec<StorageChangeSet<Hash>>,
+	)]
+	fn subscribe_query_storage(
+		&self,
+		keys: Vec<StorageKey>,
+		block: Hash,
+		hash: Option<Hash>,
+		page_size: Option<u32>,
+		max_in_flight: Option<u32>,
+	);
+
+	/// Stream the keys with the given prefix at a block's state, in batches.
+	///
+	/// The batches hold at most `batch_size` keys, 1000 by default, in lexicographic order.
+	/// The subscription is closed once all the keys have been sent.
+	#[subscription(
+		name = "state_subscribeKeysByPrefix" => "state_keysByPrefix",
+		unsubscribe = "state_unsubscribeKeysByPrefix",
+		item = Vec<StorageKey>,
+	)]
+	fn subscribe_keys_by_prefix(
+		&self,
+		prefix: StorageKey,
+		batch_size: Option<u32>,
+		hash: Option<Hash>,
+	);
+
 	/// The `traceBlock` RPC provides a way to trace the re-execution of a single
 	/// block, collecting Spans and Events from both the client and the relevant WASM runtime.
 	/// The Spans and Events are conceptually equivalent to those from the [Tracing][1] crate.
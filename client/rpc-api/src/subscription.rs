@@ -0,0 +1,107 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Utilities shared by the RPC subscriptions.
+
+use futures::future::{select, Either, Future};
+use jsonrpsee::{
+	types::{error::CallError, ErrorObject},
+	SubscriptionSink,
+};
+use std::time::Duration;
+
+/// The maximum duration a subscription is allowed to be pending before it is accepted.
+pub const PENDING_SUBSCRIPTION_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The pending subscription was not accepted in time.
+const PENDING_SUBSCRIPTION_TIMED_OUT: i32 = crate::error::base::SUBSCRIPTION + 1;
+
+/// Wait for the future that must complete before the subscription is accepted.
+///
+/// If the future does not complete within the timeout, the subscription is rejected and
+/// the future is dropped, releasing the resources it reserved for the subscription.
+///
+/// Returns the sink of the subscription and the output of the future, or `None` if the
+/// subscription was rejected.
+pub async fn accept_within<F: Future>(
+	mut sink: SubscriptionSink,
+	timeout: Duration,
+	fut: F,
+) -> Option<(SubscriptionSink, F::Output)> {
+	futures::pin_mut!(fut);
+
+	match select(fut, futures_timer::Delay::new(timeout)).await {
+		Either::Left((output, _)) => Some((sink, output)),
+		Either::Right(_) => {
+			log::debug!(target: "rpc", "Subscription not accepted within {:?}", timeout);
+			let err = CallError::Custom(ErrorObject::owned(
+				PENDING_SUBSCRIPTION_TIMED_OUT,
+				"Subscription was not accepted in time",
+				None::<()>,
+			));
+			let _ = sink.reject(err);
+			None
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use jsonrpsee::{core::Error as JsonRpseeError, types::EmptyServerParams, RpcModule};
+
+	fn module(timeout: Duration, ready: bool) -> RpcModule<()> {
+		let mut module = RpcModule::new(());
+		module
+			.register_subscription("sub", "notif", "unsub", move |_, sink, _| {
+				tokio::spawn(async move {
+					let fut = async move {
+						if !ready {
+							futures::future::pending::<()>().await;
+						}
+					};
+					if let Some((mut sink, ())) = accept_within(sink, timeout, fut).await {
+						let _ = sink.send(&1u32);
+					}
+				});
+				Ok(())
+			})
+			.unwrap();
+		module
+	}
+
+	#[tokio::test]
+	async fn pending_subscription_accepted() {
+		let module = module(Duration::from_secs(60), true);
+
+		let mut sub = module.subscribe("sub", EmptyServerParams::new()).await.unwrap();
+		let (value, _) = sub.next::<u32>().await.unwrap().unwrap();
+		assert_eq!(value, 1);
+	}
+
+	#[tokio::test]
+	async fn pending_subscription_rejected_on_timeout() {
+		let module = module(Duration::from_millis(10), false);
+
+		match module.subscribe("sub", EmptyServerParams::new()).await {
+			Err(JsonRpseeError::Call(CallError::Custom(err))) =>
+				assert_eq!(err.code(), PENDING_SUBSCRIPTION_TIMED_OUT),
+			_ => panic!("Expected the subscription to be rejected"),
+		}
+	}
+}
@@ -90,6 +90,39 @@ pub struct SyncState<Number> {
 	pub current_block: Number,
 	/// Height of the highest block in the network.
 	pub highest_block: Number,
+	/// The state of the syncing with every peer the node is syncing from.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub peers: Vec<PeerSyncState<Number>>,
+	/// The progress of the download of the blocks missing from the history after a warp sync,
+	/// if any.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub gap_sync: Option<GapSyncState<Number>>,
+}
+
+/// The state of the syncing with a peer.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerSyncState<Number> {
+	/// Peer ID
+	pub peer_id: String,
+	/// Peer best block number
+	pub best_number: Number,
+	/// Height of the last block known to be common with the peer.
+	pub common_number: Number,
+	/// Total size in bytes of the sync responses received from the peer.
+	pub downloaded_bytes: u64,
+	/// Average download speed of the sync responses of the peer, in bytes per second.
+	pub download_speed: u64,
+}
+
+/// The progress of the gap sync.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GapSyncState<Number> {
+	/// Height of the highest block of the gap queued for import.
+	pub current_block: Number,
+	/// Height of the last block of the gap.
+	pub target_block: Number,
 }
 
 #[cfg(test)]
@@ -130,6 +163,8 @@ mod tests {
 				starting_block: 12u32,
 				current_block: 50u32,
 				highest_block: 128u32,
+				peers: vec![],
+				gap_sync: None,
 			})
 			.unwrap(),
 			r#"{"startingBlock":12,"currentBlock":50,"highestBlock":128}"#,
@@ -140,9 +175,29 @@ mod tests {
 				starting_block: 12u32,
 				current_block: 50u32,
 				highest_block: 50u32,
+				peers: vec![],
+				gap_sync: None,
 			})
 			.unwrap(),
 			r#"{"startingBlock":12,"currentBlock":50,"highestBlock":50}"#,
 		);
+
+		assert_eq!(
+			::serde_json::to_string(&SyncState {
+				starting_block: 12u32,
+				current_block: 50u32,
+				highest_block: 128u32,
+				peers: vec![PeerSyncState {
+					peer_id: "2".into(),
+					best_number: 128u32,
+					common_number: 50u32,
+					downloaded_bytes: 1024,
+					download_speed: 512,
+				}],
+				gap_sync: Some(GapSyncState { current_block: 5u32, target_block: 10u32 }),
+			})
+			.unwrap(),
+			r#"{"startingBlock":12,"currentBlock":50,"highestBlock":128,"peers":[{"peerId":"2","bestNumber":128,"commonNumber":50,"downloadedBytes":1024,"downloadSpeed":512}],"gapSync":{"currentBlock":5,"targetBlock":10}}"#,
+		);
 	}
 }
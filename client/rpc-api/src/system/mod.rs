@@ -23,7 +23,9 @@ use jsonrpsee::{
 	proc_macros::rpc,
 };
 
-pub use self::helpers::{Health, NodeRole, PeerInfo, SyncState, SystemInfo};
+pub use self::helpers::{
+	GapSyncState, Health, NodeRole, PeerInfo, PeerSyncState, SyncState, SystemInfo,
+};
 
 pub mod error;
 pub mod helpers;
@@ -105,7 +107,7 @@ pub trait SystemApi<Hash, Number> {
 	async fn system_node_roles(&self) -> RpcResult<Vec<NodeRole>>;
 
 	/// Returns the state of the syncing of the node: starting block, current best block, highest
-	/// known block.
+	/// known block, the state of the syncing with every peer and the progress of the gap sync.
 	#[method(name = "system_syncState")]
 	async fn system_sync_state(&self) -> RpcResult<SyncState<Number>>;
 
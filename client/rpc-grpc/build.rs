@@ -0,0 +1,8 @@
+const PROTOS: &[&str] = &["src/schema/node.v1.proto"];
+
+fn main() {
+	tonic_build::configure()
+		.build_client(false)
+		.compile(PROTOS, &["src/schema"])
+		.unwrap();
+}
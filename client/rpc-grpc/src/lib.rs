@@ -0,0 +1,361 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Substrate gRPC server.
+//!
+//! Exposes a curated subset of the node APIs, the blocks, the storage and the submission of
+//! transactions, to the infrastructure standardized on gRPC. The protobuf definitions are in
+//! `src/schema/node.v1.proto`.
+//!
+//! Only the methods which are safe to expose publicly are part of the API.
+
+#![warn(missing_docs)]
+
+#[cfg(test)]
+mod tests;
+
+mod schema;
+
+use codec::{DecodeAll, Encode};
+use futures::{future, stream, Future, Stream, StreamExt};
+use sc_client_api::{Backend, BlockBackend, BlockchainEvents, StorageProvider};
+use sc_transaction_pool_api::{TransactionPool, TransactionSource};
+use sp_blockchain::HeaderBackend;
+use sp_core::storage::StorageKey;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT, UniqueSaturatedInto},
+};
+use std::{
+	io,
+	marker::PhantomData,
+	net::SocketAddr,
+	pin::Pin,
+	sync::Arc,
+	task::{Context, Poll},
+};
+use tokio::{
+	io::{AsyncRead, AsyncWrite, ReadBuf},
+	sync::{broadcast, OwnedSemaphorePermit, Semaphore},
+};
+use tonic::{
+	transport::{
+		server::{Connected, TcpIncoming},
+		Server,
+	},
+	Request, Response, Status,
+};
+
+use schema::v1::node::{self as proto, node_server::NodeServer};
+
+/// The maximum number of head events buffered per `FollowHeads` stream.
+///
+/// The oldest events are dropped once a slow client lags behind by more events.
+const FOLLOW_HEADS_BUFFER: usize = 64;
+
+/// The core node APIs exposed over gRPC.
+pub struct NodeService<BE, Client, Pool> {
+	client: Arc<Client>,
+	pool: Arc<Pool>,
+	_phantom: PhantomData<fn() -> BE>,
+}
+
+impl<BE, Client, Pool> NodeService<BE, Client, Pool> {
+	/// Create a new [`NodeService`].
+	pub fn new(client: Arc<Client>, pool: Arc<Pool>) -> Self {
+		NodeService { client, pool, _phantom: PhantomData }
+	}
+}
+
+impl<BE, Client, Pool> NodeService<BE, Client, Pool>
+where
+	Pool: TransactionPool,
+	Client: HeaderBackend<Pool::Block>,
+{
+	/// Returns the requested block hash, or the best block hash if `None`.
+	fn block_or_best(
+		&self,
+		hash: Option<Vec<u8>>,
+	) -> Result<<Pool::Block as BlockT>::Hash, Status> {
+		match hash {
+			Some(hash) => <Pool::Block as BlockT>::Hash::decode_all(&mut &hash[..])
+				.map_err(|e| Status::invalid_argument(format!("Invalid block hash: {}", e))),
+			None => Ok(self.client.info().best_hash),
+		}
+	}
+
+	/// Returns the header of the block, or a `NOT_FOUND` status if the block is unknown.
+	fn header(
+		&self,
+		hash: <Pool::Block as BlockT>::Hash,
+	) -> Result<<Pool::Block as BlockT>::Header, Status> {
+		self.client
+			.header(hash)
+			.map_err(|e| Status::internal(e.to_string()))?
+			.ok_or_else(|| Status::not_found(format!("Unknown block: {:?}", hash)))
+	}
+}
+
+/// Converts a header to its protobuf representation.
+fn to_proto_header<Header: HeaderT>(header: &Header) -> proto::Header {
+	proto::Header {
+		hash: header.hash().encode(),
+		number: (*header.number()).unique_saturated_into(),
+		parent_hash: header.parent_hash().encode(),
+		state_root: header.state_root().encode(),
+		extrinsics_root: header.extrinsics_root().encode(),
+		encoded: header.encode(),
+	}
+}
+
+/// Run a blocking read of the database on the blocking threads of the runtime.
+async fn spawn_blocking<T, F>(read: F) -> Result<T, Status>
+where
+	F: FnOnce() -> Result<T, Status> + Send + 'static,
+	T: Send + 'static,
+{
+	tokio::task::spawn_blocking(read)
+		.await
+		.map_err(|e| Status::internal(e.to_string()))?
+}
+
+/// The stream of the new best blocks and of the newly finalized blocks.
+type HeadEventStream = Pin<Box<dyn Stream<Item = Result<proto::HeadEvent, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl<BE, Client, Pool> proto::node_server::Node for NodeService<BE, Client, Pool>
+where
+	BE: Backend<Pool::Block> + 'static,
+	Client: HeaderBackend<Pool::Block>
+		+ BlockBackend<Pool::Block>
+		+ BlockchainEvents<Pool::Block>
+		+ StorageProvider<Pool::Block, BE>
+		+ Send
+		+ Sync
+		+ 'static,
+	Pool: TransactionPool + 'static,
+{
+	async fn get_header(
+		&self,
+		request: Request<proto::BlockRequest>,
+	) -> Result<Response<proto::HeaderResponse>, Status> {
+		let hash = self.block_or_best(request.into_inner().hash)?;
+		let header = self.header(hash)?;
+		Ok(Response::new(proto::HeaderResponse { header: Some(to_proto_header(&header)) }))
+	}
+
+	async fn get_block(
+		&self,
+		request: Request<proto::BlockRequest>,
+	) -> Result<Response<proto::BlockResponse>, Status> {
+		let hash = self.block_or_best(request.into_inner().hash)?;
+		let client = self.client.clone();
+		let block = spawn_blocking(move || {
+			client
+				.block(hash)
+				.map_err(|e| Status::internal(e.to_string()))?
+				.ok_or_else(|| Status::not_found(format!("Unknown block: {:?}", hash)))
+		})
+		.await?
+		.block;
+		Ok(Response::new(proto::BlockResponse {
+			header: Some(to_proto_header(block.header())),
+			extrinsics: block.extrinsics().iter().map(|xt| xt.encode()).collect(),
+		}))
+	}
+
+	async fn get_block_hash(
+		&self,
+		request: Request<proto::BlockHashRequest>,
+	) -> Result<Response<proto::BlockHashResponse>, Status> {
+		let number = request.into_inner().number.unique_saturated_into();
+		let hash = self.client.hash(number).map_err(|e| Status::internal(e.to_string()))?;
+		Ok(Response::new(proto::BlockHashResponse { hash: hash.map(|hash| hash.encode()) }))
+	}
+
+	type FollowHeadsStream = HeadEventStream;
+
+	async fn follow_heads(
+		&self,
+		_request: Request<proto::FollowHeadsRequest>,
+	) -> Result<Response<Self::FollowHeadsStream>, Status> {
+		let best = self
+			.client
+			.import_notification_stream()
+			.filter(|notification| future::ready(notification.is_new_best))
+			.map(|notification| (proto::head_event::Kind::Best, notification.header));
+		let finalized = self
+			.client
+			.finality_notification_stream()
+			.map(|notification| (proto::head_event::Kind::Finalized, notification.header));
+		let mut events = stream::select(best, finalized).map(|(kind, header)| proto::HeadEvent {
+			kind: kind as i32,
+			header: Some(to_proto_header(&header)),
+		});
+
+		// The notifications are buffered in a bounded channel dropping the oldest events,
+		// such that a slow client doesn't make the node buffer the notifications forever.
+		let (tx, rx) = broadcast::channel(FOLLOW_HEADS_BUFFER);
+		tokio::spawn(async move {
+			while let Some(event) = events.next().await {
+				// Fails once the client has dropped the stream.
+				if tx.send(event).is_err() {
+					break
+				}
+			}
+		});
+		let events = stream::unfold(rx, |mut rx| async move {
+			loop {
+				match rx.recv().await {
+					Ok(event) => return Some((Ok(event), rx)),
+					Err(broadcast::error::RecvError::Lagged(dropped)) => {
+						log::debug!("Dropped {} head events of a lagging gRPC client", dropped);
+					},
+					Err(broadcast::error::RecvError::Closed) => return None,
+				}
+			}
+		});
+		Ok(Response::new(Box::pin(events)))
+	}
+
+	async fn get_storage(
+		&self,
+		request: Request<proto::StorageRequest>,
+	) -> Result<Response<proto::StorageResponse>, Status> {
+		let request = request.into_inner();
+		let hash = self.block_or_best(request.hash)?;
+		let client = self.client.clone();
+		let value = spawn_blocking(move || {
+			client
+				.storage(hash, &StorageKey(request.key))
+				.map_err(|e| Status::internal(e.to_string()))
+		})
+		.await?;
+		Ok(Response::new(proto::StorageResponse {
+			block_hash: hash.encode(),
+			value: value.map(|value| value.0),
+		}))
+	}
+
+	async fn submit_transaction(
+		&self,
+		request: Request<proto::SubmitTransactionRequest>,
+	) -> Result<Response<proto::SubmitTransactionResponse>, Status> {
+		let transaction = request.into_inner().transaction;
+		let xt = <Pool::Block as BlockT>::Extrinsic::decode_all(&mut &transaction[..])
+			.map_err(|e| Status::invalid_argument(format!("Invalid transaction: {}", e)))?;
+		let best_hash = self.client.info().best_hash;
+		let hash = self
+			.pool
+			.submit_one(&BlockId::hash(best_hash), TransactionSource::External, xt)
+			.await
+			.map_err(|e| Status::failed_precondition(e.to_string()))?;
+		Ok(Response::new(proto::SubmitTransactionResponse { hash: hash.encode() }))
+	}
+}
+
+/// A connection of the gRPC server, holding one of the permits limiting the number of
+/// connections.
+struct LimitedConnection<IO> {
+	io: IO,
+	_permit: OwnedSemaphorePermit,
+}
+
+impl<IO: AsyncRead + Unpin> AsyncRead for LimitedConnection<IO> {
+	fn poll_read(
+		mut self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &mut ReadBuf<'_>,
+	) -> Poll<io::Result<()>> {
+		Pin::new(&mut self.io).poll_read(cx, buf)
+	}
+}
+
+impl<IO: AsyncWrite + Unpin> AsyncWrite for LimitedConnection<IO> {
+	fn poll_write(
+		mut self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &[u8],
+	) -> Poll<io::Result<usize>> {
+		Pin::new(&mut self.io).poll_write(cx, buf)
+	}
+
+	fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		Pin::new(&mut self.io).poll_flush(cx)
+	}
+
+	fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		Pin::new(&mut self.io).poll_shutdown(cx)
+	}
+}
+
+impl<IO: Connected> Connected for LimitedConnection<IO> {
+	type ConnectInfo = IO::ConnectInfo;
+
+	fn connect_info(&self) -> Self::ConnectInfo {
+		self.io.connect_info()
+	}
+}
+
+/// Start the gRPC server listening on the given address.
+///
+/// The address is bound right away, the returned future serves the requests. The connections
+/// accepted once `max_connections` are open are closed right away. Must be called within the
+/// context of a tokio runtime.
+pub fn start_server<BE, Client, Pool>(
+	addr: SocketAddr,
+	max_connections: u32,
+	client: Arc<Client>,
+	pool: Arc<Pool>,
+) -> Result<impl Future<Output = ()>, Box<dyn std::error::Error + Send + Sync>>
+where
+	BE: Backend<Pool::Block> + 'static,
+	Client: HeaderBackend<Pool::Block>
+		+ BlockBackend<Pool::Block>
+		+ BlockchainEvents<Pool::Block>
+		+ StorageProvider<Pool::Block, BE>
+		+ Send
+		+ Sync
+		+ 'static,
+	Pool: TransactionPool + 'static,
+{
+	let connections = Arc::new(Semaphore::new(max_connections as usize));
+	let incoming = TcpIncoming::new(addr, true, None)?.filter_map(move |connection| {
+		let connection = connection
+			.map(|io| match connections.clone().try_acquire_owned() {
+				Ok(permit) => Some(LimitedConnection { io, _permit: permit }),
+				Err(_) => {
+					log::debug!(
+						"Refused a gRPC connection, {} connections are open",
+						max_connections
+					);
+					None
+				},
+			})
+			.transpose();
+		future::ready(connection)
+	});
+	log::info!("Running gRPC server: addr={}, max connections={}", addr, max_connections);
+
+	let service = NodeServer::new(NodeService::<BE, _, _>::new(client, pool));
+	Ok(async move {
+		if let Err(e) = Server::builder().add_service(service).serve_with_incoming(incoming).await {
+			log::error!("gRPC server failed: {}", e);
+		}
+	})
+}
@@ -0,0 +1,160 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+
+use proto::node_server::Node;
+use sc_block_builder::BlockBuilderProvider;
+use sc_transaction_pool::{BasicPool, FullChainApi};
+use sp_consensus::BlockOrigin;
+use sp_core::{blake2_256, H256};
+use substrate_test_runtime_client::{
+	prelude::*,
+	runtime::{Block, ExtrinsicBuilder, Transfer},
+	Backend, Client,
+};
+use tonic::Code;
+
+type FullTransactionPool = BasicPool<FullChainApi<Client<Backend>, Block>, Block>;
+
+fn service(
+	client: Arc<Client<Backend>>,
+) -> NodeService<Backend, Client<Backend>, FullTransactionPool> {
+	let spawner = sp_core::testing::TaskExecutor::new();
+	let pool = BasicPool::new_full(Default::default(), true.into(), None, spawner, client.clone());
+	NodeService::new(client, pool)
+}
+
+#[tokio::test]
+async fn should_return_blocks_and_storage() {
+	let mut client = Arc::new(
+		TestClientBuilder::new()
+			.add_extra_storage(b":key".to_vec(), b"value".to_vec())
+			.build(),
+	);
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_hash = block.header.hash();
+	client.import(BlockOrigin::Own, block).await.unwrap();
+	let service = service(client.clone());
+
+	// The best block by default.
+	let header = service
+		.get_header(Request::new(proto::BlockRequest { hash: None }))
+		.await
+		.unwrap()
+		.into_inner()
+		.header
+		.unwrap();
+	assert_eq!(header.hash, block_hash.encode());
+	assert_eq!(header.number, 1);
+	assert_eq!(header.parent_hash, client.genesis_hash().encode());
+
+	let hash = service
+		.get_block_hash(Request::new(proto::BlockHashRequest { number: 1 }))
+		.await
+		.unwrap()
+		.into_inner()
+		.hash;
+	assert_eq!(hash, Some(block_hash.encode()));
+	let hash = service
+		.get_block_hash(Request::new(proto::BlockHashRequest { number: 2 }))
+		.await
+		.unwrap()
+		.into_inner()
+		.hash;
+	assert_eq!(hash, None);
+
+	let block = service
+		.get_block(Request::new(proto::BlockRequest { hash: Some(block_hash.encode()) }))
+		.await
+		.unwrap()
+		.into_inner();
+	assert_eq!(block.header.unwrap().hash, block_hash.encode());
+	assert!(block.extrinsics.is_empty());
+
+	let storage = service
+		.get_storage(Request::new(proto::StorageRequest { key: b":key".to_vec(), hash: None }))
+		.await
+		.unwrap()
+		.into_inner();
+	assert_eq!(storage.block_hash, block_hash.encode());
+	assert_eq!(storage.value, Some(b"value".to_vec()));
+
+	let unknown = service
+		.get_header(Request::new(proto::BlockRequest { hash: Some(H256::random().encode()) }))
+		.await
+		.unwrap_err();
+	assert_eq!(unknown.code(), Code::NotFound);
+	let invalid = service
+		.get_header(Request::new(proto::BlockRequest { hash: Some(vec![1, 2, 3]) }))
+		.await
+		.unwrap_err();
+	assert_eq!(invalid.code(), Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn should_submit_transactions() {
+	let service = service(Arc::new(substrate_test_runtime_client::new()));
+	let xt = ExtrinsicBuilder::new_transfer(Transfer {
+		amount: Default::default(),
+		nonce: 0,
+		from: AccountKeyring::Alice.into(),
+		to: AccountKeyring::Bob.into(),
+	})
+	.build()
+	.encode();
+
+	let hash = service
+		.submit_transaction(Request::new(proto::SubmitTransactionRequest {
+			transaction: xt.clone(),
+		}))
+		.await
+		.unwrap()
+		.into_inner()
+		.hash;
+	assert_eq!(hash, blake2_256(&xt).to_vec());
+
+	// The transaction is already in the pool.
+	let err = service
+		.submit_transaction(Request::new(proto::SubmitTransactionRequest { transaction: xt }))
+		.await
+		.unwrap_err();
+	assert_eq!(err.code(), Code::FailedPrecondition);
+}
+
+#[tokio::test]
+async fn should_drop_the_oldest_head_events_of_lagging_clients() {
+	let mut client = Arc::new(substrate_test_runtime_client::new());
+	let service = service(client.clone());
+	let mut heads = service
+		.follow_heads(Request::new(proto::FollowHeadsRequest {}))
+		.await
+		.unwrap()
+		.into_inner();
+
+	// The client doesn't read the events of the imported blocks.
+	for _ in 0..FOLLOW_HEADS_BUFFER + 2 {
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		client.import(BlockOrigin::Own, block).await.unwrap();
+	}
+
+	// The events of the two oldest blocks are dropped.
+	let event = heads.next().await.unwrap().unwrap();
+	assert_eq!(event.kind, proto::head_event::Kind::Best as i32);
+	assert_eq!(event.header.unwrap().number, 3);
+}
@@ -0,0 +1,485 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Authentication of the calls to the unsafe RPC methods.
+//!
+//! A single listen address can serve both the public clients and the operators. Every HTTP
+//! request and WebSocket handshake is evaluated by the configured [`RpcAuthenticator`].
+//!
+//! The requests that are not authenticated are served by an RPC module built without the
+//! unsafe methods, such that the methods that are registered anyway reject the calls by
+//! themselves. The requests granted [`Access::Unsafe`] are forwarded, along with their
+//! WebSocket connection if any, to a server bound to the loopback interface which serves
+//! the RPC module allowing the unsafe methods.
+
+use http::{
+	header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, UPGRADE},
+	Request, Response, StatusCode,
+};
+use hyper::{
+	body::{Body, Bytes, HttpBody},
+	client::HttpConnector,
+	Client,
+};
+use std::{
+	collections::HashSet,
+	future::Future,
+	io,
+	net::SocketAddr,
+	path::Path,
+	pin::Pin,
+	sync::Arc,
+	task::{Context, Poll},
+};
+use subtle::{Choice, ConstantTimeEq};
+
+/// The header authenticating the requests forwarded to the server of the authenticated
+/// requests.
+const FORWARDED_TOKEN_HEADER: &str = "x-substrate-rpc-forwarded";
+
+/// The access granted to a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+	/// The request is rejected.
+	Denied,
+	/// The request may call the safe methods only.
+	Safe,
+	/// The request may call all the methods exposed by the server.
+	Unsafe,
+}
+
+/// Evaluates the access granted to the requests.
+pub trait RpcAuthenticator: Send + Sync + std::fmt::Debug {
+	/// Evaluate the headers of an HTTP request or of a WebSocket handshake.
+	fn authenticate(&self, headers: &HeaderMap) -> Access;
+}
+
+/// Grants the unsafe access to the requests that provide one of the tokens in the
+/// `Authorization: Bearer <token>` header.
+///
+/// The requests without the header may call the safe methods, the requests with an
+/// unknown token are rejected.
+///
+/// The token is compared in constant time with every accepted token, such that the
+/// response time does not reveal how much of a token was guessed.
+#[derive(Debug, Clone)]
+pub struct BearerTokenAuthenticator {
+	/// The accepted tokens.
+	tokens: HashSet<String>,
+}
+
+impl BearerTokenAuthenticator {
+	/// Create a new [`BearerTokenAuthenticator`] accepting the given tokens.
+	pub fn new(tokens: impl IntoIterator<Item = String>) -> Self {
+		Self { tokens: tokens.into_iter().collect() }
+	}
+
+	/// Read the accepted tokens from a file, one token per line.
+	///
+	/// The empty lines and the lines starting with `#` are ignored.
+	pub fn from_file(path: &Path) -> io::Result<Self> {
+		let content = std::fs::read_to_string(path)?;
+		let tokens = content
+			.lines()
+			.map(str::trim)
+			.filter(|line| !line.is_empty() && !line.starts_with('#'))
+			.map(ToOwned::to_owned);
+		Ok(Self::new(tokens))
+	}
+}
+
+impl RpcAuthenticator for BearerTokenAuthenticator {
+	fn authenticate(&self, headers: &HeaderMap) -> Access {
+		let Some(value) = headers.get(AUTHORIZATION) else { return Access::Safe };
+
+		let token = value.to_str().ok().and_then(|value| value.strip_prefix("Bearer "));
+		let Some(token) = token.map(|token| token.trim().as_bytes()) else { return Access::Denied };
+
+		let known = self
+			.tokens
+			.iter()
+			.fold(Choice::from(0), |known, accepted| known | accepted.as_bytes().ct_eq(token));
+		if bool::from(known) {
+			Access::Unsafe
+		} else {
+			Access::Denied
+		}
+	}
+}
+
+/// Grants the unsafe access to the client identities forwarded by a TLS terminating proxy.
+///
+/// The proxy verifies the client certificate and forwards the identity of the client
+/// (ie the certificate subject) in the given header. The proxy must remove the header
+/// from the requests of the clients.
+#[derive(Debug, Clone)]
+pub struct ForwardedIdentityAuthenticator {
+	/// The header carrying the identity of the client.
+	header: HeaderName,
+	/// The identities granted the unsafe access.
+	identities: HashSet<String>,
+}
+
+impl ForwardedIdentityAuthenticator {
+	/// Create a new [`ForwardedIdentityAuthenticator`].
+	pub fn new(header: HeaderName, identities: impl IntoIterator<Item = String>) -> Self {
+		Self { header, identities: identities.into_iter().collect() }
+	}
+}
+
+impl RpcAuthenticator for ForwardedIdentityAuthenticator {
+	fn authenticate(&self, headers: &HeaderMap) -> Access {
+		let identity = headers.get(&self.header).and_then(|value| value.to_str().ok());
+		match identity {
+			Some(identity) if self.identities.contains(identity) => Access::Unsafe,
+			_ => Access::Safe,
+		}
+	}
+}
+
+/// Grants the unsafe access to the requests forwarded by an [`AuthLayer`], and rejects the
+/// other requests.
+#[derive(Debug, Clone)]
+pub(crate) struct ForwardedAuthenticator {
+	/// The random token of the forwarded requests.
+	token: HeaderValue,
+}
+
+impl ForwardedAuthenticator {
+	/// Create a new [`ForwardedAuthenticator`] with a random token.
+	pub(crate) fn new() -> Self {
+		let token = format!("{:032x}", rand::random::<u128>());
+		Self { token: HeaderValue::from_str(&token).expect("hex digits are valid; qed") }
+	}
+}
+
+impl RpcAuthenticator for ForwardedAuthenticator {
+	fn authenticate(&self, headers: &HeaderMap) -> Access {
+		let forwarded = headers
+			.get(FORWARDED_TOKEN_HEADER)
+			.map_or(false, |token| token.as_bytes().ct_eq(self.token.as_bytes()).into());
+		if forwarded {
+			Access::Unsafe
+		} else {
+			Access::Denied
+		}
+	}
+}
+
+/// The server to which the authenticated requests are forwarded.
+#[derive(Debug, Clone)]
+struct Forward {
+	/// The address of the server.
+	addr: SocketAddr,
+	/// The token of the forwarded requests.
+	token: HeaderValue,
+	/// The client forwarding the requests.
+	client: Client<HttpConnector>,
+}
+
+/// Layer that restricts the calls of the unsafe methods to the authenticated requests.
+///
+/// The layer does not restrict the requests if no authenticator is provided. The requests
+/// that are not authenticated are served by the inner service.
+#[derive(Debug, Clone)]
+pub struct AuthLayer {
+	/// The authenticator of the requests.
+	authenticator: Option<Arc<dyn RpcAuthenticator>>,
+	/// The server of the authenticated requests, the inner service if `None`.
+	forward: Option<Forward>,
+}
+
+impl AuthLayer {
+	/// Create a new [`AuthLayer`].
+	pub fn new(authenticator: Option<Arc<dyn RpcAuthenticator>>) -> Self {
+		AuthLayer { authenticator, forward: None }
+	}
+
+	/// Forward the authenticated requests to the server listening on the given address, which
+	/// authenticates them with the given [`ForwardedAuthenticator`].
+	pub(crate) fn forward_authenticated(
+		mut self,
+		addr: SocketAddr,
+		authenticator: &ForwardedAuthenticator,
+	) -> Self {
+		self.forward =
+			Some(Forward { addr, token: authenticator.token.clone(), client: Client::new() });
+		self
+	}
+}
+
+impl<S> tower::Layer<S> for AuthLayer {
+	type Service = AuthService<S>;
+
+	fn layer(&self, inner: S) -> Self::Service {
+		AuthService { inner, layer: self.clone() }
+	}
+}
+
+/// Service that forwards the requests granted the unsafe access to the server of the
+/// authenticated requests.
+#[derive(Debug, Clone)]
+pub struct AuthService<S> {
+	inner: S,
+	layer: AuthLayer,
+}
+
+impl<S> tower::Service<Request<Body>> for AuthService<S>
+where
+	S: tower::Service<Request<Body>, Response = Response<Body>>,
+	S::Future: Send + 'static,
+	S::Error: Send + 'static,
+{
+	type Response = S::Response;
+	type Error = S::Error;
+	type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+	fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		self.inner.poll_ready(cx)
+	}
+
+	fn call(&mut self, request: Request<Body>) -> Self::Future {
+		let Some(authenticator) = &self.layer.authenticator else {
+			return Box::pin(self.inner.call(request));
+		};
+
+		match (authenticator.authenticate(request.headers()), &self.layer.forward) {
+			(Access::Denied, _) => {
+				Box::pin(std::future::ready(Ok(reject(StatusCode::UNAUTHORIZED))))
+			},
+			(Access::Unsafe, Some(forward)) => {
+				let forward = forward.clone();
+				Box::pin(async move { Ok(forward_request(request, forward).await) })
+			},
+			(Access::Unsafe, None) | (Access::Safe, _) => Box::pin(self.inner.call(request)),
+		}
+	}
+}
+
+/// Forward the request to the server of the authenticated requests.
+///
+/// The WebSocket connection is forwarded once the handshake is accepted by the server.
+async fn forward_request(mut request: Request<Body>, forward: Forward) -> Response<Body> {
+	let upgrade = is_websocket_upgrade(&request).then(|| hyper::upgrade::on(&mut request));
+
+	let (mut parts, body) = request.into_parts();
+	let path = parts.uri.path_and_query().map_or("/", |path| path.as_str());
+	parts.uri = match format!("http://{}{}", forward.addr, path).parse() {
+		Ok(uri) => uri,
+		Err(_) => return reject(StatusCode::BAD_REQUEST),
+	};
+	parts
+		.headers
+		.insert(HeaderName::from_static(FORWARDED_TOKEN_HEADER), forward.token);
+
+	let mut response = match forward.client.request(Request::from_parts(parts, body)).await {
+		Ok(response) => response,
+		Err(err) => {
+			log::debug!(target: "rpc", "Failed to forward an authenticated request: {err}");
+			return reject(StatusCode::BAD_GATEWAY)
+		},
+	};
+
+	if let Some(upgrade) = upgrade {
+		if response.status() == StatusCode::SWITCHING_PROTOCOLS {
+			let forwarded = hyper::upgrade::on(&mut response);
+			tokio::spawn(async move {
+				let (Ok(mut forwarded), Ok(mut upgraded)) = (forwarded.await, upgrade.await) else {
+					return
+				};
+				let _ = tokio::io::copy_bidirectional(&mut upgraded, &mut forwarded).await;
+			});
+		}
+	}
+
+	response
+}
+
+/// Build the response of a rejected request.
+pub(crate) fn reject<RespBody: Default>(status: StatusCode) -> Response<RespBody> {
+	let mut response = Response::new(RespBody::default());
+	*response.status_mut() = status;
+	response
+}
+
+/// Returns true if the request is a WebSocket handshake.
+fn is_websocket_upgrade<B>(request: &Request<B>) -> bool {
+	request
+		.headers()
+		.get(UPGRADE)
+		.and_then(|value| value.to_str().ok())
+		.map_or(false, |value| value.eq_ignore_ascii_case("websocket"))
+}
+
+/// Read the body of the request, up to the given size.
+pub(crate) async fn read_body(mut body: Body, max_size: usize) -> Result<Bytes, StatusCode> {
+	let mut bytes = Vec::new();
+	while let Some(chunk) = body.data().await {
+		let chunk = chunk.map_err(|_| StatusCode::BAD_REQUEST)?;
+		if bytes.len() + chunk.len() > max_size {
+			return Err(StatusCode::PAYLOAD_TOO_LARGE);
+		}
+		bytes.extend_from_slice(&chunk);
+	}
+
+	Ok(bytes.into())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use jsonrpsee::{core::Error as JsonRpseeError, RpcModule};
+
+	#[test]
+	fn bearer_token_access() {
+		let authenticator =
+			BearerTokenAuthenticator::new(vec!["secret".to_string(), "other".to_string()]);
+		let mut headers = HeaderMap::new();
+		assert_eq!(authenticator.authenticate(&headers), Access::Safe);
+
+		headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer secret"));
+		assert_eq!(authenticator.authenticate(&headers), Access::Unsafe);
+
+		headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer other"));
+		assert_eq!(authenticator.authenticate(&headers), Access::Unsafe);
+
+		headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer unknown"));
+		assert_eq!(authenticator.authenticate(&headers), Access::Denied);
+
+		headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer secre"));
+		assert_eq!(authenticator.authenticate(&headers), Access::Denied);
+	}
+
+	#[test]
+	fn forwarded_identity_access() {
+		let header = HeaderName::from_static("x-client-identity");
+		let authenticator =
+			ForwardedIdentityAuthenticator::new(header.clone(), vec!["CN=operator".to_string()]);
+		let mut headers = HeaderMap::new();
+		assert_eq!(authenticator.authenticate(&headers), Access::Safe);
+
+		headers.insert(header.clone(), HeaderValue::from_static("CN=operator"));
+		assert_eq!(authenticator.authenticate(&headers), Access::Unsafe);
+
+		headers.insert(header, HeaderValue::from_static("CN=public"));
+		assert_eq!(authenticator.authenticate(&headers), Access::Safe);
+	}
+
+	/// Start a server whose `author_rotateKeys` method is unsafe, with an authenticator.
+	async fn start_authenticated_server(addr: SocketAddr) -> crate::Server {
+		// The method checks the unsafe access when called, as it is registered by both modules.
+		fn rpc_api(deny_unsafe: bool) -> RpcModule<()> {
+			let mut rpc_api = RpcModule::new(());
+			rpc_api
+				.register_method("author_rotateKeys", move |_, _| {
+					if deny_unsafe {
+						return Err(JsonRpseeError::Custom("RPC call is unsafe".into()))
+					}
+					Ok("0x00")
+				})
+				.unwrap();
+			rpc_api
+		}
+
+		let config = crate::Config {
+			addrs: [addr, addr],
+			cors: None,
+			cors_policy: None,
+			max_connections: 10,
+			max_subs_per_conn: 10,
+			ping_interval: None,
+			max_payload_in_mb: 1,
+			max_payload_out_mb: 1,
+			response_limits: Default::default(),
+			batch_limits: Default::default(),
+			http_compression: false,
+			metrics: None,
+			rpc_api: rpc_api(true),
+			authenticated_rpc_api: Some(rpc_api(false)),
+			unsafe_methods: Default::default(),
+			qos_tier: None,
+			authenticator: Some(Arc::new(BearerTokenAuthenticator::new(
+				vec!["secret".to_string()],
+			))),
+			request_hooks: Vec::new(),
+			health_provider: None,
+			health_max_finality_lag: None,
+			id_provider: None,
+			tokio_handle: tokio::runtime::Handle::current(),
+		};
+		crate::start_server(config).await.unwrap()
+	}
+
+	/// Call `author_rotateKeys` with the given token.
+	async fn rotate_keys(addr: SocketAddr, token: Option<&str>) -> serde_json::Value {
+		let mut request = Request::post(format!("http://{addr}"))
+			.header("content-type", "application/json")
+			.body(Body::from(r#"{"jsonrpc":"2.0","id":1,"method":"author_rotateKeys"}"#))
+			.unwrap();
+		if let Some(token) = token {
+			request
+				.headers_mut()
+				.insert(AUTHORIZATION, HeaderValue::from_str(&format!("Bearer {token}")).unwrap());
+		}
+
+		let response = Client::new().request(request).await.unwrap();
+		let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+		serde_json::from_slice(&body).unwrap()
+	}
+
+	/// Send a WebSocket handshake with the given token and return the status of the response.
+	async fn websocket_handshake(addr: SocketAddr, token: Option<&str>) -> StatusCode {
+		let mut request = Request::get(format!("http://{addr}"))
+			.header(UPGRADE, "websocket")
+			.header("connection", "upgrade")
+			.header("sec-websocket-key", "dGhlIHNhbXBsZSBub25jZQ==")
+			.header("sec-websocket-version", "13")
+			.body(Body::empty())
+			.unwrap();
+		if let Some(token) = token {
+			request
+				.headers_mut()
+				.insert(AUTHORIZATION, HeaderValue::from_str(&format!("Bearer {token}")).unwrap());
+		}
+
+		Client::new().request(request).await.unwrap().status()
+	}
+
+	#[tokio::test]
+	async fn unauthenticated_calls_are_denied_unsafe_methods() {
+		let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+		let server = start_authenticated_server(addr).await;
+
+		let response = rotate_keys(addr, None).await;
+		assert!(response.get("error").is_some(), "{response}");
+
+		let response = rotate_keys(addr, Some("secret")).await;
+		assert_eq!(response["result"], "0x00");
+
+		// The WebSocket connections are accepted, the unauthenticated ones being served by the
+		// module denying the unsafe methods.
+		assert_eq!(websocket_handshake(addr, None).await, StatusCode::SWITCHING_PROTOCOLS);
+		assert_eq!(
+			websocket_handshake(addr, Some("secret")).await,
+			StatusCode::SWITCHING_PROTOCOLS
+		);
+		assert_eq!(websocket_handshake(addr, Some("unknown")).await, StatusCode::UNAUTHORIZED);
+
+		server.stop().unwrap();
+	}
+}
@@ -0,0 +1,259 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Limits of the batch requests.
+//!
+//! A batch request may carry many calls to the methods iterating the storage, each of them
+//! being much more expensive than a call to the other methods. The batches are limited by
+//! their number of calls and by their cumulative cost, the heavy methods costing
+//! [`HEAVY_METHOD_COST`] and the other methods costing 1.
+//!
+//! The calls sent over a WebSocket connection are not visible to the HTTP middleware, such
+//! that only the batches of the HTTP requests are limited.
+
+use http::{
+	header::{HeaderValue, CONTENT_TYPE, UPGRADE},
+	Request, Response,
+};
+use hyper::body::Body;
+use std::{
+	future::Future,
+	pin::Pin,
+	task::{Context, Poll},
+};
+
+/// The cost of a call to a heavy method.
+pub const HEAVY_METHOD_COST: u32 = 10;
+
+/// The methods iterating the storage or executing the runtime.
+const HEAVY_METHODS: &[&str] = &[
+	"state_call",
+	"state_callAt",
+	"state_getKeys",
+	"state_getPairs",
+	"state_getKeysPaged",
+	"state_getKeysPagedAt",
+	"state_getMetadata",
+	"state_queryStorage",
+	"state_queryStorageAt",
+	"state_getReadProof",
+	"state_getChildReadProof",
+	"state_traceBlock",
+	"childstate_getKeys",
+	"childstate_getKeysPaged",
+	"childstate_getKeysPagedAt",
+	"childstate_getStorageEntries",
+];
+
+/// The error code of the rejected batches.
+const BATCH_TOO_LARGE_CODE: i32 = -32010;
+
+/// The limits of the batch requests, the batches are not limited if `None`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BatchLimits {
+	/// The maximum number of calls of a batch.
+	pub max_calls: Option<u32>,
+	/// The maximum cumulative cost of the calls of a batch.
+	pub max_cost: Option<u32>,
+}
+
+impl BatchLimits {
+	/// Returns true if the batches are limited.
+	pub fn is_limited(&self) -> bool {
+		self.max_calls.is_some() || self.max_cost.is_some()
+	}
+
+	/// Returns the reason of the rejection of the batch calling the given methods, `None` if
+	/// the batch is within the limits.
+	fn check<'a>(&self, methods: impl IntoIterator<Item = &'a str>) -> Option<String> {
+		let (calls, cost) = methods.into_iter().fold((0u32, 0u32), |(calls, cost), method| {
+			(calls.saturating_add(1), cost.saturating_add(method_cost(method)))
+		});
+
+		match (self.max_calls, self.max_cost) {
+			(Some(max_calls), _) if calls > max_calls =>
+				Some(format!("Exceeded max limit of {} calls", max_calls)),
+			(_, Some(max_cost)) if cost > max_cost =>
+				Some(format!("Exceeded max cost of {}, the batch costs {}", max_cost, cost)),
+			_ => None,
+		}
+	}
+}
+
+/// Returns the cost of a call to the method.
+fn method_cost(method: &str) -> u32 {
+	if HEAVY_METHODS.contains(&method) {
+		HEAVY_METHOD_COST
+	} else {
+		1
+	}
+}
+
+/// Layer that rejects the batch requests exceeding the limits.
+#[derive(Debug, Clone)]
+pub struct BatchLimitsLayer {
+	/// The limits of the batches.
+	limits: BatchLimits,
+	/// The maximum size of the buffered request bodies.
+	max_body_size: usize,
+}
+
+impl BatchLimitsLayer {
+	/// Create a new [`BatchLimitsLayer`].
+	pub fn new(limits: BatchLimits, max_body_size: usize) -> Self {
+		BatchLimitsLayer { limits, max_body_size }
+	}
+}
+
+impl<S> tower::Layer<S> for BatchLimitsLayer {
+	type Service = BatchLimitsService<S>;
+
+	fn layer(&self, inner: S) -> Self::Service {
+		BatchLimitsService { inner, layer: self.clone() }
+	}
+}
+
+/// Service that rejects the batch requests exceeding the limits.
+#[derive(Debug, Clone)]
+pub struct BatchLimitsService<S> {
+	inner: S,
+	layer: BatchLimitsLayer,
+}
+
+impl<S> tower::Service<Request<Body>> for BatchLimitsService<S>
+where
+	S: tower::Service<Request<Body>, Response = Response<Body>>,
+	S::Future: Send + 'static,
+	S::Error: Send + 'static,
+{
+	type Response = S::Response;
+	type Error = S::Error;
+	type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+	fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		self.inner.poll_ready(cx)
+	}
+
+	fn call(&mut self, request: Request<Body>) -> Self::Future {
+		if self.layer.limits == BatchLimits::default() || request.headers().contains_key(UPGRADE) {
+			return Box::pin(self.inner.call(request))
+		}
+
+		// The request is forwarded right away, while its body is provided once the batch
+		// is checked. The server does not read the body until the response is polled.
+		let (parts, body) = request.into_parts();
+		let (mut sender, forwarded) = Body::channel();
+		let response = self.inner.call(Request::from_parts(parts, forwarded));
+
+		let layer = self.layer.clone();
+		Box::pin(async move {
+			let bytes = match crate::auth::read_body(body, layer.max_body_size).await {
+				Ok(bytes) => bytes,
+				Err(status) => return Ok(crate::auth::reject(status)),
+			};
+
+			if let Some(reason) = check_batch(&bytes, &layer.limits) {
+				let mut response =
+					Response::new(Body::from(batch_too_large_error(reason).to_string()));
+				response
+					.headers_mut()
+					.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+				return Ok(response)
+			}
+
+			let _ = sender.try_send_data(bytes);
+			drop(sender);
+			response.await
+		})
+	}
+}
+
+/// Returns the reason of the rejection of the JSON-RPC request, `None` if the request is not
+/// a batch or is within the limits.
+///
+/// The malformed requests are left to the server to report.
+fn check_batch(request: &[u8], limits: &BatchLimits) -> Option<String> {
+	let calls = match serde_json::from_slice::<serde_json::Value>(request) {
+		Ok(serde_json::Value::Array(calls)) => calls,
+		_ => return None,
+	};
+
+	limits.check(
+		calls
+			.iter()
+			.map(|call| call.get("method").and_then(|method| method.as_str()).unwrap_or_default()),
+	)
+}
+
+/// Build the error returned instead of the responses of a rejected batch.
+fn batch_too_large_error(reason: String) -> serde_json::Value {
+	serde_json::json!({
+		"jsonrpc": "2.0",
+		"error": {
+			"code": BATCH_TOO_LARGE_CODE,
+			"message": "The batch request was too large",
+			"data": reason,
+		},
+		"id": null,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tower::{Layer, Service};
+
+	type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+	#[test]
+	fn heavy_methods_cost_more() {
+		let limits = BatchLimits { max_calls: Some(3), max_cost: Some(12) };
+		assert_eq!(limits.check(["system_health", "state_getStorage"]), None);
+		assert_eq!(limits.check(["state_getKeysPaged", "state_getStorage", "system_health"]), None);
+		assert!(limits.check(["system_health"; 4]).unwrap().contains("calls"));
+		assert!(limits.check(["state_getKeysPaged", "state_getPairs"]).unwrap().contains("cost"));
+	}
+
+	#[tokio::test]
+	async fn rejects_the_large_batches() {
+		let inner = tower::service_fn(|request: Request<Body>| async move {
+			let request = hyper::body::to_bytes(request.into_body()).await?;
+			Ok::<_, BoxError>(Response::new(Body::from(request)))
+		});
+		let limits = BatchLimits { max_calls: Some(2), max_cost: None };
+		let mut service = BatchLimitsLayer::new(limits, 1024 * 1024).layer(inner);
+
+		let call = r#"{"jsonrpc":"2.0","id":1,"method":"system_health"}"#;
+		let batch =
+			|calls: usize| Request::new(Body::from(format!("[{}]", vec![call; calls].join(","))));
+
+		let response = service.call(batch(2)).await.unwrap();
+		let response = hyper::body::to_bytes(response.into_body()).await.unwrap();
+		assert_eq!(serde_json::from_slice::<serde_json::Value>(&response).unwrap()[1]["id"], 1);
+
+		let response = service.call(batch(3)).await.unwrap();
+		let response = hyper::body::to_bytes(response.into_body()).await.unwrap();
+		let response = serde_json::from_slice::<serde_json::Value>(&response).unwrap();
+		assert_eq!(response["error"]["code"], BATCH_TOO_LARGE_CODE);
+
+		// The single calls are not limited.
+		let response = service.call(Request::new(Body::from(call))).await.unwrap();
+		let response = hyper::body::to_bytes(response.into_body()).await.unwrap();
+		assert_eq!(response, call.as_bytes());
+	}
+}
@@ -0,0 +1,249 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Compression of the HTTP responses.
+//!
+//! The responses are compressed with the encoding negotiated through the `Accept-Encoding`
+//! header of the request, brotli being preferred over gzip. The WebSocket handshakes and the
+//! small responses are left untouched.
+
+use http::{
+	header::{HeaderValue, ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH, UPGRADE, VARY},
+	Request, Response, StatusCode,
+};
+use hyper::body::{Body, HttpBody};
+use std::{
+	future::Future,
+	io::Write,
+	pin::Pin,
+	task::{Context, Poll},
+};
+
+/// The responses smaller than this are not worth compressing.
+const MIN_COMPRESSED_SIZE: usize = 1024;
+
+/// The quality of the brotli compression, a trade-off favoring the latency of the calls.
+const BROTLI_QUALITY: u32 = 5;
+
+/// The base 2 logarithm of the size of the brotli window.
+const BROTLI_WINDOW: u32 = 22;
+
+/// The encodings of the compressed responses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+	Brotli,
+	Gzip,
+}
+
+impl Encoding {
+	/// Returns the preferred encoding accepted by the request, if any.
+	fn negotiate<B>(request: &Request<B>) -> Option<Self> {
+		let mut accepted = None;
+		for value in request.headers().get_all(ACCEPT_ENCODING) {
+			let Ok(value) = value.to_str() else { continue };
+			for item in value.split(',') {
+				let mut params = item.split(';').map(str::trim);
+				let name = params.next().unwrap_or_default();
+				let refused = params.any(|param| {
+					param
+						.strip_prefix("q=")
+						.and_then(|q| q.parse::<f32>().ok())
+						.map_or(false, |q| q <= 0.0)
+				});
+				if refused {
+					continue
+				}
+				let encoding = match name {
+					"br" => Encoding::Brotli,
+					"gzip" => Encoding::Gzip,
+					_ => continue,
+				};
+				if accepted != Some(Encoding::Brotli) {
+					accepted = Some(encoding);
+				}
+			}
+		}
+		accepted
+	}
+
+	fn header_value(self) -> HeaderValue {
+		match self {
+			Encoding::Brotli => HeaderValue::from_static("br"),
+			Encoding::Gzip => HeaderValue::from_static("gzip"),
+		}
+	}
+
+	fn compress(self, data: &[u8]) -> std::io::Result<Vec<u8>> {
+		match self {
+			Encoding::Brotli => {
+				let mut encoder =
+					brotli::CompressorWriter::new(Vec::new(), 4096, BROTLI_QUALITY, BROTLI_WINDOW);
+				encoder.write_all(data)?;
+				Ok(encoder.into_inner())
+			},
+			Encoding::Gzip => {
+				let mut encoder =
+					flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+				encoder.write_all(data)?;
+				encoder.finish()
+			},
+		}
+	}
+}
+
+/// Layer compressing the HTTP responses.
+#[derive(Debug, Clone)]
+pub struct CompressionLayer {
+	enabled: bool,
+}
+
+impl CompressionLayer {
+	/// Create a new [`CompressionLayer`], the responses are not compressed unless `enabled`.
+	pub fn new(enabled: bool) -> Self {
+		CompressionLayer { enabled }
+	}
+}
+
+impl<S> tower::Layer<S> for CompressionLayer {
+	type Service = CompressionService<S>;
+
+	fn layer(&self, inner: S) -> Self::Service {
+		CompressionService { inner, layer: self.clone() }
+	}
+}
+
+/// Service that compresses the HTTP responses.
+#[derive(Debug, Clone)]
+pub struct CompressionService<S> {
+	inner: S,
+	layer: CompressionLayer,
+}
+
+impl<S> tower::Service<Request<Body>> for CompressionService<S>
+where
+	S: tower::Service<Request<Body>, Response = Response<Body>>,
+	S::Future: Send + 'static,
+	S::Error: From<hyper::Error> + Send + 'static,
+{
+	type Response = S::Response;
+	type Error = S::Error;
+	type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+	fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		self.inner.poll_ready(cx)
+	}
+
+	fn call(&mut self, request: Request<Body>) -> Self::Future {
+		let encoding = match Encoding::negotiate(&request) {
+			Some(encoding) if self.layer.enabled && !request.headers().contains_key(UPGRADE) =>
+				encoding,
+			_ => return Box::pin(self.inner.call(request)),
+		};
+
+		let response = self.inner.call(request);
+		Box::pin(async move {
+			let response = response.await?;
+			let is_small = response
+				.body()
+				.size_hint()
+				.upper()
+				.map_or(false, |size| size < MIN_COMPRESSED_SIZE as u64);
+			if response.status() == StatusCode::SWITCHING_PROTOCOLS ||
+				response.headers().contains_key(CONTENT_ENCODING) ||
+				is_small
+			{
+				return Ok(response)
+			}
+
+			// The JSON-RPC responses are built in memory by the server anyway.
+			let (mut parts, body) = response.into_parts();
+			let bytes = hyper::body::to_bytes(body).await?;
+			if bytes.len() < MIN_COMPRESSED_SIZE {
+				return Ok(Response::from_parts(parts, bytes.into()))
+			}
+
+			let compressed = match encoding.compress(&bytes) {
+				Ok(compressed) => compressed,
+				Err(e) => {
+					log::debug!("Failed to compress an RPC response: {}", e);
+					return Ok(Response::from_parts(parts, bytes.into()))
+				},
+			};
+			parts.headers.insert(CONTENT_ENCODING, encoding.header_value());
+			parts.headers.insert(CONTENT_LENGTH, compressed.len().into());
+			parts.headers.append(VARY, HeaderValue::from_static("accept-encoding"));
+			Ok(Response::from_parts(parts, compressed.into()))
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::io::Read;
+	use tower::{Layer, Service};
+
+	type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+	fn request(accept_encoding: &str) -> Request<Body> {
+		Request::builder()
+			.header(ACCEPT_ENCODING, accept_encoding)
+			.body(Body::empty())
+			.unwrap()
+	}
+
+	#[test]
+	fn negotiates_the_preferred_encoding() {
+		assert_eq!(Encoding::negotiate(&request("gzip, deflate")), Some(Encoding::Gzip));
+		assert_eq!(Encoding::negotiate(&request("gzip, br;q=0.5")), Some(Encoding::Brotli));
+		assert_eq!(Encoding::negotiate(&request("br;q=0, gzip")), Some(Encoding::Gzip));
+		assert_eq!(Encoding::negotiate(&request("deflate, identity")), None);
+		assert_eq!(Encoding::negotiate(&Request::new(())), None);
+	}
+
+	#[tokio::test]
+	async fn compresses_the_large_responses() {
+		let large = "0x".to_string() + &"ab".repeat(MIN_COMPRESSED_SIZE);
+		let inner = tower::service_fn(move |request: Request<Body>| {
+			let body = if request.uri().path() == "/large" { large.clone() } else { "{}".into() };
+			async move { Ok::<_, BoxError>(Response::new(Body::from(body))) }
+		});
+		let mut service = CompressionLayer::new(true).layer(inner);
+
+		let mut large_request = request("gzip");
+		*large_request.uri_mut() = "/large".parse().unwrap();
+		let response = service.call(large_request).await.unwrap();
+		assert_eq!(response.headers()[CONTENT_ENCODING], "gzip");
+		let compressed = hyper::body::to_bytes(response.into_body()).await.unwrap();
+		let mut decompressed = String::new();
+		flate2::read::GzDecoder::new(&compressed[..])
+			.read_to_string(&mut decompressed)
+			.unwrap();
+		assert!(decompressed.starts_with("0xabab"));
+		assert!(compressed.len() < decompressed.len());
+
+		let response = service.call(request("gzip")).await.unwrap();
+		assert!(!response.headers().contains_key(CONTENT_ENCODING));
+
+		let mut service = CompressionLayer::new(false).layer(service.inner);
+		let mut large_request = request("gzip");
+		*large_request.uri_mut() = "/large".parse().unwrap();
+		let response = service.call(large_request).await.unwrap();
+		assert!(!response.headers().contains_key(CONTENT_ENCODING));
+	}
+}
@@ -0,0 +1,386 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Per-origin CORS policy of the RPC servers.
+//!
+//! A node serving several frontends may grant each of them its own request headers and
+//! methods. The rules of the [`CorsPolicy`] are evaluated in order, the first rule whose
+//! origin pattern matches the `Origin` of the request applies. The requests of the origins
+//! matching no rule are served without the CORS headers, such that the browsers reject
+//! their responses.
+
+use http::{
+	header::{self, HeaderValue},
+	Method, Request, Response, StatusCode,
+};
+use serde::Deserialize;
+use std::{
+	fmt,
+	future::Future,
+	path::Path,
+	pin::Pin,
+	sync::Arc,
+	task::{Context, Poll},
+};
+
+/// A rule of the CORS policy.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CorsRule {
+	/// The pattern of the origins of the rule.
+	///
+	/// Either `*`, matching all the origins, or `<scheme>://<host>[:<port>]`, where the host
+	/// may start with `*.` to match its subdomains and the port may be `*` to match all the
+	/// ports, e.g. `https://*.example.com` or `http://localhost:*`.
+	pub origin: String,
+	/// The request headers allowed for the origins of the rule.
+	#[serde(default = "default_allowed_headers")]
+	pub allowed_headers: Vec<String>,
+	/// The HTTP methods allowed for the origins of the rule.
+	#[serde(default = "default_allowed_methods")]
+	pub allowed_methods: Vec<String>,
+}
+
+fn default_allowed_headers() -> Vec<String> {
+	vec!["content-type".into()]
+}
+
+fn default_allowed_methods() -> Vec<String> {
+	vec!["GET".into(), "POST".into()]
+}
+
+impl CorsRule {
+	/// Returns true if the origin matches the pattern of the rule.
+	pub fn matches(&self, origin: &str) -> bool {
+		if self.origin == "*" {
+			return true
+		}
+
+		let (Some((scheme, host, port)), Some((pattern_scheme, pattern_host, pattern_port))) =
+			(split_origin(origin), split_origin(&self.origin))
+		else {
+			return false
+		};
+
+		let host_matches = match pattern_host.strip_prefix("*.") {
+			Some(domain) => host
+				.strip_suffix(domain)
+				.map_or(false, |subdomain| subdomain.len() > 1 && subdomain.ends_with('.')),
+			None => host.eq_ignore_ascii_case(pattern_host),
+		};
+
+		scheme.eq_ignore_ascii_case(pattern_scheme) &&
+			host_matches &&
+			(pattern_port == Some("*") || port == pattern_port)
+	}
+}
+
+/// Split an origin into its scheme, host and port.
+fn split_origin(origin: &str) -> Option<(&str, &str, Option<&str>)> {
+	let (scheme, authority) = origin.split_once("://")?;
+	Some(match authority.rsplit_once(':') {
+		// The colons of an IPv6 host are enclosed in brackets.
+		Some((host, port)) if !port.contains(']') => (scheme, host, Some(port)),
+		_ => (scheme, authority, None),
+	})
+}
+
+/// The per-origin CORS policy of the RPC servers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CorsPolicy {
+	/// The rules, evaluated in order.
+	pub rules: Vec<CorsRule>,
+	/// The time the browsers may cache the responses of the preflight requests, in seconds.
+	#[serde(default)]
+	pub max_age: Option<u64>,
+}
+
+/// Errors of the CORS policy.
+#[derive(Debug)]
+pub enum CorsPolicyError {
+	/// The policy file could not be read.
+	Io(std::io::Error),
+	/// The policy file is not valid JSON.
+	Json(serde_json::Error),
+	/// The header or the method of a rule is invalid.
+	InvalidRule(String),
+}
+
+impl fmt::Display for CorsPolicyError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			CorsPolicyError::Io(err) => write!(f, "Failed to read the CORS policy: {}", err),
+			CorsPolicyError::Json(err) => write!(f, "Invalid CORS policy: {}", err),
+			CorsPolicyError::InvalidRule(origin) =>
+				write!(f, "Invalid header or method in the CORS rule of `{}`", origin),
+		}
+	}
+}
+
+impl std::error::Error for CorsPolicyError {}
+
+impl CorsPolicy {
+	/// Read and validate the policy from the given JSON file.
+	pub fn from_file(path: &Path) -> Result<Self, CorsPolicyError> {
+		let file = std::fs::File::open(path).map_err(CorsPolicyError::Io)?;
+		let policy: CorsPolicy = serde_json::from_reader(std::io::BufReader::new(file))
+			.map_err(CorsPolicyError::Json)?;
+		policy.validate()?;
+		Ok(policy)
+	}
+
+	/// Check that the headers and the methods of the rules are valid.
+	pub fn validate(&self) -> Result<(), CorsPolicyError> {
+		for rule in &self.rules {
+			let valid_headers = rule
+				.allowed_headers
+				.iter()
+				.all(|name| name.parse::<header::HeaderName>().is_ok());
+			let valid_methods =
+				rule.allowed_methods.iter().all(|method| method.parse::<Method>().is_ok());
+			if !valid_headers || !valid_methods {
+				return Err(CorsPolicyError::InvalidRule(rule.origin.clone()))
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Get the rule of the origin.
+	pub fn rule(&self, origin: &str) -> Option<&CorsRule> {
+		self.rules.iter().find(|rule| rule.matches(origin))
+	}
+}
+
+/// Layer applying the per-origin CORS policy.
+#[derive(Debug, Clone)]
+pub struct CorsPolicyLayer {
+	policy: Arc<CorsPolicy>,
+}
+
+impl CorsPolicyLayer {
+	/// Create a new [`CorsPolicyLayer`].
+	pub fn new(policy: CorsPolicy) -> Self {
+		CorsPolicyLayer { policy: Arc::new(policy) }
+	}
+}
+
+impl<S> tower::Layer<S> for CorsPolicyLayer {
+	type Service = CorsPolicyService<S>;
+
+	fn layer(&self, inner: S) -> Self::Service {
+		CorsPolicyService { inner, layer: self.clone() }
+	}
+}
+
+/// Service that answers the preflight requests and adds the CORS headers to the responses
+/// of the allowed origins.
+#[derive(Debug, Clone)]
+pub struct CorsPolicyService<S> {
+	inner: S,
+	layer: CorsPolicyLayer,
+}
+
+impl<S, ReqBody, RespBody> tower::Service<Request<ReqBody>> for CorsPolicyService<S>
+where
+	S: tower::Service<Request<ReqBody>, Response = Response<RespBody>>,
+	S::Future: Send + 'static,
+	S::Error: Send + 'static,
+	RespBody: Default + Send + 'static,
+{
+	type Response = S::Response;
+	type Error = S::Error;
+	type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+	fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		self.inner.poll_ready(cx)
+	}
+
+	fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+		let origin = request.headers().get(header::ORIGIN).cloned();
+		let rule = origin
+			.as_ref()
+			.and_then(|origin| origin.to_str().ok())
+			.and_then(|origin| self.layer.policy.rule(origin))
+			.cloned();
+
+		let is_preflight = request.method() == Method::OPTIONS &&
+			request.headers().contains_key(header::ACCESS_CONTROL_REQUEST_METHOD);
+		if is_preflight {
+			let mut response = Response::new(RespBody::default());
+			if let (Some(origin), Some(rule)) = (origin, rule) {
+				add_preflight_headers(
+					response.headers_mut(),
+					origin,
+					&rule,
+					self.layer.policy.max_age,
+				);
+			} else {
+				*response.status_mut() = StatusCode::FORBIDDEN;
+			}
+			return Box::pin(std::future::ready(Ok(response)))
+		}
+
+		let response = self.inner.call(request);
+		Box::pin(async move {
+			let mut response = response.await?;
+			let headers = response.headers_mut();
+			headers.append(header::VARY, HeaderValue::from_static("origin"));
+			if let (Some(origin), Some(_)) = (origin, rule) {
+				headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+			}
+			Ok(response)
+		})
+	}
+}
+
+/// Add the CORS headers of the response to a preflight request of an allowed origin.
+fn add_preflight_headers(
+	headers: &mut http::HeaderMap,
+	origin: HeaderValue,
+	rule: &CorsRule,
+	max_age: Option<u64>,
+) {
+	let join = |values: &[String]| HeaderValue::from_str(&values.join(", ")).ok();
+
+	headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+	headers.append(header::VARY, HeaderValue::from_static("origin"));
+	if let Some(methods) = join(&rule.allowed_methods) {
+		headers.insert(header::ACCESS_CONTROL_ALLOW_METHODS, methods);
+	}
+	if let Some(allowed_headers) = join(&rule.allowed_headers) {
+		headers.insert(header::ACCESS_CONTROL_ALLOW_HEADERS, allowed_headers);
+	}
+	if let Some(max_age) = max_age {
+		headers.insert(header::ACCESS_CONTROL_MAX_AGE, max_age.into());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tower::{Layer, Service};
+
+	type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+	fn rule(origin: &str) -> CorsRule {
+		CorsRule {
+			origin: origin.into(),
+			allowed_headers: default_allowed_headers(),
+			allowed_methods: default_allowed_methods(),
+		}
+	}
+
+	#[test]
+	fn matches_the_origin_patterns() {
+		assert!(rule("*").matches("https://example.com"));
+
+		let exact = rule("https://app.example.com");
+		assert!(exact.matches("https://app.example.com"));
+		assert!(!exact.matches("http://app.example.com"));
+		assert!(!exact.matches("https://app.example.com:8443"));
+
+		let subdomains = rule("https://*.example.com");
+		assert!(subdomains.matches("https://app.example.com"));
+		assert!(subdomains.matches("https://a.b.example.com"));
+		assert!(!subdomains.matches("https://example.com"));
+		assert!(!subdomains.matches("https://badexample.com"));
+
+		let any_port = rule("http://localhost:*");
+		assert!(any_port.matches("http://localhost:3000"));
+		assert!(!any_port.matches("http://localhost.evil.com:3000"));
+	}
+
+	#[test]
+	fn parses_the_policy() {
+		let policy: CorsPolicy = serde_json::from_str(
+			r#"{
+				"rules": [
+					{ "origin": "https://*.example.com", "allowedHeaders": ["content-type", "x-api-key"] },
+					{ "origin": "http://localhost:*", "allowedMethods": ["POST"] }
+				],
+				"maxAge": 600
+			}"#,
+		)
+		.unwrap();
+		assert!(policy.validate().is_ok());
+		assert_eq!(
+			policy.rule("https://app.example.com").unwrap().allowed_headers,
+			vec!["content-type", "x-api-key"]
+		);
+		assert_eq!(policy.rule("http://localhost:3000").unwrap().allowed_methods, vec!["POST"]);
+		assert!(policy.rule("https://other.org").is_none());
+
+		let invalid = CorsPolicy {
+			rules: vec![CorsRule { allowed_headers: vec!["bad header".into()], ..rule("*") }],
+			max_age: None,
+		};
+		assert!(matches!(invalid.validate(), Err(CorsPolicyError::InvalidRule(_))));
+	}
+
+	#[tokio::test]
+	async fn applies_the_rule_of_the_origin() {
+		let inner = tower::service_fn(|_: Request<()>| async {
+			Ok::<_, BoxError>(Response::new(String::from("{}")))
+		});
+		let policy = CorsPolicy {
+			rules: vec![CorsRule {
+				allowed_headers: vec!["x-api-key".into()],
+				..rule("https://*.example.com")
+			}],
+			max_age: Some(600),
+		};
+		let mut service = CorsPolicyLayer::new(policy).layer(inner);
+
+		let preflight = |origin: &str| {
+			Request::builder()
+				.method(Method::OPTIONS)
+				.header(header::ORIGIN, origin)
+				.header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+				.body(())
+				.unwrap()
+		};
+		let response = service.call(preflight("https://app.example.com")).await.unwrap();
+		assert_eq!(response.status(), StatusCode::OK);
+		assert_eq!(
+			response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+			"https://app.example.com"
+		);
+		assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_HEADERS], "x-api-key");
+		assert_eq!(response.headers()[header::ACCESS_CONTROL_MAX_AGE], "600");
+
+		let response = service.call(preflight("https://other.org")).await.unwrap();
+		assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+		let request = |origin: &str| {
+			Request::builder()
+				.method(Method::POST)
+				.header(header::ORIGIN, origin)
+				.body(())
+				.unwrap()
+		};
+		let response = service.call(request("https://app.example.com")).await.unwrap();
+		assert_eq!(
+			response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+			"https://app.example.com"
+		);
+		let response = service.call(request("https://other.org")).await.unwrap();
+		assert!(!response.headers().contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+	}
+}
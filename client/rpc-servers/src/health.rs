@@ -0,0 +1,342 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Health endpoints of the RPC servers.
+//!
+//! The `GET /health`, `GET /ready` and `GET /live` requests are answered with the health of
+//! the node as JSON, such that the probes of the orchestrators do not need a JSON-RPC client:
+//!
+//! - `/live` succeeds as long as the server answers the requests.
+//! - `/health` reports the sync status, the number of peers and the finality lag.
+//! - `/ready` reports the same health, and fails with `503 Service Unavailable` while the node
+//!   is syncing, has no peers although it should have some, or lags behind the finalized
+//!   block by more than the configured number of blocks.
+//!
+//! The endpoints are served by the RPC servers, and optionally by a dedicated server started
+//! by [`start_health_server`].
+
+use http::{
+	header::{HeaderValue, CONTENT_TYPE},
+	Method, Request, Response, StatusCode,
+};
+use hyper::{
+	body::Body,
+	service::{make_service_fn, service_fn},
+};
+use serde::Serialize;
+use std::{
+	convert::Infallible,
+	error::Error as StdError,
+	future::Future,
+	net::SocketAddr,
+	pin::Pin,
+	sync::Arc,
+	task::{Context, Poll},
+};
+use tower::Layer;
+
+/// The health of the node.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeHealth {
+	/// Whether the node is syncing.
+	pub is_syncing: bool,
+	/// The number of connected peers.
+	pub peers: usize,
+	/// Whether the node should have peers, false for the local chains.
+	pub should_have_peers: bool,
+	/// The number of the best block.
+	pub best_number: u64,
+	/// The number of the finalized block.
+	pub finalized_number: u64,
+}
+
+impl NodeHealth {
+	/// The number of blocks between the finalized block and the best block.
+	pub fn finality_lag(&self) -> u64 {
+		self.best_number.saturating_sub(self.finalized_number)
+	}
+}
+
+/// Provides the health of the node to the health endpoints.
+#[async_trait::async_trait]
+pub trait HealthProvider: Send + Sync + std::fmt::Debug {
+	/// Returns the current health of the node.
+	async fn health(&self) -> Result<NodeHealth, String>;
+}
+
+/// The body of the `/health` and `/ready` responses.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HealthReport {
+	#[serde(flatten)]
+	health: NodeHealth,
+	finality_lag: u64,
+	ready: bool,
+	/// The reasons why the node is not ready.
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	reasons: Vec<String>,
+}
+
+impl HealthReport {
+	fn new(health: NodeHealth, max_finality_lag: Option<u32>) -> Self {
+		let finality_lag = health.finality_lag();
+
+		let mut reasons = Vec::new();
+		if health.is_syncing {
+			reasons.push("The node is syncing".into());
+		}
+		if health.should_have_peers && health.peers == 0 {
+			reasons.push("The node has no peers".into());
+		}
+		match max_finality_lag {
+			Some(max) if finality_lag > u64::from(max) => reasons.push(format!(
+				"The finality lag of {} blocks exceeds the maximum of {} blocks",
+				finality_lag, max
+			)),
+			_ => (),
+		}
+
+		HealthReport { health, finality_lag, ready: reasons.is_empty(), reasons }
+	}
+}
+
+/// The health endpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Endpoint {
+	Health,
+	Ready,
+	Live,
+}
+
+impl Endpoint {
+	fn of<B>(request: &Request<B>) -> Option<Self> {
+		if request.method() != Method::GET {
+			return None
+		}
+
+		match request.uri().path() {
+			"/health" => Some(Endpoint::Health),
+			"/ready" => Some(Endpoint::Ready),
+			"/live" => Some(Endpoint::Live),
+			_ => None,
+		}
+	}
+}
+
+/// Layer that answers the requests to the health endpoints.
+#[derive(Debug, Clone)]
+pub struct HealthLayer {
+	/// The health of the node, the requests are passed to the server if `None`.
+	provider: Option<Arc<dyn HealthProvider>>,
+	/// The maximum finality lag of a ready node, in blocks.
+	max_finality_lag: Option<u32>,
+}
+
+impl HealthLayer {
+	/// Create a new [`HealthLayer`].
+	pub fn new(provider: Option<Arc<dyn HealthProvider>>, max_finality_lag: Option<u32>) -> Self {
+		HealthLayer { provider, max_finality_lag }
+	}
+
+	/// Answer the request to the endpoint.
+	async fn respond(
+		self,
+		endpoint: Endpoint,
+		provider: Arc<dyn HealthProvider>,
+	) -> Response<Body> {
+		if endpoint == Endpoint::Live {
+			return json_response(StatusCode::OK, &serde_json::json!({ "live": true }))
+		}
+
+		let report = match provider.health().await {
+			Ok(health) => HealthReport::new(health, self.max_finality_lag),
+			Err(error) =>
+				return json_response(
+					StatusCode::SERVICE_UNAVAILABLE,
+					&serde_json::json!({ "error": error }),
+				),
+		};
+
+		let status = match endpoint {
+			Endpoint::Ready if !report.ready => StatusCode::SERVICE_UNAVAILABLE,
+			_ => StatusCode::OK,
+		};
+		json_response(status, &report)
+	}
+}
+
+impl<S> tower::Layer<S> for HealthLayer {
+	type Service = HealthService<S>;
+
+	fn layer(&self, inner: S) -> Self::Service {
+		HealthService { inner, layer: self.clone() }
+	}
+}
+
+/// Service that answers the requests to the health endpoints.
+#[derive(Debug, Clone)]
+pub struct HealthService<S> {
+	inner: S,
+	layer: HealthLayer,
+}
+
+impl<S> tower::Service<Request<Body>> for HealthService<S>
+where
+	S: tower::Service<Request<Body>, Response = Response<Body>>,
+	S::Future: Send + 'static,
+	S::Error: Send + 'static,
+{
+	type Response = S::Response;
+	type Error = S::Error;
+	type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+	fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		self.inner.poll_ready(cx)
+	}
+
+	fn call(&mut self, request: Request<Body>) -> Self::Future {
+		match (Endpoint::of(&request), self.layer.provider.clone()) {
+			(Some(endpoint), Some(provider)) => {
+				let layer = self.layer.clone();
+				Box::pin(async move { Ok(layer.respond(endpoint, provider).await) })
+			},
+			_ => Box::pin(self.inner.call(request)),
+		}
+	}
+}
+
+/// Build a JSON response.
+fn json_response(status: StatusCode, body: &impl Serialize) -> Response<Body> {
+	let body = serde_json::to_vec(body).unwrap_or_default();
+	let mut response = Response::new(Body::from(body));
+	*response.status_mut() = status;
+	response
+		.headers_mut()
+		.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+	response
+}
+
+/// Serve the health endpoints on a dedicated address, until the server fails.
+///
+/// The other requests are answered with `404 Not Found`.
+pub async fn start_health_server(
+	addr: SocketAddr,
+	provider: Arc<dyn HealthProvider>,
+	max_finality_lag: Option<u32>,
+) -> Result<(), Box<dyn StdError + Send + Sync>> {
+	let layer = HealthLayer::new(Some(provider), max_finality_lag);
+	let server = hyper::Server::try_bind(&addr)?;
+	log::info!("Running health server: addr={}", addr);
+
+	let service = make_service_fn(move |_| {
+		let service = layer.layer(service_fn(|_request: Request<Body>| async {
+			Ok::<_, Infallible>(crate::auth::reject(StatusCode::NOT_FOUND))
+		}));
+		async move { Ok::<_, Infallible>(service) }
+	});
+
+	server.serve(service).await.map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tower::Service;
+
+	#[derive(Debug)]
+	struct StaticHealth(NodeHealth);
+
+	#[async_trait::async_trait]
+	impl HealthProvider for StaticHealth {
+		async fn health(&self) -> Result<NodeHealth, String> {
+			Ok(self.0.clone())
+		}
+	}
+
+	async fn get<S>(service: &mut S, path: &str) -> (StatusCode, serde_json::Value)
+	where
+		S: Service<Request<Body>, Response = Response<Body>>,
+		S::Error: std::fmt::Debug,
+	{
+		let request = Request::get(path).body(Body::empty()).unwrap();
+		let response = service.call(request).await.unwrap();
+		let status = response.status();
+		let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+		(status, serde_json::from_slice(&body).unwrap_or_default())
+	}
+
+	#[test]
+	fn readiness_reasons() {
+		let health = NodeHealth {
+			is_syncing: false,
+			peers: 3,
+			should_have_peers: true,
+			best_number: 120,
+			finalized_number: 100,
+		};
+		assert!(HealthReport::new(health.clone(), None).ready);
+		assert!(HealthReport::new(health.clone(), Some(20)).ready);
+
+		let report = HealthReport::new(health.clone(), Some(10));
+		assert!(!report.ready);
+		assert!(report.reasons[0].contains("finality lag of 20 blocks"));
+
+		let report =
+			HealthReport::new(NodeHealth { is_syncing: true, peers: 0, ..health.clone() }, None);
+		assert_eq!(report.reasons.len(), 2);
+
+		// The local chains do not need peers.
+		let health = NodeHealth { peers: 0, should_have_peers: false, ..health };
+		assert!(HealthReport::new(health, None).ready);
+	}
+
+	#[tokio::test]
+	async fn answers_the_health_endpoints() {
+		let inner = tower::service_fn(|_request: Request<Body>| async {
+			Ok::<_, Infallible>(Response::new(Body::from("rpc")))
+		});
+		let health = NodeHealth {
+			is_syncing: true,
+			peers: 2,
+			should_have_peers: true,
+			best_number: 10,
+			finalized_number: 8,
+		};
+		let provider = Arc::new(StaticHealth(health));
+		let mut service = HealthLayer::new(Some(provider), None).layer(inner);
+
+		let (status, body) = get(&mut service, "/health").await;
+		assert_eq!(status, StatusCode::OK);
+		assert_eq!(body["peers"], 2);
+		assert_eq!(body["finalityLag"], 2);
+		assert_eq!(body["ready"], false);
+
+		let (status, body) = get(&mut service, "/ready").await;
+		assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+		assert_eq!(body["reasons"][0], "The node is syncing");
+
+		let (status, body) = get(&mut service, "/live").await;
+		assert_eq!(status, StatusCode::OK);
+		assert_eq!(body["live"], true);
+
+		// The other requests are passed to the server.
+		let request = Request::get("/metrics").body(Body::empty()).unwrap();
+		let response = service.call(request).await.unwrap();
+		assert_eq!(hyper::body::to_bytes(response.into_body()).await.unwrap(), "rpc");
+	}
+}
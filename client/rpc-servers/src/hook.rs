@@ -0,0 +1,156 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Custom middleware of the RPC servers.
+//!
+//! The node builders register [`RpcRequestHook`]s to log, annotate, route or reject the
+//! requests without forking the server. The hooks are called in order for every HTTP
+//! request and WebSocket handshake, before the built-in middleware of the server.
+
+use http::{Request, Response};
+use hyper::body::Body;
+use std::{
+	future::Future,
+	pin::Pin,
+	sync::Arc,
+	task::{Context, Poll},
+};
+
+/// A custom middleware of the RPC servers.
+pub trait RpcRequestHook: Send + Sync + std::fmt::Debug {
+	/// Inspect or modify the HTTP request or the WebSocket handshake.
+	///
+	/// Returns the response of the request to reject it, in which case the request is
+	/// neither passed to the next hooks nor to the server.
+	fn on_request(&self, request: &mut Request<Body>) -> Result<(), Response<Body>>;
+
+	/// Inspect or modify the response of the request.
+	///
+	/// This is not called for the requests rejected by a hook.
+	fn on_response(&self, _response: &mut Response<Body>) {}
+}
+
+/// Layer that calls the hooks of the requests.
+#[derive(Debug, Clone, Default)]
+pub struct HookLayer {
+	/// The hooks, called in order.
+	hooks: Arc<Vec<Arc<dyn RpcRequestHook>>>,
+}
+
+impl HookLayer {
+	/// Create a new [`HookLayer`].
+	pub fn new(hooks: Vec<Arc<dyn RpcRequestHook>>) -> Self {
+		HookLayer { hooks: Arc::new(hooks) }
+	}
+}
+
+impl<S> tower::Layer<S> for HookLayer {
+	type Service = HookService<S>;
+
+	fn layer(&self, inner: S) -> Self::Service {
+		HookService { inner, layer: self.clone() }
+	}
+}
+
+/// Service that calls the hooks of the requests.
+#[derive(Debug, Clone)]
+pub struct HookService<S> {
+	inner: S,
+	layer: HookLayer,
+}
+
+impl<S> tower::Service<Request<Body>> for HookService<S>
+where
+	S: tower::Service<Request<Body>, Response = Response<Body>>,
+	S::Future: Send + 'static,
+	S::Error: Send + 'static,
+{
+	type Response = S::Response;
+	type Error = S::Error;
+	type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+	fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		self.inner.poll_ready(cx)
+	}
+
+	fn call(&mut self, mut request: Request<Body>) -> Self::Future {
+		if self.layer.hooks.is_empty() {
+			return Box::pin(self.inner.call(request))
+		}
+
+		for hook in self.layer.hooks.iter() {
+			if let Err(response) = hook.on_request(&mut request) {
+				return Box::pin(std::future::ready(Ok(response)))
+			}
+		}
+
+		let hooks = self.layer.hooks.clone();
+		let response = self.inner.call(request);
+		Box::pin(async move {
+			let mut response = response.await?;
+			for hook in hooks.iter() {
+				hook.on_response(&mut response);
+			}
+			Ok(response)
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use http::{HeaderValue, StatusCode};
+	use tower::{Layer, Service};
+
+	/// Reject the requests without the tenant header and tag the responses.
+	#[derive(Debug)]
+	struct TenantHook;
+
+	impl RpcRequestHook for TenantHook {
+		fn on_request(&self, request: &mut Request<Body>) -> Result<(), Response<Body>> {
+			if request.headers().contains_key("x-tenant") {
+				return Ok(())
+			}
+
+			let mut response = Response::new(Body::empty());
+			*response.status_mut() = StatusCode::FORBIDDEN;
+			Err(response)
+		}
+
+		fn on_response(&self, response: &mut Response<Body>) {
+			response.headers_mut().insert("x-served-by", HeaderValue::from_static("node"));
+		}
+	}
+
+	#[tokio::test]
+	async fn hooks_reject_requests_and_modify_responses() {
+		let inner = tower::service_fn(|_request: Request<Body>| async {
+			Ok::<_, std::convert::Infallible>(Response::new(Body::empty()))
+		});
+		let mut service = HookLayer::new(vec![Arc::new(TenantHook)]).layer(inner);
+
+		let response = service.call(Request::new(Body::empty())).await.unwrap();
+		assert_eq!(response.status(), StatusCode::FORBIDDEN);
+		assert!(!response.headers().contains_key("x-served-by"));
+
+		let request = Request::builder().header("x-tenant", "a").body(Body::empty()).unwrap();
+		let response = service.call(request).await.unwrap();
+		assert_eq!(response.status(), StatusCode::OK);
+		assert_eq!(response.headers()["x-served-by"], "node");
+	}
+}
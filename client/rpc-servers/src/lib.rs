@@ -20,8 +20,26 @@
 
 #![warn(missing_docs)]
 
+pub mod auth;
+pub mod batch;
+pub mod compression;
+pub mod cors;
+pub mod health;
+pub mod hook;
+pub mod limits;
 pub mod middleware;
+pub mod qos;
 
+use crate::{
+	auth::{AuthLayer, ForwardedAuthenticator},
+	batch::BatchLimitsLayer,
+	compression::CompressionLayer,
+	cors::CorsPolicyLayer,
+	health::HealthLayer,
+	hook::HookLayer,
+	limits::ResponseLimitsLayer,
+	qos::QosLayer,
+};
 use http::header::HeaderValue;
 use jsonrpsee::{
 	server::{
@@ -30,10 +48,22 @@ use jsonrpsee::{
 	},
 	RpcModule,
 };
-use std::{error::Error as StdError, net::SocketAddr};
+use std::{
+	collections::HashSet, error::Error as StdError, net::SocketAddr, sync::Arc, time::Duration,
+};
+use tower::util::Either;
 use tower_http::cors::{AllowOrigin, CorsLayer};
 
-pub use crate::middleware::RpcMetrics;
+pub use crate::{
+	auth::{Access, BearerTokenAuthenticator, ForwardedIdentityAuthenticator, RpcAuthenticator},
+	batch::BatchLimits,
+	cors::{CorsPolicy, CorsRule},
+	health::{start_health_server, HealthProvider, NodeHealth},
+	hook::RpcRequestHook,
+	limits::ResponseLimits,
+	middleware::{RequestInstant, RpcMetrics},
+	qos::{MethodAcl, QosConfig, QosTier, TierLimits},
+};
 pub use jsonrpsee::core::{
 	id_providers::{RandomIntegerIdProvider, RandomStringIdProvider},
 	traits::IdProvider,
@@ -41,6 +71,9 @@ pub use jsonrpsee::core::{
 
 const MEGABYTE: u32 = 1024 * 1024;
 
+/// The default interval of the pings sent to the WebSocket connections.
+const DEFAULT_PING_INTERVAL: Duration = Duration::from_secs(30);
+
 /// Type alias for the JSON-RPC server.
 pub type Server = ServerHandle;
 
@@ -51,18 +84,46 @@ pub struct Config<'a, M: Send + Sync + 'static> {
 	pub addrs: [SocketAddr; 2],
 	/// CORS.
 	pub cors: Option<&'a Vec<String>>,
+	/// Per-origin CORS policy, which supersedes `cors` if provided.
+	pub cors_policy: Option<CorsPolicy>,
 	/// Maximum connections.
 	pub max_connections: u32,
 	/// Maximum subscriptions per connection.
 	pub max_subs_per_conn: u32,
+	/// Interval of the pings sent to the WebSocket connections, 30 seconds if `None`.
+	pub ping_interval: Option<Duration>,
 	/// Maximum rpc request payload size.
 	pub max_payload_in_mb: u32,
 	/// Maximum rpc response payload size.
 	pub max_payload_out_mb: u32,
+	/// Maximum rpc response payload size of the classes of methods, `max_payload_out_mb` for
+	/// the classes without a limit.
+	pub response_limits: ResponseLimits,
+	/// Limits of the batch requests.
+	pub batch_limits: BatchLimits,
+	/// Whether the HTTP responses are compressed with the encodings accepted by the clients.
+	pub http_compression: bool,
 	/// Metrics.
 	pub metrics: Option<RpcMetrics>,
 	/// RPC API.
 	pub rpc_api: RpcModule<M>,
+	/// RPC API of the requests granted the unsafe access by the authenticator, built with the
+	/// unsafe methods allowed. The authenticated requests are served by `rpc_api` if `None`.
+	pub authenticated_rpc_api: Option<RpcModule<M>>,
+	/// The methods of the RPC API that are unsafe to expose to untrusted users.
+	pub unsafe_methods: HashSet<String>,
+	/// The quality of service tier of the server, the server is not limited if `None`.
+	pub qos_tier: Option<QosTier>,
+	/// The authenticator of the calls to the unsafe methods, the unsafe methods may be called
+	/// by everyone if `None`.
+	pub authenticator: Option<Arc<dyn RpcAuthenticator>>,
+	/// The custom middleware of the server, called in order before the built-in middleware.
+	pub request_hooks: Vec<Arc<dyn RpcRequestHook>>,
+	/// The health of the node served by the `/health`, `/ready` and `/live` endpoints, the
+	/// `/health` requests are proxied to `system_health` if `None`.
+	pub health_provider: Option<Arc<dyn HealthProvider>>,
+	/// The maximum finality lag of a ready node, in blocks, not checked if `None`.
+	pub health_max_finality_lag: Option<u32>,
 	/// Subscription ID provider.
 	pub id_provider: Option<Box<dyn IdProvider>>,
 	/// Tokio runtime handle.
@@ -76,32 +137,106 @@ pub async fn start_server<M: Send + Sync + 'static>(
 	let Config {
 		addrs,
 		cors,
+		cors_policy,
 		max_payload_in_mb,
 		max_payload_out_mb,
+		response_limits,
+		batch_limits,
+		http_compression,
 		max_connections,
 		max_subs_per_conn,
+		ping_interval,
 		metrics,
 		id_provider,
 		tokio_handle,
 		rpc_api,
+		authenticated_rpc_api,
+		unsafe_methods,
+		qos_tier,
+		authenticator,
+		request_hooks,
+		health_provider,
+		health_max_finality_lag,
 	} = config;
 
-	let host_filter = hosts_filtering(cors.is_some(), &addrs);
+	// The server accepts the WebSocket connections on the same addresses, whose batches are
+	// not visible to the HTTP middleware.
+	if batch_limits.is_limited() {
+		log::warn!(
+			"The RPC batch limits are enforced on the HTTP requests only, the batches sent over \
+			 the WebSocket connections are not limited",
+		);
+	}
+
+	let host_filter = hosts_filtering(cors.is_some() || cors_policy.is_some(), &addrs);
+	let allowed_origins = format_cors(cors, cors_policy.as_ref());
+
+	// The authenticated requests are forwarded to a dedicated server, such that the other
+	// requests never reach the RPC module allowing the unsafe methods.
+	let mut auth_layer = AuthLayer::new(authenticator.clone());
+	let mut authenticated_server = None;
+	if let (Some(_), Some(authenticated_rpc_api)) = (&authenticator, authenticated_rpc_api) {
+		let forwarded = ForwardedAuthenticator::new();
+		let rpc_api =
+			build_rpc_api(authenticated_rpc_api, unsafe_methods.clone(), qos_tier.as_ref());
+		let (handle, addr) = start_authenticated_server(
+			rpc_api,
+			forwarded.clone(),
+			max_payload_in_mb,
+			max_payload_out_mb,
+			response_limits,
+			max_connections,
+			max_subs_per_conn,
+			ping_interval,
+			metrics.clone(),
+			tokio_handle.clone(),
+		)
+		.await?;
+		auth_layer = auth_layer.forward_authenticated(addr, &forwarded);
+		authenticated_server = Some(handle);
+	}
 
 	let middleware = tower::ServiceBuilder::new()
-		// Proxy `GET /health` requests to internal `system_health` method.
+		// Compress the responses, including the ones of the custom middleware.
+		.layer(CompressionLayer::new(http_compression))
+		// Call the custom middleware of the node.
+		.layer(HookLayer::new(request_hooks))
+		// Answer the health endpoints with the health of the node.
+		.layer(HealthLayer::new(health_provider, health_max_finality_lag))
+		// Proxy `GET /health` requests to internal `system_health` method, if the health of
+		// the node is not provided.
 		.layer(ProxyGetRequestLayer::new("/health", "system_health")?)
-		.layer(try_into_cors(cors)?);
+		// Proxy `GET /health/chain-head` requests to the `chainHead` health, such that
+		// the nodes whose `chainHead` subsystem is unhealthy respond with a failure status.
+		.layer(ProxyGetRequestLayer::new("/health/chain-head", "chainHead_unstable_health")?)
+		.layer(try_into_cors(cors, cors_policy)?)
+		// Enforce the API keys and the request rate of the tier.
+		.layer(QosLayer::new(qos_tier.as_ref()))
+		// Reject the batches with too many calls or too many expensive calls.
+		.layer(BatchLimitsLayer::new(
+			batch_limits,
+			max_payload_in_mb.saturating_mul(MEGABYTE) as usize,
+		))
+		// Forward the authenticated requests to the server allowing the unsafe methods.
+		.layer(auth_layer)
+		// Enforce the response limits of the classes of methods.
+		.layer(ResponseLimitsLayer::new(
+			response_limits,
+			max_payload_out_mb,
+			max_payload_in_mb.saturating_mul(MEGABYTE) as usize,
+		));
 
 	let mut builder = ServerBuilder::new()
 		.max_request_body_size(max_payload_in_mb.saturating_mul(MEGABYTE))
-		.max_response_body_size(max_payload_out_mb.saturating_mul(MEGABYTE))
+		// The server allows the largest of the limits, the smaller ones being enforced by the
+		// middleware.
+		.max_response_body_size(response_limits.max_mb(max_payload_out_mb).saturating_mul(MEGABYTE))
 		.max_connections(max_connections)
 		.max_subscriptions_per_connection(max_subs_per_conn)
-		.ping_interval(std::time::Duration::from_secs(30))
+		.ping_interval(ping_interval.unwrap_or(DEFAULT_PING_INTERVAL))
 		.set_host_filtering(host_filter)
 		.set_middleware(middleware)
-		.custom_tokio_runtime(tokio_handle);
+		.custom_tokio_runtime(tokio_handle.clone());
 
 	if let Some(provider) = id_provider {
 		builder = builder.set_id_provider(provider);
@@ -109,7 +244,7 @@ pub async fn start_server<M: Send + Sync + 'static>(
 		builder = builder.set_id_provider(RandomStringIdProvider::new(16));
 	};
 
-	let rpc_api = build_rpc_api(rpc_api);
+	let rpc_api = build_rpc_api(rpc_api, unsafe_methods, qos_tier.as_ref());
 	let (handle, addr) = if let Some(metrics) = metrics {
 		let server = builder.set_logger(metrics).build(&addrs[..]).await?;
 		let addr = server.local_addr();
@@ -123,12 +258,70 @@ pub async fn start_server<M: Send + Sync + 'static>(
 	log::info!(
 		"Running JSON-RPC server: addr={}, allowed origins={}",
 		addr.map_or_else(|_| "unknown".to_string(), |a| a.to_string()),
-		format_cors(cors)
+		allowed_origins,
 	);
 
+	// The server of the authenticated requests is stopped along with the server.
+	if let Some(authenticated_server) = authenticated_server {
+		let handle = handle.clone();
+		tokio_handle.spawn(async move {
+			handle.stopped().await;
+			let _ = authenticated_server.stop();
+		});
+	}
+
 	Ok(handle)
 }
 
+/// Start the server of the requests granted the unsafe access, bound to the loopback
+/// interface and serving only the requests forwarded with the token of the authenticator.
+#[allow(clippy::too_many_arguments)]
+async fn start_authenticated_server<M: Send + Sync + 'static>(
+	rpc_api: RpcModule<M>,
+	authenticator: ForwardedAuthenticator,
+	max_payload_in_mb: u32,
+	max_payload_out_mb: u32,
+	response_limits: ResponseLimits,
+	max_connections: u32,
+	max_subs_per_conn: u32,
+	ping_interval: Option<Duration>,
+	metrics: Option<RpcMetrics>,
+	tokio_handle: tokio::runtime::Handle,
+) -> Result<(ServerHandle, SocketAddr), Box<dyn StdError + Send + Sync>> {
+	let middleware = tower::ServiceBuilder::new()
+		.layer(AuthLayer::new(Some(Arc::new(authenticator))))
+		.layer(ResponseLimitsLayer::new(
+			response_limits,
+			max_payload_out_mb,
+			max_payload_in_mb.saturating_mul(MEGABYTE) as usize,
+		));
+
+	let builder = ServerBuilder::new()
+		.max_request_body_size(max_payload_in_mb.saturating_mul(MEGABYTE))
+		.max_response_body_size(response_limits.max_mb(max_payload_out_mb).saturating_mul(MEGABYTE))
+		.max_connections(max_connections)
+		.max_subscriptions_per_connection(max_subs_per_conn)
+		.ping_interval(ping_interval.unwrap_or(DEFAULT_PING_INTERVAL))
+		.set_id_provider(RandomStringIdProvider::new(16))
+		.set_middleware(middleware)
+		.custom_tokio_runtime(tokio_handle);
+
+	let addr = SocketAddr::from(([127, 0, 0, 1], 0));
+	let (handle, addr) = if let Some(metrics) = metrics {
+		let server = builder.set_logger(metrics).build(addr).await?;
+		let addr = server.local_addr()?;
+		(server.start(rpc_api)?, addr)
+	} else {
+		let server = builder.build(addr).await?;
+		let addr = server.local_addr()?;
+		(server.start(rpc_api)?, addr)
+	};
+
+	log::debug!("Running JSON-RPC server of the authenticated requests: addr={}", addr);
+
+	Ok((handle, addr))
+}
+
 fn hosts_filtering(enabled: bool, addrs: &[SocketAddr]) -> AllowHosts {
 	if enabled {
 		// NOTE The listening addresses are whitelisted by default.
@@ -143,10 +336,41 @@ fn hosts_filtering(enabled: bool, addrs: &[SocketAddr]) -> AllowHosts {
 	}
 }
 
-fn build_rpc_api<M: Send + Sync + 'static>(mut rpc_api: RpcModule<M>) -> RpcModule<M> {
+fn build_rpc_api<M: Send + Sync + 'static>(
+	mut rpc_api: RpcModule<M>,
+	unsafe_methods: HashSet<String>,
+	tier: Option<&QosTier>,
+) -> RpcModule<M> {
+	// The `archive` methods are not exposed by the tiers without archive access, and the
+	// methods denied by the access list of the tier are not exposed either.
+	if let Some(tier) = tier {
+		let denied_methods = rpc_api
+			.method_names()
+			.filter(|name| {
+				(!tier.limits.archive && name.starts_with("archive_")) ||
+					!tier.methods.is_allowed(name)
+			})
+			.collect::<Vec<_>>();
+		for name in denied_methods {
+			rpc_api.remove_method(name);
+		}
+	}
+
 	let mut available_methods = rpc_api.method_names().collect::<Vec<_>>();
 	available_methods.sort();
 
+	let v2_methods = available_methods
+		.iter()
+		.filter_map(|name| {
+			let stability = v2_method_stability(name)?;
+			Some(serde_json::json!({
+				"name": name,
+				"stability": stability,
+				"unsafe": unsafe_methods.contains(*name),
+			}))
+		})
+		.collect::<Vec<_>>();
+
 	rpc_api
 		.register_method("rpc_methods", move |_, _| {
 			Ok(serde_json::json!({
@@ -155,26 +379,57 @@ fn build_rpc_api<M: Send + Sync + 'static>(mut rpc_api: RpcModule<M>) -> RpcModu
 		})
 		.expect("infallible all other methods have their own address space; qed");
 
+	rpc_api
+		.register_method("rpc_unstable_v2Methods", move |_, _| {
+			Ok(serde_json::json!({
+				"methods": v2_methods,
+			}))
+		})
+		.expect("infallible all other methods have their own address space; qed");
+
 	rpc_api
 }
 
+/// Returns the stability level of the method, if the method is part of the
+/// new RPC specification.
+///
+/// The methods of the new RPC specification are named `<namespace>_<stability>_<method>`,
+/// where the stability is either `unstable` or a version (ie `v1`).
+pub(crate) fn v2_method_stability(name: &str) -> Option<&str> {
+	let mut parts = name.splitn(3, '_');
+	let (_namespace, stability, _method) = (parts.next()?, parts.next()?, parts.next()?);
+
+	let is_version = stability.strip_prefix('v').map_or(false, |version| {
+		!version.is_empty() && version.chars().all(|c| c.is_ascii_digit())
+	});
+
+	(stability == "unstable" || is_version).then_some(stability)
+}
+
 fn try_into_cors(
 	maybe_cors: Option<&Vec<String>>,
-) -> Result<CorsLayer, Box<dyn StdError + Send + Sync>> {
+	maybe_policy: Option<CorsPolicy>,
+) -> Result<Either<CorsPolicyLayer, CorsLayer>, Box<dyn StdError + Send + Sync>> {
+	if let Some(policy) = maybe_policy {
+		return Ok(Either::A(CorsPolicyLayer::new(policy)))
+	}
+
 	if let Some(cors) = maybe_cors {
 		let mut list = Vec::new();
 		for origin in cors {
 			list.push(HeaderValue::from_str(origin)?);
 		}
-		Ok(CorsLayer::new().allow_origin(AllowOrigin::list(list)))
+		Ok(Either::B(CorsLayer::new().allow_origin(AllowOrigin::list(list))))
 	} else {
 		// allow all cors
-		Ok(CorsLayer::permissive())
+		Ok(Either::B(CorsLayer::permissive()))
 	}
 }
 
-fn format_cors(maybe_cors: Option<&Vec<String>>) -> String {
-	if let Some(cors) = maybe_cors {
+fn format_cors(maybe_cors: Option<&Vec<String>>, maybe_policy: Option<&CorsPolicy>) -> String {
+	if let Some(policy) = maybe_policy {
+		format!("{:?}", policy.rules.iter().map(|rule| &rule.origin).collect::<Vec<_>>())
+	} else if let Some(cors) = maybe_cors {
 		format!("{:?}", cors)
 	} else {
 		format!("{:?}", ["*"])
@@ -0,0 +1,341 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Limits of the size of the RPC responses per class of methods.
+//!
+//! The state queries and the blocks may be much larger than the responses of the other
+//! methods. Their classes have their own limits, such that serving them doesn't require
+//! a large limit for all the methods.
+//!
+//! The server is configured with the largest of the limits, and the responses of the HTTP
+//! requests are checked one by one against the limit of the class of their method. The
+//! calls and the notifications sent over a WebSocket connection are not visible to the HTTP
+//! middleware, such that they are limited by the largest of the limits.
+
+use http::{header::CONTENT_LENGTH, Request, Response, StatusCode};
+use hyper::body::Body;
+use jsonrpsee::types::error::{OVERSIZED_RESPONSE_CODE, OVERSIZED_RESPONSE_MSG};
+use std::{
+	collections::HashMap,
+	future::Future,
+	pin::Pin,
+	sync::Arc,
+	task::{Context, Poll},
+};
+
+const MEGABYTE: usize = 1024 * 1024;
+
+/// The limits of the size of the responses per class of methods, in megabytes.
+///
+/// The classes without a limit are limited by the maximum response size of the server.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResponseLimits {
+	/// The limit of the `state` and `childstate` queries.
+	pub state_mb: Option<u32>,
+	/// The limit of the methods returning the blocks, their headers and their bodies.
+	pub blocks_mb: Option<u32>,
+}
+
+impl ResponseLimits {
+	/// Returns the largest of the limits and of the given default limit.
+	pub fn max_mb(&self, default_mb: u32) -> u32 {
+		[self.state_mb, self.blocks_mb].into_iter().flatten().fold(default_mb, u32::max)
+	}
+
+	/// Returns the limit of the given class, if any.
+	fn class_mb(&self, class: MethodClass) -> Option<u32> {
+		match class {
+			MethodClass::State => self.state_mb,
+			MethodClass::Block => self.blocks_mb,
+		}
+	}
+}
+
+/// The classes of the methods with their own response limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MethodClass {
+	State,
+	Block,
+}
+
+impl MethodClass {
+	/// Returns the class of the method, `None` if the method has no class.
+	fn of(method: &str) -> Option<Self> {
+		const BLOCK_METHODS: &[&str] = &[
+			"chain_getBlock",
+			"chain_getHeader",
+			"chainHead_unstable_body",
+			"chainHead_unstable_header",
+			"archive_unstable_body",
+			"archive_unstable_header",
+		];
+
+		if method.starts_with("state_") ||
+			method.starts_with("childstate_") ||
+			method == "chainHead_unstable_storage" ||
+			method == "archive_unstable_storage"
+		{
+			Some(MethodClass::State)
+		} else if BLOCK_METHODS.contains(&method) {
+			Some(MethodClass::Block)
+		} else {
+			None
+		}
+	}
+}
+
+/// Layer enforcing the response limits of the classes of methods on the HTTP requests.
+#[derive(Debug, Clone)]
+pub struct ResponseLimitsLayer {
+	/// The limits of the classes.
+	limits: ResponseLimits,
+	/// The limit of the methods without a class, in bytes.
+	default_limit: usize,
+	/// The maximum size of the buffered request bodies.
+	max_body_size: usize,
+}
+
+impl ResponseLimitsLayer {
+	/// Create a new [`ResponseLimitsLayer`], the methods without a class are limited to
+	/// `default_mb` megabytes.
+	pub fn new(limits: ResponseLimits, default_mb: u32, max_body_size: usize) -> Self {
+		ResponseLimitsLayer {
+			limits,
+			default_limit: (default_mb as usize).saturating_mul(MEGABYTE),
+			max_body_size,
+		}
+	}
+
+	/// Returns the limit of the responses of the method, in bytes.
+	fn limit(&self, method: &str) -> usize {
+		MethodClass::of(method)
+			.and_then(|class| self.limits.class_mb(class))
+			.map_or(self.default_limit, |mb| (mb as usize).saturating_mul(MEGABYTE))
+	}
+
+	/// Returns true if all the classes have the limit of the methods without a class, such
+	/// that the responses are limited by the server itself.
+	fn is_noop(&self) -> bool {
+		[self.limits.state_mb, self.limits.blocks_mb]
+			.into_iter()
+			.flatten()
+			.all(|mb| (mb as usize).saturating_mul(MEGABYTE) == self.default_limit)
+	}
+}
+
+impl<S> tower::Layer<S> for ResponseLimitsLayer {
+	type Service = ResponseLimitsService<S>;
+
+	fn layer(&self, inner: S) -> Self::Service {
+		ResponseLimitsService { inner, layer: Arc::new(self.clone()) }
+	}
+}
+
+/// Service that replaces the responses exceeding the limit of the class of their method
+/// with an error.
+#[derive(Debug, Clone)]
+pub struct ResponseLimitsService<S> {
+	inner: S,
+	layer: Arc<ResponseLimitsLayer>,
+}
+
+impl<S> tower::Service<Request<Body>> for ResponseLimitsService<S>
+where
+	S: tower::Service<Request<Body>, Response = Response<Body>>,
+	S::Future: Send + 'static,
+	S::Error: From<hyper::Error> + Send + 'static,
+{
+	type Response = S::Response;
+	type Error = S::Error;
+	type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+	fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		self.inner.poll_ready(cx)
+	}
+
+	fn call(&mut self, request: Request<Body>) -> Self::Future {
+		if self.layer.is_noop() || request.headers().contains_key(http::header::UPGRADE) {
+			return Box::pin(self.inner.call(request))
+		}
+
+		// The request is forwarded right away, while its body is provided once the calls
+		// are read. The server does not read the body until the response is polled.
+		let (parts, body) = request.into_parts();
+		let (mut sender, forwarded) = Body::channel();
+		let response = self.inner.call(Request::from_parts(parts, forwarded));
+
+		let layer = self.layer.clone();
+		Box::pin(async move {
+			let bytes = match crate::auth::read_body(body, layer.max_body_size).await {
+				Ok(bytes) => bytes,
+				Err(status) => return Ok(crate::auth::reject(status)),
+			};
+			let limits = call_limits(&bytes, &layer);
+			let _ = sender.try_send_data(bytes);
+			drop(sender);
+
+			let response = response.await?;
+			if response.status() != StatusCode::OK {
+				return Ok(response)
+			}
+			let (mut parts, body) = response.into_parts();
+			let bytes = hyper::body::to_bytes(body).await?;
+			let Some(limited) = enforce_limits(&bytes, &limits) else {
+				return Ok(Response::from_parts(parts, bytes.into()))
+			};
+			parts.headers.insert(CONTENT_LENGTH, limited.len().into());
+			Ok(Response::from_parts(parts, limited.into()))
+		})
+	}
+}
+
+/// Returns the response limits of the calls of the JSON-RPC request, either a single call or
+/// a batch of calls, by the serialized id of the calls.
+///
+/// The malformed requests are left to the server to report.
+fn call_limits(request: &[u8], layer: &ResponseLimitsLayer) -> HashMap<String, usize> {
+	let call_limit = |call: &serde_json::Value| {
+		let method = call.get("method")?.as_str()?;
+		Some((call.get("id")?.to_string(), layer.limit(method)))
+	};
+
+	match serde_json::from_slice::<serde_json::Value>(request) {
+		Ok(serde_json::Value::Array(calls)) => calls.iter().filter_map(call_limit).collect(),
+		Ok(call) => call_limit(&call).into_iter().collect(),
+		Err(_) => Default::default(),
+	}
+}
+
+/// Returns the response with the oversized results replaced by an error, `None` if the
+/// response is within the limits.
+fn enforce_limits(response: &[u8], limits: &HashMap<String, usize>) -> Option<Vec<u8>> {
+	// The response can't exceed the limits of its calls if it doesn't exceed the smallest.
+	if limits.values().all(|limit| response.len() <= *limit) {
+		return None
+	}
+
+	let limit_response = |response: &mut serde_json::Value| {
+		let Some(id) = response.get("id").map(ToString::to_string) else { return false };
+		let Some(limit) = limits.get(&id) else { return false };
+		let size = serde_json::to_vec(response).map_or(0, |encoded| encoded.len());
+		if size <= *limit {
+			return false
+		}
+		*response = oversized_error(&id, *limit);
+		true
+	};
+
+	let mut response = serde_json::from_slice::<serde_json::Value>(response).ok()?;
+	let limited = match &mut response {
+		serde_json::Value::Array(responses) => responses
+			.iter_mut()
+			.fold(false, |limited, response| limit_response(response) || limited),
+		response => limit_response(response),
+	};
+	limited.then(|| serde_json::to_vec(&response).expect("JSON values are serializable; qed"))
+}
+
+/// Build the error returned instead of an oversized response.
+fn oversized_error(id: &str, limit: usize) -> serde_json::Value {
+	let id = serde_json::from_str::<serde_json::Value>(id).unwrap_or_default();
+	serde_json::json!({
+		"jsonrpc": "2.0",
+		"error": {
+			"code": OVERSIZED_RESPONSE_CODE,
+			"message": OVERSIZED_RESPONSE_MSG,
+			"data": format!("Exceeded max limit of {}", limit),
+		},
+		"id": id,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tower::{Layer, Service};
+
+	type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+	#[test]
+	fn classifies_the_methods() {
+		assert_eq!(MethodClass::of("state_getStorage"), Some(MethodClass::State));
+		assert_eq!(MethodClass::of("childstate_getKeysPaged"), Some(MethodClass::State));
+		assert_eq!(MethodClass::of("chain_getBlock"), Some(MethodClass::Block));
+		assert_eq!(MethodClass::of("chainHead_unstable_body"), Some(MethodClass::Block));
+		assert_eq!(MethodClass::of("chainHead_unstable_follow"), None);
+		assert_eq!(MethodClass::of("system_health"), None);
+
+		let limits = ResponseLimits { state_mb: Some(64), ..Default::default() };
+		assert_eq!(limits.max_mb(10), 64);
+		assert_eq!(limits.max_mb(100), 100);
+	}
+
+	#[test]
+	fn replaces_the_oversized_responses() {
+		let limits = [("1".to_string(), 64), ("2".to_string(), 16)]
+			.into_iter()
+			.collect::<HashMap<_, _>>();
+		let small = br#"{"jsonrpc":"2.0","result":"0x00","id":1}"#;
+		assert_eq!(enforce_limits(small, &limits), None);
+
+		let batch = format!(
+			r#"[{{"jsonrpc":"2.0","result":"{0}","id":1}},{{"jsonrpc":"2.0","result":"{0}","id":2}}]"#,
+			"a".repeat(32)
+		);
+		let limited = enforce_limits(batch.as_bytes(), &limits).unwrap();
+		let limited = serde_json::from_slice::<serde_json::Value>(&limited).unwrap();
+		assert_eq!(limited[0]["result"], "a".repeat(32));
+		assert_eq!(limited[1]["error"]["code"], OVERSIZED_RESPONSE_CODE);
+		assert_eq!(limited[1]["id"], 2);
+	}
+
+	#[tokio::test]
+	async fn enforces_the_limits_of_the_classes() {
+		let large = "a".repeat(2 * MEGABYTE);
+		let inner = tower::service_fn(move |request: Request<Body>| {
+			let large = large.clone();
+			async move {
+				let request = hyper::body::to_bytes(request.into_body()).await?;
+				let request = serde_json::from_slice::<serde_json::Value>(&request).unwrap();
+				let response = serde_json::json!({
+					"jsonrpc": "2.0",
+					"result": large,
+					"id": request["id"],
+				});
+				Ok::<_, BoxError>(Response::new(Body::from(response.to_string())))
+			}
+		});
+		let limits = ResponseLimits { state_mb: Some(4), ..Default::default() };
+		let mut service = ResponseLimitsLayer::new(limits, 1, MEGABYTE).layer(inner);
+
+		let call = |method: &str| {
+			let call = serde_json::json!({"jsonrpc": "2.0", "id": 7, "method": method});
+			Request::new(Body::from(call.to_string()))
+		};
+		let response = service.call(call("state_getStorage")).await.unwrap();
+		let response = hyper::body::to_bytes(response.into_body()).await.unwrap();
+		let response = serde_json::from_slice::<serde_json::Value>(&response).unwrap();
+		assert_eq!(response["result"].as_str().unwrap().len(), 2 * MEGABYTE);
+
+		let response = service.call(call("system_health")).await.unwrap();
+		let response = hyper::body::to_bytes(response.into_body()).await.unwrap();
+		let response = serde_json::from_slice::<serde_json::Value>(&response).unwrap();
+		assert_eq!(response["error"]["code"], OVERSIZED_RESPONSE_CODE);
+		assert_eq!(response["id"], 7);
+	}
+}
@@ -23,7 +23,14 @@ use prometheus_endpoint::{
 	register, Counter, CounterVec, HistogramOpts, HistogramVec, Opts, PrometheusError, Registry,
 	U64,
 };
-use std::net::SocketAddr;
+use std::{
+	collections::HashMap,
+	net::SocketAddr,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc, Mutex,
+	},
+};
 
 /// Histogram time buckets in microseconds.
 const HISTOGRAM_BUCKETS: [f64; 11] = [
@@ -40,6 +47,15 @@ const HISTOGRAM_BUCKETS: [f64; 11] = [
 	10_000_000.0,
 ];
 
+/// The moment an RPC request was received by the server.
+#[derive(Debug, Clone, Copy)]
+pub struct RequestInstant {
+	/// The unique number of the request.
+	id: u64,
+	/// The moment the request was received.
+	started_at: std::time::Instant,
+}
+
 /// Metrics for RPC middleware storing information about the number of requests started/completed,
 /// calls started/completed and their timings.
 #[derive(Debug, Clone)]
@@ -58,8 +74,31 @@ pub struct RpcMetrics {
 	ws_sessions_opened: Option<Counter<U64>>,
 	/// Number of Websocket sessions closed.
 	ws_sessions_closed: Option<Counter<U64>>,
+	/// Number of failed calls of the RPC v2 methods, labeled by the error code or
+	/// `limitReached`.
+	v2_calls_failed: CounterVec<U64>,
+	/// The number of the next request.
+	next_request_id: Arc<AtomicU64>,
+	/// The calls of the RPC v2 methods, by the number of the request that is not yet
+	/// responded to.
+	///
+	/// The error codes are only available in the response of the request.
+	v2_calls: Arc<Mutex<HashMap<u64, V2Calls>>>,
+}
+
+/// The calls of the RPC v2 methods of a request.
+#[derive(Debug, Default)]
+struct V2Calls {
+	/// The methods of the failed calls.
+	failed: Vec<String>,
+	/// The methods of the successful calls, whose result may be `limitReached`.
+	succeeded: Vec<String>,
 }
 
+/// The method label of the outcomes of a batch request that are not attributed to a single
+/// method.
+const BATCH_METHOD_LABEL: &str = "batch";
+
 impl RpcMetrics {
 	/// Create an instance of metrics
 	pub fn new(metrics_registry: Option<&Registry>) -> Result<Option<Self>, PrometheusError> {
@@ -132,6 +171,19 @@ impl RpcMetrics {
 					metrics_registry,
 				)?
 				.into(),
+				v2_calls_failed: register(
+					CounterVec::new(
+						Opts::new(
+							"substrate_rpc_v2_calls_failed",
+							"Number of failed RPC v2 calls by the error code of the response, or \
+							 `limitReached`",
+						),
+						&["protocol", "method", "code"],
+					)?,
+					metrics_registry,
+				)?,
+				next_request_id: Default::default(),
+				v2_calls: Default::default(),
 			}))
 		} else {
 			Ok(None)
@@ -140,7 +192,7 @@ impl RpcMetrics {
 }
 
 impl Logger for RpcMetrics {
-	type Instant = std::time::Instant;
+	type Instant = RequestInstant;
 
 	fn on_connect(
 		&self,
@@ -155,7 +207,10 @@ impl Logger for RpcMetrics {
 
 	fn on_request(&self, transport: TransportProtocol) -> Self::Instant {
 		let transport_label = transport_label_str(transport);
-		let now = std::time::Instant::now();
+		let now = RequestInstant {
+			id: self.next_request_id.fetch_add(1, Ordering::Relaxed),
+			started_at: std::time::Instant::now(),
+		};
 		self.requests_started.with_label_values(&[transport_label]).inc();
 		now
 	}
@@ -181,7 +236,7 @@ impl Logger for RpcMetrics {
 		transport: TransportProtocol,
 	) {
 		let transport_label = transport_label_str(transport);
-		let micros = started_at.elapsed().as_micros();
+		let micros = started_at.started_at.elapsed().as_micros();
 		log::debug!(
 			target: "rpc_metrics",
 			"[{}] {} call took {} μs",
@@ -200,6 +255,17 @@ impl Logger for RpcMetrics {
 				if success { "false" } else { "true" },
 			])
 			.inc();
+
+		if crate::v2_method_stability(name).is_some() {
+			if let Ok(mut v2_calls) = self.v2_calls.lock() {
+				let calls = v2_calls.entry(started_at.id).or_default();
+				if success {
+					calls.succeeded.push(name.to_string());
+				} else {
+					calls.failed.push(name.to_string());
+				}
+			}
+		}
 	}
 
 	fn on_response(&self, result: &str, started_at: Self::Instant, transport: TransportProtocol) {
@@ -207,6 +273,32 @@ impl Logger for RpcMetrics {
 		log::trace!(target: "rpc_metrics", "[{}] on_response started_at={:?}", transport_label, started_at);
 		log::trace!(target: "rpc_metrics::extra", "[{}] result={:?}", transport_label, result);
 		self.requests_finished.with_label_values(&[transport_label]).inc();
+
+		let calls = match self.v2_calls.lock() {
+			Ok(mut v2_calls) => v2_calls.remove(&started_at.id),
+			Err(_) => None,
+		};
+		// Only the responses of the requests calling v2 methods that look like failures are
+		// parsed.
+		let Some(calls) = calls else { return };
+		if !result.contains("\"error\"") && !result.contains(LIMIT_REACHED) {
+			return
+		}
+
+		let outcomes = failure_outcomes(result);
+		let errors = outcomes.iter().filter(|outcome| matches!(outcome, Outcome::Error(_))).count();
+		for outcome in outcomes {
+			let (methods, code) = match outcome {
+				// The errors are attributed to the failed v2 calls only if no other call
+				// of the request failed.
+				Outcome::Error(code) if errors == calls.failed.len() =>
+					(&calls.failed, code.to_string()),
+				Outcome::Error(_) => continue,
+				Outcome::LimitReached => (&calls.succeeded, LIMIT_REACHED.to_string()),
+			};
+			let Some(method) = attributed_method(methods) else { continue };
+			self.v2_calls_failed.with_label_values(&[transport_label, method, &code]).inc();
+		}
 	}
 
 	fn on_disconnect(&self, _remote_addr: SocketAddr, transport: TransportProtocol) {
@@ -216,9 +308,78 @@ impl Logger for RpcMetrics {
 	}
 }
 
+/// The result of the RPC v2 methods that cannot serve the request at the moment.
+const LIMIT_REACHED: &str = "limitReached";
+
+/// The outcome of a call that did not succeed.
+#[derive(Debug, PartialEq)]
+enum Outcome {
+	/// The call failed with the error code.
+	Error(i64),
+	/// The call returned `limitReached`.
+	LimitReached,
+}
+
+/// Returns the outcomes of the calls that did not succeed in the JSON-RPC response, which is
+/// either a single response or the responses of a batch request.
+fn failure_outcomes(response: &str) -> Vec<Outcome> {
+	let outcome = |response: &serde_json::Value| {
+		if let Some(code) = response.get("error").and_then(|error| error.get("code")) {
+			return code.as_i64().map(Outcome::Error)
+		}
+		let result = response.get("result")?.get("result")?;
+		(result == LIMIT_REACHED).then_some(Outcome::LimitReached)
+	};
+
+	match serde_json::from_str::<serde_json::Value>(response) {
+		Ok(serde_json::Value::Array(responses)) => responses.iter().filter_map(outcome).collect(),
+		Ok(response) => outcome(&response).into_iter().collect(),
+		Err(_) => Vec::new(),
+	}
+}
+
+/// Returns the method label of the outcomes of the calls of the given methods.
+///
+/// The calls of a batch request are not matched to their responses, such that the outcomes
+/// are attributed to a method only if all the calls are of this method, and labeled as
+/// `batch` otherwise.
+fn attributed_method(methods: &[String]) -> Option<&str> {
+	let (first, rest) = methods.split_first()?;
+	Some(if rest.iter().all(|method| method == first) { first } else { BATCH_METHOD_LABEL })
+}
+
 fn transport_label_str(t: TransportProtocol) -> &'static str {
 	match t {
 		TransportProtocol::Http => "http",
 		TransportProtocol::WebSocket => "ws",
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn failure_outcomes_of_batch_response() {
+		let response = r#"[
+			{"jsonrpc":"2.0","result":{"result":"started","operationId":"0"},"id":0},
+			{"jsonrpc":"2.0","error":{"code":-32801,"message":"Invalid block hash"},"id":1},
+			{"jsonrpc":"2.0","result":{"result":"limitReached"},"id":2}
+		]"#;
+		assert_eq!(failure_outcomes(response), vec![Outcome::Error(-32801), Outcome::LimitReached]);
+
+		let response = r#"{"jsonrpc":"2.0","result":{"result":"limitReached"},"id":0}"#;
+		assert_eq!(failure_outcomes(response), vec![Outcome::LimitReached]);
+		assert!(failure_outcomes(r#"{"jsonrpc":"2.0","result":"0x00","id":0}"#).is_empty());
+	}
+
+	#[test]
+	fn attributed_method_of_batch_calls() {
+		let body = "chainHead_unstable_body".to_string();
+		let call = "chainHead_unstable_call".to_string();
+
+		assert_eq!(attributed_method(&[]), None);
+		assert_eq!(attributed_method(&[body.clone(), body.clone()]), Some(body.as_str()));
+		assert_eq!(attributed_method(&[body, call]), Some(BATCH_METHOD_LABEL));
+	}
+}
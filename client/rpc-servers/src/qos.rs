@@ -0,0 +1,458 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Quality of service tiers of the RPC servers.
+//!
+//! The operator defines named tiers (ie `trusted`, `standard`, `public`), each with its
+//! own limits, and assigns them to the listen addresses of the node. The same
+//! [`QosConfig`] is consumed by the HTTP middleware of the server, which enforces the
+//! API keys and the request rate, and by the RPC modules, which enforce the limits of
+//! the `chainHead` subscriptions and the access to the `archive` methods. The methods
+//! exposed on the listen addresses of a tier may be further restricted by its
+//! [`MethodAcl`].
+//!
+//! The tier of a request is selected by its listen address only. The RPC modules are
+//! built per listen address with the limits of its tier, such that an API key cannot
+//! select another tier. The operator assigns a listen address per tier instead.
+
+use http::{Request, Response, StatusCode};
+use serde::Deserialize;
+use std::{
+	collections::HashSet,
+	fmt,
+	future::Future,
+	net::SocketAddr,
+	num::NonZeroU32,
+	path::Path,
+	pin::Pin,
+	sync::{Arc, Mutex},
+	task::{Context, Poll},
+	time::{Duration, Instant},
+};
+
+/// The HTTP header that carries the API key of the request.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// The limits of a quality of service tier.
+///
+/// The limits that are not provided fall back to the node defaults.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TierLimits {
+	/// The maximum number of requests per second accepted on the listen address.
+	///
+	/// Both the HTTP requests and the WebSocket handshakes are counted. The calls made over an
+	/// established WebSocket connection are not counted, they are bounded by the limits of
+	/// the connections and of the subscriptions instead.
+	#[serde(default)]
+	pub max_requests_per_second: Option<NonZeroU32>,
+	/// The maximum number of blocks pinned by the `chainHead` subscriptions.
+	///
+	/// If the `chainHead_follow` subscriptions are limited, the budget is split evenly between
+	/// them, such that a client cannot exhaust it by opening more subscriptions.
+	#[serde(default)]
+	pub max_pinned_blocks: Option<usize>,
+	/// The maximum number of ongoing operations per `chainHead` subscription.
+	#[serde(default)]
+	pub max_ongoing_operations: Option<usize>,
+	/// The maximum number of bytes served per minute to a `chainHead` subscription, and by the
+	/// `archive` methods of a server of the tier.
+	///
+	/// The methods are not tied to a connection: each `chainHead_follow` subscription has its
+	/// own quota, such that a connection is granted the quota once per subscription it opens,
+	/// and the `archive` quota is shared by all the clients of the server.
+	#[serde(default)]
+	pub max_bytes_per_minute: Option<u64>,
+	/// Expose the methods of the `archive` namespace.
+	#[serde(default = "default_archive")]
+	pub archive: bool,
+}
+
+fn default_archive() -> bool {
+	true
+}
+
+impl Default for TierLimits {
+	fn default() -> Self {
+		TierLimits {
+			max_requests_per_second: None,
+			max_pinned_blocks: None,
+			max_ongoing_operations: None,
+			max_bytes_per_minute: None,
+			archive: default_archive(),
+		}
+	}
+}
+
+/// The methods exposed on the listen addresses of a tier.
+///
+/// Each entry is either the name of a method (ie `author_rotateKeys`) or a namespace
+/// (ie `author`), which matches all the methods of the namespace. A method is exposed if
+/// it matches an entry of the allow list, or the allow list is empty, and it does not
+/// match any entry of the deny list.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct MethodAcl {
+	/// The methods and namespaces exposed, all the methods are exposed if empty.
+	#[serde(default)]
+	pub allow: Vec<String>,
+	/// The methods and namespaces that are not exposed.
+	#[serde(default)]
+	pub deny: Vec<String>,
+}
+
+impl MethodAcl {
+	/// Returns true if the method is exposed.
+	pub fn is_allowed(&self, method: &str) -> bool {
+		let matches = |entry: &String| {
+			entry == method ||
+				method.strip_prefix(entry.as_str()).map_or(false, |rest| rest.starts_with('_'))
+		};
+
+		(self.allow.is_empty() || self.allow.iter().any(matches)) && !self.deny.iter().any(matches)
+	}
+}
+
+/// A named quality of service tier.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct QosTier {
+	/// The name of the tier.
+	pub name: String,
+	/// The limits of the tier.
+	#[serde(default)]
+	pub limits: TierLimits,
+	/// The API keys that grant access to the listen addresses of this tier.
+	///
+	/// The requests must provide one of the keys in the `x-api-key` header,
+	/// unless no keys are configured. The keys only grant access, they do not select
+	/// the tier: the tier of a request is the tier of its listen address.
+	#[serde(default)]
+	pub api_keys: HashSet<String>,
+	/// The methods exposed on the listen addresses of this tier.
+	#[serde(default)]
+	pub methods: MethodAcl,
+}
+
+/// A listen address served with the limits of a tier.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct QosListener {
+	/// The listen address.
+	pub addr: SocketAddr,
+	/// The name of the tier.
+	pub tier: String,
+}
+
+/// The quality of service configuration of the RPC servers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct QosConfig {
+	/// The tiers defined by the operator.
+	pub tiers: Vec<QosTier>,
+	/// The tier of the default RPC listen address.
+	///
+	/// The default listen address is not limited if this is `None`.
+	#[serde(default)]
+	pub default_tier: Option<String>,
+	/// Additional listen addresses, each served with the limits of a tier.
+	#[serde(default)]
+	pub listeners: Vec<QosListener>,
+}
+
+/// Errors of the quality of service configuration.
+#[derive(Debug)]
+pub enum QosConfigError {
+	/// The configuration file could not be read.
+	Io(std::io::Error),
+	/// The configuration file is not valid JSON.
+	Json(serde_json::Error),
+	/// The tier is defined more than once.
+	DuplicateTier(String),
+	/// The tier is referenced but not defined.
+	UnknownTier(String),
+}
+
+impl fmt::Display for QosConfigError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			QosConfigError::Io(err) => write!(f, "Failed to read the QoS configuration: {}", err),
+			QosConfigError::Json(err) => write!(f, "Invalid QoS configuration: {}", err),
+			QosConfigError::DuplicateTier(name) => write!(f, "Duplicate QoS tier `{}`", name),
+			QosConfigError::UnknownTier(name) => write!(f, "QoS tier `{}` is not defined", name),
+		}
+	}
+}
+
+impl std::error::Error for QosConfigError {}
+
+impl QosConfig {
+	/// Read and validate the configuration from the given JSON file.
+	pub fn from_file(path: &Path) -> Result<Self, QosConfigError> {
+		let file = std::fs::File::open(path).map_err(QosConfigError::Io)?;
+		let config: QosConfig =
+			serde_json::from_reader(std::io::BufReader::new(file)).map_err(QosConfigError::Json)?;
+		config.validate()?;
+		Ok(config)
+	}
+
+	/// Check that the tiers are uniquely named and that all referenced tiers are defined.
+	pub fn validate(&self) -> Result<(), QosConfigError> {
+		let mut names = HashSet::new();
+		for tier in &self.tiers {
+			if !names.insert(tier.name.as_str()) {
+				return Err(QosConfigError::DuplicateTier(tier.name.clone()))
+			}
+		}
+
+		let referenced = self
+			.default_tier
+			.iter()
+			.chain(self.listeners.iter().map(|listener| &listener.tier));
+		for name in referenced {
+			if !names.contains(name.as_str()) {
+				return Err(QosConfigError::UnknownTier(name.clone()))
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Get the tier with the given name.
+	pub fn tier(&self, name: &str) -> Option<&QosTier> {
+		self.tiers.iter().find(|tier| tier.name == name)
+	}
+
+	/// Get the tier of the default listen address.
+	pub fn default_tier(&self) -> Option<&QosTier> {
+		self.default_tier.as_deref().and_then(|name| self.tier(name))
+	}
+}
+
+/// Fixed window counter of the requests received in the current second.
+#[derive(Debug)]
+struct RateLimiter {
+	/// The maximum number of requests per window.
+	max_per_second: u32,
+	/// The start of the current window.
+	window_start: Instant,
+	/// The number of requests received in the current window.
+	count: u32,
+}
+
+impl RateLimiter {
+	fn new(max_per_second: NonZeroU32) -> Self {
+		RateLimiter { max_per_second: max_per_second.get(), window_start: Instant::now(), count: 0 }
+	}
+
+	/// Returns true if the request is within the limit of the current window.
+	fn try_acquire(&mut self, now: Instant) -> bool {
+		if now.saturating_duration_since(self.window_start) >= Duration::from_secs(1) {
+			self.window_start = now;
+			self.count = 0;
+		}
+
+		if self.count >= self.max_per_second {
+			return false
+		}
+
+		self.count += 1;
+		true
+	}
+}
+
+/// Layer that enforces the API keys and the request rate of a tier.
+///
+/// The layer does not restrict the requests if no tier is provided.
+#[derive(Debug, Clone, Default)]
+pub struct QosLayer {
+	/// The accepted API keys, `None` if the requests are not authenticated.
+	api_keys: Option<Arc<HashSet<String>>>,
+	/// The rate limiter shared by all connections of the listen address.
+	rate_limiter: Option<Arc<Mutex<RateLimiter>>>,
+}
+
+impl QosLayer {
+	/// Create a new [`QosLayer`] for the given tier.
+	pub fn new(tier: Option<&QosTier>) -> Self {
+		let Some(tier) = tier else { return Default::default() };
+
+		QosLayer {
+			api_keys: (!tier.api_keys.is_empty()).then(|| Arc::new(tier.api_keys.clone())),
+			rate_limiter: tier
+				.limits
+				.max_requests_per_second
+				.map(|max| Arc::new(Mutex::new(RateLimiter::new(max)))),
+		}
+	}
+}
+
+impl<S> tower::Layer<S> for QosLayer {
+	type Service = QosService<S>;
+
+	fn layer(&self, inner: S) -> Self::Service {
+		QosService { inner, layer: self.clone() }
+	}
+}
+
+/// Service that rejects the requests exceeding the limits of a tier.
+#[derive(Debug, Clone)]
+pub struct QosService<S> {
+	inner: S,
+	layer: QosLayer,
+}
+
+impl<S> QosService<S> {
+	/// Returns the status of the rejected request, if the request is not allowed.
+	fn reject<B>(&self, request: &Request<B>) -> Option<StatusCode> {
+		if let Some(api_keys) = &self.layer.api_keys {
+			let authorized = request
+				.headers()
+				.get(API_KEY_HEADER)
+				.and_then(|key| key.to_str().ok())
+				.map_or(false, |key| api_keys.contains(key));
+
+			if !authorized {
+				return Some(StatusCode::UNAUTHORIZED)
+			}
+		}
+
+		if let Some(rate_limiter) = &self.layer.rate_limiter {
+			let mut rate_limiter = rate_limiter.lock().expect("Rate limiter lock poisoned; qed");
+			if !rate_limiter.try_acquire(Instant::now()) {
+				return Some(StatusCode::TOO_MANY_REQUESTS)
+			}
+		}
+
+		None
+	}
+}
+
+impl<S, B, RespBody> tower::Service<Request<B>> for QosService<S>
+where
+	S: tower::Service<Request<B>, Response = Response<RespBody>>,
+	S::Future: Send + 'static,
+	S::Error: Send + 'static,
+	RespBody: Default + Send + 'static,
+{
+	type Response = S::Response;
+	type Error = S::Error;
+	type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+	fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		self.inner.poll_ready(cx)
+	}
+
+	fn call(&mut self, request: Request<B>) -> Self::Future {
+		if let Some(status) = self.reject(&request) {
+			let mut response = Response::new(RespBody::default());
+			*response.status_mut() = status;
+			return Box::pin(std::future::ready(Ok(response)))
+		}
+
+		Box::pin(self.inner.call(request))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn qos_config_from_json() {
+		let config: QosConfig = serde_json::from_str(
+			r#"{
+				"tiers": [
+					{ "name": "trusted", "limits": { "archive": true } },
+					{
+						"name": "public",
+						"limits": { "maxRequestsPerSecond": 10, "maxPinnedBlocks": 64, "archive": false },
+						"apiKeys": ["key"],
+						"methods": { "deny": ["author"] }
+					}
+				],
+				"defaultTier": "trusted",
+				"listeners": [{ "addr": "0.0.0.0:9955", "tier": "public" }]
+			}"#,
+		)
+		.unwrap();
+		config.validate().unwrap();
+
+		assert_eq!(config.default_tier().unwrap().name, "trusted");
+		let public = config.tier("public").unwrap();
+		assert_eq!(public.limits.max_requests_per_second, NonZeroU32::new(10));
+		assert_eq!(public.limits.max_pinned_blocks, Some(64));
+		assert_eq!(public.limits.max_ongoing_operations, None);
+		assert!(!public.limits.archive);
+		assert!(public.api_keys.contains("key"));
+		assert_eq!(public.methods.deny, vec!["author".to_string()]);
+	}
+
+	#[test]
+	fn method_acl_matches_methods_and_namespaces() {
+		let acl = MethodAcl::default();
+		assert!(acl.is_allowed("author_rotateKeys"));
+
+		let acl = MethodAcl {
+			allow: vec!["chainHead".into(), "system_health".into()],
+			deny: vec!["chainHead_unstable_storage".into()],
+		};
+		assert!(acl.is_allowed("chainHead_unstable_follow"));
+		assert!(acl.is_allowed("system_health"));
+		assert!(!acl.is_allowed("system_name"));
+		assert!(!acl.is_allowed("chainHead_unstable_storage"));
+		// A namespace does not match the namespaces it prefixes.
+		assert!(!acl.is_allowed("chainHeadExtra_method"));
+
+		let acl = MethodAcl { allow: vec![], deny: vec!["author".into()] };
+		assert!(!acl.is_allowed("author_rotateKeys"));
+		assert!(acl.is_allowed("chain_getBlock"));
+	}
+
+	#[test]
+	fn qos_config_validation() {
+		let tier = |name: &str| QosTier {
+			name: name.into(),
+			limits: Default::default(),
+			api_keys: Default::default(),
+			methods: Default::default(),
+		};
+
+		let config =
+			QosConfig { tiers: vec![tier("public"), tier("public")], ..Default::default() };
+		assert!(matches!(config.validate(), Err(QosConfigError::DuplicateTier(_))));
+
+		let config = QosConfig {
+			tiers: vec![tier("public")],
+			default_tier: Some("trusted".into()),
+			listeners: vec![],
+		};
+		assert!(matches!(config.validate(), Err(QosConfigError::UnknownTier(_))));
+	}
+
+	#[test]
+	fn rate_limiter_resets_every_second() {
+		let mut rate_limiter = RateLimiter::new(NonZeroU32::new(2).unwrap());
+		let now = rate_limiter.window_start;
+
+		assert!(rate_limiter.try_acquire(now));
+		assert!(rate_limiter.try_acquire(now));
+		assert!(!rate_limiter.try_acquire(now + Duration::from_millis(500)));
+		assert!(rate_limiter.try_acquire(now + Duration::from_secs(1)));
+	}
+}
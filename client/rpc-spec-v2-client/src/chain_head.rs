@@ -0,0 +1,401 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Typed client of the `chainHead` methods.
+
+use crate::{decode_hex, encode_hex, Error};
+use jsonrpsee::{
+	core::client::{Subscription, SubscriptionClientT, SubscriptionKind},
+	types::SubscriptionId,
+};
+use sc_rpc_spec_v2::chain_head::{
+	api::ChainHeadApiClient,
+	event::{MethodResponse, StorageQuery, StorageResult},
+	FollowEvent,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+	collections::{HashSet, VecDeque},
+	hash::Hash as StdHash,
+	sync::Arc,
+};
+
+/// The result of a completed operation.
+enum OperationOutput {
+	Body(Vec<String>),
+	Call(String),
+	Storage(Vec<StorageResult>),
+}
+
+/// A `chainHead_unstable_follow` subscription.
+///
+/// The subscription keeps track of the blocks pinned by the server on its behalf and
+/// drives the `body`, `call` and `storage` operations to completion. The follow events
+/// received while an operation is in progress are buffered and returned by
+/// [`FollowSubscription::next_event`].
+pub struct FollowSubscription<C, Hash> {
+	/// The JSON-RPC client.
+	client: Arc<C>,
+	/// The ID of the subscription, provided to the `chainHead` methods.
+	id: String,
+	/// The events of the subscription.
+	events: Subscription<FollowEvent<Hash>>,
+	/// The events received while an operation was in progress.
+	buffered: VecDeque<FollowEvent<Hash>>,
+	/// The blocks pinned by the subscription.
+	pinned: HashSet<Hash>,
+	/// Unpin the blocks reported as pruned by the `finalized` event.
+	unpin_pruned: bool,
+	/// The subscription reports the finalized blocks only.
+	finalized_only: bool,
+}
+
+impl<C, Hash> FollowSubscription<C, Hash>
+where
+	C: SubscriptionClientT + Send + Sync,
+	Hash: Serialize + DeserializeOwned + Clone + Eq + StdHash + Send + Sync + 'static,
+{
+	/// Start a new follow subscription.
+	pub async fn new(client: Arc<C>, with_runtime: bool) -> Result<Self, Error> {
+		Self::with_finalized_history(client, with_runtime, None).await
+	}
+
+	/// Start a new follow subscription that reports up to `finalized_history` finalized
+	/// blocks preceding the current finalized block.
+	pub async fn with_finalized_history(
+		client: Arc<C>,
+		with_runtime: bool,
+		finalized_history: Option<u32>,
+	) -> Result<Self, Error> {
+		Self::with_options(client, with_runtime, finalized_history, None, None, None, None).await
+	}
+
+	/// Start a new follow subscription with all the options of `chainHead_unstable_follow`.
+	///
+	/// If `with_header` is set, the `NewBlock` events include the SCALE-encoded header and
+	/// the number of extrinsics of the reported block.
+	///
+	/// If `catch_up_from` is provided, the blocks finalized since this finalized block are
+	/// reported before the live events.
+	///
+	/// If `resumption_token` is provided, the disconnected subscription that reported this
+	/// token is resumed together with its pinned blocks.
+	///
+	/// If `finalized_only` is set, the `NewBlock` and `BestBlockChanged` events are not
+	/// reported and the blocks are announced by the `Finalized` events.
+	pub async fn with_options(
+		client: Arc<C>,
+		with_runtime: bool,
+		finalized_history: Option<u32>,
+		with_header: Option<bool>,
+		catch_up_from: Option<Hash>,
+		resumption_token: Option<String>,
+		finalized_only: Option<bool>,
+	) -> Result<Self, Error> {
+		let events = ChainHeadApiClient::<Hash>::chain_head_unstable_follow(
+			&*client,
+			with_runtime,
+			finalized_history,
+			with_header,
+			catch_up_from,
+			resumption_token,
+			finalized_only,
+		)
+		.await?;
+
+		let id = match events.kind() {
+			SubscriptionKind::Subscription(SubscriptionId::Num(num)) => num.to_string(),
+			SubscriptionKind::Subscription(SubscriptionId::Str(id)) => id.to_string(),
+			_ => return Err(Error::MissingSubscriptionId),
+		};
+
+		Ok(FollowSubscription {
+			client,
+			id,
+			events,
+			buffered: VecDeque::new(),
+			pinned: HashSet::new(),
+			unpin_pruned: false,
+			finalized_only: finalized_only.unwrap_or(false),
+		})
+	}
+
+	/// Unpin the blocks reported as pruned by the `finalized` and `catchUp` events, once the
+	/// event is received.
+	pub fn with_unpin_pruned(mut self, unpin_pruned: bool) -> Self {
+		self.unpin_pruned = unpin_pruned;
+		self
+	}
+
+	/// The ID of the subscription.
+	pub fn id(&self) -> &str {
+		&self.id
+	}
+
+	/// Returns true if the block is pinned by the subscription.
+	pub fn is_pinned(&self, hash: &Hash) -> bool {
+		self.pinned.contains(hash)
+	}
+
+	/// The blocks pinned by the subscription.
+	pub fn pinned(&self) -> impl Iterator<Item = &Hash> {
+		self.pinned.iter()
+	}
+
+	/// Get the next event of the subscription.
+	///
+	/// Returns `None` if the subscription was closed.
+	pub async fn next_event(&mut self) -> Option<Result<FollowEvent<Hash>, Error>> {
+		if let Some(event) = self.buffered.pop_front() {
+			return Some(Ok(event))
+		}
+
+		self.read_event().await
+	}
+
+	/// Unpin the block.
+	pub async fn unpin(&mut self, hash: Hash) -> Result<(), Error> {
+		ChainHeadApiClient::<Hash>::chain_head_unstable_unpin(
+			&*self.client,
+			self.id.clone(),
+			hash.clone().into(),
+		)
+		.await?;
+
+		self.pinned.remove(&hash);
+		Ok(())
+	}
+
+	/// Unpin the blocks with a single call.
+	///
+	/// No block is unpinned if any of the blocks is not pinned by the subscription.
+	pub async fn unpin_blocks(&mut self, hashes: Vec<Hash>) -> Result<(), Error> {
+		ChainHeadApiClient::<Hash>::chain_head_unstable_unpin(
+			&*self.client,
+			self.id.clone(),
+			hashes.clone().into(),
+		)
+		.await?;
+
+		for hash in &hashes {
+			self.pinned.remove(hash);
+		}
+		Ok(())
+	}
+
+	/// Restrict the events of the subscription to the descendants of the given block.
+	pub async fn set_fork_filter(&self, hash: Option<Hash>) -> Result<(), Error> {
+		ChainHeadApiClient::<Hash>::chain_head_unstable_set_fork_filter(
+			&*self.client,
+			self.id.clone(),
+			hash,
+		)
+		.await
+		.map_err(Into::into)
+	}
+
+	/// Replace the locally tracked pinned blocks with the blocks pinned by the server.
+	///
+	/// This recovers the view of the pinned blocks after the events reporting them
+	/// were missed.
+	pub async fn reconcile_pins(&mut self) -> Result<(), Error> {
+		let pinned = ChainHeadApiClient::<Hash>::chain_head_unstable_pinned_blocks(
+			&*self.client,
+			self.id.clone(),
+		)
+		.await?;
+
+		self.pinned = pinned.into_iter().map(|block| block.hash).collect();
+		Ok(())
+	}
+
+	/// Get the SCALE encoded header of a pinned block.
+	pub async fn header(&self, hash: Hash) -> Result<Option<Vec<u8>>, Error> {
+		let header = ChainHeadApiClient::<Hash>::chain_head_unstable_header(
+			&*self.client,
+			self.id.clone(),
+			hash,
+		)
+		.await?;
+
+		header.map(|header| decode_hex(&header)).transpose()
+	}
+
+	/// Get the SCALE encoded extrinsics of a pinned block.
+	pub async fn body(&mut self, hash: Hash) -> Result<Vec<Vec<u8>>, Error> {
+		let response = ChainHeadApiClient::<Hash>::chain_head_unstable_body(
+			&*self.client,
+			self.id.clone(),
+			hash,
+		)
+		.await?;
+
+		match self.wait_operation(response).await? {
+			OperationOutput::Body(extrinsics) =>
+				extrinsics.iter().map(|extrinsic| decode_hex(extrinsic)).collect(),
+			_ => Err(Error::Operation("Unexpected operation output".into())),
+		}
+	}
+
+	/// Call a runtime function at a pinned block and return the SCALE encoded output.
+	pub async fn call(
+		&mut self,
+		hash: Hash,
+		function: &str,
+		call_parameters: &[u8],
+	) -> Result<Vec<u8>, Error> {
+		let response = ChainHeadApiClient::<Hash>::chain_head_unstable_call(
+			&*self.client,
+			self.id.clone(),
+			hash,
+			function.into(),
+			encode_hex(call_parameters),
+			None,
+		)
+		.await?;
+
+		match self.wait_operation(response).await? {
+			OperationOutput::Call(output) => decode_hex(&output),
+			_ => Err(Error::Operation("Unexpected operation output".into())),
+		}
+	}
+
+	/// Query the storage of a pinned block.
+	///
+	/// The paginated results are requested until the operation is completed.
+	pub async fn storage(
+		&mut self,
+		hash: Hash,
+		items: Vec<StorageQuery<String>>,
+		child_trie: Option<String>,
+	) -> Result<Vec<StorageResult>, Error> {
+		let response = ChainHeadApiClient::<Hash>::chain_head_unstable_storage(
+			&*self.client,
+			self.id.clone(),
+			hash,
+			items,
+			child_trie,
+			None,
+		)
+		.await?;
+
+		match self.wait_operation(response).await? {
+			OperationOutput::Storage(items) => Ok(items),
+			_ => Err(Error::Operation("Unexpected operation output".into())),
+		}
+	}
+
+	/// Read the next event from the server and keep track of the pinned blocks.
+	async fn read_event(&mut self) -> Option<Result<FollowEvent<Hash>, Error>> {
+		let event = match self.events.next().await? {
+			Ok(event) => event,
+			Err(err) => return Some(Err(err.into())),
+		};
+
+		match &event {
+			FollowEvent::Initialized(initialized) => {
+				self.pinned.extend(initialized.finalized_block_hashes.iter().cloned());
+			},
+			FollowEvent::NewBlock(new_block) => {
+				self.pinned.insert(new_block.block_hash.clone());
+			},
+			FollowEvent::Finalized(finalized) => {
+				// The blocks are not announced by the `NewBlock` events.
+				if self.finalized_only {
+					self.pinned.extend(finalized.finalized_block_hashes.iter().cloned());
+					self.pinned.extend(finalized.pruned_block_hashes.iter().cloned());
+				}
+				if self.unpin_pruned {
+					for hash in &finalized.pruned_block_hashes {
+						if let Err(err) = self.unpin(hash.clone()).await {
+							return Some(Err(err))
+						}
+					}
+				}
+			},
+			FollowEvent::CatchUp(catch_up) => {
+				self.pinned.insert(catch_up.finalized_block_hash.clone());
+				if self.unpin_pruned {
+					for hash in &catch_up.pruned_block_hashes {
+						if let Err(err) = self.unpin(hash.clone()).await {
+							return Some(Err(err))
+						}
+					}
+				}
+			},
+			FollowEvent::Stop => {
+				self.pinned.clear();
+			},
+			_ => {},
+		}
+
+		Some(Ok(event))
+	}
+
+	/// Wait for the events of the operation until it is completed.
+	///
+	/// The unrelated events are buffered.
+	async fn wait_operation(&mut self, response: MethodResponse) -> Result<OperationOutput, Error> {
+		let operation_id = match response {
+			MethodResponse::Started(started) => started.operation_id,
+			MethodResponse::LimitReached => return Err(Error::LimitReached),
+		};
+
+		let mut body_items = Vec::new();
+		let mut storage_items = Vec::new();
+		loop {
+			let Some(event) = self.read_event().await else { return Err(Error::Stopped) };
+
+			match event? {
+				FollowEvent::OperationBodyItems(items) if items.operation_id == operation_id => {
+					body_items.extend(items.value);
+				},
+				FollowEvent::OperationBodyDone(done) if done.operation_id == operation_id => {
+					body_items.extend(done.value);
+					return Ok(OperationOutput::Body(body_items))
+				},
+				FollowEvent::OperationCallDone(done) if done.operation_id == operation_id =>
+					return Ok(OperationOutput::Call(done.output)),
+				FollowEvent::OperationStorageItems(items) if items.operation_id == operation_id => {
+					storage_items.extend(items.items);
+				},
+				FollowEvent::OperationWaitingForContinue(id) if id.operation_id == operation_id => {
+					ChainHeadApiClient::<Hash>::chain_head_unstable_continue(
+						&*self.client,
+						self.id.clone(),
+						operation_id.clone(),
+					)
+					.await?;
+				},
+				FollowEvent::OperationStorageDone(id) if id.operation_id == operation_id =>
+					return Ok(OperationOutput::Storage(storage_items)),
+				FollowEvent::OperationInaccessible(id) if id.operation_id == operation_id =>
+					return Err(Error::OperationInaccessible),
+				FollowEvent::OperationTimeout(id) if id.operation_id == operation_id =>
+					return Err(Error::OperationTimeout),
+				FollowEvent::OperationError(error) if error.operation_id == operation_id =>
+					return Err(Error::Operation(error.error)),
+				FollowEvent::Stop => {
+					self.buffered.push_back(FollowEvent::Stop);
+					return Err(Error::Stopped)
+				},
+				event => self.buffered.push_back(event),
+			}
+		}
+	}
+}
@@ -0,0 +1,120 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Typed JSON-RPC client of the Substrate RPC interface v2.
+//!
+//! The client is built on top of the `jsonrpsee` clients generated from the API traits
+//! of `sc-rpc-spec-v2` and reuses the same event types.
+//!
+//! - [`FollowSubscription`] wraps a `chainHead_unstable_follow` subscription, keeps track
+//! of the pinned blocks and drives the `body`, `call` and `storage` operations to completion.
+//! - [`TransactionWatch`] wraps a `transaction_unstable_submitAndWatch` subscription.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! # use sc_rpc_spec_v2_client::{ws_client, FollowSubscription};
+//! # use sp_core::H256;
+//! # use std::sync::Arc;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let client = Arc::new(ws_client("ws://127.0.0.1:9944").await.unwrap());
+//!     let mut follow = FollowSubscription::<_, H256>::new(client, false).await.unwrap();
+//!
+//!     while let Some(Ok(event)) = follow.next_event().await {
+//!         println!("{:?}", event);
+//!     }
+//! }
+//! ```
+
+#![warn(missing_docs)]
+
+mod chain_head;
+mod transaction;
+
+#[cfg(test)]
+mod tests;
+
+pub use chain_head::FollowSubscription;
+pub use jsonrpsee::ws_client::{WsClient, WsClientBuilder};
+pub use sc_rpc_spec_v2::{
+	chain_head::{
+		api::ChainHeadApiClient,
+		event::{StorageQuery, StorageQueryType, StorageResult, StorageResultType},
+		FollowEvent,
+	},
+	transaction::{
+		api::TransactionApiClient, TransactionBlock, TransactionEvent, TransactionWatchOptions,
+	},
+};
+pub use transaction::TransactionWatch;
+
+/// Client errors.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// The JSON-RPC request failed.
+	#[error(transparent)]
+	Rpc(#[from] jsonrpsee::core::Error),
+	/// The server did not provide a subscription ID.
+	#[error("The subscription ID is missing")]
+	MissingSubscriptionId,
+	/// The limit of ongoing operations of the follow subscription was reached.
+	#[error("The limit of ongoing operations was reached")]
+	LimitReached,
+	/// The operation failed.
+	#[error("Operation failed: {0}")]
+	Operation(String),
+	/// The operation could not be completed due to the server being unable to access the data.
+	#[error("The operation is inaccessible")]
+	OperationInaccessible,
+	/// The operation did not complete within the time allowed by the server.
+	#[error("The operation timed out")]
+	OperationTimeout,
+	/// The follow subscription was stopped by the server.
+	#[error("The follow subscription was stopped")]
+	Stopped,
+	/// The server reported a value that is not hex encoded.
+	#[error("Invalid hex value: {0}")]
+	InvalidHex(String),
+	/// The transaction was not included in a finalized block.
+	#[error("Transaction failed: {0}")]
+	Transaction(String),
+}
+
+/// Create a new `WebSocket` connection with shared settings.
+pub async fn ws_client(uri: impl AsRef<str>) -> Result<WsClient, String> {
+	WsClientBuilder::default()
+		.max_request_body_size(u32::MAX)
+		.request_timeout(std::time::Duration::from_secs(60 * 10))
+		.connection_timeout(std::time::Duration::from_secs(60))
+		.max_notifs_per_subscription(1024)
+		.build(uri)
+		.await
+		.map_err(|e| format!("`WsClientBuilder` failed to build: {:?}", e))
+}
+
+/// Decode a hex string reported by the server.
+fn decode_hex(value: &str) -> Result<Vec<u8>, Error> {
+	array_bytes::hex2bytes(value).map_err(|_| Error::InvalidHex(value.into()))
+}
+
+/// Encode the bytes as a hex string expected by the server.
+fn encode_hex(value: &[u8]) -> String {
+	format!("0x{}", sp_core::hexdisplay::HexDisplay::from(&value))
+}
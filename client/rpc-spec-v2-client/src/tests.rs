@@ -0,0 +1,114 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+use codec::Encode;
+use jsonrpsee::server::ServerBuilder;
+use sc_block_builder::BlockBuilderProvider;
+use sc_rpc_spec_v2::chain_head::{ChainHead, ChainHeadConfig};
+use sp_consensus::BlockOrigin;
+use sp_core::{storage::well_known_keys::CODE, testing::TaskExecutor, H256};
+use std::sync::Arc;
+use substrate_test_runtime_client::{prelude::*, runtime};
+
+const CHAIN_GENESIS: [u8; 32] = [0; 32];
+
+#[tokio::test(flavor = "multi_thread")]
+async fn follow_subscription_operations() {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let mut client = Arc::new(builder.build());
+
+	let api = ChainHead::new(
+		client.clone(),
+		backend,
+		Arc::new(TaskExecutor::default()),
+		CHAIN_GENESIS,
+		ChainHeadConfig::default(),
+	)
+	.into_rpc();
+
+	let server = ServerBuilder::default().build("127.0.0.1:0").await.unwrap();
+	let addr = server.local_addr().unwrap();
+	let _handle = server.start(api).unwrap();
+
+	let rpc_client = Arc::new(ws_client(format!("ws://{}", addr)).await.unwrap());
+	let mut follow = FollowSubscription::<_, H256>::new(rpc_client, false).await.unwrap();
+
+	let genesis_hash = client.genesis_hash();
+	assert!(matches!(follow.next_event().await, Some(Ok(FollowEvent::Initialized(_)))));
+	assert!(follow.is_pinned(&genesis_hash));
+
+	// Import a block with extrinsics.
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder
+		.push_transfer(runtime::Transfer {
+			from: AccountKeyring::Alice.into(),
+			to: AccountKeyring::Ferdie.into(),
+			amount: 42,
+			nonce: 0,
+		})
+		.unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = block.header.hash();
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	assert!(matches!(
+		follow.next_event().await,
+		Some(Ok(FollowEvent::NewBlock(new_block))) if new_block.block_hash == block_hash
+	));
+	assert!(matches!(follow.next_event().await, Some(Ok(FollowEvent::BestBlockChanged(_)))));
+	assert!(follow.is_pinned(&block_hash));
+
+	let header = follow.header(block_hash).await.unwrap();
+	assert_eq!(header, Some(block.header.encode()));
+
+	let body = follow.body(block_hash).await.unwrap();
+	let expected = block.extrinsics.iter().map(|tx| tx.encode()).collect::<Vec<_>>();
+	assert_eq!(body, expected);
+
+	// The transfer increased the nonce of Alice.
+	let alice_id = AccountKeyring::Alice.to_account_id();
+	let output = follow
+		.call(block_hash, "AccountNonceApi_account_nonce", &alice_id.encode())
+		.await
+		.unwrap();
+	assert_eq!(output, 1u64.encode());
+
+	let key = encode_hex(CODE);
+	let items = follow
+		.storage(
+			block_hash,
+			vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Exists }],
+			None,
+		)
+		.await
+		.unwrap();
+	assert_eq!(items.len(), 1);
+	assert_eq!(items[0].key, key);
+	assert_eq!(items[0].result, StorageResultType::Exists(true));
+
+	// The block is no longer pinned.
+	follow.unpin(block_hash).await.unwrap();
+	assert!(!follow.is_pinned(&block_hash));
+	assert!(follow.header(block_hash).await.is_err());
+
+	// The server reports the genesis block as the only pinned block.
+	follow.reconcile_pins().await.unwrap();
+	assert_eq!(follow.pinned().collect::<Vec<_>>(), vec![&genesis_hash]);
+}
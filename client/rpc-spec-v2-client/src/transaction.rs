@@ -0,0 +1,77 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Typed client of the `transaction` methods.
+
+use crate::Error;
+use jsonrpsee::core::client::{Subscription, SubscriptionClientT};
+use sc_rpc_spec_v2::transaction::{
+	api::TransactionApiClient, TransactionBlock, TransactionEvent, TransactionWatchOptions,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// A `transaction_unstable_submitAndWatch` subscription.
+pub struct TransactionWatch<Hash> {
+	/// The events of the transaction.
+	events: Subscription<TransactionEvent<Hash>>,
+}
+
+impl<Hash> TransactionWatch<Hash>
+where
+	Hash: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+	/// Submit the SCALE encoded extrinsic and watch its status.
+	pub async fn submit<C>(
+		client: &C,
+		extrinsic: Vec<u8>,
+		options: Option<TransactionWatchOptions>,
+	) -> Result<Self, Error>
+	where
+		C: SubscriptionClientT + Send + Sync,
+	{
+		let events =
+			TransactionApiClient::<Hash>::submit_and_watch(client, extrinsic.into(), options)
+				.await?;
+
+		Ok(TransactionWatch { events })
+	}
+
+	/// Get the next event of the transaction.
+	///
+	/// Returns `None` if the subscription was closed.
+	pub async fn next_event(&mut self) -> Option<Result<TransactionEvent<Hash>, Error>> {
+		self.events.next().await.map(|event| event.map_err(Into::into))
+	}
+
+	/// Wait until the transaction is included in a finalized block.
+	///
+	/// Returns an error if the transaction is reported as invalid, dropped or failed.
+	pub async fn wait_finalized(mut self) -> Result<TransactionBlock<Hash>, Error> {
+		while let Some(event) = self.next_event().await {
+			match event? {
+				TransactionEvent::Finalized(block) => return Ok(block),
+				TransactionEvent::Error(error) | TransactionEvent::Invalid(error) =>
+					return Err(Error::Transaction(error.error)),
+				TransactionEvent::Dropped(dropped) => return Err(Error::Transaction(dropped.error)),
+				_ => {},
+			}
+		}
+
+		Err(Error::Transaction("The subscription was closed".into()))
+	}
+}
@@ -0,0 +1,317 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! API implementation for `archive`.
+
+use crate::{
+	archive::{
+		archive_storage::ArchiveStorage,
+		checkpoint::{ScanCheckpoints, CHECKPOINT_TTL, MAX_CHECKPOINTS},
+		error::Error as ArchiveError,
+		ArchiveApiServer,
+	},
+	chain_head::{hex_string, storage_result_size, Bandwidth, BandwidthQuota},
+};
+use codec::Encode;
+use jsonrpsee::core::{async_trait, RpcResult};
+use parking_lot::Mutex;
+use sc_client_api::{
+	Backend, BlockBackend, CallExecutor, ChildInfo, ExecutorProvider, StorageKey, StorageProvider,
+};
+use sp_blockchain::{
+	Backend as BlockchainBackend, Error as BlockChainError, HeaderBackend, HeaderMetadata,
+};
+use sp_core::{traits::CallContext, Bytes};
+use sp_rpc_spec_v2::archive::{ArchiveStorageResult, MethodResult, PaginatedStorageQuery};
+use sp_runtime::{
+	traits::{Block as BlockT, NumberFor},
+	SaturatedConversion,
+};
+use std::{collections::HashSet, marker::PhantomData, sync::Arc, time::Duration};
+
+/// Logging target for the archive methods.
+const LOG_TARGET: &str = "rpc-spec-v2::archive";
+
+/// The maximum number of items reported by a descendants query of `archive_unstable_storage`.
+const MAX_DESCENDANT_RESPONSES: usize = 5;
+
+/// The maximum number of queried items processed by a call of `archive_unstable_storage`.
+const MAX_QUERIED_ITEMS: usize = 8;
+
+/// The configuration of [`Archive`].
+pub struct ArchiveConfig {
+	/// The maximum number of items reported by a descendants query.
+	///
+	/// The truncated query is resumed by `archive_unstable_storageContinue`.
+	pub max_descendant_responses: usize,
+	/// The maximum number of queried items processed by a call of `archive_unstable_storage`.
+	pub max_queried_items: usize,
+	/// The maximum number of persisted cursors of the truncated descendants queries.
+	///
+	/// The least recently updated cursor is removed to make room for a new one.
+	pub max_checkpoints: usize,
+	/// The duration after which a persisted cursor that is not continued is removed.
+	pub checkpoint_ttl: Duration,
+	/// The quota of bytes served by the methods.
+	///
+	/// The quota is shared by all the clients of the server, since the methods are not tied
+	/// to a subscription. Once it is exhausted, the methods that serve blocks, storage and
+	/// runtime calls return an error until the next period. No quota if `None`.
+	pub bandwidth_quota: Option<BandwidthQuota>,
+}
+
+impl Default for ArchiveConfig {
+	fn default() -> Self {
+		ArchiveConfig {
+			max_descendant_responses: MAX_DESCENDANT_RESPONSES,
+			max_queried_items: MAX_QUERIED_ITEMS,
+			max_checkpoints: MAX_CHECKPOINTS,
+			checkpoint_ttl: CHECKPOINT_TTL,
+			bandwidth_quota: None,
+		}
+	}
+}
+
+/// An API for archive RPC calls.
+pub struct Archive<BE: Backend<Block>, Block: BlockT, Client> {
+	/// Substrate client.
+	client: Arc<Client>,
+	/// Backend of the chain.
+	backend: Arc<BE>,
+	/// The hexadecimal encoded hash of the genesis block.
+	genesis_hash: String,
+	/// The maximum number of items reported by a descendants query.
+	max_descendant_responses: usize,
+	/// The maximum number of queried items processed by a call of `archive_unstable_storage`.
+	max_queried_items: usize,
+	/// Persist the cursors of the truncated descendants queries.
+	checkpoints: ScanCheckpoints<BE>,
+	/// Account the bytes served by the methods.
+	bandwidth: Mutex<Bandwidth>,
+	/// Phantom member to pin the block type.
+	_phantom: PhantomData<Block>,
+}
+
+impl<BE: Backend<Block>, Block: BlockT, Client> Archive<BE, Block, Client> {
+	/// Create a new [`Archive`].
+	pub fn new<GenesisHash: AsRef<[u8]>>(
+		client: Arc<Client>,
+		backend: Arc<BE>,
+		genesis_hash: GenesisHash,
+		config: ArchiveConfig,
+	) -> Self {
+		let genesis_hash = hex_string(&genesis_hash.as_ref());
+		let checkpoints = ScanCheckpoints::with_default_interval(backend.clone())
+			.with_max_checkpoints(config.max_checkpoints)
+			.with_ttl(config.checkpoint_ttl);
+		// The cursors of the queries that were not continued before the node stopped expire.
+		if let Err(err) = checkpoints.remove_expired() {
+			log::debug!(target: LOG_TARGET, "Failed to remove the expired scan checkpoints: {err}");
+		}
+
+		Self {
+			client,
+			checkpoints,
+			backend,
+			genesis_hash,
+			max_descendant_responses: config.max_descendant_responses,
+			max_queried_items: config.max_queried_items,
+			bandwidth: Mutex::new(Bandwidth::new(config.bandwidth_quota)),
+			_phantom: PhantomData,
+		}
+	}
+
+	/// Fail if the bandwidth quota is exhausted for the current period.
+	fn check_bandwidth(&self) -> Result<(), ArchiveError> {
+		if self.bandwidth.lock().is_exhausted() {
+			return Err(ArchiveError::BandwidthExhausted)
+		}
+		Ok(())
+	}
+
+	/// Account the bytes served by a method.
+	fn record_bandwidth(&self, bytes: usize) {
+		self.bandwidth.lock().record(bytes as u64);
+	}
+
+	/// The storage of the blocks, reported by `archive_unstable_storage`.
+	fn storage(&self) -> ArchiveStorage<Client, Block, BE> {
+		ArchiveStorage::new(
+			self.client.clone(),
+			self.max_descendant_responses,
+			self.max_queried_items,
+			self.checkpoints.clone(),
+		)
+	}
+}
+
+/// Parse hex-encoded string parameter as raw bytes.
+///
+/// If the parsing fails, returns an error propagated to the RPC method.
+fn parse_hex_param(param: String) -> Result<Vec<u8>, ArchiveError> {
+	// Methods can accept empty parameters.
+	if param.is_empty() {
+		return Ok(Default::default())
+	}
+
+	array_bytes::hex2bytes(&param).map_err(|_| ArchiveError::InvalidParam(param))
+}
+
+#[async_trait]
+impl<BE, Block, Client> ArchiveApiServer<Block::Hash> for Archive<BE, Block, Client>
+where
+	Block: BlockT + 'static,
+	BE: Backend<Block> + 'static,
+	Client: BlockBackend<Block>
+		+ ExecutorProvider<Block>
+		+ HeaderBackend<Block>
+		+ HeaderMetadata<Block, Error = BlockChainError>
+		+ StorageProvider<Block, BE>
+		+ 'static,
+{
+	fn archive_unstable_body(&self, hash: Block::Hash) -> RpcResult<Option<Vec<String>>> {
+		self.check_bandwidth()?;
+		let Ok(Some(signed_block)) = self.client.block(hash) else { return Ok(None) };
+
+		let extrinsics: Vec<String> = signed_block
+			.block
+			.extrinsics()
+			.iter()
+			.map(|extrinsic| hex_string(&extrinsic.encode()))
+			.collect();
+		self.record_bandwidth(extrinsics.iter().map(|extrinsic| extrinsic.len()).sum());
+		Ok(Some(extrinsics))
+	}
+
+	fn archive_unstable_genesis_hash(&self) -> RpcResult<String> {
+		Ok(self.genesis_hash.clone())
+	}
+
+	fn archive_unstable_header(&self, hash: Block::Hash) -> RpcResult<Option<String>> {
+		self.check_bandwidth()?;
+		let Ok(Some(header)) = self.client.header(hash) else { return Ok(None) };
+
+		let header = hex_string(&header.encode());
+		self.record_bandwidth(header.len());
+		Ok(Some(header))
+	}
+
+	fn archive_unstable_finalized_height(&self) -> RpcResult<u64> {
+		Ok(self.client.info().finalized_number.saturated_into())
+	}
+
+	fn archive_unstable_hash_by_height(&self, height: u64) -> RpcResult<Vec<String>> {
+		let height: NumberFor<Block> = height
+			.try_into()
+			.map_err(|_| ArchiveError::InvalidParam(format!("Invalid block height: {}", height)))?;
+
+		let finalized_number = self.client.info().finalized_number;
+		if height <= finalized_number {
+			let hash = self.client.hash(height).map_err(ArchiveError::Storage)?;
+			return Ok(hash.map(|hash| hex_string(&hash.as_ref())).into_iter().collect())
+		}
+
+		// The blocks above the finalized block are found by walking back from the leaves.
+		let leaves = self.backend.blockchain().leaves().map_err(ArchiveError::Storage)?;
+		let mut visited = HashSet::new();
+		let mut hashes = Vec::new();
+		for leaf in leaves {
+			let mut current = leaf;
+			loop {
+				let header = self.client.header_metadata(current).map_err(ArchiveError::Storage)?;
+				if header.number < height || !visited.insert(current) {
+					break
+				}
+				if header.number == height {
+					hashes.push(hex_string(&current.as_ref()));
+					break
+				}
+				current = header.parent;
+			}
+		}
+
+		Ok(hashes)
+	}
+
+	fn archive_unstable_call(
+		&self,
+		hash: Block::Hash,
+		function: String,
+		call_parameters: String,
+	) -> RpcResult<MethodResult> {
+		let call_parameters = Bytes::from(parse_hex_param(call_parameters)?);
+		self.check_bandwidth()?;
+
+		let result =
+			self.client
+				.executor()
+				.call(hash, &function, &call_parameters, CallContext::Offchain);
+
+		Ok(match result {
+			Ok(output) => {
+				let output = hex_string(&output);
+				self.record_bandwidth(output.len());
+				MethodResult::ok(output)
+			},
+			Err(error) => MethodResult::err(error.to_string()),
+		})
+	}
+
+	fn archive_unstable_storage(
+		&self,
+		hash: Block::Hash,
+		items: Vec<PaginatedStorageQuery<String>>,
+		child_trie: Option<String>,
+	) -> RpcResult<ArchiveStorageResult> {
+		let items = items
+			.into_iter()
+			.map(|query| {
+				let pagination_start_key = query
+					.pagination_start_key
+					.map(|key| parse_hex_param(key).map(StorageKey))
+					.transpose()?;
+
+				Ok::<_, ArchiveError>(PaginatedStorageQuery {
+					key: StorageKey(parse_hex_param(query.key)?),
+					query_type: query.query_type,
+					pagination_start_key,
+				})
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+
+		let child_trie = child_trie
+			.map(|child_trie| parse_hex_param(child_trie))
+			.transpose()?
+			.map(ChildInfo::new_default_from_vec);
+
+		self.check_bandwidth()?;
+		let result = self.storage().handle_query(hash, items, child_trie)?;
+		self.record_bandwidth(result.result.iter().map(storage_result_size).sum());
+		Ok(result)
+	}
+
+	fn archive_unstable_storage_continue(
+		&self,
+		continuation_token: String,
+	) -> RpcResult<ArchiveStorageResult> {
+		self.check_bandwidth()?;
+		let result = self.storage().handle_continue(&continuation_token)?;
+		self.record_bandwidth(result.result.iter().map(storage_result_size).sum());
+		Ok(result)
+	}
+}
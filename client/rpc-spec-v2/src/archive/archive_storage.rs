@@ -0,0 +1,271 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Implementation of the `archive_unstable_storage` method.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use sc_client_api::{Backend, ChildInfo, StorageKey, StorageProvider};
+use sp_api::BlockT;
+use sp_rpc_spec_v2::{
+	archive::{ArchiveStorageResult, PaginatedStorageQuery},
+	chain_head::{StorageQueryType, StorageResult, StorageResultType},
+};
+use sp_trie::MerkleValue;
+
+use super::{
+	checkpoint::{ScanCheckpoints, ScanCursor},
+	error::Error as ArchiveError,
+};
+use crate::chain_head::hex_string;
+
+/// The descendants reported by a scan and whether the scan was truncated by the limit.
+type DescendantsResult = (Vec<(StorageKey, StorageResult)>, bool);
+
+/// Generates the results of the `archive_unstable_storage` method.
+pub struct ArchiveStorage<Client, Block, BE> {
+	/// Substrate client.
+	client: Arc<Client>,
+	/// The maximum number of items reported by a descendants query.
+	max_descendant_responses: usize,
+	/// The maximum number of queried items processed by a call.
+	max_queried_items: usize,
+	/// Persist the cursors of the truncated descendants queries.
+	checkpoints: ScanCheckpoints<BE>,
+	_phantom: PhantomData<Block>,
+}
+
+impl<Client, Block, BE> ArchiveStorage<Client, Block, BE> {
+	/// Constructs a new [`ArchiveStorage`].
+	pub fn new(
+		client: Arc<Client>,
+		max_descendant_responses: usize,
+		max_queried_items: usize,
+		checkpoints: ScanCheckpoints<BE>,
+	) -> Self {
+		Self {
+			client,
+			max_descendant_responses,
+			max_queried_items,
+			checkpoints,
+			_phantom: PhantomData,
+		}
+	}
+}
+
+impl<Client, Block, BE> ArchiveStorage<Client, Block, BE>
+where
+	Block: BlockT + 'static,
+	BE: Backend<Block> + 'static,
+	Client: StorageProvider<Block, BE> + 'static,
+{
+	/// Fetch the result of a query of a single key.
+	fn query_item(
+		&self,
+		hash: Block::Hash,
+		key: &StorageKey,
+		query_type: &StorageQueryType,
+		child_key: Option<&ChildInfo>,
+	) -> Result<Option<StorageResult>, ArchiveError> {
+		let read_storage = || match child_key {
+			Some(child_key) => self.client.child_storage(hash, child_key, key),
+			None => self.client.storage(hash, key),
+		};
+		let read_storage_hash = || match child_key {
+			Some(child_key) => self.client.child_storage_hash(hash, child_key, key),
+			None => self.client.storage_hash(hash, key),
+		};
+
+		let result = match query_type {
+			StorageQueryType::Value => read_storage()
+				.map_err(ArchiveError::Storage)?
+				.map(|storage_data| StorageResultType::Value(hex_string(&storage_data.0))),
+			StorageQueryType::Hash => read_storage_hash()
+				.map_err(ArchiveError::Storage)?
+				.map(|storage_hash| StorageResultType::Hash(hex_string(&storage_hash.as_ref()))),
+			StorageQueryType::Exists => Some(StorageResultType::Exists(
+				read_storage_hash().map_err(ArchiveError::Storage)?.is_some(),
+			)),
+			StorageQueryType::ValueLength => read_storage()
+				.map_err(ArchiveError::Storage)?
+				.map(|storage_data| StorageResultType::ValueLength(storage_data.0.len() as u32)),
+			StorageQueryType::ClosestDescendantMerkleValue => {
+				let merkle_value = match child_key {
+					Some(child_key) => self.client.child_closest_merkle_value(hash, child_key, key),
+					None => self.client.closest_merkle_value(hash, key),
+				}
+				.map_err(ArchiveError::Storage)?;
+
+				merkle_value.map(|merkle_value| {
+					let merkle_value = match merkle_value {
+						MerkleValue::Node(data) => hex_string(&data),
+						MerkleValue::Hash(hash) => hex_string(&hash.as_ref()),
+					};
+					StorageResultType::ClosestDescendantMerkleValue(merkle_value)
+				})
+			},
+			StorageQueryType::AncestorValues { .. } =>
+				return Err(ArchiveError::InvalidParam(
+					"The ancestor values are not provided by the archive".into(),
+				)),
+			StorageQueryType::DescendantsValues | StorageQueryType::DescendantsHashes =>
+				return Err(ArchiveError::InvalidParam(
+					"The descendants are not provided by a single key query".into(),
+				)),
+		};
+
+		Ok(result.map(|result| StorageResult { key: hex_string(&key.0), result }))
+	}
+
+	/// Fetch at most `max_descendant_responses` descendants of the prefix, that follow the
+	/// start key if any.
+	fn query_descendants(
+		&self,
+		hash: Block::Hash,
+		prefix: &StorageKey,
+		start_key: Option<&StorageKey>,
+		child_key: Option<&ChildInfo>,
+		hashes: bool,
+	) -> Result<DescendantsResult, ArchiveError> {
+		let mut keys_iter = match child_key {
+			Some(child_key) => self
+				.client
+				.child_storage_keys(hash, child_key.to_owned(), Some(prefix), start_key),
+			None => self.client.storage_keys(hash, Some(prefix), start_key),
+		}
+		.map_err(ArchiveError::Storage)?;
+
+		let query_type = if hashes { StorageQueryType::Hash } else { StorageQueryType::Value };
+
+		let mut descendants = Vec::new();
+		for key in keys_iter.by_ref().take(self.max_descendant_responses) {
+			if let Some(result) = self.query_item(hash, &key, &query_type, child_key)? {
+				descendants.push((key, result));
+			}
+		}
+
+		Ok((descendants, keys_iter.next().is_some()))
+	}
+
+	/// Advance the cursor past the reported descendants and collect their results.
+	fn advance_cursor(
+		cursor: &mut ScanCursor<BE, Block::Hash>,
+		descendants: Vec<(StorageKey, StorageResult)>,
+		result: &mut Vec<StorageResult>,
+	) -> Result<(), ArchiveError> {
+		for (key, item) in descendants {
+			cursor.advance(key.0).map_err(ArchiveError::Storage)?;
+			result.push(item);
+		}
+		Ok(())
+	}
+
+	/// Generate the result of the `archive_unstable_storage` method.
+	///
+	/// The processing stops at the first descendants query truncated by the limit, whose
+	/// cursor is persisted and identified by the continuation token of the result.
+	pub fn handle_query(
+		&self,
+		hash: Block::Hash,
+		mut items: Vec<PaginatedStorageQuery<StorageKey>>,
+		child_key: Option<ChildInfo>,
+	) -> Result<ArchiveStorageResult, ArchiveError> {
+		let discarded_items = items.len().saturating_sub(self.max_queried_items);
+		items.truncate(self.max_queried_items);
+
+		let mut storage_results = ArchiveStorageResult {
+			result: Vec::with_capacity(items.len()),
+			discarded_items,
+			continuation_token: None,
+		};
+
+		let queried_items = items.len();
+		for (index, item) in items.into_iter().enumerate() {
+			let hashes = match item.query_type {
+				StorageQueryType::DescendantsValues => false,
+				StorageQueryType::DescendantsHashes => true,
+				_ => {
+					let result =
+						self.query_item(hash, &item.key, &item.query_type, child_key.as_ref())?;
+					storage_results.result.extend(result);
+					continue
+				},
+			};
+
+			let (descendants, truncated) = self.query_descendants(
+				hash,
+				&item.key,
+				item.pagination_start_key.as_ref(),
+				child_key.as_ref(),
+				hashes,
+			)?;
+
+			if !truncated {
+				storage_results.result.extend(descendants.into_iter().map(|(_, item)| item));
+				continue
+			}
+
+			let mut cursor = self.checkpoints.start(
+				hash,
+				item.key.0,
+				child_key.as_ref().map(|child_key| child_key.storage_key().to_vec()),
+				hashes,
+			);
+			Self::advance_cursor(&mut cursor, descendants, &mut storage_results.result)?;
+			cursor.persist().map_err(ArchiveError::Storage)?;
+
+			storage_results.continuation_token = Some(cursor.token().to_string());
+			storage_results.discarded_items += queried_items - index - 1;
+			break
+		}
+
+		Ok(storage_results)
+	}
+
+	/// Generate the result of the `archive_unstable_storageContinue` method.
+	pub fn handle_continue(&self, token: &str) -> Result<ArchiveStorageResult, ArchiveError> {
+		let Some(mut cursor) =
+			self.checkpoints.resume::<Block::Hash>(token).map_err(ArchiveError::Storage)?
+		else {
+			return Err(ArchiveError::InvalidContinuationToken)
+		};
+
+		let checkpoint = cursor.checkpoint();
+		let hash = checkpoint.hash;
+		let hashes = checkpoint.hashes;
+		let prefix = StorageKey(checkpoint.prefix.clone());
+		let start_key = checkpoint.last_key.clone().map(StorageKey);
+		let child_key = checkpoint.child_trie.clone().map(ChildInfo::new_default_from_vec);
+
+		let (descendants, truncated) =
+			self.query_descendants(hash, &prefix, start_key.as_ref(), child_key.as_ref(), hashes)?;
+
+		let mut result = Vec::with_capacity(descendants.len());
+		Self::advance_cursor(&mut cursor, descendants, &mut result)?;
+
+		let continuation_token = if truncated {
+			cursor.persist().map_err(ArchiveError::Storage)?;
+			Some(cursor.token().to_string())
+		} else {
+			cursor.complete().map_err(ArchiveError::Storage)?;
+			None
+		};
+
+		Ok(ArchiveStorageResult { result, discarded_items: 0, continuation_token })
+	}
+}
@@ -0,0 +1,444 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Persistent checkpoints of the long storage scans of the `archive` methods.
+//!
+//! A scan of the keys under a prefix may last for hours on an archive node. The cursor of
+//! the scan is periodically persisted to the auxiliary store of the client, keyed by the
+//! continuation token provided to the client, such that a scan interrupted by a restart of
+//! the node resumes from its last checkpoint instead of from the beginning.
+//!
+//! The checkpoints are listed by an index, such that their number is capped and the
+//! checkpoints that are not updated within their time to live are removed. The least
+//! recently updated checkpoint is removed to make room for a new one.
+
+use codec::{Decode, Encode};
+use parking_lot::Mutex;
+use sc_client_api::AuxStore;
+use sp_blockchain::Error as BlockChainError;
+use std::{
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
+	time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// The prefix of the auxiliary store keys of the checkpoints.
+const CHECKPOINT_KEY_PREFIX: &[u8] = b"rpc_archive_scan_checkpoint:";
+
+/// The auxiliary store key of the index of the checkpoints.
+const CHECKPOINT_INDEX_KEY: &[u8] = b"rpc_archive_scan_checkpoints";
+
+/// The default interval between two checkpoints of a scan.
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The default maximum number of checkpoints.
+pub const MAX_CHECKPOINTS: usize = 1024;
+
+/// The default duration after which a checkpoint that is not updated expires.
+pub const CHECKPOINT_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Serialize the updates of the index of the checkpoints, which is shared by the
+/// [`ScanCheckpoints`] of the RPC modules.
+static INDEX_LOCK: Mutex<()> = parking_lot::const_mutex(());
+
+/// The continuation tokens of the checkpoints and the instant of their last update, in
+/// seconds since the unix epoch, from the least to the most recently updated.
+type CheckpointIndex = Vec<(String, u64)>;
+
+/// The persisted cursor of a storage scan.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct ScanCheckpoint<Hash> {
+	/// The block whose storage is scanned.
+	pub hash: Hash,
+	/// The scanned key prefix.
+	pub prefix: Vec<u8>,
+	/// The storage key of the scanned child trie, if any.
+	pub child_trie: Option<Vec<u8>>,
+	/// True if the scan reports the hashes of the values instead of the values.
+	pub hashes: bool,
+	/// The last key reported by the scan.
+	///
+	/// The scan resumes after this key, or from the prefix if no key was reported.
+	pub last_key: Option<Vec<u8>>,
+	/// The number of items reported by the scan.
+	pub reported_items: u64,
+}
+
+/// Persist the checkpoints of the storage scans to the auxiliary store of the client.
+pub struct ScanCheckpoints<Client> {
+	/// The client providing the auxiliary store.
+	client: Arc<Client>,
+	/// The minimum interval between two checkpoints of a scan.
+	interval: Duration,
+	/// The maximum number of checkpoints.
+	max_checkpoints: usize,
+	/// The duration after which a checkpoint that is not updated expires.
+	ttl: Duration,
+	/// Distinguish the continuation tokens of the scans started at the same instant.
+	next_nonce: Arc<AtomicU64>,
+}
+
+impl<Client> Clone for ScanCheckpoints<Client> {
+	fn clone(&self) -> Self {
+		Self {
+			client: self.client.clone(),
+			interval: self.interval,
+			max_checkpoints: self.max_checkpoints,
+			ttl: self.ttl,
+			next_nonce: self.next_nonce.clone(),
+		}
+	}
+}
+
+impl<Client: AuxStore> ScanCheckpoints<Client> {
+	/// Create a new [`ScanCheckpoints`] persisting the cursors at the given interval.
+	pub fn new(client: Arc<Client>, interval: Duration) -> Self {
+		Self {
+			client,
+			interval,
+			max_checkpoints: MAX_CHECKPOINTS,
+			ttl: CHECKPOINT_TTL,
+			next_nonce: Default::default(),
+		}
+	}
+
+	/// Create a new [`ScanCheckpoints`] persisting the cursors every 30 seconds.
+	pub fn with_default_interval(client: Arc<Client>) -> Self {
+		Self::new(client, CHECKPOINT_INTERVAL)
+	}
+
+	/// Limit the number of checkpoints, at least one checkpoint is kept.
+	pub fn with_max_checkpoints(mut self, max_checkpoints: usize) -> Self {
+		self.max_checkpoints = max_checkpoints.max(1);
+		self
+	}
+
+	/// Remove the checkpoints that are not updated within the given duration.
+	pub fn with_ttl(mut self, ttl: Duration) -> Self {
+		self.ttl = ttl;
+		self
+	}
+
+	/// Start a new scan of the prefix at the given block.
+	///
+	/// The scan is identified by a new continuation token.
+	pub fn start<Hash: Encode>(
+		&self,
+		hash: Hash,
+		prefix: Vec<u8>,
+		child_trie: Option<Vec<u8>>,
+		hashes: bool,
+	) -> ScanCursor<Client, Hash> {
+		let nonce = self.next_nonce.fetch_add(1, Ordering::Relaxed);
+		let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |now| now.as_nanos());
+		let token = hex::encode(sp_core::blake2_128(&(&hash, &prefix, now, nonce).encode()));
+
+		ScanCursor {
+			checkpoints: self.clone(),
+			token,
+			checkpoint: ScanCheckpoint {
+				hash,
+				prefix,
+				child_trie,
+				hashes,
+				last_key: None,
+				reported_items: 0,
+			},
+			last_persisted: Instant::now(),
+		}
+	}
+
+	/// Resume the scan identified by the continuation token from its last checkpoint.
+	///
+	/// Returns `None` if the token is unknown, ie the scan completed, was not checkpointed or
+	/// its checkpoint was removed.
+	pub fn resume<Hash: Decode>(
+		&self,
+		token: &str,
+	) -> Result<Option<ScanCursor<Client, Hash>>, BlockChainError> {
+		let now = unix_time();
+		let is_live = self
+			.read_index()?
+			.iter()
+			.any(|(indexed, updated)| indexed == token && !self.is_expired(*updated, now));
+		if !is_live {
+			return Ok(None)
+		}
+
+		let Some(encoded) = self.client.get_aux(&checkpoint_key(token))? else { return Ok(None) };
+
+		let checkpoint = ScanCheckpoint::decode(&mut &encoded[..]).map_err(|err| {
+			BlockChainError::Backend(format!("Invalid scan checkpoint {}: {}", token, err))
+		})?;
+
+		Ok(Some(ScanCursor {
+			checkpoints: self.clone(),
+			token: token.to_string(),
+			checkpoint,
+			last_persisted: Instant::now(),
+		}))
+	}
+
+	/// Remove the expired checkpoints, ie when the node starts.
+	///
+	/// Returns the number of removed checkpoints.
+	pub fn remove_expired(&self) -> Result<usize, BlockChainError> {
+		let _lock = INDEX_LOCK.lock();
+		let mut index = self.read_index()?;
+		let removed = self.retain_live(&mut index, unix_time());
+		if !removed.is_empty() {
+			self.write_index(&index, None, &removed)?;
+		}
+		Ok(removed.len())
+	}
+
+	/// Persist the checkpoint of the scan, or remove it if not provided.
+	fn update(&self, token: &str, checkpoint: Option<Vec<u8>>) -> Result<(), BlockChainError> {
+		let _lock = INDEX_LOCK.lock();
+		let mut index = self.read_index()?;
+		index.retain(|(indexed, _)| indexed != token);
+
+		let now = unix_time();
+		let mut removed = self.retain_live(&mut index, now);
+		let checkpoint = match checkpoint {
+			Some(checkpoint) => {
+				// Make room for the checkpoint by removing the least recently updated ones.
+				let excess = (index.len() + 1).saturating_sub(self.max_checkpoints);
+				removed.extend(index.drain(..excess).map(|(token, _)| token));
+				index.push((token.to_string(), now));
+				Some((token, checkpoint))
+			},
+			None => {
+				removed.push(token.to_string());
+				None
+			},
+		};
+
+		self.write_index(&index, checkpoint, &removed)
+	}
+
+	/// Remove the expired checkpoints from the index, and return their tokens.
+	fn retain_live(&self, index: &mut CheckpointIndex, now: u64) -> Vec<String> {
+		let mut removed = Vec::new();
+		index.retain(|(token, updated)| {
+			let is_expired = self.is_expired(*updated, now);
+			if is_expired {
+				removed.push(token.clone());
+			}
+			!is_expired
+		});
+		removed
+	}
+
+	/// Returns true if the checkpoint updated at the given instant is expired.
+	fn is_expired(&self, updated: u64, now: u64) -> bool {
+		Duration::from_secs(now.saturating_sub(updated)) >= self.ttl
+	}
+
+	/// Read the index of the checkpoints.
+	fn read_index(&self) -> Result<CheckpointIndex, BlockChainError> {
+		let Some(encoded) = self.client.get_aux(CHECKPOINT_INDEX_KEY)? else {
+			return Ok(Default::default())
+		};
+
+		CheckpointIndex::decode(&mut &encoded[..]).map_err(|err| {
+			BlockChainError::Backend(format!("Invalid scan checkpoint index: {}", err))
+		})
+	}
+
+	/// Write the index along with the given checkpoint, and remove the given checkpoints.
+	fn write_index(
+		&self,
+		index: &CheckpointIndex,
+		checkpoint: Option<(&str, Vec<u8>)>,
+		removed: &[String],
+	) -> Result<(), BlockChainError> {
+		let index = index.encode();
+		let checkpoint = checkpoint.map(|(token, value)| (checkpoint_key(token), value));
+		let removed = removed.iter().map(|token| checkpoint_key(token)).collect::<Vec<_>>();
+
+		let mut insert = vec![(CHECKPOINT_INDEX_KEY, &index[..])];
+		if let Some((key, value)) = &checkpoint {
+			insert.push((&key[..], &value[..]));
+		}
+		let delete = removed.iter().map(|key| &key[..]).collect::<Vec<_>>();
+		self.client.insert_aux(&insert, &delete)
+	}
+}
+
+/// The current time, in seconds since the unix epoch.
+fn unix_time() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |now| now.as_secs())
+}
+
+/// The auxiliary store key of the checkpoint of the given scan.
+fn checkpoint_key(token: &str) -> Vec<u8> {
+	let mut key = CHECKPOINT_KEY_PREFIX.to_vec();
+	key.extend_from_slice(token.as_bytes());
+	key
+}
+
+/// The cursor of a storage scan, persisted periodically while the scan advances.
+pub struct ScanCursor<Client, Hash> {
+	/// The checkpoints of the scans.
+	checkpoints: ScanCheckpoints<Client>,
+	/// The continuation token identifying the scan.
+	token: String,
+	/// The current position of the scan.
+	checkpoint: ScanCheckpoint<Hash>,
+	/// The instant of the last checkpoint.
+	last_persisted: Instant,
+}
+
+impl<Client: AuxStore, Hash: Encode> ScanCursor<Client, Hash> {
+	/// The continuation token identifying the scan.
+	pub fn token(&self) -> &str {
+		&self.token
+	}
+
+	/// The current position of the scan.
+	pub fn checkpoint(&self) -> &ScanCheckpoint<Hash> {
+		&self.checkpoint
+	}
+
+	/// Advance the scan past the given key.
+	///
+	/// The cursor is persisted if the checkpoint interval elapsed since the last checkpoint.
+	pub fn advance(&mut self, key: Vec<u8>) -> Result<(), BlockChainError> {
+		self.checkpoint.last_key = Some(key);
+		self.checkpoint.reported_items += 1;
+
+		if self.last_persisted.elapsed() >= self.checkpoints.interval {
+			self.persist()?;
+		}
+		Ok(())
+	}
+
+	/// Persist the cursor, ie when the scan is paused until the client continues it.
+	///
+	/// The least recently updated checkpoint is removed if the maximum number of checkpoints
+	/// is reached.
+	pub fn persist(&mut self) -> Result<(), BlockChainError> {
+		self.checkpoints.update(&self.token, Some(self.checkpoint.encode()))?;
+		self.last_persisted = Instant::now();
+		Ok(())
+	}
+
+	/// The scan completed, remove its checkpoint.
+	pub fn complete(self) -> Result<(), BlockChainError> {
+		self.checkpoints.update(&self.token, None)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::H256;
+	use substrate_test_runtime_client::{prelude::*, Backend, Client};
+
+	fn checkpoints(interval: Duration) -> ScanCheckpoints<Client<Backend>> {
+		ScanCheckpoints::new(Arc::new(TestClientBuilder::new().build()), interval)
+	}
+
+	#[test]
+	fn scan_resumes_from_checkpoint() {
+		let checkpoints = checkpoints(Duration::ZERO);
+		let hash = H256::random();
+
+		let mut cursor = checkpoints.start(hash, b":prefix".to_vec(), None, false);
+		let token = cursor.token().to_string();
+		// The scan is not checkpointed before it advances.
+		assert!(checkpoints.resume::<H256>(&token).unwrap().is_none());
+
+		cursor.advance(b":prefix:a".to_vec()).unwrap();
+		cursor.advance(b":prefix:b".to_vec()).unwrap();
+
+		let resumed = checkpoints.resume::<H256>(&token).unwrap().unwrap();
+		let expected = ScanCheckpoint {
+			hash,
+			prefix: b":prefix".to_vec(),
+			child_trie: None,
+			hashes: false,
+			last_key: Some(b":prefix:b".to_vec()),
+			reported_items: 2,
+		};
+		assert_eq!(resumed.checkpoint(), &expected);
+		assert_eq!(resumed.token(), token);
+
+		// The checkpoint is removed once the scan completes.
+		resumed.complete().unwrap();
+		assert!(checkpoints.resume::<H256>(&token).unwrap().is_none());
+	}
+
+	#[test]
+	fn scan_is_checkpointed_periodically() {
+		let checkpoints = checkpoints(Duration::from_secs(60 * 60));
+		let hash = H256::random();
+
+		let mut cursor = checkpoints.start(hash, b":prefix".to_vec(), None, false);
+		let token = cursor.token().to_string();
+		// The tokens of the scans are unique.
+		assert_ne!(checkpoints.start(hash, b":prefix".to_vec(), None, false).token(), token);
+
+		cursor.advance(b":prefix:a".to_vec()).unwrap();
+		assert!(checkpoints.resume::<H256>(&token).unwrap().is_none());
+
+		cursor.persist().unwrap();
+		let resumed = checkpoints.resume::<H256>(&token).unwrap().unwrap();
+		assert_eq!(resumed.checkpoint().reported_items, 1);
+	}
+
+	#[test]
+	fn checkpoints_are_capped() {
+		let checkpoints = checkpoints(Duration::ZERO).with_max_checkpoints(2);
+		let hash = H256::random();
+
+		let mut tokens = Vec::new();
+		for key in [b"a", b"b", b"c"] {
+			let mut cursor = checkpoints.start(hash, b":prefix".to_vec(), None, false);
+			cursor.advance(key.to_vec()).unwrap();
+			tokens.push(cursor.token().to_string());
+		}
+
+		// The least recently updated checkpoint is removed.
+		assert!(checkpoints.resume::<H256>(&tokens[0]).unwrap().is_none());
+		assert!(checkpoints.resume::<H256>(&tokens[1]).unwrap().is_some());
+		assert!(checkpoints.resume::<H256>(&tokens[2]).unwrap().is_some());
+	}
+
+	#[test]
+	fn expired_checkpoints_are_removed() {
+		let client = Arc::new(TestClientBuilder::new().build());
+		let checkpoints = ScanCheckpoints::new(client.clone(), Duration::ZERO);
+		let hash = H256::random();
+
+		let mut cursor = checkpoints.start(hash, b":prefix".to_vec(), None, false);
+		cursor.advance(b":prefix:a".to_vec()).unwrap();
+		let token = cursor.token().to_string();
+		assert_eq!(checkpoints.remove_expired().unwrap(), 0);
+
+		// The checkpoint expires, and is removed when the node starts.
+		let checkpoints =
+			ScanCheckpoints::new(client.clone(), Duration::ZERO).with_ttl(Duration::ZERO);
+		assert!(checkpoints.resume::<H256>(&token).unwrap().is_none());
+		assert_eq!(checkpoints.remove_expired().unwrap(), 1);
+		assert!(client.get_aux(&checkpoint_key(&token)).unwrap().is_none());
+	}
+}
@@ -0,0 +1,381 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::chain_head::hex_string;
+
+use super::*;
+use assert_matches::assert_matches;
+use codec::{Decode, Encode};
+use jsonrpsee::{
+	core::error::Error,
+	rpc_params,
+	types::{error::CallError, EmptyServerParams as EmptyParams},
+	RpcModule,
+};
+use sc_block_builder::BlockBuilderProvider;
+use sp_blockchain::HeaderBackend;
+use sp_consensus::BlockOrigin;
+use sp_rpc_spec_v2::{
+	archive::{ArchiveStorageResult, MethodResult, PaginatedStorageQuery},
+	chain_head::{StorageQueryType, StorageResultType},
+};
+use sp_runtime::traits::Header as HeaderT;
+use std::sync::Arc;
+use substrate_test_runtime_client::{
+	prelude::*, runtime, Backend, BlockBuilderExt, Client, ClientBlockImportExt,
+};
+
+type Header = substrate_test_runtime_client::runtime::Header;
+type Block = substrate_test_runtime_client::runtime::Block;
+const CHAIN_GENESIS: [u8; 32] = [0; 32];
+const INVALID_HASH: [u8; 32] = [1; 32];
+const MAX_DESCENDANT_RESPONSES: usize = 2;
+const MAX_QUERIED_ITEMS: usize = 3;
+const KEY: &[u8] = b":mock";
+const VALUE: &[u8] = b"hello world";
+
+fn setup_api() -> (Arc<Client<Backend>>, RpcModule<Archive<Backend, Block, Client<Backend>>>) {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let client = Arc::new(builder.build());
+
+	let api = Archive::new(
+		client.clone(),
+		backend,
+		CHAIN_GENESIS,
+		ArchiveConfig {
+			max_descendant_responses: MAX_DESCENDANT_RESPONSES,
+			max_queried_items: MAX_QUERIED_ITEMS,
+			..Default::default()
+		},
+	)
+	.into_rpc();
+
+	(client, api)
+}
+
+#[tokio::test]
+async fn archive_genesis() {
+	let (_client, api) = setup_api();
+
+	let genesis: String =
+		api.call("archive_unstable_genesisHash", EmptyParams::new()).await.unwrap();
+	assert_eq!(genesis, hex_string(&CHAIN_GENESIS));
+}
+
+#[tokio::test]
+async fn archive_body_and_header() {
+	let (mut client, api) = setup_api();
+	let invalid_hash = hex_string(&INVALID_HASH);
+
+	// Unknown blocks are reported as null.
+	let body: Option<Vec<String>> =
+		api.call("archive_unstable_body", [&invalid_hash]).await.unwrap();
+	assert!(body.is_none());
+	let header: Option<String> =
+		api.call("archive_unstable_header", [&invalid_hash]).await.unwrap();
+	assert!(header.is_none());
+
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder
+		.push_transfer(runtime::Transfer {
+			from: AccountKeyring::Alice.into(),
+			to: AccountKeyring::Ferdie.into(),
+			amount: 42,
+			nonce: 0,
+		})
+		.unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	let body: Vec<String> = api.call("archive_unstable_body", [&block_hash]).await.unwrap();
+	let expected: Vec<String> = block
+		.extrinsics
+		.iter()
+		.map(|extrinsic| hex_string(&extrinsic.encode()))
+		.collect();
+	assert_eq!(body, expected);
+
+	let header: String = api.call("archive_unstable_header", [&block_hash]).await.unwrap();
+	let bytes = array_bytes::hex2bytes(&header).unwrap();
+	let header: Header = Decode::decode(&mut &bytes[..]).unwrap();
+	assert_eq!(header, block.header);
+}
+
+#[tokio::test]
+async fn archive_finalized_height_and_hash_by_height() {
+	let (mut client, api) = setup_api();
+
+	// Import block 1 and block 2 on top of it, and block 2 on a fork.
+	let block_1 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_1_hash = block_1.header.hash();
+	client.import(BlockOrigin::Own, block_1.clone()).await.unwrap();
+
+	let block_2 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_2_hash = block_2.header.hash();
+	client.import(BlockOrigin::Own, block_2.clone()).await.unwrap();
+
+	let mut builder = client.new_block_at(block_1_hash, Default::default(), false).unwrap();
+	// This push is required as otherwise the fork has the same hash as block 2.
+	builder
+		.push_transfer(runtime::Transfer {
+			from: AccountKeyring::Alice.into(),
+			to: AccountKeyring::Ferdie.into(),
+			amount: 41,
+			nonce: 0,
+		})
+		.unwrap();
+	let fork_block = builder.build().unwrap().block;
+	let fork_hash = fork_block.header.hash();
+	client.import(BlockOrigin::Own, fork_block.clone()).await.unwrap();
+
+	let height: u64 =
+		api.call("archive_unstable_finalizedHeight", EmptyParams::new()).await.unwrap();
+	assert_eq!(height, 0);
+
+	// Both blocks above the finalized block are reported.
+	let mut hashes: Vec<String> = api.call("archive_unstable_hashByHeight", [2]).await.unwrap();
+	hashes.sort();
+	let mut expected = vec![format!("{:?}", block_2_hash), format!("{:?}", fork_hash)];
+	expected.sort();
+	assert_eq!(hashes, expected);
+
+	// Only the canonical block is reported once finalized.
+	client.finalize_block(block_2_hash, None).unwrap();
+	let height: u64 =
+		api.call("archive_unstable_finalizedHeight", EmptyParams::new()).await.unwrap();
+	assert_eq!(height, 2);
+
+	let hashes: Vec<String> = api.call("archive_unstable_hashByHeight", [2]).await.unwrap();
+	assert_eq!(hashes, vec![format!("{:?}", block_2_hash)]);
+	let hashes: Vec<String> = api.call("archive_unstable_hashByHeight", [1]).await.unwrap();
+	assert_eq!(hashes, vec![format!("{:?}", block_1_hash)]);
+
+	// No blocks above the leaves.
+	let hashes: Vec<String> = api.call("archive_unstable_hashByHeight", [3]).await.unwrap();
+	assert!(hashes.is_empty());
+}
+
+#[tokio::test]
+async fn archive_call() {
+	let (client, api) = setup_api();
+	let genesis_hash = format!("{:?}", client.genesis_hash());
+
+	// Invalid parameter (not hex).
+	let err = api
+		.call::<_, serde_json::Value>(
+			"archive_unstable_call",
+			[&genesis_hash, "AccountNonceApi_account_nonce", "0x0"],
+		)
+		.await
+		.unwrap_err();
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == 6001 && err.message().contains("Invalid parameter")
+	);
+
+	// The failed runtime calls are reported by the result.
+	let result: MethodResult = api
+		.call("archive_unstable_call", [&genesis_hash, "DoesNotExist_method", "0x00"])
+		.await
+		.unwrap();
+	assert!(!result.success);
+	assert!(result.error.is_some());
+
+	let alice_id = AccountKeyring::Alice.to_account_id();
+	let call_parameters = hex_string(&alice_id.encode());
+	let result: MethodResult = api
+		.call(
+			"archive_unstable_call",
+			[&genesis_hash, "AccountNonceApi_account_nonce", &call_parameters],
+		)
+		.await
+		.unwrap();
+	assert_eq!(result, MethodResult::ok("0x0000000000000000"));
+}
+
+#[tokio::test]
+async fn archive_storage() {
+	let (mut client, api) = setup_api();
+
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder.push_storage_change(KEY.to_vec(), Some(VALUE.to_vec())).unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	let key = hex_string(&KEY);
+	let query = |query_type| PaginatedStorageQuery {
+		key: key.clone(),
+		query_type,
+		pagination_start_key: None,
+	};
+	let items = vec![
+		query(StorageQueryType::Value),
+		query(StorageQueryType::Exists),
+		query(StorageQueryType::ValueLength),
+		query(StorageQueryType::Hash),
+	];
+	let result: ArchiveStorageResult = api
+		.call("archive_unstable_storage", rpc_params![&block_hash, items])
+		.await
+		.unwrap();
+
+	// The items above the limit are discarded.
+	assert_eq!(result.discarded_items, 1);
+	assert_eq!(result.continuation_token, None);
+	let results: Vec<_> = result.result.into_iter().map(|item| item.result).collect();
+	assert_eq!(
+		results,
+		vec![
+			StorageResultType::Value(hex_string(&VALUE)),
+			StorageResultType::Exists(true),
+			StorageResultType::ValueLength(VALUE.len() as u32),
+		]
+	);
+
+	// The ancestor values are not provided.
+	let items = vec![query(StorageQueryType::AncestorValues { ancestors: 1 })];
+	let err = api
+		.call::<_, serde_json::Value>("archive_unstable_storage", rpc_params![&block_hash, items])
+		.await
+		.unwrap_err();
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == 6001
+	);
+}
+
+#[tokio::test]
+async fn archive_storage_descendants_continue() {
+	let (mut client, api) = setup_api();
+
+	let mut builder = client.new_block(Default::default()).unwrap();
+	for key in [b":AA01", b":AA02", b":AA03", b":AA04", b":AA05"] {
+		builder.push_storage_change(key.to_vec(), Some(VALUE.to_vec())).unwrap();
+	}
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	let items = vec![
+		PaginatedStorageQuery {
+			key: hex_string(b":AA"),
+			query_type: StorageQueryType::DescendantsValues,
+			pagination_start_key: None,
+		},
+		PaginatedStorageQuery {
+			key: hex_string(&KEY),
+			query_type: StorageQueryType::Value,
+			pagination_start_key: None,
+		},
+	];
+	let result: ArchiveStorageResult = api
+		.call("archive_unstable_storage", rpc_params![&block_hash, items])
+		.await
+		.unwrap();
+
+	// The query is truncated and the following items are discarded.
+	let keys: Vec<_> = result.result.iter().map(|item| item.key.clone()).collect();
+	assert_eq!(keys, vec![hex_string(b":AA01"), hex_string(b":AA02")]);
+	assert_eq!(result.discarded_items, 1);
+	let token = result.continuation_token.unwrap();
+
+	let result: ArchiveStorageResult =
+		api.call("archive_unstable_storageContinue", [&token]).await.unwrap();
+	let keys: Vec<_> = result.result.iter().map(|item| item.key.clone()).collect();
+	assert_eq!(keys, vec![hex_string(b":AA03"), hex_string(b":AA04")]);
+	assert_eq!(result.continuation_token.as_ref(), Some(&token));
+
+	let result: ArchiveStorageResult =
+		api.call("archive_unstable_storageContinue", [&token]).await.unwrap();
+	let keys: Vec<_> = result.result.iter().map(|item| item.key.clone()).collect();
+	assert_eq!(keys, vec![hex_string(b":AA05")]);
+	assert_eq!(result.continuation_token, None);
+
+	// The completed query can not be continued.
+	let err = api
+		.call::<_, serde_json::Value>("archive_unstable_storageContinue", [&token])
+		.await
+		.unwrap_err();
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == 6002 && err.message() == "Invalid continuation token"
+	);
+
+	// The query is resumed after the pagination start key.
+	let items = vec![PaginatedStorageQuery {
+		key: hex_string(b":AA"),
+		query_type: StorageQueryType::DescendantsHashes,
+		pagination_start_key: Some(hex_string(b":AA03")),
+	}];
+	let result: ArchiveStorageResult = api
+		.call("archive_unstable_storage", rpc_params![&block_hash, items])
+		.await
+		.unwrap();
+	let keys: Vec<_> = result.result.iter().map(|item| item.key.clone()).collect();
+	assert_eq!(keys, vec![hex_string(b":AA04"), hex_string(b":AA05")]);
+	assert_matches!(result.result[0].result, StorageResultType::Hash(_));
+	assert_eq!(result.continuation_token, None);
+}
+
+#[tokio::test]
+async fn archive_bandwidth_quota() {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let mut client = Arc::new(builder.build());
+
+	let api = Archive::new(
+		client.clone(),
+		backend,
+		CHAIN_GENESIS,
+		ArchiveConfig {
+			bandwidth_quota: Some(crate::chain_head::BandwidthQuota {
+				max_bytes: 1,
+				period: std::time::Duration::from_secs(60 * 60),
+			}),
+			..Default::default()
+		},
+	)
+	.into_rpc();
+
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	// The header exhausts the quota.
+	let _header: String = api.call("archive_unstable_header", [&block_hash]).await.unwrap();
+
+	let err = api
+		.call::<_, serde_json::Value>("archive_unstable_body", [&block_hash])
+		.await
+		.unwrap_err();
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == 6004
+	);
+	let err = api
+		.call::<_, serde_json::Value>("archive_unstable_storageContinue", ["0x00"])
+		.await
+		.unwrap_err();
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == 6004
+	);
+
+	// The methods that serve no block data are not limited.
+	let genesis: String =
+		api.call("archive_unstable_genesisHash", EmptyParams::new()).await.unwrap();
+	assert_eq!(genesis, hex_string(&CHAIN_GENESIS));
+}
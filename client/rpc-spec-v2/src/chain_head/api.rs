@@ -19,13 +19,42 @@
 #![allow(non_snake_case)]
 
 //! API trait of the chain head.
-use crate::chain_head::event::{FollowEvent, MethodResponse, StorageQuery};
+use crate::chain_head::{
+	event::{CallOptions, FollowEvent, MethodResponse, PinnedBlock, StorageOptions, StorageQuery},
+	health::HealthReport,
+};
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use sp_rpc::list::ListOrValue;
 
 #[rpc(client, server)]
 pub trait ChainHeadApi<Hash> {
 	/// Track the state of the head of the chain: the finalized, non-finalized, and best blocks.
 	///
+	/// If `finalized_history` is provided, up to that many finalized blocks preceding the
+	/// current finalized block are reported by the `initialized` event. The history is
+	/// limited to the blocks whose state is still available on the node.
+	///
+	/// If `with_header` is set, the `newBlock` events include the SCALE-encoded header
+	/// and the number of extrinsics of the reported block.
+	///
+	/// If `catch_up_from` is provided, the `initialized` event reports this finalized block
+	/// instead of the current finalized block. The blocks finalized since are reported by
+	/// `newBlock` events followed by a `finalized` event, before the live events. The block
+	/// must be a finalized block whose state is still available on the node, otherwise the
+	/// subscription is stopped. Every block of the gap is pinned by the subscription: the
+	/// subscription is rejected if the gap and the finalized history exceed the pinned blocks
+	/// of a subscription, 512 by default.
+	///
+	/// If `resumption_token` is provided, the subscription resumes the disconnected
+	/// subscription whose `initialized` event reported this token, and keeps its pinned
+	/// blocks. The subscription is stopped if the token is invalid or expired.
+	///
+	/// If `finalized_only` is set, the `newBlock` and `bestBlockChanged` events are not
+	/// reported. The blocks are pinned once reported by the `finalized` events, the blocks
+	/// pruned before they are finalized are neither pinned nor reported. The runtime updates
+	/// are reported by the `newBlock` events only, such that the subscription is rejected if
+	/// `with_runtime` is also set.
+	///
 	/// # Unstable
 	///
 	/// This method is unstable and subject to change in the future.
@@ -34,7 +63,15 @@ pub trait ChainHeadApi<Hash> {
 		unsubscribe = "chainHead_unstable_unfollow",
 		item = FollowEvent<Hash>,
 	)]
-	fn chain_head_unstable_follow(&self, with_runtime: bool);
+	fn chain_head_unstable_follow(
+		&self,
+		with_runtime: bool,
+		finalized_history: Option<u32>,
+		with_header: Option<bool>,
+		catch_up_from: Option<Hash>,
+		resumption_token: Option<String>,
+		finalized_only: Option<bool>,
+	);
 
 	/// Retrieves the body (list of transactions) of a pinned block.
 	///
@@ -44,6 +81,9 @@ pub trait ChainHeadApi<Hash> {
 	///
 	/// Use `archive_unstable_body` if instead you want to retrieve the body of an arbitrary block.
 	///
+	/// The body of a large block may be reported in chunks by `operationBodyItems` events,
+	/// followed by the `operationBodyDone` event carrying the last chunk.
+	///
 	/// # Unstable
 	///
 	/// This method is unstable and subject to change in the future.
@@ -73,6 +113,21 @@ pub trait ChainHeadApi<Hash> {
 		hash: Hash,
 	) -> RpcResult<Option<String>>;
 
+	/// Retrieves the headers of multiple pinned blocks.
+	///
+	/// The headers are returned in the order of the provided hashes, with the
+	/// same semantics as the `chainHead_unstable_header` method for each hash.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[method(name = "chainHead_unstable_headers", blocking)]
+	fn chain_head_unstable_headers(
+		&self,
+		follow_subscription: String,
+		hashes: Vec<Hash>,
+	) -> RpcResult<Vec<Option<String>>>;
+
 	/// Get the chain's genesis hash.
 	///
 	/// # Unstable
@@ -83,6 +138,9 @@ pub trait ChainHeadApi<Hash> {
 
 	/// Returns storage entries at a specific block's state.
 	///
+	/// The optional `storage_options` allow reporting the read proof of the items, such
+	/// that the results can be verified against the state root of the block.
+	///
 	/// # Unstable
 	///
 	/// This method is unstable and subject to change in the future.
@@ -93,10 +151,17 @@ pub trait ChainHeadApi<Hash> {
 		hash: Hash,
 		items: Vec<StorageQuery<String>>,
 		child_trie: Option<String>,
+		storage_options: Option<StorageOptions>,
 	) -> RpcResult<MethodResponse>;
 
 	/// Call into the Runtime API at a specified block's state.
 	///
+	/// The optional `call_options` allow reporting the execution statistics
+	/// of the call, such as the execution time, the recorded proof size and
+	/// the benchmarked weight of the extrinsics applied by the call, and executing the call in an on-chain context instead of the context
+	/// configured by the node. The output of the call can also be decoded as
+	/// JSON, using the runtime metadata at the given block.
+	///
 	/// # Unstable
 	///
 	/// This method is unstable and subject to change in the future.
@@ -107,18 +172,59 @@ pub trait ChainHeadApi<Hash> {
 		hash: Hash,
 		function: String,
 		call_parameters: String,
+		call_options: Option<CallOptions>,
 	) -> RpcResult<MethodResponse>;
 
-	/// Unpin a block reported by the `follow` method.
+	/// Unpin a block or a list of blocks reported by the `follow` method.
 	///
-	/// Ongoing operations that require the provided block
+	/// Ongoing operations that require the provided blocks
 	/// will continue normally.
 	///
+	/// No block is unpinned if any of the provided blocks is not pinned
+	/// by the subscription.
+	///
 	/// # Unstable
 	///
 	/// This method is unstable and subject to change in the future.
 	#[method(name = "chainHead_unstable_unpin", blocking)]
-	fn chain_head_unstable_unpin(&self, follow_subscription: String, hash: Hash) -> RpcResult<()>;
+	fn chain_head_unstable_unpin(
+		&self,
+		follow_subscription: String,
+		hash_or_hashes: ListOrValue<Hash>,
+	) -> RpcResult<()>;
+
+	/// Restrict the new blocks reported by the `follow` method to the descendants
+	/// of the provided pinned block.
+	///
+	/// The blocks outside of the selected fork are reported once they are finalized.
+	/// Calling this method without a block removes the restriction and reports the
+	/// blocks that were omitted.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[method(name = "chainHead_unstable_setForkFilter", blocking)]
+	fn chain_head_unstable_set_fork_filter(
+		&self,
+		follow_subscription: String,
+		hash: Option<Hash>,
+	) -> RpcResult<()>;
+
+	/// List the blocks currently pinned by the `follow` subscription, ordered from the
+	/// oldest pin, together with the time elapsed since they were pinned.
+	///
+	/// This allows a client to reconcile its view of the pinned blocks with the server
+	/// after it lost track of them (ie the client restarted or missed an event).
+	/// The list is empty if the subscription is not present.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[method(name = "chainHead_unstable_pinnedBlocks", blocking)]
+	fn chain_head_unstable_pinned_blocks(
+		&self,
+		follow_subscription: String,
+	) -> RpcResult<Vec<PinnedBlock<Hash>>>;
 
 	/// Resumes a storage fetch started with `chainHead_storage` after it has generated an
 	/// `operationWaitingForContinue` event.
@@ -146,4 +252,15 @@ pub trait ChainHeadApi<Hash> {
 		follow_subscription: String,
 		operation_id: String,
 	) -> RpcResult<()>;
+
+	/// Report the health of the `chainHead` subsystem of the node.
+	///
+	/// Returns an error carrying the report if the subsystem is unhealthy, such that the
+	/// `GET /health/chain-head` endpoint of the HTTP server responds with a failure status.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[method(name = "chainHead_unstable_health", blocking)]
+	fn chain_head_unstable_health(&self) -> RpcResult<HealthReport>;
 }
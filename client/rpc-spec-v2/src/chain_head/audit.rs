@@ -0,0 +1,197 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Audit of the completed `chainHead` operations.
+//!
+//! Every completed `chainHead_body`, `chainHead_call` and `chainHead_storage` operation is
+//! reported to the configured [`AuditSink`], such that the usage of the node can be metered
+//! per tenant.
+
+use crate::chain_head::chain_head::LOG_TARGET;
+use parking_lot::Mutex;
+use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver, TracingUnboundedSender};
+use std::{
+	fs::{File, OpenOptions},
+	io::{self, LineWriter, Write},
+	path::Path,
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+/// The audit record of a completed operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationRecord {
+	/// The name of the method that started the operation.
+	pub method: &'static str,
+	/// The tenant of the RPC server that served the operation, if any.
+	pub tenant: Option<String>,
+	/// The number of operation units reserved by the operation.
+	///
+	/// This is one for `chainHead_body` and `chainHead_call`, and the number of
+	/// queried items for `chainHead_storage`.
+	pub cost_units: usize,
+	/// The number of bytes of the results served by the operation.
+	pub bytes: u64,
+	/// The duration from the start to the completion of the operation.
+	pub duration: Duration,
+}
+
+impl OperationRecord {
+	/// The JSON representation of the record.
+	pub fn to_json(&self) -> serde_json::Value {
+		serde_json::json!({
+			"method": self.method,
+			"tenant": self.tenant,
+			"costUnits": self.cost_units,
+			"bytes": self.bytes,
+			"durationMicros": self.duration.as_micros() as u64,
+		})
+	}
+}
+
+/// A sink of the audit records of the completed operations.
+///
+/// The sink is called from the tasks serving the operations and must not block.
+pub trait AuditSink: Send + Sync + std::fmt::Debug {
+	/// Record a completed operation.
+	fn record(&self, record: OperationRecord);
+}
+
+/// Append the audit records as JSON lines to a file.
+#[derive(Debug)]
+pub struct FileAuditSink {
+	/// The file of the records.
+	file: Mutex<LineWriter<File>>,
+}
+
+impl FileAuditSink {
+	/// Open the file at the given path, creating it if it does not exist.
+	pub fn open(path: &Path) -> io::Result<Self> {
+		let file = OpenOptions::new().create(true).append(true).open(path)?;
+		Ok(Self { file: Mutex::new(LineWriter::new(file)) })
+	}
+}
+
+impl AuditSink for FileAuditSink {
+	fn record(&self, record: OperationRecord) {
+		if let Err(err) = writeln!(self.file.lock(), "{}", record.to_json()) {
+			log::warn!(target: LOG_TARGET, "Failed to write the audit record: {}", err);
+		}
+	}
+}
+
+/// Submit the audit records to a channel.
+#[derive(Debug, Clone)]
+pub struct ChannelAuditSink {
+	/// The sending side of the channel.
+	sender: TracingUnboundedSender<OperationRecord>,
+}
+
+impl ChannelAuditSink {
+	/// Construct a new [`ChannelAuditSink`] and the receiving side of its channel.
+	pub fn new() -> (Self, TracingUnboundedReceiver<OperationRecord>) {
+		let (sender, receiver) = tracing_unbounded("mpsc_chain_head_audit", 100_000);
+		(Self { sender }, receiver)
+	}
+}
+
+impl AuditSink for ChannelAuditSink {
+	fn record(&self, record: OperationRecord) {
+		// The records are dropped once the receiver is closed.
+		let _ = self.sender.unbounded_send(record);
+	}
+}
+
+/// Cheaply cloneable handle to the optional audit sink.
+#[derive(Debug, Clone, Default)]
+pub struct AuditLink {
+	/// The audit sink.
+	sink: Option<Arc<dyn AuditSink>>,
+	/// The tenant reported by the records.
+	tenant: Option<String>,
+}
+
+impl AuditLink {
+	/// Construct a new [`AuditLink`].
+	pub fn new(sink: Option<Arc<dyn AuditSink>>, tenant: Option<String>) -> Self {
+		Self { sink, tenant }
+	}
+
+	/// Record the operation started at the given instant, if the sink is configured.
+	pub fn record(&self, method: &'static str, cost_units: usize, bytes: u64, started: Instant) {
+		if let Some(sink) = self.sink.as_ref() {
+			sink.record(OperationRecord {
+				method,
+				tenant: self.tenant.clone(),
+				cost_units,
+				bytes,
+				duration: started.elapsed(),
+			});
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn file_sink_appends_json_lines() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("audit.jsonl");
+
+		let record = OperationRecord {
+			method: "chainHead_unstable_body",
+			tenant: Some("public".into()),
+			cost_units: 1,
+			bytes: 42,
+			duration: Duration::from_micros(7),
+		};
+		FileAuditSink::open(&path).unwrap().record(record.clone());
+		// The records of a reopened file are appended.
+		FileAuditSink::open(&path).unwrap().record(record.clone());
+
+		let content = std::fs::read_to_string(&path).unwrap();
+		let lines = content
+			.lines()
+			.map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+			.collect::<Vec<_>>();
+		let expected = serde_json::json!({
+			"method": "chainHead_unstable_body",
+			"tenant": "public",
+			"costUnits": 1,
+			"bytes": 42,
+			"durationMicros": 7,
+		});
+		assert_eq!(lines, vec![expected.clone(), expected]);
+		assert_eq!(record.to_json(), lines[0]);
+	}
+
+	#[test]
+	fn channel_sink_submits_records() {
+		let (sink, mut receiver) = ChannelAuditSink::new();
+		let link = AuditLink::new(Some(Arc::new(sink)), None);
+
+		link.record("chainHead_unstable_call", 1, 10, Instant::now());
+
+		let record = receiver.try_recv().unwrap();
+		assert_eq!(record.method, "chainHead_unstable_call");
+		assert_eq!(record.tenant, None);
+		assert_eq!(record.bytes, 10);
+	}
+}
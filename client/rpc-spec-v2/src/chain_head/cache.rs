@@ -0,0 +1,258 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Short lived cache of the `chainHead` operation results.
+//!
+//! Popular blocks are queried with identical parameters by many clients at once.
+//! The results of such operations are kept for a short period of time, such that
+//! back-to-back requests are served without touching the backend or the executor.
+
+use crate::chain_head::{event::StorageResult, metrics::MetricsLink};
+use codec::Encode;
+use parking_lot::Mutex;
+use std::{
+	collections::HashMap,
+	hash::Hash as StdHash,
+	time::{Duration, Instant},
+};
+
+/// The kind of operation that produced the cached result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OperationKind {
+	/// The result of `chainHead_body`.
+	Body,
+	/// The result of `chainHead_call`.
+	Call,
+	/// The result of a single `value` or `hash` item of `chainHead_storage`.
+	Storage,
+}
+
+/// The key of a cached operation result.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey<Hash> {
+	/// The block at which the operation was executed.
+	hash: Hash,
+	/// The kind of the operation.
+	kind: OperationKind,
+	/// Blake2 digest of the SCALE-encoded operation parameters.
+	params_digest: [u8; 32],
+}
+
+impl<Hash> CacheKey<Hash> {
+	/// Construct a new [`CacheKey`] from the given operation parameters.
+	pub fn new<Params: Encode>(hash: Hash, kind: OperationKind, params: &Params) -> Self {
+		let params_digest = sp_core::blake2_256(&(kind as u8, params).encode());
+		CacheKey { hash, kind, params_digest }
+	}
+}
+
+/// The cached result of an operation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CachedResult {
+	/// The hex-encoded extrinsics of the block.
+	Body(Vec<String>),
+	/// The hex-encoded output of the runtime call.
+	Call(String),
+	/// The result of a storage query.
+	Storage(Option<StorageResult>),
+}
+
+/// An entry of the cache.
+struct CacheEntry {
+	/// The cached result.
+	result: CachedResult,
+	/// The moment the result was inserted.
+	inserted_at: Instant,
+}
+
+/// The state of an enabled cache.
+struct CacheInner<Hash> {
+	/// The cached results.
+	entries: HashMap<CacheKey<Hash>, CacheEntry>,
+	/// The duration for which the results are considered valid.
+	ttl: Duration,
+	/// The maximum number of entries.
+	max_entries: usize,
+}
+
+impl<Hash: StdHash + Eq + Clone> CacheInner<Hash> {
+	/// Remove all entries that are older than the configured TTL.
+	fn remove_expired(&mut self, now: Instant) {
+		let ttl = self.ttl;
+		self.entries
+			.retain(|_, entry| now.saturating_duration_since(entry.inserted_at) < ttl);
+	}
+
+	/// Remove the oldest entry of the cache.
+	///
+	/// # Note
+	///
+	/// The cache is expected to be small and this iterates over all entries.
+	fn remove_oldest(&mut self) {
+		let oldest = self
+			.entries
+			.iter()
+			.min_by_key(|(_, entry)| entry.inserted_at)
+			.map(|(key, _)| key.clone());
+
+		if let Some(oldest) = oldest {
+			self.entries.remove(&oldest);
+		}
+	}
+}
+
+/// Time-to-live cache of the operation results, keyed by block hash,
+/// operation kind and parameters.
+///
+/// The entries of a block are invalidated when the block is unpinned.
+pub struct OperationCache<Hash> {
+	/// The cache state, `None` if the cache is disabled.
+	inner: Option<Mutex<CacheInner<Hash>>>,
+	/// Report cache hits and misses.
+	metrics: MetricsLink,
+}
+
+impl<Hash: StdHash + Eq + Clone> OperationCache<Hash> {
+	/// Construct a new [`OperationCache`].
+	///
+	/// The cache is disabled if `ttl` is not provided or `max_entries` is zero.
+	pub fn new(ttl: Option<Duration>, max_entries: usize, metrics: MetricsLink) -> Self {
+		let inner = ttl
+			.filter(|_| max_entries > 0)
+			.map(|ttl| Mutex::new(CacheInner { entries: HashMap::new(), ttl, max_entries }));
+
+		OperationCache { inner, metrics }
+	}
+
+	/// Returns true if the cache is enabled.
+	pub fn is_enabled(&self) -> bool {
+		self.inner.is_some()
+	}
+
+	/// Get the cached result of the operation, if it did not expire.
+	pub fn get(&self, key: &CacheKey<Hash>) -> Option<CachedResult> {
+		let inner = self.inner.as_ref()?;
+		let mut inner = inner.lock();
+
+		let ttl = inner.ttl;
+		let result = match inner.entries.get(key) {
+			Some(entry) if entry.inserted_at.elapsed() < ttl => Some(entry.result.clone()),
+			Some(_) => {
+				inner.entries.remove(key);
+				None
+			},
+			None => None,
+		};
+
+		if result.is_some() {
+			self.metrics.report(|metrics| metrics.operation_cache_hits.inc());
+		} else {
+			self.metrics.report(|metrics| metrics.operation_cache_misses.inc());
+		}
+
+		result
+	}
+
+	/// Cache the result of the operation.
+	pub fn insert(&self, key: CacheKey<Hash>, result: CachedResult) {
+		let Some(inner) = self.inner.as_ref() else { return };
+		let mut inner = inner.lock();
+
+		let now = Instant::now();
+		if inner.entries.len() >= inner.max_entries && !inner.entries.contains_key(&key) {
+			inner.remove_expired(now);
+
+			if inner.entries.len() >= inner.max_entries {
+				inner.remove_oldest();
+			}
+		}
+
+		inner.entries.insert(key, CacheEntry { result, inserted_at: now });
+	}
+
+	/// Remove all cached results of the given block.
+	pub fn invalidate_block(&self, hash: &Hash) {
+		let Some(inner) = self.inner.as_ref() else { return };
+		inner.lock().entries.retain(|key, _| &key.hash != hash);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn disabled_cache_does_not_store() {
+		let cache = OperationCache::new(None, 16, MetricsLink::default());
+		assert!(!cache.is_enabled());
+
+		let key = CacheKey::new(1u64, OperationKind::Body, &());
+		cache.insert(key.clone(), CachedResult::Body(vec!["0x00".into()]));
+		assert_eq!(cache.get(&key), None);
+	}
+
+	#[test]
+	fn cache_hit_and_invalidate() {
+		let cache = OperationCache::new(Some(Duration::from_secs(60)), 16, MetricsLink::default());
+
+		let key = CacheKey::new(1u64, OperationKind::Call, &("Core_version", vec![0u8]));
+		let other_params = CacheKey::new(1u64, OperationKind::Call, &("Core_version", vec![1u8]));
+		let other_block = CacheKey::new(2u64, OperationKind::Call, &("Core_version", vec![0u8]));
+
+		cache.insert(key.clone(), CachedResult::Call("0x01".into()));
+		cache.insert(other_block.clone(), CachedResult::Call("0x02".into()));
+
+		assert_eq!(cache.get(&key), Some(CachedResult::Call("0x01".into())));
+		assert_eq!(cache.get(&other_params), None);
+		assert_eq!(cache.get(&other_block), Some(CachedResult::Call("0x02".into())));
+
+		// Unpinning the block removes only its entries.
+		cache.invalidate_block(&1u64);
+		assert_eq!(cache.get(&key), None);
+		assert_eq!(cache.get(&other_block), Some(CachedResult::Call("0x02".into())));
+	}
+
+	#[test]
+	fn cache_entries_expire() {
+		let cache = OperationCache::new(Some(Duration::from_millis(1)), 16, MetricsLink::default());
+
+		let key = CacheKey::new(1u64, OperationKind::Body, &());
+		cache.insert(key.clone(), CachedResult::Body(vec![]));
+		std::thread::sleep(Duration::from_millis(5));
+		assert_eq!(cache.get(&key), None);
+	}
+
+	#[test]
+	fn cache_evicts_oldest_entry() {
+		let cache = OperationCache::new(Some(Duration::from_secs(60)), 2, MetricsLink::default());
+
+		let first = CacheKey::new(1u64, OperationKind::Body, &());
+		let second = CacheKey::new(2u64, OperationKind::Body, &());
+		let third = CacheKey::new(3u64, OperationKind::Body, &());
+
+		cache.insert(first.clone(), CachedResult::Body(vec![]));
+		std::thread::sleep(Duration::from_millis(1));
+		cache.insert(second.clone(), CachedResult::Body(vec![]));
+		std::thread::sleep(Duration::from_millis(1));
+		cache.insert(third.clone(), CachedResult::Body(vec![]));
+
+		assert_eq!(cache.get(&first), None);
+		assert!(cache.get(&second).is_some());
+		assert!(cache.get(&third).is_some());
+	}
+}
@@ -20,21 +20,38 @@
 
 use super::{
 	chain_head_storage::ChainHeadStorage,
-	event::{MethodResponseStarted, OperationBodyDone, OperationCallDone},
+	event::{
+		CallContextType, CallOptions, CallStats, CallWeight, MethodResponseStarted,
+		OperationBodyDone, OperationBodyItems, OperationCallDone,
+	},
 };
 use crate::{
 	chain_head::{
 		api::ChainHeadApiServer,
-		chain_head_follow::ChainHeadFollower,
+		audit::{AuditLink, AuditSink},
+		cache::{CacheKey, CachedResult, OperationCache, OperationKind},
+		chain_head_follow::{response_size, ChainHeadFollower, NotificationSource},
+		coalesce::InFlightOperations,
+		decode::CallOutputDecoder,
 		error::Error as ChainHeadRpcError,
-		event::{FollowEvent, MethodResponse, OperationError, StorageQuery, StorageQueryType},
+		event::{
+			FollowEvent, MethodResponse, OperationError, OperationId, PinnedBlock, StorageOptions,
+			StorageQuery, StorageResult,
+		},
+		health::{ChainHeadHealth, HealthReport},
 		hex_string,
-		subscription::{SubscriptionManagement, SubscriptionManagementError},
+		metrics::MetricsLink,
+		pool::OperationPool,
+		retry::{BackendRetry, RetryPolicy},
+		subscription::{
+			AgeEviction, BandwidthQuota, FollowSubscriptionPins, PinEvictionPolicy, PinStore,
+			SubscriptionManagement, SubscriptionManagementError,
+		},
 	},
 	SubscriptionTaskExecutor,
 };
-use codec::Encode;
-use futures::future::FutureExt;
+use codec::{Compact, Decode, Encode};
+use futures::{channel::oneshot, future::FutureExt};
 use jsonrpsee::{
 	core::{async_trait, RpcResult},
 	types::{SubscriptionEmptyError, SubscriptionId, SubscriptionResult},
@@ -42,14 +59,23 @@ use jsonrpsee::{
 };
 use log::debug;
 use sc_client_api::{
-	Backend, BlockBackend, BlockchainEvents, CallExecutor, ChildInfo, ExecutorProvider, StorageKey,
+	Backend, BlockBackend, CallExecutor, ChildInfo, ExecutorProvider, ProofProvider, StorageKey,
 	StorageProvider,
 };
 use sp_api::CallApiAt;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
-use sp_core::{traits::CallContext, Bytes};
-use sp_runtime::traits::Block as BlockT;
-use std::{marker::PhantomData, sync::Arc, time::Duration};
+use sp_consensus::SyncOracle;
+use sp_core::{hashing::blake2_64, traits::CallContext, Bytes};
+use sp_rpc::list::ListOrValue;
+use sp_runtime::traits::{Block as BlockT, Saturating, UniqueSaturatedInto};
+use std::{
+	marker::PhantomData,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc,
+	},
+	time::{Duration, Instant},
+};
 
 pub(crate) const LOG_TARGET: &str = "rpc-spec-v2";
 
@@ -57,13 +83,115 @@ pub(crate) const LOG_TARGET: &str = "rpc-spec-v2";
 pub struct ChainHeadConfig {
 	/// The maximum number of pinned blocks across all subscriptions.
 	pub global_max_pinned_blocks: usize,
+	/// The maximum number of pinned blocks per subscription.
+	///
+	/// A subscription exceeding this limit is stopped, while the other subscriptions
+	/// keep their pinned blocks.
+	pub subscription_max_pinned_blocks: usize,
+	/// The maximum number of running `chainHead_follow` subscriptions.
+	///
+	/// The subscriptions started once the limit is reached are rejected. The subscriptions
+	/// whose client disconnected within the resumption grace period do not count, their
+	/// pinned blocks still count toward `global_max_pinned_blocks`. This limit is not per
+	/// connection: jsonrpsee does not expose the connection of a subscription to the method
+	/// handlers. The subscriptions are not limited if this is `None`.
+	pub max_follow_subscriptions: Option<usize>,
 	/// The maximum duration that a block is allowed to be pinned per subscription.
+	///
+	/// This is used by the default eviction policy, unless another
+	/// policy is provided.
 	pub subscription_max_pinned_duration: Duration,
+	/// Select the subscriptions stopped when the global limit of pinned blocks is reached.
+	///
+	/// The subscriptions exceeding the `subscription_max_pinned_duration` are stopped
+	/// if this is `None`.
+	pub subscription_pin_eviction: Option<Arc<dyn PinEvictionPolicy>>,
 	/// The maximum number of ongoing operations per subscription.
 	pub subscription_max_ongoing_operations: usize,
 	/// The maximum number of items reported by the `chainHead_storage` before
 	/// pagination is required.
 	pub operation_max_storage_items: usize,
+	/// The maximum duration of a `chainHead_storage` operation, including the time spent
+	/// waiting for `chainHead_continue`.
+	///
+	/// The deadline is checked between the reads of the storage items and while waiting for
+	/// `chainHead_continue`; the operation is then stopped and reported by the
+	/// `operationTimeout` event. The operations are not subject to a timeout if this is `None`.
+	pub operation_storage_timeout: Option<Duration>,
+	/// The maximum duration of a `chainHead_call` operation.
+	///
+	/// A runtime call exceeding the timeout is reported by the `operationTimeout` event. The
+	/// runtime call itself cannot be interrupted: it holds its block and operation slot until
+	/// it completes, and its result is discarded. No timeout if this is `None`.
+	pub operation_call_timeout: Option<Duration>,
+	/// The maximum duration of a `chainHead_body` operation.
+	///
+	/// The read of the body cannot be interrupted: a read exceeding the timeout is reported by
+	/// the `operationTimeout` event in place of the body. No timeout if this is `None`.
+	pub operation_body_timeout: Option<Duration>,
+	/// The maximum number of bytes of the hexadecimal encoded extrinsics reported by a
+	/// single `chainHead_body` event.
+	///
+	/// A larger body is reported by `operationBodyItems` events followed by the
+	/// `operationBodyDone` event. An extrinsic larger than the limit is reported alone.
+	/// The body is reported by a single event if this is `None`.
+	pub operation_max_body_chunk_size: Option<usize>,
+	/// The duration for which the results of identical operations are cached.
+	///
+	/// The operation cache is disabled if this is `None`.
+	pub operation_cache_ttl: Option<Duration>,
+	/// The maximum number of operation results held by the operation cache.
+	pub operation_cache_max_entries: usize,
+	/// Coalesce the operations started while an identical operation of the same
+	/// subscription is in progress, instead of executing the work twice.
+	pub operation_coalescing: bool,
+	/// The dedicated pool executing the `chainHead_storage` and `chainHead_call` operations.
+	///
+	/// The runtime calls queue up once all the threads are busy, such that they cannot
+	/// starve the delivery of the `chainHead_follow` events. The pool is meant to be shared
+	/// by the instances of all the RPC servers. The operations are executed by the executor
+	/// of the RPC server if this is `None`.
+	pub operation_pool: Option<Arc<OperationPool>>,
+	/// The quota of bytes served per subscription by the `chainHead` methods.
+	///
+	/// Once the quota is exhausted, the methods of the subscription return `limitReached`
+	/// until the next period. The bandwidth is not limited if this is `None`.
+	///
+	/// The quota is not shared by the subscriptions of a connection, see [`BandwidthQuota`].
+	pub subscription_bandwidth_quota: Option<BandwidthQuota>,
+	/// The maximum number of finalized ancestors reported by the `Initialized` event of a
+	/// subscription that requested the finalized history.
+	pub subscription_max_finalized_history: usize,
+	/// The duration for which the pinned blocks of a disconnected subscription are kept.
+	///
+	/// The `Initialized` event reports a resumption token, which lets a reconnecting client
+	/// resume the subscription within this period. The subscriptions are not resumable if
+	/// this is `None`.
+	pub subscription_resume_grace_period: Option<Duration>,
+	/// The sink of the audit records of the completed operations.
+	///
+	/// The operations are not audited if this is `None`.
+	pub operation_audit_sink: Option<Arc<dyn AuditSink>>,
+	/// The tenant reported by the audit records, ie the quality of service tier.
+	pub operation_audit_tenant: Option<String>,
+	/// The health of the `chainHead` subsystem, shared by all the instances of the node.
+	pub health: ChainHeadHealth,
+	/// The retry policy of the backend reads failing with a transient error.
+	pub operation_retry_policy: RetryPolicy,
+	/// The context of the runtime calls of the `chainHead_call` method, unless the call
+	/// requests another context.
+	pub call_context: CallContext,
+	/// The `chainHead` metrics.
+	///
+	/// The metrics are registered once and shared by the RPC modules of all the servers,
+	/// see [`MetricsLink::new`]. No metrics are reported by default.
+	pub metrics: MetricsLink,
+	/// The oracle reporting the major sync of the node.
+	///
+	/// The block events of the subscriptions are paused during a major sync and summarized
+	/// by the `catchUp` event once the sync completes. The events are never paused if this
+	/// is `None`.
+	pub sync_oracle: Option<Arc<dyn SyncOracle + Send + Sync>>,
 }
 
 /// Maximum pinned blocks across all connections.
@@ -71,6 +199,11 @@ pub struct ChainHeadConfig {
 /// Note: This should never exceed the `PINNING_CACHE_SIZE` from client/db.
 const MAX_PINNED_BLOCKS: usize = 512;
 
+/// Maximum pinned blocks per subscription.
+/// Note: The subscriptions exceeding the pinned blocks of all connections together are
+/// stopped by the eviction policy.
+const MAX_PINNED_BLOCKS_PER_SUBSCRIPTION: usize = MAX_PINNED_BLOCKS;
+
 /// Any block of any subscription should not be pinned more than
 /// this constant. When a subscription contains a block older than this,
 /// the subscription becomes subject to termination.
@@ -85,13 +218,41 @@ const MAX_ONGOING_OPERATIONS: usize = 16;
 /// before paginations is required.
 const MAX_STORAGE_ITER_ITEMS: usize = 5;
 
+/// The maximum number of operation results held by the operation cache.
+const MAX_OPERATION_CACHE_ENTRIES: usize = 256;
+
+/// The maximum number of finalized ancestors reported when a subscription starts.
+/// Note: The reported blocks are pinned and count towards the pinned blocks limit.
+const MAX_FINALIZED_HISTORY: usize = 16;
+
 impl Default for ChainHeadConfig {
 	fn default() -> Self {
 		ChainHeadConfig {
 			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
+			subscription_max_pinned_blocks: MAX_PINNED_BLOCKS_PER_SUBSCRIPTION,
+			max_follow_subscriptions: None,
 			subscription_max_pinned_duration: MAX_PINNED_DURATION,
+			subscription_pin_eviction: None,
 			subscription_max_ongoing_operations: MAX_ONGOING_OPERATIONS,
 			operation_max_storage_items: MAX_STORAGE_ITER_ITEMS,
+			operation_storage_timeout: None,
+			operation_call_timeout: None,
+			operation_body_timeout: None,
+			operation_max_body_chunk_size: None,
+			operation_cache_ttl: None,
+			operation_cache_max_entries: MAX_OPERATION_CACHE_ENTRIES,
+			operation_coalescing: true,
+			operation_pool: None,
+			subscription_bandwidth_quota: None,
+			subscription_max_finalized_history: MAX_FINALIZED_HISTORY,
+			subscription_resume_grace_period: None,
+			operation_audit_sink: None,
+			operation_audit_tenant: None,
+			health: Default::default(),
+			operation_retry_policy: Default::default(),
+			call_context: CallContext::Offchain,
+			metrics: Default::default(),
+			sync_oracle: None,
 		}
 	}
 }
@@ -105,12 +266,54 @@ pub struct ChainHead<BE: Backend<Block>, Block: BlockT, Client> {
 	/// Executor to spawn subscriptions.
 	executor: SubscriptionTaskExecutor,
 	/// Keep track of the pinned blocks for each subscription.
-	subscriptions: Arc<SubscriptionManagement<Block, BE>>,
+	subscriptions: Arc<dyn PinStore<Block, BE>>,
+	/// The number of `chainHead_follow` subscriptions.
+	follow_subscriptions: Arc<AtomicUsize>,
+	/// The maximum number of `chainHead_follow` subscriptions, if limited.
+	max_follow_subscriptions: Option<usize>,
 	/// The hexadecimal encoded hash of the genesis block.
 	genesis_hash: String,
 	/// The maximum number of items reported by the `chainHead_storage` before
 	/// pagination is required.
 	operation_max_storage_items: usize,
+	/// The maximum duration of a `chainHead_storage` operation.
+	operation_storage_timeout: Option<Duration>,
+	/// The maximum duration of a `chainHead_call` operation.
+	operation_call_timeout: Option<Duration>,
+	/// The maximum duration of a `chainHead_body` operation.
+	operation_body_timeout: Option<Duration>,
+	/// The maximum number of bytes of the extrinsics reported by a `chainHead_body` event.
+	operation_max_body_chunk_size: Option<usize>,
+	/// Cache the results of identical operations.
+	cache: Arc<OperationCache<Block::Hash>>,
+	/// Coalesce the identical `chainHead_body` operations in progress.
+	in_flight_bodies: InFlightOperations<Block::Hash, Option<Vec<String>>>,
+	/// Coalesce the identical `chainHead_call` operations in progress.
+	in_flight_calls: Arc<InFlightOperations<Block::Hash, String>>,
+	/// Coalesce the identical `chainHead_storage` items in progress.
+	in_flight_storage: Arc<InFlightOperations<Block::Hash, Option<StorageResult>>>,
+	/// Decode the output of the `chainHead_call` operations.
+	call_decoder: Arc<CallOutputDecoder>,
+	/// The dedicated pool executing the `chainHead_storage` and `chainHead_call` operations.
+	operation_pool: Option<Arc<OperationPool>>,
+	/// Audit the completed operations.
+	audit: AuditLink,
+	/// The health of the `chainHead` subsystem.
+	health: ChainHeadHealth,
+	/// Report the operations and the `Stop` events.
+	metrics: MetricsLink,
+	/// Retry the backend reads failing with a transient error.
+	retry: BackendRetry,
+	/// The default context of the runtime calls.
+	call_context: CallContext,
+	/// The maximum number of finalized ancestors reported when a subscription starts.
+	subscription_max_finalized_history: usize,
+	/// The maximum number of blocks pinned by a subscription catching up.
+	subscription_max_pinned_blocks: usize,
+	/// The duration for which the pinned blocks of a disconnected subscription are kept.
+	subscription_resume_grace_period: Option<Duration>,
+	/// The oracle reporting the major sync of the node.
+	sync_oracle: Option<Arc<dyn SyncOracle + Send + Sync>>,
 	/// Phantom member to pin the block type.
 	_phantom: PhantomData<Block>,
 }
@@ -125,22 +328,98 @@ impl<BE: Backend<Block>, Block: BlockT, Client> ChainHead<BE, Block, Client> {
 		config: ChainHeadConfig,
 	) -> Self {
 		let genesis_hash = hex_string(&genesis_hash.as_ref());
+		let metrics = config.metrics;
+		let eviction = config
+			.subscription_pin_eviction
+			.unwrap_or_else(|| Arc::new(AgeEviction::new(config.subscription_max_pinned_duration)));
 		Self {
 			client,
 			backend: backend.clone(),
 			executor,
 			subscriptions: Arc::new(SubscriptionManagement::new(
 				config.global_max_pinned_blocks,
-				config.subscription_max_pinned_duration,
+				config.subscription_max_pinned_blocks,
+				eviction,
 				config.subscription_max_ongoing_operations,
+				config.subscription_bandwidth_quota,
 				backend,
+				metrics.clone(),
 			)),
+			follow_subscriptions: Default::default(),
+			max_follow_subscriptions: config.max_follow_subscriptions,
 			operation_max_storage_items: config.operation_max_storage_items,
+			operation_storage_timeout: config.operation_storage_timeout,
+			operation_call_timeout: config.operation_call_timeout,
+			operation_body_timeout: config.operation_body_timeout,
+			operation_max_body_chunk_size: config.operation_max_body_chunk_size,
+			cache: Arc::new(OperationCache::new(
+				config.operation_cache_ttl,
+				config.operation_cache_max_entries,
+				metrics.clone(),
+			)),
+			in_flight_bodies: InFlightOperations::new(config.operation_coalescing, metrics.clone()),
+			in_flight_calls: Arc::new(InFlightOperations::new(
+				config.operation_coalescing,
+				metrics.clone(),
+			)),
+			in_flight_storage: Arc::new(InFlightOperations::new(
+				config.operation_coalescing,
+				metrics.clone(),
+			)),
+			call_decoder: Default::default(),
+			operation_pool: config.operation_pool,
+			retry: BackendRetry::new(config.operation_retry_policy, metrics.clone()),
+			call_context: config.call_context,
+			audit: AuditLink::new(config.operation_audit_sink, config.operation_audit_tenant),
+			health: config.health,
+			metrics,
+			subscription_max_finalized_history: config.subscription_max_finalized_history,
+			subscription_max_pinned_blocks: config
+				.subscription_max_pinned_blocks
+				.min(config.global_max_pinned_blocks),
+			subscription_resume_grace_period: config.subscription_resume_grace_period,
+			sync_oracle: config.sync_oracle,
 			genesis_hash,
 			_phantom: PhantomData,
 		}
 	}
 
+	/// Take a slot of the limited `chainHead_follow` subscriptions.
+	///
+	/// Returns `None` if all the slots are taken.
+	fn acquire_follow_slot(&self) -> Option<FollowSubscriptionSlot> {
+		self.follow_subscriptions
+			.fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+				self.max_follow_subscriptions.map_or(true, |max| count < max).then(|| count + 1)
+			})
+			.ok()?;
+		Some(FollowSubscriptionSlot(self.follow_subscriptions.clone()))
+	}
+
+	/// Ensure the blocks pinned by a subscription catching up from `hash` do not exceed the
+	/// pinned blocks of a subscription.
+	///
+	/// The subscription pins the finalized history, the block it catches up from and every
+	/// block finalized since. The unknown blocks are left to the follower, which stops the
+	/// subscription.
+	fn check_catch_up_gap(
+		&self,
+		hash: Block::Hash,
+		finalized_history: usize,
+	) -> Result<(), ChainHeadRpcError> {
+		let Ok(Some(number)) = self.client.number(hash) else { return Ok(()) };
+		let finalized_number = self.client.info().finalized_number;
+		let gap: usize = finalized_number.saturating_sub(number).unique_saturated_into();
+		let pinned = gap.saturating_add(finalized_history).saturating_add(1);
+		if pinned > self.subscription_max_pinned_blocks {
+			return Err(ChainHeadRpcError::InvalidParam(format!(
+				"Catching up from block {:?} pins {} blocks, exceeding the limit of {}",
+				hash, pinned, self.subscription_max_pinned_blocks
+			)))
+		}
+		Ok(())
+	}
+
 	/// Accept the subscription and return the subscription ID on success.
 	fn accept_subscription(
 		&self,
@@ -164,6 +443,93 @@ impl<BE: Backend<Block>, Block: BlockT, Client> ChainHead<BE, Block, Client> {
 	}
 }
 
+/// A slot of the limited `chainHead_follow` subscriptions, released on drop.
+struct FollowSubscriptionSlot(Arc<AtomicUsize>);
+
+impl Drop for FollowSubscriptionSlot {
+	fn drop(&mut self) {
+		self.0.fetch_sub(1, Ordering::AcqRel);
+	}
+}
+
+impl<BE, Block, Client> ChainHead<BE, Block, Client>
+where
+	Block: BlockT + 'static,
+	BE: Backend<Block> + 'static,
+{
+	/// Access the blocks pinned by the `chainHead_follow` subscriptions of this instance.
+	///
+	/// This is used by the `transaction` methods to report and pin the inclusion
+	/// blocks of the watched transactions.
+	pub fn follow_subscription_pins(&self) -> Arc<dyn FollowSubscriptionPins<Block::Hash>> {
+		self.subscriptions.clone().into_follow_subscription_pins()
+	}
+
+	/// Access the store of the blocks pinned by the `chainHead_follow` subscriptions.
+	pub fn pin_store(&self) -> Arc<dyn PinStore<Block, BE>> {
+		self.subscriptions.clone()
+	}
+
+	/// Replace the store of the blocks pinned by the `chainHead_follow` subscriptions.
+	///
+	/// This must be called before the methods are exposed. The pins obtained via
+	/// [`Self::follow_subscription_pins`] before this call keep accessing the previous store.
+	pub fn with_pin_store(mut self, store: Arc<dyn PinStore<Block, BE>>) -> Self {
+		self.subscriptions = store;
+		self
+	}
+}
+
+/// The runtime function applying an extrinsic.
+const APPLY_EXTRINSIC: &str = "BlockBuilder_apply_extrinsic";
+
+/// The runtime API reporting the benchmarked weight of an extrinsic.
+const TRANSACTION_PAYMENT_API: &str = "TransactionPaymentApi";
+
+/// The runtime function reporting the benchmarked weight of an extrinsic.
+const QUERY_INFO: &str = "TransactionPaymentApi_query_info";
+
+/// The first version of `TransactionPaymentApi` reporting the two-dimensional weight.
+const TRANSACTION_PAYMENT_API_VERSION: u32 = 3;
+
+/// Query the benchmarked weight of the extrinsic applied by a `BlockBuilder_apply_extrinsic`
+/// call, using the `TransactionPaymentApi_query_info` runtime function.
+///
+/// Returns `None` for the other runtime functions, or if the weight is not available.
+fn benchmarked_weight<Block, Client>(
+	client: &Client,
+	hash: Block::Hash,
+	function: &str,
+	call_parameters: &[u8],
+) -> Option<CallWeight>
+where
+	Block: BlockT,
+	Client: CallApiAt<Block> + ExecutorProvider<Block>,
+{
+	if function != APPLY_EXTRINSIC {
+		return None
+	}
+
+	let version = client.runtime_version_at(hash).ok()?;
+	let api_version = version.api_version(&blake2_64(TRANSACTION_PAYMENT_API.as_bytes()))?;
+	if api_version < TRANSACTION_PAYMENT_API_VERSION {
+		return None
+	}
+
+	// The parameters of `query_info` are the extrinsic followed by its encoded length.
+	let mut parameters = call_parameters.to_vec();
+	(call_parameters.len() as u32).encode_to(&mut parameters);
+	let output = client
+		.executor()
+		.call(hash, QUERY_INFO, &parameters, CallContext::Offchain)
+		.ok()?;
+
+	// The `RuntimeDispatchInfo` starts with the weight of the extrinsic.
+	let (Compact(ref_time), Compact(proof_size)) =
+		<(Compact<u64>, Compact<u64>)>::decode(&mut &output[..]).ok()?;
+	Some(CallWeight { ref_time, proof_size })
+}
+
 /// Parse hex-encoded string parameter as raw bytes.
 ///
 /// If the parsing fails, returns an error propagated to the RPC method.
@@ -179,6 +545,28 @@ fn parse_hex_param(param: String) -> Result<Vec<u8>, ChainHeadRpcError> {
 	}
 }
 
+/// Split the hex-encoded extrinsics into chunks of at most `max_chunk_size` bytes.
+///
+/// A chunk holds at least one extrinsic, such that the order of the extrinsics is
+/// preserved. A single chunk is returned if the size is not limited.
+fn body_chunks(extrinsics: Vec<String>, max_chunk_size: Option<usize>) -> Vec<Vec<String>> {
+	let Some(max_chunk_size) = max_chunk_size else { return vec![extrinsics] };
+
+	let mut chunks = vec![];
+	let mut chunk = vec![];
+	let mut chunk_size = 0;
+	for extrinsic in extrinsics {
+		if !chunk.is_empty() && chunk_size + extrinsic.len() > max_chunk_size {
+			chunks.push(std::mem::take(&mut chunk));
+			chunk_size = 0;
+		}
+		chunk_size += extrinsic.len();
+		chunk.push(extrinsic);
+	}
+	chunks.push(chunk);
+	chunks
+}
+
 #[async_trait]
 impl<BE, Block, Client> ChainHeadApiServer<Block::Hash> for ChainHead<BE, Block, Client>
 where
@@ -189,16 +577,45 @@ where
 		+ ExecutorProvider<Block>
 		+ HeaderBackend<Block>
 		+ HeaderMetadata<Block, Error = BlockChainError>
-		+ BlockchainEvents<Block>
+		+ NotificationSource<Block>
 		+ CallApiAt<Block>
 		+ StorageProvider<Block, BE>
+		+ ProofProvider<Block>
 		+ 'static,
 {
 	fn chain_head_unstable_follow(
 		&self,
 		mut sink: SubscriptionSink,
 		with_runtime: bool,
+		finalized_history: Option<u32>,
+		with_header: Option<bool>,
+		catch_up_from: Option<Block::Hash>,
+		resumption_token: Option<String>,
+		finalized_only: Option<bool>,
 	) -> SubscriptionResult {
+		let finalized_history = finalized_history
+			.map_or(0, |history| history as usize)
+			.min(self.subscription_max_finalized_history);
+		// The runtime updates are reported by the `NewBlock` events only.
+		if with_runtime && finalized_only == Some(true) {
+			debug!(target: LOG_TARGET, "[follow] Runtime updates requested for finalized blocks");
+			let _ = sink.reject(ChainHeadRpcError::InvalidParam(
+				"The runtime updates flag cannot be set for the finalized blocks only".to_string(),
+			));
+			return Ok(())
+		}
+		if let Some(hash) = catch_up_from {
+			if let Err(err) = self.check_catch_up_gap(hash, finalized_history) {
+				debug!(target: LOG_TARGET, "[follow] Catch up rejected: {}", err);
+				let _ = sink.reject(err);
+				return Ok(())
+			}
+		}
+		let Some(slot) = self.acquire_follow_slot() else {
+			debug!(target: LOG_TARGET, "[follow] Too many follow subscriptions");
+			let _ = sink.reject(ChainHeadRpcError::TooManyFollowSubscriptions);
+			return Ok(())
+		};
 		let sub_id = match self.accept_subscription(&mut sink) {
 			Ok(sub_id) => sub_id,
 			Err(err) => {
@@ -206,12 +623,27 @@ where
 				return Err(err)
 			},
 		};
-		// Keep track of the subscription.
-		let Some(sub_data) = self.subscriptions.insert_subscription(sub_id.clone(), with_runtime)
-		else {
+		// Keep track of the subscription, or resume the subscription of the token.
+		let sub_data = match &resumption_token {
+			Some(token) =>
+				self.subscriptions.resume_subscription(token, sub_id.clone(), with_runtime),
+			None => self.subscriptions.insert_subscription(sub_id.clone(), with_runtime),
+		};
+		if sub_data.is_none() && resumption_token.is_some() {
+			debug!(target: LOG_TARGET, "[follow][id={:?}] Invalid resumption token", sub_id);
+			self.metrics.report(|metrics| {
+				metrics.stop_events.with_label_values(&["invalid_resumption_token"]).inc()
+			});
+			let _ = sink.send(&FollowEvent::<Block::Hash>::Stop);
+			return Ok(())
+		}
+		let Some(sub_data) = sub_data else {
 			// Inserting the subscription can only fail if the JsonRPSee
 			// generated a duplicate subscription ID.
 			debug!(target: LOG_TARGET, "[follow][id={:?}] Subscription already accepted", sub_id);
+			self.metrics.report(|metrics| {
+				metrics.stop_events.with_label_values(&["duplicate_subscription"]).inc()
+			});
 			let _ = sink.send(&FollowEvent::<Block::Hash>::Stop);
 			return Ok(())
 		};
@@ -220,16 +652,50 @@ where
 		let subscriptions = self.subscriptions.clone();
 		let backend = self.backend.clone();
 		let client = self.client.clone();
+		let with_header = with_header.unwrap_or(false);
+		let finalized_only = finalized_only.unwrap_or(false);
+		let sync_oracle = self.sync_oracle.clone();
+		let metrics = self.metrics.clone();
+		let grace_period = self.subscription_resume_grace_period;
+		// The token resuming this subscription once its client disconnects.
+		let issued_token = grace_period.map(|_| hex_string(&rand::random::<[u8; 16]>()));
+		// The follower is busy until its initial events are submitted.
+		let health = self.health.register_follower();
+		health.busy();
 		let fut = async move {
+			// The slot is held while the subscription runs.
+			let slot = slot;
 			let mut chain_head_follow = ChainHeadFollower::new(
 				client,
 				backend,
 				subscriptions.clone(),
 				with_runtime,
+				with_header,
+				finalized_only,
 				sub_id.clone(),
+				finalized_history,
+				catch_up_from,
+				issued_token.clone(),
+				health,
+				sync_oracle,
+				metrics,
 			);
 
-			chain_head_follow.generate_events(sink, sub_data).await;
+			let disconnected = chain_head_follow.generate_events(sink, sub_data).await;
+			// The subscription resuming a parked one takes its own slot.
+			drop(slot);
+
+			// Keep the pinned blocks of the disconnected client for the grace period.
+			if let (true, Some(token), Some(grace_period)) =
+				(disconnected, issued_token, grace_period)
+			{
+				if subscriptions.park_subscription(&sub_id, token.clone()) {
+					debug!(target: LOG_TARGET, "[follow][id={:?}] Subscription parked", sub_id);
+					tokio::time::sleep(grace_period).await;
+					subscriptions.expire_parked_subscription(&token);
+					return
+				}
+			}
 
 			subscriptions.remove_subscription(&sub_id);
 			debug!(target: LOG_TARGET, "[follow][id={:?}] Subscription removed", sub_id);
@@ -244,6 +710,7 @@ where
 		follow_subscription: String,
 		hash: Block::Hash,
 	) -> RpcResult<MethodResponse> {
+		let started = Instant::now();
 		let mut block_guard = match self.subscriptions.lock_block(&follow_subscription, hash, 1) {
 			Ok(block) => block,
 			Err(SubscriptionManagementError::SubscriptionAbsent) |
@@ -254,21 +721,69 @@ where
 			},
 			Err(_) => return Err(ChainHeadRpcError::InvalidBlock.into()),
 		};
+		let _timer = self.metrics.start_operation("chainHead_unstable_body");
 
 		let operation_id = block_guard.operation().operation_id();
 
-		let event = match self.client.block(hash) {
-			Ok(Some(signed_block)) => {
-				let extrinsics = signed_block
-					.block
-					.extrinsics()
-					.iter()
-					.map(|extrinsic| hex_string(&extrinsic.encode()))
-					.collect();
-				FollowEvent::<Block::Hash>::OperationBodyDone(OperationBodyDone {
+		let cache_key = CacheKey::new(hash, OperationKind::Body, &());
+		let body = match self.cache.get(&cache_key) {
+			Some(CachedResult::Body(extrinsics)) => Ok(Some(extrinsics)),
+			_ => self.in_flight_bodies.run(&follow_subscription, &cache_key, || {
+				self.retry
+					.run(|| self.client.block(hash))
+					.map(|maybe_block| {
+						maybe_block.map(|signed_block| {
+							let extrinsics: Vec<_> = signed_block
+								.block
+								.extrinsics()
+								.iter()
+								.map(|extrinsic| hex_string(&extrinsic.encode()))
+								.collect();
+
+							if self.cache.is_enabled() {
+								self.cache.insert(
+									cache_key.clone(),
+									CachedResult::Body(extrinsics.clone()),
+								);
+							}
+							extrinsics
+						})
+					})
+					.map_err(|error| error.to_string())
+			}),
+		};
+
+		let timed_out = self
+			.operation_body_timeout
+			.map_or(false, |timeout| started.elapsed() >= timeout);
+		let events = match body {
+			// The body was read, but too late to be reported.
+			Ok(Some(_)) if timed_out => {
+				debug!(
+					target: LOG_TARGET,
+					"[body][id={:?}] Operation timed out after {:?}",
+					operation_id,
+					started.elapsed()
+				);
+				vec![FollowEvent::<Block::Hash>::OperationTimeout(OperationId {
 					operation_id: operation_id.clone(),
-					value: extrinsics,
-				})
+				})]
+			},
+			Ok(Some(extrinsics)) => {
+				let mut chunks = body_chunks(extrinsics, self.operation_max_body_chunk_size);
+				let last = chunks.pop().unwrap_or_default();
+				chunks
+					.into_iter()
+					.map(|value| {
+						FollowEvent::<Block::Hash>::OperationBodyItems(OperationBodyItems {
+							operation_id: operation_id.clone(),
+							value,
+						})
+					})
+					.chain(std::iter::once(FollowEvent::<Block::Hash>::OperationBodyDone(
+						OperationBodyDone { operation_id: operation_id.clone(), value: last },
+					)))
+					.collect::<Vec<_>>()
 			},
 			Ok(None) => {
 				// The block's body was pruned. This subscription ID has become invalid.
@@ -281,13 +796,17 @@ where
 				self.subscriptions.remove_subscription(&follow_subscription);
 				return Err(ChainHeadRpcError::InvalidBlock.into())
 			},
-			Err(error) => FollowEvent::<Block::Hash>::OperationError(OperationError {
+			Err(error) => vec![FollowEvent::<Block::Hash>::OperationError(OperationError {
 				operation_id: operation_id.clone(),
 				error: error.to_string(),
-			}),
+			})],
 		};
 
-		let _ = block_guard.response_sender().unbounded_send(event);
+		let bytes = events.iter().map(response_size).sum();
+		self.audit.record("chainHead_unstable_body", 1, bytes, started);
+		for event in events {
+			let _ = block_guard.response_sender().unbounded_send(event);
+		}
 		Ok(MethodResponse::Started(MethodResponseStarted { operation_id, discarded_items: None }))
 	}
 
@@ -307,11 +826,29 @@ where
 			Err(_) => return Err(ChainHeadRpcError::InvalidBlock.into()),
 		};
 
-		self.client
+		let header = self
+			.client
 			.header(hash)
 			.map(|opt_header| opt_header.map(|h| hex_string(&h.encode())))
-			.map_err(ChainHeadRpcError::FetchBlockHeader)
-			.map_err(Into::into)
+			.map_err(ChainHeadRpcError::FetchBlockHeader)?;
+
+		if let Some(header) = &header {
+			self.subscriptions.record_bandwidth(&follow_subscription, header.len() as u64);
+		}
+		Ok(header)
+	}
+
+	fn chain_head_unstable_headers(
+		&self,
+		follow_subscription: String,
+		hashes: Vec<Block::Hash>,
+	) -> RpcResult<Vec<Option<String>>> {
+		// The blocks are locked one at a time, such that the batch does not
+		// count towards the limit of ongoing operations.
+		hashes
+			.into_iter()
+			.map(|hash| self.chain_head_unstable_header(follow_subscription.clone(), hash))
+			.collect()
 	}
 
 	fn chain_head_unstable_genesis_hash(&self) -> RpcResult<String> {
@@ -324,19 +861,15 @@ where
 		hash: Block::Hash,
 		items: Vec<StorageQuery<String>>,
 		child_trie: Option<String>,
+		storage_options: Option<StorageOptions>,
 	) -> RpcResult<MethodResponse> {
+		let include_proof = storage_options.map_or(false, |options| options.include_proof);
+		let started = Instant::now();
 		// Gain control over parameter parsing and returned error.
 		let items = items
 			.into_iter()
 			.map(|query| {
-				if query.query_type == StorageQueryType::ClosestDescendantMerkleValue {
-					// Note: remove this once all types are implemented.
-					return Err(ChainHeadRpcError::InvalidParam(
-						"Storage query type not supported".into(),
-					))
-				}
-
-				Ok(StorageQuery {
+				Ok::<_, ChainHeadRpcError>(StorageQuery {
 					key: StorageKey(parse_hex_param(query.key)?),
 					query_type: query.query_type,
 				})
@@ -362,8 +895,14 @@ where
 
 		let mut storage_client = ChainHeadStorage::<Client, Block, BE>::new(
 			self.client.clone(),
+			follow_subscription,
 			self.operation_max_storage_items,
-		);
+			self.cache.clone(),
+			self.in_flight_storage.clone(),
+			self.retry.clone(),
+		)
+		.with_proof(include_proof)
+		.with_deadline(self.operation_storage_timeout.map(|timeout| started + timeout));
 		let operation = block_guard.operation();
 		let operation_id = operation.operation_id();
 
@@ -373,12 +912,21 @@ where
 		let mut items = items;
 		items.truncate(num_operations);
 
+		let audit = self.audit.clone();
+		let pending = self.health.start_operation();
+		let timer = self.metrics.start_operation("chainHead_unstable_storage");
 		let fut = async move {
-			storage_client.generate_events(block_guard, hash, items, child_trie).await;
+			let _pending = (pending, timer);
+			let bytes = storage_client.generate_events(block_guard, hash, items, child_trie).await;
+			audit.record("chainHead_unstable_storage", num_operations, bytes, started);
 		};
 
-		self.executor
-			.spawn_blocking("substrate-rpc-subscription", Some("rpc"), fut.boxed());
+		match &self.operation_pool {
+			Some(pool) => pool.spawn_future(fut.boxed()),
+			None => self
+				.executor
+				.spawn_blocking("substrate-rpc-subscription", Some("rpc"), fut.boxed()),
+		}
 		Ok(MethodResponse::Started(MethodResponseStarted {
 			operation_id,
 			discarded_items: Some(discarded),
@@ -391,8 +939,18 @@ where
 		hash: Block::Hash,
 		function: String,
 		call_parameters: String,
+		call_options: Option<CallOptions>,
 	) -> RpcResult<MethodResponse> {
 		let call_parameters = Bytes::from(parse_hex_param(call_parameters)?);
+		let call_options = call_options.unwrap_or_default();
+		let with_stats = call_options.with_stats;
+		let decode = call_options.decode;
+		let call_context = match call_options.call_context {
+			Some(CallContextType::Offchain) => CallContext::Offchain,
+			Some(CallContextType::Onchain) => CallContext::Onchain,
+			None => self.call_context,
+		};
+		let started = Instant::now();
 
 		let mut block_guard = match self.subscriptions.lock_block(&follow_subscription, hash, 1) {
 			Ok(block) => block,
@@ -415,36 +973,174 @@ where
 			)
 			.into())
 		}
+		let timer = self.metrics.start_operation("chainHead_unstable_call");
 
 		let operation_id = block_guard.operation().operation_id();
-		let event = self
-			.client
-			.executor()
-			.call(hash, &function, &call_parameters, CallContext::Offchain)
-			.map(|result| {
-				FollowEvent::<Block::Hash>::OperationCallDone(OperationCallDone {
-					operation_id: operation_id.clone(),
-					output: hex_string(&result),
-				})
-			})
-			.unwrap_or_else(|error| {
-				FollowEvent::<Block::Hash>::OperationError(OperationError {
-					operation_id: operation_id.clone(),
-					error: error.to_string(),
+		let response_id = operation_id.clone();
+		let client = self.client.clone();
+		let cache = self.cache.clone();
+		let in_flight_calls = self.in_flight_calls.clone();
+		let call_decoder = self.call_decoder.clone();
+		let retry = self.retry.clone();
+		let audit = self.audit.clone();
+		let sender = block_guard.response_sender();
+		let call = move || {
+			let _block_guard = block_guard;
+			let _timer = timer;
+			let cache_key = CacheKey::new(
+				hash,
+				OperationKind::Call,
+				&(function.as_str(), &call_parameters[..], call_context == CallContext::Onchain),
+			);
+			// The execution statistics are specific to each call and are never cached.
+			let cached = if with_stats { None } else { cache.get(&cache_key) };
+			let output = match cached {
+				Some(CachedResult::Call(output)) => Ok((output, None)),
+				_ if with_stats => {
+					let started = Instant::now();
+					retry
+						.run(|| {
+							client.executor().prove_execution(hash, &function, &call_parameters)
+						})
+						.map(|(result, proof)| {
+							let stats = CallStats {
+								execution_time_micros: started.elapsed().as_micros() as u64,
+								proof_size: proof.encoded_size() as u64,
+								weight: benchmarked_weight(
+									&*client,
+									hash,
+									&function,
+									&call_parameters,
+								),
+							};
+							(hex_string(&result), Some(stats))
+						})
+						.map_err(|error| error.to_string())
+				},
+				_ => in_flight_calls
+					.run(&follow_subscription, &cache_key, || {
+						retry
+							.run(|| {
+								client.executor().call(
+									hash,
+									&function,
+									&call_parameters,
+									call_context,
+								)
+							})
+							.map(|result| {
+								let output = hex_string(&result);
+								if cache.is_enabled() {
+									cache.insert(
+										cache_key.clone(),
+										CachedResult::Call(output.clone()),
+									);
+								}
+								output
+							})
+							.map_err(|error| error.to_string())
+					})
+					.map(|output| (output, None)),
+			};
+
+			let event = output
+				.and_then(|(output, stats)| {
+					let decoded = if decode {
+						let bytes = array_bytes::hex2bytes(&output)
+							.map_err(|_| "Invalid hexadecimal output".to_string())?;
+						Some(call_decoder.decode::<Block, _>(&*client, hash, &function, &bytes)?)
+					} else {
+						None
+					};
+					Ok(FollowEvent::<Block::Hash>::OperationCallDone(OperationCallDone {
+						operation_id: response_id.clone(),
+						output,
+						stats,
+						decoded,
+					}))
 				})
-			});
+				.unwrap_or_else(|error| {
+					FollowEvent::<Block::Hash>::OperationError(OperationError {
+						operation_id: response_id,
+						error: error.to_string(),
+					})
+				});
+
+			audit.record("chainHead_unstable_call", 1, response_size(&event), started);
+			event
+		};
 
-		let _ = block_guard.response_sender().unbounded_send(event);
+		let Some(timeout) = self.operation_call_timeout else {
+			let call = move || {
+				let _ = sender.unbounded_send(call());
+			};
+			match &self.operation_pool {
+				Some(pool) => pool.spawn(call),
+				None => call(),
+			}
+			return Ok(MethodResponse::Started(MethodResponseStarted {
+				operation_id,
+				discarded_items: None,
+			}))
+		};
+
+		// The runtime call cannot be interrupted, only its result is discarded once the
+		// timeout is reported.
+		let (tx, rx) = oneshot::channel();
+		let call = move || {
+			let _ = tx.send(call());
+		};
+		match &self.operation_pool {
+			Some(pool) => pool.spawn(call),
+			None => self.executor.spawn_blocking(
+				"substrate-rpc-subscription",
+				Some("rpc"),
+				async move { call() }.boxed(),
+			),
+		}
+		let timeout_id = operation_id.clone();
+		let fut = async move {
+			match tokio::time::timeout(timeout, rx).await {
+				Ok(Ok(event)) => {
+					let _ = sender.unbounded_send(event);
+				},
+				// The call panicked.
+				Ok(Err(_)) => {},
+				Err(_) => {
+					debug!(
+						target: LOG_TARGET,
+						"[call][id={:?}] Operation timed out after {:?}",
+						timeout_id,
+						timeout
+					);
+					let event = FollowEvent::<Block::Hash>::OperationTimeout(OperationId {
+						operation_id: timeout_id,
+					});
+					let _ = sender.unbounded_send(event);
+				},
+			}
+		};
+		self.executor.spawn("substrate-rpc-subscription", Some("rpc"), fut.boxed());
 		Ok(MethodResponse::Started(MethodResponseStarted { operation_id, discarded_items: None }))
 	}
 
 	fn chain_head_unstable_unpin(
 		&self,
 		follow_subscription: String,
-		hash: Block::Hash,
+		hash_or_hashes: ListOrValue<Block::Hash>,
 	) -> RpcResult<()> {
-		match self.subscriptions.unpin_block(&follow_subscription, hash) {
-			Ok(()) => Ok(()),
+		let hashes = match hash_or_hashes {
+			ListOrValue::Value(hash) => vec![hash],
+			ListOrValue::List(hashes) => hashes,
+		};
+
+		match self.subscriptions.unpin_blocks(&follow_subscription, &hashes) {
+			Ok(()) => {
+				for hash in &hashes {
+					self.cache.invalidate_block(hash);
+				}
+				Ok(())
+			},
 			Err(SubscriptionManagementError::SubscriptionAbsent) => {
 				// Invalid invalid subscription ID.
 				Ok(())
@@ -457,6 +1153,36 @@ where
 		}
 	}
 
+	fn chain_head_unstable_set_fork_filter(
+		&self,
+		follow_subscription: String,
+		hash: Option<Block::Hash>,
+	) -> RpcResult<()> {
+		match self.subscriptions.set_fork_filter(&follow_subscription, hash) {
+			Ok(()) => Ok(()),
+			Err(SubscriptionManagementError::SubscriptionAbsent) => {
+				// Invalid invalid subscription ID.
+				Ok(())
+			},
+			Err(_) => Err(ChainHeadRpcError::InvalidBlock.into()),
+		}
+	}
+
+	fn chain_head_unstable_pinned_blocks(
+		&self,
+		follow_subscription: String,
+	) -> RpcResult<Vec<PinnedBlock<Block::Hash>>> {
+		// Invalid invalid subscription ID.
+		let Some(pinned) = self.subscriptions.pinned_blocks(&follow_subscription) else {
+			return Ok(Vec::new())
+		};
+
+		Ok(pinned
+			.into_iter()
+			.map(|(hash, age)| PinnedBlock { hash, pin_age_millis: age.as_millis() as u64 })
+			.collect())
+	}
+
 	fn chain_head_unstable_continue(
 		&self,
 		follow_subscription: String,
@@ -466,6 +1192,11 @@ where
 			return Ok(())
 		};
 
+		// The items of the resumed operation are charged to the subscription once reported.
+		if self.subscriptions.is_throttled(&follow_subscription) {
+			return Err(ChainHeadRpcError::BandwidthExhausted.into())
+		}
+
 		if !operation.submit_continue() {
 			// Continue called without generating a `WaitingForContinue` event.
 			Err(ChainHeadRpcError::InvalidContinue.into())
@@ -487,4 +1218,13 @@ where
 
 		Ok(())
 	}
+
+	fn chain_head_unstable_health(&self) -> RpcResult<HealthReport> {
+		let report = self.health.report();
+		if report.is_healthy {
+			Ok(report)
+		} else {
+			Err(ChainHeadRpcError::Unhealthy(report).into())
+		}
+	}
 }
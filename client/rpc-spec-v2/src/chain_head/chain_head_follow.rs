@@ -21,14 +21,19 @@
 use crate::chain_head::{
 	chain_head::LOG_TARGET,
 	event::{
-		BestBlockChanged, Finalized, FollowEvent, Initialized, NewBlock, RuntimeEvent,
-		RuntimeVersionEvent,
+		BestBlockChanged, CatchUp, ErrorEvent, Finalized, FollowEvent, Initialized, NewBlock,
+		RuntimeEvent, RuntimeVersionEvent, StorageResult, StorageResultType,
 	},
-	subscription::{InsertedSubscriptionData, SubscriptionManagement, SubscriptionManagementError},
+	health::FollowerHealth,
+	hex_string,
+	metrics::MetricsLink,
+	subscription::{InsertedSubscriptionData, PinStore, SubscriptionManagementError},
 };
+use codec::Encode;
 use futures::{
 	channel::oneshot,
-	stream::{self, Stream, StreamExt},
+	stream::{self, BoxStream, Stream, StreamExt},
+	FutureExt,
 };
 use futures_util::future::Either;
 use jsonrpsee::SubscriptionSink;
@@ -36,12 +41,82 @@ use log::{debug, error};
 use sc_client_api::{
 	Backend, BlockBackend, BlockImportNotification, BlockchainEvents, FinalityNotification,
 };
-use sp_api::CallApiAt;
+use serde::Serialize;
+use sp_api::{ApiError, CallApiAt};
 use sp_blockchain::{
 	Backend as BlockChainBackend, Error as BlockChainError, HeaderBackend, HeaderMetadata, Info,
 };
-use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor};
-use std::{collections::HashSet, sync::Arc};
+use sp_consensus::SyncOracle;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor, One, Zero};
+use std::{
+	collections::{HashSet, VecDeque},
+	sync::Arc,
+};
+
+/// The source of the block notifications from which the `chainHead_follow` events are generated.
+///
+/// The trait is implemented for every client providing [`BlockchainEvents`]. Alternative clients
+/// and tests can implement it directly to provide their own sequence of imported and
+/// finalized blocks.
+pub trait NotificationSource<Block: BlockT> {
+	/// Get a stream of the imported blocks.
+	fn import_notifications(&self) -> BoxStream<'static, BlockImportNotification<Block>>;
+
+	/// Get a stream of the finalized blocks.
+	fn finality_notifications(&self) -> BoxStream<'static, FinalityNotification<Block>>;
+}
+
+impl<Block: BlockT, T: BlockchainEvents<Block>> NotificationSource<Block> for T {
+	fn import_notifications(&self) -> BoxStream<'static, BlockImportNotification<Block>> {
+		self.import_notification_stream().boxed()
+	}
+
+	fn finality_notifications(&self) -> BoxStream<'static, FinalityNotification<Block>> {
+		self.finality_notification_stream().boxed()
+	}
+}
+
+/// The reason the events could not be handed to the connection.
+enum SendError {
+	/// The client disconnected or the subscription was closed.
+	Disconnected,
+	/// An event could not be serialized, the subscription must be stopped.
+	Serialization,
+}
+
+/// Hand the events to the connection.
+///
+/// The connection queues the events without bound: jsonrpsee 0.16 neither limits nor
+/// exposes the messages that are not yet written to the client, such that the lag of a slow
+/// client cannot be observed here.
+fn send_events<Hash: Serialize>(
+	sink: &mut SubscriptionSink,
+	events: Vec<FollowEvent<Hash>>,
+	sub_id: &str,
+) -> Result<(), SendError> {
+	for event in events {
+		// Migration note: the new version of jsonrpsee returns Result<(), DisconnectError>
+		// The logic from `Err(err)` should be moved when building the new
+		// `SubscriptionMessage`.
+
+		// For now, jsonrpsee returns:
+		// Ok(true): message sent
+		// Ok(false): client disconnected or subscription closed
+		// Err(err): serder serialization error of the event
+		match sink.send(&event) {
+			Ok(true) => {},
+			// Client disconnected or subscription was closed.
+			Ok(false) => return Err(SendError::Disconnected),
+			Err(err) => {
+				// Failed to submit event.
+				debug!(target: LOG_TARGET, "[follow][id={:?}] Failed to send event {:?}", sub_id, err);
+				return Err(SendError::Serialization)
+			},
+		}
+	}
+
+	Ok(())
+}
 
 /// Generates the events of the `chainHead_follow` method.
 pub struct ChainHeadFollower<BE: Backend<Block>, Block: BlockT, Client> {
@@ -50,13 +125,38 @@ pub struct ChainHeadFollower<BE: Backend<Block>, Block: BlockT, Client> {
 	/// Backend of the chain.
 	backend: Arc<BE>,
 	/// Subscriptions handle.
-	sub_handle: Arc<SubscriptionManagement<Block, BE>>,
+	sub_handle: Arc<dyn PinStore<Block, BE>>,
 	/// Subscription was started with the runtime updates flag.
 	with_runtime: bool,
+	/// Subscription was started with the block header flag.
+	with_header: bool,
+	/// Subscription reports the finalized blocks only, without the `NewBlock` and
+	/// `BestBlockChanged` events.
+	finalized_only: bool,
 	/// Subscription ID.
 	sub_id: String,
 	/// The best reported block by this subscription.
 	best_block_cache: Option<Block::Hash>,
+	/// Blocks that were not reported because they are not part of the fork
+	/// selected by the subscription.
+	///
+	/// The blocks are reported once they are part of the selected fork or finalized.
+	fork_filtered: HashSet<Block::Hash>,
+	/// The fork filter for which the `fork_filtered` blocks were last checked.
+	fork_filter_cache: Option<Block::Hash>,
+	/// The number of finalized ancestors reported by the `Initialized` event.
+	finalized_history: usize,
+	/// The finalized block reported by the `Initialized` event, if the subscription
+	/// catches up from an earlier finalized block.
+	catch_up_from: Option<Block::Hash>,
+	/// The token resuming the subscription, reported by the `Initialized` event.
+	resumption_token: Option<String>,
+	/// Report the health of the follower.
+	health: FollowerHealth,
+	/// The oracle reporting the major sync of the node.
+	sync_oracle: Option<Arc<dyn SyncOracle + Send + Sync>>,
+	/// Report the reasons of the `Stop` events.
+	metrics: MetricsLink,
 }
 
 impl<BE: Backend<Block>, Block: BlockT, Client> ChainHeadFollower<BE, Block, Client> {
@@ -64,21 +164,84 @@ impl<BE: Backend<Block>, Block: BlockT, Client> ChainHeadFollower<BE, Block, Cli
 	pub fn new(
 		client: Arc<Client>,
 		backend: Arc<BE>,
-		sub_handle: Arc<SubscriptionManagement<Block, BE>>,
+		sub_handle: Arc<dyn PinStore<Block, BE>>,
 		with_runtime: bool,
+		with_header: bool,
+		finalized_only: bool,
 		sub_id: String,
+		finalized_history: usize,
+		catch_up_from: Option<Block::Hash>,
+		resumption_token: Option<String>,
+		health: FollowerHealth,
+		sync_oracle: Option<Arc<dyn SyncOracle + Send + Sync>>,
+		metrics: MetricsLink,
 	) -> Self {
-		Self { client, backend, sub_handle, with_runtime, sub_id, best_block_cache: None }
+		Self {
+			client,
+			backend,
+			sub_handle,
+			with_runtime,
+			with_header,
+			finalized_only,
+			sub_id,
+			best_block_cache: None,
+			fork_filtered: Default::default(),
+			fork_filter_cache: None,
+			finalized_history,
+			catch_up_from,
+			resumption_token,
+			health,
+			sync_oracle,
+			metrics,
+		}
+	}
+
+	/// Remove the events that are not reported by the subscription.
+	fn filter_events(
+		&self,
+		events: Vec<FollowEvent<Block::Hash>>,
+	) -> Vec<FollowEvent<Block::Hash>> {
+		if !self.finalized_only {
+			return events
+		}
+
+		events
+			.into_iter()
+			.filter(|event| {
+				!matches!(event, FollowEvent::NewBlock(_) | FollowEvent::BestBlockChanged(_))
+			})
+			.collect()
+	}
+
+	/// Submit the `Stop` event and report its reason.
+	fn stop(&self, sink: &mut SubscriptionSink, reason: &'static str) {
+		self.report_stop(reason);
+		let _ = sink.send(&FollowEvent::<String>::Stop);
+	}
+
+	/// Report the reason of the `Stop` event.
+	fn report_stop(&self, reason: &'static str) {
+		self.metrics
+			.report(|metrics| metrics.stop_events.with_label_values(&[reason]).inc());
 	}
 }
 
+/// The maximum number of finality notifications held until the blocks they finalize or
+/// prune are announced.
+///
+/// The notifications are held within a burst of notifications that are already available,
+/// see [`ChainHeadFollower::release_held_finality`]. This bounds the reordering of the
+/// notifications only: the events are handed to the connection as soon as they are generated,
+/// without a bound on the events the client has not yet received, see [`send_events`].
+const MAX_HELD_FINALITY: usize = 8;
+
 /// A block notification.
 enum NotificationType<Block: BlockT> {
 	/// The initial events generated from the node's memory.
 	InitialEvents(Vec<FollowEvent<Block::Hash>>),
-	/// The new block notification obtained from `import_notification_stream`.
+	/// The new block notification obtained from `import_notifications`.
 	NewBlock(BlockImportNotification<Block>),
-	/// The finalized block notification obtained from `finality_notification_stream`.
+	/// The finalized block notification obtained from `finality_notifications`.
 	Finalized(FinalityNotification<Block>),
 	/// The response of `chainHead` method calls.
 	MethodResponse(FollowEvent<Block::Hash>),
@@ -108,6 +271,40 @@ struct InitialBlocks<Block: BlockT> {
 	pruned_forks: HashSet<Block::Hash>,
 }
 
+/// The number of bytes of the operation results carried by the event.
+///
+/// This accounts for the hexadecimal encoded payloads of the events, which dominate
+/// the size of the responses.
+pub(crate) fn response_size<Hash>(event: &FollowEvent<Hash>) -> u64 {
+	let size = match event {
+		FollowEvent::OperationBodyItems(items) => items.value.iter().map(|tx| tx.len()).sum(),
+		FollowEvent::OperationBodyDone(done) => done.value.iter().map(|tx| tx.len()).sum(),
+		FollowEvent::OperationCallDone(done) => done.output.len(),
+		FollowEvent::OperationStorageItems(items) =>
+			items.items.iter().map(storage_result_size).sum(),
+		_ => 0,
+	};
+	size as u64
+}
+
+/// The number of bytes of the hexadecimal encoded key and result of a storage item.
+pub(crate) fn storage_result_size(item: &StorageResult) -> usize {
+	let result = match &item.result {
+		StorageResultType::Value(value) |
+		StorageResultType::Hash(value) |
+		StorageResultType::ClosestDescendantMerkleValue(value) => value.len(),
+		StorageResultType::AncestorValues(values) =>
+			values.iter().flatten().map(|value| value.len()).sum(),
+		StorageResultType::Exists(_) | StorageResultType::ValueLength(_) => 0,
+	};
+	item.key.len() + result
+}
+
+/// Generate the runtime event of a block whose runtime could not be obtained.
+fn runtime_error_event(err: ApiError) -> RuntimeEvent {
+	RuntimeEvent::Invalid(ErrorEvent { error: format!("Api error: {}", err) })
+}
+
 /// The startup point from which chainHead started to generate events.
 struct StartupPoint<Block: BlockT> {
 	/// Best block hash.
@@ -135,7 +332,7 @@ where
 	Client: BlockBackend<Block>
 		+ HeaderBackend<Block>
 		+ HeaderMetadata<Block, Error = BlockChainError>
-		+ BlockchainEvents<Block>
+		+ NotificationSource<Block>
 		+ CallApiAt<Block>
 		+ 'static,
 {
@@ -152,7 +349,7 @@ where
 
 		let block_rt = match self.client.runtime_version_at(block) {
 			Ok(rt) => rt,
-			Err(err) => return Some(err.into()),
+			Err(err) => return Some(runtime_error_event(err)),
 		};
 
 		let parent = match parent {
@@ -163,7 +360,7 @@ where
 
 		let parent_rt = match self.client.runtime_version_at(parent) {
 			Ok(rt) => rt,
-			Err(err) => return Some(err.into()),
+			Err(err) => return Some(runtime_error_event(err)),
 		};
 
 		// Report the runtime version change.
@@ -174,6 +371,44 @@ where
 		}
 	}
 
+	/// Conditionally fetch the SCALE-encoded header and the number of extrinsics of the given
+	/// block.
+	fn generate_block_details(&self, block: Block::Hash) -> (Option<String>, Option<u32>) {
+		// No block details should be reported.
+		if !self.with_header {
+			return (None, None)
+		}
+
+		let header = match self.client.header(block) {
+			Ok(header) => header.map(|header| hex_string(&header.encode())),
+			Err(err) => {
+				debug!(
+					target: LOG_TARGET,
+					"[follow][id={:?}] Failed to fetch the header of {:?}: {:?}",
+					self.sub_id,
+					block,
+					err
+				);
+				None
+			},
+		};
+		let extrinsics_count = match self.client.block_body(block) {
+			Ok(body) => body.map(|body| body.len() as u32),
+			Err(err) => {
+				debug!(
+					target: LOG_TARGET,
+					"[follow][id={:?}] Failed to fetch the body of {:?}: {:?}",
+					self.sub_id,
+					block,
+					err
+				);
+				None
+			},
+		};
+
+		(header, extrinsics_count)
+	}
+
 	/// Get the in-memory blocks of the client, starting from the provided finalized hash.
 	fn get_init_blocks_with_forks(
 		&self,
@@ -208,8 +443,81 @@ where
 		Ok(InitialBlocks { finalized_block_descendants, pruned_forks })
 	}
 
+	/// Get the finalized ancestors of the startup point that are reported by the
+	/// `Initialized` event.
+	///
+	/// The history stops at the genesis block or at the first block whose state is no
+	/// longer available. The ancestors are ordered from the oldest block to the parent
+	/// of the finalized block of the startup point.
+	fn get_finalized_history(
+		&self,
+		startup_point: &StartupPoint<Block>,
+	) -> Result<Vec<Block::Hash>, SubscriptionManagementError> {
+		let mut history = Vec::with_capacity(self.finalized_history);
+		let mut hash = startup_point.finalized_hash;
+		let mut number = startup_point.finalized_number;
+
+		while history.len() < self.finalized_history && !number.is_zero() {
+			let Some(header) = self.client.header(hash)? else { break };
+			let parent = *header.parent_hash();
+			let parent_number = number - One::one();
+
+			if !self.backend.have_state_at(parent, parent_number) {
+				break
+			}
+
+			history.push(parent);
+			hash = parent;
+			number = parent_number;
+		}
+
+		history.reverse();
+		Ok(history)
+	}
+
+	/// Get the startup point of a subscription catching up from an earlier finalized block.
+	///
+	/// The block must be a finalized ancestor of the startup point whose state is still
+	/// available. Returns `None` if the subscription does not catch up.
+	fn get_catch_up_point(
+		&self,
+		startup_point: &StartupPoint<Block>,
+	) -> Result<Option<StartupPoint<Block>>, SubscriptionManagementError> {
+		let Some(hash) = self.catch_up_from else { return Ok(None) };
+		if hash == startup_point.finalized_hash {
+			return Ok(None)
+		}
+
+		let Some(header) = self.client.header(hash)? else {
+			return Err(SubscriptionManagementError::BlockHeaderAbsent)
+		};
+		let number = *header.number();
+
+		if number >= startup_point.finalized_number || self.client.hash(number)? != Some(hash) {
+			return Err(SubscriptionManagementError::Custom(format!(
+				"Block {:?} is not a finalized ancestor of {:?}",
+				hash, startup_point.finalized_hash
+			)))
+		}
+		if !self.backend.have_state_at(hash, number) {
+			return Err(SubscriptionManagementError::Custom(format!(
+				"State of block {:?} is not available",
+				hash
+			)))
+		}
+
+		Ok(Some(StartupPoint {
+			best_hash: startup_point.best_hash,
+			finalized_hash: hash,
+			finalized_number: number,
+		}))
+	}
+
 	/// Generate the initial events reported by the RPC `follow` method.
 	///
+	/// If the subscription catches up from an earlier finalized block, the blocks finalized
+	/// since are reported by `NewBlock` events followed by a `Finalized` event.
+	///
 	/// Returns the initial events that should be reported directly, together with pruned
 	/// block hashes that should be ignored by the `Finalized` event.
 	fn generate_init_events(
@@ -217,35 +525,67 @@ where
 		startup_point: &StartupPoint<Block>,
 	) -> Result<(Vec<FollowEvent<Block::Hash>>, HashSet<Block::Hash>), SubscriptionManagementError>
 	{
-		let init = self.get_init_blocks_with_forks(startup_point)?;
+		let catch_up_point = self.get_catch_up_point(startup_point)?;
+		let init_point = catch_up_point.as_ref().unwrap_or(startup_point);
+
+		let init = self.get_init_blocks_with_forks(init_point)?;
+
+		// The blocks finalized since the block the subscription catches up from.
+		let finalized_since: Vec<_> = match catch_up_point {
+			Some(_) => sp_blockchain::tree_route(
+				self.backend.blockchain(),
+				init_point.finalized_hash,
+				startup_point.finalized_hash,
+			)?
+			.enacted()
+			.iter()
+			.map(|block| block.hash)
+			.collect(),
+			None => Vec::new(),
+		};
 
-		let initial_blocks = init.finalized_block_descendants;
+		let mut initial_blocks = init.finalized_block_descendants;
+		// The descendants are pinned once finalized.
+		if self.finalized_only {
+			initial_blocks.retain(|(child, _)| finalized_since.contains(child));
+		}
+
+		// The initialized event is the first one sent. It reports the finalized history
+		// followed by the finalized block.
+		let finalized_block_hash = init_point.finalized_hash;
+		let mut finalized_block_hashes = self.get_finalized_history(init_point)?;
+		finalized_block_hashes.push(finalized_block_hash);
 
-		// The initialized event is the first one sent.
-		let finalized_block_hash = startup_point.finalized_hash;
-		self.sub_handle.pin_block(&self.sub_id, finalized_block_hash)?;
+		for hash in finalized_block_hashes.iter() {
+			self.sub_handle.pin_block(&self.sub_id, *hash)?;
+		}
 
 		let finalized_block_runtime = self.generate_runtime_event(finalized_block_hash, None);
 
 		let initialized_event = FollowEvent::Initialized(Initialized {
-			finalized_block_hash,
+			finalized_block_hashes,
 			finalized_block_runtime,
 			with_runtime: self.with_runtime,
+			resumption_token: self.resumption_token.clone(),
 		});
 
-		let mut finalized_block_descendants = Vec::with_capacity(initial_blocks.len() + 1);
+		let mut finalized_block_descendants = Vec::with_capacity(initial_blocks.len() + 2);
 
 		finalized_block_descendants.push(initialized_event);
+
 		for (child, parent) in initial_blocks.into_iter() {
 			self.sub_handle.pin_block(&self.sub_id, child)?;
 
 			let new_runtime = self.generate_runtime_event(child, Some(parent));
+			let (header, extrinsics_count) = self.generate_block_details(child);
 
 			let event = FollowEvent::NewBlock(NewBlock {
 				block_hash: child,
 				parent_block_hash: parent,
 				new_runtime,
 				with_runtime: self.with_runtime,
+				header,
+				extrinsics_count,
 			});
 
 			finalized_block_descendants.push(event);
@@ -259,6 +599,14 @@ where
 			finalized_block_descendants.push(best_block);
 		};
 
+		// Finalize the blocks reported since the block the subscription caught up from.
+		if catch_up_point.is_some() {
+			finalized_block_descendants.push(FollowEvent::Finalized(Finalized {
+				finalized_block_hashes: finalized_since,
+				pruned_block_hashes: Vec::new(),
+			}));
+		}
+
 		Ok((finalized_block_descendants, init.pruned_forks))
 	}
 
@@ -271,12 +619,15 @@ where
 		is_best_block: bool,
 	) -> Vec<FollowEvent<Block::Hash>> {
 		let new_runtime = self.generate_runtime_event(block_hash, Some(parent_block_hash));
+		let (header, extrinsics_count) = self.generate_block_details(block_hash);
 
 		let new_block = FollowEvent::NewBlock(NewBlock {
 			block_hash,
 			parent_block_hash,
 			new_runtime,
 			with_runtime: self.with_runtime,
+			header,
+			extrinsics_count,
 		});
 
 		if !is_best_block {
@@ -305,15 +656,82 @@ where
 		}
 	}
 
+	/// Returns true if the block is a descendant of the block selected by the
+	/// `chainHead_unstable_setForkFilter` method, or if no block was selected.
+	fn is_in_selected_fork(&self, hash: Block::Hash) -> bool {
+		let Some(fork_root) = self.sub_handle.fork_filter(&self.sub_id) else { return true };
+
+		match sp_blockchain::tree_route(self.backend.blockchain(), fork_root, hash) {
+			Ok(tree_route) => tree_route.retracted().is_empty(),
+			// The ancestry cannot be determined, report the block.
+			Err(_) => true,
+		}
+	}
+
+	/// Generate the `NewBlock` events for the blocks that were not reported because
+	/// of the fork filter, but are now part of the selected fork.
+	fn generate_unfiltered_events(
+		&mut self,
+	) -> Result<Vec<FollowEvent<Block::Hash>>, SubscriptionManagementError> {
+		// The filtered blocks remain outside of the selected fork until the filter changes.
+		let fork_filter = self.sub_handle.fork_filter(&self.sub_id);
+		if self.fork_filtered.is_empty() || fork_filter == self.fork_filter_cache {
+			self.fork_filter_cache = fork_filter;
+			return Ok(Default::default())
+		}
+		self.fork_filter_cache = fork_filter;
+
+		let mut unfiltered = Vec::new();
+		for hash in self.fork_filtered.iter() {
+			if !self.is_in_selected_fork(*hash) {
+				continue
+			}
+
+			let Some(header) = self.client.header(*hash)? else {
+				return Err(SubscriptionManagementError::BlockHeaderAbsent)
+			};
+			unfiltered.push(header);
+		}
+		// The parents must be reported before their descendants.
+		unfiltered.sort_by_key(|header| *header.number());
+
+		let mut events = Vec::with_capacity(unfiltered.len());
+		for header in unfiltered {
+			let hash = header.hash();
+			self.fork_filtered.remove(&hash);
+
+			if self.sub_handle.pin_block(&self.sub_id, hash)? {
+				events.extend(self.generate_import_events(hash, *header.parent_hash(), false));
+			}
+		}
+
+		Ok(events)
+	}
+
 	/// Handle the import of new blocks by generating the appropriate events.
 	fn handle_import_blocks(
 		&mut self,
 		notification: BlockImportNotification<Block>,
 		startup_point: &StartupPoint<Block>,
 	) -> Result<Vec<FollowEvent<Block::Hash>>, SubscriptionManagementError> {
+		// The blocks are not announced before they are finalized, and pinned once finalized.
+		if self.finalized_only {
+			return Ok(Default::default())
+		}
+
+		let mut events = self.generate_unfiltered_events()?;
+
+		// Blocks outside of the selected fork are neither pinned nor reported.
+		if !self.sub_handle.contains_block(&self.sub_id, notification.hash) &&
+			!self.is_in_selected_fork(notification.hash)
+		{
+			self.fork_filtered.insert(notification.hash);
+			return Ok(events)
+		}
+
 		// The block was already pinned by the initial block events or by the finalized event.
 		if !self.sub_handle.pin_block(&self.sub_id, notification.hash)? {
-			return Ok(Default::default())
+			return Ok(events)
 		}
 
 		// Ensure we are only reporting blocks after the starting point.
@@ -321,11 +739,12 @@ where
 			return Ok(Default::default())
 		}
 
-		Ok(self.generate_import_events(
+		events.extend(self.generate_import_events(
 			notification.hash,
 			*notification.header.parent_hash(),
 			notification.is_new_best,
-		))
+		));
+		Ok(events)
 	}
 
 	/// Generates new block events from the given finalized hashes.
@@ -351,6 +770,9 @@ where
 		let parents =
 			std::iter::once(first_header.parent_hash()).chain(finalized_block_hashes.iter());
 		for (i, (hash, parent)) in finalized_block_hashes.iter().zip(parents).enumerate() {
+			// Finalized blocks are reported regardless of the fork filter.
+			self.fork_filtered.remove(hash);
+
 			// Check if the block was already reported and thus, is already pinned.
 			if !self.sub_handle.pin_block(&self.sub_id, *hash)? {
 				continue
@@ -368,7 +790,12 @@ where
 				// When the node falls out of sync and then syncs up to the tip of the chain, it can
 				// happen that we skip notifications. Then it is better to terminate the connection
 				// instead of trying to send notifications for all missed blocks.
-				if let Some(best_block_hash) = self.best_block_cache {
+				//
+				// The blocks of a subscription reporting the finalized blocks only are pinned
+				// here for the first time.
+				if let Some(best_block_hash) =
+					self.best_block_cache.filter(|_| !self.finalized_only)
+				{
 					let ancestor = sp_blockchain::lowest_common_ancestor(
 						&*self.client,
 						*hash,
@@ -392,9 +819,10 @@ where
 
 	/// Get all pruned block hashes from the provided stale heads.
 	///
-	/// The result does not include hashes from `to_ignore`.
+	/// The result does not include hashes from `to_ignore` and blocks that were
+	/// not reported because of the fork filter.
 	fn get_pruned_hashes(
-		&self,
+		&mut self,
 		stale_heads: &[Block::Hash],
 		last_finalized: Block::Hash,
 		to_ignore: &mut HashSet<Block::Hash>,
@@ -407,7 +835,7 @@ where
 
 			// Collect only blocks that are not part of the canonical chain.
 			pruned.extend(tree_route.enacted().iter().filter_map(|block| {
-				if !to_ignore.remove(&block.hash) {
+				if !to_ignore.remove(&block.hash) && !self.fork_filtered.remove(&block.hash) {
 					Some(block.hash)
 				} else {
 					None
@@ -445,8 +873,20 @@ where
 
 		// Report all pruned blocks from the notification that are not
 		// part of the fork we need to ignore.
-		let pruned_block_hashes =
+		let mut pruned_block_hashes =
 			self.get_pruned_hashes(&notification.stale_heads, last_finalized, to_ignore)?;
+		// The blocks that were not finalized were neither announced nor pinned.
+		if self.finalized_only {
+			pruned_block_hashes.retain(|hash| self.sub_handle.contains_block(&self.sub_id, *hash));
+		}
+
+		// The selected fork was pruned, report the blocks of the canonical chain.
+		if let Some(fork_root) = self.sub_handle.fork_filter(&self.sub_id) {
+			if pruned_block_hashes.contains(&fork_root) {
+				let _ = self.sub_handle.set_fork_filter(&self.sub_id, None);
+				events.extend(self.generate_unfiltered_events()?);
+			}
+		}
 
 		let finalized_event = FollowEvent::Finalized(Finalized {
 			finalized_block_hashes,
@@ -477,6 +917,12 @@ where
 					events.push(finalized_event);
 					Ok(events)
 				} else {
+					// The new best block must be reported before it is announced as best.
+					if self.fork_filtered.contains(&best_block_hash) {
+						let _ = self.sub_handle.set_fork_filter(&self.sub_id, None);
+						events.extend(self.generate_unfiltered_events()?);
+					}
+
 					// The RPC needs to also submit a new best block changed before the
 					// finalized event.
 					self.best_block_cache = Some(best_block_hash);
@@ -493,99 +939,303 @@ where
 		}
 	}
 
+	/// Returns true if the node is performing a major sync.
+	fn is_major_syncing(&self) -> bool {
+		self.sync_oracle.as_ref().map_or(false, |oracle| oracle.is_major_syncing())
+	}
+
+	/// Generate the events summarizing the blocks finalized while the events were paused.
+	///
+	/// The `CatchUp` event reports the latest finalized block and the pinned blocks that were
+	/// pruned. It is followed by the `NewBlock` events of the descendants of the finalized
+	/// block that were not reported yet, and by the `BestBlockChanged` event.
+	///
+	/// Returns the events together with the new startup point and the pruned block hashes
+	/// that should be ignored by the `Finalized` event.
+	fn generate_catch_up_events(
+		&mut self,
+	) -> Result<
+		(Vec<FollowEvent<Block::Hash>>, StartupPoint<Block>, HashSet<Block::Hash>),
+		SubscriptionManagementError,
+	> {
+		let startup_point = StartupPoint::from(self.client.info());
+		let finalized = startup_point.finalized_hash;
+		let init = self.get_init_blocks_with_forks(&startup_point)?;
+
+		// The selected fork may have been pruned, report the descendants of the finalized block.
+		let _ = self.sub_handle.set_fork_filter(&self.sub_id, None);
+		self.fork_filtered.clear();
+		self.fork_filter_cache = None;
+
+		// The pinned blocks that are neither ancestors nor descendants of the finalized
+		// block were pruned.
+		let blockchain = self.backend.blockchain();
+		let pinned = self.sub_handle.pinned_blocks(&self.sub_id).unwrap_or_default();
+		let pruned_block_hashes = pinned
+			.into_iter()
+			.map(|(hash, _)| hash)
+			.filter(|hash| match sp_blockchain::tree_route(blockchain, finalized, *hash) {
+				Ok(tree_route) =>
+					!tree_route.retracted().is_empty() && !tree_route.enacted().is_empty(),
+				// The ancestry cannot be determined, the block is no longer available.
+				Err(_) => true,
+			})
+			.collect();
+
+		// The finalized block is registered by the summary, as it would be by the
+		// `Finalized` event.
+		self.sub_handle.pin_block(&self.sub_id, finalized)?;
+
+		let mut events = vec![FollowEvent::CatchUp(CatchUp {
+			finalized_block_hash: finalized,
+			finalized_block_runtime: self.generate_runtime_event(finalized, None),
+			pruned_block_hashes,
+		})];
+
+		let descendants =
+			if self.finalized_only { Vec::new() } else { init.finalized_block_descendants };
+		for (child, parent) in descendants {
+			if self.sub_handle.pin_block(&self.sub_id, child)? {
+				events.extend(self.generate_import_events(child, parent, false));
+			}
+		}
+
+		let best_block_hash = startup_point.best_hash;
+		if self.best_block_cache != Some(best_block_hash) {
+			self.best_block_cache = Some(best_block_hash);
+			events.push(FollowEvent::BestBlockChanged(BestBlockChanged { best_block_hash }));
+		}
+
+		Ok((events, startup_point, init.pruned_forks))
+	}
+
+	/// Returns true if the blocks of the finality notification were announced, or are not
+	/// reported by this subscription.
+	///
+	/// The blocks pruned by the notification are announced if their stale heads are.
+	fn is_finality_announced(
+		&self,
+		notification: &FinalityNotification<Block>,
+		to_ignore: &HashSet<Block::Hash>,
+		startup_point: &StartupPoint<Block>,
+	) -> bool {
+		// The blocks are not announced before they are finalized.
+		if self.finalized_only || *notification.header.number() < startup_point.finalized_number {
+			return true
+		}
+
+		notification
+			.tree_route
+			.iter()
+			.chain(std::iter::once(&notification.hash))
+			.chain(notification.stale_heads.iter())
+			.all(|hash| {
+				self.sub_handle.contains_block(&self.sub_id, *hash) ||
+					self.fork_filtered.contains(hash) ||
+					to_ignore.contains(hash)
+			})
+	}
+
+	/// Handle the held finality notifications, in order, whose blocks were announced.
+	///
+	/// The oldest notifications are handled regardless of their blocks while more than
+	/// `max_held` notifications are held.
+	///
+	/// This only reorders the notifications of a single burst: once the notifications that
+	/// are already available are drained, the held notifications are handled with a
+	/// `max_held` of zero. The blocks that are still unannounced are then reported by
+	/// `NewBlock` events generated from the finality notification itself, and their import
+	/// notifications arriving later are ignored. Holding the notifications until the matching
+	/// import notification instead would stall the finality of the blocks imported without
+	/// one.
+	fn release_held_finality(
+		&mut self,
+		held: &mut VecDeque<FinalityNotification<Block>>,
+		to_ignore: &mut HashSet<Block::Hash>,
+		startup_point: &StartupPoint<Block>,
+		max_held: usize,
+	) -> Result<Vec<FollowEvent<Block::Hash>>, SubscriptionManagementError> {
+		let mut events = Vec::new();
+		while let Some(notification) = held.front() {
+			if held.len() <= max_held &&
+				!self.is_finality_announced(notification, to_ignore, startup_point)
+			{
+				break
+			}
+
+			let Some(notification) = held.pop_front() else { break };
+			events.extend(self.handle_finalized_blocks(notification, to_ignore, startup_point)?);
+		}
+
+		Ok(events)
+	}
+
 	/// Submit the events from the provided stream to the RPC client
 	/// for as long as the `rx_stop` event was not called.
+	///
+	/// Returns true if the client disconnected.
 	async fn submit_events<EventStream>(
 		&mut self,
-		startup_point: &StartupPoint<Block>,
+		mut startup_point: StartupPoint<Block>,
 		mut stream: EventStream,
 		mut to_ignore: HashSet<Block::Hash>,
 		mut sink: SubscriptionSink,
-		rx_stop: oneshot::Receiver<()>,
-	) where
+		rx_stop: oneshot::Receiver<&'static str>,
+	) -> bool
+	where
 		EventStream: Stream<Item = NotificationType<Block>> + Unpin,
 	{
 		let mut stream_item = stream.next();
 		let mut stop_event = rx_stop;
-
-		while let Either::Left((Some(event), next_stop_event)) =
-			futures_util::future::select(stream_item, stop_event).await
-		{
-			let events = match event {
-				NotificationType::InitialEvents(events) => Ok(events),
-				NotificationType::NewBlock(notification) =>
-					self.handle_import_blocks(notification, &startup_point),
-				NotificationType::Finalized(notification) =>
-					self.handle_finalized_blocks(notification, &mut to_ignore, &startup_point),
-				NotificationType::MethodResponse(notification) => Ok(vec![notification]),
-			};
-
-			let events = match events {
-				Ok(events) => events,
-				Err(err) => {
-					debug!(
-						target: LOG_TARGET,
-						"[follow][id={:?}] Failed to handle stream notification {:?}",
-						self.sub_id,
-						err
-					);
-					let _ = sink.send(&FollowEvent::<String>::Stop);
-					return
-				},
-			};
-
-			for event in events {
-				let result = sink.send(&event);
-
-				// Migration note: the new version of jsonrpsee returns Result<(), DisconnectError>
-				// The logic from `Err(err)` should be moved when building the new
-				// `SubscriptionMessage`.
-
-				// For now, jsonrpsee returns:
-				// Ok(true): message sent
-				// Ok(false): client disconnected or subscription closed
-				// Err(err): serder serialization error of the event
-				if let Err(err) = result {
-					// Failed to submit event.
-					debug!(
-						target: LOG_TARGET,
-						"[follow][id={:?}] Failed to send event {:?}", self.sub_id, err
-					);
-
-					let _ = sink.send(&FollowEvent::<String>::Stop);
-					return
+		let mut held_finality = VecDeque::new();
+		// The block events are paused until the major sync of the node completes.
+		let mut paused = false;
+		// The latest finalized block reported by the `CatchUp` event.
+		let mut caught_up = None;
+
+		let reason = 'notifications: loop {
+			let (event, next_stop_event) =
+				match futures_util::future::select(stream_item, stop_event).await {
+					Either::Left((Some(event), next_stop_event)) => (event, next_stop_event),
+					Either::Left((None, _)) => {
+						self.health.notification_streams_closed();
+						break "notification_streams_closed"
+					},
+					Either::Right((reason, _)) => break reason.unwrap_or("stopped"),
+				};
+			self.health.notification_received();
+			self.health.busy();
+
+			// Handle the notifications that are already available before submitting
+			// the events, such that the held finality notifications are released last.
+			let mut notification = Some(event);
+			loop {
+				let drained = notification.is_none();
+				let events = match notification.take() {
+					// The initial events are always submitted.
+					Some(NotificationType::InitialEvents(events)) => Ok(events),
+					// The block notifications are summarized once the major sync completes,
+					// instead of generating the events of every synced block.
+					Some(NotificationType::NewBlock(_)) | Some(NotificationType::Finalized(_))
+						if self.is_major_syncing() =>
+					{
+						if !paused {
+							debug!(
+								target: LOG_TARGET,
+								"[follow][id={:?}] Block events paused during major sync",
+								self.sub_id
+							);
+							paused = true;
+							held_finality.clear();
+						}
+						Ok(vec![])
+					},
+					// The catch up events report the block of the notification.
+					Some(NotificationType::NewBlock(_)) | Some(NotificationType::Finalized(_))
+						if paused =>
+					{
+						paused = false;
+						self.generate_catch_up_events().map(|(events, point, pruned_forks)| {
+							caught_up = Some(point.finalized_hash);
+							startup_point = point;
+							to_ignore = pruned_forks;
+							events
+						})
+					},
+					Some(NotificationType::Finalized(notification))
+						if Some(notification.hash) == caught_up =>
+						Ok(vec![]),
+					Some(NotificationType::NewBlock(notification)) => self
+						.handle_import_blocks(notification, &startup_point)
+						.and_then(|mut events| {
+							// The import may announce the blocks of the held notifications.
+							events.extend(self.release_held_finality(
+								&mut held_finality,
+								&mut to_ignore,
+								&startup_point,
+								MAX_HELD_FINALITY,
+							)?);
+							Ok(events)
+						}),
+					Some(NotificationType::Finalized(notification)) => {
+						// The finality notification may be handled before the import
+						// notifications of its blocks, hold it until they are announced.
+						held_finality.push_back(notification);
+						self.release_held_finality(
+							&mut held_finality,
+							&mut to_ignore,
+							&startup_point,
+							MAX_HELD_FINALITY,
+						)
+					},
+					Some(NotificationType::MethodResponse(notification)) => {
+						self.sub_handle
+							.record_bandwidth(&self.sub_id, response_size(&notification));
+						Ok(vec![notification])
+					},
+					// No other notification is pending, such that the blocks of the held
+					// notifications were imported without being announced.
+					None => self.release_held_finality(
+						&mut held_finality,
+						&mut to_ignore,
+						&startup_point,
+						0,
+					),
+				};
+
+				let events = match events {
+					Ok(events) => events,
+					Err(err) => {
+						debug!(
+							target: LOG_TARGET,
+							"[follow][id={:?}] Failed to handle stream notification {:?}",
+							self.sub_id,
+							err
+						);
+						break 'notifications "notification_error"
+					},
+				};
+
+				match send_events(&mut sink, self.filter_events(events), &self.sub_id) {
+					Ok(()) => {},
+					Err(SendError::Disconnected) => return true,
+					Err(SendError::Serialization) => break 'notifications "send_error",
 				}
 
-				if let Ok(false) = result {
-					// Client disconnected or subscription was closed.
-					return
+				if drained {
+					break
 				}
+				notification = stream.next().now_or_never().flatten();
 			}
 
+			self.health.idle();
 			stream_item = stream.next();
 			stop_event = next_stop_event;
-		}
+		};
 
 		// If we got here either the substrate streams have closed
 		// or the `Stop` receiver was triggered.
-		let _ = sink.send(&FollowEvent::<String>::Stop);
+		self.stop(&mut sink, reason);
+		false
 	}
 
 	/// Generate the block events for the `chainHead_follow` method.
+	///
+	/// Returns true if the client disconnected without the subscription being stopped.
 	pub async fn generate_events(
 		&mut self,
 		mut sink: SubscriptionSink,
 		sub_data: InsertedSubscriptionData<Block>,
-	) {
+	) -> bool {
 		// Register for the new block and finalized notifications.
 		let stream_import = self
 			.client
-			.import_notification_stream()
+			.import_notifications()
 			.map(|notification| NotificationType::NewBlock(notification));
 
 		let stream_finalized = self
 			.client
-			.finality_notification_stream()
+			.finality_notifications()
 			.map(|notification| NotificationType::Finalized(notification));
 
 		let stream_responses = sub_data
@@ -602,8 +1252,8 @@ where
 					self.sub_id,
 					err
 				);
-				let _ = sink.send(&FollowEvent::<Block::Hash>::Stop);
-				return
+				self.stop(&mut sink, "initial_events_error");
+				return false
 			},
 		};
 
@@ -612,7 +1262,7 @@ where
 		let merged = tokio_stream::StreamExt::merge(merged, stream_responses);
 		let stream = stream::once(futures::future::ready(initial)).chain(merged);
 
-		self.submit_events(&startup_point, stream.boxed(), pruned_forks, sink, sub_data.rx_stop)
-			.await;
+		self.submit_events(startup_point, stream.boxed(), pruned_forks, sink, sub_data.rx_stop)
+			.await
 	}
 }
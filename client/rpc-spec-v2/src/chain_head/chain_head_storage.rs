@@ -18,25 +18,38 @@
 
 //! Implementation of the `chainHead_storage` method.
 
-use std::{collections::VecDeque, marker::PhantomData, sync::Arc};
+use std::{collections::VecDeque, marker::PhantomData, sync::Arc, time::Instant};
 
-use sc_client_api::{Backend, ChildInfo, StorageKey, StorageProvider};
+use sc_client_api::{Backend, ChildInfo, ProofProvider, StorageKey, StorageProvider};
 use sc_utils::mpsc::TracingUnboundedSender;
 use sp_api::BlockT;
-use sp_core::storage::well_known_keys;
+use sp_blockchain::{HeaderBackend, Result as BlockChainResult};
+use sp_core::storage::{well_known_keys, StorageData};
+use sp_runtime::traits::{Header as HeaderT, Zero};
+use sp_trie::MerkleValue;
 
 use crate::chain_head::event::OperationStorageItems;
 
+use log::debug;
+
 use super::{
+	cache::{CacheKey, CachedResult, OperationCache, OperationKind},
+	chain_head::LOG_TARGET,
+	chain_head_follow::response_size,
+	coalesce::InFlightOperations,
 	event::{
 		OperationError, OperationId, StorageQuery, StorageQueryType, StorageResult,
 		StorageResultType,
 	},
 	hex_string,
+	retry::BackendRetry,
 	subscription::BlockGuard,
 	FollowEvent,
 };
 
+/// The maximum number of ancestors queried by a `AncestorValues` storage query.
+const MAX_ANCESTOR_VALUES: u32 = 128;
+
 /// The query type of an interation.
 enum IterQueryType {
 	/// Iterating over (key, value) pairs.
@@ -46,33 +59,83 @@ enum IterQueryType {
 }
 
 /// Generates the events of the `chainHead_storage` method.
-pub struct ChainHeadStorage<Client, Block, BE> {
+pub struct ChainHeadStorage<Client, Block: BlockT, BE> {
 	/// Substrate client.
 	client: Arc<Client>,
+	/// The subscription that started the operation.
+	follow_subscription: String,
 	/// Queue of operations that may require pagination.
 	iter_operations: VecDeque<QueryIter>,
 	/// The maximum number of items reported by the `chainHead_storage` before
 	/// pagination is required.
 	operation_max_storage_items: usize,
+	/// Cache the results of identical storage queries.
+	cache: Arc<OperationCache<Block::Hash>>,
+	/// Coalesce the identical storage queries in progress.
+	in_flight: Arc<InFlightOperations<Block::Hash, Option<StorageResult>>>,
+	/// The number of bytes of the results served so far.
+	bytes_served: u64,
+	/// Retry the storage reads failing with a transient error.
+	retry: BackendRetry,
+	/// Report the read proof of the items.
+	include_proof: bool,
+	/// The operation is stopped once this instant is reached, if any.
+	deadline: Option<Instant>,
 	_phandom: PhantomData<(BE, Block)>,
 }
 
-impl<Client, Block, BE> ChainHeadStorage<Client, Block, BE> {
+impl<Client, Block: BlockT, BE> ChainHeadStorage<Client, Block, BE> {
 	/// Constructs a new [`ChainHeadStorage`].
-	pub fn new(client: Arc<Client>, operation_max_storage_items: usize) -> Self {
+	pub fn new(
+		client: Arc<Client>,
+		follow_subscription: String,
+		operation_max_storage_items: usize,
+		cache: Arc<OperationCache<Block::Hash>>,
+		in_flight: Arc<InFlightOperations<Block::Hash, Option<StorageResult>>>,
+		retry: BackendRetry,
+	) -> Self {
 		Self {
 			client,
+			follow_subscription,
 			iter_operations: VecDeque::new(),
 			operation_max_storage_items,
+			cache,
+			in_flight,
+			bytes_served: 0,
+			retry,
+			include_proof: false,
+			deadline: None,
 			_phandom: PhantomData,
 		}
 	}
+
+	/// Report the read proof of the items in the `OperationStorageItems` events.
+	pub fn with_proof(mut self, include_proof: bool) -> Self {
+		self.include_proof = include_proof;
+		self
+	}
+
+	/// Stop the operation and report the `OperationTimeout` event once the deadline is reached.
+	///
+	/// The deadline is checked between the reads of the items, between the keys of the
+	/// iterations and while waiting for `chainHead_continue`.
+	pub fn with_deadline(mut self, deadline: Option<Instant>) -> Self {
+		self.deadline = deadline;
+		self
+	}
+
+	/// Returns true if the deadline of the operation is reached.
+	fn deadline_reached(&self) -> bool {
+		self.deadline.map_or(false, |deadline| Instant::now() >= deadline)
+	}
 }
 
 /// Query to iterate over storage.
 struct QueryIter {
-	/// The next key from which the iteration should continue.
-	next_key: StorageKey,
+	/// The key whose descendants are iterated.
+	query_key: StorageKey,
+	/// The last key reported by the iteration, from which the iteration should continue.
+	pagination_start_key: Option<StorageKey>,
 	/// The type of the query (either value or hash).
 	ty: IterQueryType,
 }
@@ -91,14 +154,46 @@ fn is_key_queryable(key: &[u8]) -> bool {
 type QueryResult = Result<Option<StorageResult>, String>;
 
 /// The result of iterating over keys.
-type QueryIterResult = Result<(Vec<StorageResult>, Option<QueryIter>), String>;
+type QueryIterResult = Result<(Vec<(StorageKey, StorageResult)>, Option<QueryIter>), String>;
 
 impl<Client, Block, BE> ChainHeadStorage<Client, Block, BE>
 where
 	Block: BlockT + 'static,
 	BE: Backend<Block> + 'static,
-	Client: StorageProvider<Block, BE> + 'static,
+	Client: StorageProvider<Block, BE> + ProofProvider<Block> + HeaderBackend<Block> + 'static,
 {
+	/// Read the value from storage, retrying the transient errors.
+	fn read_storage(
+		&self,
+		hash: Block::Hash,
+		key: &StorageKey,
+		child_key: Option<&ChildInfo>,
+	) -> BlockChainResult<Option<StorageData>> {
+		self.retry.run(|| {
+			if let Some(child_key) = child_key {
+				self.client.child_storage(hash, child_key, key)
+			} else {
+				self.client.storage(hash, key)
+			}
+		})
+	}
+
+	/// Read the hash of a value from storage, retrying the transient errors.
+	fn read_storage_hash(
+		&self,
+		hash: Block::Hash,
+		key: &StorageKey,
+		child_key: Option<&ChildInfo>,
+	) -> BlockChainResult<Option<Block::Hash>> {
+		self.retry.run(|| {
+			if let Some(child_key) = child_key {
+				self.client.child_storage_hash(hash, child_key, key)
+			} else {
+				self.client.storage_hash(hash, key)
+			}
+		})
+	}
+
 	/// Fetch the value from storage.
 	fn query_storage_value(
 		&self,
@@ -106,11 +201,7 @@ where
 		key: &StorageKey,
 		child_key: Option<&ChildInfo>,
 	) -> QueryResult {
-		let result = if let Some(child_key) = child_key {
-			self.client.child_storage(hash, child_key, key)
-		} else {
-			self.client.storage(hash, key)
-		};
+		let result = self.read_storage(hash, key, child_key);
 
 		result
 			.map(|opt| {
@@ -129,11 +220,7 @@ where
 		key: &StorageKey,
 		child_key: Option<&ChildInfo>,
 	) -> QueryResult {
-		let result = if let Some(child_key) = child_key {
-			self.client.child_storage_hash(hash, child_key, key)
-		} else {
-			self.client.storage_hash(hash, key)
-		};
+		let result = self.read_storage_hash(hash, key, child_key);
 
 		result
 			.map(|opt| {
@@ -145,6 +232,190 @@ where
 			.unwrap_or_else(|error| QueryResult::Err(error.to_string()))
 	}
 
+	/// Check if the key has a value in storage.
+	///
+	/// Only the hash of the value is read from the trie, which is cheaper for large values.
+	fn query_storage_exists(
+		&self,
+		hash: Block::Hash,
+		key: &StorageKey,
+		child_key: Option<&ChildInfo>,
+	) -> QueryResult {
+		let result = self.read_storage_hash(hash, key, child_key);
+
+		result
+			.map(|opt| {
+				QueryResult::Ok(Some(StorageResult {
+					key: hex_string(&key.0),
+					result: StorageResultType::Exists(opt.is_some()),
+				}))
+			})
+			.unwrap_or_else(|error| QueryResult::Err(error.to_string()))
+	}
+
+	/// Fetch the length of a value from storage.
+	fn query_storage_value_length(
+		&self,
+		hash: Block::Hash,
+		key: &StorageKey,
+		child_key: Option<&ChildInfo>,
+	) -> QueryResult {
+		let result = self.read_storage(hash, key, child_key);
+
+		result
+			.map(|opt| {
+				QueryResult::Ok(opt.map(|storage_data| StorageResult {
+					key: hex_string(&key.0),
+					result: StorageResultType::ValueLength(storage_data.0.len() as u32),
+				}))
+			})
+			.unwrap_or_else(|error| QueryResult::Err(error.to_string()))
+	}
+
+	/// Fetch the merkle value of the closest descendant node of the key.
+	///
+	/// The merkle value is the hash of the node, or the node itself for the nodes that are
+	/// inlined in their parent.
+	fn query_storage_merkle_value(
+		&self,
+		hash: Block::Hash,
+		key: &StorageKey,
+		child_key: Option<&ChildInfo>,
+	) -> QueryResult {
+		let result = self.retry.run(|| {
+			if let Some(child_key) = child_key {
+				self.client.child_closest_merkle_value(hash, child_key, key)
+			} else {
+				self.client.closest_merkle_value(hash, key)
+			}
+		});
+
+		result
+			.map(|opt| {
+				QueryResult::Ok(opt.map(|merkle_value| {
+					let merkle_value = match merkle_value {
+						MerkleValue::Node(data) => hex_string(&data),
+						MerkleValue::Hash(hash) => hex_string(&hash.as_ref()),
+					};
+					StorageResult {
+						key: hex_string(&key.0),
+						result: StorageResultType::ClosestDescendantMerkleValue(merkle_value),
+					}
+				}))
+			})
+			.unwrap_or_else(|error| QueryResult::Err(error.to_string()))
+	}
+
+	/// Fetch the values of a key at the block and at up to `ancestors` of its ancestors.
+	///
+	/// The series is ordered from the block to its oldest ancestor. It ends early at the
+	/// genesis block or at the first ancestor whose state is no longer available.
+	fn query_storage_ancestor_values(
+		&self,
+		hash: Block::Hash,
+		key: &StorageKey,
+		child_key: Option<&ChildInfo>,
+		ancestors: u32,
+	) -> QueryResult {
+		let query_value = |hash: Block::Hash| {
+			self.read_storage(hash, key, child_key)
+				.map(|opt| opt.map(|storage_data| hex_string(&storage_data.0)))
+		};
+
+		let mut values = vec![query_value(hash).map_err(|error| error.to_string())?];
+		let mut current = hash;
+		for _ in 0..ancestors.min(MAX_ANCESTOR_VALUES) {
+			let parent = match self.client.header(current) {
+				Ok(Some(header)) if !header.number().is_zero() => *header.parent_hash(),
+				_ => break,
+			};
+
+			let Ok(value) = query_value(parent) else { break };
+			values.push(value);
+			current = parent;
+		}
+
+		Ok(Some(StorageResult {
+			key: hex_string(&key.0),
+			result: StorageResultType::AncestorValues(values),
+		}))
+	}
+
+	/// Fetch the value or the hash of a value from storage, using the operation cache.
+	///
+	/// The identical queries of the subscription in progress are coalesced.
+	fn query_storage_cached(
+		&self,
+		hash: Block::Hash,
+		key: &StorageKey,
+		child_key: Option<&ChildInfo>,
+		ty: IterQueryType,
+	) -> QueryResult {
+		let is_hash = matches!(ty, IterQueryType::Hash);
+		let cache_key = CacheKey::new(
+			hash,
+			OperationKind::Storage,
+			&(is_hash, child_key.map(|child_key| child_key.storage_key()), &key.0),
+		);
+
+		if let Some(CachedResult::Storage(result)) = self.cache.get(&cache_key) {
+			return Ok(result)
+		}
+
+		self.in_flight.run(&self.follow_subscription, &cache_key, || {
+			let result = if is_hash {
+				self.query_storage_hash(hash, key, child_key)
+			} else {
+				self.query_storage_value(hash, key, child_key)
+			}?;
+
+			if self.cache.is_enabled() {
+				self.cache.insert(cache_key.clone(), CachedResult::Storage(result.clone()));
+			}
+			Ok(result)
+		})
+	}
+
+	/// Generate the read proof of the keys, retrying the transient errors.
+	///
+	/// The proof is the list of the hexadecimal-encoded trie nodes.
+	fn read_proof(
+		&self,
+		hash: Block::Hash,
+		keys: &[StorageKey],
+		child_key: Option<&ChildInfo>,
+	) -> Result<Vec<String>, String> {
+		let proof = self
+			.retry
+			.run(|| {
+				let mut keys = keys.iter().map(|key| key.0.as_slice());
+				if let Some(child_key) = child_key {
+					self.client.read_child_proof(hash, child_key, &mut keys)
+				} else {
+					self.client.read_proof(hash, &mut keys)
+				}
+			})
+			.map_err(|err| err.to_string())?;
+
+		Ok(proof.into_iter_nodes().map(|node| hex_string(&node)).collect())
+	}
+
+	/// Build the event reporting the items, together with the read proof of their keys
+	/// if requested.
+	fn storage_items_event(
+		&self,
+		operation_id: String,
+		hash: Block::Hash,
+		child_key: Option<&ChildInfo>,
+		items: Vec<(StorageKey, StorageResult)>,
+	) -> Result<FollowEvent<Block::Hash>, String> {
+		let (keys, items): (Vec<_>, Vec<_>) = items.into_iter().unzip();
+		let proof =
+			if self.include_proof { Some(self.read_proof(hash, &keys, child_key)?) } else { None };
+
+		Ok(FollowEvent::OperationStorageItems(OperationStorageItems { operation_id, items, proof }))
+	}
+
 	/// Iterate over at most `operation_max_storage_items` keys.
 	///
 	/// Returns the storage result with a potential next key to resume iteration.
@@ -154,18 +425,31 @@ where
 		hash: Block::Hash,
 		child_key: Option<&ChildInfo>,
 	) -> QueryIterResult {
-		let QueryIter { next_key, ty } = query;
-
-		let mut keys_iter = if let Some(child_key) = child_key {
-			self.client
-				.child_storage_keys(hash, child_key.to_owned(), Some(&next_key), None)
-		} else {
-			self.client.storage_keys(hash, Some(&next_key), None)
-		}
-		.map_err(|err| err.to_string())?;
+		let QueryIter { query_key, pagination_start_key, ty } = query;
+
+		let mut keys_iter = self
+			.retry
+			.run(|| {
+				if let Some(child_key) = child_key {
+					self.client.child_storage_keys(
+						hash,
+						child_key.to_owned(),
+						Some(&query_key),
+						pagination_start_key.as_ref(),
+					)
+				} else {
+					self.client.storage_keys(hash, Some(&query_key), pagination_start_key.as_ref())
+				}
+			})
+			.map_err(|err| err.to_string())?;
 
 		let mut ret = Vec::with_capacity(self.operation_max_storage_items);
+		let mut last_key = None;
 		for _ in 0..self.operation_max_storage_items {
+			// The caller reports the timeout after sending the items read so far.
+			if self.deadline_reached() {
+				break
+			}
 			let Some(key) = keys_iter.next() else {
 				break
 			};
@@ -176,12 +460,15 @@ where
 			}?;
 
 			if let Some(value) = result {
-				ret.push(value);
+				ret.push((key.clone(), value));
 			}
+			last_key = Some(key);
 		}
 
-		// Save the next key if any to continue the iteration.
-		let maybe_next_query = keys_iter.next().map(|next_key| QueryIter { next_key, ty });
+		// Continue the iteration after the last key, if more keys are available.
+		let has_more_keys = keys_iter.next().is_some();
+		let maybe_next_query =
+			has_more_keys.then(|| QueryIter { query_key, pagination_start_key: last_key, ty });
 		Ok((ret, maybe_next_query))
 	}
 
@@ -200,6 +487,10 @@ where
 			if operation.was_stopped() {
 				return
 			}
+			if self.deadline_reached() {
+				send_timeout::<Block>(&sender, operation.operation_id());
+				return
+			}
 
 			let result = self.query_storage_iter_pagination(query, hash, child_key.as_ref());
 			let (events, maybe_next_query) = match result {
@@ -212,12 +503,27 @@ where
 
 			if !events.is_empty() {
 				// Send back the results of the iteration produced so far.
-				let _ = sender.unbounded_send(FollowEvent::<Block::Hash>::OperationStorageItems(
-					OperationStorageItems { operation_id: operation.operation_id(), items: events },
-				));
+				let event = match self.storage_items_event(
+					operation.operation_id(),
+					hash,
+					child_key.as_ref(),
+					events,
+				) {
+					Ok(event) => event,
+					Err(error) => {
+						send_error::<Block>(&sender, operation.operation_id(), error);
+						return
+					},
+				};
+				self.bytes_served += response_size(&event);
+				let _ = sender.unbounded_send(event);
 			}
 
 			if let Some(next_query) = maybe_next_query {
+				if self.deadline_reached() {
+					send_timeout::<Block>(&sender, operation.operation_id());
+					return
+				}
 				let _ =
 					sender.unbounded_send(FollowEvent::<Block::Hash>::OperationWaitingForContinue(
 						OperationId { operation_id: operation.operation_id() },
@@ -225,7 +531,16 @@ where
 
 				// The operation might be continued or cancelled only after the
 				// `OperationWaitingForContinue` is generated above.
-				operation.wait_for_continue().await;
+				match self.deadline {
+					Some(deadline) => {
+						let wait = operation.wait_for_continue();
+						if tokio::time::timeout_at(deadline.into(), wait).await.is_err() {
+							send_timeout::<Block>(&sender, operation.operation_id());
+							return
+						}
+					},
+					None => operation.wait_for_continue().await,
+				}
 
 				// Give a chance for the other items to advance next time.
 				self.iter_operations.push_back(next_query);
@@ -243,7 +558,21 @@ where
 	}
 
 	/// Generate the block events for the `chainHead_storage` method.
+	///
+	/// Returns the number of bytes of the results served by the operation.
 	pub async fn generate_events(
+		&mut self,
+		block_guard: BlockGuard<Block, BE>,
+		hash: Block::Hash,
+		items: Vec<StorageQuery<StorageKey>>,
+		child_key: Option<ChildInfo>,
+	) -> u64 {
+		self.generate_storage_events(block_guard, hash, items, child_key).await;
+		self.bytes_served
+	}
+
+	/// Generate the events of the queried items, followed by the events of the iterations.
+	async fn generate_storage_events(
 		&mut self,
 		mut block_guard: BlockGuard<Block, BE>,
 		hash: Block::Hash,
@@ -264,14 +593,28 @@ where
 
 		let mut storage_results = Vec::with_capacity(items.len());
 		for item in items {
+			// The block guard is released as soon as the operation is stopped.
+			if operation.was_stopped() {
+				return
+			}
+			if self.deadline_reached() {
+				send_timeout::<Block>(&sender, operation.operation_id());
+				return
+			}
+
 			if !is_key_queryable(&item.key.0) {
 				continue
 			}
 
 			match item.query_type {
 				StorageQueryType::Value => {
-					match self.query_storage_value(hash, &item.key, child_key.as_ref()) {
-						Ok(Some(value)) => storage_results.push(value),
+					match self.query_storage_cached(
+						hash,
+						&item.key,
+						child_key.as_ref(),
+						IterQueryType::Value,
+					) {
+						Ok(Some(value)) => storage_results.push((item.key.clone(), value)),
 						Ok(None) => continue,
 						Err(error) => {
 							send_error::<Block>(&sender, operation.operation_id(), error);
@@ -280,31 +623,84 @@ where
 					}
 				},
 				StorageQueryType::Hash =>
-					match self.query_storage_hash(hash, &item.key, child_key.as_ref()) {
-						Ok(Some(value)) => storage_results.push(value),
+					match self.query_storage_cached(
+						hash,
+						&item.key,
+						child_key.as_ref(),
+						IterQueryType::Hash,
+					) {
+						Ok(Some(value)) => storage_results.push((item.key.clone(), value)),
+						Ok(None) => continue,
+						Err(error) => {
+							send_error::<Block>(&sender, operation.operation_id(), error);
+							return
+						},
+					},
+				StorageQueryType::Exists =>
+					match self.query_storage_exists(hash, &item.key, child_key.as_ref()) {
+						Ok(Some(value)) => storage_results.push((item.key.clone(), value)),
 						Ok(None) => continue,
 						Err(error) => {
 							send_error::<Block>(&sender, operation.operation_id(), error);
 							return
 						},
 					},
-				StorageQueryType::DescendantsValues => self
-					.iter_operations
-					.push_back(QueryIter { next_key: item.key, ty: IterQueryType::Value }),
-				StorageQueryType::DescendantsHashes => self
-					.iter_operations
-					.push_back(QueryIter { next_key: item.key, ty: IterQueryType::Hash }),
-				_ => continue,
+				StorageQueryType::ValueLength =>
+					match self.query_storage_value_length(hash, &item.key, child_key.as_ref()) {
+						Ok(Some(value)) => storage_results.push((item.key.clone(), value)),
+						Ok(None) => continue,
+						Err(error) => {
+							send_error::<Block>(&sender, operation.operation_id(), error);
+							return
+						},
+					},
+				StorageQueryType::AncestorValues { ancestors } => match self
+					.query_storage_ancestor_values(hash, &item.key, child_key.as_ref(), ancestors)
+				{
+					Ok(Some(value)) => storage_results.push((item.key.clone(), value)),
+					Ok(None) => continue,
+					Err(error) => {
+						send_error::<Block>(&sender, operation.operation_id(), error);
+						return
+					},
+				},
+				StorageQueryType::ClosestDescendantMerkleValue =>
+					match self.query_storage_merkle_value(hash, &item.key, child_key.as_ref()) {
+						Ok(Some(value)) => storage_results.push((item.key.clone(), value)),
+						Ok(None) => continue,
+						Err(error) => {
+							send_error::<Block>(&sender, operation.operation_id(), error);
+							return
+						},
+					},
+				StorageQueryType::DescendantsValues => self.iter_operations.push_back(QueryIter {
+					query_key: item.key,
+					pagination_start_key: None,
+					ty: IterQueryType::Value,
+				}),
+				StorageQueryType::DescendantsHashes => self.iter_operations.push_back(QueryIter {
+					query_key: item.key,
+					pagination_start_key: None,
+					ty: IterQueryType::Hash,
+				}),
 			};
 		}
 
 		if !storage_results.is_empty() {
-			let _ = sender.unbounded_send(FollowEvent::<Block::Hash>::OperationStorageItems(
-				OperationStorageItems {
-					operation_id: operation.operation_id(),
-					items: storage_results,
+			let event = match self.storage_items_event(
+				operation.operation_id(),
+				hash,
+				child_key.as_ref(),
+				storage_results,
+			) {
+				Ok(event) => event,
+				Err(error) => {
+					send_error::<Block>(&sender, operation.operation_id(), error);
+					return
 				},
-			));
+			};
+			self.bytes_served += response_size(&event);
+			let _ = sender.unbounded_send(event);
 		}
 
 		self.generate_storage_iter_events(block_guard, hash, child_key).await
@@ -322,3 +718,13 @@ fn send_error<Block: BlockT>(
 		error,
 	}));
 }
+
+/// Report the operation stopped by its deadline back to the `chainHead_follow` method.
+fn send_timeout<Block: BlockT>(
+	sender: &TracingUnboundedSender<FollowEvent<Block::Hash>>,
+	operation_id: String,
+) {
+	debug!(target: LOG_TARGET, "[storage][id={:?}] Operation timed out", operation_id);
+	let _ = sender
+		.unbounded_send(FollowEvent::<Block::Hash>::OperationTimeout(OperationId { operation_id }));
+}
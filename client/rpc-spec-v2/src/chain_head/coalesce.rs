@@ -0,0 +1,226 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Coalescing of the identical operations in progress.
+//!
+//! A client may start the same expensive operation several times concurrently, for
+//! example when it retries a request that timed out on its side. An operation started
+//! while an identical operation of the same subscription is in progress waits for the
+//! result of the latter, instead of executing the work twice.
+
+use crate::chain_head::{cache::CacheKey, metrics::MetricsLink};
+use parking_lot::{Condvar, Mutex};
+use std::{collections::HashMap, hash::Hash as StdHash, sync::Arc};
+
+/// The key of an operation in progress: the subscription that started it and the
+/// operation itself.
+type InFlightKey<Hash> = (String, CacheKey<Hash>);
+
+/// An operation in progress.
+struct InFlight<T> {
+	/// The result of the operation, once completed.
+	result: Mutex<Option<Result<T, String>>>,
+	/// Notified when the operation completed.
+	completed: Condvar,
+}
+
+/// The operations in progress, keyed by subscription, block hash, operation kind and
+/// parameters.
+pub struct InFlightOperations<Hash, T> {
+	/// The operations in progress, `None` if the coalescing is disabled.
+	operations: Option<Mutex<HashMap<InFlightKey<Hash>, Arc<InFlight<T>>>>>,
+	/// Report the coalesced operations.
+	metrics: MetricsLink,
+}
+
+impl<Hash: StdHash + Eq + Clone, T: Clone> InFlightOperations<Hash, T> {
+	/// Construct a new [`InFlightOperations`].
+	pub fn new(enabled: bool, metrics: MetricsLink) -> Self {
+		InFlightOperations { operations: enabled.then(Default::default), metrics }
+	}
+
+	/// Execute the operation, unless an identical operation of the subscription is in
+	/// progress.
+	///
+	/// In the latter case, the current thread is blocked until the operation in progress
+	/// completes and its result is returned. This must be called from the blocking methods
+	/// or tasks.
+	pub fn run(
+		&self,
+		subscription: &str,
+		key: &CacheKey<Hash>,
+		operation: impl FnOnce() -> Result<T, String>,
+	) -> Result<T, String> {
+		let Some(operations) = self.operations.as_ref() else { return operation() };
+
+		let key = (subscription.to_owned(), key.clone());
+		let in_flight = {
+			let mut operations = operations.lock();
+			match operations.get(&key) {
+				Some(in_flight) => Some(in_flight.clone()),
+				None => {
+					let in_flight =
+						Arc::new(InFlight { result: Mutex::new(None), completed: Condvar::new() });
+					operations.insert(key.clone(), in_flight.clone());
+					None
+				},
+			}
+		};
+
+		if let Some(in_flight) = in_flight {
+			self.metrics.report(|metrics| metrics.operations_coalesced.inc());
+
+			let mut result = in_flight.result.lock();
+			while result.is_none() {
+				in_flight.completed.wait(&mut result);
+			}
+			return result.clone().expect("The result is set before the waiters are notified; qed")
+		}
+
+		// The guard completes the operation even if the execution panics, such that the
+		// waiters are never blocked forever.
+		let guard = CompleteOnDrop { operations, key };
+		let result = operation();
+		guard.complete(result.clone());
+		result
+	}
+}
+
+/// Complete the operation in progress when dropped.
+struct CompleteOnDrop<'a, Hash: StdHash + Eq, T> {
+	/// The operations in progress.
+	operations: &'a Mutex<HashMap<InFlightKey<Hash>, Arc<InFlight<T>>>>,
+	/// The key of the operation.
+	key: InFlightKey<Hash>,
+}
+
+impl<'a, Hash: StdHash + Eq, T> CompleteOnDrop<'a, Hash, T> {
+	/// Complete the operation with the given result.
+	fn complete(self, result: Result<T, String>) {
+		if let Some(in_flight) = self.operations.lock().get(&self.key) {
+			*in_flight.result.lock() = Some(result);
+		}
+	}
+}
+
+impl<'a, Hash: StdHash + Eq, T> Drop for CompleteOnDrop<'a, Hash, T> {
+	fn drop(&mut self) {
+		// New operations are executed from now on.
+		let Some(in_flight) = self.operations.lock().remove(&self.key) else { return };
+
+		in_flight
+			.result
+			.lock()
+			.get_or_insert_with(|| Err("The coalesced operation was aborted".into()));
+		in_flight.completed.notify_all();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::chain_head::cache::OperationKind;
+	use std::{sync::mpsc, thread, time::Duration};
+
+	#[test]
+	fn concurrent_operations_are_coalesced() {
+		let in_flight = Arc::new(InFlightOperations::<u64, u32>::new(true, Default::default()));
+		let key = CacheKey::new(1, OperationKind::Call, &"Core_version");
+
+		let (started_tx, started_rx) = mpsc::channel();
+		let (release_tx, release_rx) = mpsc::channel::<()>();
+		let first = thread::spawn({
+			let (in_flight, key) = (in_flight.clone(), key.clone());
+			move || {
+				in_flight.run("sub", &key, || {
+					started_tx.send(()).unwrap();
+					release_rx.recv().unwrap();
+					Ok(1)
+				})
+			}
+		});
+		started_rx.recv().unwrap();
+
+		let second = thread::spawn({
+			let (in_flight, key) = (in_flight.clone(), key.clone());
+			move || in_flight.run("sub", &key, || Ok(2))
+		});
+
+		// Wait for the second operation to join the first one.
+		let operation_key = ("sub".to_string(), key.clone());
+		while Arc::strong_count(&in_flight.operations.as_ref().unwrap().lock()[&operation_key]) < 2
+		{
+			thread::sleep(Duration::from_millis(1));
+		}
+
+		// Operations of other subscriptions are executed.
+		assert_eq!(in_flight.run("other", &key, || Ok(3)), Ok(3));
+
+		release_tx.send(()).unwrap();
+		assert_eq!(first.join().unwrap(), Ok(1));
+		assert_eq!(second.join().unwrap(), Ok(1));
+
+		// The completed operations are executed again.
+		assert_eq!(in_flight.run("sub", &key, || Ok(4)), Ok(4));
+		assert!(in_flight.operations.as_ref().unwrap().lock().is_empty());
+	}
+
+	#[test]
+	fn aborted_operation_releases_waiters() {
+		let in_flight = Arc::new(InFlightOperations::<u64, u32>::new(true, Default::default()));
+		let key = CacheKey::new(1, OperationKind::Body, &());
+
+		let (started_tx, started_rx) = mpsc::channel();
+		let (release_tx, release_rx) = mpsc::channel::<()>();
+		let first = thread::spawn({
+			let (in_flight, key) = (in_flight.clone(), key.clone());
+			move || {
+				in_flight.run("sub", &key, || {
+					started_tx.send(()).unwrap();
+					release_rx.recv().unwrap();
+					panic!("The operation panicked")
+				})
+			}
+		});
+		started_rx.recv().unwrap();
+
+		let second = thread::spawn({
+			let (in_flight, key) = (in_flight.clone(), key.clone());
+			move || in_flight.run("sub", &key, || Ok(2))
+		});
+
+		let operation_key = ("sub".to_string(), key.clone());
+		while Arc::strong_count(&in_flight.operations.as_ref().unwrap().lock()[&operation_key]) < 2
+		{
+			thread::sleep(Duration::from_millis(1));
+		}
+
+		release_tx.send(()).unwrap();
+		assert!(first.join().is_err());
+		assert!(second.join().unwrap().is_err());
+	}
+
+	#[test]
+	fn disabled_coalescing_executes_operations() {
+		let in_flight = InFlightOperations::<u64, u32>::new(false, Default::default());
+		let key = CacheKey::new(1, OperationKind::Body, &());
+
+		assert_eq!(in_flight.run("sub", &key, || Ok(1)), Ok(1));
+		assert_eq!(in_flight.run("sub", &key, || Err("error".into())), Err("error".into()));
+	}
+}
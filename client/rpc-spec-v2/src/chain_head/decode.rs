@@ -0,0 +1,390 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Decode the output of the runtime calls as JSON using the runtime metadata.
+
+use crate::chain_head::hex_string;
+use codec::{Compact, Decode, Encode};
+use frame_metadata::{
+	v15::{RuntimeApiMethodMetadata, RuntimeMetadataV15, StorageEntryType},
+	RuntimeMetadata, RuntimeMetadataPrefixed,
+};
+use parking_lot::Mutex;
+use sc_client_api::{CallExecutor, ExecutorProvider};
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef, TypeDefPrimitive};
+use serde_json::{Map, Value};
+use sp_api::{CallApiAt, RuntimeVersion};
+use sp_core::{traits::CallContext, OpaqueMetadata};
+use sp_runtime::traits::Block as BlockT;
+use std::sync::Arc;
+
+/// The runtime function returning the metadata at a given version.
+const METADATA_AT_VERSION: &str = "Metadata_metadata_at_version";
+
+/// The first metadata version describing the runtime API functions.
+const METADATA_VERSION: u32 = 15;
+
+/// Decode the output of the `chainHead_call` runtime functions.
+///
+/// The metadata of the most recently used runtime is kept around, since consecutive calls
+/// are very likely made against the same runtime.
+#[derive(Default)]
+pub struct CallOutputDecoder {
+	metadata: Mutex<Option<(RuntimeVersion, Arc<RuntimeMetadataV15>)>>,
+}
+
+impl CallOutputDecoder {
+	/// Decode the output of the runtime function called at the given block.
+	pub fn decode<Block, Client>(
+		&self,
+		client: &Client,
+		hash: Block::Hash,
+		function: &str,
+		output: &[u8],
+	) -> Result<Value, String>
+	where
+		Block: BlockT,
+		Client: CallApiAt<Block> + ExecutorProvider<Block>,
+	{
+		let metadata = self.metadata(client, hash)?;
+		decode_call_output(&metadata, function, output)
+	}
+
+	/// Get the runtime metadata at the given block.
+	pub(crate) fn metadata<Block, Client>(
+		&self,
+		client: &Client,
+		hash: Block::Hash,
+	) -> Result<Arc<RuntimeMetadataV15>, String>
+	where
+		Block: BlockT,
+		Client: CallApiAt<Block> + ExecutorProvider<Block>,
+	{
+		let version = client.runtime_version_at(hash).map_err(|error| error.to_string())?;
+
+		if let Some((cached_version, metadata)) = self.metadata.lock().as_ref() {
+			if *cached_version == version {
+				return Ok(metadata.clone())
+			}
+		}
+
+		let bytes = client
+			.executor()
+			.call(hash, METADATA_AT_VERSION, &METADATA_VERSION.encode(), CallContext::Offchain)
+			.map_err(|error| error.to_string())?;
+		let metadata = Arc::new(decode_metadata(&bytes)?);
+
+		*self.metadata.lock() = Some((version, metadata.clone()));
+		Ok(metadata)
+	}
+}
+
+/// Decode the output of the `Metadata_metadata_at_version` runtime function.
+fn decode_metadata(bytes: &[u8]) -> Result<RuntimeMetadataV15, String> {
+	let metadata = Option::<OpaqueMetadata>::decode(&mut &bytes[..])
+		.map_err(|error| format!("Invalid metadata: {}", error))?
+		.ok_or_else(|| format!("The runtime does not provide metadata V{}", METADATA_VERSION))?;
+
+	match RuntimeMetadataPrefixed::decode(&mut &metadata[..]) {
+		Ok(RuntimeMetadataPrefixed(_, RuntimeMetadata::V15(metadata))) => Ok(metadata),
+		Ok(_) => Err(format!("The runtime does not provide metadata V{}", METADATA_VERSION)),
+		Err(error) => Err(format!("Invalid metadata: {}", error)),
+	}
+}
+
+/// Get the metadata of the runtime function, named `<api>_<method>`.
+pub(crate) fn runtime_function<'a>(
+	metadata: &'a RuntimeMetadataV15,
+	function: &str,
+) -> Result<&'a RuntimeApiMethodMetadata<PortableForm>, String> {
+	metadata
+		.apis
+		.iter()
+		.flat_map(|api| api.methods.iter().map(move |method| (api, method)))
+		.find(|(api, method)| function == format!("{}_{}", api.name, method.name))
+		.map(|(_, method)| method)
+		.ok_or_else(|| format!("The function {} is not described by the metadata", function))
+}
+
+/// Decode the output of the given runtime function as described by the metadata.
+pub(crate) fn decode_call_output(
+	metadata: &RuntimeMetadataV15,
+	function: &str,
+	output: &[u8],
+) -> Result<Value, String> {
+	let type_id = runtime_function(metadata, function)?.output.id;
+
+	let mut input = output;
+	let value = decode_value(&metadata.types, type_id, &mut input)?;
+	if !input.is_empty() {
+		return Err(format!("{} bytes left after decoding the output", input.len()))
+	}
+
+	Ok(value)
+}
+
+/// Decode the value of a plain storage entry of the pallet as described by the metadata.
+pub(crate) fn decode_storage_value(
+	metadata: &RuntimeMetadataV15,
+	pallet: &str,
+	entry: &str,
+	value: &[u8],
+) -> Result<Value, String> {
+	let ty = metadata
+		.pallets
+		.iter()
+		.find(|metadata| metadata.name == pallet)
+		.and_then(|metadata| metadata.storage.as_ref())
+		.and_then(|storage| storage.entries.iter().find(|metadata| metadata.name == entry))
+		.map(|metadata| &metadata.ty);
+	let type_id = match ty {
+		Some(StorageEntryType::Plain(ty)) => ty.id,
+		Some(StorageEntryType::Map { .. }) =>
+			return Err(format!("The storage entry {}::{} is a map", pallet, entry)),
+		None =>
+			return Err(format!(
+				"The storage entry {}::{} is not described by the metadata",
+				pallet, entry
+			)),
+	};
+
+	let mut input = value;
+	let value = decode_value(&metadata.types, type_id, &mut input)?;
+	if !input.is_empty() {
+		return Err(format!("{} bytes left after decoding the storage value", input.len()))
+	}
+
+	Ok(value)
+}
+
+/// Decode a value of the given type.
+///
+/// Composites with named fields are decoded as objects, variants as their name or as an
+/// object keyed by their name, byte sequences as hexadecimal strings and 128-bit integers
+/// as decimal strings.
+fn decode_value(
+	registry: &PortableRegistry,
+	type_id: u32,
+	input: &mut &[u8],
+) -> Result<Value, String> {
+	let ty = registry.resolve(type_id).ok_or_else(|| format!("Unknown type {}", type_id))?;
+
+	match &ty.type_def {
+		TypeDef::Composite(composite) => decode_fields(registry, &composite.fields, input),
+		TypeDef::Variant(variant) => {
+			let index = u8::decode(input).map_err(|error| error.to_string())?;
+			let variant = variant
+				.variants
+				.iter()
+				.find(|variant| variant.index == index)
+				.ok_or_else(|| format!("Unknown variant {} of type {}", index, type_id))?;
+
+			if variant.fields.is_empty() {
+				return Ok(Value::String(variant.name.clone()))
+			}
+
+			let mut object = Map::new();
+			object.insert(variant.name.clone(), decode_fields(registry, &variant.fields, input)?);
+			Ok(Value::Object(object))
+		},
+		TypeDef::Sequence(sequence) => {
+			let len = Compact::<u32>::decode(input).map_err(|error| error.to_string())?.0;
+			decode_items(registry, sequence.type_param.id, len as usize, input)
+		},
+		TypeDef::Array(array) =>
+			decode_items(registry, array.type_param.id, array.len as usize, input),
+		TypeDef::Tuple(tuple) if tuple.fields.is_empty() => Ok(Value::Null),
+		TypeDef::Tuple(tuple) => tuple
+			.fields
+			.iter()
+			.map(|field| decode_value(registry, field.id, input))
+			.collect::<Result<_, _>>()
+			.map(Value::Array),
+		TypeDef::Primitive(primitive) => decode_primitive(primitive, input),
+		TypeDef::Compact(compact) => decode_compact(registry, compact.type_param.id, input),
+		TypeDef::BitSequence(_) => Err("Bit sequences are not supported".into()),
+	}
+}
+
+/// Decode the fields of a composite or of a variant.
+fn decode_fields(
+	registry: &PortableRegistry,
+	fields: &[Field<PortableForm>],
+	input: &mut &[u8],
+) -> Result<Value, String> {
+	match fields {
+		[] => Ok(Value::Null),
+		[field] if field.name.is_none() => decode_value(registry, field.ty.id, input),
+		fields if fields.iter().all(|field| field.name.is_some()) => fields
+			.iter()
+			.map(|field| {
+				let name = field.name.clone().unwrap_or_default();
+				Ok((name, decode_value(registry, field.ty.id, input)?))
+			})
+			.collect::<Result<Map<_, _>, String>>()
+			.map(Value::Object),
+		fields => fields
+			.iter()
+			.map(|field| decode_value(registry, field.ty.id, input))
+			.collect::<Result<_, _>>()
+			.map(Value::Array),
+	}
+}
+
+/// Decode the items of a sequence or of an array.
+fn decode_items(
+	registry: &PortableRegistry,
+	type_id: u32,
+	len: usize,
+	input: &mut &[u8],
+) -> Result<Value, String> {
+	let is_byte = registry
+		.resolve(type_id)
+		.map_or(false, |ty| matches!(ty.type_def, TypeDef::Primitive(TypeDefPrimitive::U8)));
+
+	if is_byte {
+		if input.len() < len {
+			return Err("Not enough data to decode the bytes".into())
+		}
+		let (bytes, rest) = input.split_at(len);
+		*input = rest;
+		return Ok(Value::String(hex_string(&bytes)))
+	}
+
+	(0..len)
+		.map(|_| decode_value(registry, type_id, input))
+		.collect::<Result<_, _>>()
+		.map(Value::Array)
+}
+
+/// Decode a primitive value.
+fn decode_primitive(primitive: &TypeDefPrimitive, input: &mut &[u8]) -> Result<Value, String> {
+	fn decode<T: Decode>(input: &mut &[u8]) -> Result<T, String> {
+		T::decode(input).map_err(|error| error.to_string())
+	}
+
+	let value = match primitive {
+		TypeDefPrimitive::Bool => Value::Bool(decode(input)?),
+		TypeDefPrimitive::Char => {
+			let code = decode::<u32>(input)?;
+			let char = char::from_u32(code).ok_or_else(|| format!("Invalid char {}", code))?;
+			Value::String(char.into())
+		},
+		TypeDefPrimitive::Str => Value::String(decode(input)?),
+		TypeDefPrimitive::U8 => decode::<u8>(input)?.into(),
+		TypeDefPrimitive::U16 => decode::<u16>(input)?.into(),
+		TypeDefPrimitive::U32 => decode::<u32>(input)?.into(),
+		TypeDefPrimitive::U64 => decode::<u64>(input)?.into(),
+		TypeDefPrimitive::U128 => Value::String(decode::<u128>(input)?.to_string()),
+		TypeDefPrimitive::U256 => Value::String(hex_string(&decode::<[u8; 32]>(input)?)),
+		TypeDefPrimitive::I8 => decode::<i8>(input)?.into(),
+		TypeDefPrimitive::I16 => decode::<i16>(input)?.into(),
+		TypeDefPrimitive::I32 => decode::<i32>(input)?.into(),
+		TypeDefPrimitive::I64 => decode::<i64>(input)?.into(),
+		TypeDefPrimitive::I128 => Value::String(decode::<i128>(input)?.to_string()),
+		TypeDefPrimitive::I256 => Value::String(hex_string(&decode::<[u8; 32]>(input)?)),
+	};
+
+	Ok(value)
+}
+
+/// Decode a compact encoded value of the given type.
+fn decode_compact(
+	registry: &PortableRegistry,
+	type_id: u32,
+	input: &mut &[u8],
+) -> Result<Value, String> {
+	let ty = registry.resolve(type_id).ok_or_else(|| format!("Unknown type {}", type_id))?;
+
+	match &ty.type_def {
+		TypeDef::Primitive(TypeDefPrimitive::U128) => {
+			let value = Compact::<u128>::decode(input).map_err(|error| error.to_string())?.0;
+			Ok(Value::String(value.to_string()))
+		},
+		TypeDef::Primitive(_) => {
+			let value = Compact::<u64>::decode(input).map_err(|error| error.to_string())?.0;
+			Ok(value.into())
+		},
+		// Compact encoded wrappers, like `Perbill`, encode their single field.
+		TypeDef::Composite(composite) if composite.fields.len() == 1 => {
+			let field = &composite.fields[0];
+			let value = decode_compact(registry, field.ty.id, input)?;
+			match &field.name {
+				Some(name) => Ok(Value::Object(Map::from_iter([(name.clone(), value)]))),
+				None => Ok(value),
+			}
+		},
+		_ => Err(format!("Type {} cannot be compact encoded", type_id)),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use scale_info::{meta_type, Registry, TypeInfo};
+
+	fn decode_as<T: TypeInfo + Encode + 'static>(value: T) -> Result<Value, String> {
+		let mut registry = Registry::new();
+		let type_id = registry.register_type(&meta_type::<T>()).id;
+		let registry: PortableRegistry = registry.into();
+
+		let encoded = value.encode();
+		let mut input = &encoded[..];
+		let value = decode_value(&registry, type_id, &mut input)?;
+		assert!(input.is_empty());
+		Ok(value)
+	}
+
+	#[test]
+	fn decode_primitives() {
+		assert_eq!(decode_as(true).unwrap(), Value::Bool(true));
+		assert_eq!(decode_as(7u64).unwrap(), Value::from(7u64));
+		assert_eq!(decode_as(-7i32).unwrap(), Value::from(-7i32));
+		assert_eq!(decode_as(u128::MAX).unwrap(), Value::String(u128::MAX.to_string()));
+		assert_eq!(decode_as(String::from("abc")).unwrap(), Value::String("abc".into()));
+		assert_eq!(decode_as(Compact(1_000u32)).unwrap(), Value::from(1_000u32));
+	}
+
+	#[test]
+	fn decode_collections() {
+		assert_eq!(decode_as(vec![1u8, 2, 3]).unwrap(), Value::String("0x010203".into()));
+		assert_eq!(decode_as([1u16, 2]).unwrap(), serde_json::json!([1, 2]));
+		assert_eq!(decode_as((1u32, false)).unwrap(), serde_json::json!([1, false]));
+		assert_eq!(decode_as(()).unwrap(), Value::Null);
+	}
+
+	#[test]
+	fn decode_variants() {
+		assert_eq!(decode_as(Some(5u32)).unwrap(), serde_json::json!({ "Some": 5 }));
+		assert_eq!(decode_as(None::<u32>).unwrap(), Value::String("None".into()));
+		assert_eq!(
+			decode_as(Result::<u32, bool>::Err(true)).unwrap(),
+			serde_json::json!({ "Err": true })
+		);
+	}
+
+	#[test]
+	fn decode_invalid_input() {
+		let mut registry = Registry::new();
+		let type_id = registry.register_type(&meta_type::<u64>()).id;
+		let registry: PortableRegistry = registry.into();
+
+		assert!(decode_value(&registry, type_id, &mut &[1u8, 2][..]).is_err());
+		assert!(decode_value(&registry, type_id + 1, &mut &[0u8; 8][..]).is_err());
+	}
+}
@@ -18,6 +18,7 @@
 
 //! Error helpers for `chainHead` RPC module.
 
+use crate::chain_head::health::HealthReport;
 use jsonrpsee::{
 	core::Error as RpcError,
 	types::error::{CallError, ErrorObject},
@@ -42,6 +43,15 @@ pub enum Error {
 	/// Wait-for-continue event not generated.
 	#[error("Wait for continue event was not generated for the subscription")]
 	InvalidContinue,
+	/// The `chainHead` subsystem is unhealthy.
+	#[error("The chainHead subsystem is unhealthy")]
+	Unhealthy(HealthReport),
+	/// The maximum number of `chainHead_follow` subscriptions is reached.
+	#[error("Maximum number of chainHead_follow has been reached")]
+	TooManyFollowSubscriptions,
+	/// The bandwidth quota of the subscription is exhausted for the current period.
+	#[error("The bandwidth quota of the subscription is exhausted")]
+	BandwidthExhausted,
 }
 
 // Base code for all `chainHead` errors.
@@ -56,6 +66,12 @@ const INVALID_PARAM_ERROR: i32 = BASE_ERROR + 3;
 const INVALID_SUB_ID: i32 = BASE_ERROR + 4;
 /// Wait-for-continue event not generated.
 const INVALID_CONTINUE: i32 = BASE_ERROR + 5;
+/// The `chainHead` subsystem is unhealthy.
+const UNHEALTHY: i32 = BASE_ERROR + 6;
+/// The bandwidth quota of the subscription is exhausted.
+const BANDWIDTH_EXHAUSTED: i32 = BASE_ERROR + 7;
+/// The maximum number of `chainHead_follow` subscriptions is reached, defined by the spec.
+const TOO_MANY_FOLLOW_SUBSCRIPTIONS: i32 = -32800;
 
 impl From<Error> for ErrorObject<'static> {
 	fn from(e: Error) -> Self {
@@ -68,6 +84,10 @@ impl From<Error> for ErrorObject<'static> {
 			Error::InvalidParam(_) => ErrorObject::owned(INVALID_PARAM_ERROR, msg, None::<()>),
 			Error::InvalidSubscriptionID => ErrorObject::owned(INVALID_SUB_ID, msg, None::<()>),
 			Error::InvalidContinue => ErrorObject::owned(INVALID_CONTINUE, msg, None::<()>),
+			Error::Unhealthy(report) => ErrorObject::owned(UNHEALTHY, msg, Some(report)),
+			Error::TooManyFollowSubscriptions =>
+				ErrorObject::owned(TOO_MANY_FOLLOW_SUBSCRIPTIONS, msg, None::<()>),
+			Error::BandwidthExhausted => ErrorObject::owned(BANDWIDTH_EXHAUSTED, msg, None::<()>),
 		}
 		.into()
 	}
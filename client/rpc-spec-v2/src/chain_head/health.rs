@@ -0,0 +1,334 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Health of the `chainHead` subsystem.
+//!
+//! The followers report when they start and finish handling a notification, and the
+//! operations executed in the background report when they are pending. A node whose
+//! followers are stuck, whose notification streams closed or whose pending operations
+//! exceed the threshold is reported as unhealthy, such that a load balancer can drain it.
+
+use parking_lot::Mutex;
+use prometheus_endpoint::{register, Counter, Gauge, PrometheusError, Registry, U64};
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::HashMap,
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+/// The maximum duration a follower may spend handling a notification before it is
+/// considered stalled.
+const MAX_FOLLOWER_STALL: Duration = Duration::from_secs(30);
+
+/// The maximum number of operations pending in the background.
+const MAX_PENDING_OPERATIONS: usize = 1024;
+
+/// The thresholds of a healthy `chainHead` subsystem.
+#[derive(Debug, Clone, Copy)]
+pub struct HealthThresholds {
+	/// The maximum duration a follower may spend handling a notification.
+	pub max_follower_stall: Duration,
+	/// The maximum number of operations pending in the background.
+	pub max_pending_operations: usize,
+}
+
+impl Default for HealthThresholds {
+	fn default() -> Self {
+		HealthThresholds {
+			max_follower_stall: MAX_FOLLOWER_STALL,
+			max_pending_operations: MAX_PENDING_OPERATIONS,
+		}
+	}
+}
+
+/// The health of the `chainHead` subsystem.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+	/// The number of running followers.
+	pub followers: usize,
+	/// The number of followers handling a notification for longer than the threshold.
+	pub stalled_followers: usize,
+	/// The number of followers that stopped because their notification streams closed,
+	/// since a follower last received a notification.
+	pub closed_notification_streams: usize,
+	/// The number of operations pending in the background.
+	pub pending_operations: usize,
+	/// True if the subsystem is healthy.
+	pub is_healthy: bool,
+}
+
+/// The state shared by the followers and the operations.
+#[derive(Default)]
+struct HealthState {
+	/// The identifier of the next follower.
+	next_follower: u64,
+	/// The running followers and the instant they started handling a notification, if any.
+	followers: HashMap<u64, Option<Instant>>,
+	/// The number of followers that stopped because their notification streams closed,
+	/// since a follower last received a notification.
+	closed_notification_streams: usize,
+	/// The number of operations pending in the background.
+	pending_operations: usize,
+}
+
+/// The Prometheus metrics of the health.
+struct HealthMetrics {
+	/// The number of running followers.
+	followers: Gauge<U64>,
+	/// The number of operations pending in the background.
+	pending_operations: Gauge<U64>,
+	/// The number of followers that stopped because their notification streams closed.
+	closed_notification_streams: Counter<U64>,
+}
+
+impl HealthMetrics {
+	/// Register the metrics at the given Prometheus registry.
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			followers: register(
+				Gauge::new(
+					"substrate_rpc_chain_head_followers",
+					"Number of running chainHead followers",
+				)?,
+				registry,
+			)?,
+			pending_operations: register(
+				Gauge::new(
+					"substrate_rpc_chain_head_pending_operations",
+					"Number of chainHead operations pending in the background",
+				)?,
+				registry,
+			)?,
+			closed_notification_streams: register(
+				Counter::new(
+					"substrate_rpc_chain_head_closed_notification_streams",
+					"Total number of chainHead followers stopped by closed notification streams",
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+/// Cheaply cloneable handle to the health of the `chainHead` subsystem.
+///
+/// The handle is shared by all the `chainHead` instances of the node.
+#[derive(Clone)]
+pub struct ChainHeadHealth {
+	/// The shared state.
+	state: Arc<Mutex<HealthState>>,
+	/// The thresholds of a healthy subsystem.
+	thresholds: HealthThresholds,
+	/// The optional metrics.
+	metrics: Arc<Option<HealthMetrics>>,
+}
+
+impl Default for ChainHeadHealth {
+	fn default() -> Self {
+		Self::new(HealthThresholds::default(), None)
+	}
+}
+
+impl ChainHeadHealth {
+	/// Create a new [`ChainHeadHealth`] and register its metrics at the given registry, if any.
+	pub fn new(thresholds: HealthThresholds, registry: Option<&Registry>) -> Self {
+		let metrics = registry.and_then(|registry| {
+			HealthMetrics::register(registry)
+				.map_err(|err| {
+					log::warn!("Failed to register chainHead health prometheus metrics: {}", err);
+				})
+				.ok()
+		});
+
+		Self { state: Default::default(), thresholds, metrics: Arc::new(metrics) }
+	}
+
+	/// Report the health of the subsystem.
+	pub fn report(&self) -> HealthReport {
+		let state = self.state.lock();
+		let stalled_followers = state
+			.followers
+			.values()
+			.filter(|busy_since| {
+				busy_since
+					.map_or(false, |since| since.elapsed() > self.thresholds.max_follower_stall)
+			})
+			.count();
+
+		HealthReport {
+			followers: state.followers.len(),
+			stalled_followers,
+			closed_notification_streams: state.closed_notification_streams,
+			pending_operations: state.pending_operations,
+			is_healthy: stalled_followers == 0 &&
+				state.closed_notification_streams == 0 &&
+				state.pending_operations <= self.thresholds.max_pending_operations,
+		}
+	}
+
+	/// Register a running follower.
+	pub(crate) fn register_follower(&self) -> FollowerHealth {
+		let mut state = self.state.lock();
+		let id = state.next_follower;
+		state.next_follower += 1;
+		state.followers.insert(id, None);
+		self.report_metrics(|metrics| metrics.followers.set(state.followers.len() as u64));
+
+		FollowerHealth { health: self.clone(), id }
+	}
+
+	/// Register an operation pending in the background.
+	pub(crate) fn start_operation(&self) -> PendingOperation {
+		let mut state = self.state.lock();
+		state.pending_operations += 1;
+		self.report_metrics(|metrics| {
+			metrics.pending_operations.set(state.pending_operations as u64)
+		});
+
+		PendingOperation { health: self.clone() }
+	}
+
+	/// Report to the metrics, if the metrics are registered.
+	fn report_metrics(&self, do_this: impl FnOnce(&HealthMetrics)) {
+		if let Some(metrics) = self.metrics.as_ref() {
+			do_this(metrics);
+		}
+	}
+}
+
+/// The health of a running follower.
+///
+/// The follower is unregistered when this is dropped.
+pub(crate) struct FollowerHealth {
+	health: ChainHeadHealth,
+	id: u64,
+}
+
+impl FollowerHealth {
+	/// The follower started handling a notification.
+	pub fn busy(&self) {
+		if let Some(busy_since) = self.health.state.lock().followers.get_mut(&self.id) {
+			busy_since.get_or_insert_with(Instant::now);
+		}
+	}
+
+	/// The follower finished handling the notifications.
+	pub fn idle(&self) {
+		if let Some(busy_since) = self.health.state.lock().followers.get_mut(&self.id) {
+			*busy_since = None;
+		}
+	}
+
+	/// The follower received a notification, the notification streams recovered.
+	pub fn notification_received(&self) {
+		self.health.state.lock().closed_notification_streams = 0;
+	}
+
+	/// The notification streams of the follower closed.
+	pub fn notification_streams_closed(&self) {
+		self.health.state.lock().closed_notification_streams += 1;
+		self.health.report_metrics(|metrics| metrics.closed_notification_streams.inc());
+	}
+}
+
+impl Drop for FollowerHealth {
+	fn drop(&mut self) {
+		let mut state = self.health.state.lock();
+		state.followers.remove(&self.id);
+		self.health
+			.report_metrics(|metrics| metrics.followers.set(state.followers.len() as u64));
+	}
+}
+
+/// An operation pending in the background.
+///
+/// The operation is completed when this is dropped.
+pub(crate) struct PendingOperation {
+	health: ChainHeadHealth,
+}
+
+impl Drop for PendingOperation {
+	fn drop(&mut self) {
+		let mut state = self.health.state.lock();
+		state.pending_operations = state.pending_operations.saturating_sub(1);
+		self.health.report_metrics(|metrics| {
+			metrics.pending_operations.set(state.pending_operations as u64)
+		});
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn stalled_follower_is_unhealthy() {
+		let health = ChainHeadHealth::new(
+			HealthThresholds { max_follower_stall: Duration::from_millis(1), ..Default::default() },
+			None,
+		);
+
+		let follower = health.register_follower();
+		follower.busy();
+		std::thread::sleep(Duration::from_millis(5));
+		let report = health.report();
+		assert_eq!(report.followers, 1);
+		assert_eq!(report.stalled_followers, 1);
+		assert!(!report.is_healthy);
+
+		follower.idle();
+		assert!(health.report().is_healthy);
+
+		drop(follower);
+		assert_eq!(health.report().followers, 0);
+	}
+
+	#[test]
+	fn closed_streams_and_pending_operations() {
+		let health = ChainHeadHealth::new(
+			HealthThresholds { max_pending_operations: 1, ..Default::default() },
+			None,
+		);
+
+		let first = health.start_operation();
+		assert!(health.report().is_healthy);
+		let second = health.start_operation();
+		let report = health.report();
+		assert_eq!(report.pending_operations, 2);
+		assert!(!report.is_healthy);
+
+		drop((first, second));
+		assert!(health.report().is_healthy);
+
+		// The closed notification streams are reported until a follower receives a notification.
+		health.register_follower().notification_streams_closed();
+		let report = health.report();
+		assert_eq!(report.followers, 0);
+		assert_eq!(report.closed_notification_streams, 1);
+		assert!(!report.is_healthy);
+
+		let follower = health.register_follower();
+		assert!(!health.report().is_healthy);
+		follower.notification_received();
+		let report = health.report();
+		assert_eq!(report.closed_notification_streams, 0);
+		assert!(report.is_healthy);
+	}
+}
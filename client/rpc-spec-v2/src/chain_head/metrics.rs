@@ -0,0 +1,197 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Prometheus metrics of the `chainHead` RPC methods.
+
+use prometheus_endpoint::{
+	register, Counter, CounterVec, Gauge, GaugeVec, HistogramOpts, HistogramVec, Opts,
+	PrometheusError, Registry, U64,
+};
+use std::{sync::Arc, time::Instant};
+
+/// Histogram time buckets of the operations in microseconds.
+const HISTOGRAM_BUCKETS: [f64; 11] = [
+	5.0,
+	25.0,
+	100.0,
+	500.0,
+	1_000.0,
+	2_500.0,
+	10_000.0,
+	25_000.0,
+	100_000.0,
+	1_000_000.0,
+	10_000_000.0,
+];
+
+/// Cheaply cloneable handle to the optional `chainHead` metrics.
+#[derive(Clone, Default)]
+pub struct MetricsLink(Arc<Option<Metrics>>);
+
+impl MetricsLink {
+	/// Register the metrics at the given Prometheus registry, if any.
+	pub fn new(registry: Option<&Registry>) -> Self {
+		Self(Arc::new(registry.and_then(|registry| {
+			Metrics::register(registry)
+				.map_err(|err| {
+					log::warn!("Failed to register chainHead prometheus metrics: {}", err);
+				})
+				.ok()
+		})))
+	}
+
+	/// Report an event to the metrics, if the metrics are registered.
+	pub fn report(&self, do_this: impl FnOnce(&Metrics)) {
+		if let Some(metrics) = self.0.as_ref() {
+			do_this(metrics);
+		}
+	}
+
+	/// Report the start of an operation of the given method.
+	///
+	/// The operation is reported as completed when the returned timer is dropped.
+	pub fn start_operation(&self, method: &'static str) -> OperationTimer {
+		self.report(|metrics| metrics.operations_in_flight.with_label_values(&[method]).inc());
+		OperationTimer { metrics: self.clone(), method, started: Instant::now() }
+	}
+}
+
+/// An operation in progress.
+///
+/// The duration of the operation is reported when this is dropped.
+pub struct OperationTimer {
+	metrics: MetricsLink,
+	method: &'static str,
+	started: Instant,
+}
+
+impl Drop for OperationTimer {
+	fn drop(&mut self) {
+		let micros = self.started.elapsed().as_micros();
+		self.metrics.report(|metrics| {
+			metrics.operations_in_flight.with_label_values(&[self.method]).dec();
+			metrics
+				.operation_duration
+				.with_label_values(&[self.method])
+				.observe(micros as f64);
+		});
+	}
+}
+
+/// The `chainHead` Prometheus metrics.
+pub struct Metrics {
+	/// Number of operations served from the operation cache.
+	pub operation_cache_hits: Counter<U64>,
+	/// Number of operations that were not found in the operation cache.
+	pub operation_cache_misses: Counter<U64>,
+	/// Number of backend reads retried after a transient error.
+	pub backend_retries: Counter<U64>,
+	/// Number of backend reads that failed after exhausting their retries.
+	pub backend_retries_exhausted: Counter<U64>,
+	/// Number of operations coalesced onto an identical operation in progress.
+	pub operations_coalesced: Counter<U64>,
+	/// Number of blocks pinned across all subscriptions.
+	pub pinned_blocks: Gauge<U64>,
+	/// Number of operations in progress per method.
+	pub operations_in_flight: GaugeVec<U64>,
+	/// Duration of the operations per method.
+	pub operation_duration: HistogramVec,
+	/// Number of `Stop` events per reason.
+	pub stop_events: CounterVec<U64>,
+}
+
+impl Metrics {
+	/// Register the metrics at the given Prometheus registry.
+	pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			operation_cache_hits: register(
+				Counter::new(
+					"substrate_rpc_chain_head_operation_cache_hits",
+					"Total number of chainHead operations served from the operation cache",
+				)?,
+				registry,
+			)?,
+			operation_cache_misses: register(
+				Counter::new(
+					"substrate_rpc_chain_head_operation_cache_misses",
+					"Total number of chainHead operations not found in the operation cache",
+				)?,
+				registry,
+			)?,
+			backend_retries: register(
+				Counter::new(
+					"substrate_rpc_chain_head_backend_retries",
+					"Total number of chainHead backend reads retried after a transient error",
+				)?,
+				registry,
+			)?,
+			backend_retries_exhausted: register(
+				Counter::new(
+					"substrate_rpc_chain_head_backend_retries_exhausted",
+					"Total number of chainHead backend reads failed after exhausting their retries",
+				)?,
+				registry,
+			)?,
+			operations_coalesced: register(
+				Counter::new(
+					"substrate_rpc_chain_head_operations_coalesced",
+					"Total number of chainHead operations coalesced onto an identical operation",
+				)?,
+				registry,
+			)?,
+			pinned_blocks: register(
+				Gauge::new(
+					"substrate_rpc_chain_head_pinned_blocks",
+					"Number of blocks pinned across all chainHead subscriptions",
+				)?,
+				registry,
+			)?,
+			operations_in_flight: register(
+				GaugeVec::new(
+					Opts::new(
+						"substrate_rpc_chain_head_operations_in_flight",
+						"Number of chainHead operations in progress",
+					),
+					&["method"],
+				)?,
+				registry,
+			)?,
+			operation_duration: register(
+				HistogramVec::new(
+					HistogramOpts::new(
+						"substrate_rpc_chain_head_operation_time",
+						"Total time [μs] of the chainHead operations",
+					)
+					.buckets(HISTOGRAM_BUCKETS.to_vec()),
+					&["method"],
+				)?,
+				registry,
+			)?,
+			stop_events: register(
+				CounterVec::new(
+					Opts::new(
+						"substrate_rpc_chain_head_stop_events",
+						"Total number of chainHead Stop events by reason",
+					),
+					&["reason"],
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
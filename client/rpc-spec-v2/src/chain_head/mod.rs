@@ -32,16 +32,40 @@ pub mod chain_head;
 pub mod error;
 pub mod event;
 
+mod audit;
+mod cache;
 mod chain_head_follow;
 mod chain_head_storage;
+mod coalesce;
+pub(crate) mod decode;
+mod health;
+mod metrics;
+mod pool;
+mod retry;
 mod subscription;
 
 pub use api::ChainHeadApiServer;
+pub use audit::{AuditSink, ChannelAuditSink, FileAuditSink, OperationRecord};
 pub use chain_head::{ChainHead, ChainHeadConfig};
+pub use chain_head_follow::NotificationSource;
 pub use event::{
-	BestBlockChanged, ErrorEvent, Finalized, FollowEvent, Initialized, NewBlock, RuntimeEvent,
-	RuntimeVersionEvent,
+	BestBlockChanged, CallContextType, CallOptions, CallStats, CallWeight, CatchUp, ErrorEvent,
+	Finalized, FollowEvent, Initialized, NewBlock, PinnedBlock, RuntimeEvent, RuntimeVersionEvent,
+	StorageOptions,
 };
+pub use health::{ChainHeadHealth, HealthReport, HealthThresholds};
+pub use metrics::MetricsLink as ChainHeadMetrics;
+pub use pool::OperationPool;
+pub use retry::RetryPolicy;
+pub use subscription::{
+	AgeEviction, BandwidthQuota, BlockGuard, FollowSubscriptionInfo, FollowSubscriptionPins,
+	InsertedSubscriptionData, LruEviction, MemoryWeightedEviction, OperationState,
+	PinEvictionPolicy, PinStore, SubscriptionManagement, SubscriptionManagementError,
+	SubscriptionPins,
+};
+
+pub(crate) use chain_head_follow::storage_result_size;
+pub(crate) use subscription::Bandwidth;
 
 use sp_core::hexdisplay::{AsBytesRef, HexDisplay};
 
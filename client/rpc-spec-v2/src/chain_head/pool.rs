@@ -0,0 +1,162 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Dedicated pool of threads executing the CPU-heavy operations.
+//!
+//! The wasm calls of `chainHead_call` may run for a long time. Executing them on a bounded
+//! pool of dedicated threads keeps them from starving the subscription executor, which
+//! delivers the `chainHead_follow` events.
+//!
+//! A single pool is meant to be shared by the `chainHead` instances of all the RPC servers,
+//! such that the number of threads does not grow with the number of servers.
+
+use crate::chain_head::chain_head::LOG_TARGET;
+use futures::future::BoxFuture;
+use log::{debug, warn};
+use parking_lot::Mutex;
+use std::{
+	panic::{catch_unwind, AssertUnwindSafe},
+	sync::{mpsc, Arc},
+};
+
+/// A job executed by the pool.
+type Job = Box<dyn FnOnce() + Send>;
+
+/// A bounded pool of threads executing the operations.
+///
+/// The jobs spawned while all the threads are busy are queued until a thread is available.
+/// The threads exit once the pool is dropped and the queued jobs are completed.
+pub struct OperationPool {
+	/// Submit the jobs to the threads.
+	sender: Mutex<mpsc::Sender<Job>>,
+	/// The runtime driving the asynchronous operations.
+	handle: tokio::runtime::Handle,
+}
+
+impl OperationPool {
+	/// Spawn a pool of `size` threads, at least one, driving the asynchronous operations on
+	/// the runtime of the given handle.
+	pub fn new(size: usize, handle: tokio::runtime::Handle) -> Self {
+		let (sender, receiver) = mpsc::channel::<Job>();
+		let receiver = Arc::new(Mutex::new(receiver));
+
+		for index in 0..size.max(1) {
+			let receiver = receiver.clone();
+			let spawned = std::thread::Builder::new()
+				.name(format!("chain-head-operation-{}", index))
+				.spawn(move || loop {
+					// The lock is released before the job is executed.
+					let job = receiver.lock().recv();
+					let Ok(job) = job else { return };
+
+					if catch_unwind(AssertUnwindSafe(job)).is_err() {
+						warn!(target: LOG_TARGET, "[pool] Operation panicked");
+					}
+				});
+
+			if let Err(error) = spawned {
+				warn!(target: LOG_TARGET, "[pool] Failed to spawn operation thread: {}", error);
+			}
+		}
+
+		OperationPool { sender: Mutex::new(sender), handle }
+	}
+
+	/// Execute the job on one of the threads of the pool.
+	///
+	/// The job is executed on the current thread if the pool has no thread.
+	pub fn spawn(&self, job: impl FnOnce() + Send + 'static) {
+		if let Err(mpsc::SendError(job)) = self.sender.lock().send(Box::new(job)) {
+			debug!(target: LOG_TARGET, "[pool] No operation thread, executing inline");
+			job();
+		}
+	}
+
+	/// Drive the future to completion on the runtime of the pool.
+	///
+	/// The future is not executed by the threads of the pool, which it would otherwise
+	/// occupy while it waits (ie for the `chainHead_continue` calls).
+	pub fn spawn_future(&self, fut: BoxFuture<'static, ()>) {
+		self.handle.spawn(fut);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::{
+		sync::{
+			atomic::{AtomicUsize, Ordering},
+			Barrier,
+		},
+		time::Duration,
+	};
+
+	#[tokio::test]
+	async fn jobs_are_executed_concurrently_up_to_the_pool_size() {
+		let pool = OperationPool::new(2, tokio::runtime::Handle::current());
+		let barrier = Arc::new(Barrier::new(3));
+		let running = Arc::new(AtomicUsize::new(0));
+
+		for _ in 0..2 {
+			let (barrier, running) = (barrier.clone(), running.clone());
+			pool.spawn(move || {
+				running.fetch_add(1, Ordering::SeqCst);
+				barrier.wait();
+			});
+		}
+		// The third job is queued until one of the threads is available.
+		let (tx, rx) = mpsc::channel();
+		pool.spawn(move || tx.send(()).unwrap());
+
+		// Both jobs are running while the third one is queued.
+		barrier.wait();
+		assert_eq!(running.load(Ordering::SeqCst), 2);
+		rx.recv_timeout(Duration::from_secs(5)).unwrap();
+	}
+
+	#[tokio::test]
+	async fn pool_survives_panicking_jobs() {
+		let pool = OperationPool::new(1, tokio::runtime::Handle::current());
+		pool.spawn(|| panic!("Operation failed"));
+
+		let (tx, rx) = mpsc::channel();
+		pool.spawn(move || tx.send(()).unwrap());
+		rx.recv_timeout(Duration::from_secs(5)).unwrap();
+	}
+
+	#[tokio::test]
+	async fn futures_do_not_occupy_the_threads() {
+		let pool = OperationPool::new(1, tokio::runtime::Handle::current());
+		let (continue_tx, continue_rx) = futures::channel::oneshot::channel::<()>();
+		let (done_tx, done_rx) = futures::channel::oneshot::channel();
+		pool.spawn_future(Box::pin(async move {
+			tokio::time::sleep(Duration::from_millis(1)).await;
+			continue_rx.await.unwrap();
+			done_tx.send(()).unwrap();
+		}));
+
+		// The waiting future does not keep the job from the single thread of the pool.
+		let (tx, rx) = mpsc::channel();
+		pool.spawn(move || tx.send(()).unwrap());
+		rx.recv_timeout(Duration::from_secs(5)).unwrap();
+
+		continue_tx.send(()).unwrap();
+		done_rx.await.unwrap();
+	}
+}
@@ -0,0 +1,205 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Retry of the backend reads that fail with a transient error.
+//!
+//! The reads of the `chainHead_body`, `chainHead_call` and `chainHead_storage` methods are
+//! retried with an exponential backoff before the error is reported to the client.
+
+use crate::chain_head::{chain_head::LOG_TARGET, metrics::MetricsLink};
+use log::debug;
+use sp_blockchain::Error as BlockChainError;
+use std::time::Duration;
+
+/// The maximum number of retries of a failed read.
+const MAX_RETRIES: u32 = 3;
+
+/// The backoff before the first retry.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(10);
+
+/// The maximum backoff between two retries.
+const MAX_BACKOFF: Duration = Duration::from_millis(200);
+
+/// The retry policy of the backend reads.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+	/// The maximum number of retries of a failed read.
+	///
+	/// The reads are not retried if this is zero.
+	pub max_retries: u32,
+	/// The backoff before the first retry, doubled after every retry.
+	pub initial_backoff: Duration,
+	/// The maximum backoff between two retries.
+	pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+	fn default() -> Self {
+		RetryPolicy {
+			max_retries: MAX_RETRIES,
+			initial_backoff: INITIAL_BACKOFF,
+			max_backoff: MAX_BACKOFF,
+		}
+	}
+}
+
+/// The state database error of a block whose record is not committed to the database yet.
+///
+/// The state database errors are only reported as their debug representation.
+const STATE_DB_BLOCK_UNAVAILABLE: &str =
+	"Trying to get a block record from db while it is not commit to db yet";
+
+/// Returns true if the error is caused by the backend and may not happen again.
+///
+/// The execution and decoding errors are deterministic and are never retried. The state
+/// database errors are deterministic as well, such as the pruned state, except for the
+/// block records that are not committed yet.
+fn is_transient(error: &BlockChainError) -> bool {
+	match error {
+		BlockChainError::Backend(_) |
+		BlockChainError::DatabaseError(_) |
+		BlockChainError::Storage(_) => true,
+		BlockChainError::StateDatabase(message) => message == STATE_DB_BLOCK_UNAVAILABLE,
+		BlockChainError::Blockchain(error) => is_transient(error),
+		_ => false,
+	}
+}
+
+/// Retry the backend reads according to the policy.
+#[derive(Clone)]
+pub struct BackendRetry {
+	/// The retry policy.
+	policy: RetryPolicy,
+	/// Report the retries.
+	metrics: MetricsLink,
+}
+
+impl BackendRetry {
+	/// Create a new [`BackendRetry`].
+	pub fn new(policy: RetryPolicy, metrics: MetricsLink) -> Self {
+		Self { policy, metrics }
+	}
+
+	/// Run the read, retrying it while it fails with a transient error.
+	///
+	/// This blocks the current thread during the backoff and must be called from the
+	/// blocking methods or tasks.
+	pub fn run<T>(
+		&self,
+		mut read: impl FnMut() -> Result<T, BlockChainError>,
+	) -> Result<T, BlockChainError> {
+		let mut backoff = self.policy.initial_backoff;
+		let mut retries = 0;
+
+		loop {
+			match read() {
+				Err(error) if is_transient(&error) && retries < self.policy.max_retries => {
+					debug!(
+						target: LOG_TARGET,
+						"Retrying backend read in {:?} after transient error {:?}", backoff, error
+					);
+					self.metrics.report(|metrics| metrics.backend_retries.inc());
+
+					std::thread::sleep(backoff);
+					backoff = (backoff * 2).min(self.policy.max_backoff);
+					retries += 1;
+				},
+				Err(error) => {
+					if retries > 0 {
+						self.metrics.report(|metrics| metrics.backend_retries_exhausted.inc());
+					}
+					return Err(error)
+				},
+				result => return result,
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn retry(max_retries: u32) -> BackendRetry {
+		let policy = RetryPolicy {
+			max_retries,
+			initial_backoff: Duration::ZERO,
+			max_backoff: Duration::ZERO,
+		};
+		BackendRetry::new(policy, MetricsLink::default())
+	}
+
+	#[test]
+	fn transient_errors_are_retried() {
+		let mut attempts = 0;
+		let result = retry(3).run(|| {
+			attempts += 1;
+			if attempts < 3 {
+				Err(BlockChainError::Backend("busy".into()))
+			} else {
+				Ok(attempts)
+			}
+		});
+		assert_eq!(result.unwrap(), 3);
+
+		// The read fails once the retries are exhausted.
+		let mut attempts = 0;
+		let result = retry(2).run(|| -> Result<(), _> {
+			attempts += 1;
+			Err(BlockChainError::Blockchain(Box::new(BlockChainError::Storage("busy".into()))))
+		});
+		assert!(result.is_err());
+		assert_eq!(attempts, 3);
+	}
+
+	#[test]
+	fn deterministic_errors_are_not_retried() {
+		let mut attempts = 0;
+		let result = retry(3).run(|| -> Result<(), _> {
+			attempts += 1;
+			Err(BlockChainError::InvalidState)
+		});
+		assert!(result.is_err());
+		assert_eq!(attempts, 1);
+
+		// The state pruned from the state database is never available again.
+		let mut attempts = 0;
+		let result = retry(3).run(|| -> Result<(), _> {
+			attempts += 1;
+			Err(BlockChainError::StateDatabase(
+				"Block record is missing from the pruning window".into(),
+			))
+		});
+		assert!(result.is_err());
+		assert_eq!(attempts, 1);
+	}
+
+	#[test]
+	fn uncommitted_state_database_blocks_are_retried() {
+		let mut attempts = 0;
+		let result = retry(3).run(|| {
+			attempts += 1;
+			if attempts < 2 {
+				Err(BlockChainError::StateDatabase(STATE_DB_BLOCK_UNAVAILABLE.into()))
+			} else {
+				Ok(attempts)
+			}
+		});
+		assert_eq!(result.unwrap(), 2);
+	}
+}
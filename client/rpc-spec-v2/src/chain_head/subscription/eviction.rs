@@ -0,0 +1,170 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Policies selecting the subscriptions stopped to release pinned blocks.
+
+use std::time::{Duration, Instant};
+
+/// The pinned blocks of a subscription considered for eviction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriptionPins {
+	/// The ID of the subscription.
+	pub sub_id: String,
+	/// The time when the oldest block reported by the subscription was pinned.
+	pub oldest_pin: Option<Instant>,
+	/// The time when the most recent block of the subscription was pinned.
+	pub newest_pin: Option<Instant>,
+	/// The number of blocks pinned by the subscription.
+	pub pinned_blocks: usize,
+	/// The number of blocks pinned only by this subscription.
+	///
+	/// These blocks are unpinned from the backend once the subscription is stopped.
+	pub exclusive_blocks: usize,
+}
+
+/// Select the subscriptions stopped when the global limit of pinned blocks is reached.
+///
+/// The policy is consulted until enough blocks are released, or until it selects no
+/// subscription. All the subscriptions are stopped if the selected subscriptions did not
+/// release enough blocks.
+pub trait PinEvictionPolicy: Send + Sync + std::fmt::Debug {
+	/// Returns the IDs of the subscriptions to stop.
+	fn select(&self, subscriptions: &[SubscriptionPins]) -> Vec<String>;
+}
+
+/// Stop the subscriptions that pinned a block for longer than the maximum duration.
+#[derive(Debug, Clone)]
+pub struct AgeEviction {
+	max_pin_duration: Duration,
+}
+
+impl AgeEviction {
+	/// Construct a new [`AgeEviction`].
+	pub fn new(max_pin_duration: Duration) -> Self {
+		AgeEviction { max_pin_duration }
+	}
+}
+
+impl PinEvictionPolicy for AgeEviction {
+	fn select(&self, subscriptions: &[SubscriptionPins]) -> Vec<String> {
+		let now = Instant::now();
+
+		subscriptions
+			.iter()
+			.filter(|sub| {
+				let Some(oldest_pin) = sub.oldest_pin else { return false };
+				match now.checked_duration_since(oldest_pin) {
+					Some(duration) => duration > self.max_pin_duration,
+					None => true,
+				}
+			})
+			.map(|sub| sub.sub_id.clone())
+			.collect()
+	}
+}
+
+/// Stop the subscription that pinned a block least recently.
+#[derive(Debug, Clone, Default)]
+pub struct LruEviction;
+
+impl PinEvictionPolicy for LruEviction {
+	fn select(&self, subscriptions: &[SubscriptionPins]) -> Vec<String> {
+		subscriptions
+			.iter()
+			.filter(|sub| sub.exclusive_blocks > 0)
+			.filter_map(|sub| sub.newest_pin.map(|newest_pin| (newest_pin, sub)))
+			.min_by_key(|(newest_pin, _)| *newest_pin)
+			.map(|(_, sub)| sub.sub_id.clone())
+			.into_iter()
+			.collect()
+	}
+}
+
+/// Stop the subscription releasing the most blocks from the backend.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryWeightedEviction;
+
+impl PinEvictionPolicy for MemoryWeightedEviction {
+	fn select(&self, subscriptions: &[SubscriptionPins]) -> Vec<String> {
+		subscriptions
+			.iter()
+			.filter(|sub| sub.exclusive_blocks > 0)
+			.max_by_key(|sub| (sub.exclusive_blocks, sub.pinned_blocks))
+			.map(|sub| sub.sub_id.clone())
+			.into_iter()
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn sub_pins(
+		sub_id: &str,
+		pinned_ago: Duration,
+		pinned_blocks: usize,
+		exclusive_blocks: usize,
+	) -> SubscriptionPins {
+		let pinned_at = Instant::now() - pinned_ago;
+		SubscriptionPins {
+			sub_id: sub_id.into(),
+			oldest_pin: Some(pinned_at),
+			newest_pin: Some(pinned_at),
+			pinned_blocks,
+			exclusive_blocks,
+		}
+	}
+
+	#[test]
+	fn age_eviction_selects_expired() {
+		let subs = vec![
+			sub_pins("A", Duration::from_secs(20), 1, 1),
+			sub_pins("B", Duration::from_secs(1), 1, 1),
+			sub_pins("C", Duration::from_secs(30), 1, 0),
+		];
+
+		let policy = AgeEviction::new(Duration::from_secs(10));
+		assert_eq!(policy.select(&subs), vec!["A".to_string(), "C".to_string()]);
+	}
+
+	#[test]
+	fn lru_eviction_selects_least_recent() {
+		let subs = vec![
+			sub_pins("A", Duration::from_secs(20), 1, 1),
+			sub_pins("B", Duration::from_secs(1), 1, 1),
+			// Stopping the subscription releases no block.
+			sub_pins("C", Duration::from_secs(30), 1, 0),
+		];
+
+		assert_eq!(LruEviction.select(&subs), vec!["A".to_string()]);
+		assert!(LruEviction.select(&subs[2..]).is_empty());
+	}
+
+	#[test]
+	fn memory_weighted_eviction_selects_heaviest() {
+		let subs = vec![
+			sub_pins("A", Duration::from_secs(20), 4, 1),
+			sub_pins("B", Duration::from_secs(1), 3, 2),
+			sub_pins("C", Duration::from_secs(30), 5, 0),
+		];
+
+		assert_eq!(MemoryWeightedEviction.select(&subs), vec!["B".to_string()]);
+		assert!(MemoryWeightedEviction.select(&subs[2..]).is_empty());
+	}
+}
@@ -22,12 +22,18 @@ use sc_client_api::Backend;
 use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver, TracingUnboundedSender};
 use sp_runtime::traits::Block as BlockT;
 use std::{
-	collections::{hash_map::Entry, HashMap},
+	collections::{hash_map::Entry, HashMap, HashSet},
 	sync::{atomic::AtomicBool, Arc},
 	time::{Duration, Instant},
 };
 
-use crate::chain_head::{subscription::SubscriptionManagementError, FollowEvent};
+use crate::chain_head::{
+	metrics::MetricsLink,
+	subscription::{
+		FollowSubscriptionInfo, PinEvictionPolicy, SubscriptionManagementError, SubscriptionPins,
+	},
+	FollowEvent,
+};
 
 /// The queue size after which the `sc_utils::mpsc::tracing_unbounded` would produce warnings.
 const QUEUE_SIZE_WARNING: usize = 512;
@@ -155,6 +161,64 @@ struct PermitOperations {
 	_permit: tokio::sync::OwnedSemaphorePermit,
 }
 
+/// The quota of bytes served to a subscription, or by the `archive` methods of a server.
+///
+/// The `chainHead` quota is accounted per follow subscription, not per connection: the method
+/// handlers of jsonrpsee 0.16 are not given the ID of the connection they are called on. A
+/// connection that opens several subscriptions is granted the quota once for each of them, up
+/// to the maximum number of follow subscriptions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BandwidthQuota {
+	/// The maximum number of bytes served within one period.
+	pub max_bytes: u64,
+	/// The duration of the period after which the served bytes are no longer counted.
+	pub period: Duration,
+}
+
+/// Account the bytes served against a [`BandwidthQuota`].
+///
+/// Each follow subscription owns one, and so does the `archive` server.
+pub(crate) struct Bandwidth {
+	/// The optional quota.
+	quota: Option<BandwidthQuota>,
+	/// The number of bytes served since the accounting started.
+	total_bytes: u64,
+	/// The number of bytes served within the current period.
+	period_bytes: u64,
+	/// The moment the current period started.
+	period_start: Instant,
+}
+
+impl Bandwidth {
+	/// Constructs a new [`Bandwidth`].
+	pub(crate) fn new(quota: Option<BandwidthQuota>) -> Self {
+		Bandwidth { quota, total_bytes: 0, period_bytes: 0, period_start: Instant::now() }
+	}
+
+	/// Start a new period if the current one has elapsed.
+	fn advance_period(&mut self) {
+		let Some(quota) = self.quota else { return };
+
+		if self.period_start.elapsed() >= quota.period {
+			self.period_start = Instant::now();
+			self.period_bytes = 0;
+		}
+	}
+
+	/// Account the bytes served.
+	pub(crate) fn record(&mut self, bytes: u64) {
+		self.advance_period();
+		self.total_bytes = self.total_bytes.saturating_add(bytes);
+		self.period_bytes = self.period_bytes.saturating_add(bytes);
+	}
+
+	/// Returns true if the quota is exhausted for the current period.
+	pub(crate) fn is_exhausted(&mut self) -> bool {
+		self.advance_period();
+		self.quota.map_or(false, |quota| self.period_bytes >= quota.max_bytes)
+	}
+}
+
 /// The state of one operation.
 ///
 /// This is directly exposed to users via `chain_head_unstable_continue` and
@@ -183,20 +247,22 @@ impl OperationState {
 		self.send_continue.try_send(()).is_ok()
 	}
 
-	/// Stops the operation if `waitingForContinue` event was emitted for the associated
-	/// operation ID.
+	/// Stops the operation.
+	///
+	/// The operation in progress stops before producing its next item, while an operation
+	/// that emitted the `waitingForContinue` event is woken up to stop right away.
 	///
 	/// Returns nothing in accordance with `chainHead_unstable_stopOperation`.
 	pub fn stop_operation(&self) {
+		self.shared_state
+			.operation_stopped
+			.store(true, std::sync::atomic::Ordering::SeqCst);
+
 		// `waitingForContinue` not generated.
-		if !self.shared_state.requested_continue.load(std::sync::atomic::Ordering::Acquire) {
+		if !self.shared_state.requested_continue.load(std::sync::atomic::Ordering::SeqCst) {
 			return
 		}
 
-		self.shared_state
-			.operation_stopped
-			.store(true, std::sync::atomic::Ordering::Release);
-
 		// Send might not have enough capacity if `submit_continue` was sent first.
 		// However, the `operation_stopped` boolean was set.
 		let _ = self.send_continue.try_send(());
@@ -247,7 +313,15 @@ impl RegisteredOperation {
 	pub async fn wait_for_continue(&mut self) {
 		self.shared_state
 			.requested_continue
-			.store(true, std::sync::atomic::Ordering::Release);
+			.store(true, std::sync::atomic::Ordering::SeqCst);
+
+		// The operation was stopped before it started waiting.
+		if self.shared_state.operation_stopped.load(std::sync::atomic::Ordering::SeqCst) {
+			self.shared_state
+				.requested_continue
+				.store(false, std::sync::atomic::Ordering::SeqCst);
+			return
+		}
 
 		// The sender part of this channel is around for as long as this object exists,
 		// because it is stored in the `OperationState` of the `operations` field.
@@ -261,7 +335,7 @@ impl RegisteredOperation {
 
 	/// Returns true if the current operation was stopped.
 	pub fn was_stopped(&self) -> bool {
-		self.shared_state.operation_stopped.load(std::sync::atomic::Ordering::Acquire)
+		self.shared_state.operation_stopped.load(std::sync::atomic::Ordering::SeqCst)
 	}
 
 	/// Get the operation ID.
@@ -348,7 +422,7 @@ struct SubscriptionState<Block: BlockT> {
 	/// The `with_runtime` parameter flag of the subscription.
 	with_runtime: bool,
 	/// Signals the "Stop" event.
-	tx_stop: Option<oneshot::Sender<()>>,
+	tx_stop: Option<oneshot::Sender<&'static str>>,
 	/// The sender of message responses to the `chainHead_follow` events.
 	///
 	/// This object is cloned between methods.
@@ -366,6 +440,15 @@ struct SubscriptionState<Block: BlockT> {
 	/// - extra space: an extra BTreeMap<Instant, Hash> to older hashes by oldest insertion
 	/// - extra time: O(log(N)) for insert/remove/find each `pin` block time per subscriptions
 	blocks: HashMap<Block::Hash, BlockState>,
+	/// Blocks pinned on behalf of this subscription by other RPC methods,
+	/// before the block was reported by the `chainHead_follow` events.
+	///
+	/// The block is moved to [`Self::blocks`] once the events report it.
+	external_pins: HashMap<Block::Hash, Instant>,
+	/// The events are restricted to the descendants of this block, if provided.
+	fork_filter: Option<Block::Hash>,
+	/// The bytes served to the subscription.
+	bandwidth: Bandwidth,
 }
 
 impl<Block: BlockT> SubscriptionState<Block> {
@@ -373,9 +456,11 @@ impl<Block: BlockT> SubscriptionState<Block> {
 	///
 	/// This can happen on internal failure (ie, the pruning deleted the block from memory)
 	/// or if the subscription exceeded the available pinned blocks.
-	fn stop(&mut self) {
+	///
+	/// The reason labels the `Stop` event in the metrics.
+	fn stop(&mut self, reason: &'static str) {
 		if let Some(tx_stop) = self.tx_stop.take() {
-			let _ = tx_stop.send(());
+			let _ = tx_stop.send(reason);
 		}
 	}
 
@@ -445,6 +530,10 @@ impl<Block: BlockT> SubscriptionState<Block> {
 	///
 	/// Returns `true` if the subscription contains the block.
 	fn contains_block(&self, hash: Block::Hash) -> bool {
+		if self.external_pins.contains_key(&hash) {
+			return true
+		}
+
 		let Some(state) = self.blocks.get(&hash) else {
 			// Block was not tracked.
 			return false
@@ -454,17 +543,46 @@ impl<Block: BlockT> SubscriptionState<Block> {
 		!state.state_machine.was_unpinned()
 	}
 
-	/// Get the timestamp of the oldest inserted block.
+	/// The number of blocks pinned by the subscription, including the blocks pinned
+	/// on its behalf by other RPC methods.
+	fn pinned_blocks(&self) -> usize {
+		let reported = self.blocks.values().filter(|state| !state.state_machine.was_unpinned());
+		reported.count() + self.external_pins.len()
+	}
+
+	/// Get the pinned blocks of the subscription considered for eviction.
 	///
 	/// # Note
 	///
 	/// This iterates over all the blocks of the subscription.
-	fn find_oldest_block_timestamp(&self) -> Instant {
-		let mut timestamp = Instant::now();
-		for (_, state) in self.blocks.iter() {
-			timestamp = std::cmp::min(timestamp, state.timestamp);
+	fn eviction_candidate(
+		&self,
+		sub_id: &str,
+		global_blocks: &HashMap<Block::Hash, usize>,
+	) -> SubscriptionPins {
+		let oldest_pin = self.blocks.values().map(|state| state.timestamp).min();
+		let newest_pin = self
+			.blocks
+			.values()
+			.map(|state| state.timestamp)
+			.chain(self.external_pins.values().copied())
+			.max();
+		let exclusive_blocks = self
+			.blocks
+			.iter()
+			.filter(|(_, state)| !state.state_machine.was_unpinned())
+			.map(|(hash, _)| hash)
+			.chain(self.external_pins.keys())
+			.filter(|hash| global_blocks.get(*hash) == Some(&1))
+			.count();
+
+		SubscriptionPins {
+			sub_id: sub_id.to_string(),
+			oldest_pin,
+			newest_pin,
+			pinned_blocks: self.pinned_blocks(),
+			exclusive_blocks,
 		}
-		timestamp
 	}
 
 	/// Register a new operation.
@@ -480,6 +598,19 @@ impl<Block: BlockT> SubscriptionState<Block> {
 	}
 }
 
+/// The operations that hold a block locked via [`BlockGuard`].
+#[derive(Debug, Default)]
+struct LockedBlock {
+	/// The number of in-flight operations on the block.
+	operations: usize,
+	/// The last subscription reference of the block was released while operations
+	/// were still in flight, and the backend unpin is left to the last operation.
+	unpin_deferred: bool,
+}
+
+/// Reference count the blocks locked by in-flight operations across all subscriptions.
+type LockedBlocks<Hash> = Arc<Mutex<HashMap<Hash, LockedBlock>>>;
+
 /// Keeps a specific block pinned while the handle is alive.
 /// This object ensures that the block is not unpinned while
 /// executing an RPC method call.
@@ -488,6 +619,7 @@ pub struct BlockGuard<Block: BlockT, BE: Backend<Block>> {
 	with_runtime: bool,
 	response_sender: TracingUnboundedSender<FollowEvent<Block::Hash>>,
 	operation: RegisteredOperation,
+	locked_blocks: LockedBlocks<Block::Hash>,
 	backend: Arc<BE>,
 }
 
@@ -506,13 +638,12 @@ impl<Block: BlockT, BE: Backend<Block>> BlockGuard<Block, BE> {
 		with_runtime: bool,
 		response_sender: TracingUnboundedSender<FollowEvent<Block::Hash>>,
 		operation: RegisteredOperation,
+		locked_blocks: LockedBlocks<Block::Hash>,
 		backend: Arc<BE>,
-	) -> Result<Self, SubscriptionManagementError> {
-		backend
-			.pin_block(hash)
-			.map_err(|err| SubscriptionManagementError::Custom(err.to_string()))?;
+	) -> Self {
+		locked_blocks.lock().entry(hash).or_default().operations += 1;
 
-		Ok(Self { hash, with_runtime, response_sender, operation, backend })
+		Self { hash, with_runtime, response_sender, operation, locked_blocks, backend }
 	}
 
 	/// The `with_runtime` flag of the subscription.
@@ -533,15 +664,25 @@ impl<Block: BlockT, BE: Backend<Block>> BlockGuard<Block, BE> {
 
 impl<Block: BlockT, BE: Backend<Block>> Drop for BlockGuard<Block, BE> {
 	fn drop(&mut self) {
-		self.backend.unpin_block(self.hash);
+		let mut locked_blocks = self.locked_blocks.lock();
+		let Entry::Occupied(mut occupied) = locked_blocks.entry(self.hash) else { return };
+
+		let locked = occupied.get_mut();
+		locked.operations -= 1;
+		if locked.operations == 0 {
+			// The block was unpinned by all subscriptions while this operation was running.
+			if occupied.remove().unpin_deferred {
+				self.backend.unpin_block(self.hash);
+			}
+		}
 	}
 }
 
 /// The data propagated back to the `chainHead_follow` method after
 /// the subscription is successfully inserted.
 pub struct InsertedSubscriptionData<Block: BlockT> {
-	/// Signal that the subscription must stop.
-	pub rx_stop: oneshot::Receiver<()>,
+	/// Signal that the subscription must stop, with the reason of the `Stop` event.
+	pub rx_stop: oneshot::Receiver<&'static str>,
 	/// Receive message responses from the `chainHead` methods.
 	pub response_receiver: TracingUnboundedReceiver<FollowEvent<Block::Hash>>,
 }
@@ -552,35 +693,58 @@ pub struct SubscriptionsInner<Block: BlockT, BE: Backend<Block>> {
 	/// The pinned blocks cannot exceed the [`Self::global_limit`] limit.
 	/// When the limit is exceeded subscriptions are stopped via the `Stop` event.
 	global_blocks: HashMap<Block::Hash, usize>,
+	/// The blocks locked by in-flight operations.
+	///
+	/// Unpinning a block from the backend is deferred until all operations complete.
+	locked_blocks: LockedBlocks<Block::Hash>,
 	/// The maximum number of pinned blocks across all subscriptions.
 	global_max_pinned_blocks: usize,
-	/// The maximum duration that a block is allowed to be pinned per subscription.
-	local_max_pin_duration: Duration,
+	/// The maximum number of pinned blocks per subscription.
+	local_max_pinned_blocks: usize,
+	/// Select the subscriptions stopped when the global limit of pinned blocks is reached.
+	eviction: Arc<dyn PinEvictionPolicy>,
 	/// The maximum number of ongoing operations per subscription.
 	max_ongoing_operations: usize,
+	/// The quota of bytes served per subscription.
+	bandwidth_quota: Option<BandwidthQuota>,
 	/// Map the subscription ID to internal details of the subscription.
 	subs: HashMap<String, SubscriptionState<Block>>,
+	/// Map the resumption token to the subscriptions whose client disconnected.
+	///
+	/// The blocks pinned by these subscriptions are kept until the subscription
+	/// is resumed or expires.
+	parked: HashMap<String, SubscriptionState<Block>>,
 	/// Backend pinning / unpinning blocks.
 	///
 	/// The `Arc` is handled one level-above, but substrate exposes the backend as Arc<T>.
 	backend: Arc<BE>,
+	/// Report the pinned blocks.
+	metrics: MetricsLink,
 }
 
 impl<Block: BlockT, BE: Backend<Block>> SubscriptionsInner<Block, BE> {
 	/// Construct a new [`SubscriptionsInner`] from the specified limits.
 	pub fn new(
 		global_max_pinned_blocks: usize,
-		local_max_pin_duration: Duration,
+		local_max_pinned_blocks: usize,
+		eviction: Arc<dyn PinEvictionPolicy>,
 		max_ongoing_operations: usize,
+		bandwidth_quota: Option<BandwidthQuota>,
 		backend: Arc<BE>,
+		metrics: MetricsLink,
 	) -> Self {
 		SubscriptionsInner {
 			global_blocks: Default::default(),
+			locked_blocks: Default::default(),
 			global_max_pinned_blocks,
-			local_max_pin_duration,
+			local_max_pinned_blocks,
+			eviction,
 			max_ongoing_operations,
+			bandwidth_quota,
 			subs: Default::default(),
+			parked: Default::default(),
 			backend,
+			metrics,
 		}
 	}
 
@@ -600,6 +764,9 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionsInner<Block, BE> {
 				response_sender,
 				blocks: Default::default(),
 				operations: Operations::new(self.max_ongoing_operations),
+				external_pins: Default::default(),
+				fork_filter: None,
+				bandwidth: Bandwidth::new(self.bandwidth_quota),
 			};
 			entry.insert(state);
 
@@ -611,61 +778,122 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionsInner<Block, BE> {
 
 	/// Remove the subscription ID with associated pinned blocks.
 	pub fn remove_subscription(&mut self, sub_id: &str) {
-		let Some(mut sub) = self.subs.remove(sub_id) else { return };
+		self.stop_subscription(sub_id, "removed");
+	}
+
+	/// Remove the subscription ID with associated pinned blocks, for the given reason.
+	fn stop_subscription(&mut self, sub_id: &str, reason: &'static str) {
+		let Some(sub) = self.subs.remove(sub_id) else { return };
+		self.release_subscription(sub, reason);
+	}
+
+	/// Keep the pinned blocks of the subscription under the resumption token,
+	/// after the client of the subscription disconnected.
+	///
+	/// Returns false if the subscription ID is invalid.
+	pub fn park_subscription(&mut self, sub_id: &str, token: String) -> bool {
+		let Some(mut sub) = self.subs.remove(sub_id) else { return false };
+
+		// The follower of the subscription is gone.
+		sub.tx_stop = None;
+		self.parked.insert(token, sub);
+		true
+	}
+
+	/// Resume the subscription parked under the resumption token with a new subscription ID.
+	///
+	/// The new subscription inherits the pinned blocks of the parked subscription.
+	/// Returns none if the token is invalid or expired, or if the subscription ID
+	/// was already inserted.
+	pub fn resume_subscription(
+		&mut self,
+		token: &str,
+		sub_id: String,
+		with_runtime: bool,
+	) -> Option<InsertedSubscriptionData<Block>> {
+		let Entry::Vacant(entry) = self.subs.entry(sub_id) else { return None };
+		let mut state = self.parked.remove(token)?;
+
+		let (tx_stop, rx_stop) = oneshot::channel();
+		let (response_sender, response_receiver) =
+			tracing_unbounded("chain-head-method-responses", QUEUE_SIZE_WARNING);
+		state.with_runtime = with_runtime;
+		state.tx_stop = Some(tx_stop);
+		state.response_sender = response_sender;
+		state.operations = Operations::new(self.max_ongoing_operations);
+		entry.insert(state);
+
+		Some(InsertedSubscriptionData { rx_stop, response_receiver })
+	}
+
+	/// Remove the subscription parked under the resumption token with associated pinned blocks.
+	pub fn expire_parked_subscription(&mut self, token: &str) {
+		let Some(sub) = self.parked.remove(token) else { return };
+		self.release_subscription(sub, "parked_expired");
+	}
 
+	/// Stop the subscription and unpin its blocks.
+	fn release_subscription(&mut self, mut sub: SubscriptionState<Block>, reason: &'static str) {
 		// The `Stop` event can be generated only once.
-		sub.stop();
+		sub.stop(reason);
 
 		for (hash, state) in sub.blocks.iter() {
 			if !state.state_machine.was_unpinned() {
 				self.global_unregister_block(*hash);
 			}
 		}
+
+		for hash in sub.external_pins.keys() {
+			self.global_unregister_block(*hash);
+		}
 	}
 
 	/// Ensure that a new block could be pinned.
 	///
 	/// If the global number of blocks has been reached this method
-	/// will remove all subscriptions that have blocks older than the
-	/// specified pin duration.
+	/// will remove the subscriptions selected by the eviction policy,
+	/// until there is space for pinning a new block.
 	///
-	/// If after removing all subscriptions that exceed the pin duration
-	/// there is no space for pinning a new block, then all subscriptions
+	/// If the policy does not release enough blocks, then all subscriptions
 	/// are terminated.
 	///
 	/// Returns true if the given subscription is also terminated.
 	fn ensure_block_space(&mut self, request_sub_id: &str) -> bool {
-		if self.global_blocks.len() < self.global_max_pinned_blocks {
-			return false
+		// The parked subscriptions are released before any active subscription.
+		if self.global_blocks.len() >= self.global_max_pinned_blocks {
+			let tokens: Vec<_> = self.parked.keys().cloned().collect();
+			for token in tokens {
+				self.expire_parked_subscription(&token);
+			}
 		}
 
-		// Terminate all subscriptions that have blocks older than
-		// the specified pin duration.
-		let now = Instant::now();
-
-		let to_remove: Vec<_> = self
-			.subs
-			.iter_mut()
-			.filter_map(|(sub_id, sub)| {
-				let sub_time = sub.find_oldest_block_timestamp();
-				// Subscriptions older than the specified pin duration should be removed.
-				let should_remove = match now.checked_duration_since(sub_time) {
-					Some(duration) => duration > self.local_max_pin_duration,
-					None => true,
-				};
-				should_remove.then(|| sub_id.clone())
-			})
-			.collect();
-
 		let mut is_terminated = false;
-		for sub_id in to_remove {
-			if sub_id == request_sub_id {
-				is_terminated = true;
+		while self.global_blocks.len() >= self.global_max_pinned_blocks {
+			let candidates: Vec<_> = self
+				.subs
+				.iter()
+				.map(|(sub_id, sub)| sub.eviction_candidate(sub_id, &self.global_blocks))
+				.collect();
+
+			let mut num_removed = 0;
+			for sub_id in self.eviction.select(&candidates) {
+				if !self.subs.contains_key(&sub_id) {
+					continue
+				}
+				if sub_id == request_sub_id {
+					is_terminated = true;
+				}
+				self.stop_subscription(&sub_id, "pin_eviction");
+				num_removed += 1;
+			}
+
+			// The policy cannot release more blocks.
+			if num_removed == 0 {
+				break
 			}
-			self.remove_subscription(&sub_id);
 		}
 
-		// Make sure we have enough space after first pass of terminating subscriptions.
+		// Make sure we have enough space after terminating the selected subscriptions.
 		if self.global_blocks.len() < self.global_max_pinned_blocks {
 			return is_terminated
 		}
@@ -677,7 +905,7 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionsInner<Block, BE> {
 			if sub_id == request_sub_id {
 				is_terminated = true;
 			}
-			self.remove_subscription(&sub_id);
+			self.stop_subscription(&sub_id, "global_pin_limit");
 		}
 		return is_terminated
 	}
@@ -691,6 +919,20 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionsInner<Block, BE> {
 			return Err(SubscriptionManagementError::SubscriptionAbsent)
 		};
 
+		// Block was pinned on behalf of the subscription and therefore
+		// globally tracked. This is the first time the events report it.
+		if sub.external_pins.remove(&hash).is_some() {
+			sub.register_block(hash);
+			return Ok(true)
+		}
+
+		// The subscription exceeded its share of the pinned blocks and is terminated,
+		// without affecting the other subscriptions.
+		if !sub.blocks.contains_key(&hash) && sub.pinned_blocks() >= self.local_max_pinned_blocks {
+			self.stop_subscription(sub_id, "subscription_pin_limit");
+			return Err(SubscriptionManagementError::ExceededLimits)
+		}
+
 		// Block was already registered for this subscription and therefore
 		// globally tracked.
 		if !sub.register_block(hash) {
@@ -709,6 +951,139 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionsInner<Block, BE> {
 		Ok(true)
 	}
 
+	/// Pin the block on behalf of the subscription, before the block
+	/// is reported by the `chainHead_follow` events.
+	///
+	/// This is a no-op if the subscription already contains the block.
+	pub fn pin_block_external(
+		&mut self,
+		sub_id: &str,
+		hash: Block::Hash,
+	) -> Result<(), SubscriptionManagementError> {
+		let Some(sub) = self.subs.get(sub_id) else {
+			return Err(SubscriptionManagementError::SubscriptionAbsent)
+		};
+
+		if sub.contains_block(hash) {
+			return Ok(())
+		}
+
+		// The block was reported and the user called `unpin`.
+		if sub.blocks.contains_key(&hash) {
+			return Err(SubscriptionManagementError::BlockHashAbsent)
+		}
+
+		if sub.pinned_blocks() >= self.local_max_pinned_blocks {
+			self.stop_subscription(sub_id, "subscription_pin_limit");
+			return Err(SubscriptionManagementError::ExceededLimits)
+		}
+
+		// Ensure we have enough space only if the hash is not globally registered.
+		if !self.global_blocks.contains_key(&hash) {
+			// Subscription ID was terminated while ensuring enough space.
+			if self.ensure_block_space(sub_id) {
+				return Err(SubscriptionManagementError::ExceededLimits)
+			}
+		}
+
+		self.global_register_block(hash)?;
+
+		// The subscription was not terminated while ensuring enough space.
+		let Some(sub) = self.subs.get_mut(sub_id) else {
+			self.global_unregister_block(hash);
+			return Err(SubscriptionManagementError::SubscriptionAbsent)
+		};
+		sub.external_pins.insert(hash, Instant::now());
+		Ok(())
+	}
+
+	/// Stop the subscriptions that track at least one block matching the predicate.
+	///
+	/// Returns the number of stopped subscriptions.
+	pub fn stop_subscriptions_with(&mut self, should_stop: impl Fn(&Block::Hash) -> bool) -> usize {
+		let to_remove: Vec<_> = self
+			.subs
+			.iter()
+			.filter(|(_, sub)| {
+				sub.blocks.keys().chain(sub.external_pins.keys()).any(|hash| should_stop(hash))
+			})
+			.map(|(sub_id, _)| sub_id.clone())
+			.collect();
+
+		for sub_id in to_remove.iter() {
+			self.stop_subscription(sub_id, "blocks_unavailable");
+		}
+		to_remove.len()
+	}
+
+	/// Returns true if the subscription contains the block.
+	pub fn contains_block(&self, sub_id: &str, hash: Block::Hash) -> bool {
+		self.subs.get(sub_id).map_or(false, |sub| sub.contains_block(hash))
+	}
+
+	/// Restrict the events of the subscription to the descendants of the block.
+	///
+	/// The block must be pinned by the subscription. The filter is removed if
+	/// the block is not provided.
+	pub fn set_fork_filter(
+		&mut self,
+		sub_id: &str,
+		hash: Option<Block::Hash>,
+	) -> Result<(), SubscriptionManagementError> {
+		let Some(sub) = self.subs.get_mut(sub_id) else {
+			return Err(SubscriptionManagementError::SubscriptionAbsent)
+		};
+
+		if let Some(hash) = hash {
+			if !sub.contains_block(hash) {
+				return Err(SubscriptionManagementError::BlockHashAbsent)
+			}
+		}
+
+		sub.fork_filter = hash;
+		Ok(())
+	}
+
+	/// Get the block whose descendants are reported by the subscription, if any.
+	pub fn fork_filter(&self, sub_id: &str) -> Option<Block::Hash> {
+		self.subs.get(sub_id)?.fork_filter
+	}
+
+	/// Get the blocks pinned by the subscription and the duration since they were pinned,
+	/// ordered from the oldest pin.
+	///
+	/// Returns `None` if the subscription is not present.
+	pub fn pinned_blocks(&self, sub_id: &str) -> Option<Vec<(Block::Hash, Duration)>> {
+		let sub = self.subs.get(sub_id)?;
+
+		let mut pinned: Vec<_> = sub
+			.blocks
+			.iter()
+			.filter(|(_, state)| !state.state_machine.was_unpinned())
+			.map(|(hash, state)| (*hash, state.timestamp))
+			.chain(sub.external_pins.iter().map(|(hash, timestamp)| (*hash, *timestamp)))
+			.collect();
+		pinned.sort_by_key(|(_, timestamp)| *timestamp);
+
+		let pinned = pinned.into_iter().map(|(hash, timestamp)| (hash, timestamp.elapsed()));
+		Some(pinned.collect())
+	}
+
+	/// List the subscriptions, ordered by subscription ID.
+	pub fn subscriptions_info(&self) -> Vec<FollowSubscriptionInfo<Block::Hash>> {
+		let mut info: Vec<_> = self
+			.subs
+			.iter()
+			.map(|(sub_id, sub)| FollowSubscriptionInfo {
+				sub_id: sub_id.clone(),
+				with_runtime: sub.with_runtime,
+				pinned_blocks: self.pinned_blocks(sub_id).unwrap_or_default(),
+			})
+			.collect();
+		info.sort_by(|a, b| a.sub_id.cmp(&b.sub_id));
+		info
+	}
+
 	/// Register the block internally.
 	///
 	/// If the block is present the reference counter is increased.
@@ -727,6 +1102,7 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionsInner<Block, BE> {
 					.map_err(|err| SubscriptionManagementError::Custom(err.to_string()))?;
 
 				vacant.insert(1);
+				self.metrics.report(|metrics| metrics.pinned_blocks.inc());
 			},
 		};
 		Ok(())
@@ -737,13 +1113,18 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionsInner<Block, BE> {
 	/// If the block is present the reference counter is decreased.
 	/// If this is the last reference of the block, the block
 	/// is unpinned from the backend and removed from internal tracking.
+	/// The backend unpin is deferred while operations hold the block locked.
 	fn global_unregister_block(&mut self, hash: Block::Hash) {
 		if let Entry::Occupied(mut occupied) = self.global_blocks.entry(hash) {
 			let counter = occupied.get_mut();
 			if *counter == 1 {
-				// Unpin the block from the backend.
-				self.backend.unpin_block(hash);
+				match self.locked_blocks.lock().get_mut(&hash) {
+					// The last operation on the block unpins it from the backend.
+					Some(locked) => locked.unpin_deferred = true,
+					None => self.backend.unpin_block(hash),
+				}
 				occupied.remove();
+				self.metrics.report(|metrics| metrics.pinned_blocks.dec());
 			} else {
 				*counter -= 1;
 			}
@@ -754,18 +1135,39 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionsInner<Block, BE> {
 		&mut self,
 		sub_id: &str,
 		hash: Block::Hash,
+	) -> Result<(), SubscriptionManagementError> {
+		self.unpin_blocks(sub_id, std::iter::once(hash))
+	}
+
+	/// Unpin the blocks from the subscription.
+	///
+	/// No block is unpinned if any of the blocks is not pinned by the subscription.
+	pub fn unpin_blocks(
+		&mut self,
+		sub_id: &str,
+		hashes: impl IntoIterator<Item = Block::Hash>,
 	) -> Result<(), SubscriptionManagementError> {
 		let Some(sub) = self.subs.get_mut(sub_id) else {
 			return Err(SubscriptionManagementError::SubscriptionAbsent)
 		};
 
-		// Check that unpin was not called before and the block was pinned
+		// Check that unpin was not called before and the blocks were pinned
 		// for this subscription.
-		if !sub.unregister_block(hash) {
+		let hashes: HashSet<_> = hashes.into_iter().collect();
+		if hashes.iter().any(|hash| !sub.contains_block(*hash)) {
 			return Err(SubscriptionManagementError::BlockHashAbsent)
 		}
 
-		self.global_unregister_block(hash);
+		for hash in &hashes {
+			// Block was pinned on behalf of the subscription and not yet reported.
+			if sub.external_pins.remove(hash).is_none() {
+				sub.unregister_block(*hash);
+			}
+		}
+
+		for hash in hashes {
+			self.global_unregister_block(hash);
+		}
 		Ok(())
 	}
 
@@ -783,29 +1185,50 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionsInner<Block, BE> {
 			return Err(SubscriptionManagementError::BlockHashAbsent)
 		}
 
+		// The subscription is throttled until the next period of its quota.
+		if sub.bandwidth.is_exhausted() {
+			return Err(SubscriptionManagementError::ExceededLimits)
+		}
+
 		let Some(operation) = sub.register_operation(to_reserve) else {
 			// Error when the server cannot execute at least one operation.
 			return Err(SubscriptionManagementError::ExceededLimits)
 		};
 
-		BlockGuard::new(
+		Ok(BlockGuard::new(
 			hash,
 			sub.with_runtime,
 			sub.response_sender.clone(),
 			operation,
+			self.locked_blocks.clone(),
 			self.backend.clone(),
-		)
+		))
 	}
 
 	pub fn get_operation(&mut self, sub_id: &str, id: &str) -> Option<OperationState> {
 		let state = self.subs.get(sub_id)?;
 		state.get_operation(id)
 	}
+
+	pub fn record_bandwidth(&mut self, sub_id: &str, bytes: u64) {
+		if let Some(sub) = self.subs.get_mut(sub_id) {
+			sub.bandwidth.record(bytes);
+		}
+	}
+
+	pub fn is_throttled(&mut self, sub_id: &str) -> bool {
+		self.subs.get_mut(sub_id).map_or(false, |sub| sub.bandwidth.is_exhausted())
+	}
+
+	pub fn bytes_served(&self, sub_id: &str) -> Option<u64> {
+		self.subs.get(sub_id).map(|sub| sub.bandwidth.total_bytes)
+	}
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::chain_head::subscription::{AgeEviction, LruEviction};
 	use sc_block_builder::BlockBuilderProvider;
 	use sc_service::client::new_in_mem;
 	use sp_consensus::BlockOrigin;
@@ -915,6 +1338,9 @@ mod tests {
 			response_sender,
 			operations: Operations::new(MAX_OPERATIONS_PER_SUB),
 			blocks: Default::default(),
+			external_pins: Default::default(),
+			fork_filter: None,
+			bandwidth: Bandwidth::new(None),
 		};
 
 		let hash = H256::random();
@@ -944,6 +1370,9 @@ mod tests {
 			response_sender,
 			blocks: Default::default(),
 			operations: Operations::new(MAX_OPERATIONS_PER_SUB),
+			external_pins: Default::default(),
+			fork_filter: None,
+			bandwidth: Bandwidth::new(None),
 		};
 
 		let hash = H256::random();
@@ -975,8 +1404,15 @@ mod tests {
 	fn subscription_lock_block() {
 		let builder = TestClientBuilder::new();
 		let backend = builder.backend();
-		let mut subs =
-			SubscriptionsInner::new(10, Duration::from_secs(10), MAX_OPERATIONS_PER_SUB, backend);
+		let mut subs = SubscriptionsInner::new(
+			10,
+			10,
+			Arc::new(AgeEviction::new(Duration::from_secs(10))),
+			MAX_OPERATIONS_PER_SUB,
+			None,
+			backend,
+			Default::default(),
+		);
 
 		let id = "abc".to_string();
 		let hash = H256::random();
@@ -1008,8 +1444,15 @@ mod tests {
 		let hash = block.header.hash();
 		futures::executor::block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
 
-		let mut subs =
-			SubscriptionsInner::new(10, Duration::from_secs(10), MAX_OPERATIONS_PER_SUB, backend);
+		let mut subs = SubscriptionsInner::new(
+			10,
+			10,
+			Arc::new(AgeEviction::new(Duration::from_secs(10))),
+			MAX_OPERATIONS_PER_SUB,
+			None,
+			backend,
+			Default::default(),
+		);
 		let id = "abc".to_string();
 
 		let _stop = subs.insert_subscription(id.clone(), true).unwrap();
@@ -1031,6 +1474,48 @@ mod tests {
 		assert_eq!(err, SubscriptionManagementError::BlockHashAbsent);
 	}
 
+	#[test]
+	fn unpin_deferred_until_operations_complete() {
+		let (backend, mut client) = init_backend();
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		let hash = block.header.hash();
+		futures::executor::block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
+
+		let mut subs = SubscriptionsInner::new(
+			10,
+			10,
+			Arc::new(AgeEviction::new(Duration::from_secs(10))),
+			MAX_OPERATIONS_PER_SUB,
+			None,
+			backend.clone(),
+			Default::default(),
+		);
+		let id = "abc".to_string();
+		let _stop = subs.insert_subscription(id.clone(), true).unwrap();
+
+		assert_eq!(subs.pin_block(&id, hash).unwrap(), true);
+		let pinned_refs = backend.pin_refs(&hash).unwrap();
+
+		let first = subs.lock_block(&id, hash, 1).unwrap();
+		let second = subs.lock_block(&id, hash, 1).unwrap();
+		// Locking the block does not pin it again in the backend.
+		assert_eq!(backend.pin_refs(&hash).unwrap(), pinned_refs);
+
+		// The block is released by the subscription, but stays pinned for the operations.
+		subs.unpin_block(&id, hash).unwrap();
+		assert!(subs.global_blocks.get(&hash).is_none());
+		assert!(subs.locked_blocks.lock().get(&hash).unwrap().unpin_deferred);
+		assert_eq!(backend.pin_refs(&hash).unwrap(), pinned_refs);
+
+		drop(first);
+		assert_eq!(backend.pin_refs(&hash).unwrap(), pinned_refs);
+
+		// The last operation unpins the block from the backend.
+		drop(second);
+		assert!(subs.locked_blocks.lock().get(&hash).is_none());
+		assert_eq!(backend.pin_refs(&hash).unwrap(), pinned_refs - 1);
+	}
+
 	#[test]
 	fn subscription_ref_count() {
 		let (backend, mut client) = init_backend();
@@ -1038,8 +1523,15 @@ mod tests {
 		let hash = block.header.hash();
 		futures::executor::block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
 
-		let mut subs =
-			SubscriptionsInner::new(10, Duration::from_secs(10), MAX_OPERATIONS_PER_SUB, backend);
+		let mut subs = SubscriptionsInner::new(
+			10,
+			10,
+			Arc::new(AgeEviction::new(Duration::from_secs(10))),
+			MAX_OPERATIONS_PER_SUB,
+			None,
+			backend,
+			Default::default(),
+		);
 		let id = "abc".to_string();
 
 		let _stop = subs.insert_subscription(id.clone(), true).unwrap();
@@ -1074,6 +1566,255 @@ mod tests {
 		assert!(subs.global_blocks.get(&hash).is_none());
 	}
 
+	#[test]
+	fn subscription_reports_pinned_blocks() {
+		let (backend, mut client) = init_backend();
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		let hash = block.header.hash();
+		futures::executor::block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
+
+		let registry = prometheus_endpoint::Registry::new();
+		let metrics = MetricsLink::new(Some(&registry));
+		let mut subs = SubscriptionsInner::new(
+			10,
+			10,
+			Arc::new(AgeEviction::new(Duration::from_secs(10))),
+			MAX_OPERATIONS_PER_SUB,
+			None,
+			backend,
+			metrics.clone(),
+		);
+		let pinned_blocks = || {
+			let mut pinned_blocks = 0;
+			metrics.report(|metrics| pinned_blocks = metrics.pinned_blocks.get());
+			pinned_blocks
+		};
+
+		let id = "abc".to_string();
+		let id_second = "abcd".to_string();
+		let _stop = subs.insert_subscription(id.clone(), true).unwrap();
+		let _stop = subs.insert_subscription(id_second.clone(), true).unwrap();
+
+		// The blocks are counted once across the subscriptions.
+		assert_eq!(subs.pin_block(&id, hash).unwrap(), true);
+		assert_eq!(subs.pin_block(&id_second, hash).unwrap(), true);
+		assert_eq!(pinned_blocks(), 1);
+
+		subs.unpin_block(&id, hash).unwrap();
+		assert_eq!(pinned_blocks(), 1);
+		subs.unpin_block(&id_second, hash).unwrap();
+		assert_eq!(pinned_blocks(), 0);
+	}
+
+	#[test]
+	fn unpin_blocks_atomically() {
+		let (backend, mut client) = init_backend();
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		let hash_1 = block.header.hash();
+		futures::executor::block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		let hash_2 = block.header.hash();
+		futures::executor::block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
+
+		let mut subs = SubscriptionsInner::new(
+			10,
+			10,
+			Arc::new(AgeEviction::new(Duration::from_secs(10))),
+			MAX_OPERATIONS_PER_SUB,
+			None,
+			backend,
+			Default::default(),
+		);
+		let id = "abc".to_string();
+
+		let _stop = subs.insert_subscription(id.clone(), true).unwrap();
+		assert_eq!(subs.pin_block(&id, hash_1).unwrap(), true);
+		assert_eq!(subs.pin_block(&id, hash_2).unwrap(), true);
+
+		// No block is unpinned if one of the blocks is not pinned.
+		let invalid_hash = H256::from_low_u64_be(1);
+		let err = subs.unpin_blocks(&id, vec![hash_1, invalid_hash]).unwrap_err();
+		assert_eq!(err, SubscriptionManagementError::BlockHashAbsent);
+		assert!(subs.contains_block(&id, hash_1));
+		assert_eq!(*subs.global_blocks.get(&hash_1).unwrap(), 1);
+
+		// The duplicated hashes are unpinned once.
+		subs.unpin_blocks(&id, vec![hash_1, hash_2, hash_1]).unwrap();
+		assert!(!subs.contains_block(&id, hash_1));
+		assert!(!subs.contains_block(&id, hash_2));
+		assert!(subs.global_blocks.is_empty());
+	}
+
+	#[test]
+	fn subscription_pin_block_external() {
+		let (backend, mut client) = init_backend();
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		let hash = block.header.hash();
+		futures::executor::block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
+
+		let mut subs = SubscriptionsInner::new(
+			10,
+			10,
+			Arc::new(AgeEviction::new(Duration::from_secs(10))),
+			MAX_OPERATIONS_PER_SUB,
+			None,
+			backend,
+			Default::default(),
+		);
+		let id = "abc".to_string();
+
+		let _stop = subs.insert_subscription(id.clone(), true).unwrap();
+		assert!(!subs.contains_block(&id, hash));
+
+		// Pin the block before the events report it.
+		subs.pin_block_external(&id, hash).unwrap();
+		assert!(subs.contains_block(&id, hash));
+		assert_eq!(*subs.global_blocks.get(&hash).unwrap(), 1);
+		// Pinning again is a no-op.
+		subs.pin_block_external(&id, hash).unwrap();
+		assert_eq!(*subs.global_blocks.get(&hash).unwrap(), 1);
+
+		// The events report the block for the first time without pinning it again.
+		assert_eq!(subs.pin_block(&id, hash).unwrap(), true);
+		assert_eq!(*subs.global_blocks.get(&hash).unwrap(), 1);
+		assert_eq!(subs.pin_block(&id, hash).unwrap(), false);
+
+		subs.unpin_block(&id, hash).unwrap();
+		assert!(!subs.contains_block(&id, hash));
+		assert!(subs.global_blocks.get(&hash).is_none());
+
+		// Cannot pin a block that was unpinned by the user.
+		let err = subs.pin_block_external(&id, hash).unwrap_err();
+		assert_eq!(err, SubscriptionManagementError::BlockHashAbsent);
+
+		// External pins are released when the subscription is removed.
+		let id_second = "abcd".to_string();
+		let _stop = subs.insert_subscription(id_second.clone(), true).unwrap();
+		subs.pin_block_external(&id_second, hash).unwrap();
+		assert_eq!(*subs.global_blocks.get(&hash).unwrap(), 1);
+		subs.remove_subscription(&id_second);
+		assert!(subs.global_blocks.get(&hash).is_none());
+	}
+
+	#[test]
+	fn subscription_fork_filter() {
+		let (backend, mut client) = init_backend();
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		let hash = block.header.hash();
+		futures::executor::block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
+
+		let mut subs = SubscriptionsInner::new(
+			10,
+			10,
+			Arc::new(AgeEviction::new(Duration::from_secs(10))),
+			MAX_OPERATIONS_PER_SUB,
+			None,
+			backend,
+			Default::default(),
+		);
+		let id = "abc".to_string();
+
+		let err = subs.set_fork_filter(&id, Some(hash)).unwrap_err();
+		assert_eq!(err, SubscriptionManagementError::SubscriptionAbsent);
+
+		let _stop = subs.insert_subscription(id.clone(), true).unwrap();
+		assert_eq!(subs.fork_filter(&id), None);
+
+		// The block must be pinned by the subscription.
+		let err = subs.set_fork_filter(&id, Some(hash)).unwrap_err();
+		assert_eq!(err, SubscriptionManagementError::BlockHashAbsent);
+
+		subs.pin_block(&id, hash).unwrap();
+		subs.set_fork_filter(&id, Some(hash)).unwrap();
+		assert_eq!(subs.fork_filter(&id), Some(hash));
+
+		// The filter is kept after the block is unpinned.
+		subs.unpin_block(&id, hash).unwrap();
+		assert_eq!(subs.fork_filter(&id), Some(hash));
+
+		subs.set_fork_filter(&id, None).unwrap();
+		assert_eq!(subs.fork_filter(&id), None);
+	}
+
+	#[test]
+	fn subscription_pinned_blocks() {
+		let (backend, mut client) = init_backend();
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		let hash = block.header.hash();
+		futures::executor::block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		let hash_second = block.header.hash();
+		futures::executor::block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
+
+		let mut subs = SubscriptionsInner::new(
+			10,
+			10,
+			Arc::new(AgeEviction::new(Duration::from_secs(10))),
+			MAX_OPERATIONS_PER_SUB,
+			None,
+			backend,
+			Default::default(),
+		);
+		let id = "abc".to_string();
+		assert!(subs.pinned_blocks(&id).is_none());
+
+		let _stop = subs.insert_subscription(id.clone(), true).unwrap();
+		assert!(subs.pinned_blocks(&id).unwrap().is_empty());
+
+		// Both the reported and the externally pinned blocks are listed, oldest first.
+		subs.pin_block(&id, hash).unwrap();
+		subs.pin_block_external(&id, hash_second).unwrap();
+		let pinned = subs.pinned_blocks(&id).unwrap();
+		let hashes: Vec<_> = pinned.iter().map(|(hash, _)| *hash).collect();
+		assert_eq!(hashes, vec![hash, hash_second]);
+		assert!(pinned[0].1 >= pinned[1].1);
+
+		// The unpinned blocks are no longer listed.
+		subs.unpin_block(&id, hash).unwrap();
+		let pinned = subs.pinned_blocks(&id).unwrap();
+		assert_eq!(pinned.len(), 1);
+		assert_eq!(pinned[0].0, hash_second);
+	}
+
+	#[test]
+	fn subscription_stop_subscriptions_with() {
+		let (backend, mut client) = init_backend();
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		let hash_1 = block.header.hash();
+		futures::executor::block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		let hash_2 = block.header.hash();
+		futures::executor::block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
+
+		let mut subs = SubscriptionsInner::new(
+			10,
+			10,
+			Arc::new(AgeEviction::new(Duration::from_secs(10))),
+			MAX_OPERATIONS_PER_SUB,
+			None,
+			backend,
+			Default::default(),
+		);
+		let id_1 = "abc".to_string();
+		let id_2 = "abcd".to_string();
+
+		let mut sub_data_1 = subs.insert_subscription(id_1.clone(), true).unwrap();
+		assert_eq!(subs.pin_block(&id_1, hash_1).unwrap(), true);
+		assert_eq!(subs.pin_block(&id_1, hash_2).unwrap(), true);
+
+		let mut sub_data_2 = subs.insert_subscription(id_2.clone(), true).unwrap();
+		assert_eq!(subs.pin_block(&id_2, hash_1).unwrap(), true);
+
+		// Only the first subscription tracks the second block.
+		assert_eq!(subs.stop_subscriptions_with(|hash| *hash == hash_2), 1);
+		assert_eq!(sub_data_1.rx_stop.try_recv().unwrap(), Some("blocks_unavailable"));
+		assert!(sub_data_2.rx_stop.try_recv().unwrap().is_none());
+
+		assert!(subs.subs.get(&id_1).is_none());
+		assert_eq!(*subs.global_blocks.get(&hash_1).unwrap(), 1);
+		assert!(subs.global_blocks.get(&hash_2).is_none());
+	}
+
 	#[test]
 	fn subscription_remove_subscription() {
 		let (backend, mut client) = init_backend();
@@ -1087,8 +1828,15 @@ mod tests {
 		let hash_3 = block.header.hash();
 		futures::executor::block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
 
-		let mut subs =
-			SubscriptionsInner::new(10, Duration::from_secs(10), MAX_OPERATIONS_PER_SUB, backend);
+		let mut subs = SubscriptionsInner::new(
+			10,
+			10,
+			Arc::new(AgeEviction::new(Duration::from_secs(10))),
+			MAX_OPERATIONS_PER_SUB,
+			None,
+			backend,
+			Default::default(),
+		);
 		let id_1 = "abc".to_string();
 		let id_2 = "abcd".to_string();
 
@@ -1119,6 +1867,48 @@ mod tests {
 		assert_eq!(subs.global_blocks.len(), 0);
 	}
 
+	#[test]
+	fn subscription_park_and_resume() {
+		let (backend, mut client) = init_backend();
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		let hash = block.header.hash();
+		futures::executor::block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
+
+		let mut subs = SubscriptionsInner::new(
+			10,
+			10,
+			Arc::new(AgeEviction::new(Duration::from_secs(10))),
+			MAX_OPERATIONS_PER_SUB,
+			None,
+			backend,
+			Default::default(),
+		);
+		let id_1 = "abc".to_string();
+		let id_2 = "abcd".to_string();
+		let token = "token".to_string();
+
+		let _stop = subs.insert_subscription(id_1.clone(), true).unwrap();
+		assert_eq!(subs.pin_block(&id_1, hash).unwrap(), true);
+
+		// The parked subscription keeps its blocks pinned.
+		assert!(subs.park_subscription(&id_1, token.clone()));
+		assert!(!subs.park_subscription(&id_1, token.clone()));
+		assert!(!subs.contains_block(&id_1, hash));
+		assert_eq!(*subs.global_blocks.get(&hash).unwrap(), 1);
+
+		// The resumed subscription inherits the pinned blocks.
+		assert!(subs.resume_subscription("invalid", id_2.clone(), true).is_none());
+		let _stop = subs.resume_subscription(&token, id_2.clone(), true).unwrap();
+		assert!(subs.contains_block(&id_2, hash));
+		assert!(subs.resume_subscription(&token, "other".into(), true).is_none());
+
+		// The expired subscription releases its blocks.
+		assert!(subs.park_subscription(&id_2, token.clone()));
+		subs.expire_parked_subscription(&token);
+		assert!(subs.global_blocks.get(&hash).is_none());
+		assert!(subs.resume_subscription(&token, id_2, true).is_none());
+	}
+
 	#[test]
 	fn subscription_check_limits() {
 		let (backend, mut client) = init_backend();
@@ -1133,8 +1923,15 @@ mod tests {
 		futures::executor::block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
 
 		// Maximum number of pinned blocks is 2.
-		let mut subs =
-			SubscriptionsInner::new(2, Duration::from_secs(10), MAX_OPERATIONS_PER_SUB, backend);
+		let mut subs = SubscriptionsInner::new(
+			2,
+			2,
+			Arc::new(AgeEviction::new(Duration::from_secs(10))),
+			MAX_OPERATIONS_PER_SUB,
+			None,
+			backend,
+			Default::default(),
+		);
 		let id_1 = "abc".to_string();
 		let id_2 = "abcd".to_string();
 
@@ -1184,8 +1981,15 @@ mod tests {
 		futures::executor::block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
 
 		// Maximum number of pinned blocks is 2 and maximum pin duration is 5 second.
-		let mut subs =
-			SubscriptionsInner::new(2, Duration::from_secs(5), MAX_OPERATIONS_PER_SUB, backend);
+		let mut subs = SubscriptionsInner::new(
+			2,
+			2,
+			Arc::new(AgeEviction::new(Duration::from_secs(5))),
+			MAX_OPERATIONS_PER_SUB,
+			None,
+			backend,
+			Default::default(),
+		);
 		let id_1 = "abc".to_string();
 		let id_2 = "abcd".to_string();
 
@@ -1230,12 +2034,115 @@ mod tests {
 		assert_eq!(subs.global_blocks.len(), 0);
 	}
 
+	#[test]
+	fn subscription_check_limits_with_lru_eviction() {
+		let (backend, mut client) = init_backend();
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		let hash_1 = block.header.hash();
+		futures::executor::block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		let hash_2 = block.header.hash();
+		futures::executor::block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		let hash_3 = block.header.hash();
+		futures::executor::block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
+
+		// Maximum number of pinned blocks is 2.
+		let mut subs = SubscriptionsInner::new(
+			2,
+			2,
+			Arc::new(LruEviction),
+			MAX_OPERATIONS_PER_SUB,
+			None,
+			backend,
+			Default::default(),
+		);
+		let id_1 = "abc".to_string();
+		let id_2 = "abcd".to_string();
+
+		let _stop = subs.insert_subscription(id_1.clone(), true).unwrap();
+		assert_eq!(subs.pin_block(&id_1, hash_1).unwrap(), true);
+
+		let _stop = subs.insert_subscription(id_2.clone(), true).unwrap();
+		assert_eq!(subs.pin_block(&id_2, hash_2).unwrap(), true);
+
+		// The first subscription pinned a block least recently and is terminated.
+		assert_eq!(subs.pin_block(&id_2, hash_3).unwrap(), true);
+
+		let err = subs.lock_block(&id_1, hash_1, 1).unwrap_err();
+		assert_eq!(err, SubscriptionManagementError::SubscriptionAbsent);
+		let _block_guard = subs.lock_block(&id_2, hash_3, 1).unwrap();
+
+		assert!(subs.global_blocks.get(&hash_1).is_none());
+		assert_eq!(*subs.global_blocks.get(&hash_2).unwrap(), 1);
+		assert_eq!(*subs.global_blocks.get(&hash_3).unwrap(), 1);
+	}
+
+	#[test]
+	fn subscription_check_local_limits() {
+		let (backend, mut client) = init_backend();
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		let hash_1 = block.header.hash();
+		futures::executor::block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		let hash_2 = block.header.hash();
+		futures::executor::block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		let hash_3 = block.header.hash();
+		futures::executor::block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
+
+		// Maximum number of pinned blocks is 10 and 2 per subscription.
+		let mut subs = SubscriptionsInner::new(
+			10,
+			2,
+			Arc::new(AgeEviction::new(Duration::from_secs(10))),
+			MAX_OPERATIONS_PER_SUB,
+			None,
+			backend,
+			Default::default(),
+		);
+		let id_1 = "abc".to_string();
+		let id_2 = "abcd".to_string();
+
+		let mut stop_1 = subs.insert_subscription(id_1.clone(), true).unwrap();
+		assert_eq!(subs.pin_block(&id_1, hash_1).unwrap(), true);
+		assert_eq!(subs.pin_block(&id_1, hash_2).unwrap(), true);
+
+		let mut stop_2 = subs.insert_subscription(id_2.clone(), true).unwrap();
+		assert_eq!(subs.pin_block(&id_2, hash_1).unwrap(), true);
+
+		// The unpinned blocks do not count towards the limit.
+		subs.unpin_block(&id_2, hash_1).unwrap();
+		assert_eq!(subs.pin_block(&id_2, hash_2).unwrap(), true);
+		subs.pin_block_external(&id_2, hash_3).unwrap();
+
+		// Pinning a third block terminates only the first subscription.
+		let err = subs.pin_block(&id_1, hash_3).unwrap_err();
+		assert_eq!(err, SubscriptionManagementError::ExceededLimits);
+		let err = subs.lock_block(&id_1, hash_1, 1).unwrap_err();
+		assert_eq!(err, SubscriptionManagementError::SubscriptionAbsent);
+		assert_eq!(stop_1.rx_stop.try_recv().unwrap(), Some("subscription_pin_limit"));
+
+		let _block_guard = subs.lock_block(&id_2, hash_2, 1).unwrap();
+		assert!(stop_2.rx_stop.try_recv().unwrap().is_none());
+		assert!(subs.global_blocks.get(&hash_1).is_none());
+		assert_eq!(*subs.global_blocks.get(&hash_2).unwrap(), 1);
+		assert_eq!(*subs.global_blocks.get(&hash_3).unwrap(), 1);
+	}
+
 	#[test]
 	fn subscription_check_stop_event() {
 		let builder = TestClientBuilder::new();
 		let backend = builder.backend();
-		let mut subs =
-			SubscriptionsInner::new(10, Duration::from_secs(10), MAX_OPERATIONS_PER_SUB, backend);
+		let mut subs = SubscriptionsInner::new(
+			10,
+			10,
+			Arc::new(AgeEviction::new(Duration::from_secs(10))),
+			MAX_OPERATIONS_PER_SUB,
+			None,
+			backend,
+			Default::default(),
+		);
 
 		let id = "abc".to_string();
 
@@ -1246,11 +2153,53 @@ mod tests {
 		assert!(res.is_none());
 
 		let sub = subs.subs.get_mut(&id).unwrap();
-		sub.stop();
+		sub.stop("test");
 
-		// Check the signal was received.
+		// Check the signal was received with its reason.
 		let res = sub_data.rx_stop.try_recv().unwrap();
-		assert!(res.is_some());
+		assert_eq!(res, Some("test"));
+	}
+
+	#[test]
+	fn subscription_bandwidth_quota() {
+		let (backend, mut client) = init_backend();
+
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		let hash = block.header.hash();
+		futures::executor::block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
+
+		let quota = BandwidthQuota { max_bytes: 100, period: Duration::from_secs(60) };
+		let mut subs = SubscriptionsInner::new(
+			10,
+			10,
+			Arc::new(AgeEviction::new(Duration::from_secs(10))),
+			MAX_OPERATIONS_PER_SUB,
+			Some(quota),
+			backend,
+			Default::default(),
+		);
+		let id = "abc".to_string();
+
+		let _stop = subs.insert_subscription(id.clone(), true).unwrap();
+		assert_eq!(subs.pin_block(&id, hash).unwrap(), true);
+		assert_eq!(subs.bytes_served(&id), Some(0));
+
+		// The quota is not exhausted.
+		subs.record_bandwidth(&id, 60);
+		let block = subs.lock_block(&id, hash, 1).unwrap();
+		drop(block);
+
+		// The subscription is throttled once the quota is exhausted.
+		subs.record_bandwidth(&id, 40);
+		let err = subs.lock_block(&id, hash, 1).unwrap_err();
+		assert_eq!(err, SubscriptionManagementError::ExceededLimits);
+		assert_eq!(subs.bytes_served(&id), Some(100));
+
+		// The next period restores the quota, while the served bytes are still reported.
+		let sub = subs.subs.get_mut(&id).unwrap();
+		sub.bandwidth.period_start -= quota.period;
+		let _block = subs.lock_block(&id, hash, 1).unwrap();
+		assert_eq!(subs.bytes_served(&id), Some(100));
 	}
 
 	#[test]
@@ -1278,4 +2227,32 @@ mod tests {
 		let permit_three = ops.reserve_at_most(1).unwrap();
 		assert_eq!(permit_three.num_ops, 1);
 	}
+
+	#[tokio::test]
+	async fn stop_operation_in_progress() {
+		let mut ops = Operations::new(2);
+
+		// The operation is stopped before emitting `waitingForContinue`.
+		let mut operation = ops.register_operation(1).unwrap();
+		let state = ops.get_operation(&operation.operation_id()).unwrap();
+		assert!(!operation.was_stopped());
+		state.stop_operation();
+		assert!(operation.was_stopped());
+		// The operation does not wait for a continue that will never come.
+		operation.wait_for_continue().await;
+		assert!(!state.submit_continue());
+
+		// The operation waiting for continue is woken up.
+		let mut operation = ops.register_operation(1).unwrap();
+		let state = ops.get_operation(&operation.operation_id()).unwrap();
+		let mut wait = Box::pin(operation.wait_for_continue());
+		assert!(futures::poll!(&mut wait).is_pending());
+		state.stop_operation();
+		wait.await;
+		assert!(operation.was_stopped());
+
+		// The stopped operations are released.
+		drop(operation);
+		assert!(ops.get_operation("1").is_none());
+	}
 }
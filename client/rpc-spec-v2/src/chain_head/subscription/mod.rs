@@ -16,19 +16,212 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use crate::chain_head::metrics::MetricsLink;
 use parking_lot::RwLock;
 use sc_client_api::Backend;
 use sp_runtime::traits::Block as BlockT;
 use std::{sync::Arc, time::Duration};
 
 mod error;
+mod eviction;
 mod inner;
 
 use self::inner::SubscriptionsInner;
 
 pub use self::inner::OperationState;
 pub use error::SubscriptionManagementError;
-pub use inner::{BlockGuard, InsertedSubscriptionData};
+pub use eviction::{
+	AgeEviction, LruEviction, MemoryWeightedEviction, PinEvictionPolicy, SubscriptionPins,
+};
+pub use inner::{BandwidthQuota, BlockGuard, InsertedSubscriptionData};
+
+pub(crate) use inner::Bandwidth;
+
+/// Access the blocks pinned by the `chainHead_follow` subscriptions.
+///
+/// This is used by other RPC methods to report whether a block is available
+/// to a `chainHead_follow` subscription and to pin blocks on its behalf.
+pub trait FollowSubscriptionPins<Hash>: Send + Sync {
+	/// Returns true if the block is pinned by the `chainHead_follow` subscription.
+	fn is_block_pinned(&self, sub_id: &str, hash: Hash) -> bool;
+
+	/// Pin the block on behalf of the `chainHead_follow` subscription.
+	///
+	/// The block must be unpinned via `chainHead_unpin`, even if it was not yet
+	/// reported by the `chainHead_follow` events.
+	///
+	/// Returns true if the block is pinned by the subscription.
+	fn pin_block_external(&self, sub_id: &str, hash: Hash) -> bool;
+
+	/// Stop the `chainHead_follow` subscriptions that track at least one block
+	/// matching the predicate.
+	///
+	/// The stopped subscriptions receive the `Stop` event.
+	///
+	/// Returns the number of stopped subscriptions.
+	fn stop_subscriptions_with(&self, should_stop: &dyn Fn(&Hash) -> bool) -> usize;
+
+	/// List the `chainHead_follow` subscriptions, ordered by subscription ID.
+	fn subscriptions_info(&self) -> Vec<FollowSubscriptionInfo<Hash>>;
+}
+
+/// The details of a `chainHead_follow` subscription.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FollowSubscriptionInfo<Hash> {
+	/// The subscription ID.
+	pub sub_id: String,
+	/// The subscription was started with the runtime updates flag.
+	pub with_runtime: bool,
+	/// The blocks pinned by the subscription and the duration since they were pinned,
+	/// ordered from the oldest pin.
+	pub pinned_blocks: Vec<(Hash, Duration)>,
+}
+
+/// Store the blocks pinned by the `chainHead_follow` subscriptions.
+///
+/// [`SubscriptionManagement`] is the default in-memory store. Alternative stores, for example
+/// shared across RPC workers or persisted, are provided to `ChainHead::with_pin_store` and
+/// typically wrap the default store obtained from `ChainHead::pin_store`.
+pub trait PinStore<Block: BlockT, BE: Backend<Block>>: FollowSubscriptionPins<Block::Hash> {
+	/// Insert a new subscription ID.
+	///
+	/// If the subscription was not previously inserted, returns the receiver that is
+	/// triggered upon the "Stop" event. Otherwise, if the subscription ID was already
+	/// inserted returns none.
+	fn insert_subscription(
+		&self,
+		sub_id: String,
+		runtime_updates: bool,
+	) -> Option<InsertedSubscriptionData<Block>>;
+
+	/// Remove the subscription ID with associated pinned blocks.
+	fn remove_subscription(&self, sub_id: &str);
+
+	/// Keep the pinned blocks of the subscription under the resumption token,
+	/// after the client of the subscription disconnected.
+	///
+	/// Returns false if the subscription ID is invalid.
+	fn park_subscription(&self, sub_id: &str, token: String) -> bool;
+
+	/// Resume the subscription parked under the resumption token with a new subscription ID.
+	///
+	/// Returns none if the token is invalid or expired, or if the subscription ID
+	/// was already inserted.
+	fn resume_subscription(
+		&self,
+		token: &str,
+		sub_id: String,
+		runtime_updates: bool,
+	) -> Option<InsertedSubscriptionData<Block>>;
+
+	/// Remove the subscription parked under the resumption token with associated pinned blocks.
+	fn expire_parked_subscription(&self, token: &str);
+
+	/// The block is pinned in the backend only once when the block's hash is first encountered.
+	///
+	/// Each subscription is expected to call this method twice:
+	/// - once from the `NewBlock` import
+	/// - once from the `Finalized` import
+	///
+	/// Returns
+	/// - Ok(true) if the subscription did not previously contain this block
+	/// - Ok(false) if the subscription already contained this this
+	/// - Error if the backend failed to pin the block or the subscription ID is invalid
+	fn pin_block(
+		&self,
+		sub_id: &str,
+		hash: Block::Hash,
+	) -> Result<bool, SubscriptionManagementError>;
+
+	/// Returns true if the block is pinned by the subscription.
+	fn contains_block(&self, sub_id: &str, hash: Block::Hash) -> bool;
+
+	/// Restrict the events of the subscription to the descendants of the block,
+	/// or remove the restriction if the block is not provided.
+	///
+	/// Returns an error if the block is not pinned for the subscription or
+	/// the subscription ID is invalid.
+	fn set_fork_filter(
+		&self,
+		sub_id: &str,
+		hash: Option<Block::Hash>,
+	) -> Result<(), SubscriptionManagementError>;
+
+	/// Get the block whose descendants are reported by the subscription, if any.
+	fn fork_filter(&self, sub_id: &str) -> Option<Block::Hash>;
+
+	/// Get the blocks pinned by the subscription and the duration since they were pinned,
+	/// ordered from the oldest pin.
+	///
+	/// Returns `None` if the subscription is not present.
+	fn pinned_blocks(&self, sub_id: &str) -> Option<Vec<(Block::Hash, Duration)>>;
+
+	/// Unpin the block from the subscription.
+	///
+	/// The last subscription that unpins the block is also unpinning the block
+	/// from the backend.
+	///
+	/// This method is called only once per subscription.
+	///
+	/// Returns an error if the block is not pinned for the subscription or
+	/// the subscription ID is invalid.
+	fn unpin_block(
+		&self,
+		sub_id: &str,
+		hash: Block::Hash,
+	) -> Result<(), SubscriptionManagementError>;
+
+	/// Unpin the blocks from the subscription.
+	///
+	/// The blocks are unpinned atomically: no block is unpinned if any of the blocks
+	/// is not pinned for the subscription.
+	fn unpin_blocks(
+		&self,
+		sub_id: &str,
+		hashes: &[Block::Hash],
+	) -> Result<(), SubscriptionManagementError>;
+
+	/// Ensure the block remains pinned until the return object is dropped.
+	///
+	/// Returns a [`BlockGuard`] that pins and unpins the block hash in RAII manner
+	/// and reserves capacity for ogoing operations.
+	///
+	/// Returns an error if the block hash is not pinned for the subscription,
+	/// the subscription ID is invalid or the limit of ongoing operations was exceeded.
+	///
+	/// # Note
+	///
+	/// The blocks reported by the `chainHead_follow` are pinned in the backend until
+	/// the user unpins them. Therefore, the block can be locked even after it was
+	/// retracted from the best chain or reported as pruned.
+	fn lock_block(
+		&self,
+		sub_id: &str,
+		hash: Block::Hash,
+		to_reserve: usize,
+	) -> Result<BlockGuard<Block, BE>, SubscriptionManagementError>;
+
+	/// Get the operation state.
+	fn get_operation(&self, sub_id: &str, operation_id: &str) -> Option<OperationState>;
+
+	/// Account the bytes served to the subscription.
+	///
+	/// Once the bandwidth quota of the subscription is exhausted, [`Self::lock_block`]
+	/// returns [`SubscriptionManagementError::ExceededLimits`] until the next period.
+	fn record_bandwidth(&self, sub_id: &str, bytes: u64);
+
+	/// Returns true if the subscription has exhausted its bandwidth quota for the current
+	/// period.
+	fn is_throttled(&self, sub_id: &str) -> bool;
+
+	/// Get the number of bytes served to the subscription since it started.
+	fn bytes_served(&self, sub_id: &str) -> Option<u64>;
+
+	/// Access the store as the [`FollowSubscriptionPins`] of the other RPC methods.
+	fn into_follow_subscription_pins(
+		self: Arc<Self>,
+	) -> Arc<dyn FollowSubscriptionPins<Block::Hash>>;
+}
 
 /// Manage block pinning / unpinning for subscription IDs.
 pub struct SubscriptionManagement<Block: BlockT, BE: Backend<Block>> {
@@ -41,26 +234,60 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionManagement<Block, BE> {
 	/// Construct a new [`SubscriptionManagement`].
 	pub fn new(
 		global_max_pinned_blocks: usize,
-		local_max_pin_duration: Duration,
+		local_max_pinned_blocks: usize,
+		eviction: Arc<dyn PinEvictionPolicy>,
 		max_ongoing_operations: usize,
+		bandwidth_quota: Option<BandwidthQuota>,
 		backend: Arc<BE>,
+		metrics: MetricsLink,
 	) -> Self {
 		SubscriptionManagement {
 			inner: RwLock::new(SubscriptionsInner::new(
 				global_max_pinned_blocks,
-				local_max_pin_duration,
+				local_max_pinned_blocks,
+				eviction,
 				max_ongoing_operations,
+				bandwidth_quota,
 				backend,
+				metrics,
 			)),
 		}
 	}
 
-	/// Insert a new subscription ID.
+	/// Pin the block on behalf of the subscription before the block is
+	/// reported by the `chainHead_follow` events.
 	///
-	/// If the subscription was not previously inserted, returns the receiver that is
-	/// triggered upon the "Stop" event. Otherwise, if the subscription ID was already
-	/// inserted returns none.
-	pub fn insert_subscription(
+	/// The next [`PinStore::pin_block`] of the block returns `Ok(true)` without pinning
+	/// the block again.
+	///
+	/// Returns an error if the block was unpinned by the subscription, the backend
+	/// failed to pin the block or the subscription ID is invalid.
+	pub fn pin_block_external(
+		&self,
+		sub_id: &str,
+		hash: Block::Hash,
+	) -> Result<(), SubscriptionManagementError> {
+		let mut inner = self.inner.write();
+		inner.pin_block_external(sub_id, hash)
+	}
+
+	/// Stop the subscriptions that track at least one block matching the predicate.
+	///
+	/// Returns the number of stopped subscriptions.
+	pub fn stop_subscriptions_with(&self, should_stop: impl Fn(&Block::Hash) -> bool) -> usize {
+		let mut inner = self.inner.write();
+		inner.stop_subscriptions_with(should_stop)
+	}
+
+	/// List the subscriptions, ordered by subscription ID.
+	pub fn subscriptions_info(&self) -> Vec<FollowSubscriptionInfo<Block::Hash>> {
+		let inner = self.inner.read();
+		inner.subscriptions_info()
+	}
+}
+
+impl<Block: BlockT, BE: Backend<Block>> PinStore<Block, BE> for SubscriptionManagement<Block, BE> {
+	fn insert_subscription(
 		&self,
 		sub_id: String,
 		runtime_updates: bool,
@@ -69,23 +296,32 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionManagement<Block, BE> {
 		inner.insert_subscription(sub_id, runtime_updates)
 	}
 
-	/// Remove the subscription ID with associated pinned blocks.
-	pub fn remove_subscription(&self, sub_id: &str) {
+	fn remove_subscription(&self, sub_id: &str) {
 		let mut inner = self.inner.write();
 		inner.remove_subscription(sub_id)
 	}
 
-	/// The block is pinned in the backend only once when the block's hash is first encountered.
-	///
-	/// Each subscription is expected to call this method twice:
-	/// - once from the `NewBlock` import
-	/// - once from the `Finalized` import
-	///
-	/// Returns
-	/// - Ok(true) if the subscription did not previously contain this block
-	/// - Ok(false) if the subscription already contained this this
-	/// - Error if the backend failed to pin the block or the subscription ID is invalid
-	pub fn pin_block(
+	fn park_subscription(&self, sub_id: &str, token: String) -> bool {
+		let mut inner = self.inner.write();
+		inner.park_subscription(sub_id, token)
+	}
+
+	fn resume_subscription(
+		&self,
+		token: &str,
+		sub_id: String,
+		runtime_updates: bool,
+	) -> Option<InsertedSubscriptionData<Block>> {
+		let mut inner = self.inner.write();
+		inner.resume_subscription(token, sub_id, runtime_updates)
+	}
+
+	fn expire_parked_subscription(&self, token: &str) {
+		let mut inner = self.inner.write();
+		inner.expire_parked_subscription(token)
+	}
+
+	fn pin_block(
 		&self,
 		sub_id: &str,
 		hash: Block::Hash,
@@ -94,16 +330,31 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionManagement<Block, BE> {
 		inner.pin_block(sub_id, hash)
 	}
 
-	/// Unpin the block from the subscription.
-	///
-	/// The last subscription that unpins the block is also unpinning the block
-	/// from the backend.
-	///
-	/// This method is called only once per subscription.
-	///
-	/// Returns an error if the block is not pinned for the subscription or
-	/// the subscription ID is invalid.
-	pub fn unpin_block(
+	fn contains_block(&self, sub_id: &str, hash: Block::Hash) -> bool {
+		let inner = self.inner.read();
+		inner.contains_block(sub_id, hash)
+	}
+
+	fn set_fork_filter(
+		&self,
+		sub_id: &str,
+		hash: Option<Block::Hash>,
+	) -> Result<(), SubscriptionManagementError> {
+		let mut inner = self.inner.write();
+		inner.set_fork_filter(sub_id, hash)
+	}
+
+	fn fork_filter(&self, sub_id: &str) -> Option<Block::Hash> {
+		let inner = self.inner.read();
+		inner.fork_filter(sub_id)
+	}
+
+	fn pinned_blocks(&self, sub_id: &str) -> Option<Vec<(Block::Hash, Duration)>> {
+		let inner = self.inner.read();
+		inner.pinned_blocks(sub_id)
+	}
+
+	fn unpin_block(
 		&self,
 		sub_id: &str,
 		hash: Block::Hash,
@@ -112,14 +363,16 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionManagement<Block, BE> {
 		inner.unpin_block(sub_id, hash)
 	}
 
-	/// Ensure the block remains pinned until the return object is dropped.
-	///
-	/// Returns a [`BlockGuard`] that pins and unpins the block hash in RAII manner
-	/// and reserves capacity for ogoing operations.
-	///
-	/// Returns an error if the block hash is not pinned for the subscription,
-	/// the subscription ID is invalid or the limit of ongoing operations was exceeded.
-	pub fn lock_block(
+	fn unpin_blocks(
+		&self,
+		sub_id: &str,
+		hashes: &[Block::Hash],
+	) -> Result<(), SubscriptionManagementError> {
+		let mut inner = self.inner.write();
+		inner.unpin_blocks(sub_id, hashes.iter().copied())
+	}
+
+	fn lock_block(
 		&self,
 		sub_id: &str,
 		hash: Block::Hash,
@@ -129,9 +382,49 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionManagement<Block, BE> {
 		inner.lock_block(sub_id, hash, to_reserve)
 	}
 
-	/// Get the operation state.
-	pub fn get_operation(&self, sub_id: &str, operation_id: &str) -> Option<OperationState> {
+	fn get_operation(&self, sub_id: &str, operation_id: &str) -> Option<OperationState> {
 		let mut inner = self.inner.write();
 		inner.get_operation(sub_id, operation_id)
 	}
+
+	fn record_bandwidth(&self, sub_id: &str, bytes: u64) {
+		let mut inner = self.inner.write();
+		inner.record_bandwidth(sub_id, bytes)
+	}
+
+	fn is_throttled(&self, sub_id: &str) -> bool {
+		let mut inner = self.inner.write();
+		inner.is_throttled(sub_id)
+	}
+
+	fn bytes_served(&self, sub_id: &str) -> Option<u64> {
+		let inner = self.inner.read();
+		inner.bytes_served(sub_id)
+	}
+
+	fn into_follow_subscription_pins(
+		self: Arc<Self>,
+	) -> Arc<dyn FollowSubscriptionPins<Block::Hash>> {
+		self
+	}
+}
+
+impl<Block: BlockT, BE: Backend<Block>> FollowSubscriptionPins<Block::Hash>
+	for SubscriptionManagement<Block, BE>
+{
+	fn is_block_pinned(&self, sub_id: &str, hash: Block::Hash) -> bool {
+		self.contains_block(sub_id, hash)
+	}
+
+	fn pin_block_external(&self, sub_id: &str, hash: Block::Hash) -> bool {
+		SubscriptionManagement::pin_block_external(self, sub_id, hash).is_ok()
+	}
+
+	fn stop_subscriptions_with(&self, should_stop: &dyn Fn(&Block::Hash) -> bool) -> usize {
+		SubscriptionManagement::stop_subscriptions_with(self, should_stop)
+	}
+
+	fn subscriptions_info(&self) -> Vec<FollowSubscriptionInfo<Block::Hash>> {
+		SubscriptionManagement::subscriptions_info(self)
+	}
 }
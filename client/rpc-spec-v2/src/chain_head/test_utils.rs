@@ -16,24 +16,29 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use super::NotificationSource;
+use futures::stream::{BoxStream, StreamExt};
 use parking_lot::Mutex;
 use sc_client_api::{
-	execution_extensions::ExecutionExtensions, BlockBackend, BlockImportNotification,
-	BlockchainEvents, CallExecutor, ChildInfo, ExecutorProvider, FinalityNotification,
-	FinalityNotifications, FinalizeSummary, ImportNotifications, KeysIter, PairsIter, StorageData,
-	StorageEventStream, StorageKey, StorageProvider,
+	execution_extensions::ExecutionExtensions, BlockBackend, BlockImportNotification, CallExecutor,
+	ChildInfo, ExecutorProvider, FinalityNotification, FinalizeSummary, KeysIter, PairsIter,
+	StorageData, StorageKey, StorageProvider,
 };
 use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedSender};
 use sp_api::{CallApiAt, CallApiAtParams, NumberFor, RuntimeVersion};
 use sp_blockchain::{BlockStatus, CachedHeaderMetadata, HeaderBackend, HeaderMetadata, Info};
-use sp_consensus::BlockOrigin;
+use sp_consensus::{BlockOrigin, SyncOracle};
 use sp_runtime::{
 	generic::SignedBlock,
 	traits::{Block as BlockT, Header as HeaderT},
 	Justifications,
 };
-use std::sync::Arc;
-use substrate_test_runtime::{Block, Hash, Header};
+use sp_trie::MerkleValue;
+use std::sync::{
+	atomic::{AtomicBool, Ordering},
+	Arc,
+};
+use substrate_test_runtime::{Block, Header};
 
 pub struct ChainHeadMockClient<Client> {
 	client: Arc<Client>,
@@ -51,7 +56,7 @@ impl<Client> ChainHeadMockClient<Client> {
 	}
 
 	pub async fn trigger_import_stream(&self, header: Header) {
-		// Ensure the client called the `import_notification_stream`.
+		// Ensure the client called the `import_notifications`.
 		while self.import_sinks.lock().is_empty() {
 			tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
 		}
@@ -67,18 +72,23 @@ impl<Client> ChainHeadMockClient<Client> {
 	}
 
 	pub async fn trigger_finality_stream(&self, header: Header) {
-		// Ensure the client called the `finality_notification_stream`.
+		self.trigger_finality_stream_with_stale_heads(header, vec![]).await
+	}
+
+	pub async fn trigger_finality_stream_with_stale_heads(
+		&self,
+		header: Header,
+		stale_heads: Vec<<Block as BlockT>::Hash>,
+	) {
+		// Ensure the client called the `finality_notifications`.
 		while self.finality_sinks.lock().is_empty() {
 			tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
 		}
 
 		// Build the notification.
 		let (sink, _stream) = tracing_unbounded("test_sink", 100_000);
-		let summary = FinalizeSummary {
-			header: header.clone(),
-			finalized: vec![header.hash()],
-			stale_heads: vec![],
-		};
+		let summary =
+			FinalizeSummary { header: header.clone(), finalized: vec![header.hash()], stale_heads };
 		let notification = FinalityNotification::from_summary(summary, sink);
 
 		for sink in self.finality_sinks.lock().iter_mut() {
@@ -87,31 +97,19 @@ impl<Client> ChainHeadMockClient<Client> {
 	}
 }
 
-// ChainHead calls `import_notification_stream` and `finality_notification_stream` in order to
+// ChainHead calls `import_notifications` and `finality_notifications` in order to
 // subscribe to block events.
-impl<Client> BlockchainEvents<Block> for ChainHeadMockClient<Client> {
-	fn import_notification_stream(&self) -> ImportNotifications<Block> {
+impl<Client> NotificationSource<Block> for ChainHeadMockClient<Client> {
+	fn import_notifications(&self) -> BoxStream<'static, BlockImportNotification<Block>> {
 		let (sink, stream) = tracing_unbounded("import_notification_stream", 1024);
 		self.import_sinks.lock().push(sink);
-		stream
-	}
-
-	fn every_import_notification_stream(&self) -> ImportNotifications<Block> {
-		unimplemented!()
+		stream.boxed()
 	}
 
-	fn finality_notification_stream(&self) -> FinalityNotifications<Block> {
+	fn finality_notifications(&self) -> BoxStream<'static, FinalityNotification<Block>> {
 		let (sink, stream) = tracing_unbounded("finality_notification_stream", 1024);
 		self.finality_sinks.lock().push(sink);
-		stream
-	}
-
-	fn storage_changes_notification_stream(
-		&self,
-		_filter_keys: Option<&[StorageKey]>,
-		_child_filter_keys: Option<&[(StorageKey, Option<Vec<StorageKey>>)]>,
-	) -> sp_blockchain::Result<StorageEventStream<Hash>> {
-		unimplemented!()
+		stream.boxed()
 	}
 }
 
@@ -198,6 +196,23 @@ impl<
 	) -> sp_blockchain::Result<Option<Block::Hash>> {
 		self.client.child_storage_hash(hash, child_info, key)
 	}
+
+	fn closest_merkle_value(
+		&self,
+		hash: Block::Hash,
+		key: &StorageKey,
+	) -> sp_blockchain::Result<Option<MerkleValue<Block::Hash>>> {
+		self.client.closest_merkle_value(hash, key)
+	}
+
+	fn child_closest_merkle_value(
+		&self,
+		hash: Block::Hash,
+		child_info: &ChildInfo,
+		key: &StorageKey,
+	) -> sp_blockchain::Result<Option<MerkleValue<Block::Hash>>> {
+		self.client.child_closest_merkle_value(hash, child_info, key)
+	}
 }
 
 impl<Block: BlockT, Client: CallApiAt<Block>> CallApiAt<Block> for ChainHeadMockClient<Client> {
@@ -323,3 +338,25 @@ impl<Block: BlockT, Client: HeaderBackend<Block> + Send + Sync> HeaderBackend<Bl
 		self.client.hash(number)
 	}
 }
+
+/// A sync oracle whose major sync is set by the tests.
+#[derive(Default)]
+pub struct MockSyncOracle {
+	is_major_syncing: AtomicBool,
+}
+
+impl MockSyncOracle {
+	pub fn set_major_syncing(&self, is_major_syncing: bool) {
+		self.is_major_syncing.store(is_major_syncing, Ordering::SeqCst);
+	}
+}
+
+impl SyncOracle for MockSyncOracle {
+	fn is_major_syncing(&self) -> bool {
+		self.is_major_syncing.load(Ordering::SeqCst)
+	}
+
+	fn is_offline(&self) -> bool {
+		false
+	}
+}
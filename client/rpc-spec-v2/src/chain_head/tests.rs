@@ -1,12 +1,12 @@
 use crate::chain_head::{
-	event::{MethodResponse, StorageQuery, StorageQueryType, StorageResultType},
-	test_utils::ChainHeadMockClient,
+	event::{MethodResponse, StorageQuery, StorageQueryType, StorageResult, StorageResultType},
+	test_utils::{ChainHeadMockClient, MockSyncOracle},
 };
 
 use super::*;
 use assert_matches::assert_matches;
 use codec::{Decode, Encode};
-use futures::Future;
+use futures::{Future, StreamExt};
 use jsonrpsee::{
 	core::{error::Error, server::rpc_module::Subscription as RpcSubscription},
 	rpc_params,
@@ -34,6 +34,7 @@ use substrate_test_runtime_client::{
 
 type Header = substrate_test_runtime_client::runtime::Header;
 type Block = substrate_test_runtime_client::runtime::Block;
+type BlockHash = <Block as BlockT>::Hash;
 const MAX_PINNED_BLOCKS: usize = 32;
 const MAX_PINNED_SECS: u64 = 60;
 const MAX_OPERATIONS: usize = 16;
@@ -94,6 +95,7 @@ async fn setup_api() -> (
 			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
 			subscription_max_ongoing_operations: MAX_OPERATIONS,
 			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			..Default::default()
 		},
 	)
 	.into_rpc();
@@ -138,6 +140,7 @@ async fn follow_subscription_produces_blocks() {
 			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
 			subscription_max_ongoing_operations: MAX_OPERATIONS,
 			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			..Default::default()
 		},
 	)
 	.into_rpc();
@@ -148,9 +151,10 @@ async fn follow_subscription_produces_blocks() {
 	// Initialized must always be reported first.
 	let event: FollowEvent<String> = get_next_event(&mut sub).await;
 	let expected = FollowEvent::Initialized(Initialized {
-		finalized_block_hash: format!("{:?}", finalized_hash),
+		finalized_block_hashes: vec![format!("{:?}", finalized_hash)],
 		finalized_block_runtime: None,
 		with_runtime: false,
+		resumption_token: None,
 	});
 	assert_eq!(event, expected);
 
@@ -165,6 +169,8 @@ async fn follow_subscription_produces_blocks() {
 		parent_block_hash: format!("{:?}", finalized_hash),
 		new_runtime: None,
 		with_runtime: false,
+		header: None,
+		extrinsics_count: None,
 	});
 	assert_eq!(event, expected);
 
@@ -185,7 +191,7 @@ async fn follow_subscription_produces_blocks() {
 }
 
 #[tokio::test]
-async fn follow_with_runtime() {
+async fn follow_reports_finalized_history() {
 	let builder = TestClientBuilder::new();
 	let backend = builder.backend();
 	let mut client = Arc::new(builder.build());
@@ -200,107 +206,120 @@ async fn follow_with_runtime() {
 			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
 			subscription_max_ongoing_operations: MAX_OPERATIONS,
 			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			subscription_max_finalized_history: 3,
+			..Default::default()
 		},
 	)
 	.into_rpc();
 
-	let finalized_hash = client.info().finalized_hash;
-	let mut sub = api.subscribe("chainHead_unstable_follow", [true]).await.unwrap();
-
-	// Initialized must always be reported first.
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-
-	// it is basically json-encoded substrate_test_runtime_client::runtime::VERSION
-	let runtime_str = "{\"specName\":\"test\",\"implName\":\"parity-test\",\"authoringVersion\":1,\
-		\"specVersion\":2,\"implVersion\":2,\"apis\":[[\"0xdf6acb689907609b\",4],\
-		[\"0x37e397fc7c91f5e4\",2],[\"0xd2bc9897eed08f15\",3],[\"0x40fe3ad401f8959a\",6],\
-		[\"0xbc9d89904f5b923f\",1],[\"0xc6e9a76309f39b09\",2],[\"0xdd718d5cc53262d4\",1],\
-		[\"0xcbca25e39f142387\",2],[\"0xf78b278be53f454c\",2],[\"0xab3c0572291feb8b\",1],\
-		[\"0xed99c5acb25eedf5\",3],[\"0xfbc577b9d747efd6\",1]],\"transactionVersion\":1,\"stateVersion\":1}";
+	// Import and finalize 3 blocks.
+	let mut hashes = vec![client.genesis_hash()];
+	for _ in 0..3 {
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		hashes.push(block.header.hash());
+		client.import(BlockOrigin::Own, block).await.unwrap();
+	}
+	client.finalize_block(hashes[3], None).unwrap();
 
-	let runtime: RuntimeVersion = serde_json::from_str(runtime_str).unwrap();
+	// Report the last 2 finalized ancestors.
+	let mut sub = api.subscribe("chainHead_unstable_follow", rpc_params![false, 2]).await.unwrap();
+	let sub_id = sub.subscription_id();
+	let sub_id = serde_json::to_string(&sub_id).unwrap();
 
-	let finalized_block_runtime =
-		Some(RuntimeEvent::Valid(RuntimeVersionEvent { spec: runtime.clone() }));
-	// Runtime must always be reported with the first event.
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
 	let expected = FollowEvent::Initialized(Initialized {
-		finalized_block_hash: format!("{:?}", finalized_hash),
-		finalized_block_runtime,
+		finalized_block_hashes: hashes[1..].iter().map(|hash| format!("{:?}", hash)).collect(),
+		finalized_block_runtime: None,
 		with_runtime: false,
+		resumption_token: None,
 	});
-	pretty_assertions::assert_eq!(event, expected);
+	assert_eq!(event, expected);
 
-	// Import a new block without runtime changes.
-	// The runtime field must be None in this case.
-	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
-	let best_hash = block.header.hash();
-	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+	// The reported blocks are pinned.
+	let block_hash = format!("{:?}", hashes[1]);
+	let res: Option<String> =
+		api.call("chainHead_unstable_header", [&sub_id, &block_hash]).await.unwrap();
+	assert!(res.is_some());
 
+	// The history is limited by the configuration.
+	let mut sub = api
+		.subscribe("chainHead_unstable_follow", rpc_params![false, 10])
+		.await
+		.unwrap();
 	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::NewBlock(NewBlock {
-		block_hash: format!("{:?}", best_hash),
-		parent_block_hash: format!("{:?}", finalized_hash),
-		new_runtime: None,
+	let expected = FollowEvent::Initialized(Initialized {
+		finalized_block_hashes: hashes.iter().map(|hash| format!("{:?}", hash)).collect(),
+		finalized_block_runtime: None,
 		with_runtime: false,
+		resumption_token: None,
 	});
 	assert_eq!(event, expected);
+}
 
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
-		best_block_hash: format!("{:?}", best_hash),
-	});
-	assert_eq!(event, expected);
+#[tokio::test]
+async fn follow_with_header() {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let mut client = Arc::new(builder.build());
 
-	client.finalize_block(best_hash, None).unwrap();
+	let api = ChainHead::new(
+		client.clone(),
+		backend,
+		Arc::new(TaskExecutor::default()),
+		CHAIN_GENESIS,
+		ChainHeadConfig {
+			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
+			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
+			subscription_max_ongoing_operations: MAX_OPERATIONS,
+			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			..Default::default()
+		},
+	)
+	.into_rpc();
 
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::Finalized(Finalized {
-		finalized_block_hashes: vec![format!("{:?}", best_hash)],
-		pruned_block_hashes: vec![],
-	});
-	assert_eq!(event, expected);
+	let finalized_hash = client.info().finalized_hash;
+	let mut sub = api
+		.subscribe("chainHead_unstable_follow", rpc_params![false, None::<u32>, true])
+		.await
+		.unwrap();
 
-	let finalized_hash = best_hash;
-	// The `RuntimeVersion` is embedded into the WASM blob at the `runtime_version`
-	// section. Modify the `RuntimeVersion` and commit the changes to a new block.
-	// The RPC must notify the runtime event change.
-	let wasm = sp_maybe_compressed_blob::decompress(
-		runtime::wasm_binary_unwrap(),
-		sp_maybe_compressed_blob::CODE_BLOB_BOMB_LIMIT,
-	)
-	.unwrap();
-	// Update the runtime spec version.
-	let mut runtime = runtime;
-	runtime.spec_version += 1;
-	let embedded = sp_version::embed::embed_runtime_version(&wasm, runtime.clone()).unwrap();
-	let wasm = sp_maybe_compressed_blob::compress(
-		&embedded,
-		sp_maybe_compressed_blob::CODE_BLOB_BOMB_LIMIT,
-	)
-	.unwrap();
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::Initialized(_)
+	);
 
+	// Import a block with extrinsics.
 	let mut builder = client.new_block(Default::default()).unwrap();
-	builder.push_storage_change(CODE.to_vec(), Some(wasm)).unwrap();
+	builder
+		.push_transfer(runtime::Transfer {
+			from: AccountKeyring::Alice.into(),
+			to: AccountKeyring::Ferdie.into(),
+			amount: 42,
+			nonce: 0,
+		})
+		.unwrap();
 	let block = builder.build().unwrap().block;
-	let best_hash = block.header.hash();
+	let block_hash = block.header.hash();
 	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
 
-	let new_runtime = Some(RuntimeEvent::Valid(RuntimeVersionEvent { spec: runtime.clone() }));
+	// The header and the number of extrinsics are reported with the block.
 	let event: FollowEvent<String> = get_next_event(&mut sub).await;
 	let expected = FollowEvent::NewBlock(NewBlock {
-		block_hash: format!("{:?}", best_hash),
+		block_hash: format!("{:?}", block_hash),
 		parent_block_hash: format!("{:?}", finalized_hash),
-		new_runtime,
+		new_runtime: None,
 		with_runtime: false,
+		header: Some(hex_string(&block.header.encode())),
+		extrinsics_count: Some(1),
 	});
 	assert_eq!(event, expected);
 }
 
 #[tokio::test]
-async fn get_genesis() {
+async fn follow_finalized_only() {
 	let builder = TestClientBuilder::new();
 	let backend = builder.backend();
-	let client = Arc::new(builder.build());
+	let mut client = Arc::new(builder.build());
 
 	let api = ChainHead::new(
 		client.clone(),
@@ -312,38 +331,56 @@ async fn get_genesis() {
 			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
 			subscription_max_ongoing_operations: MAX_OPERATIONS,
 			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			..Default::default()
 		},
 	)
 	.into_rpc();
 
-	let genesis: String =
-		api.call("chainHead_unstable_genesisHash", EmptyParams::new()).await.unwrap();
-	assert_eq!(genesis, hex_string(&CHAIN_GENESIS));
-}
-
-#[tokio::test]
-async fn get_header() {
-	let (_client, api, _sub, sub_id, block) = setup_api().await;
-	let block_hash = format!("{:?}", block.header.hash());
-	let invalid_hash = hex_string(&INVALID_HASH);
-
-	// Invalid subscription ID must produce no results.
-	let res: Option<String> = api
-		.call("chainHead_unstable_header", ["invalid_sub_id", &invalid_hash])
+	let mut sub = api
+		.subscribe(
+			"chainHead_unstable_follow",
+			rpc_params![false, None::<u32>, None::<bool>, None::<String>, None::<String>, true],
+		)
 		.await
 		.unwrap();
-	assert!(res.is_none());
+	let sub_id = sub.subscription_id();
+	let sub_id = serde_json::to_string(&sub_id).unwrap();
 
-	// Valid subscription with invalid block hash will error.
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::Initialized(_)
+	);
+
+	// The imported block is neither pinned nor reported.
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_hash = block.header.hash();
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+	does_not_produce_event::<FollowEvent<String>>(
+		&mut sub,
+		std::time::Duration::from_secs(DOES_NOT_PRODUCE_EVENTS_SECONDS),
+	)
+	.await;
 	let err = api
-		.call::<_, serde_json::Value>("chainHead_unstable_header", [&sub_id, &invalid_hash])
+		.call::<_, Option<String>>(
+			"chainHead_unstable_header",
+			[&sub_id, &format!("{:?}", block_hash)],
+		)
 		.await
 		.unwrap_err();
 	assert_matches!(err,
 		Error::Call(CallError::Custom(ref err)) if err.code() == 2001 && err.message() == "Invalid block hash"
 	);
 
-	// Obtain the valid header.
+	// The block is reported once finalized.
+	client.finalize_block(block_hash, None).unwrap();
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::Finalized(Finalized {
+		finalized_block_hashes: vec![format!("{:?}", block_hash)],
+		pruned_block_hashes: vec![],
+	});
+	assert_eq!(event, expected);
+
+	let block_hash = format!("{:?}", block_hash);
 	let res: String = api.call("chainHead_unstable_header", [&sub_id, &block_hash]).await.unwrap();
 	let bytes = array_bytes::hex2bytes(&res).unwrap();
 	let header: Header = Decode::decode(&mut &bytes[..]).unwrap();
@@ -351,163 +388,204 @@ async fn get_header() {
 }
 
 #[tokio::test]
-async fn get_body() {
-	let (mut client, api, mut block_sub, sub_id, block) = setup_api().await;
-	let block_hash = format!("{:?}", block.header.hash());
-	let invalid_hash = hex_string(&INVALID_HASH);
+async fn follow_finalized_only_rejects_runtime_updates() {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let client = Arc::new(builder.build());
 
-	// Subscription ID is invalid.
-	let response: MethodResponse = api
-		.call("chainHead_unstable_body", ["invalid_sub_id", &invalid_hash])
-		.await
-		.unwrap();
-	assert_matches!(response, MethodResponse::LimitReached);
+	let api = ChainHead::new(
+		client.clone(),
+		backend,
+		Arc::new(TaskExecutor::default()),
+		CHAIN_GENESIS,
+		ChainHeadConfig {
+			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
+			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
+			subscription_max_ongoing_operations: MAX_OPERATIONS,
+			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			..Default::default()
+		},
+	)
+	.into_rpc();
 
-	// Block hash is invalid.
+	// The runtime updates are reported by the `newBlock` events only.
 	let err = api
-		.call::<_, serde_json::Value>("chainHead_unstable_body", [&sub_id, &invalid_hash])
+		.subscribe(
+			"chainHead_unstable_follow",
+			rpc_params![true, None::<u32>, None::<bool>, None::<String>, None::<String>, true],
+		)
 		.await
 		.unwrap_err();
 	assert_matches!(err,
-		Error::Call(CallError::Custom(ref err)) if err.code() == 2001 && err.message() == "Invalid block hash"
+		Error::Call(CallError::Custom(ref err)) if err.code() == 2003
 	);
+}
 
-	// Valid call.
-	let response: MethodResponse =
-		api.call("chainHead_unstable_body", [&sub_id, &block_hash]).await.unwrap();
-	let operation_id = match response {
-		MethodResponse::Started(started) => started.operation_id,
-		MethodResponse::LimitReached => panic!("Expected started response"),
-	};
+#[tokio::test]
+async fn follow_catch_up_from_finalized_block() {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let mut client = Arc::new(builder.build());
 
-	// Response propagated to `chainHead_follow`.
-	assert_matches!(
-			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-			FollowEvent::OperationBodyDone(done) if done.operation_id == operation_id && done.value.is_empty()
-	);
+	let api = ChainHead::new(
+		client.clone(),
+		backend,
+		Arc::new(TaskExecutor::default()),
+		CHAIN_GENESIS,
+		ChainHeadConfig {
+			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
+			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
+			subscription_max_ongoing_operations: MAX_OPERATIONS,
+			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			..Default::default()
+		},
+	)
+	.into_rpc();
 
-	// Import a block with extrinsics.
-	let mut builder = client.new_block(Default::default()).unwrap();
-	builder
-		.push_transfer(runtime::Transfer {
-			from: AccountKeyring::Alice.into(),
-			to: AccountKeyring::Ferdie.into(),
-			amount: 42,
-			nonce: 0,
-		})
+	// Import and finalize 3 blocks.
+	let mut hashes = vec![client.genesis_hash()];
+	for _ in 0..3 {
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		hashes.push(block.header.hash());
+		client.import(BlockOrigin::Own, block).await.unwrap();
+	}
+	client.finalize_block(hashes[3], None).unwrap();
+
+	// Catch up from the first imported block.
+	let catch_up_from = format!("{:?}", hashes[1]);
+	let mut sub = api
+		.subscribe(
+			"chainHead_unstable_follow",
+			rpc_params![false, None::<u32>, None::<bool>, &catch_up_from],
+		)
+		.await
 		.unwrap();
-	let block = builder.build().unwrap().block;
-	let block_hash = format!("{:?}", block.header.hash());
-	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
-	// Ensure the imported block is propagated and pinned for this subscription.
-	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-		FollowEvent::NewBlock(_)
-	);
-	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-		FollowEvent::BestBlockChanged(_)
-	);
 
-	// Valid call to a block with extrinsics.
-	let response: MethodResponse =
-		api.call("chainHead_unstable_body", [&sub_id, &block_hash]).await.unwrap();
-	let operation_id = match response {
-		MethodResponse::Started(started) => started.operation_id,
-		MethodResponse::LimitReached => panic!("Expected started response"),
-	};
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::Initialized(Initialized {
+		finalized_block_hashes: vec![catch_up_from],
+		finalized_block_runtime: None,
+		with_runtime: false,
+		resumption_token: None,
+	});
+	assert_eq!(event, expected);
 
-	// Response propagated to `chainHead_follow`.
-	let expected_tx = hex_string(&block.extrinsics[0].encode());
-	assert_matches!(
-			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-			FollowEvent::OperationBodyDone(done) if done.operation_id == operation_id && done.value == vec![expected_tx]
-	);
-}
+	// The blocks finalized since are reported as new blocks.
+	for index in 2..4 {
+		let event: FollowEvent<String> = get_next_event(&mut sub).await;
+		let expected = FollowEvent::NewBlock(NewBlock {
+			block_hash: format!("{:?}", hashes[index]),
+			parent_block_hash: format!("{:?}", hashes[index - 1]),
+			new_runtime: None,
+			with_runtime: false,
+			header: None,
+			extrinsics_count: None,
+		});
+		assert_eq!(event, expected);
+	}
 
-#[tokio::test]
-async fn call_runtime() {
-	let (_client, api, mut block_sub, sub_id, block) = setup_api().await;
-	let block_hash = format!("{:?}", block.header.hash());
-	let invalid_hash = hex_string(&INVALID_HASH);
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
+		best_block_hash: format!("{:?}", hashes[3]),
+	});
+	assert_eq!(event, expected);
 
-	// Subscription ID is invalid.
-	let response: MethodResponse = api
-		.call(
-			"chainHead_unstable_call",
-			["invalid_sub_id", &block_hash, "BabeApi_current_epoch", "0x00"],
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::Finalized(Finalized {
+		finalized_block_hashes: hashes[2..].iter().map(|hash| format!("{:?}", hash)).collect(),
+		pruned_block_hashes: vec![],
+	});
+	assert_eq!(event, expected);
+
+	// The live events are reported afterwards.
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_hash = block.header.hash();
+	client.import(BlockOrigin::Own, block).await.unwrap();
+
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::NewBlock(NewBlock {
+		block_hash: format!("{:?}", block_hash),
+		parent_block_hash: format!("{:?}", hashes[3]),
+		new_runtime: None,
+		with_runtime: false,
+		header: None,
+		extrinsics_count: None,
+	});
+	assert_eq!(event, expected);
+
+	// The subscription cannot catch up from a block that is not finalized.
+	let catch_up_from = format!("{:?}", block_hash);
+	let mut sub = api
+		.subscribe(
+			"chainHead_unstable_follow",
+			rpc_params![false, None::<u32>, None::<bool>, &catch_up_from],
 		)
 		.await
 		.unwrap();
-	assert_matches!(response, MethodResponse::LimitReached);
+	assert_matches!(get_next_event::<FollowEvent<String>>(&mut sub).await, FollowEvent::Stop);
+}
 
-	// Block hash is invalid.
-	let err = api
-		.call::<_, serde_json::Value>(
-			"chainHead_unstable_call",
-			[&sub_id, &invalid_hash, "BabeApi_current_epoch", "0x00"],
-		)
-		.await
-		.unwrap_err();
-	assert_matches!(err,
-		Error::Call(CallError::Custom(ref err)) if err.code() == 2001 && err.message() == "Invalid block hash"
-	);
+#[tokio::test]
+async fn follow_catch_up_rejects_large_gaps() {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let mut client = Arc::new(builder.build());
 
-	// Pass an invalid parameters that cannot be decode.
+	let api = ChainHead::new(
+		client.clone(),
+		backend,
+		Arc::new(TaskExecutor::default()),
+		CHAIN_GENESIS,
+		ChainHeadConfig {
+			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
+			subscription_max_pinned_blocks: 2,
+			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
+			subscription_max_ongoing_operations: MAX_OPERATIONS,
+			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			..Default::default()
+		},
+	)
+	.into_rpc();
+
+	// Import and finalize 3 blocks.
+	let mut hashes = vec![client.genesis_hash()];
+	for _ in 0..3 {
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		hashes.push(block.header.hash());
+		client.import(BlockOrigin::Own, block).await.unwrap();
+	}
+	client.finalize_block(hashes[3], None).unwrap();
+
+	// Catching up from the first imported block pins 3 blocks.
+	let catch_up_from = format!("{:?}", hashes[1]);
 	let err = api
-		.call::<_, serde_json::Value>(
-			"chainHead_unstable_call",
-			// 0x0 is invalid.
-			[&sub_id, &block_hash, "BabeApi_current_epoch", "0x0"],
+		.subscribe(
+			"chainHead_unstable_follow",
+			rpc_params![false, None::<u32>, None::<bool>, &catch_up_from],
 		)
 		.await
 		.unwrap_err();
 	assert_matches!(err,
-		Error::Call(CallError::Custom(ref err)) if err.code() == 2003 && err.message().contains("Invalid parameter")
+		Error::Call(CallError::Custom(ref err)) if err.code() == 2003 && err.message().contains("exceeding the limit of 2")
 	);
 
-	// Valid call.
-	let alice_id = AccountKeyring::Alice.to_account_id();
-	// Hex encoded scale encoded bytes representing the call parameters.
-	let call_parameters = hex_string(&alice_id.encode());
-	let response: MethodResponse = api
-		.call(
-			"chainHead_unstable_call",
-			[&sub_id, &block_hash, "AccountNonceApi_account_nonce", &call_parameters],
+	// Catching up from the second imported block pins 2 blocks.
+	let catch_up_from = format!("{:?}", hashes[2]);
+	let mut sub = api
+		.subscribe(
+			"chainHead_unstable_follow",
+			rpc_params![false, None::<u32>, None::<bool>, &catch_up_from],
 		)
 		.await
 		.unwrap();
-	let operation_id = match response {
-		MethodResponse::Started(started) => started.operation_id,
-		MethodResponse::LimitReached => panic!("Expected started response"),
-	};
-
-	// Response propagated to `chainHead_follow`.
-	assert_matches!(
-			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-			FollowEvent::OperationCallDone(done) if done.operation_id == operation_id && done.output == "0x0000000000000000"
-	);
-
-	// The `current_epoch` takes no parameters and not draining the input buffer
-	// will cause the execution to fail.
-	let response: MethodResponse = api
-		.call("chainHead_unstable_call", [&sub_id, &block_hash, "BabeApi_current_epoch", "0x00"])
-		.await
-		.unwrap();
-	let operation_id = match response {
-		MethodResponse::Started(started) => started.operation_id,
-		MethodResponse::LimitReached => panic!("Expected started response"),
-	};
-
-	// Error propagated to `chainHead_follow`.
 	assert_matches!(
-			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-			FollowEvent::OperationError(error) if error.operation_id == operation_id && error.error.contains("Execution failed")
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::Initialized(_)
 	);
 }
 
 #[tokio::test]
-async fn call_runtime_without_flag() {
+async fn follow_resume_with_token() {
 	let builder = TestClientBuilder::new();
 	let backend = builder.backend();
 	let mut client = Arc::new(builder.build());
@@ -522,23 +600,25 @@ async fn call_runtime_without_flag() {
 			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
 			subscription_max_ongoing_operations: MAX_OPERATIONS,
 			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			subscription_resume_grace_period: Some(Duration::from_secs(60)),
+			..Default::default()
 		},
 	)
 	.into_rpc();
+	let genesis_hash = format!("{:?}", client.genesis_hash());
 
 	let mut sub = api.subscribe("chainHead_unstable_follow", [false]).await.unwrap();
-	let sub_id = sub.subscription_id();
-	let sub_id = serde_json::to_string(&sub_id).unwrap();
+	let token = match get_next_event::<FollowEvent<String>>(&mut sub).await {
+		FollowEvent::Initialized(initialized) => initialized.resumption_token.unwrap(),
+		event => panic!("Expected initialized event, got {:?}", event),
+	};
 
+	// Import and finalize a block, such that the genesis is no longer reported by
+	// the initial events.
 	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
-	let block_hash = format!("{:?}", block.header.hash());
-	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
-
-	// Ensure the imported block is propagated and pinned for this subscription.
-	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut sub).await,
-		FollowEvent::Initialized(_)
-	);
+	let block_hash = block.header.hash();
+	client.import(BlockOrigin::Own, block).await.unwrap();
+	client.finalize_block(block_hash, None).unwrap();
 	assert_matches!(
 		get_next_event::<FollowEvent<String>>(&mut sub).await,
 		FollowEvent::NewBlock(_)
@@ -547,175 +627,343 @@ async fn call_runtime_without_flag() {
 		get_next_event::<FollowEvent<String>>(&mut sub).await,
 		FollowEvent::BestBlockChanged(_)
 	);
-
-	// Valid runtime call on a subscription started with `with_runtime` false.
-	let alice_id = AccountKeyring::Alice.to_account_id();
-	let call_parameters = hex_string(&alice_id.encode());
-	let err = api
-		.call::<_, serde_json::Value>(
-			"chainHead_unstable_call",
-			[&sub_id, &block_hash, "AccountNonceApi_account_nonce", &call_parameters],
-		)
-		.await
-		.unwrap_err();
-
-	assert_matches!(err,
-		Error::Call(CallError::Custom(ref err)) if err.code() == 2003 && err.message().contains("The runtime updates flag must be set")
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::Finalized(_)
 	);
-}
 
-#[tokio::test]
-async fn get_storage_hash() {
-	let (mut client, api, mut block_sub, sub_id, block) = setup_api().await;
-	let block_hash = format!("{:?}", block.header.hash());
-	let invalid_hash = hex_string(&INVALID_HASH);
-	let key = hex_string(&KEY);
+	// The client disconnects, which is noticed once the next event is submitted.
+	drop(sub);
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	client.import(BlockOrigin::Own, block).await.unwrap();
 
-	// Subscription ID is invalid.
-	let response: MethodResponse = api
-		.call(
-			"chainHead_unstable_storage",
-			rpc_params![
-				"invalid_sub_id",
-				&invalid_hash,
-				vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Hash }]
-			],
+	// An invalid token stops the subscription.
+	let mut sub = api
+		.subscribe(
+			"chainHead_unstable_follow",
+			rpc_params![false, None::<u32>, None::<bool>, None::<String>, "0x00"],
 		)
 		.await
 		.unwrap();
-	assert_matches!(response, MethodResponse::LimitReached);
+	assert_matches!(get_next_event::<FollowEvent<String>>(&mut sub).await, FollowEvent::Stop);
 
-	// Block hash is invalid.
-	let err = api
-		.call::<_, serde_json::Value>(
-			"chainHead_unstable_storage",
-			rpc_params![
-				&sub_id,
-				&invalid_hash,
-				vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Hash }]
-			],
-		)
-		.await
-		.unwrap_err();
-	assert_matches!(err,
-		Error::Call(CallError::Custom(ref err)) if err.code() == 2001 && err.message() == "Invalid block hash"
-	);
+	// Resume the subscription once it is parked.
+	let (mut sub, resumed_token) = loop {
+		let mut sub = api
+			.subscribe(
+				"chainHead_unstable_follow",
+				rpc_params![false, None::<u32>, None::<bool>, None::<String>, &token],
+			)
+			.await
+			.unwrap();
+		match get_next_event::<FollowEvent<String>>(&mut sub).await {
+			FollowEvent::Initialized(initialized) =>
+				break (sub, initialized.resumption_token.unwrap()),
+			FollowEvent::Stop => tokio::time::sleep(Duration::from_millis(10)).await,
+			event => panic!("Unexpected event {:?}", event),
+		}
+	};
+	assert_ne!(resumed_token, token);
+	let sub_id = serde_json::to_string(&sub.subscription_id()).unwrap();
 
-	// Valid call without storage at the key.
-	let response: MethodResponse = api
-		.call(
-			"chainHead_unstable_storage",
-			rpc_params![
-				&sub_id,
-				&block_hash,
-				vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Hash }]
-			],
-		)
+	// The genesis block is still pinned by the resumed subscription.
+	let header: Option<String> = api
+		.call("chainHead_unstable_header", rpc_params![&sub_id, &genesis_hash])
 		.await
 		.unwrap();
-	let operation_id = match response {
-		MethodResponse::Started(started) => started.operation_id,
-		MethodResponse::LimitReached => panic!("Expected started response"),
-	};
-	// The `Done` event is generated directly since the key does not have any value associated.
-	assert_matches!(
-			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
-	);
-
-	// Import a new block with storage changes.
-	let mut builder = client.new_block(Default::default()).unwrap();
-	builder.push_storage_change(KEY.to_vec(), Some(VALUE.to_vec())).unwrap();
-	let block = builder.build().unwrap().block;
-	let block_hash = format!("{:?}", block.header.hash());
-	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
-
-	// Ensure the imported block is propagated and pinned for this subscription.
-	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-		FollowEvent::NewBlock(_)
-	);
-	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-		FollowEvent::BestBlockChanged(_)
-	);
+	assert!(header.is_some());
 
-	// Valid call with storage at the key.
-	let response: MethodResponse = api
-		.call(
-			"chainHead_unstable_storage",
-			rpc_params![
-				&sub_id,
-				&block_hash,
-				vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Hash }]
-			],
+	// The token is consumed by the resumed subscription.
+	let mut other_sub = api
+		.subscribe(
+			"chainHead_unstable_follow",
+			rpc_params![false, None::<u32>, None::<bool>, None::<String>, &token],
 		)
 		.await
 		.unwrap();
-	let operation_id = match response {
-		MethodResponse::Started(started) => started.operation_id,
-		MethodResponse::LimitReached => panic!("Expected started response"),
-	};
-
-	let expected_hash = format!("{:?}", Blake2Hasher::hash(&VALUE));
-	assert_matches!(
-			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-			FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
-				res.items.len() == 1 &&
-				res.items[0].key == key && res.items[0].result == StorageResultType::Hash(expected_hash)
-	);
+	assert_matches!(get_next_event::<FollowEvent<String>>(&mut other_sub).await, FollowEvent::Stop);
 	assert_matches!(
-			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(_)
 	);
+}
 
-	// Child value set in `setup_api`.
-	let child_info = hex_string(&CHILD_STORAGE_KEY);
-	let genesis_hash = format!("{:?}", client.genesis_hash());
+#[tokio::test]
+async fn follow_with_runtime() {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let mut client = Arc::new(builder.build());
 
-	// Valid call with storage at the key.
-	let response: MethodResponse = api
-		.call(
-			"chainHead_unstable_storage",
-			rpc_params![
-				&sub_id,
-				&genesis_hash,
-				vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Hash }],
-				&child_info
-			],
-		)
+	let api = ChainHead::new(
+		client.clone(),
+		backend,
+		Arc::new(TaskExecutor::default()),
+		CHAIN_GENESIS,
+		ChainHeadConfig {
+			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
+			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
+			subscription_max_ongoing_operations: MAX_OPERATIONS,
+			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			..Default::default()
+		},
+	)
+	.into_rpc();
+
+	let finalized_hash = client.info().finalized_hash;
+	let mut sub = api.subscribe("chainHead_unstable_follow", [true]).await.unwrap();
+
+	// Initialized must always be reported first.
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+
+	// it is basically json-encoded substrate_test_runtime_client::runtime::VERSION
+	let runtime_str = "{\"specName\":\"test\",\"implName\":\"parity-test\",\"authoringVersion\":1,\
+		\"specVersion\":2,\"implVersion\":2,\"apis\":[[\"0xdf6acb689907609b\",4],\
+		[\"0x37e397fc7c91f5e4\",2],[\"0xd2bc9897eed08f15\",3],[\"0x40fe3ad401f8959a\",6],\
+		[\"0xbc9d89904f5b923f\",1],[\"0xc6e9a76309f39b09\",2],[\"0xdd718d5cc53262d4\",1],\
+		[\"0xcbca25e39f142387\",2],[\"0xf78b278be53f454c\",2],[\"0xab3c0572291feb8b\",1],\
+		[\"0xed99c5acb25eedf5\",3],[\"0xfbc577b9d747efd6\",1]],\"transactionVersion\":1,\"stateVersion\":1}";
+
+	let runtime: RuntimeVersion = serde_json::from_str(runtime_str).unwrap();
+
+	let finalized_block_runtime =
+		Some(RuntimeEvent::Valid(RuntimeVersionEvent { spec: runtime.clone() }));
+	// Runtime must always be reported with the first event.
+	let expected = FollowEvent::Initialized(Initialized {
+		finalized_block_hashes: vec![format!("{:?}", finalized_hash)],
+		finalized_block_runtime,
+		with_runtime: false,
+		resumption_token: None,
+	});
+	pretty_assertions::assert_eq!(event, expected);
+
+	// Import a new block without runtime changes.
+	// The runtime field must be None in this case.
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let best_hash = block.header.hash();
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::NewBlock(NewBlock {
+		block_hash: format!("{:?}", best_hash),
+		parent_block_hash: format!("{:?}", finalized_hash),
+		new_runtime: None,
+		with_runtime: false,
+		header: None,
+		extrinsics_count: None,
+	});
+	assert_eq!(event, expected);
+
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
+		best_block_hash: format!("{:?}", best_hash),
+	});
+	assert_eq!(event, expected);
+
+	client.finalize_block(best_hash, None).unwrap();
+
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::Finalized(Finalized {
+		finalized_block_hashes: vec![format!("{:?}", best_hash)],
+		pruned_block_hashes: vec![],
+	});
+	assert_eq!(event, expected);
+
+	let finalized_hash = best_hash;
+	// The `RuntimeVersion` is embedded into the WASM blob at the `runtime_version`
+	// section. Modify the `RuntimeVersion` and commit the changes to a new block.
+	// The RPC must notify the runtime event change.
+	let wasm = sp_maybe_compressed_blob::decompress(
+		runtime::wasm_binary_unwrap(),
+		sp_maybe_compressed_blob::CODE_BLOB_BOMB_LIMIT,
+	)
+	.unwrap();
+	// Update the runtime spec version.
+	let mut runtime = runtime;
+	runtime.spec_version += 1;
+	let embedded = sp_version::embed::embed_runtime_version(&wasm, runtime.clone()).unwrap();
+	let wasm = sp_maybe_compressed_blob::compress(
+		&embedded,
+		sp_maybe_compressed_blob::CODE_BLOB_BOMB_LIMIT,
+	)
+	.unwrap();
+
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder.push_storage_change(CODE.to_vec(), Some(wasm)).unwrap();
+	let block = builder.build().unwrap().block;
+	let best_hash = block.header.hash();
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	let new_runtime = Some(RuntimeEvent::Valid(RuntimeVersionEvent { spec: runtime.clone() }));
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::NewBlock(NewBlock {
+		block_hash: format!("{:?}", best_hash),
+		parent_block_hash: format!("{:?}", finalized_hash),
+		new_runtime,
+		with_runtime: false,
+		header: None,
+		extrinsics_count: None,
+	});
+	assert_eq!(event, expected);
+}
+
+#[tokio::test]
+async fn get_genesis() {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let client = Arc::new(builder.build());
+
+	let api = ChainHead::new(
+		client.clone(),
+		backend,
+		Arc::new(TaskExecutor::default()),
+		CHAIN_GENESIS,
+		ChainHeadConfig {
+			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
+			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
+			subscription_max_ongoing_operations: MAX_OPERATIONS,
+			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			..Default::default()
+		},
+	)
+	.into_rpc();
+
+	let genesis: String =
+		api.call("chainHead_unstable_genesisHash", EmptyParams::new()).await.unwrap();
+	assert_eq!(genesis, hex_string(&CHAIN_GENESIS));
+}
+
+#[tokio::test]
+async fn get_header() {
+	let (_client, api, _sub, sub_id, block) = setup_api().await;
+	let block_hash = format!("{:?}", block.header.hash());
+	let invalid_hash = hex_string(&INVALID_HASH);
+
+	// Invalid subscription ID must produce no results.
+	let res: Option<String> = api
+		.call("chainHead_unstable_header", ["invalid_sub_id", &invalid_hash])
+		.await
+		.unwrap();
+	assert!(res.is_none());
+
+	// Valid subscription with invalid block hash will error.
+	let err = api
+		.call::<_, serde_json::Value>("chainHead_unstable_header", [&sub_id, &invalid_hash])
+		.await
+		.unwrap_err();
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == 2001 && err.message() == "Invalid block hash"
+	);
+
+	// Obtain the valid header.
+	let res: String = api.call("chainHead_unstable_header", [&sub_id, &block_hash]).await.unwrap();
+	let bytes = array_bytes::hex2bytes(&res).unwrap();
+	let header: Header = Decode::decode(&mut &bytes[..]).unwrap();
+	assert_eq!(header, block.header);
+}
+
+#[tokio::test]
+async fn get_headers() {
+	let (client, api, _sub, sub_id, block) = setup_api().await;
+	let block_hash = format!("{:?}", block.header.hash());
+	let genesis_hash = format!("{:?}", client.genesis_hash());
+	let invalid_hash = hex_string(&INVALID_HASH);
+
+	// Invalid subscription ID must produce no results.
+	let res: Vec<Option<String>> = api
+		.call(
+			"chainHead_unstable_headers",
+			rpc_params!["invalid_sub_id", vec![&genesis_hash, &block_hash]],
+		)
+		.await
+		.unwrap();
+	assert_eq!(res, vec![None, None]);
+
+	// Any invalid block hash will error.
+	let err = api
+		.call::<_, serde_json::Value>(
+			"chainHead_unstable_headers",
+			rpc_params![&sub_id, vec![&block_hash, &invalid_hash]],
+		)
+		.await
+		.unwrap_err();
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == 2001 && err.message() == "Invalid block hash"
+	);
+
+	// Obtain the valid headers in the order of the hashes.
+	let res: Vec<String> = api
+		.call("chainHead_unstable_headers", rpc_params![&sub_id, vec![&block_hash, &genesis_hash]])
+		.await
+		.unwrap();
+	let headers = res
+		.iter()
+		.map(|header| {
+			let bytes = array_bytes::hex2bytes(header).unwrap();
+			Header::decode(&mut &bytes[..]).unwrap()
+		})
+		.collect::<Vec<_>>();
+	assert_eq!(headers.len(), 2);
+	assert_eq!(headers[0], block.header);
+	assert_eq!(headers[1].hash(), client.genesis_hash());
+}
+
+#[tokio::test]
+async fn get_body() {
+	let (mut client, api, mut block_sub, sub_id, block) = setup_api().await;
+	let block_hash = format!("{:?}", block.header.hash());
+	let invalid_hash = hex_string(&INVALID_HASH);
+
+	// Subscription ID is invalid.
+	let response: MethodResponse = api
+		.call("chainHead_unstable_body", ["invalid_sub_id", &invalid_hash])
 		.await
 		.unwrap();
+	assert_matches!(response, MethodResponse::LimitReached);
+
+	// Block hash is invalid.
+	let err = api
+		.call::<_, serde_json::Value>("chainHead_unstable_body", [&sub_id, &invalid_hash])
+		.await
+		.unwrap_err();
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == 2001 && err.message() == "Invalid block hash"
+	);
+
+	// Valid call.
+	let response: MethodResponse =
+		api.call("chainHead_unstable_body", [&sub_id, &block_hash]).await.unwrap();
 	let operation_id = match response {
 		MethodResponse::Started(started) => started.operation_id,
 		MethodResponse::LimitReached => panic!("Expected started response"),
 	};
 
-	let expected_hash = format!("{:?}", Blake2Hasher::hash(&CHILD_VALUE));
-	assert_matches!(
-			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-			FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
-				res.items.len() == 1 &&
-				res.items[0].key == key && res.items[0].result == StorageResultType::Hash(expected_hash)
-	);
+	// Response propagated to `chainHead_follow`.
 	assert_matches!(
 			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+			FollowEvent::OperationBodyDone(done) if done.operation_id == operation_id && done.value.is_empty()
 	);
-}
-
-#[tokio::test]
-async fn get_storage_multi_query_iter() {
-	let (mut client, api, mut block_sub, sub_id, _) = setup_api().await;
-	let key = hex_string(&KEY);
 
-	// Import a new block with storage changes.
+	// Import a block with extrinsics.
 	let mut builder = client.new_block(Default::default()).unwrap();
-	builder.push_storage_change(KEY.to_vec(), Some(VALUE.to_vec())).unwrap();
+	builder
+		.push_transfer(runtime::Transfer {
+			from: AccountKeyring::Alice.into(),
+			to: AccountKeyring::Ferdie.into(),
+			amount: 42,
+			nonce: 0,
+		})
+		.unwrap();
+	builder
+		.push_transfer(runtime::Transfer {
+			from: AccountKeyring::Alice.into(),
+			to: AccountKeyring::Bob.into(),
+			amount: 42,
+			nonce: 1,
+		})
+		.unwrap();
 	let block = builder.build().unwrap().block;
 	let block_hash = format!("{:?}", block.header.hash());
 	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
-
 	// Ensure the imported block is propagated and pinned for this subscription.
 	assert_matches!(
 		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
@@ -726,120 +974,118 @@ async fn get_storage_multi_query_iter() {
 		FollowEvent::BestBlockChanged(_)
 	);
 
-	// Valid call with storage at the key.
-	let response: MethodResponse = api
-		.call(
-			"chainHead_unstable_storage",
-			rpc_params![
-				&sub_id,
-				&block_hash,
-				vec![
-					StorageQuery {
-						key: key.clone(),
-						query_type: StorageQueryType::DescendantsHashes
-					},
-					StorageQuery {
-						key: key.clone(),
-						query_type: StorageQueryType::DescendantsValues
-					}
-				]
-			],
-		)
-		.await
-		.unwrap();
+	// Valid call to a block with extrinsics.
+	let response: MethodResponse =
+		api.call("chainHead_unstable_body", [&sub_id, &block_hash]).await.unwrap();
 	let operation_id = match response {
 		MethodResponse::Started(started) => started.operation_id,
 		MethodResponse::LimitReached => panic!("Expected started response"),
 	};
 
-	let expected_hash = format!("{:?}", Blake2Hasher::hash(&VALUE));
-	let expected_value = hex_string(&VALUE);
+	// Response propagated to `chainHead_follow`.
+	// Each extrinsic is hex-encoded individually.
+	let expected_txs =
+		block.extrinsics.iter().map(|tx| hex_string(&tx.encode())).collect::<Vec<_>>();
+	assert_eq!(expected_txs.len(), 2);
 	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-		FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
-			res.items.len() == 1 &&
-			res.items[0].key == key &&
-			res.items[0].result == StorageResultType::Hash(expected_hash)
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::OperationBodyDone(done) if done.operation_id == operation_id && done.value == expected_txs
+	);
+}
+
+#[tokio::test]
+async fn get_body_in_chunks() {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let mut client = Arc::new(builder.build());
+
+	// Every extrinsic is larger than the chunk size and is reported alone.
+	let api = ChainHead::new(
+		client.clone(),
+		backend,
+		Arc::new(TaskExecutor::default()),
+		CHAIN_GENESIS,
+		ChainHeadConfig {
+			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
+			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
+			subscription_max_ongoing_operations: MAX_OPERATIONS,
+			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			operation_max_body_chunk_size: Some(1),
+			..Default::default()
+		},
+	)
+	.into_rpc();
+
+	let mut sub = api.subscribe("chainHead_unstable_follow", [true]).await.unwrap();
+	let sub_id = sub.subscription_id();
+	let sub_id = serde_json::to_string(&sub_id).unwrap();
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::Initialized(_)
 	);
+
+	let mut builder = client.new_block(Default::default()).unwrap();
+	for (nonce, to) in [AccountKeyring::Bob, AccountKeyring::Charlie, AccountKeyring::Ferdie]
+		.into_iter()
+		.enumerate()
+	{
+		builder
+			.push_transfer(runtime::Transfer {
+				from: AccountKeyring::Alice.into(),
+				to: to.into(),
+				amount: 42,
+				nonce: nonce as u64,
+			})
+			.unwrap();
+	}
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
 	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-		FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
-			res.items.len() == 1 &&
-			res.items[0].key == key &&
-			res.items[0].result == StorageResultType::Value(expected_value)
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(_)
 	);
 	assert_matches!(
-			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::BestBlockChanged(_)
 	);
 
-	// Child value set in `setup_api`.
-	let child_info = hex_string(&CHILD_STORAGE_KEY);
-	let genesis_hash = format!("{:?}", client.genesis_hash());
-	let expected_hash = format!("{:?}", Blake2Hasher::hash(&CHILD_VALUE));
-	let expected_value = hex_string(&CHILD_VALUE);
-	let response: MethodResponse = api
-		.call(
-			"chainHead_unstable_storage",
-			rpc_params![
-				&sub_id,
-				&genesis_hash,
-				vec![
-					StorageQuery {
-						key: key.clone(),
-						query_type: StorageQueryType::DescendantsHashes
-					},
-					StorageQuery {
-						key: key.clone(),
-						query_type: StorageQueryType::DescendantsValues
-					}
-				],
-				&child_info
-			],
-		)
-		.await
-		.unwrap();
+	let response: MethodResponse =
+		api.call("chainHead_unstable_body", [&sub_id, &block_hash]).await.unwrap();
 	let operation_id = match response {
 		MethodResponse::Started(started) => started.operation_id,
 		MethodResponse::LimitReached => panic!("Expected started response"),
 	};
 
+	// The extrinsics are reported in order, the last one by the `operationBodyDone` event.
+	let expected_txs =
+		block.extrinsics.iter().map(|tx| hex_string(&tx.encode())).collect::<Vec<_>>();
+	assert_eq!(expected_txs.len(), 3);
+	for expected in &expected_txs[..2] {
+		assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut sub).await,
+			FollowEvent::OperationBodyItems(items) if items.operation_id == operation_id &&
+				items.value == vec![expected.clone()]
+		);
+	}
 	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-		FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
-			res.items.len() == 1 &&
-			res.items[0].key == key &&
-			res.items[0].result == StorageResultType::Hash(expected_hash)
-	);
-	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-		FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
-			res.items.len() == 1 &&
-			res.items[0].key == key &&
-			res.items[0].result == StorageResultType::Value(expected_value)
-	);
-	assert_matches!(
-			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::OperationBodyDone(done) if done.operation_id == operation_id &&
+			done.value == vec![expected_txs[2].clone()]
 	);
 }
 
 #[tokio::test]
-async fn get_storage_value() {
-	let (mut client, api, mut block_sub, sub_id, block) = setup_api().await;
+async fn call_runtime() {
+	let (_client, api, mut block_sub, sub_id, block) = setup_api().await;
 	let block_hash = format!("{:?}", block.header.hash());
 	let invalid_hash = hex_string(&INVALID_HASH);
-	let key = hex_string(&KEY);
 
 	// Subscription ID is invalid.
 	let response: MethodResponse = api
 		.call(
-			"chainHead_unstable_storage",
-			rpc_params![
-				"invalid_sub_id",
-				&invalid_hash,
-				vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Value }]
-			],
+			"chainHead_unstable_call",
+			["invalid_sub_id", &block_hash, "BabeApi_current_epoch", "0x00"],
 		)
 		.await
 		.unwrap();
@@ -848,12 +1094,8 @@ async fn get_storage_value() {
 	// Block hash is invalid.
 	let err = api
 		.call::<_, serde_json::Value>(
-			"chainHead_unstable_storage",
-			rpc_params![
-				&sub_id,
-				&invalid_hash,
-				vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Value }]
-			],
+			"chainHead_unstable_call",
+			[&sub_id, &invalid_hash, "BabeApi_current_epoch", "0x00"],
 		)
 		.await
 		.unwrap_err();
@@ -861,15 +1103,27 @@ async fn get_storage_value() {
 		Error::Call(CallError::Custom(ref err)) if err.code() == 2001 && err.message() == "Invalid block hash"
 	);
 
-	// Valid call without storage at the key.
+	// Pass an invalid parameters that cannot be decode.
+	let err = api
+		.call::<_, serde_json::Value>(
+			"chainHead_unstable_call",
+			// 0x0 is invalid.
+			[&sub_id, &block_hash, "BabeApi_current_epoch", "0x0"],
+		)
+		.await
+		.unwrap_err();
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == 2003 && err.message().contains("Invalid parameter")
+	);
+
+	// Valid call.
+	let alice_id = AccountKeyring::Alice.to_account_id();
+	// Hex encoded scale encoded bytes representing the call parameters.
+	let call_parameters = hex_string(&alice_id.encode());
 	let response: MethodResponse = api
 		.call(
-			"chainHead_unstable_storage",
-			rpc_params![
-				&sub_id,
-				&block_hash,
-				vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Value }]
-			],
+			"chainHead_unstable_call",
+			[&sub_id, &block_hash, "AccountNonceApi_account_nonce", &call_parameters],
 		)
 		.await
 		.unwrap();
@@ -877,37 +1131,48 @@ async fn get_storage_value() {
 		MethodResponse::Started(started) => started.operation_id,
 		MethodResponse::LimitReached => panic!("Expected started response"),
 	};
-	// The `Done` event is generated directly since the key does not have any value associated.
+
+	// Response propagated to `chainHead_follow`.
 	assert_matches!(
 			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+			FollowEvent::OperationCallDone(done) if done.operation_id == operation_id && done.output == "0x0000000000000000"
 	);
 
-	// Import a new block with storage changes.
-	let mut builder = client.new_block(Default::default()).unwrap();
-	builder.push_storage_change(KEY.to_vec(), Some(VALUE.to_vec())).unwrap();
-	let block = builder.build().unwrap().block;
-	let block_hash = format!("{:?}", block.header.hash());
-	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+	// The `current_epoch` takes no parameters and not draining the input buffer
+	// will cause the execution to fail.
+	let response: MethodResponse = api
+		.call("chainHead_unstable_call", [&sub_id, &block_hash, "BabeApi_current_epoch", "0x00"])
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
 
-	// Ensure the imported block is propagated and pinned for this subscription.
-	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-		FollowEvent::NewBlock(_)
-	);
+	// Error propagated to `chainHead_follow`.
 	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-		FollowEvent::BestBlockChanged(_)
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::OperationError(error) if error.operation_id == operation_id && error.error.contains("Execution failed")
 	);
+}
 
-	// Valid call with storage at the key.
+#[tokio::test]
+async fn call_runtime_with_stats() {
+	let (_client, api, mut block_sub, sub_id, block) = setup_api().await;
+	let block_hash = format!("{:?}", block.header.hash());
+
+	let alice_id = AccountKeyring::Alice.to_account_id();
+	// Hex encoded scale encoded bytes representing the call parameters.
+	let call_parameters = hex_string(&alice_id.encode());
 	let response: MethodResponse = api
 		.call(
-			"chainHead_unstable_storage",
+			"chainHead_unstable_call",
 			rpc_params![
 				&sub_id,
 				&block_hash,
-				vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Value }]
+				"AccountNonceApi_account_nonce",
+				&call_parameters,
+				CallOptions { with_stats: true, ..Default::default() }
 			],
 		)
 		.await
@@ -917,30 +1182,31 @@ async fn get_storage_value() {
 		MethodResponse::LimitReached => panic!("Expected started response"),
 	};
 
-	let expected_value = hex_string(&VALUE);
-	assert_matches!(
-			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-			FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
-				res.items.len() == 1 &&
-				res.items[0].key == key && res.items[0].result == StorageResultType::Value(expected_value)
-	);
+	// The output is identical to a call without stats and the proof is recorded.
 	assert_matches!(
 			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+			FollowEvent::OperationCallDone(done) if done.operation_id == operation_id &&
+				done.output == "0x0000000000000000" &&
+				done.stats.as_ref().map_or(false, |stats| stats.proof_size > 0)
 	);
+}
 
-	// Child value set in `setup_api`.
-	let child_info = hex_string(&CHILD_STORAGE_KEY);
-	let genesis_hash = format!("{:?}", client.genesis_hash());
+#[tokio::test]
+async fn call_runtime_onchain_context() {
+	let (_client, api, mut block_sub, sub_id, block) = setup_api().await;
+	let block_hash = format!("{:?}", block.header.hash());
 
+	let alice_id = AccountKeyring::Alice.to_account_id();
+	let call_parameters = hex_string(&alice_id.encode());
 	let response: MethodResponse = api
 		.call(
-			"chainHead_unstable_storage",
+			"chainHead_unstable_call",
 			rpc_params![
 				&sub_id,
-				&genesis_hash,
-				vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Value }],
-				&child_info
+				&block_hash,
+				"AccountNonceApi_account_nonce",
+				&call_parameters,
+				CallOptions { call_context: Some(CallContextType::Onchain), ..Default::default() }
 			],
 		)
 		.await
@@ -950,38 +1216,29 @@ async fn get_storage_value() {
 		MethodResponse::LimitReached => panic!("Expected started response"),
 	};
 
-	let expected_value = hex_string(&CHILD_VALUE);
-
-	assert_matches!(
-			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-			FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
-				res.items.len() == 1 &&
-				res.items[0].key == key && res.items[0].result == StorageResultType::Value(expected_value)
-	);
 	assert_matches!(
 			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+			FollowEvent::OperationCallDone(done) if done.operation_id == operation_id &&
+				done.output == "0x0000000000000000" && done.stats.is_none()
 	);
 }
 
 #[tokio::test]
-async fn get_storage_non_queryable_key() {
-	let (mut _client, api, mut block_sub, sub_id, block) = setup_api().await;
+async fn call_runtime_decode_without_metadata() {
+	let (_client, api, mut block_sub, sub_id, block) = setup_api().await;
 	let block_hash = format!("{:?}", block.header.hash());
-	let key = hex_string(&KEY);
-
-	// Key is prefixed by CHILD_STORAGE_KEY_PREFIX.
-	let mut prefixed_key = well_known_keys::CHILD_STORAGE_KEY_PREFIX.to_vec();
-	prefixed_key.extend_from_slice(&KEY);
-	let prefixed_key = hex_string(&prefixed_key);
 
+	let alice_id = AccountKeyring::Alice.to_account_id();
+	let call_parameters = hex_string(&alice_id.encode());
 	let response: MethodResponse = api
 		.call(
-			"chainHead_unstable_storage",
+			"chainHead_unstable_call",
 			rpc_params![
 				&sub_id,
 				&block_hash,
-				vec![StorageQuery { key: prefixed_key, query_type: StorageQueryType::Value }]
+				"AccountNonceApi_account_nonce",
+				&call_parameters,
+				CallOptions { decode: true, ..Default::default() }
 			],
 		)
 		.await
@@ -990,23 +1247,118 @@ async fn get_storage_non_queryable_key() {
 		MethodResponse::Started(started) => started.operation_id,
 		MethodResponse::LimitReached => panic!("Expected started response"),
 	};
-	// The `Done` event is generated directly since the key is not queryable.
+
+	// The test runtime does not provide its metadata, so the output cannot be decoded.
 	assert_matches!(
 			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+			FollowEvent::OperationError(error) if error.operation_id == operation_id
 	);
+}
 
-	// Key is prefixed by DEFAULT_CHILD_STORAGE_KEY_PREFIX.
-	let mut prefixed_key = well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX.to_vec();
-	prefixed_key.extend_from_slice(&KEY);
-	let prefixed_key = hex_string(&prefixed_key);
+#[tokio::test]
+async fn call_runtime_without_flag() {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let mut client = Arc::new(builder.build());
+
+	let api = ChainHead::new(
+		client.clone(),
+		backend,
+		Arc::new(TaskExecutor::default()),
+		CHAIN_GENESIS,
+		ChainHeadConfig {
+			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
+			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
+			subscription_max_ongoing_operations: MAX_OPERATIONS,
+			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			..Default::default()
+		},
+	)
+	.into_rpc();
+
+	let mut sub = api.subscribe("chainHead_unstable_follow", [false]).await.unwrap();
+	let sub_id = sub.subscription_id();
+	let sub_id = serde_json::to_string(&sub_id).unwrap();
+
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	// Ensure the imported block is propagated and pinned for this subscription.
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::Initialized(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
+
+	// Valid runtime call on a subscription started with `with_runtime` false.
+	let alice_id = AccountKeyring::Alice.to_account_id();
+	let call_parameters = hex_string(&alice_id.encode());
+	let err = api
+		.call::<_, serde_json::Value>(
+			"chainHead_unstable_call",
+			[&sub_id, &block_hash, "AccountNonceApi_account_nonce", &call_parameters],
+		)
+		.await
+		.unwrap_err();
+
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == 2003 && err.message().contains("The runtime updates flag must be set")
+	);
+}
+
+#[tokio::test]
+async fn get_storage_hash() {
+	let (mut client, api, mut block_sub, sub_id, block) = setup_api().await;
+	let block_hash = format!("{:?}", block.header.hash());
+	let invalid_hash = hex_string(&INVALID_HASH);
+	let key = hex_string(&KEY);
+
+	// Subscription ID is invalid.
+	let response: MethodResponse = api
+		.call(
+			"chainHead_unstable_storage",
+			rpc_params![
+				"invalid_sub_id",
+				&invalid_hash,
+				vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Hash }]
+			],
+		)
+		.await
+		.unwrap();
+	assert_matches!(response, MethodResponse::LimitReached);
+
+	// Block hash is invalid.
+	let err = api
+		.call::<_, serde_json::Value>(
+			"chainHead_unstable_storage",
+			rpc_params![
+				&sub_id,
+				&invalid_hash,
+				vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Hash }]
+			],
+		)
+		.await
+		.unwrap_err();
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == 2001 && err.message() == "Invalid block hash"
+	);
+
+	// Valid call without storage at the key.
 	let response: MethodResponse = api
 		.call(
 			"chainHead_unstable_storage",
 			rpc_params![
 				&sub_id,
 				&block_hash,
-				vec![StorageQuery { key: prefixed_key, query_type: StorageQueryType::Value }]
+				vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Hash }]
 			],
 		)
 		.await
@@ -1015,24 +1367,37 @@ async fn get_storage_non_queryable_key() {
 		MethodResponse::Started(started) => started.operation_id,
 		MethodResponse::LimitReached => panic!("Expected started response"),
 	};
-	// The `Done` event is generated directly since the key is not queryable.
+	// The `Done` event is generated directly since the key does not have any value associated.
 	assert_matches!(
 			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
 			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
 	);
 
-	// Child key is prefixed by CHILD_STORAGE_KEY_PREFIX.
-	let mut prefixed_key = well_known_keys::CHILD_STORAGE_KEY_PREFIX.to_vec();
-	prefixed_key.extend_from_slice(CHILD_STORAGE_KEY);
-	let prefixed_key = hex_string(&prefixed_key);
+	// Import a new block with storage changes.
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder.push_storage_change(KEY.to_vec(), Some(VALUE.to_vec())).unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	// Ensure the imported block is propagated and pinned for this subscription.
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+		FollowEvent::NewBlock(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
+
+	// Valid call with storage at the key.
 	let response: MethodResponse = api
 		.call(
 			"chainHead_unstable_storage",
 			rpc_params![
 				&sub_id,
 				&block_hash,
-				vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Value }],
-				&prefixed_key
+				vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Hash }]
 			],
 		)
 		.await
@@ -1041,24 +1406,93 @@ async fn get_storage_non_queryable_key() {
 		MethodResponse::Started(started) => started.operation_id,
 		MethodResponse::LimitReached => panic!("Expected started response"),
 	};
-	// The `Done` event is generated directly since the key is not queryable.
+
+	let expected_hash = format!("{:?}", Blake2Hasher::hash(&VALUE));
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
+				res.items.len() == 1 &&
+				res.items[0].key == key && res.items[0].result == StorageResultType::Hash(expected_hash)
+	);
 	assert_matches!(
 			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
 			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
 	);
 
-	// Child key is prefixed by DEFAULT_CHILD_STORAGE_KEY_PREFIX.
-	let mut prefixed_key = well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX.to_vec();
-	prefixed_key.extend_from_slice(CHILD_STORAGE_KEY);
-	let prefixed_key = hex_string(&prefixed_key);
+	// Child value set in `setup_api`.
+	let child_info = hex_string(&CHILD_STORAGE_KEY);
+	let genesis_hash = format!("{:?}", client.genesis_hash());
+
+	// Valid call with storage at the key.
+	let response: MethodResponse = api
+		.call(
+			"chainHead_unstable_storage",
+			rpc_params![
+				&sub_id,
+				&genesis_hash,
+				vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Hash }],
+				&child_info
+			],
+		)
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+
+	let expected_hash = format!("{:?}", Blake2Hasher::hash(&CHILD_VALUE));
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
+				res.items.len() == 1 &&
+				res.items[0].key == key && res.items[0].result == StorageResultType::Hash(expected_hash)
+	);
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+	);
+}
+
+#[tokio::test]
+async fn get_storage_multi_query_iter() {
+	let (mut client, api, mut block_sub, sub_id, _) = setup_api().await;
+	let key = hex_string(&KEY);
+
+	// Import a new block with storage changes.
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder.push_storage_change(KEY.to_vec(), Some(VALUE.to_vec())).unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	// Ensure the imported block is propagated and pinned for this subscription.
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+		FollowEvent::NewBlock(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
+
+	// Valid call with storage at the key.
 	let response: MethodResponse = api
 		.call(
 			"chainHead_unstable_storage",
 			rpc_params![
 				&sub_id,
 				&block_hash,
-				vec![StorageQuery { key, query_type: StorageQueryType::Value }],
-				&prefixed_key
+				vec![
+					StorageQuery {
+						key: key.clone(),
+						query_type: StorageQueryType::DescendantsHashes
+					},
+					StorageQuery {
+						key: key.clone(),
+						query_type: StorageQueryType::DescendantsValues
+					}
+				]
 			],
 		)
 		.await
@@ -1067,93 +1501,2164 @@ async fn get_storage_non_queryable_key() {
 		MethodResponse::Started(started) => started.operation_id,
 		MethodResponse::LimitReached => panic!("Expected started response"),
 	};
-	// The `Done` event is generated directly since the key is not queryable.
+
+	let expected_hash = format!("{:?}", Blake2Hasher::hash(&VALUE));
+	let expected_value = hex_string(&VALUE);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+		FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
+			res.items.len() == 1 &&
+			res.items[0].key == key &&
+			res.items[0].result == StorageResultType::Hash(expected_hash)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+		FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
+			res.items.len() == 1 &&
+			res.items[0].key == key &&
+			res.items[0].result == StorageResultType::Value(expected_value)
+	);
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+	);
+
+	// Child value set in `setup_api`.
+	let child_info = hex_string(&CHILD_STORAGE_KEY);
+	let genesis_hash = format!("{:?}", client.genesis_hash());
+	let expected_hash = format!("{:?}", Blake2Hasher::hash(&CHILD_VALUE));
+	let expected_value = hex_string(&CHILD_VALUE);
+	let response: MethodResponse = api
+		.call(
+			"chainHead_unstable_storage",
+			rpc_params![
+				&sub_id,
+				&genesis_hash,
+				vec![
+					StorageQuery {
+						key: key.clone(),
+						query_type: StorageQueryType::DescendantsHashes
+					},
+					StorageQuery {
+						key: key.clone(),
+						query_type: StorageQueryType::DescendantsValues
+					}
+				],
+				&child_info
+			],
+		)
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+		FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
+			res.items.len() == 1 &&
+			res.items[0].key == key &&
+			res.items[0].result == StorageResultType::Hash(expected_hash)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+		FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
+			res.items.len() == 1 &&
+			res.items[0].key == key &&
+			res.items[0].result == StorageResultType::Value(expected_value)
+	);
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+	);
+}
+
+#[tokio::test]
+async fn get_storage_value() {
+	let (mut client, api, mut block_sub, sub_id, block) = setup_api().await;
+	let block_hash = format!("{:?}", block.header.hash());
+	let invalid_hash = hex_string(&INVALID_HASH);
+	let key = hex_string(&KEY);
+
+	// Subscription ID is invalid.
+	let response: MethodResponse = api
+		.call(
+			"chainHead_unstable_storage",
+			rpc_params![
+				"invalid_sub_id",
+				&invalid_hash,
+				vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Value }]
+			],
+		)
+		.await
+		.unwrap();
+	assert_matches!(response, MethodResponse::LimitReached);
+
+	// Block hash is invalid.
+	let err = api
+		.call::<_, serde_json::Value>(
+			"chainHead_unstable_storage",
+			rpc_params![
+				&sub_id,
+				&invalid_hash,
+				vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Value }]
+			],
+		)
+		.await
+		.unwrap_err();
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == 2001 && err.message() == "Invalid block hash"
+	);
+
+	// Valid call without storage at the key.
+	let response: MethodResponse = api
+		.call(
+			"chainHead_unstable_storage",
+			rpc_params![
+				&sub_id,
+				&block_hash,
+				vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Value }]
+			],
+		)
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+	// The `Done` event is generated directly since the key does not have any value associated.
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+	);
+
+	// Import a new block with storage changes.
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder.push_storage_change(KEY.to_vec(), Some(VALUE.to_vec())).unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	// Ensure the imported block is propagated and pinned for this subscription.
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+		FollowEvent::NewBlock(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
+
+	// Valid call with storage at the key.
+	let response: MethodResponse = api
+		.call(
+			"chainHead_unstable_storage",
+			rpc_params![
+				&sub_id,
+				&block_hash,
+				vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Value }]
+			],
+		)
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+
+	let expected_value = hex_string(&VALUE);
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
+				res.items.len() == 1 &&
+				res.items[0].key == key && res.items[0].result == StorageResultType::Value(expected_value)
+	);
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+	);
+
+	// Child value set in `setup_api`.
+	let child_info = hex_string(&CHILD_STORAGE_KEY);
+	let genesis_hash = format!("{:?}", client.genesis_hash());
+
+	let response: MethodResponse = api
+		.call(
+			"chainHead_unstable_storage",
+			rpc_params![
+				&sub_id,
+				&genesis_hash,
+				vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Value }],
+				&child_info
+			],
+		)
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+
+	let expected_value = hex_string(&CHILD_VALUE);
+
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
+				res.items.len() == 1 &&
+				res.items[0].key == key && res.items[0].result == StorageResultType::Value(expected_value)
+	);
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+	);
+}
+
+#[tokio::test]
+async fn get_storage_multiple_keys() {
+	let (mut client, api, mut block_sub, sub_id, _block) = setup_api().await;
+
+	// Import a new block with storage changes.
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder.push_storage_change(KEY.to_vec(), Some(VALUE.to_vec())).unwrap();
+	builder
+		.push_storage_change(b":other".to_vec(), Some(CHILD_VALUE.to_vec()))
+		.unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+		FollowEvent::NewBlock(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
+
+	// The keys without value are not reported.
+	let key = hex_string(&KEY);
+	let other_key = hex_string(b":other");
+	let queries = vec![
+		StorageQuery { key: key.clone(), query_type: StorageQueryType::Value },
+		StorageQuery { key: hex_string(b":missing"), query_type: StorageQueryType::Value },
+		StorageQuery { key: other_key.clone(), query_type: StorageQueryType::Value },
+		StorageQuery { key: key.clone(), query_type: StorageQueryType::Hash },
+	];
+	let response: MethodResponse = api
+		.call("chainHead_unstable_storage", rpc_params![&sub_id, &block_hash, queries])
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => {
+			assert_eq!(started.discarded_items, Some(0));
+			started.operation_id
+		},
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+
+	// The results of all the queried items are reported by a single event, in order.
+	let expected_hash = format!("{:?}", Blake2Hasher::hash(&VALUE));
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+		FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
+			res.items == vec![
+				StorageResult { key: key.clone(), result: StorageResultType::Value(hex_string(&VALUE)) },
+				StorageResult { key: other_key, result: StorageResultType::Value(hex_string(&CHILD_VALUE)) },
+				StorageResult { key, result: StorageResultType::Hash(expected_hash) },
+			]
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+		FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+	);
+}
+
+#[tokio::test]
+async fn get_storage_with_proof() {
+	let (mut client, api, mut block_sub, sub_id, _block) = setup_api().await;
+
+	// Import a new block with storage changes.
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder.push_storage_change(KEY.to_vec(), Some(VALUE.to_vec())).unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+		FollowEvent::NewBlock(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
+
+	let key = hex_string(&KEY);
+	let response: MethodResponse = api
+		.call(
+			"chainHead_unstable_storage",
+			rpc_params![
+				&sub_id,
+				&block_hash,
+				vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Value }],
+				Option::<String>::None,
+				StorageOptions { include_proof: true }
+			],
+		)
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+
+	let proof = match get_next_event::<FollowEvent<String>>(&mut block_sub).await {
+		FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id => {
+			assert_eq!(
+				res.items,
+				vec![StorageResult { key, result: StorageResultType::Value(hex_string(&VALUE)) }]
+			);
+			res.proof.expect("The proof is requested; qed")
+		},
+		event => panic!("Expected storage items, got {:?}", event),
+	};
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+		FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+	);
+
+	// The value is verified against the state root of the block.
+	let nodes = proof.iter().map(|node| array_bytes::hex2bytes(node).unwrap());
+	let db = sp_trie::StorageProof::new(nodes).into_memory_db::<Blake2Hasher>();
+	let value = sp_trie::read_trie_value::<sp_trie::LayoutV1<Blake2Hasher>, _>(
+		&db,
+		&block.header.state_root,
+		KEY,
+		None,
+		None,
+	)
+	.unwrap();
+	assert_eq!(value, Some(VALUE.to_vec()));
+}
+
+#[tokio::test]
+async fn get_storage_closest_merkle_value() {
+	let (mut client, api, mut block_sub, sub_id, _block) = setup_api().await;
+	let prefix = hex_string(b":AA");
+
+	let mut merkle_values = Vec::new();
+	for value in [b"first".to_vec(), b"second".to_vec()] {
+		let mut builder = client.new_block(Default::default()).unwrap();
+		builder.push_storage_change(b":AAAA".to_vec(), Some(VALUE.to_vec())).unwrap();
+		builder.push_storage_change(b":AABB".to_vec(), Some(value)).unwrap();
+		let block = builder.build().unwrap().block;
+		let block_hash = format!("{:?}", block.header.hash());
+		client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+		assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::NewBlock(_)
+		);
+		assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::BestBlockChanged(_)
+		);
+
+		// The prefixes without descendants are not reported.
+		let queries = vec![
+			StorageQuery {
+				key: prefix.clone(),
+				query_type: StorageQueryType::ClosestDescendantMerkleValue,
+			},
+			StorageQuery {
+				key: hex_string(b":ZZ"),
+				query_type: StorageQueryType::ClosestDescendantMerkleValue,
+			},
+		];
+		let response: MethodResponse = api
+			.call("chainHead_unstable_storage", rpc_params![&sub_id, &block_hash, queries])
+			.await
+			.unwrap();
+		let operation_id = match response {
+			MethodResponse::Started(started) => started.operation_id,
+			MethodResponse::LimitReached => panic!("Expected started response"),
+		};
+
+		let mut items = match get_next_event::<FollowEvent<String>>(&mut block_sub).await {
+			FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id =>
+				res.items,
+			event => panic!("Expected storage items, got {:?}", event),
+		};
+		assert_eq!(items.len(), 1);
+		let item = items.pop().unwrap();
+		assert_eq!(item.key, prefix);
+		assert_matches!(item.result, StorageResultType::ClosestDescendantMerkleValue(value) => {
+			merkle_values.push(value)
+		});
+		assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+		);
+	}
+
+	// The merkle value changes with the values of the descendants.
+	assert_ne!(merkle_values[0], merkle_values[1]);
+}
+
+#[tokio::test]
+async fn get_storage_exists_and_value_length() {
+	let (mut client, api, mut block_sub, sub_id, _block) = setup_api().await;
+	let key = hex_string(&KEY);
+	let queries = vec![
+		StorageQuery { key: key.clone(), query_type: StorageQueryType::Exists },
+		StorageQuery { key: key.clone(), query_type: StorageQueryType::ValueLength },
+	];
+
+	// Import a new block with storage changes.
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder.push_storage_change(KEY.to_vec(), Some(VALUE.to_vec())).unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	// Ensure the imported block is propagated and pinned for this subscription.
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+		FollowEvent::NewBlock(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
+
+	// The key does not have a value at the genesis block.
+	let genesis_hash = format!("{:?}", client.genesis_hash());
+	let response: MethodResponse = api
+		.call("chainHead_unstable_storage", rpc_params![&sub_id, &genesis_hash, queries.clone()])
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+	// Only the existence is reported for a missing key.
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
+				res.items.len() == 1 &&
+				res.items[0].key == key && res.items[0].result == StorageResultType::Exists(false)
+	);
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+	);
+
+	// The key has a value at the imported block.
+	let response: MethodResponse = api
+		.call("chainHead_unstable_storage", rpc_params![&sub_id, &block_hash, queries])
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
+				res.items.len() == 2 &&
+				res.items[0].key == key && res.items[0].result == StorageResultType::Exists(true) &&
+				res.items[1].key == key &&
+				res.items[1].result == StorageResultType::ValueLength(VALUE.len() as u32)
+	);
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+	);
+}
+
+#[tokio::test]
+async fn get_storage_ancestor_values() {
+	let (mut client, api, mut block_sub, sub_id, _block) = setup_api().await;
+	let key = hex_string(&KEY);
+	let queries = vec![
+		StorageQuery {
+			key: key.clone(),
+			query_type: StorageQueryType::AncestorValues { ancestors: 1 },
+		},
+		StorageQuery {
+			key: key.clone(),
+			query_type: StorageQueryType::AncestorValues { ancestors: 5 },
+		},
+	];
+
+	// Import a new block with storage changes.
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder.push_storage_change(KEY.to_vec(), Some(VALUE.to_vec())).unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	// Ensure the imported block is propagated and pinned for this subscription.
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+		FollowEvent::NewBlock(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
+
+	let response: MethodResponse = api
+		.call("chainHead_unstable_storage", rpc_params![&sub_id, &block_hash, queries])
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+
+	// The series ends at the genesis block.
+	let value = Some(hex_string(&VALUE));
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
+				res.items.len() == 2 &&
+				res.items[0].key == key &&
+				res.items[0].result == StorageResultType::AncestorValues(vec![value.clone(), None]) &&
+				res.items[1].key == key &&
+				res.items[1].result == StorageResultType::AncestorValues(vec![value.clone(), None, None])
+	);
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+	);
+}
+
+#[tokio::test]
+async fn get_storage_non_queryable_key() {
+	let (mut _client, api, mut block_sub, sub_id, block) = setup_api().await;
+	let block_hash = format!("{:?}", block.header.hash());
+	let key = hex_string(&KEY);
+
+	// Key is prefixed by CHILD_STORAGE_KEY_PREFIX.
+	let mut prefixed_key = well_known_keys::CHILD_STORAGE_KEY_PREFIX.to_vec();
+	prefixed_key.extend_from_slice(&KEY);
+	let prefixed_key = hex_string(&prefixed_key);
+
+	let response: MethodResponse = api
+		.call(
+			"chainHead_unstable_storage",
+			rpc_params![
+				&sub_id,
+				&block_hash,
+				vec![StorageQuery { key: prefixed_key, query_type: StorageQueryType::Value }]
+			],
+		)
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+	// The `Done` event is generated directly since the key is not queryable.
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+	);
+
+	// Key is prefixed by DEFAULT_CHILD_STORAGE_KEY_PREFIX.
+	let mut prefixed_key = well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX.to_vec();
+	prefixed_key.extend_from_slice(&KEY);
+	let prefixed_key = hex_string(&prefixed_key);
+	let response: MethodResponse = api
+		.call(
+			"chainHead_unstable_storage",
+			rpc_params![
+				&sub_id,
+				&block_hash,
+				vec![StorageQuery { key: prefixed_key, query_type: StorageQueryType::Value }]
+			],
+		)
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+	// The `Done` event is generated directly since the key is not queryable.
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+	);
+
+	// Child key is prefixed by CHILD_STORAGE_KEY_PREFIX.
+	let mut prefixed_key = well_known_keys::CHILD_STORAGE_KEY_PREFIX.to_vec();
+	prefixed_key.extend_from_slice(CHILD_STORAGE_KEY);
+	let prefixed_key = hex_string(&prefixed_key);
+	let response: MethodResponse = api
+		.call(
+			"chainHead_unstable_storage",
+			rpc_params![
+				&sub_id,
+				&block_hash,
+				vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Value }],
+				&prefixed_key
+			],
+		)
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+	// The `Done` event is generated directly since the key is not queryable.
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+	);
+
+	// Child key is prefixed by DEFAULT_CHILD_STORAGE_KEY_PREFIX.
+	let mut prefixed_key = well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX.to_vec();
+	prefixed_key.extend_from_slice(CHILD_STORAGE_KEY);
+	let prefixed_key = hex_string(&prefixed_key);
+	let response: MethodResponse = api
+		.call(
+			"chainHead_unstable_storage",
+			rpc_params![
+				&sub_id,
+				&block_hash,
+				vec![StorageQuery { key, query_type: StorageQueryType::Value }],
+				&prefixed_key
+			],
+		)
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+	// The `Done` event is generated directly since the key is not queryable.
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+	);
+}
+
+#[tokio::test]
+async fn unique_operation_ids() {
+	let (mut _client, api, mut block_sub, sub_id, block) = setup_api().await;
+	let block_hash = format!("{:?}", block.header.hash());
+
+	let mut op_ids = HashSet::new();
+
+	// Ensure that operation IDs are unique for multiple method calls.
+	for _ in 0..5 {
+		// Valid `chainHead_unstable_body` call.
+		let response: MethodResponse =
+			api.call("chainHead_unstable_body", [&sub_id, &block_hash]).await.unwrap();
+		let operation_id = match response {
+			MethodResponse::Started(started) => started.operation_id,
+			MethodResponse::LimitReached => panic!("Expected started response"),
+		};
+		assert_matches!(
+				get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+				FollowEvent::OperationBodyDone(done) if done.operation_id == operation_id && done.value.is_empty()
+		);
+		// Ensure uniqueness.
+		assert!(op_ids.insert(operation_id));
+
+		// Valid `chainHead_unstable_storage` call.
+		let key = hex_string(&KEY);
+		let response: MethodResponse = api
+			.call(
+				"chainHead_unstable_storage",
+				rpc_params![
+					&sub_id,
+					&block_hash,
+					vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Value }]
+				],
+			)
+			.await
+			.unwrap();
+		let operation_id = match response {
+			MethodResponse::Started(started) => started.operation_id,
+			MethodResponse::LimitReached => panic!("Expected started response"),
+		};
+		// The `Done` event is generated directly since the key does not have any value associated.
+		assert_matches!(
+				get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+				FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+		);
+		// Ensure uniqueness.
+		assert!(op_ids.insert(operation_id));
+
+		// Valid `chainHead_unstable_call` call.
+		let alice_id = AccountKeyring::Alice.to_account_id();
+		let call_parameters = hex_string(&alice_id.encode());
+		let response: MethodResponse = api
+			.call(
+				"chainHead_unstable_call",
+				[&sub_id, &block_hash, "AccountNonceApi_account_nonce", &call_parameters],
+			)
+			.await
+			.unwrap();
+		let operation_id = match response {
+			MethodResponse::Started(started) => started.operation_id,
+			MethodResponse::LimitReached => panic!("Expected started response"),
+		};
+		// Response propagated to `chainHead_follow`.
+		assert_matches!(
+				get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+				FollowEvent::OperationCallDone(done) if done.operation_id == operation_id && done.output == "0x0000000000000000"
+		);
+		// Ensure uniqueness.
+		assert!(op_ids.insert(operation_id));
+	}
+}
+
+#[tokio::test]
+async fn separate_operation_ids_for_subscriptions() {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let mut client = Arc::new(builder.build());
+
+	let api = ChainHead::new(
+		client.clone(),
+		backend,
+		Arc::new(TaskExecutor::default()),
+		CHAIN_GENESIS,
+		ChainHeadConfig {
+			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
+			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
+			subscription_max_ongoing_operations: MAX_OPERATIONS,
+			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			..Default::default()
+		},
+	)
+	.into_rpc();
+
+	// Create two separate subscriptions.
+	let mut sub_first = api.subscribe("chainHead_unstable_follow", [true]).await.unwrap();
+	let sub_id_first = sub_first.subscription_id();
+	let sub_id_first = serde_json::to_string(&sub_id_first).unwrap();
+
+	let mut sub_second = api.subscribe("chainHead_unstable_follow", [true]).await.unwrap();
+	let sub_id_second = sub_second.subscription_id();
+	let sub_id_second = serde_json::to_string(&sub_id_second).unwrap();
+
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+	let block_hash = format!("{:?}", block.header.hash());
+
+	// Ensure the imported block is propagated and pinned.
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub_first).await,
+		FollowEvent::Initialized(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub_first).await,
+		FollowEvent::NewBlock(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub_first).await,
+		FollowEvent::BestBlockChanged(_)
+	);
+
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub_second).await,
+		FollowEvent::Initialized(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub_second).await,
+		FollowEvent::NewBlock(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub_second).await,
+		FollowEvent::BestBlockChanged(_)
+	);
+
+	// Each `chainHead_follow` subscription receives a separate operation ID.
+	let response: MethodResponse =
+		api.call("chainHead_unstable_body", [&sub_id_first, &block_hash]).await.unwrap();
+	let operation_id: String = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+	assert_eq!(operation_id, "0");
+
+	let response: MethodResponse = api
+		.call("chainHead_unstable_body", [&sub_id_second, &block_hash])
+		.await
+		.unwrap();
+	let operation_id_second: String = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+	// The second subscription does not increment the operation ID of the first one.
+	assert_eq!(operation_id_second, "0");
+}
+
+#[tokio::test]
+async fn follow_generates_initial_blocks() {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let mut client = Arc::new(builder.build());
+
+	let api = ChainHead::new(
+		client.clone(),
+		backend,
+		Arc::new(TaskExecutor::default()),
+		CHAIN_GENESIS,
+		ChainHeadConfig {
+			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
+			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
+			subscription_max_ongoing_operations: MAX_OPERATIONS,
+			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			..Default::default()
+		},
+	)
+	.into_rpc();
+
+	let finalized_hash = client.info().finalized_hash;
+
+	// Block tree:
+	// finalized -> block 1 -> block 2 -> block 4
+	//           -> block 1 -> block 3
+	let block_1 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_1_hash = block_1.header.hash();
+	client.import(BlockOrigin::Own, block_1.clone()).await.unwrap();
+
+	let block_2 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_2_hash = block_2.header.hash();
+	client.import(BlockOrigin::Own, block_2.clone()).await.unwrap();
+
+	let mut block_builder =
+		client.new_block_at(block_1.header.hash(), Default::default(), false).unwrap();
+	// This push is required as otherwise block 3 has the same hash as block 2 and won't get
+	// imported
+	block_builder
+		.push_transfer(Transfer {
+			from: AccountKeyring::Alice.into(),
+			to: AccountKeyring::Ferdie.into(),
+			amount: 41,
+			nonce: 0,
+		})
+		.unwrap();
+	let block_3 = block_builder.build().unwrap().block;
+	let block_3_hash = block_3.header.hash();
+	client.import(BlockOrigin::Own, block_3.clone()).await.unwrap();
+
+	let mut sub = api.subscribe("chainHead_unstable_follow", [false]).await.unwrap();
+
+	// Initialized must always be reported first.
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::Initialized(Initialized {
+		finalized_block_hashes: vec![format!("{:?}", finalized_hash)],
+		finalized_block_runtime: None,
+		with_runtime: false,
+		resumption_token: None,
+	});
+	assert_eq!(event, expected);
+
+	// Check block 1.
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::NewBlock(NewBlock {
+		block_hash: format!("{:?}", block_1_hash),
+		parent_block_hash: format!("{:?}", finalized_hash),
+		new_runtime: None,
+		with_runtime: false,
+		header: None,
+		extrinsics_count: None,
+	});
+	assert_eq!(event, expected);
+
+	// Check block 2.
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::NewBlock(NewBlock {
+		block_hash: format!("{:?}", block_2_hash),
+		parent_block_hash: format!("{:?}", block_1_hash),
+		new_runtime: None,
+		with_runtime: false,
+		header: None,
+		extrinsics_count: None,
+	});
+	assert_eq!(event, expected);
+	// Check block 3.
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::NewBlock(NewBlock {
+		block_hash: format!("{:?}", block_3_hash),
+		parent_block_hash: format!("{:?}", block_1_hash),
+		new_runtime: None,
+		with_runtime: false,
+		header: None,
+		extrinsics_count: None,
+	});
+	assert_eq!(event, expected);
+
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
+		best_block_hash: format!("{:?}", block_2_hash),
+	});
+	assert_eq!(event, expected);
+
+	// Import block 4.
+	let block_4 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_4_hash = block_4.header.hash();
+	client.import(BlockOrigin::Own, block_4.clone()).await.unwrap();
+
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::NewBlock(NewBlock {
+		block_hash: format!("{:?}", block_4_hash),
+		parent_block_hash: format!("{:?}", block_2_hash),
+		new_runtime: None,
+		with_runtime: false,
+		header: None,
+		extrinsics_count: None,
+	});
+	assert_eq!(event, expected);
+
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
+		best_block_hash: format!("{:?}", block_4_hash),
+	});
+	assert_eq!(event, expected);
+
+	// Check the finalized event:
+	//  - blocks 1, 2, 4 from canonical chain are finalized
+	//  - block 3 from the fork is pruned
+	client.finalize_block(block_4_hash, None).unwrap();
+
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::Finalized(Finalized {
+		finalized_block_hashes: vec![
+			format!("{:?}", block_1_hash),
+			format!("{:?}", block_2_hash),
+			format!("{:?}", block_4_hash),
+		],
+		pruned_block_hashes: vec![format!("{:?}", block_3_hash)],
+	});
+	assert_eq!(event, expected);
+}
+
+#[tokio::test]
+async fn follow_exceeding_pinned_blocks() {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let mut client = Arc::new(builder.build());
+
+	let api = ChainHead::new(
+		client.clone(),
+		backend,
+		Arc::new(TaskExecutor::default()),
+		CHAIN_GENESIS,
+		ChainHeadConfig {
+			global_max_pinned_blocks: 2,
+			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
+			subscription_max_ongoing_operations: MAX_OPERATIONS,
+			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			..Default::default()
+		},
+	)
+	.into_rpc();
+
+	let mut sub = api.subscribe("chainHead_unstable_follow", [false]).await.unwrap();
+
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	// Ensure the imported block is propagated and pinned for this subscription.
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::Initialized(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
+
+	// Block tree:
+	//   finalized_block -> block -> block2
+	// The first 2 blocks are pinned into the subscription, but the block2 will exceed the limit (2
+	// blocks).
+	let block2 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	client.import(BlockOrigin::Own, block2.clone()).await.unwrap();
+
+	assert_matches!(get_next_event::<FollowEvent<String>>(&mut sub).await, FollowEvent::Stop);
+
+	// Subscription will not produce any more event for further blocks.
+	let block3 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	client.import(BlockOrigin::Own, block3.clone()).await.unwrap();
+
+	assert!(sub.next::<FollowEvent<String>>().await.is_none());
+}
+
+#[tokio::test]
+async fn follow_exceeding_subscriptions() {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let mut client = Arc::new(builder.build());
+
+	let api = ChainHead::new(
+		client.clone(),
+		backend,
+		Arc::new(TaskExecutor::default()),
+		CHAIN_GENESIS,
+		ChainHeadConfig {
+			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
+			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
+			subscription_max_ongoing_operations: MAX_OPERATIONS,
+			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			max_follow_subscriptions: Some(1),
+			..Default::default()
+		},
+	)
+	.into_rpc();
+
+	let mut sub = api.subscribe("chainHead_unstable_follow", [false]).await.unwrap();
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::Initialized(_)
+	);
+
+	// The second subscription is rejected.
+	let err = api.subscribe("chainHead_unstable_follow", [false]).await.unwrap_err();
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == -32800
+	);
+
+	// The slot is released once the subscription is removed. The client disconnects,
+	// which is noticed once the next event is submitted.
+	drop(sub);
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	client.import(BlockOrigin::Own, block).await.unwrap();
+
+	let mut sub = loop {
+		match api.subscribe("chainHead_unstable_follow", [false]).await {
+			Ok(sub) => break sub,
+			Err(_) => tokio::time::sleep(Duration::from_millis(10)).await,
+		}
+	};
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::Initialized(_)
+	);
+}
+
+#[tokio::test]
+async fn follow_with_unpin() {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let mut client = Arc::new(builder.build());
+
+	let api = ChainHead::new(
+		client.clone(),
+		backend,
+		Arc::new(TaskExecutor::default()),
+		CHAIN_GENESIS,
+		ChainHeadConfig {
+			global_max_pinned_blocks: 2,
+			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
+			subscription_max_ongoing_operations: MAX_OPERATIONS,
+			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			..Default::default()
+		},
+	)
+	.into_rpc();
+
+	let mut sub = api.subscribe("chainHead_unstable_follow", [false]).await.unwrap();
+	let sub_id = sub.subscription_id();
+	let sub_id = serde_json::to_string(&sub_id).unwrap();
+
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	// Ensure the imported block is propagated and pinned for this subscription.
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::Initialized(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
+
+	// Unpin an invalid subscription ID must return Ok(()).
+	let invalid_hash = hex_string(&INVALID_HASH);
+	let _res: () = api
+		.call("chainHead_unstable_unpin", ["invalid_sub_id", &invalid_hash])
+		.await
+		.unwrap();
+
+	// Valid subscription with invalid block hash.
+	let invalid_hash = hex_string(&INVALID_HASH);
+	let err = api
+		.call::<_, serde_json::Value>("chainHead_unstable_unpin", [&sub_id, &invalid_hash])
+		.await
+		.unwrap_err();
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == 2001 && err.message() == "Invalid block hash"
+	);
+
+	// To not exceed the number of pinned blocks, we need to unpin before the next import.
+	let _res: () = api.call("chainHead_unstable_unpin", [&sub_id, &block_hash]).await.unwrap();
+
+	// Block tree:
+	//   finalized_block -> block -> block2
+	//                      ^ has been unpinned
+	let block2 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	client.import(BlockOrigin::Own, block2.clone()).await.unwrap();
+
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(_)
+	);
+
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
+
+	let block3 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	client.import(BlockOrigin::Own, block3.clone()).await.unwrap();
+
+	assert_matches!(get_next_event::<FollowEvent<String>>(&mut sub).await, FollowEvent::Stop);
+	assert!(sub.next::<FollowEvent<String>>().await.is_none());
+}
+
+#[tokio::test]
+async fn follow_with_multiple_unpin_hashes() {
+	let (client, api, _sub, sub_id, block) = setup_api().await;
+	let genesis_hash = format!("{:?}", client.genesis_hash());
+	let block_hash = format!("{:?}", block.header.hash());
+	let invalid_hash = hex_string(&INVALID_HASH);
+
+	// No block is unpinned if any of the hashes is invalid.
+	let err = api
+		.call::<_, serde_json::Value>(
+			"chainHead_unstable_unpin",
+			rpc_params![&sub_id, vec![&block_hash, &invalid_hash]],
+		)
+		.await
+		.unwrap_err();
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == 2001 && err.message() == "Invalid block hash"
+	);
+	let pinned: Vec<PinnedBlock<String>> =
+		api.call("chainHead_unstable_pinnedBlocks", [&sub_id]).await.unwrap();
+	assert_eq!(pinned.len(), 2);
+
+	// Unpin both blocks with a single call.
+	let _res: () = api
+		.call("chainHead_unstable_unpin", rpc_params![&sub_id, vec![&genesis_hash, &block_hash]])
+		.await
+		.unwrap();
+	let pinned: Vec<PinnedBlock<String>> =
+		api.call("chainHead_unstable_pinnedBlocks", [&sub_id]).await.unwrap();
+	assert!(pinned.is_empty());
+
+	// The blocks can not be unpinned twice.
+	let err = api
+		.call::<_, serde_json::Value>(
+			"chainHead_unstable_unpin",
+			rpc_params![&sub_id, vec![&block_hash]],
+		)
+		.await
+		.unwrap_err();
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == 2001 && err.message() == "Invalid block hash"
+	);
+}
+
+#[tokio::test]
+async fn follow_lists_pinned_blocks() {
+	let (client, api, _sub, sub_id, block) = setup_api().await;
+	let genesis_hash = format!("{:?}", client.genesis_hash());
+	let block_hash = format!("{:?}", block.header.hash());
+
+	// Invalid subscription ID must return an empty list.
+	let pinned: Vec<PinnedBlock<String>> =
+		api.call("chainHead_unstable_pinnedBlocks", ["invalid_sub_id"]).await.unwrap();
+	assert!(pinned.is_empty());
+
+	// The blocks are listed from the oldest pin.
+	let pinned: Vec<PinnedBlock<String>> =
+		api.call("chainHead_unstable_pinnedBlocks", [&sub_id]).await.unwrap();
+	let hashes: Vec<_> = pinned.iter().map(|block| block.hash.clone()).collect();
+	assert_eq!(hashes, vec![genesis_hash.clone(), block_hash.clone()]);
+	assert!(pinned[0].pin_age_millis >= pinned[1].pin_age_millis);
+
+	// The unpinned block is no longer listed.
+	let _res: () = api.call("chainHead_unstable_unpin", [&sub_id, &block_hash]).await.unwrap();
+	let pinned: Vec<PinnedBlock<String>> =
+		api.call("chainHead_unstable_pinnedBlocks", [&sub_id]).await.unwrap();
+	let hashes: Vec<_> = pinned.into_iter().map(|block| block.hash).collect();
+	assert_eq!(hashes, vec![genesis_hash]);
+}
+
+#[tokio::test]
+async fn follow_prune_best_block() {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let mut client = Arc::new(builder.build());
+
+	let api = ChainHead::new(
+		client.clone(),
+		backend,
+		Arc::new(TaskExecutor::default()),
+		CHAIN_GENESIS,
+		ChainHeadConfig {
+			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
+			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
+			subscription_max_ongoing_operations: MAX_OPERATIONS,
+			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			..Default::default()
+		},
+	)
+	.into_rpc();
+
+	let finalized_hash = client.info().finalized_hash;
+	let mut sub = api.subscribe("chainHead_unstable_follow", [false]).await.unwrap();
+
+	// Initialized must always be reported first.
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::Initialized(Initialized {
+		finalized_block_hashes: vec![format!("{:?}", finalized_hash)],
+		finalized_block_runtime: None,
+		with_runtime: false,
+		resumption_token: None,
+	});
+	assert_eq!(event, expected);
+
+	// Block tree:
+	//
+	// finalized -> block 1 -> block 2
+	//                         ^^^ best block reported
+	//
+	//           -> block 1 -> block 3 -> block 4
+	//                                    ^^^ finalized
+	//
+	// The block 4 is needed on the longest chain because we want the
+	// best block 2 to be reported as pruned. Pruning is happening at
+	// height (N - 1), where N is the finalized block number.
+
+	let block_1 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_1_hash = block_1.header.hash();
+	client.import(BlockOrigin::Own, block_1.clone()).await.unwrap();
+
+	let block_3 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_3_hash = block_3.header.hash();
+	client.import(BlockOrigin::Own, block_3.clone()).await.unwrap();
+
+	let block_4 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_4_hash = block_4.header.hash();
+	client.import(BlockOrigin::Own, block_4.clone()).await.unwrap();
+
+	// Import block 2 as best on the fork.
+	let mut block_builder = client.new_block_at(block_1_hash, Default::default(), false).unwrap();
+	// This push is required as otherwise block 3 has the same hash as block 2 and won't get
+	// imported
+	block_builder
+		.push_transfer(Transfer {
+			from: AccountKeyring::Alice.into(),
+			to: AccountKeyring::Ferdie.into(),
+			amount: 41,
+			nonce: 0,
+		})
+		.unwrap();
+	let block_2 = block_builder.build().unwrap().block;
+	let block_2_hash = block_2.header.hash();
+	client.import_as_best(BlockOrigin::Own, block_2.clone()).await.unwrap();
+
+	// Check block 1.
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::NewBlock(NewBlock {
+		block_hash: format!("{:?}", block_1_hash),
+		parent_block_hash: format!("{:?}", finalized_hash),
+		new_runtime: None,
+		with_runtime: false,
+		header: None,
+		extrinsics_count: None,
+	});
+	assert_eq!(event, expected);
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
+		best_block_hash: format!("{:?}", block_1_hash),
+	});
+	assert_eq!(event, expected);
+
+	// Check block 3.
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::NewBlock(NewBlock {
+		block_hash: format!("{:?}", block_3_hash),
+		parent_block_hash: format!("{:?}", block_1_hash),
+		new_runtime: None,
+		with_runtime: false,
+		header: None,
+		extrinsics_count: None,
+	});
+	assert_eq!(event, expected);
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
+		best_block_hash: format!("{:?}", block_3_hash),
+	});
+	assert_eq!(event, expected);
+
+	// Check block 4.
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::NewBlock(NewBlock {
+		block_hash: format!("{:?}", block_4_hash),
+		parent_block_hash: format!("{:?}", block_3_hash),
+		new_runtime: None,
+		with_runtime: false,
+		header: None,
+		extrinsics_count: None,
+	});
+	assert_eq!(event, expected);
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
+		best_block_hash: format!("{:?}", block_4_hash),
+	});
+	assert_eq!(event, expected);
+
+	// Check block 2, that we imported as custom best.
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::NewBlock(NewBlock {
+		block_hash: format!("{:?}", block_2_hash),
+		parent_block_hash: format!("{:?}", block_1_hash),
+		new_runtime: None,
+		with_runtime: false,
+		header: None,
+		extrinsics_count: None,
+	});
+	assert_eq!(event, expected);
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
+		best_block_hash: format!("{:?}", block_2_hash),
+	});
+	assert_eq!(event, expected);
+
+	// Finalize the block 4 from the fork.
+	client.finalize_block(block_4_hash, None).unwrap();
+
+	// Expect to report the best block changed before the finalized event.
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
+		best_block_hash: format!("{:?}", block_4_hash),
+	});
+	assert_eq!(event, expected);
+
+	// Block 2 must be reported as pruned, even if it was the previous best.
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::Finalized(Finalized {
+		finalized_block_hashes: vec![
+			format!("{:?}", block_1_hash),
+			format!("{:?}", block_3_hash),
+			format!("{:?}", block_4_hash),
+		],
+		pruned_block_hashes: vec![format!("{:?}", block_2_hash)],
+	});
+	assert_eq!(event, expected);
+
+	// Pruned hash can be unpinned.
+	let sub_id = sub.subscription_id();
+	let sub_id = serde_json::to_string(&sub_id).unwrap();
+	let hash = format!("{:?}", block_2_hash);
+	let _res: () = api.call("chainHead_unstable_unpin", [&sub_id, &hash]).await.unwrap();
+}
+
+#[tokio::test]
+async fn follow_forks_pruned_block() {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let mut client = Arc::new(builder.build());
+
+	let api = ChainHead::new(
+		client.clone(),
+		backend,
+		Arc::new(TaskExecutor::default()),
+		CHAIN_GENESIS,
+		ChainHeadConfig {
+			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
+			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
+			subscription_max_ongoing_operations: MAX_OPERATIONS,
+			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			..Default::default()
+		},
+	)
+	.into_rpc();
+
+	// Block tree before the subscription:
+	//
+	// finalized -> block 1 -> block 2 -> block 3
+	//                                        ^^^ finalized
+	//           -> block 1 -> block 4 -> block 5
+	//
+
+	let block_1 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	client.import(BlockOrigin::Own, block_1.clone()).await.unwrap();
+
+	let block_2 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	client.import(BlockOrigin::Own, block_2.clone()).await.unwrap();
+
+	let block_3 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_3_hash = block_3.header.hash();
+	client.import(BlockOrigin::Own, block_3.clone()).await.unwrap();
+
+	// Block 4 with parent Block 1 is not the best imported.
+	let mut block_builder =
+		client.new_block_at(block_1.header.hash(), Default::default(), false).unwrap();
+	// This push is required as otherwise block 4 has the same hash as block 2 and won't get
+	// imported
+	block_builder
+		.push_transfer(Transfer {
+			from: AccountKeyring::Alice.into(),
+			to: AccountKeyring::Ferdie.into(),
+			amount: 41,
+			nonce: 0,
+		})
+		.unwrap();
+	let block_4 = block_builder.build().unwrap().block;
+	client.import(BlockOrigin::Own, block_4.clone()).await.unwrap();
+
+	let mut block_builder =
+		client.new_block_at(block_4.header.hash(), Default::default(), false).unwrap();
+	block_builder
+		.push_transfer(Transfer {
+			from: AccountKeyring::Bob.into(),
+			to: AccountKeyring::Ferdie.into(),
+			amount: 41,
+			nonce: 0,
+		})
+		.unwrap();
+	let block_5 = block_builder.build().unwrap().block;
+	client.import(BlockOrigin::Own, block_5.clone()).await.unwrap();
+
+	// Block 4 and 5 are not pruned, pruning happens at height (N - 1).
+	client.finalize_block(block_3_hash, None).unwrap();
+
+	let mut sub = api.subscribe("chainHead_unstable_follow", [false]).await.unwrap();
+
+	// Initialized must always be reported first.
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::Initialized(Initialized {
+		finalized_block_hashes: vec![format!("{:?}", block_3_hash)],
+		finalized_block_runtime: None,
+		with_runtime: false,
+		resumption_token: None,
+	});
+	assert_eq!(event, expected);
+
+	// Block tree:
+	//
+	// finalized -> block 1 -> block 2 -> block 3 -> block 6
+	//                                                  ^^^ finalized
+	//           -> block 1 -> block 4 -> block 5
+	//
+	// Mark block 6 as finalized to force block 4 and 5 to get pruned.
+
+	let block_6 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_6_hash = block_6.header.hash();
+	client.import(BlockOrigin::Own, block_6.clone()).await.unwrap();
+
+	client.finalize_block(block_6_hash, None).unwrap();
+
+	// Check block 6.
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::NewBlock(NewBlock {
+		block_hash: format!("{:?}", block_6_hash),
+		parent_block_hash: format!("{:?}", block_3_hash),
+		new_runtime: None,
+		with_runtime: false,
+		header: None,
+		extrinsics_count: None,
+	});
+	assert_eq!(event, expected);
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
+		best_block_hash: format!("{:?}", block_6_hash),
+	});
+	assert_eq!(event, expected);
+
+	// Block 4 and 5 must not be reported as pruned.
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::Finalized(Finalized {
+		finalized_block_hashes: vec![format!("{:?}", block_6_hash)],
+		pruned_block_hashes: vec![],
+	});
+	assert_eq!(event, expected);
+}
+
+#[tokio::test]
+async fn retracted_pinned_block_operable() {
+	// The state of the retracted forks is discarded on finalization, unless pinned.
+	let backend = Arc::new(Backend::new_test(1, 32));
+	let mut client = Arc::new(TestClientBuilder::with_backend(backend.clone()).build());
+
+	let api = ChainHead::new(
+		client.clone(),
+		backend,
+		Arc::new(TaskExecutor::default()),
+		CHAIN_GENESIS,
+		ChainHeadConfig {
+			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
+			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
+			subscription_max_ongoing_operations: MAX_OPERATIONS,
+			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			..Default::default()
+		},
+	)
+	.into_rpc();
+
+	let mut sub = api.subscribe("chainHead_unstable_follow", [false]).await.unwrap();
+	let sub_id = sub.subscription_id();
+	let sub_id = serde_json::to_string(&sub_id).unwrap();
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::Initialized(_)
+	);
+
+	// Block tree:
+	//
+	// finalized -> block 1
+	//           -> block 2 -> block 3
+	//                           ^^^ finalized
+	//
+	// Block 1 is the best block until block 3 is imported.
+	let genesis_hash = client.genesis_hash();
+	let mut block_builder = client.new_block_at(genesis_hash, Default::default(), false).unwrap();
+	block_builder
+		.push_transfer(Transfer {
+			from: AccountKeyring::Alice.into(),
+			to: AccountKeyring::Ferdie.into(),
+			amount: 41,
+			nonce: 0,
+		})
+		.unwrap();
+	block_builder.push_storage_change(KEY.to_vec(), Some(VALUE.to_vec())).unwrap();
+	let block_1 = block_builder.build().unwrap().block;
+	let block_1_hash = block_1.header.hash();
+	client.import(BlockOrigin::Own, block_1.clone()).await.unwrap();
+
+	let block_2 = client.new_block_at(genesis_hash, Default::default(), false).unwrap();
+	let block_2 = block_2.build().unwrap().block;
+	let block_2_hash = block_2.header.hash();
+	client.import(BlockOrigin::Own, block_2.clone()).await.unwrap();
+
+	let block_3 = client.new_block_at(block_2_hash, Default::default(), false).unwrap();
+	let block_3 = block_3.build().unwrap().block;
+	let block_3_hash = block_3.header.hash();
+	client.import(BlockOrigin::Own, block_3.clone()).await.unwrap();
+
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(block) if block.block_hash == format!("{:?}", block_1_hash)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::BestBlockChanged(best) if best.best_block_hash == format!("{:?}", block_1_hash)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(block) if block.block_hash == format!("{:?}", block_2_hash)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(block) if block.block_hash == format!("{:?}", block_3_hash)
+	);
+	// Block 1 is retracted from the best chain.
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::BestBlockChanged(best) if best.best_block_hash == format!("{:?}", block_3_hash)
+	);
+
+	// Block 1 is pruned from the database, but remains pinned by the subscription.
+	client.finalize_block(block_3_hash, None).unwrap();
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::Finalized(Finalized {
+		finalized_block_hashes: vec![format!("{:?}", block_2_hash), format!("{:?}", block_3_hash)],
+		pruned_block_hashes: vec![format!("{:?}", block_1_hash)],
+	});
+	assert_eq!(event, expected);
+
+	let block_hash = format!("{:?}", block_1_hash);
+
+	// The body of the retracted block.
+	let response: MethodResponse =
+		api.call("chainHead_unstable_body", [&sub_id, &block_hash]).await.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+	let expected_txs =
+		block_1.extrinsics.iter().map(|tx| hex_string(&tx.encode())).collect::<Vec<_>>();
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut sub).await,
+			FollowEvent::OperationBodyDone(done) if done.operation_id == operation_id && done.value == expected_txs
+	);
+
+	// The storage of the retracted block.
+	let key = hex_string(&KEY);
+	let response: MethodResponse = api
+		.call(
+			"chainHead_unstable_storage",
+			rpc_params![
+				&sub_id,
+				&block_hash,
+				vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Value }]
+			],
+		)
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+	let expected_value = hex_string(&VALUE);
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut sub).await,
+			FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
+				res.items.len() == 1 &&
+				res.items[0].key == key && res.items[0].result == StorageResultType::Value(expected_value)
+	);
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut sub).await,
+			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+	);
+
+	// The runtime call executed on the state of the retracted block.
+	let alice_id = AccountKeyring::Alice.to_account_id();
+	let call_parameters = hex_string(&alice_id.encode());
+	let response: MethodResponse = api
+		.call(
+			"chainHead_unstable_call",
+			[&sub_id, &block_hash, "AccountNonceApi_account_nonce", &call_parameters],
+		)
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+	// The transfer of block 1 increased the nonce of Alice.
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut sub).await,
+			FollowEvent::OperationCallDone(done) if done.operation_id == operation_id && done.output == "0x0100000000000000"
+	);
+}
+
+#[tokio::test]
+async fn follow_fork_filter() {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let mut client = Arc::new(builder.build());
+
+	let api = ChainHead::new(
+		client.clone(),
+		backend,
+		Arc::new(TaskExecutor::default()),
+		CHAIN_GENESIS,
+		ChainHeadConfig {
+			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
+			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
+			subscription_max_ongoing_operations: MAX_OPERATIONS,
+			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			..Default::default()
+		},
+	)
+	.into_rpc();
+
+	let mut sub = api.subscribe("chainHead_unstable_follow", [false]).await.unwrap();
+	let sub_id = sub.subscription_id();
+	let sub_id = serde_json::to_string(&sub_id).unwrap();
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::Initialized(_)
+	);
+
+	// Block tree:
+	//
+	// finalized -> block 1 -> block 3 -> block 5 -> block 6
+	//           -> block 2 -> block 4
+	let genesis_hash = client.genesis_hash();
+	let block_1 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_1_hash = block_1.header.hash();
+	client.import(BlockOrigin::Own, block_1.clone()).await.unwrap();
+
+	let mut block_builder = client.new_block_at(genesis_hash, Default::default(), false).unwrap();
+	// This push is required as otherwise block 2 has the same hash as block 1.
+	block_builder
+		.push_transfer(Transfer {
+			from: AccountKeyring::Alice.into(),
+			to: AccountKeyring::Ferdie.into(),
+			amount: 41,
+			nonce: 0,
+		})
+		.unwrap();
+	let block_2 = block_builder.build().unwrap().block;
+	let block_2_hash = block_2.header.hash();
+	client.import(BlockOrigin::Own, block_2.clone()).await.unwrap();
+
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(block) if block.block_hash == format!("{:?}", block_1_hash)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(block) if block.block_hash == format!("{:?}", block_2_hash)
+	);
+
+	// The block must be pinned by the subscription.
+	let err = api
+		.call::<_, ()>(
+			"chainHead_unstable_setForkFilter",
+			rpc_params![&sub_id, hex_string(&INVALID_HASH)],
+		)
+		.await
+		.unwrap_err();
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == 2001 && err.message() == "Invalid block hash"
+	);
+
+	// Report only the descendants of block 1.
+	let _: () = api
+		.call(
+			"chainHead_unstable_setForkFilter",
+			rpc_params![&sub_id, format!("{:?}", block_1_hash)],
+		)
+		.await
+		.unwrap();
+
+	let block_3 = client.new_block_at(block_1_hash, Default::default(), false).unwrap();
+	let block_3 = block_3.build().unwrap().block;
+	let block_3_hash = block_3.header.hash();
+	client.import(BlockOrigin::Own, block_3.clone()).await.unwrap();
+
+	let block_4 = client.new_block_at(block_2_hash, Default::default(), false).unwrap();
+	let block_4 = block_4.build().unwrap().block;
+	let block_4_hash = block_4.header.hash();
+	client.import(BlockOrigin::Own, block_4.clone()).await.unwrap();
+
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(block) if block.block_hash == format!("{:?}", block_3_hash)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
+	// Block 4 is not part of the selected fork.
+	does_not_produce_event::<FollowEvent<String>>(
+		&mut sub,
+		std::time::Duration::from_secs(DOES_NOT_PRODUCE_EVENTS_SECONDS),
+	)
+	.await;
+
+	let block_5 = client.new_block_at(block_3_hash, Default::default(), false).unwrap();
+	let block_5 = block_5.build().unwrap().block;
+	let block_5_hash = block_5.header.hash();
+	client.import(BlockOrigin::Own, block_5.clone()).await.unwrap();
+
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(block) if block.block_hash == format!("{:?}", block_5_hash)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
+
+	// Remove the filter, the omitted block 4 is reported before block 6.
+	let _: () = api
+		.call("chainHead_unstable_setForkFilter", rpc_params![&sub_id])
+		.await
+		.unwrap();
+
+	let block_6 = client.new_block_at(block_5_hash, Default::default(), false).unwrap();
+	let block_6 = block_6.build().unwrap().block;
+	let block_6_hash = block_6.header.hash();
+	client.import(BlockOrigin::Own, block_6.clone()).await.unwrap();
+
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::NewBlock(NewBlock {
+		block_hash: format!("{:?}", block_4_hash),
+		parent_block_hash: format!("{:?}", block_2_hash),
+		new_runtime: None,
+		with_runtime: false,
+		header: None,
+		extrinsics_count: None,
+	});
+	assert_eq!(event, expected);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(block) if block.block_hash == format!("{:?}", block_6_hash)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
+}
+
+#[tokio::test]
+async fn follow_report_multiple_pruned_block() {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let mut client = Arc::new(builder.build());
+
+	let api = ChainHead::new(
+		client.clone(),
+		backend,
+		Arc::new(TaskExecutor::default()),
+		CHAIN_GENESIS,
+		ChainHeadConfig {
+			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
+			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
+			subscription_max_ongoing_operations: MAX_OPERATIONS,
+			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			..Default::default()
+		},
+	)
+	.into_rpc();
+
+	// Block tree:
+	//
+	// finalized -> block 1 -> block 2 -> block 3
+	//                                        ^^^ finalized after subscription
+	//           -> block 1 -> block 4 -> block 5
+
+	let finalized_hash = client.info().finalized_hash;
+
+	let block_1 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_1_hash = block_1.header.hash();
+	client.import(BlockOrigin::Own, block_1.clone()).await.unwrap();
+
+	let block_2 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_2_hash = block_2.header.hash();
+	client.import(BlockOrigin::Own, block_2.clone()).await.unwrap();
+
+	let block_3 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_3_hash = block_3.header.hash();
+	client.import(BlockOrigin::Own, block_3.clone()).await.unwrap();
+
+	// Block 4 with parent Block 1 is not the best imported.
+	let mut block_builder =
+		client.new_block_at(block_1.header.hash(), Default::default(), false).unwrap();
+	// This push is required as otherwise block 4 has the same hash as block 2 and won't get
+	// imported
+	block_builder
+		.push_transfer(Transfer {
+			from: AccountKeyring::Alice.into(),
+			to: AccountKeyring::Ferdie.into(),
+			amount: 41,
+			nonce: 0,
+		})
+		.unwrap();
+	let block_4 = block_builder.build().unwrap().block;
+	let block_4_hash = block_4.header.hash();
+	client.import(BlockOrigin::Own, block_4.clone()).await.unwrap();
+
+	let mut block_builder =
+		client.new_block_at(block_4.header.hash(), Default::default(), false).unwrap();
+	block_builder
+		.push_transfer(Transfer {
+			from: AccountKeyring::Bob.into(),
+			to: AccountKeyring::Ferdie.into(),
+			amount: 41,
+			nonce: 0,
+		})
+		.unwrap();
+	let block_5 = block_builder.build().unwrap().block;
+	let block_5_hash = block_5.header.hash();
+	client.import(BlockOrigin::Own, block_5.clone()).await.unwrap();
+	let mut sub = api.subscribe("chainHead_unstable_follow", [false]).await.unwrap();
+
+	// Initialized must always be reported first.
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::Initialized(Initialized {
+		finalized_block_hashes: vec![format!("{:?}", finalized_hash)],
+		finalized_block_runtime: None,
+		with_runtime: false,
+		resumption_token: None,
+	});
+	assert_eq!(event, expected);
+
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::NewBlock(NewBlock {
+		block_hash: format!("{:?}", block_1_hash),
+		parent_block_hash: format!("{:?}", finalized_hash),
+		new_runtime: None,
+		with_runtime: false,
+		header: None,
+		extrinsics_count: None,
+	});
+	assert_eq!(event, expected);
+
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::NewBlock(NewBlock {
+		block_hash: format!("{:?}", block_2_hash),
+		parent_block_hash: format!("{:?}", block_1_hash),
+		new_runtime: None,
+		with_runtime: false,
+		header: None,
+		extrinsics_count: None,
+	});
+	assert_eq!(event, expected);
+
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::NewBlock(NewBlock {
+		block_hash: format!("{:?}", block_3_hash),
+		parent_block_hash: format!("{:?}", block_2_hash),
+		new_runtime: None,
+		with_runtime: false,
+		header: None,
+		extrinsics_count: None,
+	});
+	assert_eq!(event, expected);
+
+	// The fork must also be reported.
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::NewBlock(NewBlock {
+		block_hash: format!("{:?}", block_4_hash),
+		parent_block_hash: format!("{:?}", block_1_hash),
+		new_runtime: None,
+		with_runtime: false,
+		header: None,
+		extrinsics_count: None,
+	});
+	assert_eq!(event, expected);
+
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::NewBlock(NewBlock {
+		block_hash: format!("{:?}", block_5_hash),
+		parent_block_hash: format!("{:?}", block_4_hash),
+		new_runtime: None,
+		with_runtime: false,
+		header: None,
+		extrinsics_count: None,
+	});
+	assert_eq!(event, expected);
+
+	// The best block of the chain must also be reported.
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
+		best_block_hash: format!("{:?}", block_3_hash),
+	});
+	assert_eq!(event, expected);
+
+	// Block 4 and 5 are not pruned, pruning happens at height (N - 1).
+	client.finalize_block(block_3_hash, None).unwrap();
+
+	// Finalizing block 3 directly will also result in block 1 and 2 being finalized.
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::Finalized(Finalized {
+		finalized_block_hashes: vec![
+			format!("{:?}", block_1_hash),
+			format!("{:?}", block_2_hash),
+			format!("{:?}", block_3_hash),
+		],
+		pruned_block_hashes: vec![],
+	});
+	assert_eq!(event, expected);
+
+	// Block tree:
+	//
+	// finalized -> block 1 -> block 2 -> block 3 -> block 6
+	//                                                  ^^^ finalized
+	//           -> block 1 -> block 4 -> block 5
+	//
+	// Mark block 6 as finalized to force block 4 and 5 to get pruned.
+
+	let block_6 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_6_hash = block_6.header.hash();
+	client.import(BlockOrigin::Own, block_6.clone()).await.unwrap();
+
+	client.finalize_block(block_6_hash, None).unwrap();
+
+	// Check block 6.
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::NewBlock(NewBlock {
+		block_hash: format!("{:?}", block_6_hash),
+		parent_block_hash: format!("{:?}", block_3_hash),
+		new_runtime: None,
+		with_runtime: false,
+		header: None,
+		extrinsics_count: None,
+	});
+	assert_eq!(event, expected);
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
+		best_block_hash: format!("{:?}", block_6_hash),
+	});
+	assert_eq!(event, expected);
+
+	// Block 4 and 5 be reported as pruned, not just the stale head (block 5).
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::Finalized(Finalized {
+		finalized_block_hashes: vec![format!("{:?}", block_6_hash)],
+		pruned_block_hashes: vec![format!("{:?}", block_4_hash), format!("{:?}", block_5_hash)],
+	});
+	assert_eq!(event, expected);
+}
+
+#[tokio::test]
+async fn pin_block_references() {
+	// Manually construct an in-memory backend and client.
+	let backend = Arc::new(sc_client_api::in_mem::Backend::new());
+	let executor = substrate_test_runtime_client::new_native_or_wasm_executor();
+	let client_config = sc_service::ClientConfig::default();
+
+	let genesis_block_builder = sc_service::GenesisBlockBuilder::new(
+		&substrate_test_runtime_client::GenesisParameters::default().genesis_storage(),
+		!client_config.no_genesis,
+		backend.clone(),
+		executor.clone(),
+	)
+	.unwrap();
+
+	let mut client = Arc::new(
+		new_in_mem::<_, Block, _, RuntimeApi>(
+			backend.clone(),
+			executor,
+			genesis_block_builder,
+			None,
+			None,
+			Box::new(TaskExecutor::new()),
+			client_config,
+		)
+		.unwrap(),
+	);
+
+	let api = ChainHead::new(
+		client.clone(),
+		backend.clone(),
+		Arc::new(TaskExecutor::default()),
+		CHAIN_GENESIS,
+		ChainHeadConfig {
+			global_max_pinned_blocks: 3,
+			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
+			subscription_max_ongoing_operations: MAX_OPERATIONS,
+			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			..Default::default()
+		},
+	)
+	.into_rpc();
+
+	async fn wait_pinned_references<Block: BlockT>(
+		backend: &Arc<sc_client_api::in_mem::Backend<Block>>,
+		hash: &Block::Hash,
+		target: i64,
+	) {
+		// Retry for at most 2 minutes.
+		let mut retries = 120;
+		while backend.pin_refs(hash).unwrap() != target {
+			if retries == 0 {
+				panic!("Expected target={} pinned references for hash={:?}", target, hash);
+			}
+			retries -= 1;
+
+			tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+		}
+	}
+
+	let mut sub = api.subscribe("chainHead_unstable_follow", [false]).await.unwrap();
+	let sub_id = sub.subscription_id();
+	let sub_id = serde_json::to_string(&sub_id).unwrap();
+
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let hash = block.header.hash();
+	let block_hash = format!("{:?}", hash);
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	// Ensure the imported block is propagated for this subscription.
 	assert_matches!(
-			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::Initialized(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::BestBlockChanged(_)
 	);
-}
 
-#[tokio::test]
-async fn unique_operation_ids() {
-	let (mut _client, api, mut block_sub, sub_id, block) = setup_api().await;
-	let block_hash = format!("{:?}", block.header.hash());
+	// We need to wait a bit for:
+	// 1. `NewBlock` and `BestBlockChanged` notifications to propagate to the chainHead
+	// subscription. (pin_refs == 2)
+	// 2. The chainHead to call `pin_blocks` only once for the `NewBlock`
+	// notification (pin_refs == 3)
+	// 3. Both notifications to go out of scope (pin_refs ==  1 (total 3 - dropped 2)).
+	wait_pinned_references(&backend, &hash, 1).await;
 
-	let mut op_ids = HashSet::new();
+	// To not exceed the number of pinned blocks, we need to unpin before the next import.
+	let _res: () = api.call("chainHead_unstable_unpin", [&sub_id, &block_hash]).await.unwrap();
 
-	// Ensure that operation IDs are unique for multiple method calls.
-	for _ in 0..5 {
-		// Valid `chainHead_unstable_body` call.
-		let response: MethodResponse =
-			api.call("chainHead_unstable_body", [&sub_id, &block_hash]).await.unwrap();
-		let operation_id = match response {
-			MethodResponse::Started(started) => started.operation_id,
-			MethodResponse::LimitReached => panic!("Expected started response"),
-		};
-		assert_matches!(
-				get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-				FollowEvent::OperationBodyDone(done) if done.operation_id == operation_id && done.value.is_empty()
-		);
-		// Ensure uniqueness.
-		assert!(op_ids.insert(operation_id));
+	// Make sure unpin clears out the reference.
+	let refs = backend.pin_refs(&hash).unwrap();
+	assert_eq!(refs, 0);
 
-		// Valid `chainHead_unstable_storage` call.
-		let key = hex_string(&KEY);
-		let response: MethodResponse = api
-			.call(
-				"chainHead_unstable_storage",
-				rpc_params![
-					&sub_id,
-					&block_hash,
-					vec![StorageQuery { key: key.clone(), query_type: StorageQueryType::Value }]
-				],
-			)
-			.await
-			.unwrap();
-		let operation_id = match response {
-			MethodResponse::Started(started) => started.operation_id,
-			MethodResponse::LimitReached => panic!("Expected started response"),
-		};
-		// The `Done` event is generated directly since the key does not have any value associated.
+	// Add another 2 blocks and make sure we drop the subscription with the blocks pinned.
+	let mut hashes = Vec::new();
+	for _ in 0..2 {
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		let hash = block.header.hash();
+		client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+		// Ensure the imported block is propagated for this subscription.
 		assert_matches!(
-				get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-				FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+			get_next_event::<FollowEvent<String>>(&mut sub).await,
+			FollowEvent::NewBlock(_)
 		);
-		// Ensure uniqueness.
-		assert!(op_ids.insert(operation_id));
-
-		// Valid `chainHead_unstable_call` call.
-		let alice_id = AccountKeyring::Alice.to_account_id();
-		let call_parameters = hex_string(&alice_id.encode());
-		let response: MethodResponse = api
-			.call(
-				"chainHead_unstable_call",
-				[&sub_id, &block_hash, "AccountNonceApi_account_nonce", &call_parameters],
-			)
-			.await
-			.unwrap();
-		let operation_id = match response {
-			MethodResponse::Started(started) => started.operation_id,
-			MethodResponse::LimitReached => panic!("Expected started response"),
-		};
-		// Response propagated to `chainHead_follow`.
 		assert_matches!(
-				get_next_event::<FollowEvent<String>>(&mut block_sub).await,
-				FollowEvent::OperationCallDone(done) if done.operation_id == operation_id && done.output == "0x0000000000000000"
+			get_next_event::<FollowEvent<String>>(&mut sub).await,
+			FollowEvent::BestBlockChanged(_)
 		);
-		// Ensure uniqueness.
-		assert!(op_ids.insert(operation_id));
+
+		hashes.push(hash);
+	}
+
+	// Make sure the pin was propagated.
+	for hash in &hashes {
+		wait_pinned_references(&backend, hash, 1).await;
+	}
+
+	// Drop the subscription and expect the pinned blocks to be released.
+	drop(sub);
+	// The `chainHead` detects the subscription was terminated when it tries
+	// to send another block.
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	for hash in &hashes {
+		wait_pinned_references(&backend, &hash, 0).await;
 	}
 }
 
 #[tokio::test]
-async fn separate_operation_ids_for_subscriptions() {
+async fn follow_finalized_before_new_block() {
 	let builder = TestClientBuilder::new();
 	let backend = builder.backend();
 	let mut client = Arc::new(builder.build());
 
+	let client_mock = Arc::new(ChainHeadMockClient::new(client.clone()));
+
 	let api = ChainHead::new(
-		client.clone(),
+		client_mock.clone(),
 		backend,
 		Arc::new(TaskExecutor::default()),
 		CHAIN_GENESIS,
@@ -1162,76 +3667,186 @@ async fn separate_operation_ids_for_subscriptions() {
 			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
 			subscription_max_ongoing_operations: MAX_OPERATIONS,
 			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			..Default::default()
 		},
 	)
 	.into_rpc();
 
-	// Create two separate subscriptions.
-	let mut sub_first = api.subscribe("chainHead_unstable_follow", [true]).await.unwrap();
-	let sub_id_first = sub_first.subscription_id();
-	let sub_id_first = serde_json::to_string(&sub_id_first).unwrap();
+	// Make sure the block is imported for it to be pinned.
+	let block_1 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_1_hash = block_1.header.hash();
+	client.import(BlockOrigin::Own, block_1.clone()).await.unwrap();
 
-	let mut sub_second = api.subscribe("chainHead_unstable_follow", [true]).await.unwrap();
-	let sub_id_second = sub_second.subscription_id();
-	let sub_id_second = serde_json::to_string(&sub_id_second).unwrap();
+	let mut sub = api.subscribe("chainHead_unstable_follow", [false]).await.unwrap();
 
-	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
-	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
-	let block_hash = format!("{:?}", block.header.hash());
+	// Trigger the `FinalizedNotification` for block 1 before the `BlockImportNotification`, and
+	// expect for the `chainHead` to generate `NewBlock`, `BestBlock` and `Finalized` events.
 
-	// Ensure the imported block is propagated and pinned.
+	// Trigger the Finalized notification before the NewBlock one.
+	run_with_timeout(client_mock.trigger_finality_stream(block_1.header.clone())).await;
+
+	// Initialized must always be reported first.
+	let finalized_hash = client.info().finalized_hash;
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::Initialized(Initialized {
+		finalized_block_hashes: vec![format!("{:?}", finalized_hash)],
+		finalized_block_runtime: None,
+		with_runtime: false,
+		resumption_token: None,
+	});
+	assert_eq!(event, expected);
+
+	// Block 1 must be reported because we triggered the finalized notification.
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::NewBlock(NewBlock {
+		block_hash: format!("{:?}", block_1_hash),
+		parent_block_hash: format!("{:?}", finalized_hash),
+		new_runtime: None,
+		with_runtime: false,
+		header: None,
+		extrinsics_count: None,
+	});
+	assert_eq!(event, expected);
+
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
+		best_block_hash: format!("{:?}", block_1_hash),
+	});
+	assert_eq!(event, expected);
+
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::Finalized(Finalized {
+		finalized_block_hashes: vec![format!("{:?}", block_1_hash)],
+		pruned_block_hashes: vec![],
+	});
+	assert_eq!(event, expected);
+
+	let block_2 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_2_hash = block_2.header.hash();
+	client.import(BlockOrigin::Own, block_2.clone()).await.unwrap();
+
+	// Triggering the `BlockImportNotification` notification for block 1 should have no effect
+	// on the notification because the events were handled by the `FinalizedNotification`.
+	// Also trigger the `BlockImportNotification` notification for block 2 to ensure
+	// `NewBlock and `BestBlock` events are generated.
+
+	// Trigger NewBlock notification for block 1 and block 2.
+	run_with_timeout(client_mock.trigger_import_stream(block_1.header)).await;
+	run_with_timeout(client_mock.trigger_import_stream(block_2.header)).await;
+
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::NewBlock(NewBlock {
+		block_hash: format!("{:?}", block_2_hash),
+		parent_block_hash: format!("{:?}", block_1_hash),
+		new_runtime: None,
+		with_runtime: false,
+		header: None,
+		extrinsics_count: None,
+	});
+	assert_eq!(event, expected);
+
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
+		best_block_hash: format!("{:?}", block_2_hash),
+	});
+	assert_eq!(event, expected);
+}
+
+#[tokio::test]
+async fn follow_holds_finalized_until_pruned_block_announced() {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let mut client = Arc::new(builder.build());
+
+	let client_mock = Arc::new(ChainHeadMockClient::new(client.clone()));
+
+	let api = ChainHead::new(
+		client_mock.clone(),
+		backend,
+		Arc::new(TaskExecutor::default()),
+		CHAIN_GENESIS,
+		ChainHeadConfig {
+			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
+			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
+			subscription_max_ongoing_operations: MAX_OPERATIONS,
+			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			..Default::default()
+		},
+	)
+	.into_rpc();
+
+	let mut sub = api.subscribe("chainHead_unstable_follow", [false]).await.unwrap();
+	let genesis_hash = client.info().finalized_hash;
 	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut sub_first).await,
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
 		FollowEvent::Initialized(_)
 	);
-	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut sub_first).await,
-		FollowEvent::NewBlock(_)
-	);
-	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut sub_first).await,
-		FollowEvent::BestBlockChanged(_)
-	);
 
+	// Import block 1 and a fork of block 1 without triggering the notifications.
+	let block_1 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_1_hash = block_1.header.hash();
+	client.import(BlockOrigin::Own, block_1.clone()).await.unwrap();
+
+	let mut block_builder = client.new_block_at(genesis_hash, Default::default(), false).unwrap();
+	// This push is required as otherwise the fork has the same hash as block 1.
+	block_builder
+		.push_transfer(Transfer {
+			from: AccountKeyring::Alice.into(),
+			to: AccountKeyring::Ferdie.into(),
+			amount: 41,
+			nonce: 0,
+		})
+		.unwrap();
+	let fork_1 = block_builder.build().unwrap().block;
+	let fork_1_hash = fork_1.header.hash();
+	client.import(BlockOrigin::Own, fork_1.clone()).await.unwrap();
+
+	// The finality of block 1 that prunes the fork is notified before the imports.
+	run_with_timeout(
+		client_mock
+			.trigger_finality_stream_with_stale_heads(block_1.header.clone(), vec![fork_1_hash]),
+	)
+	.await;
+	run_with_timeout(client_mock.trigger_import_stream(block_1.header)).await;
+	run_with_timeout(client_mock.trigger_import_stream(fork_1.header)).await;
+
+	// The pruned fork is announced before the `Finalized` event.
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
 	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut sub_second).await,
-		FollowEvent::Initialized(_)
-	);
-	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut sub_second).await,
-		FollowEvent::NewBlock(_)
+		event,
+		FollowEvent::NewBlock(block) if block.block_hash == format!("{:?}", block_1_hash)
 	);
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	assert_matches!(event, FollowEvent::BestBlockChanged(_));
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
 	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut sub_second).await,
-		FollowEvent::BestBlockChanged(_)
+		event,
+		FollowEvent::NewBlock(block) if block.block_hash == format!("{:?}", fork_1_hash)
 	);
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	assert_matches!(event, FollowEvent::BestBlockChanged(_));
 
-	// Each `chainHead_follow` subscription receives a separate operation ID.
-	let response: MethodResponse =
-		api.call("chainHead_unstable_body", [&sub_id_first, &block_hash]).await.unwrap();
-	let operation_id: String = match response {
-		MethodResponse::Started(started) => started.operation_id,
-		MethodResponse::LimitReached => panic!("Expected started response"),
-	};
-	assert_eq!(operation_id, "0");
+	// The fork reported as best is pruned, block 1 is reported as best again.
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
+		best_block_hash: format!("{:?}", block_1_hash),
+	});
+	assert_eq!(event, expected);
 
-	let response: MethodResponse = api
-		.call("chainHead_unstable_body", [&sub_id_second, &block_hash])
-		.await
-		.unwrap();
-	let operation_id_second: String = match response {
-		MethodResponse::Started(started) => started.operation_id,
-		MethodResponse::LimitReached => panic!("Expected started response"),
-	};
-	// The second subscription does not increment the operation ID of the first one.
-	assert_eq!(operation_id_second, "0");
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::Finalized(Finalized {
+		finalized_block_hashes: vec![format!("{:?}", block_1_hash)],
+		pruned_block_hashes: vec![format!("{:?}", fork_1_hash)],
+	});
+	assert_eq!(event, expected);
 }
 
 #[tokio::test]
-async fn follow_generates_initial_blocks() {
+async fn follow_catch_up_after_major_sync() {
 	let builder = TestClientBuilder::new();
 	let backend = builder.backend();
 	let mut client = Arc::new(builder.build());
+	let sync_oracle = Arc::new(MockSyncOracle::default());
 
 	let api = ChainHead::new(
 		client.clone(),
@@ -1243,27 +3858,37 @@ async fn follow_generates_initial_blocks() {
 			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
 			subscription_max_ongoing_operations: MAX_OPERATIONS,
 			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			sync_oracle: Some(sync_oracle.clone()),
+			..Default::default()
 		},
 	)
 	.into_rpc();
 
-	let finalized_hash = client.info().finalized_hash;
+	let mut sub = api.subscribe("chainHead_unstable_follow", [false]).await.unwrap();
+	let genesis_hash = client.info().finalized_hash;
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::Initialized(_)
+	);
 
 	// Block tree:
-	// finalized -> block 1 -> block 2 -> block 4
-	//           -> block 1 -> block 3
+	//
+	// finalized -> block 1 -> block 2 -> block 3 -> block 4
+	//                                      ^^^ finalized during the major sync
+	//           -> fork 1
 	let block_1 = client.new_block(Default::default()).unwrap().build().unwrap().block;
-	let block_1_hash = block_1.header.hash();
 	client.import(BlockOrigin::Own, block_1.clone()).await.unwrap();
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
 
-	let block_2 = client.new_block(Default::default()).unwrap().build().unwrap().block;
-	let block_2_hash = block_2.header.hash();
-	client.import(BlockOrigin::Own, block_2.clone()).await.unwrap();
-
-	let mut block_builder =
-		client.new_block_at(block_1.header.hash(), Default::default(), false).unwrap();
-	// This push is required as otherwise block 3 has the same hash as block 2 and won't get
-	// imported
+	let mut block_builder = client.new_block_at(genesis_hash, Default::default(), false).unwrap();
+	// This push is required as otherwise the fork has the same hash as block 1.
 	block_builder
 		.push_transfer(Transfer {
 			from: AccountKeyring::Alice.into(),
@@ -1272,114 +3897,197 @@ async fn follow_generates_initial_blocks() {
 			nonce: 0,
 		})
 		.unwrap();
-	let block_3 = block_builder.build().unwrap().block;
+	let fork_1 = block_builder.build().unwrap().block;
+	let fork_1_hash = fork_1.header.hash();
+	client.import(BlockOrigin::Own, fork_1.clone()).await.unwrap();
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(_)
+	);
+
+	// The blocks imported and finalized during the major sync are not reported.
+	sync_oracle.set_major_syncing(true);
+	let block_2 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	client.import(BlockOrigin::Own, block_2.clone()).await.unwrap();
+	let block_3 = client.new_block(Default::default()).unwrap().build().unwrap().block;
 	let block_3_hash = block_3.header.hash();
 	client.import(BlockOrigin::Own, block_3.clone()).await.unwrap();
+	client.finalize_block(block_3_hash, None).unwrap();
+	does_not_produce_event::<FollowEvent<String>>(
+		&mut sub,
+		std::time::Duration::from_secs(DOES_NOT_PRODUCE_EVENTS_SECONDS),
+	)
+	.await;
 
-	let mut sub = api.subscribe("chainHead_unstable_follow", [false]).await.unwrap();
+	// The first block after the major sync reports the summary.
+	sync_oracle.set_major_syncing(false);
+	let block_4 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_4_hash = block_4.header.hash();
+	client.import(BlockOrigin::Own, block_4.clone()).await.unwrap();
 
-	// Initialized must always be reported first.
 	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::Initialized(Initialized {
-		finalized_block_hash: format!("{:?}", finalized_hash),
+	let expected = FollowEvent::CatchUp(CatchUp {
+		finalized_block_hashes: vec![format!("{:?}", block_3_hash)],
 		finalized_block_runtime: None,
-		with_runtime: false,
-	});
-	assert_eq!(event, expected);
-
-	// Check block 1.
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::NewBlock(NewBlock {
-		block_hash: format!("{:?}", block_1_hash),
-		parent_block_hash: format!("{:?}", finalized_hash),
-		new_runtime: None,
-		with_runtime: false,
+		pruned_block_hashes: vec![format!("{:?}", fork_1_hash)],
 	});
 	assert_eq!(event, expected);
 
-	// Check block 2.
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::NewBlock(NewBlock {
-		block_hash: format!("{:?}", block_2_hash),
-		parent_block_hash: format!("{:?}", block_1_hash),
-		new_runtime: None,
-		with_runtime: false,
-	});
-	assert_eq!(event, expected);
-	// Check block 3.
 	let event: FollowEvent<String> = get_next_event(&mut sub).await;
 	let expected = FollowEvent::NewBlock(NewBlock {
-		block_hash: format!("{:?}", block_3_hash),
-		parent_block_hash: format!("{:?}", block_1_hash),
+		block_hash: format!("{:?}", block_4_hash),
+		parent_block_hash: format!("{:?}", block_3_hash),
 		new_runtime: None,
 		with_runtime: false,
+		header: None,
+		extrinsics_count: None,
 	});
 	assert_eq!(event, expected);
 
 	let event: FollowEvent<String> = get_next_event(&mut sub).await;
 	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
-		best_block_hash: format!("{:?}", block_2_hash),
+		best_block_hash: format!("{:?}", block_4_hash),
 	});
 	assert_eq!(event, expected);
 
-	// Import block 4.
-	let block_4 = client.new_block(Default::default()).unwrap().build().unwrap().block;
-	let block_4_hash = block_4.header.hash();
-	client.import(BlockOrigin::Own, block_4.clone()).await.unwrap();
+	// The block events are reported again.
+	let block_5 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_5_hash = block_5.header.hash();
+	client.import(BlockOrigin::Own, block_5.clone()).await.unwrap();
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(block) if block.block_hash == format!("{:?}", block_5_hash)
+	);
+}
 
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::NewBlock(NewBlock {
-		block_hash: format!("{:?}", block_4_hash),
-		parent_block_hash: format!("{:?}", block_2_hash),
-		new_runtime: None,
-		with_runtime: false,
-	});
-	assert_eq!(event, expected);
+#[tokio::test]
+async fn ensure_operation_limits_works() {
+	let child_info = ChildInfo::new_default(CHILD_STORAGE_KEY);
+	let builder = TestClientBuilder::new().add_extra_child_storage(
+		&child_info,
+		KEY.to_vec(),
+		CHILD_VALUE.to_vec(),
+	);
+	let backend = builder.backend();
+	let mut client = Arc::new(builder.build());
 
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
-		best_block_hash: format!("{:?}", block_4_hash),
-	});
-	assert_eq!(event, expected);
+	// Configure the chainHead with maximum 1 ongoing operations.
+	let api = ChainHead::new(
+		client.clone(),
+		backend,
+		Arc::new(TaskExecutor::default()),
+		CHAIN_GENESIS,
+		ChainHeadConfig {
+			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
+			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
+			subscription_max_ongoing_operations: 1,
+			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			..Default::default()
+		},
+	)
+	.into_rpc();
 
-	// Check the finalized event:
-	//  - blocks 1, 2, 4 from canonical chain are finalized
-	//  - block 3 from the fork is pruned
-	client.finalize_block(block_4_hash, None).unwrap();
+	let mut sub = api.subscribe("chainHead_unstable_follow", [true]).await.unwrap();
+	let sub_id = sub.subscription_id();
+	let sub_id = serde_json::to_string(&sub_id).unwrap();
 
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::Finalized(Finalized {
-		finalized_block_hashes: vec![
-			format!("{:?}", block_1_hash),
-			format!("{:?}", block_2_hash),
-			format!("{:?}", block_4_hash),
-		],
-		pruned_block_hashes: vec![format!("{:?}", block_3_hash)],
-	});
-	assert_eq!(event, expected);
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	// Ensure the imported block is propagated and pinned for this subscription.
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::Initialized(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
+
+	let block_hash = format!("{:?}", block.header.hash());
+	let key = hex_string(&KEY);
+
+	let items = vec![
+		StorageQuery { key: key.clone(), query_type: StorageQueryType::DescendantsHashes },
+		StorageQuery { key: key.clone(), query_type: StorageQueryType::DescendantsHashes },
+		StorageQuery { key: key.clone(), query_type: StorageQueryType::DescendantsValues },
+		StorageQuery { key: key.clone(), query_type: StorageQueryType::DescendantsValues },
+	];
+
+	let response: MethodResponse = api
+		.call("chainHead_unstable_storage", rpc_params![&sub_id, &block_hash, items])
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => {
+			// Check discarded items.
+			assert_eq!(started.discarded_items.unwrap(), 3);
+			started.operation_id
+		},
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+	// No value associated with the provided key.
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut sub).await,
+			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+	);
+
+	// The storage is finished and capactiy must be released.
+	let alice_id = AccountKeyring::Alice.to_account_id();
+	// Hex encoded scale encoded bytes representing the call parameters.
+	let call_parameters = hex_string(&alice_id.encode());
+	let response: MethodResponse = api
+		.call(
+			"chainHead_unstable_call",
+			[&sub_id, &block_hash, "AccountNonceApi_account_nonce", &call_parameters],
+		)
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+
+	// Response propagated to `chainHead_follow`.
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut sub).await,
+			FollowEvent::OperationCallDone(done) if done.operation_id == operation_id && done.output == "0x0000000000000000"
+	);
 }
 
 #[tokio::test]
-async fn follow_exceeding_pinned_blocks() {
+async fn follow_bandwidth_quota() {
 	let builder = TestClientBuilder::new();
 	let backend = builder.backend();
 	let mut client = Arc::new(builder.build());
 
+	// The output of a single call fits the quota.
 	let api = ChainHead::new(
 		client.clone(),
 		backend,
 		Arc::new(TaskExecutor::default()),
 		CHAIN_GENESIS,
 		ChainHeadConfig {
-			global_max_pinned_blocks: 2,
+			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
 			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
 			subscription_max_ongoing_operations: MAX_OPERATIONS,
 			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			subscription_bandwidth_quota: Some(BandwidthQuota {
+				max_bytes: 20,
+				period: Duration::from_secs(60 * 60),
+			}),
+			..Default::default()
 		},
 	)
 	.into_rpc();
 
 	let mut sub = api.subscribe("chainHead_unstable_follow", [false]).await.unwrap();
+	let sub_id = sub.subscription_id();
+	let sub_id = serde_json::to_string(&sub_id).unwrap();
 
 	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
 	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
@@ -1398,38 +4106,65 @@ async fn follow_exceeding_pinned_blocks() {
 		FollowEvent::BestBlockChanged(_)
 	);
 
-	// Block tree:
-	//   finalized_block -> block -> block2
-	// The first 2 blocks are pinned into the subscription, but the block2 will exceed the limit (2
-	// blocks).
-	let block2 = client.new_block(Default::default()).unwrap().build().unwrap().block;
-	client.import(BlockOrigin::Own, block2.clone()).await.unwrap();
+	let block_hash = format!("{:?}", block.header.hash());
+	let alice_id = AccountKeyring::Alice.to_account_id();
+	let call_parameters = hex_string(&alice_id.encode());
 
-	assert_matches!(get_next_event::<FollowEvent<String>>(&mut sub).await, FollowEvent::Stop);
+	for _ in 0..2 {
+		let response: MethodResponse = api
+			.call(
+				"chainHead_unstable_call",
+				[&sub_id, &block_hash, "AccountNonceApi_account_nonce", &call_parameters],
+			)
+			.await
+			.unwrap();
+		let operation_id = match response {
+			MethodResponse::Started(started) => started.operation_id,
+			MethodResponse::LimitReached => panic!("Expected started response"),
+		};
+		assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut sub).await,
+			FollowEvent::OperationCallDone(done) if done.operation_id == operation_id
+		);
+	}
 
-	// Subscription will not produce any more event for further blocks.
-	let block3 = client.new_block(Default::default()).unwrap().build().unwrap().block;
-	client.import(BlockOrigin::Own, block3.clone()).await.unwrap();
+	// The outputs of the calls exhausted the quota.
+	let response: MethodResponse = api
+		.call(
+			"chainHead_unstable_call",
+			[&sub_id, &block_hash, "AccountNonceApi_account_nonce", &call_parameters],
+		)
+		.await
+		.unwrap();
+	assert_matches!(response, MethodResponse::LimitReached);
 
-	assert!(sub.next::<FollowEvent<String>>().await.is_none());
+	let header: Option<String> =
+		api.call("chainHead_unstable_header", [&sub_id, &block_hash]).await.unwrap();
+	assert!(header.is_none());
 }
 
 #[tokio::test]
-async fn follow_with_unpin() {
+async fn continue_operation_bandwidth_quota() {
 	let builder = TestClientBuilder::new();
 	let backend = builder.backend();
 	let mut client = Arc::new(builder.build());
 
+	// The first item of the operation exhausts the quota.
 	let api = ChainHead::new(
 		client.clone(),
 		backend,
 		Arc::new(TaskExecutor::default()),
 		CHAIN_GENESIS,
 		ChainHeadConfig {
-			global_max_pinned_blocks: 2,
+			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
 			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
 			subscription_max_ongoing_operations: MAX_OPERATIONS,
-			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			operation_max_storage_items: 1,
+			subscription_bandwidth_quota: Some(BandwidthQuota {
+				max_bytes: 1,
+				period: Duration::from_secs(60 * 60),
+			}),
+			..Default::default()
 		},
 	)
 	.into_rpc();
@@ -1438,11 +4173,13 @@ async fn follow_with_unpin() {
 	let sub_id = sub.subscription_id();
 	let sub_id = serde_json::to_string(&sub_id).unwrap();
 
-	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder.push_storage_change(b":m".to_vec(), Some(b"a".to_vec())).unwrap();
+	builder.push_storage_change(b":mo".to_vec(), Some(b"ab".to_vec())).unwrap();
+	let block = builder.build().unwrap().block;
 	let block_hash = format!("{:?}", block.header.hash());
 	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
 
-	// Ensure the imported block is propagated and pinned for this subscription.
 	assert_matches!(
 		get_next_event::<FollowEvent<String>>(&mut sub).await,
 		FollowEvent::Initialized(_)
@@ -1456,216 +4193,50 @@ async fn follow_with_unpin() {
 		FollowEvent::BestBlockChanged(_)
 	);
 
-	// Unpin an invalid subscription ID must return Ok(()).
-	let invalid_hash = hex_string(&INVALID_HASH);
-	let _res: () = api
-		.call("chainHead_unstable_unpin", ["invalid_sub_id", &invalid_hash])
-		.await
-		.unwrap();
-
-	// Valid subscription with invalid block hash.
-	let invalid_hash = hex_string(&INVALID_HASH);
-	let err = api
-		.call::<_, serde_json::Value>("chainHead_unstable_unpin", [&sub_id, &invalid_hash])
-		.await
-		.unwrap_err();
-	assert_matches!(err,
-		Error::Call(CallError::Custom(ref err)) if err.code() == 2001 && err.message() == "Invalid block hash"
-	);
-
-	// To not exceed the number of pinned blocks, we need to unpin before the next import.
-	let _res: () = api.call("chainHead_unstable_unpin", [&sub_id, &block_hash]).await.unwrap();
-
-	// Block tree:
-	//   finalized_block -> block -> block2
-	//                      ^ has been unpinned
-	let block2 = client.new_block(Default::default()).unwrap().build().unwrap().block;
-	client.import(BlockOrigin::Own, block2.clone()).await.unwrap();
-
-	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut sub).await,
-		FollowEvent::NewBlock(_)
-	);
-
-	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut sub).await,
-		FollowEvent::BestBlockChanged(_)
-	);
-
-	let block3 = client.new_block(Default::default()).unwrap().build().unwrap().block;
-	client.import(BlockOrigin::Own, block3.clone()).await.unwrap();
-
-	assert_matches!(get_next_event::<FollowEvent<String>>(&mut sub).await, FollowEvent::Stop);
-	assert!(sub.next::<FollowEvent<String>>().await.is_none());
-}
-
-#[tokio::test]
-async fn follow_prune_best_block() {
-	let builder = TestClientBuilder::new();
-	let backend = builder.backend();
-	let mut client = Arc::new(builder.build());
-
-	let api = ChainHead::new(
-		client.clone(),
-		backend,
-		Arc::new(TaskExecutor::default()),
-		CHAIN_GENESIS,
-		ChainHeadConfig {
-			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
-			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
-			subscription_max_ongoing_operations: MAX_OPERATIONS,
-			operation_max_storage_items: MAX_PAGINATION_LIMIT,
-		},
-	)
-	.into_rpc();
-
-	let finalized_hash = client.info().finalized_hash;
-	let mut sub = api.subscribe("chainHead_unstable_follow", [false]).await.unwrap();
-
-	// Initialized must always be reported first.
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::Initialized(Initialized {
-		finalized_block_hash: format!("{:?}", finalized_hash),
-		finalized_block_runtime: None,
-		with_runtime: false,
-	});
-	assert_eq!(event, expected);
-
-	// Block tree:
-	//
-	// finalized -> block 1 -> block 2
-	//                         ^^^ best block reported
-	//
-	//           -> block 1 -> block 3 -> block 4
-	//                                    ^^^ finalized
-	//
-	// The block 4 is needed on the longest chain because we want the
-	// best block 2 to be reported as pruned. Pruning is happening at
-	// height (N - 1), where N is the finalized block number.
-
-	let block_1 = client.new_block(Default::default()).unwrap().build().unwrap().block;
-	let block_1_hash = block_1.header.hash();
-	client.import(BlockOrigin::Own, block_1.clone()).await.unwrap();
-
-	let block_3 = client.new_block(Default::default()).unwrap().build().unwrap().block;
-	let block_3_hash = block_3.header.hash();
-	client.import(BlockOrigin::Own, block_3.clone()).await.unwrap();
-
-	let block_4 = client.new_block(Default::default()).unwrap().build().unwrap().block;
-	let block_4_hash = block_4.header.hash();
-	client.import(BlockOrigin::Own, block_4.clone()).await.unwrap();
-
-	// Import block 2 as best on the fork.
-	let mut block_builder = client.new_block_at(block_1_hash, Default::default(), false).unwrap();
-	// This push is required as otherwise block 3 has the same hash as block 2 and won't get
-	// imported
-	block_builder
-		.push_transfer(Transfer {
-			from: AccountKeyring::Alice.into(),
-			to: AccountKeyring::Ferdie.into(),
-			amount: 41,
-			nonce: 0,
-		})
-		.unwrap();
-	let block_2 = block_builder.build().unwrap().block;
-	let block_2_hash = block_2.header.hash();
-	client.import_as_best(BlockOrigin::Own, block_2.clone()).await.unwrap();
-
-	// Check block 1.
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::NewBlock(NewBlock {
-		block_hash: format!("{:?}", block_1_hash),
-		parent_block_hash: format!("{:?}", finalized_hash),
-		new_runtime: None,
-		with_runtime: false,
-	});
-	assert_eq!(event, expected);
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
-		best_block_hash: format!("{:?}", block_1_hash),
-	});
-	assert_eq!(event, expected);
-
-	// Check block 3.
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::NewBlock(NewBlock {
-		block_hash: format!("{:?}", block_3_hash),
-		parent_block_hash: format!("{:?}", block_1_hash),
-		new_runtime: None,
-		with_runtime: false,
-	});
-	assert_eq!(event, expected);
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
-		best_block_hash: format!("{:?}", block_3_hash),
-	});
-	assert_eq!(event, expected);
-
-	// Check block 4.
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::NewBlock(NewBlock {
-		block_hash: format!("{:?}", block_4_hash),
-		parent_block_hash: format!("{:?}", block_3_hash),
-		new_runtime: None,
-		with_runtime: false,
-	});
-	assert_eq!(event, expected);
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
-		best_block_hash: format!("{:?}", block_4_hash),
-	});
-	assert_eq!(event, expected);
-
-	// Check block 2, that we imported as custom best.
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::NewBlock(NewBlock {
-		block_hash: format!("{:?}", block_2_hash),
-		parent_block_hash: format!("{:?}", block_1_hash),
-		new_runtime: None,
-		with_runtime: false,
-	});
-	assert_eq!(event, expected);
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
-		best_block_hash: format!("{:?}", block_2_hash),
-	});
-	assert_eq!(event, expected);
-
-	// Finalize the block 4 from the fork.
-	client.finalize_block(block_4_hash, None).unwrap();
-
-	// Expect to report the best block changed before the finalized event.
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
-		best_block_hash: format!("{:?}", block_4_hash),
-	});
-	assert_eq!(event, expected);
-
-	// Block 2 must be reported as pruned, even if it was the previous best.
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::Finalized(Finalized {
-		finalized_block_hashes: vec![
-			format!("{:?}", block_1_hash),
-			format!("{:?}", block_3_hash),
-			format!("{:?}", block_4_hash),
-		],
-		pruned_block_hashes: vec![format!("{:?}", block_2_hash)],
-	});
-	assert_eq!(event, expected);
+	let response: MethodResponse = api
+		.call(
+			"chainHead_unstable_storage",
+			rpc_params![
+				&sub_id,
+				&block_hash,
+				vec![StorageQuery {
+					key: hex_string(b":m"),
+					query_type: StorageQueryType::DescendantsValues
+				}]
+			],
+		)
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::OperationWaitingForContinue(res) if res.operation_id == operation_id
+	);
 
-	// Pruned hash can be unpinned.
-	let sub_id = sub.subscription_id();
-	let sub_id = serde_json::to_string(&sub_id).unwrap();
-	let hash = format!("{:?}", block_2_hash);
-	let _res: () = api.call("chainHead_unstable_unpin", [&sub_id, &hash]).await.unwrap();
+	// The operation is not resumed until the next period of the quota.
+	let err = api
+		.call::<_, serde_json::Value>("chainHead_unstable_continue", [&sub_id, &operation_id])
+		.await
+		.unwrap_err();
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == 2007
+	);
 }
 
 #[tokio::test]
-async fn follow_forks_pruned_block() {
+async fn operations_are_audited() {
 	let builder = TestClientBuilder::new();
 	let backend = builder.backend();
 	let mut client = Arc::new(builder.build());
 
+	let (sink, mut records) = ChannelAuditSink::new();
 	let api = ChainHead::new(
 		client.clone(),
 		backend,
@@ -1676,114 +4247,102 @@ async fn follow_forks_pruned_block() {
 			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
 			subscription_max_ongoing_operations: MAX_OPERATIONS,
 			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			operation_audit_sink: Some(Arc::new(sink)),
+			operation_audit_tenant: Some("public".into()),
+			..Default::default()
 		},
 	)
 	.into_rpc();
 
-	// Block tree before the subscription:
-	//
-	// finalized -> block 1 -> block 2 -> block 3
-	//                                        ^^^ finalized
-	//           -> block 1 -> block 4 -> block 5
-	//
-
-	let block_1 = client.new_block(Default::default()).unwrap().build().unwrap().block;
-	client.import(BlockOrigin::Own, block_1.clone()).await.unwrap();
-
-	let block_2 = client.new_block(Default::default()).unwrap().build().unwrap().block;
-	client.import(BlockOrigin::Own, block_2.clone()).await.unwrap();
+	let mut sub = api.subscribe("chainHead_unstable_follow", [true]).await.unwrap();
+	let sub_id = sub.subscription_id();
+	let sub_id = serde_json::to_string(&sub_id).unwrap();
 
-	let block_3 = client.new_block(Default::default()).unwrap().build().unwrap().block;
-	let block_3_hash = block_3.header.hash();
-	client.import(BlockOrigin::Own, block_3.clone()).await.unwrap();
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
 
-	// Block 4 with parent Block 1 is not the best imported.
-	let mut block_builder =
-		client.new_block_at(block_1.header.hash(), Default::default(), false).unwrap();
-	// This push is required as otherwise block 4 has the same hash as block 2 and won't get
-	// imported
-	block_builder
-		.push_transfer(Transfer {
-			from: AccountKeyring::Alice.into(),
-			to: AccountKeyring::Ferdie.into(),
-			amount: 41,
-			nonce: 0,
-		})
-		.unwrap();
-	let block_4 = block_builder.build().unwrap().block;
-	client.import(BlockOrigin::Own, block_4.clone()).await.unwrap();
+	// Ensure the imported block is propagated and pinned for this subscription.
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::Initialized(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
 
-	let mut block_builder =
-		client.new_block_at(block_4.header.hash(), Default::default(), false).unwrap();
-	block_builder
-		.push_transfer(Transfer {
-			from: AccountKeyring::Bob.into(),
-			to: AccountKeyring::Ferdie.into(),
-			amount: 41,
-			nonce: 0,
-		})
+	// The output of the call is reported as the bytes served.
+	let block_hash = format!("{:?}", block.header.hash());
+	let alice_id = AccountKeyring::Alice.to_account_id();
+	let call_parameters = hex_string(&alice_id.encode());
+	let response: MethodResponse = api
+		.call(
+			"chainHead_unstable_call",
+			[&sub_id, &block_hash, "AccountNonceApi_account_nonce", &call_parameters],
+		)
+		.await
 		.unwrap();
-	let block_5 = block_builder.build().unwrap().block;
-	client.import(BlockOrigin::Own, block_5.clone()).await.unwrap();
-
-	// Block 4 and 5 are not pruned, pruning happens at height (N - 1).
-	client.finalize_block(block_3_hash, None).unwrap();
-
-	let mut sub = api.subscribe("chainHead_unstable_follow", [false]).await.unwrap();
-
-	// Initialized must always be reported first.
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::Initialized(Initialized {
-		finalized_block_hash: format!("{:?}", block_3_hash),
-		finalized_block_runtime: None,
-		with_runtime: false,
-	});
-	assert_eq!(event, expected);
-
-	// Block tree:
-	//
-	// finalized -> block 1 -> block 2 -> block 3 -> block 6
-	//                                                  ^^^ finalized
-	//           -> block 1 -> block 4 -> block 5
-	//
-	// Mark block 6 as finalized to force block 4 and 5 to get pruned.
-
-	let block_6 = client.new_block(Default::default()).unwrap().build().unwrap().block;
-	let block_6_hash = block_6.header.hash();
-	client.import(BlockOrigin::Own, block_6.clone()).await.unwrap();
+	assert_matches!(response, MethodResponse::Started(_));
+	let output = assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::OperationCallDone(done) => done.output
+	);
 
-	client.finalize_block(block_6_hash, None).unwrap();
+	let record = run_with_timeout(records.next()).await.unwrap();
+	assert_eq!(record.method, "chainHead_unstable_call");
+	assert_eq!(record.tenant, Some("public".into()));
+	assert_eq!(record.cost_units, 1);
+	assert_eq!(record.bytes, output.len() as u64);
 
-	// Check block 6.
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::NewBlock(NewBlock {
-		block_hash: format!("{:?}", block_6_hash),
-		parent_block_hash: format!("{:?}", block_3_hash),
-		new_runtime: None,
-		with_runtime: false,
-	});
-	assert_eq!(event, expected);
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
-		best_block_hash: format!("{:?}", block_6_hash),
-	});
-	assert_eq!(event, expected);
+	// Each queried item costs one unit.
+	let key = hex_string(&KEY);
+	let response: MethodResponse = api
+		.call(
+			"chainHead_unstable_storage",
+			rpc_params![
+				&sub_id,
+				&block_hash,
+				vec![
+					StorageQuery { key: key.clone(), query_type: StorageQueryType::Exists },
+					StorageQuery { key: key.clone(), query_type: StorageQueryType::Value },
+				]
+			],
+		)
+		.await
+		.unwrap();
+	assert_matches!(response, MethodResponse::Started(_));
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::OperationStorageItems(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::OperationStorageDone(_)
+	);
 
-	// Block 4 and 5 must not be reported as pruned.
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::Finalized(Finalized {
-		finalized_block_hashes: vec![format!("{:?}", block_6_hash)],
-		pruned_block_hashes: vec![],
-	});
-	assert_eq!(event, expected);
+	// Only the existence of the missing key is reported.
+	let record = run_with_timeout(records.next()).await.unwrap();
+	assert_eq!(record.method, "chainHead_unstable_storage");
+	assert_eq!(record.cost_units, 2);
+	assert_eq!(record.bytes, key.len() as u64);
 }
 
 #[tokio::test]
-async fn follow_report_multiple_pruned_block() {
-	let builder = TestClientBuilder::new();
+async fn check_continue_operation() {
+	let child_info = ChildInfo::new_default(CHILD_STORAGE_KEY);
+	let builder = TestClientBuilder::new().add_extra_child_storage(
+		&child_info,
+		KEY.to_vec(),
+		CHILD_VALUE.to_vec(),
+	);
 	let backend = builder.backend();
 	let mut client = Arc::new(builder.build());
 
+	// Configure the chainHead with maximum 1 item before asking for pagination.
 	let api = ChainHead::new(
 		client.clone(),
 		backend,
@@ -1793,247 +4352,181 @@ async fn follow_report_multiple_pruned_block() {
 			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
 			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
 			subscription_max_ongoing_operations: MAX_OPERATIONS,
-			operation_max_storage_items: MAX_PAGINATION_LIMIT,
-		},
-	)
-	.into_rpc();
-
-	// Block tree:
-	//
-	// finalized -> block 1 -> block 2 -> block 3
-	//                                        ^^^ finalized after subscription
-	//           -> block 1 -> block 4 -> block 5
-
-	let finalized_hash = client.info().finalized_hash;
-
-	let block_1 = client.new_block(Default::default()).unwrap().build().unwrap().block;
-	let block_1_hash = block_1.header.hash();
-	client.import(BlockOrigin::Own, block_1.clone()).await.unwrap();
-
-	let block_2 = client.new_block(Default::default()).unwrap().build().unwrap().block;
-	let block_2_hash = block_2.header.hash();
-	client.import(BlockOrigin::Own, block_2.clone()).await.unwrap();
-
-	let block_3 = client.new_block(Default::default()).unwrap().build().unwrap().block;
-	let block_3_hash = block_3.header.hash();
-	client.import(BlockOrigin::Own, block_3.clone()).await.unwrap();
-
-	// Block 4 with parent Block 1 is not the best imported.
-	let mut block_builder =
-		client.new_block_at(block_1.header.hash(), Default::default(), false).unwrap();
-	// This push is required as otherwise block 4 has the same hash as block 2 and won't get
-	// imported
-	block_builder
-		.push_transfer(Transfer {
-			from: AccountKeyring::Alice.into(),
-			to: AccountKeyring::Ferdie.into(),
-			amount: 41,
-			nonce: 0,
-		})
-		.unwrap();
-	let block_4 = block_builder.build().unwrap().block;
-	let block_4_hash = block_4.header.hash();
-	client.import(BlockOrigin::Own, block_4.clone()).await.unwrap();
-
-	let mut block_builder =
-		client.new_block_at(block_4.header.hash(), Default::default(), false).unwrap();
-	block_builder
-		.push_transfer(Transfer {
-			from: AccountKeyring::Bob.into(),
-			to: AccountKeyring::Ferdie.into(),
-			amount: 41,
-			nonce: 0,
-		})
-		.unwrap();
-	let block_5 = block_builder.build().unwrap().block;
-	let block_5_hash = block_5.header.hash();
-	client.import(BlockOrigin::Own, block_5.clone()).await.unwrap();
-	let mut sub = api.subscribe("chainHead_unstable_follow", [false]).await.unwrap();
-
-	// Initialized must always be reported first.
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::Initialized(Initialized {
-		finalized_block_hash: format!("{:?}", finalized_hash),
-		finalized_block_runtime: None,
-		with_runtime: false,
-	});
-	assert_eq!(event, expected);
-
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::NewBlock(NewBlock {
-		block_hash: format!("{:?}", block_1_hash),
-		parent_block_hash: format!("{:?}", finalized_hash),
-		new_runtime: None,
-		with_runtime: false,
-	});
-	assert_eq!(event, expected);
-
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::NewBlock(NewBlock {
-		block_hash: format!("{:?}", block_2_hash),
-		parent_block_hash: format!("{:?}", block_1_hash),
-		new_runtime: None,
-		with_runtime: false,
-	});
-	assert_eq!(event, expected);
+			operation_max_storage_items: 1,
+			..Default::default()
+		},
+	)
+	.into_rpc();
 
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::NewBlock(NewBlock {
-		block_hash: format!("{:?}", block_3_hash),
-		parent_block_hash: format!("{:?}", block_2_hash),
-		new_runtime: None,
-		with_runtime: false,
-	});
-	assert_eq!(event, expected);
+	let mut sub = api.subscribe("chainHead_unstable_follow", [true]).await.unwrap();
+	let sub_id = sub.subscription_id();
+	let sub_id = serde_json::to_string(&sub_id).unwrap();
 
-	// The fork must also be reported.
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::NewBlock(NewBlock {
-		block_hash: format!("{:?}", block_4_hash),
-		parent_block_hash: format!("{:?}", block_1_hash),
-		new_runtime: None,
-		with_runtime: false,
-	});
-	assert_eq!(event, expected);
+	// Import a new block with storage changes.
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder.push_storage_change(b":m".to_vec(), Some(b"a".to_vec())).unwrap();
+	builder.push_storage_change(b":mo".to_vec(), Some(b"ab".to_vec())).unwrap();
+	builder.push_storage_change(b":moc".to_vec(), Some(b"abc".to_vec())).unwrap();
+	builder.push_storage_change(b":mock".to_vec(), Some(b"abcd".to_vec())).unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
 
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::NewBlock(NewBlock {
-		block_hash: format!("{:?}", block_5_hash),
-		parent_block_hash: format!("{:?}", block_4_hash),
-		new_runtime: None,
-		with_runtime: false,
-	});
-	assert_eq!(event, expected);
+	// Ensure the imported block is propagated and pinned for this subscription.
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::Initialized(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
 
-	// The best block of the chain must also be reported.
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
-		best_block_hash: format!("{:?}", block_3_hash),
-	});
-	assert_eq!(event, expected);
+	let invalid_hash = hex_string(&INVALID_HASH);
 
-	// Block 4 and 5 are not pruned, pruning happens at height (N - 1).
-	client.finalize_block(block_3_hash, None).unwrap();
+	// Invalid subscription ID must produce no results.
+	let _res: () = api
+		.call("chainHead_unstable_continue", ["invalid_sub_id", &invalid_hash])
+		.await
+		.unwrap();
 
-	// Finalizing block 3 directly will also result in block 1 and 2 being finalized.
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::Finalized(Finalized {
-		finalized_block_hashes: vec![
-			format!("{:?}", block_1_hash),
-			format!("{:?}", block_2_hash),
-			format!("{:?}", block_3_hash),
-		],
-		pruned_block_hashes: vec![],
-	});
-	assert_eq!(event, expected);
+	// Invalid operation ID must produce no results.
+	let _res: () = api.call("chainHead_unstable_continue", [&sub_id, &invalid_hash]).await.unwrap();
 
-	// Block tree:
-	//
-	// finalized -> block 1 -> block 2 -> block 3 -> block 6
-	//                                                  ^^^ finalized
-	//           -> block 1 -> block 4 -> block 5
-	//
-	// Mark block 6 as finalized to force block 4 and 5 to get pruned.
+	// Valid call with storage at the key.
+	let response: MethodResponse = api
+		.call(
+			"chainHead_unstable_storage",
+			rpc_params![
+				&sub_id,
+				&block_hash,
+				vec![StorageQuery {
+					key: hex_string(b":m"),
+					query_type: StorageQueryType::DescendantsValues
+				}]
+			],
+		)
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
 
-	let block_6 = client.new_block(Default::default()).unwrap().build().unwrap().block;
-	let block_6_hash = block_6.header.hash();
-	client.import(BlockOrigin::Own, block_6.clone()).await.unwrap();
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
+			res.items.len() == 1 &&
+			res.items[0].key == hex_string(b":m") &&
+			res.items[0].result == StorageResultType::Value(hex_string(b"a"))
+	);
 
-	client.finalize_block(block_6_hash, None).unwrap();
+	// Pagination event.
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::OperationWaitingForContinue(res) if res.operation_id == operation_id
+	);
 
-	// Check block 6.
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::NewBlock(NewBlock {
-		block_hash: format!("{:?}", block_6_hash),
-		parent_block_hash: format!("{:?}", block_3_hash),
-		new_runtime: None,
-		with_runtime: false,
-	});
-	assert_eq!(event, expected);
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
-		best_block_hash: format!("{:?}", block_6_hash),
-	});
-	assert_eq!(event, expected);
+	does_not_produce_event::<FollowEvent<String>>(
+		&mut sub,
+		std::time::Duration::from_secs(DOES_NOT_PRODUCE_EVENTS_SECONDS),
+	)
+	.await;
+	let _res: () = api.call("chainHead_unstable_continue", [&sub_id, &operation_id]).await.unwrap();
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
+			res.items.len() == 1 &&
+			res.items[0].key == hex_string(b":mo") &&
+			res.items[0].result == StorageResultType::Value(hex_string(b"ab"))
+	);
 
-	// Block 4 and 5 be reported as pruned, not just the stale head (block 5).
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::Finalized(Finalized {
-		finalized_block_hashes: vec![format!("{:?}", block_6_hash)],
-		pruned_block_hashes: vec![format!("{:?}", block_4_hash), format!("{:?}", block_5_hash)],
-	});
-	assert_eq!(event, expected);
-}
+	// Pagination event.
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::OperationWaitingForContinue(res) if res.operation_id == operation_id
+	);
 
-#[tokio::test]
-async fn pin_block_references() {
-	// Manually construct an in-memory backend and client.
-	let backend = Arc::new(sc_client_api::in_mem::Backend::new());
-	let executor = substrate_test_runtime_client::new_native_or_wasm_executor();
-	let client_config = sc_service::ClientConfig::default();
+	does_not_produce_event::<FollowEvent<String>>(
+		&mut sub,
+		std::time::Duration::from_secs(DOES_NOT_PRODUCE_EVENTS_SECONDS),
+	)
+	.await;
+	let _res: () = api.call("chainHead_unstable_continue", [&sub_id, &operation_id]).await.unwrap();
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
+			res.items.len() == 1 &&
+			res.items[0].key == hex_string(b":moc") &&
+			res.items[0].result == StorageResultType::Value(hex_string(b"abc"))
+	);
 
-	let genesis_block_builder = sc_service::GenesisBlockBuilder::new(
-		&substrate_test_runtime_client::GenesisParameters::default().genesis_storage(),
-		!client_config.no_genesis,
-		backend.clone(),
-		executor.clone(),
+	// Pagination event.
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::OperationWaitingForContinue(res) if res.operation_id == operation_id
+	);
+	does_not_produce_event::<FollowEvent<String>>(
+		&mut sub,
+		std::time::Duration::from_secs(DOES_NOT_PRODUCE_EVENTS_SECONDS),
 	)
-	.unwrap();
+	.await;
+	let _res: () = api.call("chainHead_unstable_continue", [&sub_id, &operation_id]).await.unwrap();
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
+			res.items.len() == 1 &&
+			res.items[0].key == hex_string(b":mock") &&
+			res.items[0].result == StorageResultType::Value(hex_string(b"abcd"))
+	);
 
-	let mut client = Arc::new(
-		new_in_mem::<_, Block, _, RuntimeApi>(
-			backend.clone(),
-			executor,
-			genesis_block_builder,
-			None,
-			None,
-			Box::new(TaskExecutor::new()),
-			client_config,
-		)
-		.unwrap(),
+	// Finished.
+	assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut sub).await,
+			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
 	);
+}
+
+#[tokio::test]
+async fn storage_operation_timeout() {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let mut client = Arc::new(builder.build());
 
+	// Configure the chainHead with maximum 1 item before asking for pagination.
 	let api = ChainHead::new(
 		client.clone(),
-		backend.clone(),
+		backend,
 		Arc::new(TaskExecutor::default()),
 		CHAIN_GENESIS,
 		ChainHeadConfig {
-			global_max_pinned_blocks: 3,
+			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
 			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
 			subscription_max_ongoing_operations: MAX_OPERATIONS,
-			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			operation_max_storage_items: 1,
+			operation_storage_timeout: Some(Duration::from_secs(1)),
+			..Default::default()
 		},
 	)
 	.into_rpc();
 
-	async fn wait_pinned_references<Block: BlockT>(
-		backend: &Arc<sc_client_api::in_mem::Backend<Block>>,
-		hash: &Block::Hash,
-		target: i64,
-	) {
-		// Retry for at most 2 minutes.
-		let mut retries = 120;
-		while backend.pin_refs(hash).unwrap() != target {
-			if retries == 0 {
-				panic!("Expected target={} pinned references for hash={:?}", target, hash);
-			}
-			retries -= 1;
-
-			tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-		}
-	}
-
-	let mut sub = api.subscribe("chainHead_unstable_follow", [false]).await.unwrap();
+	let mut sub = api.subscribe("chainHead_unstable_follow", [true]).await.unwrap();
 	let sub_id = sub.subscription_id();
 	let sub_id = serde_json::to_string(&sub_id).unwrap();
-
-	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
-	let hash = block.header.hash();
-	let block_hash = format!("{:?}", hash);
+
+	// Import a new block with storage changes.
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder.push_storage_change(b":m".to_vec(), Some(b"a".to_vec())).unwrap();
+	builder.push_storage_change(b":mo".to_vec(), Some(b"ab".to_vec())).unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
 	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
 
-	// Ensure the imported block is propagated for this subscription.
+	// Ensure the imported block is propagated and pinned for this subscription.
 	assert_matches!(
 		get_next_event::<FollowEvent<String>>(&mut sub).await,
 		FollowEvent::Initialized(_)
@@ -2047,68 +4540,58 @@ async fn pin_block_references() {
 		FollowEvent::BestBlockChanged(_)
 	);
 
-	// We need to wait a bit for:
-	// 1. `NewBlock` and `BestBlockChanged` notifications to propagate to the chainHead
-	// subscription. (pin_refs == 2)
-	// 2. The chainHead to call `pin_blocks` only once for the `NewBlock`
-	// notification (pin_refs == 3)
-	// 3. Both notifications to go out of scope (pin_refs ==  1 (total 3 - dropped 2)).
-	wait_pinned_references(&backend, &hash, 1).await;
-
-	// To not exceed the number of pinned blocks, we need to unpin before the next import.
-	let _res: () = api.call("chainHead_unstable_unpin", [&sub_id, &block_hash]).await.unwrap();
-
-	// Make sure unpin clears out the reference.
-	let refs = backend.pin_refs(&hash).unwrap();
-	assert_eq!(refs, 0);
-
-	// Add another 2 blocks and make sure we drop the subscription with the blocks pinned.
-	let mut hashes = Vec::new();
-	for _ in 0..2 {
-		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
-		let hash = block.header.hash();
-		client.import(BlockOrigin::Own, block.clone()).await.unwrap();
-
-		// Ensure the imported block is propagated for this subscription.
-		assert_matches!(
-			get_next_event::<FollowEvent<String>>(&mut sub).await,
-			FollowEvent::NewBlock(_)
-		);
-		assert_matches!(
-			get_next_event::<FollowEvent<String>>(&mut sub).await,
-			FollowEvent::BestBlockChanged(_)
-		);
-
-		hashes.push(hash);
-	}
+	let response: MethodResponse = api
+		.call(
+			"chainHead_unstable_storage",
+			rpc_params![
+				&sub_id,
+				&block_hash,
+				vec![StorageQuery {
+					key: hex_string(b":m"),
+					query_type: StorageQueryType::DescendantsValues
+				}]
+			],
+		)
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
 
-	// Make sure the pin was propagated.
-	for hash in &hashes {
-		wait_pinned_references(&backend, hash, 1).await;
-	}
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::OperationWaitingForContinue(res) if res.operation_id == operation_id
+	);
 
-	// Drop the subscription and expect the pinned blocks to be released.
-	drop(sub);
-	// The `chainHead` detects the subscription was terminated when it tries
-	// to send another block.
-	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
-	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+	// The operation is stopped while waiting for `chainHead_continue`.
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::OperationTimeout(res) if res.operation_id == operation_id
+	);
 
-	for hash in &hashes {
-		wait_pinned_references(&backend, &hash, 0).await;
-	}
+	// The operation is released.
+	let _res: () = api.call("chainHead_unstable_continue", [&sub_id, &operation_id]).await.unwrap();
+	does_not_produce_event::<FollowEvent<String>>(
+		&mut sub,
+		std::time::Duration::from_secs(DOES_NOT_PRODUCE_EVENTS_SECONDS),
+	)
+	.await;
 }
 
 #[tokio::test]
-async fn follow_finalized_before_new_block() {
+async fn operations_past_their_deadline_time_out() {
 	let builder = TestClientBuilder::new();
 	let backend = builder.backend();
 	let mut client = Arc::new(builder.build());
 
-	let client_mock = Arc::new(ChainHeadMockClient::new(client.clone()));
-
+	// The deadline of the operations is reached as soon as they start.
 	let api = ChainHead::new(
-		client_mock.clone(),
+		client.clone(),
 		backend,
 		Arc::new(TaskExecutor::default()),
 		CHAIN_GENESIS,
@@ -2117,97 +4600,76 @@ async fn follow_finalized_before_new_block() {
 			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
 			subscription_max_ongoing_operations: MAX_OPERATIONS,
 			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			operation_storage_timeout: Some(Duration::ZERO),
+			operation_body_timeout: Some(Duration::ZERO),
+			..Default::default()
 		},
 	)
 	.into_rpc();
 
-	// Make sure the block is imported for it to be pinned.
-	let block_1 = client.new_block(Default::default()).unwrap().build().unwrap().block;
-	let block_1_hash = block_1.header.hash();
-	client.import(BlockOrigin::Own, block_1.clone()).await.unwrap();
-
-	let mut sub = api.subscribe("chainHead_unstable_follow", [false]).await.unwrap();
-
-	// Trigger the `FinalizedNotification` for block 1 before the `BlockImportNotification`, and
-	// expect for the `chainHead` to generate `NewBlock`, `BestBlock` and `Finalized` events.
-
-	// Trigger the Finalized notification before the NewBlock one.
-	run_with_timeout(client_mock.trigger_finality_stream(block_1.header.clone())).await;
-
-	// Initialized must always be reported first.
-	let finalized_hash = client.info().finalized_hash;
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::Initialized(Initialized {
-		finalized_block_hash: format!("{:?}", finalized_hash),
-		finalized_block_runtime: None,
-		with_runtime: false,
-	});
-	assert_eq!(event, expected);
-
-	// Block 1 must be reported because we triggered the finalized notification.
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::NewBlock(NewBlock {
-		block_hash: format!("{:?}", block_1_hash),
-		parent_block_hash: format!("{:?}", finalized_hash),
-		new_runtime: None,
-		with_runtime: false,
-	});
-	assert_eq!(event, expected);
-
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
-		best_block_hash: format!("{:?}", block_1_hash),
-	});
-	assert_eq!(event, expected);
-
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::Finalized(Finalized {
-		finalized_block_hashes: vec![format!("{:?}", block_1_hash)],
-		pruned_block_hashes: vec![],
-	});
-	assert_eq!(event, expected);
-
-	let block_2 = client.new_block(Default::default()).unwrap().build().unwrap().block;
-	let block_2_hash = block_2.header.hash();
-	client.import(BlockOrigin::Own, block_2.clone()).await.unwrap();
+	let mut sub = api.subscribe("chainHead_unstable_follow", [true]).await.unwrap();
+	let sub_id = sub.subscription_id();
+	let sub_id = serde_json::to_string(&sub_id).unwrap();
 
-	// Triggering the `BlockImportNotification` notification for block 1 should have no effect
-	// on the notification because the events were handled by the `FinalizedNotification`.
-	// Also trigger the `BlockImportNotification` notification for block 2 to ensure
-	// `NewBlock and `BestBlock` events are generated.
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder.push_storage_change(b":m".to_vec(), Some(b"a".to_vec())).unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
 
-	// Trigger NewBlock notification for block 1 and block 2.
-	run_with_timeout(client_mock.trigger_import_stream(block_1.header)).await;
-	run_with_timeout(client_mock.trigger_import_stream(block_2.header)).await;
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::Initialized(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
 
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::NewBlock(NewBlock {
-		block_hash: format!("{:?}", block_2_hash),
-		parent_block_hash: format!("{:?}", block_1_hash),
-		new_runtime: None,
-		with_runtime: false,
-	});
-	assert_eq!(event, expected);
+	// The storage items are not read past the deadline.
+	let response: MethodResponse = api
+		.call(
+			"chainHead_unstable_storage",
+			rpc_params![
+				&sub_id,
+				&block_hash,
+				vec![StorageQuery { key: hex_string(b":m"), query_type: StorageQueryType::Value }]
+			],
+		)
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::OperationTimeout(res) if res.operation_id == operation_id
+	);
 
-	let event: FollowEvent<String> = get_next_event(&mut sub).await;
-	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
-		best_block_hash: format!("{:?}", block_2_hash),
-	});
-	assert_eq!(event, expected);
+	// The late body is not reported.
+	let response: MethodResponse =
+		api.call("chainHead_unstable_body", [&sub_id, &block_hash]).await.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::OperationTimeout(res) if res.operation_id == operation_id
+	);
 }
 
 #[tokio::test]
-async fn ensure_operation_limits_works() {
-	let child_info = ChildInfo::new_default(CHILD_STORAGE_KEY);
-	let builder = TestClientBuilder::new().add_extra_child_storage(
-		&child_info,
-		KEY.to_vec(),
-		CHILD_VALUE.to_vec(),
-	);
+async fn operations_run_on_the_dedicated_pool() {
+	let builder = TestClientBuilder::new();
 	let backend = builder.backend();
 	let mut client = Arc::new(builder.build());
 
-	// Configure the chainHead with maximum 1 ongoing operations.
 	let api = ChainHead::new(
 		client.clone(),
 		backend,
@@ -2216,8 +4678,13 @@ async fn ensure_operation_limits_works() {
 		ChainHeadConfig {
 			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
 			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
-			subscription_max_ongoing_operations: 1,
+			subscription_max_ongoing_operations: MAX_OPERATIONS,
 			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			operation_pool: Some(Arc::new(OperationPool::new(
+				1,
+				tokio::runtime::Handle::current(),
+			))),
+			..Default::default()
 		},
 	)
 	.into_rpc();
@@ -2226,10 +4693,12 @@ async fn ensure_operation_limits_works() {
 	let sub_id = sub.subscription_id();
 	let sub_id = serde_json::to_string(&sub_id).unwrap();
 
-	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder.push_storage_change(b":m".to_vec(), Some(b"a".to_vec())).unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
 	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
 
-	// Ensure the imported block is propagated and pinned for this subscription.
 	assert_matches!(
 		get_next_event::<FollowEvent<String>>(&mut sub).await,
 		FollowEvent::Initialized(_)
@@ -2243,37 +4712,23 @@ async fn ensure_operation_limits_works() {
 		FollowEvent::BestBlockChanged(_)
 	);
 
-	let block_hash = format!("{:?}", block.header.hash());
-	let key = hex_string(&KEY);
-
-	let items = vec![
-		StorageQuery { key: key.clone(), query_type: StorageQueryType::DescendantsHashes },
-		StorageQuery { key: key.clone(), query_type: StorageQueryType::DescendantsHashes },
-		StorageQuery { key: key.clone(), query_type: StorageQueryType::DescendantsValues },
-		StorageQuery { key: key.clone(), query_type: StorageQueryType::DescendantsValues },
-	];
-
 	let response: MethodResponse = api
-		.call("chainHead_unstable_storage", rpc_params![&sub_id, &block_hash, items])
+		.call(
+			"chainHead_unstable_storage",
+			rpc_params![
+				&sub_id,
+				&block_hash,
+				vec![StorageQuery { key: hex_string(b":m"), query_type: StorageQueryType::Value }]
+			],
+		)
 		.await
 		.unwrap();
-	let operation_id = match response {
-		MethodResponse::Started(started) => {
-			// Check discarded items.
-			assert_eq!(started.discarded_items.unwrap(), 3);
-			started.operation_id
-		},
+	let storage_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
 		MethodResponse::LimitReached => panic!("Expected started response"),
 	};
-	// No value associated with the provided key.
-	assert_matches!(
-			get_next_event::<FollowEvent<String>>(&mut sub).await,
-			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
-	);
 
-	// The storage is finished and capactiy must be released.
 	let alice_id = AccountKeyring::Alice.to_account_id();
-	// Hex encoded scale encoded bytes representing the call parameters.
 	let call_parameters = hex_string(&alice_id.encode());
 	let response: MethodResponse = api
 		.call(
@@ -2282,28 +4737,38 @@ async fn ensure_operation_limits_works() {
 		)
 		.await
 		.unwrap();
-	let operation_id = match response {
+	let call_id = match response {
 		MethodResponse::Started(started) => started.operation_id,
 		MethodResponse::LimitReached => panic!("Expected started response"),
 	};
 
-	// Response propagated to `chainHead_follow`.
+	// The single thread of the pool executes the operations in order.
 	assert_matches!(
-			get_next_event::<FollowEvent<String>>(&mut sub).await,
-			FollowEvent::OperationCallDone(done) if done.operation_id == operation_id && done.output == "0x0000000000000000"
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::OperationStorageItems(res) if res.operation_id == storage_id &&
+			res.items.len() == 1 && res.items[0].result == StorageResultType::Value(hex_string(b"a"))
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::OperationStorageDone(done) if done.operation_id == storage_id
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::OperationCallDone(done) if done.operation_id == call_id &&
+			done.output == "0x0000000000000000"
 	);
 }
 
 #[tokio::test]
-async fn check_continue_operation() {
+async fn check_continue_child_trie_operation() {
 	let child_info = ChildInfo::new_default(CHILD_STORAGE_KEY);
-	let builder = TestClientBuilder::new().add_extra_child_storage(
-		&child_info,
-		KEY.to_vec(),
-		CHILD_VALUE.to_vec(),
-	);
+	let builder = TestClientBuilder::new()
+		.add_extra_child_storage(&child_info, b"a1".to_vec(), b"1".to_vec())
+		.add_extra_child_storage(&child_info, b"a2".to_vec(), b"2".to_vec())
+		.add_extra_child_storage(&child_info, b"a3".to_vec(), b"3".to_vec())
+		.add_extra_child_storage(&child_info, b"b".to_vec(), b"4".to_vec());
 	let backend = builder.backend();
-	let mut client = Arc::new(builder.build());
+	let client = Arc::new(builder.build());
 
 	// Configure the chainHead with maximum 1 item before asking for pagination.
 	let api = ChainHead::new(
@@ -2316,60 +4781,31 @@ async fn check_continue_operation() {
 			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
 			subscription_max_ongoing_operations: MAX_OPERATIONS,
 			operation_max_storage_items: 1,
+			..Default::default()
 		},
 	)
 	.into_rpc();
 
-	let mut sub = api.subscribe("chainHead_unstable_follow", [true]).await.unwrap();
+	let mut sub = api.subscribe("chainHead_unstable_follow", [false]).await.unwrap();
 	let sub_id = sub.subscription_id();
 	let sub_id = serde_json::to_string(&sub_id).unwrap();
-
-	// Import a new block with storage changes.
-	let mut builder = client.new_block(Default::default()).unwrap();
-	builder.push_storage_change(b":m".to_vec(), Some(b"a".to_vec())).unwrap();
-	builder.push_storage_change(b":mo".to_vec(), Some(b"ab".to_vec())).unwrap();
-	builder.push_storage_change(b":moc".to_vec(), Some(b"abc".to_vec())).unwrap();
-	builder.push_storage_change(b":mock".to_vec(), Some(b"abcd".to_vec())).unwrap();
-	let block = builder.build().unwrap().block;
-	let block_hash = format!("{:?}", block.header.hash());
-	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
-
-	// Ensure the imported block is propagated and pinned for this subscription.
 	assert_matches!(
 		get_next_event::<FollowEvent<String>>(&mut sub).await,
 		FollowEvent::Initialized(_)
 	);
-	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut sub).await,
-		FollowEvent::NewBlock(_)
-	);
-	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut sub).await,
-		FollowEvent::BestBlockChanged(_)
-	);
-
-	let invalid_hash = hex_string(&INVALID_HASH);
-
-	// Invalid subscription ID must produce no results.
-	let _res: () = api
-		.call("chainHead_unstable_continue", ["invalid_sub_id", &invalid_hash])
-		.await
-		.unwrap();
-
-	// Invalid operation ID must produce no results.
-	let _res: () = api.call("chainHead_unstable_continue", [&sub_id, &invalid_hash]).await.unwrap();
 
-	// Valid call with storage at the key.
+	let genesis_hash = format!("{:?}", client.genesis_hash());
 	let response: MethodResponse = api
 		.call(
 			"chainHead_unstable_storage",
 			rpc_params![
 				&sub_id,
-				&block_hash,
+				&genesis_hash,
 				vec![StorageQuery {
-					key: hex_string(b":m"),
+					key: hex_string(b"a"),
 					query_type: StorageQueryType::DescendantsValues
-				}]
+				}],
+				hex_string(&CHILD_STORAGE_KEY)
 			],
 		)
 		.await
@@ -2379,77 +4815,30 @@ async fn check_continue_operation() {
 		MethodResponse::LimitReached => panic!("Expected started response"),
 	};
 
-	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut sub).await,
-		FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
-			res.items.len() == 1 &&
-			res.items[0].key == hex_string(b":m") &&
-			res.items[0].result == StorageResultType::Value(hex_string(b"a"))
-	);
-
-	// Pagination event.
-	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut sub).await,
-		FollowEvent::OperationWaitingForContinue(res) if res.operation_id == operation_id
-	);
-
-	does_not_produce_event::<FollowEvent<String>>(
-		&mut sub,
-		std::time::Duration::from_secs(DOES_NOT_PRODUCE_EVENTS_SECONDS),
-	)
-	.await;
-	let _res: () = api.call("chainHead_unstable_continue", [&sub_id, &operation_id]).await.unwrap();
-	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut sub).await,
-		FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
-			res.items.len() == 1 &&
-			res.items[0].key == hex_string(b":mo") &&
-			res.items[0].result == StorageResultType::Value(hex_string(b"ab"))
-	);
-
-	// Pagination event.
-	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut sub).await,
-		FollowEvent::OperationWaitingForContinue(res) if res.operation_id == operation_id
-	);
+	// The descendants of the key are reported one page at a time, until exhausted.
+	let descendants = [(b"a1", b"1"), (b"a2", b"2"), (b"a3", b"3")];
+	for (index, (key, value)) in descendants.into_iter().enumerate() {
+		if index > 0 {
+			assert_matches!(
+				get_next_event::<FollowEvent<String>>(&mut sub).await,
+				FollowEvent::OperationWaitingForContinue(res) if res.operation_id == operation_id
+			);
+			let _res: () =
+				api.call("chainHead_unstable_continue", [&sub_id, &operation_id]).await.unwrap();
+		}
 
-	does_not_produce_event::<FollowEvent<String>>(
-		&mut sub,
-		std::time::Duration::from_secs(DOES_NOT_PRODUCE_EVENTS_SECONDS),
-	)
-	.await;
-	let _res: () = api.call("chainHead_unstable_continue", [&sub_id, &operation_id]).await.unwrap();
-	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut sub).await,
-		FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
-			res.items.len() == 1 &&
-			res.items[0].key == hex_string(b":moc") &&
-			res.items[0].result == StorageResultType::Value(hex_string(b"abc"))
-	);
+		assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut sub).await,
+			FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
+				res.items.len() == 1 &&
+				res.items[0].key == hex_string(key) &&
+				res.items[0].result == StorageResultType::Value(hex_string(value))
+		);
+	}
 
-	// Pagination event.
-	assert_matches!(
-		get_next_event::<FollowEvent<String>>(&mut sub).await,
-		FollowEvent::OperationWaitingForContinue(res) if res.operation_id == operation_id
-	);
-	does_not_produce_event::<FollowEvent<String>>(
-		&mut sub,
-		std::time::Duration::from_secs(DOES_NOT_PRODUCE_EVENTS_SECONDS),
-	)
-	.await;
-	let _res: () = api.call("chainHead_unstable_continue", [&sub_id, &operation_id]).await.unwrap();
 	assert_matches!(
 		get_next_event::<FollowEvent<String>>(&mut sub).await,
-		FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
-			res.items.len() == 1 &&
-			res.items[0].key == hex_string(b":mock") &&
-			res.items[0].result == StorageResultType::Value(hex_string(b"abcd"))
-	);
-
-	// Finished.
-	assert_matches!(
-			get_next_event::<FollowEvent<String>>(&mut sub).await,
-			FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+		FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
 	);
 }
 
@@ -2475,6 +4864,7 @@ async fn stop_storage_operation() {
 			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
 			subscription_max_ongoing_operations: MAX_OPERATIONS,
 			operation_max_storage_items: 1,
+			..Default::default()
 		},
 	)
 	.into_rpc();
@@ -2565,3 +4955,233 @@ async fn stop_storage_operation() {
 	)
 	.await;
 }
+
+#[tokio::test]
+async fn health_reports_followers() {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let client = Arc::new(builder.build());
+
+	let health = ChainHeadHealth::default();
+	let api = ChainHead::new(
+		client.clone(),
+		backend,
+		Arc::new(TaskExecutor::default()),
+		CHAIN_GENESIS,
+		ChainHeadConfig { health: health.clone(), ..Default::default() },
+	)
+	.into_rpc();
+
+	let mut sub = api.subscribe("chainHead_unstable_follow", [false]).await.unwrap();
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::Initialized(_)
+	);
+
+	let report: HealthReport =
+		api.call("chainHead_unstable_health", EmptyParams::new()).await.unwrap();
+	assert_eq!(
+		report,
+		HealthReport {
+			followers: 1,
+			stalled_followers: 0,
+			closed_notification_streams: 0,
+			pending_operations: 0,
+			is_healthy: true,
+		}
+	);
+
+	// A follower whose notification streams closed makes the node unhealthy.
+	health.register_follower().notification_streams_closed();
+	let err = api
+		.call::<_, HealthReport>("chainHead_unstable_health", EmptyParams::new())
+		.await
+		.unwrap_err();
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == 2006 &&
+			err.message() == "The chainHead subsystem is unhealthy"
+	);
+}
+
+/// Delegate to the default store and count the locked blocks.
+struct CountingPinStore {
+	inner: Arc<dyn PinStore<Block, Backend>>,
+	locked: std::sync::atomic::AtomicUsize,
+}
+
+impl FollowSubscriptionPins<BlockHash> for CountingPinStore {
+	fn is_block_pinned(&self, sub_id: &str, hash: BlockHash) -> bool {
+		self.inner.is_block_pinned(sub_id, hash)
+	}
+
+	fn pin_block_external(&self, sub_id: &str, hash: BlockHash) -> bool {
+		self.inner.pin_block_external(sub_id, hash)
+	}
+
+	fn stop_subscriptions_with(&self, should_stop: &dyn Fn(&BlockHash) -> bool) -> usize {
+		self.inner.stop_subscriptions_with(should_stop)
+	}
+
+	fn subscriptions_info(&self) -> Vec<FollowSubscriptionInfo<BlockHash>> {
+		self.inner.subscriptions_info()
+	}
+}
+
+impl PinStore<Block, Backend> for CountingPinStore {
+	fn insert_subscription(
+		&self,
+		sub_id: String,
+		runtime_updates: bool,
+	) -> Option<InsertedSubscriptionData<Block>> {
+		self.inner.insert_subscription(sub_id, runtime_updates)
+	}
+
+	fn remove_subscription(&self, sub_id: &str) {
+		self.inner.remove_subscription(sub_id)
+	}
+
+	fn park_subscription(&self, sub_id: &str, token: String) -> bool {
+		self.inner.park_subscription(sub_id, token)
+	}
+
+	fn resume_subscription(
+		&self,
+		token: &str,
+		sub_id: String,
+		runtime_updates: bool,
+	) -> Option<InsertedSubscriptionData<Block>> {
+		self.inner.resume_subscription(token, sub_id, runtime_updates)
+	}
+
+	fn expire_parked_subscription(&self, token: &str) {
+		self.inner.expire_parked_subscription(token)
+	}
+
+	fn pin_block(
+		&self,
+		sub_id: &str,
+		hash: BlockHash,
+	) -> Result<bool, SubscriptionManagementError> {
+		self.inner.pin_block(sub_id, hash)
+	}
+
+	fn contains_block(&self, sub_id: &str, hash: BlockHash) -> bool {
+		self.inner.contains_block(sub_id, hash)
+	}
+
+	fn set_fork_filter(
+		&self,
+		sub_id: &str,
+		hash: Option<BlockHash>,
+	) -> Result<(), SubscriptionManagementError> {
+		self.inner.set_fork_filter(sub_id, hash)
+	}
+
+	fn fork_filter(&self, sub_id: &str) -> Option<BlockHash> {
+		self.inner.fork_filter(sub_id)
+	}
+
+	fn pinned_blocks(&self, sub_id: &str) -> Option<Vec<(BlockHash, Duration)>> {
+		self.inner.pinned_blocks(sub_id)
+	}
+
+	fn unpin_block(
+		&self,
+		sub_id: &str,
+		hash: BlockHash,
+	) -> Result<(), SubscriptionManagementError> {
+		self.inner.unpin_block(sub_id, hash)
+	}
+
+	fn unpin_blocks(
+		&self,
+		sub_id: &str,
+		hashes: &[BlockHash],
+	) -> Result<(), SubscriptionManagementError> {
+		self.inner.unpin_blocks(sub_id, hashes)
+	}
+
+	fn lock_block(
+		&self,
+		sub_id: &str,
+		hash: BlockHash,
+		to_reserve: usize,
+	) -> Result<BlockGuard<Block, Backend>, SubscriptionManagementError> {
+		self.locked.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+		self.inner.lock_block(sub_id, hash, to_reserve)
+	}
+
+	fn get_operation(&self, sub_id: &str, operation_id: &str) -> Option<OperationState> {
+		self.inner.get_operation(sub_id, operation_id)
+	}
+
+	fn record_bandwidth(&self, sub_id: &str, bytes: u64) {
+		self.inner.record_bandwidth(sub_id, bytes)
+	}
+
+	fn is_throttled(&self, sub_id: &str) -> bool {
+		self.inner.is_throttled(sub_id)
+	}
+
+	fn bytes_served(&self, sub_id: &str) -> Option<u64> {
+		self.inner.bytes_served(sub_id)
+	}
+
+	fn into_follow_subscription_pins(
+		self: Arc<Self>,
+	) -> Arc<dyn FollowSubscriptionPins<BlockHash>> {
+		self
+	}
+}
+
+#[tokio::test]
+async fn custom_pin_store() {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let mut client = Arc::new(builder.build());
+
+	let api = ChainHead::new(
+		client.clone(),
+		backend,
+		Arc::new(TaskExecutor::default()),
+		CHAIN_GENESIS,
+		ChainHeadConfig {
+			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
+			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
+			subscription_max_ongoing_operations: MAX_OPERATIONS,
+			operation_max_storage_items: MAX_PAGINATION_LIMIT,
+			..Default::default()
+		},
+	);
+	let store = Arc::new(CountingPinStore { inner: api.pin_store(), locked: Default::default() });
+	let api = api.with_pin_store(store.clone()).into_rpc();
+
+	let mut sub = api.subscribe("chainHead_unstable_follow", [false]).await.unwrap();
+	let sub_id = sub.subscription_id();
+	let sub_id = serde_json::to_string(&sub_id).unwrap();
+
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::Initialized(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
+
+	// The blocks are pinned and locked by the custom store.
+	let res: String = api.call("chainHead_unstable_header", [&sub_id, &block_hash]).await.unwrap();
+	let bytes = array_bytes::hex2bytes(&res).unwrap();
+	let header: Header = Decode::decode(&mut &bytes[..]).unwrap();
+	assert_eq!(header, block.header);
+	assert_eq!(store.locked.load(std::sync::atomic::Ordering::SeqCst), 1);
+	assert_eq!(store.pinned_blocks(&sub_id).map(|pinned| pinned.len()), Some(2));
+}
@@ -25,19 +25,15 @@ use sc_chain_spec::Properties;
 pub trait ChainSpecApi {
 	/// Get the chain name, as present in the chain specification.
 	///
-	/// # Unstable
-	///
-	/// This method is unstable and subject to change in the future.
-	#[method(name = "chainSpec_unstable_chainName")]
-	fn chain_spec_unstable_chain_name(&self) -> RpcResult<String>;
+	/// The method is also exposed as `chainSpec_unstable_chainName`.
+	#[method(name = "chainSpec_v1_chainName", aliases = ["chainSpec_unstable_chainName"])]
+	fn chain_spec_v1_chain_name(&self) -> RpcResult<String>;
 
 	/// Get the chain's genesis hash.
 	///
-	/// # Unstable
-	///
-	/// This method is unstable and subject to change in the future.
-	#[method(name = "chainSpec_unstable_genesisHash")]
-	fn chain_spec_unstable_genesis_hash(&self) -> RpcResult<String>;
+	/// The method is also exposed as `chainSpec_unstable_genesisHash`.
+	#[method(name = "chainSpec_v1_genesisHash", aliases = ["chainSpec_unstable_genesisHash"])]
+	fn chain_spec_v1_genesis_hash(&self) -> RpcResult<String>;
 
 	/// Get the properties of the chain, as present in the chain specification.
 	///
@@ -45,9 +41,23 @@ pub trait ChainSpecApi {
 	///
 	/// The json whitespaces are not guaranteed to persist.
 	///
+	/// The method is also exposed as `chainSpec_unstable_properties`.
+	#[method(name = "chainSpec_v1_properties", aliases = ["chainSpec_unstable_properties"])]
+	fn chain_spec_v1_properties(&self) -> RpcResult<Properties>;
+
+	/// Export the chain specification of the node, in the raw or plain form.
+	///
+	/// The raw form contains the genesis storage, while the plain form contains
+	/// the runtime genesis configuration.
+	///
+	/// # Unsafe
+	///
+	/// This method is exposed only by the servers that allow unsafe methods.
+	/// Otherwise, an error is returned.
+	///
 	/// # Unstable
 	///
 	/// This method is unstable and subject to change in the future.
-	#[method(name = "chainSpec_unstable_properties")]
-	fn chain_spec_unstable_properties(&self) -> RpcResult<Properties>;
+	#[method(name = "chainSpec_unstable_export")]
+	fn chain_spec_unstable_export(&self, raw: bool) -> RpcResult<serde_json::Value>;
 }
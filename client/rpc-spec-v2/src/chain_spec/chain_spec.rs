@@ -18,7 +18,7 @@
 
 //! API implementation for the specification of a chain.
 
-use crate::chain_spec::api::ChainSpecApiServer;
+use crate::chain_spec::{api::ChainSpecApiServer, error::Error as ChainSpecRpcError};
 use jsonrpsee::core::RpcResult;
 use sc_chain_spec::Properties;
 
@@ -30,6 +30,8 @@ pub struct ChainSpec {
 	genesis_hash: String,
 	/// Chain properties.
 	properties: Properties,
+	/// The chain specification exported by `chainSpec_unstable_export`.
+	export: Option<Box<dyn sc_chain_spec::ChainSpec>>,
 }
 
 impl ChainSpec {
@@ -41,20 +43,38 @@ impl ChainSpec {
 	) -> Self {
 		let genesis_hash = format!("0x{}", hex::encode(genesis_hash));
 
-		Self { name, properties, genesis_hash }
+		Self { name, properties, genesis_hash, export: None }
+	}
+
+	/// Enable exporting the given chain specification via `chainSpec_unstable_export`.
+	///
+	/// This must be enabled only for servers that allow unsafe methods.
+	pub fn with_export(mut self, chain_spec: Box<dyn sc_chain_spec::ChainSpec>) -> Self {
+		self.export = Some(chain_spec);
+		self
 	}
 }
 
 impl ChainSpecApiServer for ChainSpec {
-	fn chain_spec_unstable_chain_name(&self) -> RpcResult<String> {
+	fn chain_spec_v1_chain_name(&self) -> RpcResult<String> {
 		Ok(self.name.clone())
 	}
 
-	fn chain_spec_unstable_genesis_hash(&self) -> RpcResult<String> {
+	fn chain_spec_v1_genesis_hash(&self) -> RpcResult<String> {
 		Ok(self.genesis_hash.clone())
 	}
 
-	fn chain_spec_unstable_properties(&self) -> RpcResult<Properties> {
+	fn chain_spec_v1_properties(&self) -> RpcResult<Properties> {
 		Ok(self.properties.clone())
 	}
+
+	fn chain_spec_unstable_export(&self, raw: bool) -> RpcResult<serde_json::Value> {
+		let Some(chain_spec) = self.export.as_ref() else {
+			return Err(ChainSpecRpcError::ExportDisabled.into())
+		};
+
+		let json = chain_spec.as_json(raw).map_err(ChainSpecRpcError::ExportFailed)?;
+		serde_json::from_str(&json)
+			.map_err(|err| ChainSpecRpcError::ExportFailed(err.to_string()).into())
+	}
 }
@@ -0,0 +1,59 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Error helpers for `chainSpec` RPC module.
+
+use jsonrpsee::{
+	core::Error as RpcError,
+	types::error::{CallError, ErrorObject},
+};
+
+/// ChainSpec RPC errors.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// Exporting the chain specification is not enabled.
+	#[error("Exporting the chain specification is not enabled")]
+	ExportDisabled,
+	/// The chain specification could not be exported.
+	#[error("Could not export the chain specification: {0}")]
+	ExportFailed(String),
+}
+
+// Base code for all `chainSpec` errors.
+const BASE_ERROR: i32 = 4000;
+/// Exporting the chain specification is not enabled.
+const EXPORT_DISABLED_ERROR: i32 = BASE_ERROR + 1;
+/// The chain specification could not be exported.
+const EXPORT_FAILED_ERROR: i32 = BASE_ERROR + 2;
+
+impl From<Error> for ErrorObject<'static> {
+	fn from(e: Error) -> Self {
+		let msg = e.to_string();
+
+		match e {
+			Error::ExportDisabled => ErrorObject::owned(EXPORT_DISABLED_ERROR, msg, None::<()>),
+			Error::ExportFailed(_) => ErrorObject::owned(EXPORT_FAILED_ERROR, msg, None::<()>),
+		}
+		.into()
+	}
+}
+
+impl From<Error> for RpcError {
+	fn from(e: Error) -> Self {
+		CallError::Custom(e.into()).into()
+	}
+}
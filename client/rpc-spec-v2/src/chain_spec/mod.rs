@@ -33,6 +33,13 @@ mod tests;
 
 pub mod api;
 pub mod chain_spec;
+pub mod error;
 
 pub use api::ChainSpecApiServer;
 pub use chain_spec::ChainSpec;
+
+/// The methods that are unsafe to expose to untrusted users.
+///
+/// The methods are registered by every server, but they are only functional if the
+/// server allows unsafe methods, see [`ChainSpec::with_export`].
+pub const UNSAFE_METHODS: &[&str] = &["chainSpec_unstable_export"];
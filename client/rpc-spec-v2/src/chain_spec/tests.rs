@@ -17,8 +17,14 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use super::*;
-use jsonrpsee::{types::EmptyServerParams as EmptyParams, RpcModule};
-use sc_chain_spec::Properties;
+use assert_matches::assert_matches;
+use jsonrpsee::{
+	core::error::Error,
+	rpc_params,
+	types::{error::CallError, EmptyServerParams as EmptyParams},
+	RpcModule,
+};
+use sc_chain_spec::{ChainType, GenericChainSpec, Properties};
 
 const CHAIN_NAME: &'static str = "TEST_CHAIN_NAME";
 const CHAIN_GENESIS: [u8; 32] = [0; 32];
@@ -59,3 +65,81 @@ async fn chain_spec_properties_works() {
 		.unwrap();
 	assert_eq!(properties, serde_json::from_str(CHAIN_PROPERTIES).unwrap());
 }
+
+#[tokio::test]
+async fn chain_spec_v1_methods_work() {
+	let api = api();
+
+	let name = api
+		.call::<_, String>("chainSpec_v1_chainName", EmptyParams::new())
+		.await
+		.unwrap();
+	assert_eq!(name, CHAIN_NAME);
+
+	let genesis = api
+		.call::<_, String>("chainSpec_v1_genesisHash", EmptyParams::new())
+		.await
+		.unwrap();
+	assert_eq!(genesis, format!("0x{}", hex::encode(CHAIN_GENESIS)));
+
+	let properties = api
+		.call::<_, Properties>("chainSpec_v1_properties", EmptyParams::new())
+		.await
+		.unwrap();
+	assert_eq!(properties, serde_json::from_str(CHAIN_PROPERTIES).unwrap());
+}
+
+#[test]
+fn chain_spec_unsafe_methods_are_registered() {
+	let api = api();
+	for method in UNSAFE_METHODS {
+		assert!(api.method(method).is_some(), "{method} is not registered");
+	}
+}
+
+#[tokio::test]
+async fn chain_spec_export_disabled() {
+	let err = api()
+		.call::<_, serde_json::Value>("chainSpec_unstable_export", rpc_params![false])
+		.await
+		.unwrap_err();
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == 4001 && err.message().contains("not enabled")
+	);
+}
+
+#[tokio::test]
+async fn chain_spec_export_works() {
+	let chain_spec = GenericChainSpec::<()>::from_genesis(
+		CHAIN_NAME,
+		"test_chain",
+		ChainType::Development,
+		|| (),
+		Vec::new(),
+		None,
+		None,
+		None,
+		serde_json::from_str(CHAIN_PROPERTIES).unwrap(),
+		None,
+	);
+	let api = ChainSpec::new(
+		CHAIN_NAME.to_string(),
+		CHAIN_GENESIS,
+		serde_json::from_str(CHAIN_PROPERTIES).unwrap(),
+	)
+	.with_export(Box::new(chain_spec))
+	.into_rpc();
+
+	let exported = api
+		.call::<_, serde_json::Value>("chainSpec_unstable_export", rpc_params![false])
+		.await
+		.unwrap();
+	assert_eq!(exported["name"], CHAIN_NAME);
+	assert_eq!(exported["id"], "test_chain");
+	assert_eq!(
+		exported["properties"],
+		serde_json::from_str::<serde_json::Value>(CHAIN_PROPERTIES).unwrap()
+	);
+	// The plain form contains the runtime genesis configuration.
+	assert!(exported["genesis"].get("runtime").is_some());
+}
@@ -0,0 +1,84 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! API trait of the dev methods.
+
+use crate::chain_head::event::PinnedBlock;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use serde::{Deserialize, Serialize};
+
+/// The result of the `dev_unstable_revertBlocks` method.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevertedBlocks<Hash> {
+	/// The number of blocks that were reverted.
+	///
+	/// This may be higher than requested, because the leaves above the
+	/// best block are reverted as well.
+	pub num_reverted: u32,
+	/// The best block of the chain after the revert.
+	pub best_block_hash: Hash,
+	/// The number of `chainHead_follow` subscriptions that were stopped.
+	pub stopped_subscriptions: u32,
+}
+
+/// A `chainHead_follow` subscription of the node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FollowSubscription<Hash> {
+	/// The subscription ID.
+	pub subscription_id: String,
+	/// The subscription was started with the runtime updates flag.
+	pub with_runtime: bool,
+	/// The blocks pinned by the subscription, ordered from the oldest pin.
+	pub pinned_blocks: Vec<PinnedBlock<Hash>>,
+}
+
+#[rpc(client, server)]
+pub trait DevApi<Hash> {
+	/// Revert the given number of non-finalized blocks of the best chain.
+	///
+	/// The `chainHead_follow` subscriptions that track any of the reverted blocks
+	/// receive the `Stop` event and must be re-initialized by the user.
+	///
+	/// # Unsafe
+	///
+	/// This method is only meant for test networks and is exposed only by the
+	/// servers that allow unsafe methods.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[method(name = "dev_unstable_revertBlocks")]
+	fn dev_unstable_revert_blocks(&self, num_blocks: u32) -> RpcResult<RevertedBlocks<Hash>>;
+
+	/// List the `chainHead_follow` subscriptions of the node, with their pinned blocks
+	/// and the time elapsed since the blocks were pinned.
+	///
+	/// This helps to find the clients that never unpin their blocks.
+	///
+	/// # Unsafe
+	///
+	/// This method exposes the subscriptions of all the clients and is exposed only by
+	/// the servers that allow unsafe methods.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[method(name = "dev_unstable_followSubscriptions")]
+	fn dev_unstable_follow_subscriptions(&self) -> RpcResult<Vec<FollowSubscription<Hash>>>;
+}
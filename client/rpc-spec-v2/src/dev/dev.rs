@@ -0,0 +1,117 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! API implementation for the dev methods.
+
+use crate::{
+	chain_head::{chain_head::LOG_TARGET, event::PinnedBlock, FollowSubscriptionPins},
+	dev::{
+		api::{DevApiServer, FollowSubscription, RevertedBlocks},
+		error::Error as DevRpcError,
+	},
+};
+use jsonrpsee::core::RpcResult;
+use log::debug;
+use sc_client_api::Backend;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	traits::{Block as BlockT, NumberFor, UniqueSaturatedInto},
+	SaturatedConversion,
+};
+use std::{marker::PhantomData, sync::Arc};
+
+/// An API for dev RPC calls.
+pub struct Dev<BE: Backend<Block>, Block: BlockT, Client> {
+	/// Substrate client.
+	client: Arc<Client>,
+	/// Backend of the chain.
+	backend: Arc<BE>,
+	/// The blocks tracked by the `chainHead_follow` subscriptions.
+	follow_subscriptions: Arc<dyn FollowSubscriptionPins<Block::Hash>>,
+	/// Phantom member to pin the block type.
+	_phantom: PhantomData<Block>,
+}
+
+impl<BE: Backend<Block>, Block: BlockT, Client> Dev<BE, Block, Client> {
+	/// Create a new [`Dev`].
+	pub fn new(
+		client: Arc<Client>,
+		backend: Arc<BE>,
+		follow_subscriptions: Arc<dyn FollowSubscriptionPins<Block::Hash>>,
+	) -> Self {
+		Self { client, backend, follow_subscriptions, _phantom: PhantomData }
+	}
+}
+
+impl<BE, Block, Client> DevApiServer<Block::Hash> for Dev<BE, Block, Client>
+where
+	Block: BlockT + 'static,
+	BE: Backend<Block> + 'static,
+	Client: HeaderBackend<Block> + 'static,
+{
+	fn dev_unstable_revert_blocks(
+		&self,
+		num_blocks: u32,
+	) -> RpcResult<RevertedBlocks<Block::Hash>> {
+		let num_blocks: NumberFor<Block> = num_blocks.into();
+		let (num_reverted, _) =
+			self.backend.revert(num_blocks, false).map_err(DevRpcError::RevertFailed)?;
+
+		let info = self.client.info();
+		let best_number = info.best_number;
+
+		// The reverted blocks are no longer available in the database, and all the
+		// blocks above the best block were reverted.
+		let stopped_subscriptions =
+			self.follow_subscriptions
+				.stop_subscriptions_with(&|hash: &Block::Hash| match self.client.number(*hash) {
+					Ok(Some(number)) => number > best_number,
+					_ => true,
+				});
+
+		debug!(
+			target: LOG_TARGET,
+			"[dev] Reverted {} blocks, best block #{} ({:?}), stopped {} follow subscriptions",
+			num_reverted,
+			best_number,
+			info.best_hash,
+			stopped_subscriptions,
+		);
+
+		Ok(RevertedBlocks {
+			num_reverted: num_reverted.unique_saturated_into(),
+			best_block_hash: info.best_hash,
+			stopped_subscriptions: stopped_subscriptions.saturated_into(),
+		})
+	}
+
+	fn dev_unstable_follow_subscriptions(&self) -> RpcResult<Vec<FollowSubscription<Block::Hash>>> {
+		let subscriptions = self.follow_subscriptions.subscriptions_info();
+		Ok(subscriptions
+			.into_iter()
+			.map(|info| FollowSubscription {
+				subscription_id: info.sub_id,
+				with_runtime: info.with_runtime,
+				pinned_blocks: info
+					.pinned_blocks
+					.into_iter()
+					.map(|(hash, age)| PinnedBlock { hash, pin_age_millis: age.as_millis() as u64 })
+					.collect(),
+			})
+			.collect())
+	}
+}
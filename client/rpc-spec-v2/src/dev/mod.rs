@@ -0,0 +1,39 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Substrate dev API.
+//!
+//! The dev methods allow test harnesses to manipulate the chain of a development
+//! network. The methods are unsafe and must not be exposed to untrusted users.
+//!
+//! # Note
+//!
+//! Methods are prefixed by `dev`.
+
+#[cfg(test)]
+mod tests;
+
+pub mod api;
+pub mod dev;
+pub mod error;
+
+pub use api::{DevApiServer, FollowSubscription, RevertedBlocks};
+pub use dev::Dev;
+
+/// The methods that are unsafe to expose to untrusted users.
+pub const UNSAFE_METHODS: &[&str] =
+	&["dev_unstable_revertBlocks", "dev_unstable_followSubscriptions"];
@@ -0,0 +1,138 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use super::*;
+use crate::chain_head::{event::FollowEvent, ChainHead, ChainHeadConfig};
+use assert_matches::assert_matches;
+use jsonrpsee::{
+	core::server::rpc_module::Subscription as RpcSubscription, rpc_params, types::SubscriptionId,
+};
+use sc_block_builder::BlockBuilderProvider;
+use sp_blockchain::HeaderBackend;
+use sp_consensus::BlockOrigin;
+use sp_core::testing::TaskExecutor;
+use std::sync::Arc;
+use substrate_test_runtime_client::{prelude::*, ClientBlockImportExt};
+
+type Block = substrate_test_runtime_client::runtime::Block;
+const CHAIN_GENESIS: [u8; 32] = [0; 32];
+
+async fn get_next_event<T: serde::de::DeserializeOwned>(sub: &mut RpcSubscription) -> T {
+	let (event, _sub_id) = tokio::time::timeout(std::time::Duration::from_secs(60), sub.next())
+		.await
+		.unwrap()
+		.unwrap()
+		.unwrap();
+	event
+}
+
+#[tokio::test]
+async fn revert_blocks_stops_follow_subscriptions() {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let mut client = Arc::new(builder.build());
+
+	let chain_head = ChainHead::new(
+		client.clone(),
+		backend.clone(),
+		Arc::new(TaskExecutor::default()),
+		CHAIN_GENESIS,
+		ChainHeadConfig::default(),
+	);
+	let dev =
+		Dev::<_, Block, _>::new(client.clone(), backend, chain_head.follow_subscription_pins())
+			.into_rpc();
+	let chain_head = chain_head.into_rpc();
+
+	let genesis_hash = client.info().genesis_hash;
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_1_hash = block.header.hash();
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	let mut sub = chain_head.subscribe("chainHead_unstable_follow", [false]).await.unwrap();
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::Initialized(_)
+	);
+	// The non-finalized block is reported.
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
+	assert_eq!(client.info().best_hash, block_1_hash);
+
+	let reverted: RevertedBlocks<String> =
+		dev.call("dev_unstable_revertBlocks", rpc_params![1]).await.unwrap();
+	assert_eq!(
+		reverted,
+		RevertedBlocks {
+			num_reverted: 1,
+			best_block_hash: format!("{:?}", genesis_hash),
+			stopped_subscriptions: 1,
+		}
+	);
+
+	// The subscription tracked the reverted block.
+	assert_matches!(get_next_event::<FollowEvent<String>>(&mut sub).await, FollowEvent::Stop);
+}
+
+#[tokio::test]
+async fn follow_subscriptions_list_pinned_blocks() {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let client = Arc::new(builder.build());
+
+	let chain_head = ChainHead::new(
+		client.clone(),
+		backend.clone(),
+		Arc::new(TaskExecutor::default()),
+		CHAIN_GENESIS,
+		ChainHeadConfig::default(),
+	);
+	let dev =
+		Dev::<_, Block, _>::new(client.clone(), backend, chain_head.follow_subscription_pins())
+			.into_rpc();
+	let chain_head = chain_head.into_rpc();
+
+	let subscriptions: Vec<FollowSubscription<String>> =
+		dev.call("dev_unstable_followSubscriptions", rpc_params![]).await.unwrap();
+	assert!(subscriptions.is_empty());
+
+	let mut sub = chain_head.subscribe("chainHead_unstable_follow", [true]).await.unwrap();
+	let sub_id = match sub.subscription_id() {
+		SubscriptionId::Num(num) => num.to_string(),
+		SubscriptionId::Str(id) => id.to_string(),
+	};
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::Initialized(_)
+	);
+
+	// The finalized block reported by the `Initialized` event is pinned.
+	let subscriptions: Vec<FollowSubscription<String>> =
+		dev.call("dev_unstable_followSubscriptions", rpc_params![]).await.unwrap();
+	assert_eq!(subscriptions.len(), 1);
+	assert_eq!(subscriptions[0].subscription_id, sub_id);
+	assert!(subscriptions[0].with_runtime);
+	let pinned: Vec<_> =
+		subscriptions[0].pinned_blocks.iter().map(|block| block.hash.clone()).collect();
+	assert_eq!(pinned, vec![format!("{:?}", client.info().genesis_hash)]);
+}
@@ -0,0 +1,41 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! API trait of the system events.
+
+use jsonrpsee::proc_macros::rpc;
+use sp_rpc_spec_v2::events::{EventFilter, SystemEventsEvent};
+
+#[rpc(client, server)]
+pub trait SystemEventsApi<Hash> {
+	/// Subscribe to the events deposited by the new best blocks, or by the newly finalized
+	/// blocks if `finalized` is true, decoded using the metadata of the runtime.
+	///
+	/// A `block` event is generated for each block, reporting the events of the block that
+	/// are selected by any of the `filters`, or all the events if no filter is provided. An
+	/// `error` event is generated instead if the events of the block cannot be decoded.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[subscription(
+		name = "events_unstable_follow" => "events_unstable_followEvent",
+		unsubscribe = "events_unstable_unfollow",
+		item = SystemEventsEvent<Hash>,
+	)]
+	fn events_unstable_follow(&self, finalized: bool, filters: Option<Vec<EventFilter>>);
+}
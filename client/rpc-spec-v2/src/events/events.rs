@@ -0,0 +1,255 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! API implementation for the system events.
+
+use crate::{
+	chain_head::decode::{decode_storage_value, CallOutputDecoder},
+	events::api::SystemEventsApiServer,
+	SubscriptionTaskExecutor,
+};
+use futures::{future, FutureExt, StreamExt};
+use jsonrpsee::{types::SubscriptionResult, SubscriptionSink};
+use log::debug;
+use sc_client_api::{BlockchainEvents, ExecutorProvider};
+use serde_json::Value;
+use sp_api::{CallApiAt, StateBackend};
+use sp_core::twox_128;
+use sp_rpc_spec_v2::events::{
+	BlockEvents, BlockEventsError, EventFilter, SystemEvent, SystemEventsEvent,
+};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use std::{marker::PhantomData, sync::Arc};
+
+/// The logging target.
+const LOG_TARGET: &str = "rpc-spec-v2::events";
+
+/// An API to subscribe to the decoded system events.
+pub struct SystemEvents<Block, Client> {
+	/// Substrate client.
+	client: Arc<Client>,
+	/// Executor to spawn subscriptions.
+	executor: SubscriptionTaskExecutor,
+	/// The metadata of the most recently used runtime, shared by the subscriptions.
+	metadata: Arc<CallOutputDecoder>,
+	/// Phantom member to pin the block type.
+	_phantom: PhantomData<Block>,
+}
+
+impl<Block, Client> SystemEvents<Block, Client> {
+	/// Creates a new [`SystemEvents`].
+	pub fn new(client: Arc<Client>, executor: SubscriptionTaskExecutor) -> Self {
+		Self { client, executor, metadata: Default::default(), _phantom: PhantomData }
+	}
+}
+
+impl<Block, Client> SystemEventsApiServer<Block::Hash> for SystemEvents<Block, Client>
+where
+	Block: BlockT + 'static,
+	Client: BlockchainEvents<Block>
+		+ CallApiAt<Block>
+		+ ExecutorProvider<Block>
+		+ Send
+		+ Sync
+		+ 'static,
+{
+	fn events_unstable_follow(
+		&self,
+		mut sink: SubscriptionSink,
+		finalized: bool,
+		filters: Option<Vec<EventFilter>>,
+	) -> SubscriptionResult {
+		let blocks = if finalized {
+			self.client
+				.finality_notification_stream()
+				.map(|notification| (notification.hash, *notification.header.parent_hash()))
+				.boxed()
+		} else {
+			self.client
+				.import_notification_stream()
+				.filter(|notification| future::ready(notification.is_new_best))
+				.map(|notification| (notification.hash, *notification.header.parent_hash()))
+				.boxed()
+		};
+
+		let client = self.client.clone();
+		let metadata = self.metadata.clone();
+		let filters = filters.unwrap_or_default();
+		let stream = blocks.map(move |(hash, parent_hash)| {
+			match block_events(&*client, &metadata, hash, parent_hash, &filters) {
+				Ok(events) => SystemEventsEvent::Block(BlockEvents { hash, events }),
+				Err(error) => {
+					debug!(target: LOG_TARGET, "Cannot decode the events of {:?}: {}", hash, error);
+					SystemEventsEvent::Error(BlockEventsError { hash, error })
+				},
+			}
+		});
+
+		let fut = async move {
+			sink.pipe_from_stream(stream.boxed()).await;
+		};
+
+		self.executor.spawn("substrate-rpc-subscription", Some("rpc"), fut.boxed());
+		Ok(())
+	}
+}
+
+/// Decode the events deposited by the block, selected by the filters.
+fn block_events<Block, Client>(
+	client: &Client,
+	metadata: &CallOutputDecoder,
+	hash: Block::Hash,
+	parent_hash: Block::Hash,
+	filters: &[EventFilter],
+) -> Result<Vec<SystemEvent>, String>
+where
+	Block: BlockT,
+	Client: CallApiAt<Block> + ExecutorProvider<Block>,
+{
+	// The events are deposited by the runtime of the parent block, which is not the runtime
+	// of the block if the block upgraded the runtime.
+	let metadata = metadata.metadata::<Block, _>(client, parent_hash)?;
+
+	let key = [twox_128(b"System"), twox_128(b"Events")].concat();
+	let value = client
+		.state_at(hash)
+		.map_err(|error| error.to_string())?
+		.storage(&key)
+		.map_err(|error| error.to_string())?;
+	let Some(value) = value else { return Ok(Vec::new()) };
+
+	let records = decode_storage_value(&metadata, "System", "Events", &value)?;
+	system_events(records, filters)
+}
+
+/// Split the decoded event records, keeping the events selected by the filters.
+fn system_events(records: Value, filters: &[EventFilter]) -> Result<Vec<SystemEvent>, String> {
+	let Value::Array(records) = records else { return Err("The events are not a sequence".into()) };
+
+	let mut events = Vec::new();
+	for record in records {
+		let event = system_event(record)?;
+		if filters.is_empty() ||
+			filters.iter().any(|filter| filter.matches(&event.pallet, &event.event))
+		{
+			events.push(event);
+		}
+	}
+
+	Ok(events)
+}
+
+/// Split an event record, decoded as an object of its `phase`, `event` and `topics`.
+///
+/// The event is decoded as an object keyed by the name of the pallet, holding the event of
+/// the pallet keyed by its name.
+fn system_event(record: Value) -> Result<SystemEvent, String> {
+	let Value::Object(mut record) = record else {
+		return Err("The event record is not an object".into())
+	};
+
+	let (pallet, event) = variant(record.remove("event").unwrap_or_default())?;
+	let (event, fields) = variant(event)?;
+
+	Ok(SystemEvent {
+		pallet,
+		event,
+		fields,
+		phase: record.remove("phase").unwrap_or_default(),
+		topics: record.remove("topics").unwrap_or_default(),
+	})
+}
+
+/// Split a decoded variant into its name and its fields.
+fn variant(value: Value) -> Result<(String, Value), String> {
+	match value {
+		Value::String(name) => Ok((name, Value::Null)),
+		Value::Object(object) if object.len() == 1 =>
+			Ok(object.into_iter().next().expect("The object has one entry; qed")),
+		value => Err(format!("The event {} is not a variant", value)),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	fn records() -> Value {
+		json!([
+			{
+				"phase": { "ApplyExtrinsic": 1 },
+				"event": { "Balances": { "Transfer": { "from": "0x01", "to": "0x02", "amount": "10" } } },
+				"topics": [],
+			},
+			{
+				"phase": "Finalization",
+				"event": { "System": "CodeUpdated" },
+				"topics": [],
+			},
+		])
+	}
+
+	#[test]
+	fn split_events() {
+		let events = system_events(records(), &[]).unwrap();
+		assert_eq!(
+			events,
+			vec![
+				SystemEvent {
+					pallet: "Balances".into(),
+					event: "Transfer".into(),
+					fields: json!({ "from": "0x01", "to": "0x02", "amount": "10" }),
+					phase: json!({ "ApplyExtrinsic": 1 }),
+					topics: json!([]),
+				},
+				SystemEvent {
+					pallet: "System".into(),
+					event: "CodeUpdated".into(),
+					fields: Value::Null,
+					phase: json!("Finalization"),
+					topics: json!([]),
+				},
+			]
+		);
+
+		assert!(system_events(json!({}), &[]).is_err());
+		assert!(system_events(json!([{ "event": [] }]), &[]).is_err());
+	}
+
+	#[test]
+	fn filter_events() {
+		let filter = |pallet: &str, event: Option<&str>| EventFilter {
+			pallet: pallet.into(),
+			event: event.map(Into::into),
+		};
+		let names = |filters: &[EventFilter]| {
+			system_events(records(), filters)
+				.unwrap()
+				.into_iter()
+				.map(|event| event.event)
+				.collect::<Vec<_>>()
+		};
+
+		assert_eq!(names(&[filter("System", None)]), vec!["CodeUpdated"]);
+		assert_eq!(names(&[filter("Balances", Some("Deposit"))]), Vec::<String>::new());
+		assert_eq!(
+			names(&[filter("Balances", Some("Transfer")), filter("System", None)]),
+			vec!["Transfer", "CodeUpdated"]
+		);
+	}
+}
@@ -0,0 +1,76 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use super::*;
+use jsonrpsee::{core::server::rpc_module::Subscription as RpcSubscription, RpcModule};
+use sc_block_builder::BlockBuilderProvider;
+use sp_consensus::BlockOrigin;
+use sp_core::testing::TaskExecutor;
+use sp_rpc_spec_v2::events::SystemEventsEvent;
+use std::{sync::Arc, time::Duration};
+use substrate_test_runtime_client::{
+	prelude::*,
+	runtime::{Block, Hash},
+	Client, ClientBlockImportExt,
+};
+
+fn setup_api() -> (Arc<Client<Backend>>, RpcModule<SystemEvents<Block, Client<Backend>>>) {
+	let client = Arc::new(substrate_test_runtime_client::new());
+	let api = SystemEvents::new(client.clone(), Arc::new(TaskExecutor::default())).into_rpc();
+	(client, api)
+}
+
+async fn get_next_event(sub: &mut RpcSubscription) -> SystemEventsEvent<Hash> {
+	let (event, _sub_id) = tokio::time::timeout(Duration::from_secs(60), sub.next())
+		.await
+		.unwrap()
+		.unwrap()
+		.unwrap();
+	event
+}
+
+#[tokio::test]
+async fn follow_best_blocks_without_metadata() {
+	let (mut client, api) = setup_api();
+	let mut sub = api.subscribe("events_unstable_follow", [false]).await.unwrap();
+
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let hash = block.header.hash();
+	client.import(BlockOrigin::Own, block).await.unwrap();
+
+	// The test runtime does not provide its metadata, so the events cannot be decoded.
+	match get_next_event(&mut sub).await {
+		SystemEventsEvent::Error(error) => assert_eq!(error.hash, hash),
+		event => panic!("Unexpected event {:?}", event),
+	}
+}
+
+#[tokio::test]
+async fn follow_finalized_blocks_without_metadata() {
+	let (mut client, api) = setup_api();
+	let mut sub = api.subscribe("events_unstable_follow", [true]).await.unwrap();
+
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let hash = block.header.hash();
+	client.import(BlockOrigin::Own, block).await.unwrap();
+	client.finalize_block(hash, None).unwrap();
+
+	match get_next_event(&mut sub).await {
+		SystemEventsEvent::Error(error) => assert_eq!(error.hash, hash),
+		event => panic!("Unexpected event {:?}", event),
+	}
+}
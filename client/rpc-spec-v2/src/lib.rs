@@ -23,9 +23,23 @@
 #![warn(missing_docs)]
 #![deny(unused_crate_dependencies)]
 
+pub mod archive;
 pub mod chain_head;
 pub mod chain_spec;
+pub mod dev;
+pub mod events;
+pub mod runtime_api;
+pub mod sudo;
 pub mod transaction;
 
 /// Task executor that is being used by RPC subscriptions.
 pub type SubscriptionTaskExecutor = std::sync::Arc<dyn sp_core::traits::SpawnNamed>;
+
+/// The methods of the specification that are unsafe to expose to untrusted users.
+pub fn unsafe_methods() -> impl Iterator<Item = &'static str> {
+	chain_spec::UNSAFE_METHODS
+		.iter()
+		.chain(dev::UNSAFE_METHODS)
+		.chain(sudo::UNSAFE_METHODS)
+		.copied()
+}
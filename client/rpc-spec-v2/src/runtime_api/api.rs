@@ -0,0 +1,48 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! API trait of the runtime API explorer.
+
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use serde_json::Value;
+
+#[rpc(client, server)]
+pub trait RuntimeApiExplorerApi<Hash> {
+	/// Call a runtime API function with its arguments as JSON, and return its output as JSON.
+	///
+	/// The `function` is named `<api>_<method>`, e.g. `AccountNonceApi_account_nonce`. The
+	/// `args` are either an array of the arguments, in order, or an object keyed by the names
+	/// of the arguments, and may be omitted if the function has no arguments. The function is
+	/// called at the given block, or at the best block if `None`.
+	///
+	/// The arguments and the output use the JSON representation of the runtime types: the
+	/// structs are objects, the enums are their variant name or an object keyed by their
+	/// variant name, the byte sequences are hexadecimal strings and the 128-bit integers are
+	/// decimal strings.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[method(name = "runtimeApi_unstable_call", blocking)]
+	fn runtime_api_unstable_call(
+		&self,
+		function: String,
+		args: Option<Value>,
+		hash: Option<Hash>,
+	) -> RpcResult<Value>;
+}
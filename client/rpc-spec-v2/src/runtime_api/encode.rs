@@ -0,0 +1,332 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Encode the arguments of the runtime calls from JSON using the runtime metadata.
+//!
+//! The JSON representation of the values is the one of the decoded outputs of the calls.
+
+use crate::chain_head::decode::runtime_function;
+use codec::{Compact, Encode};
+use frame_metadata::v15::RuntimeMetadataV15;
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef, TypeDefPrimitive};
+use serde_json::Value;
+
+/// Encode the arguments of the given runtime function as described by the metadata.
+///
+/// The arguments are either an array of the arguments in order, or an object keyed by the
+/// names of the arguments. The functions without arguments accept `null`.
+pub(crate) fn encode_call_input(
+	metadata: &RuntimeMetadataV15,
+	function: &str,
+	args: &Value,
+) -> Result<Vec<u8>, String> {
+	let inputs = &runtime_function(metadata, function)?.inputs;
+
+	let args = match args {
+		Value::Null if inputs.is_empty() => Vec::new(),
+		Value::Array(args) if args.len() == inputs.len() => args.iter().collect(),
+		Value::Object(args) if args.len() == inputs.len() => inputs
+			.iter()
+			.map(|input| {
+				args.get(&input.name).ok_or_else(|| format!("Missing argument {}", input.name))
+			})
+			.collect::<Result<_, _>>()?,
+		_ => return Err(format!("{} expects {} arguments", function, inputs.len())),
+	};
+
+	let mut output = Vec::new();
+	for (input, arg) in inputs.iter().zip(args) {
+		encode_value(&metadata.types, input.ty.id, arg, &mut output)
+			.map_err(|error| format!("Invalid argument {}: {}", input.name, error))?;
+	}
+	Ok(output)
+}
+
+/// Encode a value of the given type.
+fn encode_value(
+	registry: &PortableRegistry,
+	type_id: u32,
+	value: &Value,
+	output: &mut Vec<u8>,
+) -> Result<(), String> {
+	let ty = registry.resolve(type_id).ok_or_else(|| format!("Unknown type {}", type_id))?;
+
+	match &ty.type_def {
+		TypeDef::Composite(composite) => encode_fields(registry, &composite.fields, value, output),
+		TypeDef::Variant(variant) => {
+			let null = Value::Null;
+			let (name, fields) = match value {
+				Value::String(name) => (name, &null),
+				Value::Object(object) if object.len() == 1 =>
+					object.iter().next().expect("The object has one entry; qed"),
+				_ => return Err(format!("Expected a variant of type {}", type_id)),
+			};
+			let variant = variant
+				.variants
+				.iter()
+				.find(|variant| &variant.name == name)
+				.ok_or_else(|| format!("Unknown variant {} of type {}", name, type_id))?;
+
+			variant.index.encode_to(output);
+			encode_fields(registry, &variant.fields, fields, output)
+		},
+		TypeDef::Sequence(sequence) => {
+			let items = sequence_items(registry, sequence.type_param.id, value)?;
+			Compact(items.len() as u32).encode_to(output);
+			encode_items(registry, sequence.type_param.id, items, output)
+		},
+		TypeDef::Array(array) => {
+			let items = sequence_items(registry, array.type_param.id, value)?;
+			if items.len() != array.len as usize {
+				return Err(format!("Expected {} items, got {}", array.len, items.len()))
+			}
+			encode_items(registry, array.type_param.id, items, output)
+		},
+		TypeDef::Tuple(tuple) if tuple.fields.is_empty() => match value {
+			Value::Null => Ok(()),
+			_ => Err("Expected null".into()),
+		},
+		TypeDef::Tuple(tuple) => match value {
+			Value::Array(items) if items.len() == tuple.fields.len() => tuple
+				.fields
+				.iter()
+				.zip(items)
+				.try_for_each(|(field, item)| encode_value(registry, field.id, item, output)),
+			_ => Err(format!("Expected an array of {} items", tuple.fields.len())),
+		},
+		TypeDef::Primitive(primitive) => encode_primitive(primitive, value, output),
+		TypeDef::Compact(compact) => encode_compact(registry, compact.type_param.id, value, output),
+		TypeDef::BitSequence(_) => Err("Bit sequences are not supported".into()),
+	}
+}
+
+/// Encode the fields of a composite or of a variant.
+fn encode_fields(
+	registry: &PortableRegistry,
+	fields: &[Field<PortableForm>],
+	value: &Value,
+	output: &mut Vec<u8>,
+) -> Result<(), String> {
+	match (fields, value) {
+		([], Value::Null) => Ok(()),
+		([field], value) if field.name.is_none() =>
+			encode_value(registry, field.ty.id, value, output),
+		(fields, Value::Object(object)) if fields.iter().all(|field| field.name.is_some()) =>
+			fields.iter().try_for_each(|field| {
+				let name = field.name.as_deref().unwrap_or_default();
+				let value = object.get(name).ok_or_else(|| format!("Missing field {}", name))?;
+				encode_value(registry, field.ty.id, value, output)
+			}),
+		(fields, Value::Array(items))
+			if items.len() == fields.len() && fields.iter().all(|field| field.name.is_none()) =>
+			fields
+				.iter()
+				.zip(items)
+				.try_for_each(|(field, item)| encode_value(registry, field.ty.id, item, output)),
+		_ => Err(format!("Expected {} fields", fields.len())),
+	}
+}
+
+/// Get the items of a sequence or of an array, the byte sequences being hexadecimal strings.
+fn sequence_items(
+	registry: &PortableRegistry,
+	type_id: u32,
+	value: &Value,
+) -> Result<Vec<Value>, String> {
+	let is_byte = registry
+		.resolve(type_id)
+		.map_or(false, |ty| matches!(ty.type_def, TypeDef::Primitive(TypeDefPrimitive::U8)));
+
+	match value {
+		Value::String(hex) if is_byte => array_bytes::hex2bytes(hex)
+			.map(|bytes| bytes.into_iter().map(Value::from).collect())
+			.map_err(|error| format!("Invalid hexadecimal string: {:?}", error)),
+		Value::Array(items) => Ok(items.clone()),
+		_ => Err("Expected an array".into()),
+	}
+}
+
+/// Encode the items of a sequence or of an array.
+fn encode_items(
+	registry: &PortableRegistry,
+	type_id: u32,
+	items: Vec<Value>,
+	output: &mut Vec<u8>,
+) -> Result<(), String> {
+	items.iter().try_for_each(|item| encode_value(registry, type_id, item, output))
+}
+
+/// Get an unsigned integer, either a number or a decimal string.
+fn as_u128(value: &Value) -> Result<u128, String> {
+	match value {
+		Value::Number(number) => number.as_u64().map(Into::into),
+		Value::String(string) => string.parse().ok(),
+		_ => None,
+	}
+	.ok_or_else(|| format!("Expected an unsigned integer, got {}", value))
+}
+
+/// Get a signed integer, either a number or a decimal string.
+fn as_i128(value: &Value) -> Result<i128, String> {
+	match value {
+		Value::Number(number) => number.as_i64().map(Into::into),
+		Value::String(string) => string.parse().ok(),
+		_ => None,
+	}
+	.ok_or_else(|| format!("Expected an integer, got {}", value))
+}
+
+/// Encode a primitive value.
+fn encode_primitive(
+	primitive: &TypeDefPrimitive,
+	value: &Value,
+	output: &mut Vec<u8>,
+) -> Result<(), String> {
+	fn narrow<T: TryFrom<N>, N: std::fmt::Display + Copy>(value: N) -> Result<T, String> {
+		T::try_from(value).map_err(|_| format!("{} is out of range", value))
+	}
+
+	match primitive {
+		TypeDefPrimitive::Bool => value
+			.as_bool()
+			.ok_or_else(|| "Expected a boolean".to_string())?
+			.encode_to(output),
+		TypeDefPrimitive::Char => {
+			let mut chars = value.as_str().unwrap_or_default().chars();
+			match (chars.next(), chars.next()) {
+				(Some(char), None) => (char as u32).encode_to(output),
+				_ => return Err("Expected a single character".into()),
+			}
+		},
+		TypeDefPrimitive::Str =>
+			value.as_str().ok_or_else(|| "Expected a string".to_string())?.encode_to(output),
+		TypeDefPrimitive::U8 => narrow::<u8, _>(as_u128(value)?)?.encode_to(output),
+		TypeDefPrimitive::U16 => narrow::<u16, _>(as_u128(value)?)?.encode_to(output),
+		TypeDefPrimitive::U32 => narrow::<u32, _>(as_u128(value)?)?.encode_to(output),
+		TypeDefPrimitive::U64 => narrow::<u64, _>(as_u128(value)?)?.encode_to(output),
+		TypeDefPrimitive::U128 => as_u128(value)?.encode_to(output),
+		TypeDefPrimitive::I8 => narrow::<i8, _>(as_i128(value)?)?.encode_to(output),
+		TypeDefPrimitive::I16 => narrow::<i16, _>(as_i128(value)?)?.encode_to(output),
+		TypeDefPrimitive::I32 => narrow::<i32, _>(as_i128(value)?)?.encode_to(output),
+		TypeDefPrimitive::I64 => narrow::<i64, _>(as_i128(value)?)?.encode_to(output),
+		TypeDefPrimitive::I128 => as_i128(value)?.encode_to(output),
+		TypeDefPrimitive::U256 | TypeDefPrimitive::I256 => {
+			let bytes = value
+				.as_str()
+				.and_then(|hex| array_bytes::hex2array::<_, 32>(hex).ok())
+				.ok_or_else(|| "Expected a 32 bytes hexadecimal string".to_string())?;
+			bytes.encode_to(output)
+		},
+	}
+
+	Ok(())
+}
+
+/// Encode a compact value of the given type.
+fn encode_compact(
+	registry: &PortableRegistry,
+	type_id: u32,
+	value: &Value,
+	output: &mut Vec<u8>,
+) -> Result<(), String> {
+	let ty = registry.resolve(type_id).ok_or_else(|| format!("Unknown type {}", type_id))?;
+
+	match &ty.type_def {
+		// The compact encoding of an integer does not depend on its width.
+		TypeDef::Primitive(_) => Compact(as_u128(value)?).encode_to(output),
+		// Compact encoded wrappers, like `Perbill`, encode their single field.
+		TypeDef::Composite(composite) if composite.fields.len() == 1 => {
+			let field = &composite.fields[0];
+			let value = match (&field.name, value) {
+				(Some(name), Value::Object(object)) =>
+					object.get(name).ok_or_else(|| format!("Missing field {}", name))?,
+				_ => value,
+			};
+			return encode_compact(registry, field.ty.id, value, output)
+		},
+		_ => return Err(format!("Type {} cannot be compact encoded", type_id)),
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use scale_info::{meta_type, Registry, TypeInfo};
+
+	fn encode_as<T: TypeInfo + 'static>(value: Value) -> Result<Vec<u8>, String> {
+		let mut registry = Registry::new();
+		let type_id = registry.register_type(&meta_type::<T>()).id;
+		let registry: PortableRegistry = registry.into();
+
+		let mut output = Vec::new();
+		encode_value(&registry, type_id, &value, &mut output)?;
+		Ok(output)
+	}
+
+	#[test]
+	fn encode_primitives() {
+		assert_eq!(encode_as::<bool>(true.into()).unwrap(), true.encode());
+		assert_eq!(encode_as::<u64>(7.into()).unwrap(), 7u64.encode());
+		assert_eq!(encode_as::<i32>((-7).into()).unwrap(), (-7i32).encode());
+		assert_eq!(encode_as::<u128>(u128::MAX.to_string().into()).unwrap(), u128::MAX.encode());
+		assert_eq!(encode_as::<String>("abc".into()).unwrap(), "abc".encode());
+		assert_eq!(encode_as::<Compact<u32>>(1_000.into()).unwrap(), Compact(1_000u32).encode());
+		assert!(encode_as::<u8>(256.into()).is_err());
+		assert!(encode_as::<u32>((-1).into()).is_err());
+	}
+
+	#[test]
+	fn encode_collections() {
+		assert_eq!(encode_as::<Vec<u8>>("0x010203".into()).unwrap(), vec![1u8, 2, 3].encode());
+		assert_eq!(encode_as::<[u16; 2]>(serde_json::json!([1, 2])).unwrap(), [1u16, 2].encode());
+		assert_eq!(
+			encode_as::<(u32, bool)>(serde_json::json!([1, false])).unwrap(),
+			(1u32, false).encode()
+		);
+		assert_eq!(encode_as::<()>(Value::Null).unwrap(), ().encode());
+		assert!(encode_as::<[u16; 2]>(serde_json::json!([1])).is_err());
+	}
+
+	#[test]
+	fn encode_variants() {
+		assert_eq!(
+			encode_as::<Option<u32>>(serde_json::json!({ "Some": 5 })).unwrap(),
+			Some(5u32).encode()
+		);
+		assert_eq!(encode_as::<Option<u32>>("None".into()).unwrap(), None::<u32>.encode());
+		assert!(encode_as::<Option<u32>>("Unknown".into()).is_err());
+	}
+
+	#[test]
+	fn encode_structs() {
+		#[derive(Encode, TypeInfo)]
+		struct Transfer {
+			to: [u8; 2],
+			amount: u128,
+		}
+
+		let value = serde_json::json!({ "to": "0x0102", "amount": "10" });
+		assert_eq!(
+			encode_as::<Transfer>(value).unwrap(),
+			Transfer { to: [1, 2], amount: 10 }.encode()
+		);
+		assert!(encode_as::<Transfer>(serde_json::json!({ "to": "0x0102" })).is_err());
+	}
+}
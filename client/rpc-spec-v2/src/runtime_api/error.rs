@@ -0,0 +1,73 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Error helpers for `runtimeApi` RPC module.
+
+use jsonrpsee::{
+	core::Error as RpcError,
+	types::error::{CallError, ErrorObject},
+};
+
+/// Runtime API explorer RPC errors.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// The function is unknown or its arguments are invalid.
+	#[error("Invalid parameter: {0}")]
+	InvalidParam(String),
+	/// The metadata of the runtime is not available.
+	#[error("Metadata unavailable: {0}")]
+	MetadataUnavailable(String),
+	/// The runtime function failed.
+	#[error("Runtime call failed: {0}")]
+	CallFailed(String),
+	/// The output of the runtime function could not be decoded.
+	#[error("Could not decode the output: {0}")]
+	InvalidOutput(String),
+}
+
+// Base code for all `runtimeApi` errors.
+const BASE_ERROR: i32 = 7000;
+/// The function is unknown or its arguments are invalid.
+const INVALID_PARAM_ERROR: i32 = BASE_ERROR + 1;
+/// The metadata of the runtime is not available.
+const METADATA_UNAVAILABLE_ERROR: i32 = BASE_ERROR + 2;
+/// The runtime function failed.
+const CALL_FAILED_ERROR: i32 = BASE_ERROR + 3;
+/// The output of the runtime function could not be decoded.
+const INVALID_OUTPUT_ERROR: i32 = BASE_ERROR + 4;
+
+impl From<Error> for ErrorObject<'static> {
+	fn from(e: Error) -> Self {
+		let msg = e.to_string();
+
+		match e {
+			Error::InvalidParam(_) => ErrorObject::owned(INVALID_PARAM_ERROR, msg, None::<()>),
+			Error::MetadataUnavailable(_) =>
+				ErrorObject::owned(METADATA_UNAVAILABLE_ERROR, msg, None::<()>),
+			Error::CallFailed(_) => ErrorObject::owned(CALL_FAILED_ERROR, msg, None::<()>),
+			Error::InvalidOutput(_) => ErrorObject::owned(INVALID_OUTPUT_ERROR, msg, None::<()>),
+		}
+		.into()
+	}
+}
+
+impl From<Error> for RpcError {
+	fn from(e: Error) -> Self {
+		CallError::Custom(e.into()).into()
+	}
+}
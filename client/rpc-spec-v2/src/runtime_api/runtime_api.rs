@@ -0,0 +1,80 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! API implementation for the runtime API explorer.
+
+use crate::{
+	chain_head::decode::{decode_call_output, CallOutputDecoder},
+	runtime_api::{api::RuntimeApiExplorerApiServer, encode::encode_call_input, error::Error},
+};
+use jsonrpsee::core::RpcResult;
+use sc_client_api::{CallExecutor, ExecutorProvider};
+use serde_json::Value;
+use sp_api::CallApiAt;
+use sp_blockchain::HeaderBackend;
+use sp_core::traits::CallContext;
+use sp_runtime::traits::Block as BlockT;
+use std::{marker::PhantomData, sync::Arc};
+
+/// An API to call the runtime API functions with JSON arguments.
+pub struct RuntimeApiExplorer<Block, Client> {
+	/// Substrate client.
+	client: Arc<Client>,
+	/// The metadata of the most recently used runtime.
+	metadata: CallOutputDecoder,
+	/// Phantom member to pin the block type.
+	_phantom: PhantomData<Block>,
+}
+
+impl<Block, Client> RuntimeApiExplorer<Block, Client> {
+	/// Creates a new [`RuntimeApiExplorer`].
+	pub fn new(client: Arc<Client>) -> Self {
+		Self { client, metadata: Default::default(), _phantom: PhantomData }
+	}
+}
+
+impl<Block, Client> RuntimeApiExplorerApiServer<Block::Hash> for RuntimeApiExplorer<Block, Client>
+where
+	Block: BlockT + 'static,
+	Client:
+		HeaderBackend<Block> + CallApiAt<Block> + ExecutorProvider<Block> + Send + Sync + 'static,
+{
+	fn runtime_api_unstable_call(
+		&self,
+		function: String,
+		args: Option<Value>,
+		hash: Option<Block::Hash>,
+	) -> RpcResult<Value> {
+		let hash = hash.unwrap_or_else(|| self.client.info().best_hash);
+		let metadata = self
+			.metadata
+			.metadata::<Block, _>(&*self.client, hash)
+			.map_err(Error::MetadataUnavailable)?;
+
+		let input = encode_call_input(&metadata, &function, &args.unwrap_or(Value::Null))
+			.map_err(Error::InvalidParam)?;
+		let output = self
+			.client
+			.executor()
+			.call(hash, &function, &input, CallContext::Offchain)
+			.map_err(|error| Error::CallFailed(error.to_string()))?;
+
+		decode_call_output(&metadata, &function, &output)
+			.map_err(|error| Error::InvalidOutput(error).into())
+	}
+}
@@ -0,0 +1,46 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+use assert_matches::assert_matches;
+use jsonrpsee::{core::error::Error, rpc_params, types::error::CallError};
+use std::sync::Arc;
+use substrate_test_runtime_client::{runtime::Block, Client, TestClientBuilder};
+
+type Api =
+	jsonrpsee::RpcModule<RuntimeApiExplorer<Block, Client<substrate_test_runtime_client::Backend>>>;
+
+fn api() -> Api {
+	let client = Arc::new(TestClientBuilder::new().build());
+	RuntimeApiExplorer::new(client).into_rpc()
+}
+
+#[tokio::test]
+async fn runtime_api_call_without_metadata() {
+	let api = api();
+
+	// The test runtime does not provide its metadata, so the arguments cannot be encoded.
+	let err = api
+		.call::<_, serde_json::Value>(
+			"runtimeApi_unstable_call",
+			rpc_params!["AccountNonceApi_account_nonce", ["0x00"]],
+		)
+		.await
+		.unwrap_err();
+	assert_matches!(err, Error::Call(CallError::Custom(ref err)) if err.code() == 7002);
+}
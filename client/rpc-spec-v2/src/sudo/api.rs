@@ -0,0 +1,59 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! API trait of the sudo methods.
+
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+
+#[rpc(client, server)]
+pub trait SudoApi {
+	/// Get the name and the version of the client.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[method(name = "sudo_unstable_version")]
+	fn sudo_unstable_version(&self) -> RpcResult<String>;
+}
+
+/// The sudo methods that are unsafe to expose to untrusted users.
+///
+/// These methods are registered only by the servers that allow unsafe methods.
+#[rpc(client, server)]
+pub trait SudoUnsafeApi {
+	/// Add the multiaddress to the addresses of the known peers.
+	///
+	/// The multiaddress must end with the `/p2p/` peer ID. The node might use the
+	/// address to connect to the peer.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[method(name = "sudo_unstable_p2pDiscover")]
+	async fn sudo_unstable_p2p_discover(&self, multiaddr: String) -> RpcResult<()>;
+
+	/// Generate new session keys in the keystore of the node.
+	///
+	/// Returns the hex-encoded public keys bundle of the new session keys, which is
+	/// SCALE-encoded by the runtime. This replaces the `author_rotateKeys` method.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[method(name = "sudo_unstable_rotateKeys")]
+	fn sudo_unstable_rotate_keys(&self) -> RpcResult<String>;
+}
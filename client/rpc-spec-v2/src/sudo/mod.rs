@@ -0,0 +1,37 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Substrate sudo API.
+//!
+//! The sudo methods allow node operators to inspect and control the node.
+//!
+//! # Note
+//!
+//! Methods are prefixed by `sudo`.
+
+#[cfg(test)]
+mod tests;
+
+pub mod api;
+pub mod error;
+pub mod sudo;
+
+pub use api::{SudoApiServer, SudoUnsafeApiServer};
+pub use sudo::{Sudo, SudoNetwork, SudoUnsafe};
+
+/// The methods that are unsafe to expose to untrusted users, see [`SudoUnsafeApiServer`].
+pub const UNSAFE_METHODS: &[&str] = &["sudo_unstable_p2pDiscover", "sudo_unstable_rotateKeys"];
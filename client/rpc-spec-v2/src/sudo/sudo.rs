@@ -0,0 +1,108 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! API implementation for the sudo methods.
+
+use crate::{
+	chain_head::hex_string,
+	sudo::{
+		api::{SudoApiServer, SudoUnsafeApiServer},
+		error::Error as SudoRpcError,
+	},
+};
+use jsonrpsee::core::{async_trait, RpcResult};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_keystore::{KeystoreExt, KeystorePtr};
+use sp_runtime::traits::Block as BlockT;
+use sp_session::SessionKeys;
+use std::{marker::PhantomData, sync::Arc};
+
+/// The network of the node, as used by the sudo methods.
+#[async_trait]
+pub trait SudoNetwork: Send + Sync {
+	/// Add the multiaddress, which ends with the `/p2p/` peer ID, to the known
+	/// addresses of the peer.
+	///
+	/// Returns an error if the multiaddress is invalid.
+	async fn add_known_address(&self, multiaddr: String) -> Result<(), String>;
+}
+
+/// An API for sudo RPC calls.
+pub struct Sudo {
+	/// The name and the version of the client.
+	version: String,
+}
+
+impl Sudo {
+	/// Creates a new [`Sudo`].
+	pub fn new(impl_name: &str, impl_version: &str) -> Self {
+		Self { version: format!("{} {}", impl_name, impl_version) }
+	}
+}
+
+impl SudoApiServer for Sudo {
+	fn sudo_unstable_version(&self) -> RpcResult<String> {
+		Ok(self.version.clone())
+	}
+}
+
+/// An API for the unsafe sudo RPC calls.
+pub struct SudoUnsafe<Block, Client> {
+	/// Substrate client.
+	client: Arc<Client>,
+	/// The keystore of the node.
+	keystore: KeystorePtr,
+	/// The network of the node.
+	network: Arc<dyn SudoNetwork>,
+	/// Phantom member to pin the block type.
+	_phantom: PhantomData<Block>,
+}
+
+impl<Block, Client> SudoUnsafe<Block, Client> {
+	/// Creates a new [`SudoUnsafe`].
+	pub fn new(client: Arc<Client>, keystore: KeystorePtr, network: Arc<dyn SudoNetwork>) -> Self {
+		Self { client, keystore, network, _phantom: PhantomData }
+	}
+}
+
+#[async_trait]
+impl<Block, Client> SudoUnsafeApiServer for SudoUnsafe<Block, Client>
+where
+	Block: BlockT + 'static,
+	Client: HeaderBackend<Block> + ProvideRuntimeApi<Block> + Send + Sync + 'static,
+	Client::Api: SessionKeys<Block>,
+{
+	async fn sudo_unstable_p2p_discover(&self, multiaddr: String) -> RpcResult<()> {
+		self.network
+			.add_known_address(multiaddr)
+			.await
+			.map_err(|err| SudoRpcError::InvalidMultiaddr(err).into())
+	}
+
+	fn sudo_unstable_rotate_keys(&self) -> RpcResult<String> {
+		let best_block_hash = self.client.info().best_hash;
+		let mut runtime_api = self.client.runtime_api();
+
+		runtime_api.register_extension(KeystoreExt::from(self.keystore.clone()));
+
+		runtime_api
+			.generate_session_keys(best_block_hash, None)
+			.map(|keys| hex_string(&keys))
+			.map_err(|err| SudoRpcError::SessionKeysFailed(err.to_string()).into())
+	}
+}
@@ -0,0 +1,116 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use super::*;
+use assert_matches::assert_matches;
+use codec::Decode;
+use jsonrpsee::{
+	core::{async_trait, error::Error},
+	rpc_params,
+	types::{error::CallError, EmptyServerParams as EmptyParams},
+	RpcModule,
+};
+use parking_lot::Mutex;
+use sp_core::{
+	crypto::ByteArray,
+	testing::{ED25519, SR25519},
+};
+use sp_keystore::{testing::MemoryKeystore, Keystore};
+use std::sync::Arc;
+use substrate_test_runtime_client::{
+	runtime::{Block, SessionKeys},
+	Client, TestClientBuilder,
+};
+
+const IMPL_NAME: &str = "substrate-node";
+const IMPL_VERSION: &str = "1.0.0";
+const PEER_ADDR: &str =
+	"/ip4/198.51.100.19/tcp/30333/p2p/QmSk5HQbn6LhUwDiNMseVUjuRYhEtYj4aUZ6WfWoGURpdV";
+
+/// Network that records the known addresses.
+#[derive(Default)]
+struct MockNetwork {
+	known_addresses: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl SudoNetwork for MockNetwork {
+	async fn add_known_address(&self, multiaddr: String) -> Result<(), String> {
+		if !multiaddr.contains("/p2p/") {
+			return Err("Missing peer ID".into());
+		}
+		self.known_addresses.lock().push(multiaddr);
+		Ok(())
+	}
+}
+
+type UnsafeApi = RpcModule<SudoUnsafe<Block, Client<substrate_test_runtime_client::Backend>>>;
+
+fn unsafe_api() -> (Arc<MockNetwork>, Arc<MemoryKeystore>, UnsafeApi) {
+	let network = Arc::new(MockNetwork::default());
+	let keystore = Arc::new(MemoryKeystore::new());
+	let client = Arc::new(TestClientBuilder::new().build());
+	let api = SudoUnsafe::new(client, keystore.clone(), network.clone()).into_rpc();
+	(network, keystore, api)
+}
+
+#[test]
+fn sudo_unsafe_methods_are_declared() {
+	let (_network, _keystore, api) = unsafe_api();
+	let mut methods = api.method_names().collect::<Vec<_>>();
+	methods.sort();
+	let mut declared = UNSAFE_METHODS.to_vec();
+	declared.sort();
+	assert_eq!(methods, declared);
+}
+
+#[tokio::test]
+async fn sudo_version_works() {
+	let api = Sudo::new(IMPL_NAME, IMPL_VERSION).into_rpc();
+	let version = api
+		.call::<_, String>("sudo_unstable_version", EmptyParams::new())
+		.await
+		.unwrap();
+	assert_eq!(version, format!("{} {}", IMPL_NAME, IMPL_VERSION));
+}
+
+#[tokio::test]
+async fn sudo_p2p_discover_works() {
+	let (network, _keystore, api) = unsafe_api();
+	let _: () = api.call("sudo_unstable_p2pDiscover", rpc_params![PEER_ADDR]).await.unwrap();
+	assert_eq!(*network.known_addresses.lock(), vec![PEER_ADDR.to_string()]);
+
+	let err = api
+		.call::<_, ()>("sudo_unstable_p2pDiscover", rpc_params!["/ip4/198.51.100.19/tcp/30333"])
+		.await
+		.unwrap_err();
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == 5001 && err.message() == "Invalid multiaddress: Missing peer ID"
+	);
+}
+
+#[tokio::test]
+async fn sudo_rotate_keys_works() {
+	let (_network, keystore, api) = unsafe_api();
+
+	let keys: String = api.call("sudo_unstable_rotateKeys", EmptyParams::new()).await.unwrap();
+	let keys = array_bytes::hex2bytes(&keys).unwrap();
+	let session_keys = SessionKeys::decode(&mut &keys[..]).unwrap();
+
+	assert!(keystore.keys(ED25519).unwrap().contains(&session_keys.ed25519.to_raw_vec()));
+	assert!(keystore.keys(SR25519).unwrap().contains(&session_keys.sr25519.to_raw_vec()));
+}
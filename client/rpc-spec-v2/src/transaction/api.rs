@@ -18,8 +18,8 @@
 
 //! API trait for transactions.
 
-use crate::transaction::event::TransactionEvent;
-use jsonrpsee::proc_macros::rpc;
+use crate::transaction::event::{TransactionEvent, TransactionWatchOptions};
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use sp_core::Bytes;
 
 #[rpc(client, server)]
@@ -28,10 +28,45 @@ pub trait TransactionApi<Hash: Clone> {
 	///
 	/// See [`TransactionEvent`](crate::transaction::event::TransactionEvent) for details on
 	/// transaction life cycle.
+	///
+	/// # Unstable
+	///
+	/// The optional `options` parameter associates the transaction with a
+	/// `chainHead_follow` subscription. The `bestChainBlockIncluded` and `finalized`
+	/// events then report whether the inclusion block is pinned by that subscription,
+	/// and pin the block on its behalf when requested.
 	#[subscription(
 		name = "transaction_unstable_submitAndWatch" => "transaction_unstable_submitExtrinsic",
 		unsubscribe = "transaction_unstable_unwatch",
 		item = TransactionEvent<Hash>,
 	)]
-	fn submit_and_watch(&self, bytes: Bytes);
+	fn submit_and_watch(&self, bytes: Bytes, options: Option<TransactionWatchOptions>);
+}
+
+#[rpc(client, server)]
+pub trait TransactionBroadcastApi {
+	/// Broadcast an extrinsic to the peers of the node.
+	///
+	/// The transaction is gossiped again whenever it is dropped from the transaction pool,
+	/// until it is finalized or the broadcast is stopped by `transaction_unstable_stop`.
+	/// The broadcast ends early when the transaction becomes invalid, is replaced by another
+	/// transaction or is rejected by the pool for a reason retrying does not change, and it
+	/// expires after a maximum duration configured by the node.
+	///
+	/// Returns the operation ID of the broadcast, or null if the maximum number of
+	/// broadcasts is reached.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[method(name = "transaction_unstable_broadcast")]
+	fn broadcast(&self, bytes: Bytes) -> RpcResult<Option<String>>;
+
+	/// Stop the broadcast of the given operation ID.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[method(name = "transaction_unstable_stop")]
+	fn stop_broadcast(&self, operation_id: String) -> RpcResult<()>;
 }
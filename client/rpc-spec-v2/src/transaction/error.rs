@@ -21,6 +21,10 @@
 //! Errors are interpreted as transaction events for subscriptions.
 
 use crate::transaction::event::{TransactionError, TransactionEvent};
+use jsonrpsee::{
+	core::Error as RpcError,
+	types::error::{CallError, ErrorObject, INVALID_PARAMS_CODE},
+};
 use sc_transaction_pool_api::error::Error as PoolError;
 use sp_runtime::transaction_validity::InvalidTransaction;
 
@@ -95,6 +99,46 @@ impl<Hash> From<Error> for TransactionEvent<Hash> {
 				TransactionEvent::Invalid(TransactionError {
 					error: "The pool is not accepting future transactions".into(),
 				}),
+			Error::Pool(PoolError::TooManyFutureTransactions) =>
+				TransactionEvent::Invalid(TransactionError {
+					error: "The sender has too many future transactions in the pool".into(),
+				}),
 		}
 	}
 }
+
+/// The transaction broadcast RPC errors.
+#[derive(Debug, thiserror::Error)]
+pub enum ErrorBroadcast {
+	/// The extrinsic has an invalid format.
+	#[error("Extrinsic has invalid format: {0}")]
+	BadFormat(codec::Error),
+	/// The operation ID does not identify an active broadcast.
+	#[error("Invalid operation id")]
+	InvalidOperationID,
+}
+
+/// Extrinsic has an invalid format.
+///
+/// # Note
+///
+/// This is similar to the old `author` API error code.
+pub(crate) const BAD_FORMAT: i32 = 1001;
+
+impl From<ErrorBroadcast> for ErrorObject<'static> {
+	fn from(e: ErrorBroadcast) -> Self {
+		let msg = e.to_string();
+
+		match e {
+			ErrorBroadcast::BadFormat(_) => ErrorObject::owned(BAD_FORMAT, msg, None::<()>),
+			ErrorBroadcast::InvalidOperationID =>
+				ErrorObject::owned(INVALID_PARAMS_CODE, msg, None::<()>),
+		}
+	}
+}
+
+impl From<ErrorBroadcast> for RpcError {
+	fn from(e: ErrorBroadcast) -> Self {
+		CallError::Custom(e.into()).into()
+	}
+}
@@ -0,0 +1,108 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+use assert_matches::assert_matches;
+use codec::Encode;
+use jsonrpsee::{core::error::Error, rpc_params, types::error::CallError, RpcModule};
+use sc_transaction_pool::{BasicPool, FullChainApi};
+use sp_core::{testing::TaskExecutor, Bytes};
+use std::{sync::Arc, time::Duration};
+use substrate_test_runtime_client::{
+	runtime::{Block, ExtrinsicBuilder, Transfer},
+	AccountKeyring, Backend, Client, TestClientBuilder,
+};
+
+type FullTransactionPool = BasicPool<FullChainApi<Client<Backend>, Block>, Block>;
+
+fn setup_api(
+	max_broadcasts: usize,
+) -> RpcModule<TransactionBroadcast<FullTransactionPool, Client<Backend>>> {
+	let client = Arc::new(TestClientBuilder::new().build());
+	let spawner = TaskExecutor::new();
+	let pool = BasicPool::new_full(Default::default(), true.into(), None, spawner, client.clone());
+
+	TransactionBroadcast::new(client, pool, Arc::new(TaskExecutor::default()))
+		.with_max_broadcasts(max_broadcasts)
+		.into_rpc()
+}
+
+fn transfer(nonce: u64) -> Bytes {
+	let transfer = Transfer {
+		amount: Default::default(),
+		nonce,
+		from: AccountKeyring::Alice.into(),
+		to: AccountKeyring::Bob.into(),
+	};
+	ExtrinsicBuilder::new_transfer(transfer).build().encode().into()
+}
+
+#[tokio::test]
+async fn broadcast_is_limited() {
+	let api = setup_api(1);
+
+	let operation_id: Option<String> =
+		api.call("transaction_unstable_broadcast", [transfer(0)]).await.unwrap();
+	let operation_id = operation_id.unwrap();
+
+	// The limit is reached.
+	let rejected: Option<String> =
+		api.call("transaction_unstable_broadcast", [transfer(1)]).await.unwrap();
+	assert!(rejected.is_none());
+
+	let _: () = api.call("transaction_unstable_stop", rpc_params![&operation_id]).await.unwrap();
+
+	// The stopped broadcast makes room for a new one.
+	let accepted: Option<String> =
+		api.call("transaction_unstable_broadcast", [transfer(1)]).await.unwrap();
+	assert!(accepted.is_some());
+}
+
+#[tokio::test]
+async fn broadcast_stops_on_invalid_transaction() {
+	let api = setup_api(1);
+
+	let xt: Bytes = ExtrinsicBuilder::new_include_data(vec![]).unsigned().build().encode().into();
+	let operation_id: Option<String> =
+		api.call("transaction_unstable_broadcast", [xt]).await.unwrap();
+	assert!(operation_id.is_some());
+
+	// The pool rejects the transaction for good, so the broadcast ends on its own and
+	// makes room for a new one.
+	let accepted = async {
+		loop {
+			let operation_id: Option<String> =
+				api.call("transaction_unstable_broadcast", [transfer(0)]).await.unwrap();
+			if operation_id.is_some() {
+				break
+			}
+			tokio::time::sleep(Duration::from_millis(50)).await;
+		}
+	};
+	tokio::time::timeout(Duration::from_secs(60), accepted).await.unwrap();
+}
+
+#[tokio::test]
+async fn stop_unknown_broadcast() {
+	let api = setup_api(1);
+
+	assert_matches!(
+		api.call::<_, ()>("transaction_unstable_stop", ["0xdeadbeef"]).await,
+		Err(Error::Call(CallError::Custom(err))) if err.message() == "Invalid operation id"
+	);
+}
@@ -19,12 +19,13 @@
 //! API implementation for submitting transactions.
 
 use crate::{
+	chain_head::FollowSubscriptionPins,
 	transaction::{
 		api::TransactionApiServer,
-		error::Error,
+		error::{Error, BAD_FORMAT},
 		event::{
 			TransactionBlock, TransactionBroadcasted, TransactionDropped, TransactionError,
-			TransactionEvent,
+			TransactionEvent, TransactionWatchOptions,
 		},
 	},
 	SubscriptionTaskExecutor,
@@ -37,6 +38,7 @@ use jsonrpsee::{
 	},
 	SubscriptionSink,
 };
+use sc_rpc_api::subscription::{accept_within, PENDING_SUBSCRIPTION_TIMEOUT};
 use sc_transaction_pool_api::{
 	error::IntoPoolError, BlockHash, TransactionFor, TransactionPool, TransactionSource,
 	TransactionStatus,
@@ -52,19 +54,33 @@ use codec::Decode;
 use futures::{FutureExt, StreamExt, TryFutureExt};
 
 /// An API for transaction RPC calls.
-pub struct Transaction<Pool, Client> {
+pub struct Transaction<Pool: TransactionPool, Client> {
 	/// Substrate client.
 	client: Arc<Client>,
 	/// Transactions pool.
 	pool: Arc<Pool>,
 	/// Executor to spawn subscriptions.
 	executor: SubscriptionTaskExecutor,
+	/// The blocks pinned by the `chainHead_follow` subscriptions.
+	follow_pins: Option<Arc<dyn FollowSubscriptionPins<BlockHash<Pool>>>>,
 }
 
-impl<Pool, Client> Transaction<Pool, Client> {
+impl<Pool: TransactionPool, Client> Transaction<Pool, Client> {
 	/// Creates a new [`Transaction`].
 	pub fn new(client: Arc<Client>, pool: Arc<Pool>, executor: SubscriptionTaskExecutor) -> Self {
-		Transaction { client, pool, executor }
+		Transaction { client, pool, executor, follow_pins: None }
+	}
+
+	/// Report and pin the inclusion blocks for the `chainHead_follow` subscriptions.
+	///
+	/// Without this, the watch options of `transaction_unstable_submitAndWatch`
+	/// are ignored.
+	pub fn with_follow_pins(
+		mut self,
+		follow_pins: Arc<dyn FollowSubscriptionPins<BlockHash<Pool>>>,
+	) -> Self {
+		self.follow_pins = Some(follow_pins);
+		self
 	}
 }
 
@@ -73,14 +89,7 @@ impl<Pool, Client> Transaction<Pool, Client> {
 /// Possibly in the future we could allow opt-in for special treatment
 /// of such transactions, so that the block authors can inject
 /// some unique transactions via RPC and have them included in the pool.
-const TX_SOURCE: TransactionSource = TransactionSource::External;
-
-/// Extrinsic has an invalid format.
-///
-/// # Note
-///
-/// This is similar to the old `author` API error code.
-const BAD_FORMAT: i32 = 1001;
+pub(crate) const TX_SOURCE: TransactionSource = TransactionSource::External;
 
 #[async_trait]
 impl<Pool, Client> TransactionApiServer<BlockHash<Pool>> for Transaction<Pool, Client>
@@ -90,7 +99,12 @@ where
 	<Pool::Block as BlockT>::Hash: Unpin,
 	Client: HeaderBackend<Pool::Block> + ProvideRuntimeApi<Pool::Block> + Send + Sync + 'static,
 {
-	fn submit_and_watch(&self, mut sink: SubscriptionSink, xt: Bytes) -> SubscriptionResult {
+	fn submit_and_watch(
+		&self,
+		mut sink: SubscriptionSink,
+		xt: Bytes,
+		options: Option<TransactionWatchOptions>,
+	) -> SubscriptionResult {
 		// This is the only place where the RPC server can return an error for this
 		// subscription. Other defects must be signaled as events to the sink.
 		let decoded_extrinsic = match TransactionFor::<Pool>::decode(&mut &xt[..]) {
@@ -108,6 +122,14 @@ where
 
 		let best_block_hash = self.client.info().best_hash;
 
+		let follow_pinning = options.and_then(|options| {
+			self.follow_pins.clone().map(|pins| FollowPinning {
+				follow_subscription: options.follow_subscription,
+				pin_blocks: options.pin_blocks,
+				pins,
+			})
+		});
+
 		let submit = self
 			.pool
 			.submit_and_watch(
@@ -122,11 +144,18 @@ where
 			});
 
 		let fut = async move {
-			match submit.await {
+			// The subscription is accepted once the transaction is submitted to the pool.
+			let Some((mut sink, submitted)) =
+				accept_within(sink, PENDING_SUBSCRIPTION_TIMEOUT, submit).await
+			else {
+				return
+			};
+
+			match submitted {
 				Ok(stream) => {
-					let mut state = TransactionState::new();
-					let stream =
-						stream.filter_map(|event| async move { state.handle_event(event) });
+					let mut state = TransactionState::new(follow_pinning);
+					let stream = stream
+						.filter_map(move |event| futures::future::ready(state.handle_event(event)));
 					sink.pipe_from_stream(stream.boxed()).await;
 				},
 				Err(err) => {
@@ -150,22 +179,31 @@ where
 ///
 /// In the future, the RPC server can submit only the last event when multiple
 /// identical events happen in a row.
-#[derive(Clone, Copy)]
-struct TransactionState {
+struct TransactionState<BlockHash> {
 	/// True if the transaction was previously broadcasted.
 	broadcasted: bool,
+	/// Report the inclusion blocks as pinned by a `chainHead_follow` subscription.
+	follow_pinning: Option<FollowPinning<BlockHash>>,
 }
 
-impl TransactionState {
+impl<BlockHash: Clone> TransactionState<BlockHash> {
 	/// Construct a new [`TransactionState`].
-	pub fn new() -> Self {
-		TransactionState { broadcasted: false }
+	pub fn new(follow_pinning: Option<FollowPinning<BlockHash>>) -> Self {
+		TransactionState { broadcasted: false, follow_pinning }
+	}
+
+	/// Returns whether the inclusion block is pinned by the `chainHead_follow`
+	/// subscription, if the subscription was provided.
+	fn is_pinned(&self, hash: &BlockHash) -> Option<bool> {
+		self.follow_pinning
+			.as_ref()
+			.map(|follow_pinning| follow_pinning.is_pinned(hash))
 	}
 
 	/// Handle events generated by the transaction-pool and convert them
 	/// to the new API expected state.
 	#[inline]
-	pub fn handle_event<Hash: Clone, BlockHash: Clone>(
+	pub fn handle_event<Hash: Clone>(
 		&mut self,
 		event: TransactionStatus<Hash, BlockHash>,
 	) -> Option<TransactionEvent<BlockHash>> {
@@ -183,6 +221,7 @@ impl TransactionState {
 			},
 			TransactionStatus::InBlock((hash, index)) =>
 				Some(TransactionEvent::BestChainBlockIncluded(Some(TransactionBlock {
+					pinned: self.is_pinned(&hash),
 					hash,
 					index,
 				}))),
@@ -193,7 +232,11 @@ impl TransactionState {
 					error: "Maximum number of finality watchers has been reached".into(),
 				})),
 			TransactionStatus::Finalized((hash, index)) =>
-				Some(TransactionEvent::Finalized(TransactionBlock { hash, index })),
+				Some(TransactionEvent::Finalized(TransactionBlock {
+					pinned: self.is_pinned(&hash),
+					hash,
+					index,
+				})),
 			TransactionStatus::Usurped(_) => Some(TransactionEvent::Invalid(TransactionError {
 				error: "Extrinsic was rendered invalid by another extrinsic".into(),
 			})),
@@ -206,3 +249,25 @@ impl TransactionState {
 		}
 	}
 }
+
+/// Report the inclusion blocks as pinned by a `chainHead_follow` subscription.
+struct FollowPinning<BlockHash> {
+	/// The `chainHead_follow` subscription ID.
+	follow_subscription: String,
+	/// Pin the inclusion blocks on behalf of the subscription.
+	pin_blocks: bool,
+	/// The blocks pinned by the `chainHead_follow` subscriptions.
+	pins: Arc<dyn FollowSubscriptionPins<BlockHash>>,
+}
+
+impl<BlockHash: Clone> FollowPinning<BlockHash> {
+	/// Returns true if the block is pinned by the subscription, pinning the block
+	/// first if requested.
+	fn is_pinned(&self, hash: &BlockHash) -> bool {
+		if self.pin_blocks {
+			self.pins.pin_block_external(&self.follow_subscription, hash.clone())
+		} else {
+			self.pins.is_block_pinned(&self.follow_subscription, hash.clone())
+		}
+	}
+}
@@ -0,0 +1,230 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! API implementation for broadcasting transactions.
+
+use crate::{
+	transaction::{
+		api::TransactionBroadcastApiServer, error::ErrorBroadcast, transaction::TX_SOURCE,
+	},
+	SubscriptionTaskExecutor,
+};
+use codec::{Decode, Encode};
+use futures::{
+	future::{self, AbortHandle, Abortable, Either},
+	FutureExt, StreamExt,
+};
+use jsonrpsee::core::{async_trait, RpcResult};
+use log::debug;
+use parking_lot::Mutex;
+use sc_transaction_pool_api::{
+	error::{Error as PoolError, IntoPoolError},
+	TransactionFor, TransactionPool, TransactionStatus,
+};
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::{generic, transaction_validity::InvalidTransaction};
+use std::{
+	collections::HashMap,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The log target of the broadcasts.
+const LOG_TARGET: &str = "rpc-spec-v2::transaction";
+
+/// The default maximum number of active broadcasts.
+const MAX_BROADCASTS: usize = 64;
+
+/// The default maximum duration of a broadcast.
+const MAX_BROADCAST_DURATION: Duration = Duration::from_secs(30 * 60);
+
+/// The delay before the transaction is submitted again after the pool rejected it.
+const RESUBMIT_DELAY: Duration = Duration::from_secs(6);
+
+/// An API for the transaction broadcast RPC calls.
+pub struct TransactionBroadcast<Pool, Client> {
+	/// Substrate client.
+	client: Arc<Client>,
+	/// Transactions pool.
+	pool: Arc<Pool>,
+	/// Executor to spawn the broadcasts.
+	executor: SubscriptionTaskExecutor,
+	/// The active broadcasts, keyed by operation ID.
+	broadcasts: Arc<Mutex<HashMap<String, AbortHandle>>>,
+	/// The maximum number of active broadcasts.
+	max_broadcasts: usize,
+	/// The maximum duration of a broadcast.
+	max_duration: Duration,
+	/// Distinguish the operation IDs of the broadcasts started at the same instant.
+	next_nonce: AtomicU64,
+}
+
+impl<Pool, Client> TransactionBroadcast<Pool, Client> {
+	/// Creates a new [`TransactionBroadcast`].
+	pub fn new(client: Arc<Client>, pool: Arc<Pool>, executor: SubscriptionTaskExecutor) -> Self {
+		TransactionBroadcast {
+			client,
+			pool,
+			executor,
+			broadcasts: Default::default(),
+			max_broadcasts: MAX_BROADCASTS,
+			max_duration: MAX_BROADCAST_DURATION,
+			next_nonce: Default::default(),
+		}
+	}
+
+	/// Limit the number of active broadcasts.
+	///
+	/// The `transaction_unstable_broadcast` method returns null once the limit is reached.
+	pub fn with_max_broadcasts(mut self, max_broadcasts: usize) -> Self {
+		self.max_broadcasts = max_broadcasts;
+		self
+	}
+
+	/// Limit the duration of a broadcast.
+	///
+	/// The broadcasts are not tied to the connection that started them, so a broadcast
+	/// nobody stops gives up on the transaction after this duration.
+	pub fn with_max_duration(mut self, max_duration: Duration) -> Self {
+		self.max_duration = max_duration;
+		self
+	}
+
+	/// Generate a new operation ID, unpredictable for the other clients of the node.
+	fn generate_operation_id(&self) -> String {
+		let nonce = self.next_nonce.fetch_add(1, Ordering::Relaxed);
+		let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |now| now.as_nanos());
+		hex::encode(sp_core::blake2_128(&(now, nonce).encode()))
+	}
+}
+
+#[async_trait]
+impl<Pool, Client> TransactionBroadcastApiServer for TransactionBroadcast<Pool, Client>
+where
+	Pool: TransactionPool + Sync + Send + 'static,
+	Client: HeaderBackend<Pool::Block> + Send + Sync + 'static,
+{
+	fn broadcast(&self, xt: Bytes) -> RpcResult<Option<String>> {
+		let decoded_extrinsic =
+			TransactionFor::<Pool>::decode(&mut &xt[..]).map_err(ErrorBroadcast::BadFormat)?;
+
+		let operation_id = self.generate_operation_id();
+		let (abort_handle, abort_registration) = AbortHandle::new_pair();
+		{
+			let mut broadcasts = self.broadcasts.lock();
+			if broadcasts.len() >= self.max_broadcasts {
+				return Ok(None)
+			}
+			broadcasts.insert(operation_id.clone(), abort_handle);
+		}
+
+		let client = self.client.clone();
+		let pool = self.pool.clone();
+		let max_duration = self.max_duration;
+		let rebroadcast = async move {
+			loop {
+				let best_block_hash = client.info().best_hash;
+				let submitted = pool
+					.submit_and_watch(
+						&generic::BlockId::hash(best_block_hash),
+						TX_SOURCE,
+						decoded_extrinsic.clone(),
+					)
+					.await;
+
+				match submitted {
+					Ok(mut stream) => {
+						// Submit the transaction again once it is dropped from the pool
+						// without being finalized.
+						while let Some(status) = stream.next().await {
+							match status {
+								TransactionStatus::Finalized(_) |
+								TransactionStatus::Usurped(_) |
+								TransactionStatus::Invalid => return,
+								TransactionStatus::FinalityTimeout(_) |
+								TransactionStatus::Dropped => break,
+								_ => continue,
+							}
+						}
+					},
+					Err(error) => {
+						let error = error.into_pool_error();
+						debug!(target: LOG_TARGET, "Failed to submit the transaction: {:?}", error);
+						if !error.as_ref().map_or(false, is_retriable) {
+							return
+						}
+					},
+				}
+
+				futures_timer::Delay::new(RESUBMIT_DELAY).await;
+			}
+		};
+
+		let rebroadcast = async move {
+			let expired = futures_timer::Delay::new(max_duration);
+			if let Either::Right(_) = future::select(rebroadcast.boxed(), expired).await {
+				debug!(target: LOG_TARGET, "Broadcast expired after {:?}", max_duration);
+			}
+		};
+
+		let broadcasts = self.broadcasts.clone();
+		let finished_operation_id = operation_id.clone();
+		let fut = Abortable::new(rebroadcast, abort_registration).map(move |result| {
+			// The stopped broadcasts are removed by `transaction_unstable_stop`.
+			if result.is_ok() {
+				broadcasts.lock().remove(&finished_operation_id);
+			}
+		});
+
+		self.executor
+			.spawn("substrate-rpc-transaction-broadcast", Some("rpc"), fut.boxed());
+		Ok(Some(operation_id))
+	}
+
+	fn stop_broadcast(&self, operation_id: String) -> RpcResult<()> {
+		let Some(abort_handle) = self.broadcasts.lock().remove(&operation_id) else {
+			return Err(ErrorBroadcast::InvalidOperationID.into())
+		};
+
+		abort_handle.abort();
+		Ok(())
+	}
+}
+
+/// Returns true if submitting the transaction again might succeed later.
+fn is_retriable(error: &PoolError) -> bool {
+	match error {
+		PoolError::InvalidTransaction(invalid) =>
+			matches!(invalid, InvalidTransaction::Future | InvalidTransaction::ExhaustsResources),
+		PoolError::TemporarilyBanned |
+		PoolError::AlreadyImported(_) |
+		PoolError::TooLowPriority { .. } |
+		PoolError::ImmediatelyDropped |
+		PoolError::InvalidBlockId(_) |
+		PoolError::RejectedFutureTransaction |
+		PoolError::TooManyFutureTransactions => true,
+		PoolError::UnknownTransaction(_) |
+		PoolError::NoTagsProvided |
+		PoolError::CycleDetected |
+		PoolError::Unactionable => false,
+	}
+}
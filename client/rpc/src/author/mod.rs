@@ -26,13 +26,16 @@ use std::sync::Arc;
 use crate::SubscriptionTaskExecutor;
 
 use codec::{Decode, Encode};
-use futures::{FutureExt, TryFutureExt};
+use futures::{stream, FutureExt, StreamExt, TryFutureExt};
 use jsonrpsee::{
 	core::{async_trait, Error as JsonRpseeError, RpcResult},
 	types::SubscriptionResult,
 	SubscriptionSink,
 };
-use sc_rpc_api::DenyUnsafe;
+use sc_rpc_api::{
+	subscription::{accept_within, PENDING_SUBSCRIPTION_TIMEOUT},
+	DenyUnsafe,
+};
 use sc_transaction_pool_api::{
 	error::IntoPoolError, BlockHash, InPoolTransaction, TransactionFor, TransactionPool,
 	TransactionSource, TxHash,
@@ -49,7 +52,7 @@ use self::error::{Error, Result};
 pub use sc_rpc_api::author::*;
 
 /// Authoring API
-pub struct Author<P, Client> {
+pub struct Author<P: TransactionPool, Client> {
 	/// Substrate client
 	client: Arc<Client>,
 	/// Transactions pool
@@ -60,9 +63,11 @@ pub struct Author<P, Client> {
 	deny_unsafe: DenyUnsafe,
 	/// Executor to spawn subscriptions.
 	executor: SubscriptionTaskExecutor,
+	/// Computes the fee information of the watched extrinsics, if any.
+	fee_provider: Option<Arc<dyn ExtrinsicFeeProvider<P::Block>>>,
 }
 
-impl<P, Client> Author<P, Client> {
+impl<P: TransactionPool, Client> Author<P, Client> {
 	/// Create new instance of Authoring API.
 	pub fn new(
 		client: Arc<Client>,
@@ -71,7 +76,17 @@ impl<P, Client> Author<P, Client> {
 		deny_unsafe: DenyUnsafe,
 		executor: SubscriptionTaskExecutor,
 	) -> Self {
-		Author { client, pool, keystore, deny_unsafe, executor }
+		Author { client, pool, keystore, deny_unsafe, executor, fee_provider: None }
+	}
+
+	/// Set the provider of the fee information of the extrinsics watched with
+	/// `author_submitAndWatchExtrinsic`.
+	pub fn with_fee_provider(
+		mut self,
+		fee_provider: Arc<dyn ExtrinsicFeeProvider<P::Block>>,
+	) -> Self {
+		self.fee_provider = Some(fee_provider);
+		self
 	}
 }
 
@@ -181,7 +196,12 @@ where
 			.collect())
 	}
 
-	fn watch_extrinsic(&self, mut sink: SubscriptionSink, xt: Bytes) -> SubscriptionResult {
+	fn watch_extrinsic(
+		&self,
+		mut sink: SubscriptionSink,
+		xt: Bytes,
+		with_fee_info: Option<bool>,
+	) -> SubscriptionResult {
 		let best_block_hash = self.client.info().best_hash;
 		let dxt = match TransactionFor::<P>::decode(&mut &xt[..]).map_err(|e| Error::from(e)) {
 			Ok(dxt) => dxt,
@@ -191,6 +211,15 @@ where
 			},
 		};
 
+		let fee_query = match (with_fee_info.unwrap_or(false), &self.fee_provider) {
+			(false, _) => None,
+			(true, Some(provider)) => Some((provider.clone(), dxt.clone(), xt.len() as u32)),
+			(true, None) => {
+				let _ = sink.reject(JsonRpseeError::from(Error::FeeInfoUnavailable));
+				return Ok(())
+			},
+		};
+
 		let submit = self
 			.pool
 			.submit_and_watch(&generic::BlockId::hash(best_block_hash), TX_SOURCE, dxt)
@@ -201,14 +230,50 @@ where
 			});
 
 		let fut = async move {
-			let stream = match submit.await {
-				Ok(stream) => stream,
+			// The subscription is accepted once the transaction is submitted to the pool.
+			let Some((mut sink, submitted)) =
+				accept_within(sink, PENDING_SUBSCRIPTION_TIMEOUT, submit).await
+			else {
+				return
+			};
+
+			let stream = match submitted {
+				Ok(stream) => stream.map(ExtrinsicUpdate::Status),
 				Err(err) => {
 					let _ = sink.reject(JsonRpseeError::from(err));
 					return
 				},
 			};
 
+			// The extrinsic is validated by now, so its fee can be computed at the same block.
+			let fee_info = match fee_query {
+				Some((provider, dxt, len)) => tokio::task::spawn_blocking(move || {
+					provider.fee_info(best_block_hash, &dxt, len)
+				})
+				.await
+				.unwrap_or_else(|e| Err(e.to_string()))
+				.map_err(|e| log::debug!("Failed to compute the fee of a watched extrinsic: {}", e))
+				.ok(),
+				None => None,
+			};
+
+			let fee_info =
+				stream::iter(fee_info.map(|fee_info| ExtrinsicUpdate::FeeInfo { fee_info }));
+			sink.pipe_from_stream(fee_info.chain(stream)).await;
+		};
+
+		self.executor.spawn("substrate-rpc-subscription", Some("rpc"), fut.boxed());
+		Ok(())
+	}
+
+	fn subscribe_pool_events(&self, mut sink: SubscriptionSink) -> SubscriptionResult {
+		if let Err(e) = self.deny_unsafe.check_if_safe() {
+			let _ = sink.reject(JsonRpseeError::from(e));
+			return Ok(())
+		}
+
+		let stream = self.pool.pool_events();
+		let fut = async move {
 			sink.pipe_from_stream(stream).await;
 		};
 
@@ -27,7 +27,7 @@ use jsonrpsee::{
 	RpcModule,
 };
 use sc_transaction_pool::{BasicPool, FullChainApi};
-use sc_transaction_pool_api::TransactionStatus;
+use sc_transaction_pool_api::{PoolEvent, TransactionStatus};
 use sp_core::{
 	blake2_256,
 	bytes::to_hex,
@@ -37,7 +37,9 @@ use sp_core::{
 	H256,
 };
 use sp_keystore::{testing::MemoryKeystore, Keystore};
+use sp_rpc::number::NumberOrHex;
 use sp_runtime::Perbill;
+use sp_weights::Weight;
 use std::sync::Arc;
 use substrate_test_runtime_client::{
 	self,
@@ -83,6 +85,7 @@ impl TestSetup {
 			keystore: self.keystore.clone(),
 			deny_unsafe: DenyUnsafe::No,
 			executor: test_executor(),
+			fee_provider: None,
 		}
 	}
 
@@ -150,6 +153,74 @@ async fn author_should_watch_extrinsic() {
 	assert_eq!(&sub_id, sub.subscription_id());
 }
 
+struct TestFeeProvider;
+
+impl ExtrinsicFeeProvider<Block> for TestFeeProvider {
+	fn fee_info(
+		&self,
+		_at: H256,
+		_extrinsic: &Extrinsic,
+		len: u32,
+	) -> std::result::Result<ExtrinsicFeeInfo, String> {
+		Ok(ExtrinsicFeeInfo {
+			partial_fee: NumberOrHex::Number(len as u64),
+			weight: Weight::from_parts(1_000, 10),
+		})
+	}
+}
+
+#[tokio::test]
+async fn author_should_watch_extrinsic_with_fee_info() {
+	let mut author = TestSetup::default().author();
+	author.fee_provider = Some(Arc::new(TestFeeProvider));
+	let api = author.into_rpc();
+	let xt = ExtrinsicBuilder::new_call_with_priority(0)
+		.signer(AccountKeyring::Alice.into())
+		.build()
+		.encode();
+	let len = xt.len() as u64;
+
+	let mut sub = api
+		.subscribe("author_submitAndWatchExtrinsic", (to_hex(&xt, true), true))
+		.await
+		.unwrap();
+	let (update, _) = timeout_secs(10, sub.next::<ExtrinsicUpdate<H256, H256>>())
+		.await
+		.unwrap()
+		.unwrap()
+		.unwrap();
+	assert_eq!(
+		update,
+		ExtrinsicUpdate::FeeInfo {
+			fee_info: ExtrinsicFeeInfo {
+				partial_fee: NumberOrHex::Number(len),
+				weight: Weight::from_parts(1_000, 10),
+			}
+		}
+	);
+
+	// The status events follow unchanged.
+	let (tx, _) = timeout_secs(10, sub.next::<TransactionStatus<H256, H256>>())
+		.await
+		.unwrap()
+		.unwrap()
+		.unwrap();
+	assert_matches!(tx, TransactionStatus::Ready);
+}
+
+#[tokio::test]
+async fn author_should_reject_fee_info_without_provider() {
+	let api = TestSetup::into_rpc();
+	let xt = to_hex(&uxt(AccountKeyring::Alice, 0).encode(), true);
+
+	let failed_sub = api.subscribe("author_submitAndWatchExtrinsic", (xt, true)).await;
+
+	assert_matches!(
+		failed_sub,
+		Err(RpcError::Call(CallError::Custom(err))) if err.message().contains("fee information")
+	);
+}
+
 #[tokio::test]
 async fn author_should_return_watch_validation_error() {
 	const METHOD: &'static str = "author_submitAndWatchExtrinsic";
@@ -215,6 +286,51 @@ async fn author_should_remove_extrinsics() {
 	assert_eq!(removed, vec![xt1_hash, xt2_hash, xt3_hash]);
 }
 
+#[tokio::test]
+async fn author_should_send_pool_events() {
+	let api = TestSetup::into_rpc();
+	let mut sub = api.subscribe("author_subscribePoolEvents", EmptyParams::new()).await.unwrap();
+
+	// The second extrinsic waits for the first one in the future queue.
+	let xt1 = to_hex(&uxt(AccountKeyring::Alice, 1).encode(), true);
+	let xt1_hash: H256 = api.call("author_submitExtrinsic", [xt1]).await.unwrap();
+	let xt0 = to_hex(&uxt(AccountKeyring::Alice, 0).encode(), true);
+	let xt0_hash: H256 = api.call("author_submitExtrinsic", [xt0]).await.unwrap();
+
+	let mut events = Vec::new();
+	for _ in 0..3 {
+		let (event, _) = timeout_secs(10, sub.next::<PoolEvent<H256, H256>>())
+			.await
+			.unwrap()
+			.unwrap()
+			.unwrap();
+		events.push(event);
+	}
+
+	assert_eq!(
+		events,
+		vec![
+			PoolEvent::Future { hash: xt1_hash },
+			PoolEvent::Ready { hash: xt0_hash },
+			PoolEvent::Promoted { hash: xt1_hash },
+		]
+	);
+}
+
+#[tokio::test]
+async fn author_should_deny_unsafe_pool_events() {
+	let setup = TestSetup::default();
+	let author = Author { deny_unsafe: DenyUnsafe::Yes, ..setup.author() };
+	let api = author.into_rpc();
+
+	let failed_sub = api.subscribe("author_subscribePoolEvents", EmptyParams::new()).await;
+
+	assert_matches!(
+		failed_sub,
+		Err(RpcError::Call(CallError::Custom(err))) if err.message().contains("unsafe")
+	);
+}
+
 #[tokio::test]
 async fn author_should_insert_key() {
 	let setup = TestSetup::default();
@@ -22,14 +22,19 @@ use super::{client_err, ChainBackend, Error};
 use crate::SubscriptionTaskExecutor;
 use std::{marker::PhantomData, sync::Arc};
 
+use codec::Encode;
 use futures::{
 	future::{self, FutureExt},
 	stream::{self, Stream, StreamExt},
 };
 use jsonrpsee::SubscriptionSink;
-use sc_client_api::{BlockBackend, BlockchainEvents};
+use sc_client_api::{extrinsic_index, AuxStore, BlockBackend, BlockchainEvents};
+use sc_rpc_api::chain::ExtrinsicDetails;
 use sp_blockchain::HeaderBackend;
-use sp_runtime::{generic::SignedBlock, traits::Block as BlockT};
+use sp_runtime::{
+	generic::SignedBlock,
+	traits::{Block as BlockT, NumberFor},
+};
 
 /// Blockchain API backend for full nodes. Reads all the data from local database.
 pub struct FullChain<Block: BlockT, Client> {
@@ -52,7 +57,8 @@ impl<Block, Client> ChainBackend<Client, Block> for FullChain<Block, Client>
 where
 	Block: BlockT + 'static,
 	Block::Header: Unpin,
-	Client: BlockBackend<Block> + HeaderBackend<Block> + BlockchainEvents<Block> + 'static,
+	Client:
+		BlockBackend<Block> + HeaderBackend<Block> + BlockchainEvents<Block> + AuxStore + 'static,
 {
 	fn client(&self) -> &Arc<Client> {
 		&self.client
@@ -66,6 +72,37 @@ where
 		self.client.block(self.unwrap_or_best(hash)).map_err(client_err)
 	}
 
+	fn extrinsic_by_hash(
+		&self,
+		hash: Block::Hash,
+	) -> Result<Option<ExtrinsicDetails<NumberFor<Block>, Block::Hash>>, Error> {
+		if !extrinsic_index::is_enabled(&*self.client).map_err(client_err)? {
+			return Err(Error::Other(
+				"The extrinsic index is disabled, see `--extrinsic-index`".into(),
+			))
+		}
+
+		for (block_hash, index) in
+			extrinsic_index::locations::<Block>(&*self.client, hash).map_err(client_err)?
+		{
+			let block_number = match self.client.number(block_hash).map_err(client_err)? {
+				Some(block_number) => block_number,
+				None => continue,
+			};
+			if self.client.hash(block_number).map_err(client_err)? != Some(block_hash) {
+				continue
+			}
+
+			let extrinsic =
+				self.client.block_body(block_hash).map_err(client_err)?.and_then(|extrinsics| {
+					extrinsics.get(index as usize).map(|xt| xt.encode().into())
+				});
+			return Ok(Some(ExtrinsicDetails { block_hash, block_number, index, extrinsic }))
+		}
+
+		Ok(None)
+	}
+
 	fn subscribe_all_heads(&self, sink: SubscriptionSink) {
 		subscribe_headers(
 			&self.client,
@@ -28,7 +28,7 @@ use std::sync::Arc;
 use crate::SubscriptionTaskExecutor;
 
 use jsonrpsee::{core::RpcResult, types::SubscriptionResult, SubscriptionSink};
-use sc_client_api::BlockchainEvents;
+use sc_client_api::{AuxStore, BlockchainEvents};
 use sp_rpc::{list::ListOrValue, number::NumberOrHex};
 use sp_runtime::{
 	generic::SignedBlock,
@@ -90,6 +90,12 @@ where
 		Ok(self.client().info().finalized_hash)
 	}
 
+	/// Get the extrinsic of the canon chain with the given hash.
+	fn extrinsic_by_hash(
+		&self,
+		hash: Block::Hash,
+	) -> Result<Option<ExtrinsicDetails<NumberFor<Block>, Block::Hash>>, Error>;
+
 	/// All new head subscription
 	fn subscribe_all_heads(&self, sink: SubscriptionSink);
 
@@ -108,7 +114,8 @@ pub fn new_full<Block: BlockT, Client>(
 where
 	Block: BlockT + 'static,
 	Block::Header: Unpin,
-	Client: BlockBackend<Block> + HeaderBackend<Block> + BlockchainEvents<Block> + 'static,
+	Client:
+		BlockBackend<Block> + HeaderBackend<Block> + BlockchainEvents<Block> + AuxStore + 'static,
 {
 	Chain { backend: Box::new(self::chain_full::FullChain::new(client, executor)) }
 }
@@ -156,6 +163,13 @@ where
 		self.backend.finalized_head().map_err(Into::into)
 	}
 
+	fn extrinsic_by_hash(
+		&self,
+		hash: Block::Hash,
+	) -> RpcResult<Option<ExtrinsicDetails<NumberFor<Block>, Block::Hash>>> {
+		self.backend.extrinsic_by_hash(hash).map_err(Into::into)
+	}
+
 	fn subscribe_all_heads(&self, sink: SubscriptionSink) -> SubscriptionResult {
 		self.backend.subscribe_all_heads(sink);
 		Ok(())
@@ -19,13 +19,16 @@
 use super::*;
 use crate::testing::{test_executor, timeout_secs};
 use assert_matches::assert_matches;
+use codec::Encode;
 use jsonrpsee::types::EmptyServerParams as EmptyParams;
 use sc_block_builder::BlockBuilderProvider;
+use sc_client_api::extrinsic_index;
 use sp_consensus::BlockOrigin;
+use sp_core::Bytes;
 use sp_rpc::list::ListOrValue;
 use substrate_test_runtime_client::{
 	prelude::*,
-	runtime::{Block, Header, H256},
+	runtime::{Block, Header, Transfer, H256},
 };
 
 #[tokio::test]
@@ -211,6 +214,54 @@ async fn should_return_finalized_hash() {
 	assert_eq!(res, block_hash);
 }
 
+#[tokio::test]
+async fn should_return_extrinsic_by_hash() {
+	let mut client = Arc::new(substrate_test_runtime_client::new());
+	let api = new_full(client.clone(), test_executor()).into_rpc();
+
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder
+		.push_transfer(Transfer {
+			from: AccountKeyring::Alice.into(),
+			to: AccountKeyring::Ferdie.into(),
+			amount: 42,
+			nonce: 0,
+		})
+		.unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = block.hash();
+	let extrinsic = block.extrinsics[0].clone();
+	let extrinsic_hash = H256::from(sp_core::blake2_256(&extrinsic.encode()));
+	client.import(BlockOrigin::Own, block).await.unwrap();
+
+	// the index is disabled
+	assert!(api
+		.call::<_, Option<ExtrinsicDetails<u64, H256>>>(
+			"chain_getExtrinsicByHash",
+			[extrinsic_hash]
+		)
+		.await
+		.is_err());
+
+	extrinsic_index::enable(&*client).unwrap();
+	let res: Option<ExtrinsicDetails<u64, H256>> =
+		api.call("chain_getExtrinsicByHash", [extrinsic_hash]).await.unwrap();
+	assert_eq!(res, None);
+
+	extrinsic_index::index_block::<Block>(&*client, block_hash, &[extrinsic.clone()]).unwrap();
+	let res: Option<ExtrinsicDetails<u64, H256>> =
+		api.call("chain_getExtrinsicByHash", [extrinsic_hash]).await.unwrap();
+	assert_eq!(
+		res,
+		Some(ExtrinsicDetails {
+			block_hash,
+			block_number: 1,
+			index: 0,
+			extrinsic: Some(Bytes(extrinsic.encode())),
+		})
+	);
+}
+
 #[tokio::test]
 async fn should_notify_about_latest_block() {
 	test_head_subscription("chain_subscribeAllHeads").await;
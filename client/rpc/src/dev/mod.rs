@@ -25,12 +25,13 @@ mod tests;
 use jsonrpsee::core::RpcResult;
 use sc_client_api::{BlockBackend, HeaderBackend};
 use sc_rpc_api::{dev::error::Error, DenyUnsafe};
-use sp_api::{ApiExt, Core, ProvideRuntimeApi};
-use sp_core::Encode;
+use sp_api::{ApiExt, CallApiAt, Core, ProvideRuntimeApi};
+use sp_core::{twox_128, Decode, Encode};
 use sp_runtime::{
 	generic::DigestItem,
 	traits::{Block as BlockT, Header},
 };
+use sp_weights::Weight;
 use std::{
 	marker::{PhantomData, Send, Sync},
 	sync::Arc,
@@ -40,6 +41,11 @@ pub use sc_rpc_api::dev::{BlockStats, DevApiServer};
 
 type HasherOf<Block> = <<Block as BlockT>::Header as Header>::Hashing;
 
+/// The storage key of `frame_system::BlockWeight`, the weight consumed by the current block.
+fn block_weight_key() -> Vec<u8> {
+	[twox_128(b"System"), twox_128(b"BlockWeight")].concat()
+}
+
 /// The Dev API. All methods are unsafe.
 pub struct Dev<Block: BlockT, Client> {
 	client: Arc<Client>,
@@ -60,6 +66,7 @@ where
 	Client: BlockBackend<Block>
 		+ HeaderBackend<Block>
 		+ ProvideRuntimeApi<Block>
+		+ CallApiAt<Block>
 		+ Send
 		+ Sync
 		+ 'static,
@@ -95,19 +102,61 @@ where
 		let block_len = block.encoded_size() as u64;
 		let num_extrinsics = block.extrinsics().len() as u64;
 		let pre_root = *parent_header.state_root();
+		let parent_hash = parent_header.hash();
 		let mut runtime_api = self.client.runtime_api();
 		runtime_api.record_proof();
 		runtime_api
-			.execute_block(parent_header.hash(), block)
+			.execute_block(parent_hash, block)
 			.map_err(|_| Error::BlockExecutionFailed)?;
+		let num_storage_reads = runtime_api
+			.proof_recorder()
+			.expect("We enabled proof recording. A proof recorder must be available; qed")
+			.recorded_keys_count() as u64;
 		let witness = runtime_api
 			.extract_proof()
 			.expect("We enabled proof recording. A proof must be available; qed");
+		let changes = {
+			let state = self
+				.client
+				.state_at(parent_hash)
+				.map_err(|e| Error::BlockQueryError(Box::new(e)))?;
+			runtime_api
+				.into_storage_changes(&state, parent_hash)
+				.map_err(Error::StorageChangesFailed)?
+		};
+		let num_storage_writes = changes.main_storage_changes.len() as u64 +
+			changes
+				.child_storage_changes
+				.iter()
+				.map(|(_, changes)| changes.len() as u64)
+				.sum::<u64>();
+		// The weight is stored per dispatch class and removed at the start of every block.
+		let block_weight_key = block_weight_key();
+		let total_weight = changes
+			.main_storage_changes
+			.iter()
+			.find(|(key, _)| *key == block_weight_key)
+			.and_then(|(_, value)| match value {
+				Some(value) => <[Weight; 3]>::decode(&mut &value[..]).ok().map(|weights| {
+					weights
+						.into_iter()
+						.fold(Weight::zero(), |total, weight| total.saturating_add(weight))
+				}),
+				None => Some(Weight::zero()),
+			});
 		let witness_len = witness.encoded_size() as u64;
 		let witness_compact_len = witness
 			.into_compact_proof::<HasherOf<Block>>(pre_root)
 			.map_err(|_| Error::WitnessCompactionFailed)?
 			.encoded_size() as u64;
-		Ok(Some(BlockStats { witness_len, witness_compact_len, block_len, num_extrinsics }))
+		Ok(Some(BlockStats {
+			witness_len,
+			witness_compact_len,
+			block_len,
+			num_extrinsics,
+			num_storage_reads,
+			num_storage_writes,
+			total_weight,
+		}))
 	}
 }
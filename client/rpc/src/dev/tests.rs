@@ -29,18 +29,29 @@ async fn block_stats_work() {
 
 	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
 
-	let (expected_witness_len, expected_witness_compact_len, expected_block_len) = {
+	let (
+		expected_witness_len,
+		expected_witness_compact_len,
+		expected_block_len,
+		expected_num_storage_reads,
+		expected_num_storage_writes,
+	) = {
 		let genesis_hash = client.chain_info().genesis_hash;
 		let mut runtime_api = client.runtime_api();
 		runtime_api.record_proof();
 		runtime_api.execute_block(genesis_hash, block.clone()).unwrap();
+		let num_storage_reads = runtime_api.proof_recorder().unwrap().recorded_keys_count();
 		let witness = runtime_api.extract_proof().unwrap();
 		let pre_root = *client.header(genesis_hash).unwrap().unwrap().state_root();
+		let state = client.state_at(genesis_hash).unwrap();
+		let changes = runtime_api.into_storage_changes(&state, genesis_hash).unwrap();
 
 		(
 			witness.clone().encoded_size() as u64,
 			witness.into_compact_proof::<HasherOf<Block>>(pre_root).unwrap().encoded_size() as u64,
 			block.encoded_size() as u64,
+			num_storage_reads as u64,
+			changes.main_storage_changes.len() as u64,
 		)
 	};
 
@@ -54,17 +65,27 @@ async fn block_stats_work() {
 		None
 	);
 
+	let stats = api
+		.call::<_, Option<BlockStats>>("dev_getBlockStats", [client.info().best_hash])
+		.await
+		.unwrap()
+		.unwrap();
+	// The test runtime is built with FRAME, which records the weight of every block.
+	assert!(stats.total_weight.is_some());
 	assert_eq!(
-		api.call::<_, Option<BlockStats>>("dev_getBlockStats", [client.info().best_hash])
-			.await
-			.unwrap(),
-		Some(BlockStats {
+		stats,
+		BlockStats {
 			witness_len: expected_witness_len,
 			witness_compact_len: expected_witness_compact_len,
 			block_len: expected_block_len,
 			num_extrinsics: 0,
-		}),
+			num_storage_reads: expected_num_storage_reads,
+			num_storage_writes: expected_num_storage_writes,
+			total_weight: stats.total_weight,
+		},
 	);
+	assert!(expected_num_storage_reads > 0);
+	assert!(expected_num_storage_writes > 0);
 }
 
 #[tokio::test]
@@ -24,7 +24,7 @@ mod utils;
 #[cfg(test)]
 mod tests;
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use crate::SubscriptionTaskExecutor;
 
@@ -53,6 +53,13 @@ use sp_blockchain::{HeaderBackend, HeaderMetadata};
 
 const STORAGE_KEYS_PAGED_MAX_COUNT: u32 = 1000;
 
+/// The default number of blocks of the pages of `state_subscribeQueryStorage`.
+const QUERY_STORAGE_DEFAULT_PAGE_SIZE: u32 = 100;
+/// The maximum number of blocks of the pages of `state_subscribeQueryStorage`.
+const QUERY_STORAGE_MAX_PAGE_SIZE: u32 = 1000;
+/// The maximum number of pages of `state_subscribeQueryStorage` queried concurrently.
+const QUERY_STORAGE_MAX_IN_FLIGHT: u32 = 8;
+
 /// State backend API.
 #[async_trait]
 pub trait StateBackend<Block: BlockT, Client>: Send + Sync + 'static
@@ -162,6 +169,27 @@ where
 
 	/// New storage subscription
 	fn subscribe_storage(&self, sink: SubscriptionSink, keys: Option<Vec<StorageKey>>);
+
+	/// Stream the changes of the storage entries in a range of blocks, in pages of `page_size`
+	/// blocks. At most `max_in_flight` pages are queried concurrently.
+	fn subscribe_query_storage(
+		&self,
+		sink: SubscriptionSink,
+		keys: Vec<StorageKey>,
+		from: Block::Hash,
+		to: Option<Block::Hash>,
+		page_size: u32,
+		max_in_flight: u32,
+	);
+
+	/// Stream the keys with the given prefix in batches of `batch_size` keys.
+	fn subscribe_keys_by_prefix(
+		&self,
+		sink: SubscriptionSink,
+		prefix: StorageKey,
+		batch_size: u32,
+		block: Option<Block::Hash>,
+	);
 }
 
 /// Create new state API that works on full node.
@@ -188,9 +216,44 @@ where
 		+ 'static,
 	Client::Api: Metadata<Block>,
 {
-	let child_backend =
-		Box::new(self::state_full::FullState::new(client.clone(), executor.clone()));
-	let backend = Box::new(self::state_full::FullState::new(client, executor));
+	new_full_with_call_timeout(client, executor, deny_unsafe, None)
+}
+
+/// Create new state API that works on full node.
+///
+/// The calls iterating the storage are cancelled once they run for longer than
+/// `call_timeout`, if any.
+pub fn new_full_with_call_timeout<BE, Block: BlockT, Client>(
+	client: Arc<Client>,
+	executor: SubscriptionTaskExecutor,
+	deny_unsafe: DenyUnsafe,
+	call_timeout: Option<Duration>,
+) -> (State<Block, Client>, ChildState<Block, Client>)
+where
+	Block: BlockT + 'static,
+	Block::Hash: Unpin,
+	BE: Backend<Block> + 'static,
+	Client: ExecutorProvider<Block>
+		+ StorageProvider<Block, BE>
+		+ ProofProvider<Block>
+		+ HeaderMetadata<Block, Error = sp_blockchain::Error>
+		+ BlockchainEvents<Block>
+		+ CallApiAt<Block>
+		+ HeaderBackend<Block>
+		+ BlockBackend<Block>
+		+ ProvideRuntimeApi<Block>
+		+ Send
+		+ Sync
+		+ 'static,
+	Client::Api: Metadata<Block>,
+{
+	let child_backend = Box::new(
+		self::state_full::FullState::new(client.clone(), executor.clone())
+			.with_call_timeout(call_timeout),
+	);
+	let backend = Box::new(
+		self::state_full::FullState::new(client, executor).with_call_timeout(call_timeout),
+	);
 	(State { backend, deny_unsafe }, ChildState { backend: child_backend })
 }
 
@@ -345,6 +408,51 @@ where
 		self.backend.subscribe_storage(sink, keys);
 		Ok(())
 	}
+
+	fn subscribe_query_storage(
+		&self,
+		mut sink: SubscriptionSink,
+		keys: Vec<StorageKey>,
+		from: Block::Hash,
+		to: Option<Block::Hash>,
+		page_size: Option<u32>,
+		max_in_flight: Option<u32>,
+	) -> SubscriptionResult {
+		let page_size = page_size.unwrap_or(QUERY_STORAGE_DEFAULT_PAGE_SIZE);
+		let max_in_flight = max_in_flight.unwrap_or(1);
+		for (value, max) in
+			[(page_size, QUERY_STORAGE_MAX_PAGE_SIZE), (max_in_flight, QUERY_STORAGE_MAX_IN_FLIGHT)]
+		{
+			if value == 0 || value > max {
+				let _ = sink.reject(JsonRpseeError::from(Error::InvalidCount { value, max }));
+				return Ok(())
+			}
+		}
+
+		self.backend
+			.subscribe_query_storage(sink, keys, from, to, page_size, max_in_flight);
+		Ok(())
+	}
+
+	fn subscribe_keys_by_prefix(
+		&self,
+		mut sink: SubscriptionSink,
+		prefix: StorageKey,
+		batch_size: Option<u32>,
+		block: Option<Block::Hash>,
+	) -> SubscriptionResult {
+		let batch_size = batch_size.unwrap_or(STORAGE_KEYS_PAGED_MAX_COUNT);
+		if batch_size == 0 || batch_size > STORAGE_KEYS_PAGED_MAX_COUNT {
+			let _ = sink.reject(JsonRpseeError::from(Error::InvalidCount {
+				value: batch_size,
+				max: STORAGE_KEYS_PAGED_MAX_COUNT,
+			}));
+			return Ok(())
+		}
+
+		self.backend.subscribe_keys_by_prefix(sink, prefix, batch_size, block);
+		Ok(())
+	}
 }
 
 /// Child state backend API.
@@ -23,13 +23,14 @@ use std::{collections::HashMap, marker::PhantomData, sync::Arc, time::Duration};
 use super::{
 	client_err,
 	error::{Error, Result},
+	utils::{Deadline, SpawnWithTimeoutError},
 	ChildStateBackend, StateBackend,
 };
 use crate::{DenyUnsafe, SubscriptionTaskExecutor};
 
-use futures::{future, stream, FutureExt, StreamExt};
+use futures::{future, stream, FutureExt, StreamExt, TryStreamExt};
 use jsonrpsee::{
-	core::{async_trait, Error as JsonRpseeError},
+	core::{async_trait, server::rpc_module::SubscriptionClosed, Error as JsonRpseeError},
 	SubscriptionSink,
 };
 use sc_client_api::{
@@ -65,6 +66,8 @@ struct QueryStorageRange<Block: BlockT> {
 pub struct FullState<BE, Block: BlockT, Client> {
 	client: Arc<Client>,
 	executor: SubscriptionTaskExecutor,
+	/// The maximum time allowed for the calls iterating the storage, if any.
+	call_timeout: Option<Duration>,
 	_phantom: PhantomData<(BE, Block)>,
 }
 
@@ -79,7 +82,35 @@ where
 {
 	/// Create new state API backend for full nodes.
 	pub fn new(client: Arc<Client>, executor: SubscriptionTaskExecutor) -> Self {
-		Self { client, executor, _phantom: PhantomData }
+		Self { client, executor, call_timeout: None, _phantom: PhantomData }
+	}
+
+	/// Cancel the calls iterating the storage once they run for longer than `timeout`.
+	///
+	/// The runtime calls are not cancelled, they can't be interrupted once started.
+	pub fn with_call_timeout(mut self, timeout: Option<Duration>) -> Self {
+		self.call_timeout = timeout;
+		self
+	}
+
+	/// Returns the error of a call which ran for longer than the timeout.
+	fn timed_out(&self) -> Error {
+		Error::CallTimeout(self.call_timeout.unwrap_or_default())
+	}
+
+	/// Collects the items of the storage iterator, unless the call runs for longer than the
+	/// timeout.
+	fn collect_until_timeout<T>(
+		&self,
+		iter: impl Iterator<Item = T>,
+	) -> std::result::Result<Vec<T>, Error> {
+		let deadline = Deadline::new(self.call_timeout);
+		let mut items = Vec::new();
+		for item in iter {
+			deadline.check_if_timed_out().map_err(|_| self.timed_out())?;
+			items.push(item);
+		}
+		Ok(items)
 	}
 
 	/// Returns given block hash or best block hash if None is passed.
@@ -92,6 +123,7 @@ where
 		&self,
 		from: Block::Hash,
 		to: Option<Block::Hash>,
+		deadline: &Deadline,
 	) -> Result<QueryStorageRange<Block>> {
 		let to = self
 			.block_or_best(to)
@@ -116,6 +148,7 @@ where
 			let mut hashes = vec![to_meta.hash];
 			let mut last = to_meta.clone();
 			while last.number > from_number {
+				deadline.check_if_timed_out().map_err(|_| self.timed_out())?;
 				let header_metadata = self
 					.client
 					.header_metadata(last.parent)
@@ -137,6 +170,37 @@ where
 		Ok(QueryStorageRange { hashes })
 	}
 
+	/// Queries the changes of keys' values in a page of consecutive blocks.
+	///
+	/// The values are compared to the ones at `parent`, the last block of the previous page.
+	/// All the keys are reported in the first change set of the first page, without `parent`.
+	fn query_storage_page(
+		&self,
+		parent: Option<Block::Hash>,
+		hashes: Vec<Block::Hash>,
+		keys: &[StorageKey],
+	) -> Result<Vec<StorageChangeSet<Block::Hash>>> {
+		let deadline = Deadline::new(self.call_timeout);
+		let mut last_values = HashMap::new();
+		if let Some(parent) = parent {
+			for key in keys {
+				deadline.check_if_timed_out().map_err(|_| self.timed_out())?;
+				let data = self.client.storage(parent, key).map_err(client_err)?;
+				last_values.insert(key.clone(), data);
+			}
+		}
+
+		let mut changes = Vec::new();
+		self.query_storage_unfiltered(
+			&QueryStorageRange { hashes },
+			keys,
+			&mut last_values,
+			&mut changes,
+			&deadline,
+		)?;
+		Ok(changes)
+	}
+
 	/// Iterates through range.unfiltered_range and check each block for changes of keys' values.
 	fn query_storage_unfiltered(
 		&self,
@@ -144,10 +208,12 @@ where
 		keys: &[StorageKey],
 		last_values: &mut HashMap<StorageKey, Option<StorageData>>,
 		changes: &mut Vec<StorageChangeSet<Block::Hash>>,
+		deadline: &Deadline,
 	) -> Result<()> {
 		for block_hash in &range.hashes {
 			let mut block_changes = StorageChangeSet { block: *block_hash, changes: Vec::new() };
 			for key in keys {
+				deadline.check_if_timed_out().map_err(|_| self.timed_out())?;
 				let (has_changed, data) = {
 					let curr_data = self.client.storage(*block_hash, key).map_err(client_err)?;
 					match last_values.get(key) {
@@ -211,10 +277,11 @@ where
 		prefix: StorageKey,
 	) -> std::result::Result<Vec<StorageKey>, Error> {
 		// TODO: Remove the `.collect`.
-		self.block_or_best(block)
+		let iter = self
+			.block_or_best(block)
 			.and_then(|block| self.client.storage_keys(block, Some(&prefix), None))
-			.map(|iter| iter.collect())
-			.map_err(client_err)
+			.map_err(client_err)?;
+		self.collect_until_timeout(iter)
 	}
 
 	// TODO: This is horribly broken; either remove it, or make it streaming.
@@ -224,10 +291,11 @@ where
 		prefix: StorageKey,
 	) -> std::result::Result<Vec<(StorageKey, StorageData)>, Error> {
 		// TODO: Remove the `.collect`.
-		self.block_or_best(block)
+		let iter = self
+			.block_or_best(block)
 			.and_then(|block| self.client.storage_pairs(block, Some(&prefix), None))
-			.map(|iter| iter.collect())
-			.map_err(client_err)
+			.map_err(client_err)?;
+		self.collect_until_timeout(iter)
 	}
 
 	fn storage_keys_paged(
@@ -237,10 +305,11 @@ where
 		count: u32,
 		start_key: Option<StorageKey>,
 	) -> std::result::Result<Vec<StorageKey>, Error> {
-		self.block_or_best(block)
+		let iter = self
+			.block_or_best(block)
 			.and_then(|block| self.client.storage_keys(block, prefix.as_ref(), start_key.as_ref()))
-			.map(|iter| iter.take(count as usize).collect())
-			.map_err(client_err)
+			.map_err(client_err)?;
+		self.collect_until_timeout(iter.take(count as usize))
 	}
 
 	fn storage(
@@ -269,6 +338,7 @@ where
 			DenyUnsafe::Yes => Some(MAXIMUM_SAFE_RPC_CALL_TIMEOUT),
 			DenyUnsafe::No => None,
 		};
+		let timeout = timeout.into_iter().chain(self.call_timeout).min();
 
 		super::utils::spawn_blocking_with_timeout(timeout, move |is_timed_out| {
 			// Does the key point to a concrete entry in the database?
@@ -299,7 +369,10 @@ where
 			}
 		})
 		.await
-		.map_err(|error| Error::Client(Box::new(error)))?
+		.map_err(|error| match error {
+			SpawnWithTimeoutError::Timeout => Error::CallTimeout(timeout.unwrap_or_default()),
+			error => Error::Client(Box::new(error)),
+		})?
 	}
 
 	fn storage_hash(
@@ -338,10 +411,17 @@ where
 		keys: Vec<StorageKey>,
 	) -> std::result::Result<Vec<StorageChangeSet<Block::Hash>>, Error> {
 		let call_fn = move || {
-			let range = self.query_storage_range(from, to)?;
+			let deadline = Deadline::new(self.call_timeout);
+			let range = self.query_storage_range(from, to, &deadline)?;
 			let mut changes = Vec::new();
 			let mut last_values = HashMap::new();
-			self.query_storage_unfiltered(&range, &keys, &mut last_values, &mut changes)?;
+			self.query_storage_unfiltered(
+				&range,
+				&keys,
+				&mut last_values,
+				&mut changes,
+				&deadline,
+			)?;
 			Ok(changes)
 		};
 		call_fn()
@@ -457,6 +537,133 @@ where
 		self.executor.spawn("substrate-rpc-subscription", Some("rpc"), fut.boxed());
 	}
 
+	fn subscribe_query_storage(
+		&self,
+		mut sink: SubscriptionSink,
+		keys: Vec<StorageKey>,
+		from: Block::Hash,
+		to: Option<Block::Hash>,
+		page_size: u32,
+		max_in_flight: u32,
+	) {
+		// Only the hashes of the range are kept in memory, the changes are queried page by page.
+		let range = match self.query_storage_range(from, to, &Deadline::new(self.call_timeout)) {
+			Ok(range) => range,
+			Err(e) => {
+				let _ = sink.reject(JsonRpseeError::from(e));
+				return
+			},
+		};
+
+		let mut parent = None;
+		let pages = range
+			.hashes
+			.chunks(page_size as usize)
+			.map(|hashes| {
+				let page = (parent, hashes.to_vec());
+				parent = hashes.last().copied();
+				page
+			})
+			.collect::<Vec<_>>();
+
+		let client = self.client.clone();
+		let executor = self.executor.clone();
+		let call_timeout = self.call_timeout;
+		let keys = Arc::new(keys);
+		// Every page is compared to the last block of the previous one, so that the pages can be
+		// queried concurrently.
+		let changes = stream::iter(pages)
+			.map(move |(parent, hashes)| {
+				let state =
+					Self::new(client.clone(), executor.clone()).with_call_timeout(call_timeout);
+				let keys = keys.clone();
+				async move {
+					tokio::task::spawn_blocking(move || {
+						state.query_storage_page(parent, hashes, &keys)
+					})
+					.await
+					.map_err(|e| Error::Client(Box::new(e)))
+					.and_then(|changes| changes)
+				}
+			})
+			.buffered(max_in_flight as usize)
+			.try_filter(|changes| future::ready(!changes.is_empty()));
+
+		let fut = async move {
+			match sink.pipe_from_try_stream(changes.boxed()).await {
+				SubscriptionClosed::RemotePeerAborted => {},
+				// Notify the client that the whole range has been sent, or of the failure.
+				closed => {
+					sink.close(closed);
+				},
+			}
+		};
+
+		self.executor.spawn("substrate-rpc-subscription", Some("rpc"), fut.boxed());
+	}
+
+	fn subscribe_keys_by_prefix(
+		&self,
+		mut sink: SubscriptionSink,
+		prefix: StorageKey,
+		batch_size: u32,
+		block: Option<Block::Hash>,
+	) {
+		// Reject the unknown blocks before accepting the subscription.
+		let block = match self
+			.block_or_best(block)
+			.and_then(|block| self.client.storage_keys(block, Some(&prefix), None).map(|_| block))
+		{
+			Ok(block) => block,
+			Err(e) => {
+				let _ = sink.reject(JsonRpseeError::from(client_err(e)));
+				return
+			},
+		};
+
+		let client = self.client.clone();
+		// Every batch starts after the last key of the previous one, the stream ends after the
+		// first batch which isn't full.
+		let batches = stream::unfold(Some(None), move |start_key: Option<Option<StorageKey>>| {
+			let client = client.clone();
+			let prefix = prefix.clone();
+			async move {
+				let start_key = start_key?;
+				let batch = tokio::task::spawn_blocking(move || {
+					client
+						.storage_keys(block, Some(&prefix), start_key.as_ref())
+						.map(|iter| iter.take(batch_size as usize).collect::<Vec<_>>())
+						.map_err(client_err)
+				})
+				.await
+				.map_err(|e| Error::Client(Box::new(e)))
+				.and_then(|batch| batch);
+
+				match batch {
+					Ok(batch) if batch.is_empty() => None,
+					Ok(batch) => {
+						let next =
+							(batch.len() == batch_size as usize).then(|| batch.last().cloned());
+						Some((Ok(batch), next))
+					},
+					Err(e) => Some((Err(e), None)),
+				}
+			}
+		});
+
+		let fut = async move {
+			match sink.pipe_from_try_stream(batches.boxed()).await {
+				SubscriptionClosed::RemotePeerAborted => {},
+				// Notify the client that all the keys have been sent, or of the failure.
+				closed => {
+					sink.close(closed);
+				},
+			}
+		};
+
+		self.executor.spawn("substrate-rpc-subscription", Some("rpc"), fut.boxed());
+	}
+
 	fn trace_block(
 		&self,
 		block: Block::Hash,
@@ -526,7 +733,8 @@ where
 		prefix: StorageKey,
 	) -> std::result::Result<Vec<StorageKey>, Error> {
 		// TODO: Remove the `.collect`.
-		self.block_or_best(block)
+		let iter = self
+			.block_or_best(block)
 			.and_then(|block| {
 				let child_info = match ChildType::from_prefixed_key(&storage_key) {
 					Some((ChildType::ParentKeyId, storage_key)) =>
@@ -535,8 +743,8 @@ where
 				};
 				self.client.child_storage_keys(block, child_info, Some(&prefix), None)
 			})
-			.map(|iter| iter.collect())
-			.map_err(client_err)
+			.map_err(client_err)?;
+		self.collect_until_timeout(iter)
 	}
 
 	fn storage_keys_paged(
@@ -547,7 +755,8 @@ where
 		count: u32,
 		start_key: Option<StorageKey>,
 	) -> std::result::Result<Vec<StorageKey>, Error> {
-		self.block_or_best(block)
+		let iter = self
+			.block_or_best(block)
 			.and_then(|block| {
 				let child_info = match ChildType::from_prefixed_key(&storage_key) {
 					Some((ChildType::ParentKeyId, storage_key)) =>
@@ -561,8 +770,8 @@ where
 					start_key.as_ref(),
 				)
 			})
-			.map(|iter| iter.take(count as usize).collect())
-			.map_err(client_err)
+			.map_err(client_err)?;
+		self.collect_until_timeout(iter.take(count as usize))
 	}
 
 	fn storage(
@@ -194,6 +194,115 @@ async fn should_return_child_storage_entries() {
 	assert_matches!(child.storage_size(child_key.clone(), keys[0].clone(), None), Ok(Some(1)));
 }
 
+#[tokio::test]
+async fn should_stream_keys_by_prefix() {
+	let client = TestClientBuilder::new()
+		.add_extra_storage(b":map:acc1".to_vec(), vec![1])
+		.add_extra_storage(b":map:acc2".to_vec(), vec![2])
+		.add_extra_storage(b":map:acc3".to_vec(), vec![3])
+		.build();
+	let (api, _child) = new_full(Arc::new(client), test_executor(), DenyUnsafe::Yes);
+	let api_rpc = api.into_rpc();
+	let prefix = StorageKey(b":map".to_vec());
+
+	let mut sub = api_rpc
+		.subscribe("state_subscribeKeysByPrefix", (prefix.clone(), 2, None::<H256>))
+		.await
+		.unwrap();
+	let key = |name: &[u8]| StorageKey([&b":map:"[..], name].concat());
+	let (batch, _) =
+		timeout_secs(1, sub.next::<Vec<StorageKey>>()).await.unwrap().unwrap().unwrap();
+	assert_eq!(batch, vec![key(b"acc1"), key(b"acc2")]);
+	let (batch, _) =
+		timeout_secs(1, sub.next::<Vec<StorageKey>>()).await.unwrap().unwrap().unwrap();
+	assert_eq!(batch, vec![key(b"acc3")]);
+	// The subscription is closed once all the keys have been sent.
+	assert_matches!(timeout_secs(1, sub.next::<Vec<StorageKey>>()).await, Ok(None));
+
+	let err = api_rpc
+		.subscribe("state_subscribeKeysByPrefix", (prefix, 1001, None::<H256>))
+		.await;
+	assert_matches!(err, Err(RpcError::Call(RpcCallError::Custom(e))) if e.message().starts_with("count exceeds maximum value"));
+}
+
+#[tokio::test]
+async fn should_stream_storage_changes() {
+	let mut client = Arc::new(substrate_test_runtime_client::new());
+	let (api, _child) = new_full(client.clone(), test_executor(), DenyUnsafe::No);
+	let genesis_hash = client.genesis_hash();
+
+	for index in 0..3 {
+		let mut builder = client.new_block(Default::default()).unwrap();
+		builder
+			.push(
+				ExtrinsicBuilder::new_storage_change(
+					vec![3],
+					if index == 0 { Some(vec![3]) } else { None },
+				)
+				.build(),
+			)
+			.unwrap();
+		builder
+			.push(ExtrinsicBuilder::new_storage_change(vec![5], Some(vec![index])).build())
+			.unwrap();
+		let block = builder.build().unwrap().block;
+		executor::block_on(client.import(BlockOrigin::Own, block)).unwrap();
+	}
+
+	let keys = vec![StorageKey(vec![3]), StorageKey(vec![5])];
+	let expected = api.query_storage(keys.clone(), genesis_hash, None).unwrap();
+	assert_eq!(expected.len(), 4);
+
+	let api_rpc = api.into_rpc();
+	let mut sub = api_rpc
+		.subscribe("state_subscribeQueryStorage", (keys.clone(), genesis_hash, None::<H256>, 2, 2))
+		.await
+		.unwrap();
+	// The pages are compared to the last block of the previous page.
+	let (page, _) = timeout_secs(1, sub.next::<Vec<StorageChangeSet<H256>>>())
+		.await
+		.unwrap()
+		.unwrap()
+		.unwrap();
+	assert_eq!(page, expected[..2]);
+	let (page, _) = timeout_secs(1, sub.next::<Vec<StorageChangeSet<H256>>>())
+		.await
+		.unwrap()
+		.unwrap()
+		.unwrap();
+	assert_eq!(page, expected[2..]);
+	// The subscription is closed once the whole range has been sent.
+	assert_matches!(timeout_secs(1, sub.next::<Vec<StorageChangeSet<H256>>>()).await, Ok(None));
+
+	let err = api_rpc
+		.subscribe("state_subscribeQueryStorage", (keys, genesis_hash, None::<H256>, 0, 1))
+		.await;
+	assert_matches!(err, Err(RpcError::Call(RpcCallError::Custom(e))) if e.message().starts_with("count exceeds maximum value"));
+}
+
+#[tokio::test]
+async fn storage_iteration_is_cancelled_after_the_call_timeout() {
+	let client = Arc::new(substrate_test_runtime_client::new());
+	let (api, _child) =
+		new_full_with_call_timeout(client.clone(), test_executor(), DenyUnsafe::No, None);
+	assert!(!api.storage_keys_paged(None, 10, None, None).unwrap().is_empty());
+
+	let (api, _child) = new_full_with_call_timeout(
+		client,
+		test_executor(),
+		DenyUnsafe::No,
+		Some(std::time::Duration::ZERO),
+	);
+	assert_matches!(
+		api.storage_keys_paged(None, 10, None, None),
+		Err(RpcError::Call(RpcCallError::Custom(e))) if e.message().starts_with("call has been cancelled")
+	);
+	assert_matches!(
+		api.storage_keys(StorageKey(vec![]), None),
+		Err(RpcError::Call(RpcCallError::Custom(e))) if e.message().starts_with("call has been cancelled")
+	);
+}
+
 #[tokio::test]
 async fn should_call_contract() {
 	let client = Arc::new(substrate_test_runtime_client::new());
@@ -21,7 +21,7 @@ use std::{
 		atomic::{AtomicBool, Ordering},
 		Arc,
 	},
-	time::Duration,
+	time::{Duration, Instant},
 };
 
 /// An error signifying that a task has been cancelled due to a timeout.
@@ -50,6 +50,27 @@ impl IsTimedOut {
 	}
 }
 
+/// A deadline of a call executed on the current thread.
+///
+/// The call should continuously call [`Deadline::check_if_timed_out`], which will return an
+/// error once the call runs for longer than the timeout.
+pub struct Deadline(Option<Instant>);
+
+impl Deadline {
+	/// Start the deadline of a call. The call never times out if `timeout` is `None`.
+	pub fn new(timeout: Option<Duration>) -> Self {
+		Deadline(timeout.and_then(|timeout| Instant::now().checked_add(timeout)))
+	}
+
+	#[must_use]
+	pub fn check_if_timed_out(&self) -> std::result::Result<(), Timeout> {
+		match self.0 {
+			Some(deadline) if Instant::now() >= deadline => Err(Timeout),
+			_ => Ok(()),
+		}
+	}
+}
+
 /// An error for a task which either panicked, or has been cancelled due to a timeout.
 #[derive(Debug)]
 pub enum SpawnWithTimeoutError {
@@ -137,4 +158,14 @@ mod tests {
 
 		assert_matches::assert_matches!(task, Ok(()));
 	}
+
+	#[test]
+	fn deadline_works() {
+		let deadline = Deadline::new(Some(Duration::from_millis(20)));
+		assert!(deadline.check_if_timed_out().is_ok());
+		std::thread::sleep(Duration::from_millis(40));
+		assert!(deadline.check_if_timed_out().is_err());
+
+		assert!(Deadline::new(None).check_if_timed_out().is_ok());
+	}
 }
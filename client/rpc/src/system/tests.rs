@@ -112,6 +112,13 @@ fn api<T: Into<Option<Status>>>(sync: T) -> RpcModule<System<Block>> {
 							sender.send(Err(error::Error::MalformattedPeerArg(s.to_string()))),
 					};
 				},
+				Request::NetworkAddKnownAddress(peer, sender) => {
+					let _ = match sc_network::config::parse_str_addr(&peer) {
+						Ok(_) => sender.send(Ok(())),
+						Err(s) =>
+							sender.send(Err(error::Error::MalformattedPeerArg(s.to_string()))),
+					};
+				},
 				Request::NetworkReservedPeers(sender) => {
 					let _ = sender
 						.send(vec!["QmSk5HQbn6LhUwDiNMseVUjuRYhEtYj4aUZ6WfWoGURpdV".to_string()]);
@@ -124,6 +131,14 @@ fn api<T: Into<Option<Status>>>(sync: T) -> RpcModule<System<Block>> {
 						starting_block: 1,
 						current_block: 2,
 						highest_block: 3,
+						peers: vec![PeerSyncState {
+							peer_id: "QmSk5HQbn6LhUwDiNMseVUjuRYhEtYj4aUZ6WfWoGURpdV".to_string(),
+							best_number: 3,
+							common_number: 2,
+							downloaded_bytes: 1024,
+							download_speed: 512,
+						}],
+						gap_sync: None,
 					});
 				},
 			};
@@ -297,7 +312,22 @@ async fn system_node_roles() {
 async fn system_sync_state() {
 	let sync_state: SyncState<i32> =
 		api(None).call("system_syncState", EmptyParams::new()).await.unwrap();
-	assert_eq!(sync_state, SyncState { starting_block: 1, current_block: 2, highest_block: 3 });
+	assert_eq!(
+		sync_state,
+		SyncState {
+			starting_block: 1,
+			current_block: 2,
+			highest_block: 3,
+			peers: vec![PeerSyncState {
+				peer_id: "QmSk5HQbn6LhUwDiNMseVUjuRYhEtYj4aUZ6WfWoGURpdV".to_string(),
+				best_number: 3,
+				common_number: 2,
+				downloaded_bytes: 1024,
+				download_speed: 512,
+			}],
+			gap_sync: None,
+		}
+	);
 }
 
 #[tokio::test]
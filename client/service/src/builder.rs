@@ -19,7 +19,7 @@
 use crate::{
 	build_network_future, build_system_rpc_future,
 	client::{Client, ClientConfig},
-	config::{Configuration, KeystoreConfig, PrometheusConfig},
+	config::{Configuration, KeystoreConfig, PrometheusConfig, QosTier},
 	error::Error,
 	metrics::MetricsService,
 	start_rpc_servers, BuildGenesisBlock, GenesisBlockBuilder, RpcHandlers, SpawnTaskHandle,
@@ -27,11 +27,11 @@ use crate::{
 };
 use futures::{channel::oneshot, future::ready, FutureExt, StreamExt};
 use jsonrpsee::RpcModule;
-use log::info;
+use log::{info, warn};
 use prometheus_endpoint::Registry;
 use sc_chain_spec::get_extension;
 use sc_client_api::{
-	execution_extensions::ExecutionExtensions, proof_provider::ProofProvider, BadBlocks,
+	execution_extensions::ExecutionExtensions, proof_provider::ProofProvider, AuxStore, BadBlocks,
 	BlockBackend, BlockchainEvents, ExecutorProvider, ForkBlocks, StorageProvider, UsageProvider,
 };
 use sc_client_db::{Backend, DatabaseSettings};
@@ -62,7 +62,16 @@ use sc_rpc::{
 	system::SystemApiServer,
 	DenyUnsafe, SubscriptionTaskExecutor,
 };
-use sc_rpc_spec_v2::{chain_head::ChainHeadApiServer, transaction::TransactionApiServer};
+use sc_rpc_spec_v2::{
+	archive::ArchiveApiServer,
+	chain_head::ChainHeadApiServer,
+	chain_spec::ChainSpecApiServer,
+	dev::DevApiServer,
+	events::SystemEventsApiServer,
+	runtime_api::RuntimeApiExplorerApiServer,
+	sudo::{SudoApiServer, SudoUnsafeApiServer},
+	transaction::{TransactionApiServer, TransactionBroadcastApiServer},
+};
 use sc_telemetry::{telemetry, ConnectionMessage, Telemetry, TelemetryHandle, SUBSTRATE_INFO};
 use sc_transaction_pool_api::{MaintainedTransactionPool, TransactionPool};
 use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedSender};
@@ -73,7 +82,10 @@ use sp_consensus::block_validation::{
 };
 use sp_core::traits::{CodeExecutor, SpawnNamed};
 use sp_keystore::KeystorePtr;
-use sp_runtime::traits::{Block as BlockT, BlockIdTo, NumberFor, Zero};
+use sp_runtime::{
+	traits::{Block as BlockT, BlockIdTo, NumberFor, Zero},
+	SaturatedConversion,
+};
 use std::{str::FromStr, sync::Arc, time::SystemTime};
 
 /// Full client type.
@@ -356,8 +368,15 @@ pub struct SpawnTasksParams<'a, TBl: BlockT, TCl, TExPool, TRpc, Backend> {
 	pub sync_service: Arc<SyncingService<TBl>>,
 	/// Telemetry instance for this node.
 	pub telemetry: Option<&'a mut Telemetry>,
+	/// Computes the fee information of the extrinsics watched with
+	/// `author_submitAndWatchExtrinsic`, if any.
+	pub extrinsic_fee_provider: Option<Arc<dyn sc_rpc::author::ExtrinsicFeeProvider<TBl>>>,
 }
 
+/// The maximum number of threads executing the `chainHead` runtime calls, shared by the RPC
+/// servers.
+const MAX_CHAIN_HEAD_OPERATION_THREADS: usize = 4;
+
 /// Spawn the tasks that are required to run a node.
 pub fn spawn_tasks<TBl, TBackend, TExPool, TRpc, TCl>(
 	params: SpawnTasksParams<TBl, TCl, TExPool, TRpc, TBackend>,
@@ -375,6 +394,7 @@ where
 		+ UsageProvider<TBl>
 		+ StorageProvider<TBl, TBackend>
 		+ CallApiAt<TBl>
+		+ AuxStore
 		+ Send
 		+ 'static,
 	<TCl as ProvideRuntimeApi<TBl>>::Api: sp_api::Metadata<TBl>
@@ -400,6 +420,7 @@ where
 		tx_handler_controller,
 		sync_service,
 		telemetry,
+		extrinsic_fee_provider,
 	} = params;
 
 	let chain_info = client.usage_info().chain;
@@ -442,6 +463,19 @@ where
 		),
 	);
 
+	if config.extrinsic_index {
+		spawn_handle.spawn(
+			"extrinsic-index",
+			None,
+			crate::extrinsic_index::build_extrinsic_index_future(
+				client.clone(),
+				config.blocks_pruning,
+			),
+		);
+	} else if let Err(e) = sc_client_api::extrinsic_index::disable(&*client) {
+		warn!("Failed to disable the extrinsic index: {}", e);
+	}
+
 	// Prometheus metrics.
 	let metrics_service =
 		if let Some(PrometheusConfig { port, registry }) = config.prometheus_config.clone() {
@@ -464,6 +498,7 @@ where
 		None,
 		metrics_service.run(
 			client.clone(),
+			backend.clone(),
 			transaction_pool.clone(),
 			network.clone(),
 			sync_service.clone(),
@@ -471,11 +506,27 @@ where
 	);
 
 	let rpc_id_provider = config.rpc_id_provider.take();
+	// The health and the metrics of the `chainHead` methods are shared by the RPC modules of
+	// all the servers.
+	let chain_head_health = sc_rpc_spec_v2::chain_head::ChainHeadHealth::new(
+		Default::default(),
+		config.prometheus_registry(),
+	);
+	let chain_head_metrics =
+		sc_rpc_spec_v2::chain_head::ChainHeadMetrics::new(config.prometheus_registry());
+	// The threads executing the `chainHead` runtime calls are shared as well.
+	let chain_head_operation_pool = Arc::new(sc_rpc_spec_v2::chain_head::OperationPool::new(
+		std::thread::available_parallelism()
+			.map_or(1, |n| n.get())
+			.min(MAX_CHAIN_HEAD_OPERATION_THREADS),
+		config.tokio_handle.clone(),
+	));
 
 	// jsonrpsee RPC
-	let gen_rpc_module = |deny_unsafe: DenyUnsafe| {
+	let gen_rpc_module = |deny_unsafe: DenyUnsafe, tier: Option<&QosTier>| {
 		gen_rpc_module(
 			deny_unsafe,
+			tier,
 			task_manager.spawn_handle(),
 			client.clone(),
 			transaction_pool.clone(),
@@ -483,12 +534,48 @@ where
 			system_rpc_tx.clone(),
 			&config,
 			backend.clone(),
+			&chain_head_health,
+			&chain_head_metrics,
+			&chain_head_operation_pool,
+			sync_service.clone(),
+			extrinsic_fee_provider.clone(),
 			&*rpc_builder,
 		)
 	};
 
-	let rpc = start_rpc_servers(&config, gen_rpc_module, rpc_id_provider)?;
-	let rpc_handlers = RpcHandlers(Arc::new(gen_rpc_module(sc_rpc::DenyUnsafe::No)?.into()));
+	// The health endpoints report the sync status of the network and the finality lag.
+	let health: Arc<dyn sc_rpc_server::HealthProvider> =
+		Arc::new(SystemRpcHealth { system_rpc_tx: system_rpc_tx.clone(), client: client.clone() });
+
+	let rpc = start_rpc_servers(&config, gen_rpc_module, rpc_id_provider, health.clone())?;
+	let rpc_handlers = RpcHandlers(Arc::new(gen_rpc_module(sc_rpc::DenyUnsafe::No, None)?.into()));
+
+	if let Some(addr) = config.grpc_addr {
+		// The listener is bound by the runtime of the node.
+		let _runtime = config.tokio_handle.enter();
+		let grpc_server = sc_rpc_grpc::start_server::<TBackend, _, _>(
+			addr,
+			config.grpc_max_connections,
+			client.clone(),
+			transaction_pool.clone(),
+		)
+		.map_err(Error::Application)?;
+		spawn_handle.spawn("grpc-server", Some("rpc"), grpc_server);
+	}
+
+	if let Some(addr) = config.health_addr {
+		let health_server =
+			sc_rpc_server::start_health_server(addr, health, config.health_max_finality_lag);
+		spawn_handle.spawn(
+			"health-endpoint",
+			Some("rpc"),
+			health_server.map(|result| {
+				if let Err(e) = result {
+					warn!("Health server failed: {}", e);
+				}
+			}),
+		);
+	}
 
 	// Spawn informant task
 	spawn_handle.spawn(
@@ -575,6 +662,7 @@ where
 
 fn gen_rpc_module<TBl, TBackend, TCl, TRpc, TExPool>(
 	deny_unsafe: DenyUnsafe,
+	tier: Option<&QosTier>,
 	spawn_handle: SpawnTaskHandle,
 	client: Arc<TCl>,
 	transaction_pool: Arc<TExPool>,
@@ -582,6 +670,11 @@ fn gen_rpc_module<TBl, TBackend, TCl, TRpc, TExPool>(
 	system_rpc_tx: TracingUnboundedSender<sc_rpc::system::Request<TBl>>,
 	config: &Configuration,
 	backend: Arc<TBackend>,
+	chain_head_health: &sc_rpc_spec_v2::chain_head::ChainHeadHealth,
+	chain_head_metrics: &sc_rpc_spec_v2::chain_head::ChainHeadMetrics,
+	chain_head_operation_pool: &Arc<sc_rpc_spec_v2::chain_head::OperationPool>,
+	sync_oracle: Arc<dyn sp_consensus::SyncOracle + Send + Sync>,
+	extrinsic_fee_provider: Option<Arc<dyn sc_rpc::author::ExtrinsicFeeProvider<TBl>>>,
 	rpc_builder: &(dyn Fn(DenyUnsafe, SubscriptionTaskExecutor) -> Result<RpcModule<TRpc>, Error>),
 ) -> Result<RpcModule<()>, Error>
 where
@@ -595,6 +688,7 @@ where
 		+ ProofProvider<TBl>
 		+ StorageProvider<TBl, TBackend>
 		+ BlockBackend<TBl>
+		+ AuxStore
 		+ Send
 		+ Sync
 		+ 'static,
@@ -617,38 +711,153 @@ where
 
 	let (chain, state, child_state) = {
 		let chain = sc_rpc::chain::new_full(client.clone(), task_executor.clone()).into_rpc();
-		let (state, child_state) =
-			sc_rpc::state::new_full(client.clone(), task_executor.clone(), deny_unsafe);
+		let (state, child_state) = sc_rpc::state::new_full_with_call_timeout(
+			client.clone(),
+			task_executor.clone(),
+			deny_unsafe,
+			config.rpc_call_timeout,
+		);
 		let state = state.into_rpc();
 		let child_state = child_state.into_rpc();
 
 		(chain, state, child_state)
 	};
 
+	// Defaults to sensible limits for the `ChainHead`.
+	let mut chain_head_config = sc_rpc_spec_v2::chain_head::ChainHeadConfig {
+		subscription_max_pinned_blocks: config.rpc_max_pinned_blocks_per_subscription,
+		max_follow_subscriptions: config.rpc_max_follow_subscriptions,
+		operation_cache_ttl: config.rpc_operation_cache_ttl,
+		operation_coalescing: config.rpc_operation_coalescing,
+		operation_max_body_chunk_size: config.rpc_operation_max_body_chunk_size,
+		operation_storage_timeout: config.rpc_operation_storage_timeout,
+		operation_call_timeout: config.rpc_operation_call_timeout,
+		operation_body_timeout: config.rpc_operation_body_timeout,
+		subscription_pin_eviction: config.rpc_pin_eviction.clone(),
+		operation_audit_sink: config.rpc_audit_sink.clone(),
+		operation_audit_tenant: tier.map(|tier| tier.name.clone()),
+		health: chain_head_health.clone(),
+		metrics: chain_head_metrics.clone(),
+		operation_pool: Some(chain_head_operation_pool.clone()),
+		sync_oracle: Some(sync_oracle),
+		..Default::default()
+	};
+	// The bandwidth quota of the tier, per minute.
+	let bandwidth_quota = tier.and_then(|tier| tier.limits.max_bytes_per_minute).map(|max_bytes| {
+		sc_rpc_spec_v2::chain_head::BandwidthQuota {
+			max_bytes,
+			period: std::time::Duration::from_secs(60),
+		}
+	});
+	// The limits of the quality of service tier take precedence.
+	if let Some(limits) = tier.map(|tier| &tier.limits) {
+		if let Some(max_pinned_blocks) = limits.max_pinned_blocks {
+			chain_head_config.global_max_pinned_blocks = max_pinned_blocks;
+			// The limited follow subscriptions together cannot exceed the budget of the tier.
+			let per_subscription = chain_head_config
+				.max_follow_subscriptions
+				.map_or(max_pinned_blocks, |max| (max_pinned_blocks / max.max(1)).max(1));
+			chain_head_config.subscription_max_pinned_blocks =
+				chain_head_config.subscription_max_pinned_blocks.min(per_subscription);
+		}
+		if let Some(max_ongoing_operations) = limits.max_ongoing_operations {
+			chain_head_config.subscription_max_ongoing_operations = max_ongoing_operations;
+		}
+	}
+	chain_head_config.subscription_bandwidth_quota = bandwidth_quota;
+
+	let chain_head_v2 = sc_rpc_spec_v2::chain_head::ChainHead::new(
+		client.clone(),
+		backend.clone(),
+		task_executor.clone(),
+		client.info().genesis_hash,
+		chain_head_config,
+	);
+
+	// The transaction events report the blocks pinned by the `chainHead_follow` subscriptions.
 	let transaction_v2 = sc_rpc_spec_v2::transaction::Transaction::new(
 		client.clone(),
 		transaction_pool.clone(),
 		task_executor.clone(),
 	)
+	.with_follow_pins(chain_head_v2.follow_subscription_pins())
 	.into_rpc();
 
-	let chain_head_v2 = sc_rpc_spec_v2::chain_head::ChainHead::new(
+	let transaction_broadcast_v2 = sc_rpc_spec_v2::transaction::TransactionBroadcast::new(
 		client.clone(),
-		backend.clone(),
+		transaction_pool.clone(),
 		task_executor.clone(),
-		client.info().genesis_hash,
-		// Defaults to sensible limits for the `ChainHead`.
-		sc_rpc_spec_v2::chain_head::ChainHeadConfig::default(),
 	)
+	.with_max_broadcasts(config.rpc_max_broadcasts)
 	.into_rpc();
 
+	// The dev methods are unsafe and exposed only if unsafe methods are allowed.
+	let dev_v2 = deny_unsafe.check_if_safe().is_ok().then(|| {
+		sc_rpc_spec_v2::dev::Dev::new(
+			client.clone(),
+			backend.clone(),
+			chain_head_v2.follow_subscription_pins(),
+		)
+		.into_rpc()
+	});
+
+	let chain_head_v2 = chain_head_v2.into_rpc();
+
+	// The archive methods query any block of the history, provided only by the archive nodes.
+	let is_archive_node = config.state_pruning.as_ref().map_or(false, |mode| mode.is_archive()) &&
+		config.blocks_pruning.is_archive();
+	let archive_v2 = is_archive_node.then(|| {
+		sc_rpc_spec_v2::archive::Archive::new(
+			client.clone(),
+			backend.clone(),
+			client.info().genesis_hash,
+			sc_rpc_spec_v2::archive::ArchiveConfig { bandwidth_quota, ..Default::default() },
+		)
+		.into_rpc()
+	});
+
+	let chain_spec_v2 = sc_rpc_spec_v2::chain_spec::ChainSpec::new(
+		config.chain_spec.name().into(),
+		client.info().genesis_hash,
+		config.chain_spec.properties(),
+	);
+	// Exporting the chain spec is unsafe.
+	let chain_spec_v2 = if deny_unsafe.check_if_safe().is_ok() {
+		chain_spec_v2.with_export(config.chain_spec.cloned_box())
+	} else {
+		chain_spec_v2
+	}
+	.into_rpc();
+
+	let runtime_api_v2 =
+		sc_rpc_spec_v2::runtime_api::RuntimeApiExplorer::new(client.clone()).into_rpc();
+
+	let events_v2 =
+		sc_rpc_spec_v2::events::SystemEvents::new(client.clone(), task_executor.clone()).into_rpc();
+
+	let sudo_v2 =
+		sc_rpc_spec_v2::sudo::Sudo::new(&config.impl_name, &config.impl_version).into_rpc();
+	// The node-operator methods are unsafe and exposed only if unsafe methods are allowed.
+	let sudo_unsafe_v2 = deny_unsafe.check_if_safe().is_ok().then(|| {
+		sc_rpc_spec_v2::sudo::SudoUnsafe::new(
+			client.clone(),
+			keystore.clone(),
+			Arc::new(SystemRpcSudoNetwork(system_rpc_tx.clone())),
+		)
+		.into_rpc()
+	});
+
 	let author = sc_rpc::author::Author::new(
 		client.clone(),
 		transaction_pool,
 		keystore,
 		deny_unsafe,
 		task_executor.clone(),
-	)
+	);
+	let author = match extrinsic_fee_provider {
+		Some(fee_provider) => author.with_fee_provider(fee_provider),
+		None => author,
+	}
 	.into_rpc();
 
 	let system = sc_rpc::system::System::new(system_info, system_rpc_tx, deny_unsafe).into_rpc();
@@ -661,7 +870,23 @@ where
 
 	// Part of the RPC v2 spec.
 	rpc_api.merge(transaction_v2).map_err(|e| Error::Application(e.into()))?;
+	rpc_api
+		.merge(transaction_broadcast_v2)
+		.map_err(|e| Error::Application(e.into()))?;
 	rpc_api.merge(chain_head_v2).map_err(|e| Error::Application(e.into()))?;
+	if let Some(archive_v2) = archive_v2 {
+		rpc_api.merge(archive_v2).map_err(|e| Error::Application(e.into()))?;
+	}
+	rpc_api.merge(chain_spec_v2).map_err(|e| Error::Application(e.into()))?;
+	if let Some(dev_v2) = dev_v2 {
+		rpc_api.merge(dev_v2).map_err(|e| Error::Application(e.into()))?;
+	}
+	rpc_api.merge(runtime_api_v2).map_err(|e| Error::Application(e.into()))?;
+	rpc_api.merge(events_v2).map_err(|e| Error::Application(e.into()))?;
+	rpc_api.merge(sudo_v2).map_err(|e| Error::Application(e.into()))?;
+	if let Some(sudo_unsafe_v2) = sudo_unsafe_v2 {
+		rpc_api.merge(sudo_unsafe_v2).map_err(|e| Error::Application(e.into()))?;
+	}
 
 	// Part of the old RPC spec.
 	rpc_api.merge(chain).map_err(|e| Error::Application(e.into()))?;
@@ -676,6 +901,57 @@ where
 	Ok(rpc_api)
 }
 
+/// Forwards the network requests of the `sudo` RPC methods to the system RPC future.
+struct SystemRpcSudoNetwork<Block: BlockT>(TracingUnboundedSender<sc_rpc::system::Request<Block>>);
+
+#[async_trait::async_trait]
+impl<Block: BlockT> sc_rpc_spec_v2::sudo::SudoNetwork for SystemRpcSudoNetwork<Block> {
+	async fn add_known_address(&self, multiaddr: String) -> Result<(), String> {
+		let (tx, rx) = oneshot::channel();
+		let _ = self
+			.0
+			.unbounded_send(sc_rpc::system::Request::NetworkAddKnownAddress(multiaddr, tx));
+		match rx.await {
+			Ok(result) => result.map_err(|err| err.to_string()),
+			Err(err) => Err(err.to_string()),
+		}
+	}
+}
+
+/// Provides the health of the node to the health endpoints of the RPC servers.
+struct SystemRpcHealth<Block: BlockT, Client> {
+	system_rpc_tx: TracingUnboundedSender<sc_rpc::system::Request<Block>>,
+	client: Arc<Client>,
+}
+
+impl<Block: BlockT, Client> std::fmt::Debug for SystemRpcHealth<Block, Client> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("SystemRpcHealth").finish()
+	}
+}
+
+#[async_trait::async_trait]
+impl<Block, Client> sc_rpc_server::HealthProvider for SystemRpcHealth<Block, Client>
+where
+	Block: BlockT,
+	Client: HeaderBackend<Block> + Send + Sync,
+{
+	async fn health(&self) -> Result<sc_rpc_server::NodeHealth, String> {
+		let (tx, rx) = oneshot::channel();
+		let _ = self.system_rpc_tx.unbounded_send(sc_rpc::system::Request::Health(tx));
+		let health = rx.await.map_err(|err| err.to_string())?;
+
+		let info = self.client.info();
+		Ok(sc_rpc_server::NodeHealth {
+			is_syncing: health.is_syncing,
+			peers: health.peers,
+			should_have_peers: health.should_have_peers,
+			best_number: info.best_number.saturated_into(),
+			finalized_number: info.finalized_number.saturated_into(),
+		})
+	}
+}
+
 /// Parameters to pass into `build_network`.
 pub struct BuildNetworkParams<'a, TBl: BlockT, TExPool, TImpQu, TCl> {
 	/// The service configuration.
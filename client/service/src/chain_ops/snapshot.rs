@@ -0,0 +1,345 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::error::Error;
+use codec::{Decode, Encode, IoReader};
+use futures::future;
+use log::info;
+use sc_client_api::{backend::Backend, BlockBackend, HeaderBackend};
+use sc_consensus::{
+	import_queue::{BlockImportError, BlockImportStatus, ImportQueue, IncomingBlock, Link},
+	ImportedState,
+};
+use sp_consensus::BlockOrigin;
+use sp_core::{
+	hashing::blake2_256,
+	storage::{well_known_keys, ChildInfo},
+};
+use sp_runtime::{
+	traits::{Block as BlockT, Header as HeaderT, NumberFor},
+	Justifications,
+};
+use sp_state_machine::{Backend as _, IterArgs, KeyValueStates, KeyValueStorageLevel};
+use std::{
+	io::{Read, Write},
+	sync::Arc,
+	task::Poll,
+};
+
+/// The bytes starting a state snapshot.
+const SNAPSHOT_MAGIC: [u8; 4] = *b"snap";
+
+/// The version of the state snapshot format.
+const SNAPSHOT_VERSION: u32 = 2;
+
+/// A record of a state snapshot.
+///
+/// A snapshot starts with the magic bytes, the version, the header and the justifications of
+/// the block, followed by the records of the state of the block.
+#[derive(Debug, PartialEq, Encode, Decode)]
+enum SnapshotRecord {
+	/// A key value pair of the top trie.
+	Top(Vec<u8>, Vec<u8>),
+	/// The unprefixed storage key and the root of a default child trie, whose key value pairs
+	/// are the following `Child` records.
+	ChildTrie(Vec<u8>, Vec<u8>),
+	/// A key value pair of the last child trie.
+	Child(Vec<u8>, Vec<u8>),
+	/// The end of the snapshot, with the number and the checksum of the preceding records.
+	End(u64, [u8; 32]),
+}
+
+/// The checksum of the records of a snapshot, chaining the hashes of the encoded records.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct Checksum([u8; 32]);
+
+impl Checksum {
+	/// Add the record to the checksum.
+	fn update(&mut self, record: &SnapshotRecord) {
+		record.using_encoded(|record| self.0 = blake2_256(&[&self.0[..], record].concat()));
+	}
+}
+
+/// The state snapshot of a block.
+struct Snapshot<B: BlockT> {
+	/// The header of the block.
+	header: B::Header,
+	/// The justifications of the block.
+	justifications: Option<Justifications>,
+	/// The state of the block.
+	state: KeyValueStates,
+	/// The number of records of the snapshot.
+	records: u64,
+}
+
+/// Export the state at the given block into a snapshot, along with the header and the
+/// justifications of the block.
+///
+/// Returns the number of written records. The export fails if the state of the block cannot
+/// be read entirely, ie if it was pruned meanwhile.
+pub fn export_snapshot<B, BA, C>(
+	client: Arc<C>,
+	backend: Arc<BA>,
+	hash: B::Hash,
+	mut output: impl Write,
+) -> Result<u64, Error>
+where
+	C: HeaderBackend<B> + BlockBackend<B>,
+	B: BlockT,
+	BA: Backend<B>,
+{
+	let header = client
+		.header(hash)?
+		.ok_or_else(|| Error::Other(format!("Unknown block {:?}", hash)))?;
+	let justifications = client.justifications(hash)?;
+	info!("Exporting the state of block #{} ({:?})", header.number(), hash);
+	output.write_all(&(SNAPSHOT_MAGIC, SNAPSHOT_VERSION, &header, &justifications).encode())?;
+
+	let state = backend.state_at(hash)?;
+	let state_error = |e| Error::Other(format!("Error reading the state of {:?}: {}", hash, e));
+
+	let mut records = 0u64;
+	let mut checksum = Checksum::default();
+	let mut write = |record: SnapshotRecord| -> Result<(), Error> {
+		checksum.update(&record);
+		output.write_all(&record.encode())?;
+		records += 1;
+		if records % 100_000 == 0 {
+			info!("Exported {} records", records);
+		}
+		Ok(())
+	};
+	for pair in state.pairs(IterArgs::default()).map_err(state_error)? {
+		let (key, value) = pair.map_err(state_error)?;
+		if let Some(storage_key) =
+			key.strip_prefix(well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX)
+		{
+			// The child tries follow their roots, which are recalculated on import.
+			write(SnapshotRecord::ChildTrie(storage_key.to_vec(), value))?;
+			let mut args = IterArgs::default();
+			args.child_info = Some(ChildInfo::new_default(storage_key));
+			for child_pair in state.pairs(args).map_err(state_error)? {
+				let (child_key, child_value) = child_pair.map_err(state_error)?;
+				write(SnapshotRecord::Child(child_key, child_value))?;
+			}
+		} else {
+			write(SnapshotRecord::Top(key, value))?;
+		}
+	}
+
+	output.write_all(&SnapshotRecord::End(records, checksum.0).encode())?;
+	output.flush()?;
+	info!("🎉 Exported {} records", records);
+
+	Ok(records)
+}
+
+/// Import a state snapshot written by [`export_snapshot`].
+///
+/// The block of the snapshot is imported with its state and its justifications, without
+/// its parents. The snapshot is rejected if its checksum doesn't match its records, and the
+/// state is rejected if its root doesn't match the header of the block.
+///
+/// The backend commits the state of a block in a single transaction, as for the state sync,
+/// such that the whole state is held in memory until the block is imported.
+pub async fn import_snapshot<B, IQ, C>(
+	client: Arc<C>,
+	mut import_queue: IQ,
+	input: impl Read,
+) -> Result<(), Error>
+where
+	C: HeaderBackend<B>,
+	B: BlockT,
+	IQ: ImportQueue<B>,
+{
+	let Snapshot { header, justifications, state, records } = read_snapshot::<B>(input)?;
+	let hash = header.hash();
+
+	import_queue.service_ref().import_blocks(
+		BlockOrigin::File,
+		vec![IncomingBlock::<B> {
+			hash,
+			header: Some(header),
+			body: None,
+			indexed_body: None,
+			justifications,
+			origin: None,
+			allow_missing_state: true,
+			skip_execution: true,
+			import_existing: true,
+			state: Some(ImportedState { block: hash, state }),
+		}],
+	);
+
+	let mut link = WaitLink::default();
+	future::poll_fn(|cx| {
+		import_queue.poll_actions(cx, &mut link);
+		match link.result.take() {
+			Some(result) => Poll::Ready(result),
+			None => Poll::Pending,
+		}
+	})
+	.await?;
+
+	info!(
+		"🎉 Imported {} records. Best: #{}, finalized: #{}",
+		records,
+		client.info().best_number,
+		client.info().finalized_number,
+	);
+
+	Ok(())
+}
+
+/// Read a state snapshot written by [`export_snapshot`], checking its checksum.
+fn read_snapshot<B: BlockT>(input: impl Read) -> Result<Snapshot<B>, Error> {
+	let mut input = IoReader(input);
+	let (magic, version, header, justifications) =
+		<([u8; 4], u32, B::Header, Option<Justifications>)>::decode(&mut input)
+			.map_err(|e| Error::Other(format!("Error reading the snapshot: {}", e)))?;
+	if magic != SNAPSHOT_MAGIC || version != SNAPSHOT_VERSION {
+		return Err(Error::Other("Unsupported snapshot format".into()))
+	}
+
+	let hash = header.hash();
+	info!("Importing the state of block #{} ({:?})", header.number(), hash);
+
+	let top = KeyValueStorageLevel {
+		state_root: Vec::new(),
+		parent_storage_keys: Vec::new(),
+		key_values: Vec::new(),
+	};
+	let mut levels = vec![top];
+	let mut records = 0u64;
+	let mut checksum = Checksum::default();
+	loop {
+		let record = SnapshotRecord::decode(&mut input).map_err(|e| {
+			Error::Other(format!("Error reading the snapshot record #{}: {}", records, e))
+		})?;
+		if !matches!(record, SnapshotRecord::End(..)) {
+			checksum.update(&record);
+		}
+		match record {
+			SnapshotRecord::Top(key, value) => levels[0].key_values.push((key, value)),
+			SnapshotRecord::ChildTrie(storage_key, root) => levels.push(KeyValueStorageLevel {
+				state_root: root,
+				parent_storage_keys: vec![ChildInfo::new_default(&storage_key)
+					.prefixed_storage_key()
+					.into_inner()],
+				key_values: Vec::new(),
+			}),
+			SnapshotRecord::Child(key, value) => match levels[1..].last_mut() {
+				Some(child) => child.key_values.push((key, value)),
+				None => return Err(Error::Other("Child record without a child trie".into())),
+			},
+			SnapshotRecord::End(count, _) if count != records =>
+				return Err(Error::Other(format!(
+					"Corrupted snapshot: expected {} records, read {}",
+					count, records
+				))),
+			SnapshotRecord::End(_, expected) if Checksum(expected) != checksum =>
+				return Err(Error::Other("Corrupted snapshot: checksum mismatch".into())),
+			SnapshotRecord::End(..) => break,
+		}
+		records += 1;
+		if records % 100_000 == 0 {
+			info!("Read {} records", records);
+		}
+	}
+
+	Ok(Snapshot { header, justifications, state: KeyValueStates(levels), records })
+}
+
+/// A link waiting for the import of the snapshot block.
+#[derive(Default)]
+struct WaitLink {
+	result: Option<Result<(), Error>>,
+}
+
+impl<B: BlockT> Link<B> for WaitLink {
+	fn blocks_processed(
+		&mut self,
+		_imported: usize,
+		_count: usize,
+		results: Vec<(Result<BlockImportStatus<NumberFor<B>>, BlockImportError>, B::Hash)>,
+	) {
+		if let Some((result, hash)) = results.into_iter().next() {
+			self.result = Some(result.map(|_| ()).map_err(|e| {
+				Error::Other(format!("Error importing the snapshot block {:?}: {}", hash, e))
+			}));
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_state_machine::Backend as _;
+	use substrate_test_runtime_client::{runtime::Block, TestClientBuilder, TestClientBuilderExt};
+
+	#[test]
+	fn snapshot_round_trip() {
+		let child_info = ChildInfo::new_default(b"child");
+		let (client, backend) = TestClientBuilder::new()
+			.add_extra_child_storage(&child_info, b"key".to_vec(), b"value".to_vec())
+			.build_with_backend();
+		let client = Arc::new(client);
+		let hash = client.info().genesis_hash;
+
+		let mut output = Vec::new();
+		let records = export_snapshot(client.clone(), backend.clone(), hash, &mut output).unwrap();
+		let snapshot = read_snapshot::<Block>(&output[..]).unwrap();
+		assert_eq!(snapshot.records, records);
+		assert_eq!(snapshot.header, client.header(hash).unwrap().unwrap());
+		assert!(snapshot.justifications.is_none());
+
+		let state = backend.state_at(hash).unwrap();
+		let top = state
+			.pairs(IterArgs::default())
+			.unwrap()
+			.map(Result::unwrap)
+			.filter(|(key, _)| !well_known_keys::is_child_storage_key(key))
+			.collect::<Vec<_>>();
+		let [top_level, child_level] = &snapshot.state.0[..] else {
+			panic!("Expected the top trie and one child trie")
+		};
+		assert_eq!(top_level.key_values, top);
+		assert_eq!(
+			child_level.parent_storage_keys,
+			vec![child_info.prefixed_storage_key().into_inner()]
+		);
+		assert_eq!(child_level.key_values, vec![(b"key".to_vec(), b"value".to_vec())]);
+	}
+
+	#[test]
+	fn corrupted_snapshot_is_rejected() {
+		let (client, backend) = TestClientBuilder::new().build_with_backend();
+		let hash = client.info().genesis_hash;
+
+		let mut output = Vec::new();
+		export_snapshot(Arc::new(client), backend, hash, &mut output).unwrap();
+
+		// Truncated snapshot.
+		assert!(read_snapshot::<Block>(&output[..output.len() - 1]).is_err());
+
+		// Altered checksum.
+		let last = output.len() - 1;
+		output[last] ^= 1;
+		assert!(read_snapshot::<Block>(&output[..]).is_err());
+	}
+}
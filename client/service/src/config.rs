@@ -30,16 +30,25 @@ pub use sc_network::{
 	},
 	Multiaddr,
 };
+pub use sc_rpc_server::{
+	BatchLimits, BearerTokenAuthenticator, CorsPolicy, CorsRule, MethodAcl, QosConfig, QosTier,
+	ResponseLimits, RpcAuthenticator, RpcRequestHook, TierLimits,
+};
+pub use sc_rpc_spec_v2::chain_head::{
+	AuditSink, FileAuditSink, LruEviction, MemoryWeightedEviction, PinEvictionPolicy,
+};
 
 use prometheus_endpoint::Registry;
 use sc_chain_spec::ChainSpec;
 pub use sc_telemetry::TelemetryEndpoints;
-pub use sc_transaction_pool::Options as TransactionPoolOptions;
+pub use sc_transaction_pool::{Options as TransactionPoolOptions, SelectiveRevalidation};
 use sp_core::crypto::SecretString;
 use std::{
 	io, iter,
 	net::SocketAddr,
 	path::{Path, PathBuf},
+	sync::Arc,
+	time::Duration,
 };
 use tempfile::TempDir;
 
@@ -82,22 +91,92 @@ pub struct Configuration {
 	pub wasm_runtime_overrides: Option<PathBuf>,
 	/// JSON-RPC server binding address.
 	pub rpc_addr: Option<SocketAddr>,
+	/// gRPC server binding address, the gRPC server is not started if `None`.
+	pub grpc_addr: Option<SocketAddr>,
+	/// Maximum number of connections of the gRPC server.
+	pub grpc_max_connections: u32,
+	/// Binding address of a dedicated server of the health endpoints, which are otherwise
+	/// served by the JSON-RPC servers only.
+	pub health_addr: Option<SocketAddr>,
+	/// Maximum finality lag of a ready node, in blocks, not checked if `None`.
+	pub health_max_finality_lag: Option<u32>,
 	/// Maximum number of connections for JSON-RPC server.
 	pub rpc_max_connections: u32,
 	/// CORS settings for HTTP & WS servers. `None` if all origins are allowed.
 	pub rpc_cors: Option<Vec<String>>,
+	/// Per-origin CORS policy of the RPC servers, which supersedes `rpc_cors` if provided.
+	pub rpc_cors_policy: Option<CorsPolicy>,
 	/// RPC methods to expose (by default only a safe subset or all of them).
 	pub rpc_methods: RpcMethods,
 	/// Maximum payload of a rpc request
 	pub rpc_max_request_size: u32,
 	/// Maximum payload of a rpc response.
 	pub rpc_max_response_size: u32,
+	/// Maximum payload of the rpc responses of the subscriptions, the state queries and the
+	/// blocks, `rpc_max_response_size` for the classes without a limit.
+	pub rpc_response_limits: ResponseLimits,
+	/// Maximum number of calls and maximum cumulative cost of the rpc batch requests.
+	pub rpc_batch_limits: BatchLimits,
 	/// Custom JSON-RPC subscription ID provider.
 	///
 	/// Default: [`crate::RandomStringSubscriptionId`].
 	pub rpc_id_provider: Option<Box<dyn crate::RpcSubscriptionIdProvider>>,
 	/// Maximum allowed subscriptions per rpc connection
 	pub rpc_max_subs_per_conn: u32,
+	/// Interval of the pings sent to the WebSocket connections of the RPC servers.
+	///
+	/// The connections of the dead clients are detected once a ping fails, which releases
+	/// their subscriptions and the blocks pinned by their `chainHead` subscriptions. The
+	/// default interval of the RPC servers is used if `None`.
+	pub rpc_ping_interval: Option<Duration>,
+	/// Whether the HTTP responses of the RPC servers are compressed with gzip or brotli, as
+	/// accepted by the clients.
+	pub rpc_http_compression: bool,
+	/// Maximum time allowed for the `state` and `childstate` calls iterating the storage.
+	///
+	/// The calls running for longer are cancelled with an error, such that the stuck reads of
+	/// the backend don't hold the threads of the RPC servers. No timeout if `None`.
+	pub rpc_call_timeout: Option<Duration>,
+	/// Maximum number of active `transaction_unstable_broadcast` broadcasts.
+	pub rpc_max_broadcasts: usize,
+	/// Duration for which the results of identical `chainHead` operations are cached. The
+	/// operation cache is disabled if `None`.
+	pub rpc_operation_cache_ttl: Option<Duration>,
+	/// Whether the identical `chainHead` operations started while one is in progress share its
+	/// result, instead of executing the work twice.
+	pub rpc_operation_coalescing: bool,
+	/// Maximum number of bytes of the extrinsics reported by a single `chainHead_body` event.
+	/// The body is reported by a single event if `None`.
+	pub rpc_operation_max_body_chunk_size: Option<usize>,
+	/// Maximum duration of a `chainHead_storage` operation, including the time spent waiting for
+	/// `chainHead_continue`. No timeout if `None`.
+	pub rpc_operation_storage_timeout: Option<Duration>,
+	/// Maximum duration of a `chainHead_call` operation. The runtime call is not interrupted,
+	/// its result is discarded once the timeout is reported. No timeout if `None`.
+	pub rpc_operation_call_timeout: Option<Duration>,
+	/// Maximum duration of a `chainHead_body` operation. The read of the body is not interrupted,
+	/// a late body is reported by the `operationTimeout` event instead. No timeout if `None`.
+	pub rpc_operation_body_timeout: Option<Duration>,
+	/// Select the `chainHead_follow` subscriptions stopped when the global limit of pinned blocks
+	/// is reached. The subscriptions pinning blocks for too long are stopped if `None`.
+	pub rpc_pin_eviction: Option<Arc<dyn PinEvictionPolicy>>,
+	/// Maximum number of blocks pinned by a `chainHead_follow` subscription.
+	pub rpc_max_pinned_blocks_per_subscription: usize,
+	/// Maximum number of running `chainHead_follow` subscriptions per RPC server. Not limited if
+	/// `None`.
+	pub rpc_max_follow_subscriptions: Option<usize>,
+	/// Quality of service tiers of the RPC servers. `None` if the servers are not limited.
+	pub rpc_qos: Option<QosConfig>,
+	/// Sink of the audit records of the completed `chainHead` operations. `None` if disabled.
+	pub rpc_audit_sink: Option<Arc<dyn AuditSink>>,
+	/// Authenticator of the calls to the unsafe RPC methods of the exposed addresses. `None` if
+	/// the unsafe methods are not restricted to the authenticated calls.
+	pub rpc_authenticator: Option<Arc<dyn RpcAuthenticator>>,
+	/// Custom middleware of the RPC servers, called in order for every HTTP request and
+	/// WebSocket handshake.
+	///
+	/// The node builders register their hooks before the RPC servers are started.
+	pub rpc_request_hooks: Vec<Arc<dyn RpcRequestHook>>,
 	/// JSON-RPC server default port.
 	pub rpc_port: u16,
 	/// Prometheus endpoint configuration. `None` if disabled.
@@ -108,6 +187,9 @@ pub struct Configuration {
 	pub default_heap_pages: Option<u64>,
 	/// Should offchain workers be executed.
 	pub offchain_worker: OffchainWorkerConfig,
+	/// Should the extrinsics of the imported blocks be indexed by hash, for
+	/// `chain_getExtrinsicByHash`.
+	pub extrinsic_index: bool,
 	/// Enable authoring even when offline.
 	pub force_authoring: bool,
 	/// Disable GRANDPA when running in validator mode
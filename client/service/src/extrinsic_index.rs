@@ -0,0 +1,113 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Maintenance of the index of the extrinsics by hash, see [`sc_client_api::extrinsic_index`].
+
+use crate::config::BlocksPruning;
+use futures::{future::Either, stream, StreamExt};
+use log::warn;
+use sc_client_api::{
+	extrinsic_index, AuxStore, BlockBackend, BlockchainEvents, FinalityNotification, HeaderBackend,
+};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor, One, Saturating};
+use std::sync::Arc;
+
+/// Builds a future indexing the extrinsics of the imported blocks.
+///
+/// The blocks of the stale forks are removed from the index on finality, as well as the
+/// finalized blocks whose bodies are pruned.
+pub(crate) async fn build_extrinsic_index_future<Block, Client>(
+	client: Arc<Client>,
+	blocks_pruning: BlocksPruning,
+) where
+	Block: BlockT,
+	Client: BlockchainEvents<Block> + BlockBackend<Block> + HeaderBackend<Block> + AuxStore,
+{
+	if let Err(e) = extrinsic_index::enable(&*client) {
+		warn!("Failed to enable the extrinsic index: {}", e);
+		return
+	}
+
+	// Every imported block is indexed, including the blocks imported during the major syncs.
+	let imports = client.every_import_notification_stream().map(Either::Left);
+	let finality = client.finality_notification_stream().map(Either::Right);
+	let mut events = stream::select(imports, finality);
+
+	while let Some(event) = events.next().await {
+		let result = match event {
+			Either::Left(notification) => index_block(&*client, notification.hash),
+			Either::Right(notification) => prune(&*client, &notification, blocks_pruning),
+		};
+
+		if let Err(e) = result {
+			warn!("Failed to update the extrinsic index: {}", e);
+		}
+	}
+}
+
+fn index_block<Block, Client>(client: &Client, hash: Block::Hash) -> sp_blockchain::Result<()>
+where
+	Block: BlockT,
+	Client: BlockBackend<Block> + AuxStore,
+{
+	match client.block_body(hash)? {
+		Some(extrinsics) => extrinsic_index::index_block::<Block>(client, hash, &extrinsics),
+		None => Ok(()),
+	}
+}
+
+fn prune<Block, Client>(
+	client: &Client,
+	notification: &FinalityNotification<Block>,
+	blocks_pruning: BlocksPruning,
+) -> sp_blockchain::Result<()>
+where
+	Block: BlockT,
+	Client: HeaderBackend<Block> + AuxStore,
+{
+	// The stale forks, down to their common ancestors with the finalized chain.
+	for head in notification.stale_heads.iter() {
+		let mut hash = *head;
+		while let Some(header) = client.header(hash)? {
+			if client.hash(*header.number())? == Some(hash) {
+				break
+			}
+			extrinsic_index::remove_block::<Block>(client, hash)?;
+			hash = *header.parent_hash();
+		}
+	}
+
+	// The finalized blocks whose bodies are pruned, `keep` blocks behind the newly finalized
+	// blocks.
+	if let BlocksPruning::Some(keep) = blocks_pruning {
+		let keep = NumberFor::<Block>::from(keep);
+		let finalized = *notification.header.number();
+		let newly_finalized = NumberFor::<Block>::from(notification.tree_route.len() as u32);
+		let mut number = finalized.saturating_sub(newly_finalized);
+		while number <= finalized {
+			if number >= keep {
+				if let Some(hash) = client.hash(number - keep)? {
+					extrinsic_index::remove_block::<Block>(client, hash)?;
+				}
+			}
+			number += One::one();
+		}
+	}
+
+	Ok(())
+}
@@ -31,6 +31,7 @@ mod builder;
 pub mod client;
 #[cfg(not(feature = "test-helpers"))]
 mod client;
+mod extrinsic_index;
 mod metrics;
 mod task_manager;
 
@@ -38,7 +39,7 @@ use std::{collections::HashMap, net::SocketAddr};
 
 use codec::{Decode, Encode};
 use futures::{channel::mpsc, pin_mut, FutureExt, StreamExt};
-use jsonrpsee::{core::Error as JsonRpseeError, RpcModule};
+use jsonrpsee::{core::Error as JsonRpseeError, types::SubscriptionId, RpcModule};
 use log::{debug, error, warn};
 use sc_client_api::{blockchain::HeaderBackend, BlockBackend, BlockchainEvents, ProofProvider};
 use sc_network::{
@@ -308,6 +309,17 @@ pub async fn build_system_rpc_future<
 					))),
 				};
 			},
+			sc_rpc::system::Request::NetworkAddKnownAddress(peer_addr, sender) => {
+				let result = match MultiaddrWithPeerId::try_from(peer_addr) {
+					Ok(peer) => {
+						network_service.add_known_address(peer.peer_id, peer.multiaddr);
+						Ok(())
+					},
+					Err(err) => Err(err.to_string()),
+				};
+				let x = result.map_err(sc_rpc::system::error::Error::MalformattedPeerArg);
+				let _ = sender.send(x);
+			},
 			sc_rpc::system::Request::NetworkReservedPeers(sender) => {
 				let reserved_peers = network_service.reserved_peers().await;
 				if let Ok(reserved_peers) = reserved_peers {
@@ -329,19 +341,36 @@ pub async fn build_system_rpc_future<
 				let _ = sender.send(vec![node_role]);
 			},
 			sc_rpc::system::Request::SyncState(sender) => {
-				use sc_rpc::system::SyncState;
-
-				match sync_service.best_seen_block().await {
-					Ok(best_seen_block) => {
-						let best_number = client.info().best_number;
-						let _ = sender.send(SyncState {
-							starting_block,
-							current_block: best_number,
-							highest_block: best_seen_block.unwrap_or(best_number),
-						});
-					},
-					Err(_) => log::error!("`SyncingEngine` shut down"),
-				}
+				use sc_rpc::system::{GapSyncState, PeerSyncState, SyncState};
+
+				let (status, peers) =
+					match (sync_service.status().await, sync_service.sync_peers_info().await) {
+						(Ok(status), Ok(peers)) => (status, peers),
+						_ => {
+							log::error!("`SyncingEngine` shut down");
+							continue
+						},
+					};
+				let best_number = client.info().best_number;
+				let _ = sender.send(SyncState {
+					starting_block,
+					current_block: best_number,
+					highest_block: status.best_seen_block.unwrap_or(best_number),
+					peers: peers
+						.into_iter()
+						.map(|(peer_id, p)| PeerSyncState {
+							peer_id: peer_id.to_base58(),
+							best_number: p.best_number,
+							common_number: p.common_number,
+							downloaded_bytes: p.downloaded_bytes,
+							download_speed: p.download_speed,
+						})
+						.collect(),
+					gap_sync: status.gap_sync.map(|gap_sync| GapSyncState {
+						current_block: gap_sync.best_queued_number,
+						target_block: gap_sync.target,
+					}),
+				});
 			},
 		}
 	}
@@ -363,14 +392,28 @@ mod waiting {
 	}
 }
 
+/// A subscription ID provider shared by several RPC servers.
+#[derive(Debug, Clone)]
+struct SharedIdProvider(Arc<dyn RpcSubscriptionIdProvider>);
+
+impl RpcSubscriptionIdProvider for SharedIdProvider {
+	fn next_id(&self) -> SubscriptionId<'static> {
+		self.0.next_id()
+	}
+}
+
 /// Starts RPC servers.
+///
+/// A server is started for the default listen address and for every listen address of the
+/// quality of service tiers.
 fn start_rpc_servers<R>(
 	config: &Configuration,
 	gen_rpc_module: R,
 	rpc_id_provider: Option<Box<dyn RpcSubscriptionIdProvider>>,
+	health_provider: Arc<dyn sc_rpc_server::HealthProvider>,
 ) -> Result<Box<dyn std::any::Any + Send + Sync>, error::Error>
 where
-	R: Fn(sc_rpc::DenyUnsafe) -> Result<RpcModule<()>, Error>,
+	R: Fn(sc_rpc::DenyUnsafe, Option<&config::QosTier>) -> Result<RpcModule<()>, Error>,
 {
 	fn deny_unsafe(addr: SocketAddr, methods: &RpcMethods) -> sc_rpc::DenyUnsafe {
 		let is_exposed_addr = !addr.ip().is_loopback();
@@ -387,32 +430,72 @@ where
 	};
 
 	let addr = config.rpc_addr.unwrap_or_else(|| ([127, 0, 0, 1], config.rpc_port).into());
-	let backup_addr = backup_port(addr);
 	let metrics = sc_rpc_server::RpcMetrics::new(config.prometheus_registry())?;
 
-	let server_config = sc_rpc_server::Config {
-		addrs: [addr, backup_addr],
-		max_connections: config.rpc_max_connections,
-		max_payload_in_mb: config.rpc_max_request_size,
-		max_payload_out_mb: config.rpc_max_response_size,
-		max_subs_per_conn: config.rpc_max_subs_per_conn,
-		rpc_api: gen_rpc_module(deny_unsafe(addr, &config.rpc_methods))?,
-		metrics,
-		id_provider: rpc_id_provider,
-		cors: config.rpc_cors.as_ref(),
-		tokio_handle: config.tokio_handle.clone(),
-	};
+	let mut listeners = vec![(addr, None)];
+	if let Some(qos) = &config.rpc_qos {
+		qos.validate().map_err(|e| Error::Application(Box::new(e)))?;
 
-	// TODO: https://github.com/paritytech/substrate/issues/13773
-	//
-	// `block_in_place` is a hack to allow callers to call `block_on` prior to
-	// calling `start_rpc_servers`.
-	match tokio::task::block_in_place(|| {
-		config.tokio_handle.block_on(sc_rpc_server::start_server(server_config))
-	}) {
-		Ok(server) => Ok(Box::new(waiting::Server(Some(server)))),
-		Err(e) => Err(Error::Application(e)),
+		listeners[0].1 = qos.default_tier();
+		listeners
+			.extend(qos.listeners.iter().map(|listener| (listener.addr, qos.tier(&listener.tier))));
 	}
+
+	// The custom subscription ID provider is shared by all the listen addresses.
+	let rpc_id_provider = rpc_id_provider.map(|provider| SharedIdProvider(provider.into()));
+	let mut servers = Vec::with_capacity(listeners.len());
+	for (addr, tier) in listeners {
+		let deny_unsafe = deny_unsafe(addr, &config.rpc_methods);
+		let rpc_api = gen_rpc_module(deny_unsafe, tier)?;
+		// The unsafe methods of the exposed addresses are available to the authenticated
+		// requests only, which are served by their own RPC module.
+		let authenticated_rpc_api = match (deny_unsafe, &config.rpc_methods) {
+			(sc_rpc::DenyUnsafe::Yes, RpcMethods::Auto) if config.rpc_authenticator.is_some() =>
+				Some(gen_rpc_module(sc_rpc::DenyUnsafe::No, tier)?),
+			_ => None,
+		};
+		let unsafe_methods = sc_rpc_spec_v2::unsafe_methods().map(ToOwned::to_owned).collect();
+
+		let server_config = sc_rpc_server::Config {
+			addrs: [addr, backup_port(addr)],
+			max_connections: config.rpc_max_connections,
+			max_payload_in_mb: config.rpc_max_request_size,
+			max_payload_out_mb: config.rpc_max_response_size,
+			response_limits: config.rpc_response_limits,
+			batch_limits: config.rpc_batch_limits,
+			http_compression: config.rpc_http_compression,
+			max_subs_per_conn: config.rpc_max_subs_per_conn,
+			ping_interval: config.rpc_ping_interval,
+			rpc_api,
+			authenticated_rpc_api,
+			unsafe_methods,
+			metrics: metrics.clone(),
+			qos_tier: tier.cloned(),
+			authenticator: config.rpc_authenticator.clone(),
+			request_hooks: config.rpc_request_hooks.clone(),
+			health_provider: Some(health_provider.clone()),
+			health_max_finality_lag: config.health_max_finality_lag,
+			id_provider: rpc_id_provider
+				.clone()
+				.map(|provider| Box::new(provider) as Box<dyn RpcSubscriptionIdProvider>),
+			cors: config.rpc_cors.as_ref(),
+			cors_policy: config.rpc_cors_policy.clone(),
+			tokio_handle: config.tokio_handle.clone(),
+		};
+
+		// TODO: https://github.com/paritytech/substrate/issues/13773
+		//
+		// `block_in_place` is a hack to allow callers to call `block_on` prior to
+		// calling `start_rpc_servers`.
+		match tokio::task::block_in_place(|| {
+			config.tokio_handle.block_on(sc_rpc_server::start_server(server_config))
+		}) {
+			Ok(server) => servers.push(waiting::Server(Some(server))),
+			Err(e) => return Err(Error::Application(e)),
+		}
+	}
+
+	Ok(Box::new(servers))
 }
 
 /// Transaction pool adapter.
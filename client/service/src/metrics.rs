@@ -20,8 +20,10 @@ use std::time::SystemTime;
 
 use crate::config::Configuration;
 use futures_timer::Delay;
-use prometheus_endpoint::{register, Gauge, GaugeVec, Opts, PrometheusError, Registry, U64};
-use sc_client_api::{ClientInfo, UsageProvider};
+use prometheus_endpoint::{
+	register, CounterVec, Gauge, GaugeVec, Opts, PrometheusError, Registry, U64,
+};
+use sc_client_api::{Backend, ClientInfo, UsageProvider};
 use sc_network::{config::Role, NetworkStatus, NetworkStatusProvider};
 use sc_network_common::sync::{SyncStatus, SyncStatusProvider};
 use sc_telemetry::{telemetry, TelemetryHandle, SUBSTRATE_INFO};
@@ -29,6 +31,7 @@ use sc_transaction_pool_api::{MaintainedTransactionPool, PoolStatus};
 use sc_utils::metrics::register_globals;
 use sp_api::ProvideRuntimeApi;
 use sp_runtime::traits::{Block, NumberFor, SaturatedConversion, UniqueSaturatedInto};
+use sp_trie::cache::{CacheHitStats, TrieCacheHitStats};
 use std::{
 	sync::Arc,
 	time::{Duration, Instant},
@@ -43,6 +46,8 @@ struct PrometheusMetrics {
 	// I/O
 	database_cache: Gauge<U64>,
 	state_cache: Gauge<U64>,
+	trie_cache_hits: CounterVec<U64>,
+	trie_cache_misses: CounterVec<U64>,
 }
 
 impl PrometheusMetrics {
@@ -116,6 +121,26 @@ impl PrometheusMetrics {
 				Gauge::new("substrate_state_cache_bytes", "State cache size in bytes")?,
 				registry,
 			)?,
+			trie_cache_hits: register(
+				CounterVec::new(
+					Opts::new(
+						"substrate_trie_cache_hits",
+						"Total number of lookups served by the shared trie cache",
+					),
+					&["cache"],
+				)?,
+				registry,
+			)?,
+			trie_cache_misses: register(
+				CounterVec::new(
+					Opts::new(
+						"substrate_trie_cache_misses",
+						"Total number of lookups not found in the shared trie cache",
+					),
+					&["cache"],
+				)?,
+				registry,
+			)?,
 		})
 	}
 }
@@ -128,6 +153,7 @@ pub struct MetricsService {
 	last_update: Instant,
 	last_total_bytes_inbound: u64,
 	last_total_bytes_outbound: u64,
+	last_trie_cache_stats: TrieCacheHitStats,
 	telemetry: Option<TelemetryHandle>,
 }
 
@@ -139,6 +165,7 @@ impl MetricsService {
 			metrics: None,
 			last_total_bytes_inbound: 0,
 			last_total_bytes_outbound: 0,
+			last_trie_cache_stats: Default::default(),
 			last_update: Instant::now(),
 			telemetry,
 		}
@@ -167,6 +194,7 @@ impl MetricsService {
 			metrics: Some(p),
 			last_total_bytes_inbound: 0,
 			last_total_bytes_outbound: 0,
+			last_trie_cache_stats: Default::default(),
 			last_update: Instant::now(),
 			telemetry,
 		})
@@ -175,15 +203,17 @@ impl MetricsService {
 	/// Returns a never-ending `Future` that performs the
 	/// metric and telemetry updates with information from
 	/// the given sources.
-	pub async fn run<TBl, TExPool, TCl, TNet, TSync>(
+	pub async fn run<TBl, TExPool, TCl, TBackend, TNet, TSync>(
 		mut self,
 		client: Arc<TCl>,
+		backend: Arc<TBackend>,
 		transactions: Arc<TExPool>,
 		network: TNet,
 		syncing: TSync,
 	) where
 		TBl: Block,
 		TCl: ProvideRuntimeApi<TBl> + UsageProvider<TBl>,
+		TBackend: Backend<TBl>,
 		TExPool: MaintainedTransactionPool<Block = TBl, Hash = <TBl as Block>::Hash>,
 		TNet: NetworkStatusProvider,
 		TSync: SyncStatusProvider<TBl>,
@@ -203,12 +233,37 @@ impl MetricsService {
 
 			// Update / Send the metrics.
 			self.update(&client.usage_info(), &transactions.status(), net_status, sync_status);
+			if let Some(stats) = backend.trie_cache_hit_stats() {
+				self.update_trie_cache(stats);
+			}
 
 			// Schedule next tick.
 			timer.reset(timer_interval);
 		}
 	}
 
+	/// Increase the trie cache counters by the lookups performed since the previous update.
+	fn update_trie_cache(&mut self, stats: TrieCacheHitStats) {
+		let Some(metrics) = self.metrics.as_ref() else { return };
+		let previous = &mut self.last_trie_cache_stats;
+
+		for (cache, current, previous) in [
+			("node", stats.node_cache, &mut previous.node_cache),
+			("value", stats.value_cache, &mut previous.value_cache),
+		] {
+			let CacheHitStats { hits, misses } = current;
+			metrics
+				.trie_cache_hits
+				.with_label_values(&[cache])
+				.inc_by(hits.saturating_sub(previous.hits));
+			metrics
+				.trie_cache_misses
+				.with_label_values(&[cache])
+				.inc_by(misses.saturating_sub(previous.misses));
+			*previous = current;
+		}
+	}
+
 	fn update<T: Block>(
 		&mut self,
 		info: &ClientInfo<T>,
@@ -246,18 +246,43 @@ fn node_config<
 		wasm_method: Default::default(),
 		wasm_runtime_overrides: Default::default(),
 		rpc_addr: Default::default(),
+		grpc_addr: None,
+		grpc_max_connections: Default::default(),
+		health_addr: None,
+		health_max_finality_lag: None,
 		rpc_max_connections: Default::default(),
 		rpc_cors: None,
+		rpc_cors_policy: None,
 		rpc_methods: Default::default(),
 		rpc_max_request_size: Default::default(),
 		rpc_max_response_size: Default::default(),
+		rpc_response_limits: Default::default(),
+		rpc_batch_limits: Default::default(),
 		rpc_id_provider: Default::default(),
 		rpc_max_subs_per_conn: Default::default(),
+		rpc_ping_interval: None,
+		rpc_http_compression: false,
+		rpc_call_timeout: None,
+		rpc_max_broadcasts: 64,
+		rpc_operation_cache_ttl: None,
+		rpc_operation_coalescing: true,
+		rpc_operation_max_body_chunk_size: None,
+		rpc_operation_storage_timeout: None,
+		rpc_operation_call_timeout: None,
+		rpc_operation_body_timeout: None,
+		rpc_pin_eviction: None,
+		rpc_max_pinned_blocks_per_subscription: 512,
+		rpc_max_follow_subscriptions: None,
+		rpc_qos: None,
+		rpc_audit_sink: None,
+		rpc_authenticator: None,
+		rpc_request_hooks: Default::default(),
 		rpc_port: 9944,
 		prometheus_config: None,
 		telemetry_endpoints: None,
 		default_heap_pages: None,
 		offchain_worker: Default::default(),
+		extrinsic_index: false,
 		force_authoring: false,
 		disable_grandpa: false,
 		dev_key_seed: key_seed,
@@ -69,6 +69,9 @@ pub enum Error {
 
 	#[error("The pool is not accepting future transactions")]
 	RejectedFutureTransaction,
+
+	#[error("The sender has too many future transactions in the pool")]
+	TooManyFutureTransactions,
 }
 
 /// Transaction pool error conversion.
@@ -133,6 +133,68 @@ pub enum TransactionStatus<Hash, BlockHash> {
 	Invalid,
 }
 
+/// An event of the pool, about any of its transactions.
+///
+/// The events explain how the transactions enter the pool, move between its queues and leave
+/// it, which is e.g. useful to monitor why the transactions disappear.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "event")]
+pub enum PoolEvent<Hash, BlockHash> {
+	/// The transaction entered the future queue.
+	Future {
+		/// The hash of the transaction.
+		hash: Hash,
+	},
+	/// The transaction entered the ready queue.
+	Ready {
+		/// The hash of the transaction.
+		hash: Hash,
+	},
+	/// The transaction moved from the future queue to the ready queue, since the tags it
+	/// requires are now provided.
+	Promoted {
+		/// The hash of the transaction.
+		hash: Hash,
+	},
+	/// The transaction has been replaced by another transaction providing the same tags.
+	Usurped {
+		/// The hash of the transaction.
+		hash: Hash,
+		/// The hash of the replacing transaction.
+		by: Hash,
+	},
+	/// The transaction has been removed from the pool.
+	Dropped {
+		/// The hash of the transaction.
+		hash: Hash,
+		/// Why the transaction has been removed.
+		reason: DropReason,
+	},
+	/// The transaction has been included in a block and pruned from the pool.
+	Pruned {
+		/// The hash of the transaction.
+		hash: Hash,
+		/// The hash of the block including the transaction.
+		block: BlockHash,
+	},
+}
+
+/// The reason of the removal of a transaction from the pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DropReason {
+	/// The pool limits have been exceeded.
+	Limit,
+	/// The transaction is no longer valid.
+	Invalid,
+	/// The transaction, or its sender, has been banned.
+	Banned,
+}
+
+/// The stream of the events of the pool.
+pub type PoolEventStream<Hash, BlockHash> =
+	futures::channel::mpsc::Receiver<PoolEvent<Hash, BlockHash>>;
+
 /// The stream of transaction events.
 pub type TransactionStatusStream<Hash, BlockHash> =
 	dyn Stream<Item = TransactionStatus<Hash, BlockHash>> + Send;
@@ -254,6 +316,11 @@ pub trait TransactionPool: Send + Sync {
 	/// Return an event stream of transactions imported to the pool.
 	fn import_notification_stream(&self) -> ImportNotificationStream<TxHash<Self>>;
 
+	/// Return a stream of the events of all the transactions of the pool.
+	///
+	/// The events are dropped when the stream is not consumed fast enough.
+	fn pool_events(&self) -> PoolEventStream<TxHash<Self>, BlockHash<Self>>;
+
 	// *** networking
 	/// Notify the pool about transactions broadcast.
 	fn on_broadcasted(&self, propagations: HashMap<TxHash<Self>, Vec<String>>);
@@ -263,6 +330,18 @@ pub trait TransactionPool: Send + Sync {
 
 	/// Return specific ready transaction by hash, if there is one.
 	fn ready_transaction(&self, hash: &TxHash<Self>) -> Option<Arc<Self::InPoolTransaction>>;
+
+	// *** RPC
+	/// Returns the transactions of the future queue, waiting for the tags they require.
+	fn futures(&self) -> Vec<Arc<Self::InPoolTransaction>>;
+
+	/// Temporarily ban the transactions identified by given hashes from entering the pool, and
+	/// remove them (and dependent transactions) from the pool.
+	fn ban_transactions(&self, hashes: &[TxHash<Self>]) -> Vec<Arc<Self::InPoolTransaction>>;
+
+	/// Temporarily ban the given senders, identified by their encoding, from entering the pool,
+	/// and remove their transactions (and dependent transactions) from the pool.
+	fn ban_senders(&self, senders: Vec<Vec<u8>>) -> Vec<Arc<Self::InPoolTransaction>>;
 }
 
 /// An iterator of ready transactions.
@@ -519,4 +598,16 @@ mod tests {
 		let event_dec: TransactionStatus<u8, u8> = serde_json::from_str(exp).unwrap();
 		assert_eq!(event_dec, TransactionStatus::Finalized((1, 0)));
 	}
+
+	#[test]
+	fn pool_event_serialization() {
+		let event: PoolEvent<u8, u8> = PoolEvent::Dropped { hash: 1, reason: DropReason::Limit };
+		let ser = serde_json::to_string(&event).unwrap();
+		assert_eq!(ser, r#"{"event":"dropped","hash":1,"reason":"limit"}"#);
+		assert_eq!(serde_json::from_str::<PoolEvent<u8, u8>>(&ser).unwrap(), event);
+
+		let event: PoolEvent<u8, u8> = PoolEvent::Usurped { hash: 1, by: 2 };
+		let ser = serde_json::to_string(&event).unwrap();
+		assert_eq!(ser, r#"{"event":"usurped","hash":1,"by":2}"#);
+	}
 }
@@ -20,7 +20,12 @@
 //!
 //! For a more full-featured pool, have a look at the `pool` module.
 
-use std::{cmp::Ordering, collections::HashSet, fmt, hash, sync::Arc};
+use std::{
+	cmp::{Ordering, Reverse},
+	collections::HashSet,
+	fmt, hash, mem,
+	sync::Arc,
+};
 
 use crate::LOG_TARGET;
 use log::{debug, trace, warn};
@@ -33,6 +38,7 @@ use sp_runtime::{
 		TransactionLongevity as Longevity, TransactionPriority as Priority,
 		TransactionSource as Source, TransactionTag as Tag,
 	},
+	Percent,
 };
 
 use super::{
@@ -146,6 +152,23 @@ impl<Hash, Extrinsic> InPoolTransaction for Transaction<Hash, Extrinsic> {
 	}
 }
 
+impl<Hash, Extrinsic> Transaction<Hash, Extrinsic> {
+	/// Returns an estimate of the memory used by the transaction in the pool, in bytes.
+	///
+	/// This accounts for the transaction, its encoding and its tags, which are stored once more
+	/// by the indexes of the pool. The actual heap usage of the extrinsic is not known, and is
+	/// assumed to be about the size of its encoding.
+	pub fn memory_usage(&self) -> usize {
+		let tags = self
+			.requires
+			.iter()
+			.chain(&self.provides)
+			.map(|tag| mem::size_of::<Tag>() + tag.len())
+			.sum::<usize>();
+		mem::size_of::<Self>() + self.bytes + 2 * tags
+	}
+}
+
 impl<Hash: Clone, Extrinsic: Clone> Transaction<Hash, Extrinsic> {
 	/// Explicit transaction clone.
 	///
@@ -211,6 +234,10 @@ const RECENTLY_PRUNED_TAGS: usize = 2;
 #[derive(Debug)]
 pub struct BasePool<Hash: hash::Hash + Eq, Ex> {
 	reject_future_transactions: bool,
+	/// The maximum number of future transactions of a sender.
+	future_limit_per_sender: Option<usize>,
+	/// The length of the encoded nonces ending the tags identifying the senders.
+	nonce_len: usize,
 	future: FutureTransactions<Hash, Ex>,
 	ready: ReadyTransactions<Hash, Ex>,
 	/// Store recently pruned tags (for last two invocations).
@@ -232,6 +259,9 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 	pub fn new(reject_future_transactions: bool) -> Self {
 		Self {
 			reject_future_transactions,
+			future_limit_per_sender: None,
+			// The `u32` nonces of the node runtime.
+			nonce_len: 4,
 			future: Default::default(),
 			ready: Default::default(),
 			recently_pruned: Default::default(),
@@ -239,6 +269,26 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		}
 	}
 
+	/// Sets the minimum priority increase of a transaction replacing the ready transactions
+	/// that provide the same tags.
+	pub fn with_replacement_priority_bump(mut self, bump: Percent) -> Self {
+		self.ready = self.ready.with_replacement_priority_bump(bump);
+		self
+	}
+
+	/// Sets the maximum number of future transactions of a sender, see [`tag_sender`].
+	pub fn with_future_limit_per_sender(mut self, limit: Option<usize>) -> Self {
+		self.future_limit_per_sender = limit;
+		self
+	}
+
+	/// Sets the length of the encoded nonces ending the tags identifying the senders, see
+	/// [`tag_sender`].
+	pub fn with_nonce_len(mut self, nonce_len: usize) -> Self {
+		self.nonce_len = nonce_len;
+		self
+	}
+
 	/// Temporary enables future transactions, runs closure and then restores
 	/// `reject_future_transactions` flag back to previous value.
 	///
@@ -287,6 +337,20 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 				return Err(error::Error::RejectedFutureTransaction)
 			}
 
+			let nonce_len = self.nonce_len;
+			if let (Some(limit), Some(sender)) =
+				(self.future_limit_per_sender, tx_sender(&tx.transaction, nonce_len))
+			{
+				let count = self
+					.future
+					.all()
+					.filter(|other| tx_sender(other, nonce_len) == Some(sender))
+					.count();
+				if count >= limit {
+					return Err(error::Error::TooManyFutureTransactions)
+				}
+			}
+
 			let hash = tx.transaction.hash.clone();
 			self.future.import(tx);
 			return Ok(Imported::Future { hash })
@@ -365,6 +429,11 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		self.future.all()
 	}
 
+	/// Returns the future transactions in the pool.
+	pub fn future_transactions(&self) -> Vec<Arc<Transaction<Hash, Ex>>> {
+		self.future.transactions()
+	}
+
 	/// Returns pool transactions given list of hashes.
 	///
 	/// Includes both ready and future pool. For every hash in the `hashes`
@@ -395,30 +464,7 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		let mut removed = vec![];
 
 		while ready.is_exceeded(self.ready.len(), self.ready.bytes()) {
-			// find the worst transaction
-			let worst = self.ready.fold::<TransactionRef<Hash, Ex>, _>(|worst, current| {
-				let transaction = &current.transaction;
-				worst
-					.map(|worst| {
-						// Here we don't use `TransactionRef`'s ordering implementation because
-						// while it prefers priority like need here, it also prefers older
-						// transactions for inclusion purposes and limit enforcement needs to prefer
-						// newer transactions instead and drop the older ones.
-						match worst.transaction.priority.cmp(&transaction.transaction.priority) {
-							Ordering::Less => worst,
-							Ordering::Equal =>
-								if worst.insertion_id > transaction.insertion_id {
-									transaction.clone()
-								} else {
-									worst
-								},
-							Ordering::Greater => transaction.clone(),
-						}
-					})
-					.or_else(|| Some(transaction.clone()))
-			});
-
-			if let Some(worst) = worst {
+			if let Some(worst) = self.worst_ready() {
 				removed.append(&mut self.remove_subtree(&[worst.transaction.hash.clone()]))
 			} else {
 				break
@@ -443,6 +489,93 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		removed
 	}
 
+	/// Removes the transactions until the memory used by the pool is within the budget, in
+	/// bytes, see [`Transaction::memory_usage`].
+	///
+	/// The future transactions are removed first, from the lowest priority, then the worst
+	/// ready transactions.
+	///
+	/// Returns the removed transactions.
+	pub fn enforce_memory_budget(&mut self, budget: usize) -> Vec<Arc<Transaction<Hash, Ex>>> {
+		let mut usage = self.memory_usage();
+		let mut removed = vec![];
+
+		while usage > budget {
+			let worst = match self.worst_future() {
+				Some(worst) => worst,
+				None => match self.worst_ready() {
+					Some(worst) => worst.transaction.hash.clone(),
+					None => break,
+				},
+			};
+
+			let subtree = self.remove_subtree(&[worst]);
+			if subtree.is_empty() {
+				break
+			}
+			usage = usage.saturating_sub(subtree.iter().map(|tx| tx.memory_usage()).sum());
+			removed.extend(subtree);
+		}
+
+		removed
+	}
+
+	/// Returns an estimate of the memory used by the transactions of the pool, in bytes.
+	pub fn memory_usage(&mut self) -> usize {
+		let ready = self
+			.ready
+			.fold(|usage, current| {
+				Some(usage.unwrap_or(0) + current.transaction.transaction.memory_usage())
+			})
+			.unwrap_or(0);
+		let future = self.future.all().map(Transaction::memory_usage).sum::<usize>();
+		ready + future
+	}
+
+	/// Returns the worst ready transaction, i.e. the one with the lowest priority, and the
+	/// newest one among them.
+	fn worst_ready(&mut self) -> Option<TransactionRef<Hash, Ex>> {
+		self.ready.fold::<TransactionRef<Hash, Ex>, _>(|worst, current| {
+			let transaction = &current.transaction;
+			worst
+				.map(|worst| {
+					// Here we don't use `TransactionRef`'s ordering implementation because
+					// while it prefers priority like need here, it also prefers older
+					// transactions for inclusion purposes and limit enforcement needs to prefer
+					// newer transactions instead and drop the older ones.
+					match worst.transaction.priority.cmp(&transaction.transaction.priority) {
+						Ordering::Less => worst,
+						Ordering::Equal =>
+							if worst.insertion_id > transaction.insertion_id {
+								transaction.clone()
+							} else {
+								worst
+							},
+						Ordering::Greater => transaction.clone(),
+					}
+				})
+				.or_else(|| Some(transaction.clone()))
+		})
+	}
+
+	/// Returns the hash of the worst future transaction, i.e. the one with the lowest
+	/// priority, and the newest one among them.
+	fn worst_future(&mut self) -> Option<Hash> {
+		self.future
+			.fold(|worst, current| {
+				let candidate = (
+					current.transaction.priority,
+					Reverse(current.imported_at),
+					current.transaction.hash.clone(),
+				);
+				match worst {
+					Some(worst) if (worst.0, worst.1) <= (candidate.0, candidate.1) => Some(worst),
+					_ => Some(candidate),
+				}
+			})
+			.map(|(_, _, hash)| hash)
+	}
+
 	/// Removes all transactions represented by the hashes and all other transactions
 	/// that depend on them.
 	///
@@ -530,6 +663,20 @@ impl Limit {
 	}
 }
 
+/// Returns the part of a `(sender, nonce)` tag identifying its sender, given the length of
+/// the encoded nonces.
+///
+/// The `(sender, nonce)` tags are provided by the transactions of the `CheckNonce` signed
+/// extension.
+pub(crate) fn tag_sender(tag: &Tag, nonce_len: usize) -> Option<&[u8]> {
+	tag.len().checked_sub(nonce_len).filter(|len| *len > 0).map(|len| &tag[..len])
+}
+
+/// Returns the sender of the transaction, identified by the first tag it provides.
+fn tx_sender<Hash, Ex>(tx: &Transaction<Hash, Ex>, nonce_len: usize) -> Option<&[u8]> {
+	tx.provides.first().and_then(|tag| tag_sender(tag, nonce_len))
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -995,6 +1142,65 @@ source: TransactionSource::External, requires: [03, 02], provides: [04], data: [
 		}
 	}
 
+	#[test]
+	fn should_limit_future_transactions_per_sender() {
+		// given
+		let mut pool = pool().with_future_limit_per_sender(Some(2)).with_nonce_len(1);
+		let tag = |sender: u8, nonce: u8| vec![sender, sender, nonce];
+		let future = |hash: Hash, sender: u8, nonce: u8| Transaction {
+			data: vec![hash as u8],
+			hash,
+			requires: vec![tag(sender, nonce - 1)],
+			provides: vec![tag(sender, nonce)],
+			..DEFAULT_TX.clone()
+		};
+
+		// when
+		pool.import(future(1, 1, 5)).unwrap();
+		pool.import(future(2, 1, 6)).unwrap();
+		pool.import(future(3, 2, 5)).unwrap();
+		let err = pool.import(future(4, 1, 7)).unwrap_err();
+
+		// then
+		assert!(matches!(err, error::Error::TooManyFutureTransactions));
+		assert_eq!(pool.future.len(), 3);
+	}
+
+	#[test]
+	fn should_enforce_memory_budget() {
+		// given
+		let mut pool = pool();
+		let tx = |hash: Hash, priority: u64, requires: Vec<Tag>, provides: Vec<Tag>| Transaction {
+			data: vec![hash as u8; 100],
+			bytes: 100,
+			hash,
+			priority,
+			requires,
+			provides,
+			..DEFAULT_TX.clone()
+		};
+		pool.import(tx(1, 5, vec![], vec![vec![1]])).unwrap();
+		pool.import(tx(2, 1, vec![], vec![vec![2]])).unwrap();
+		pool.import(tx(3, 9, vec![vec![10]], vec![vec![3]])).unwrap();
+		pool.import(tx(4, 2, vec![vec![11]], vec![vec![4]])).unwrap();
+		let usage = pool.memory_usage();
+		let tx_usage = tx(0, 0, vec![], vec![vec![0]]).memory_usage();
+
+		// when
+		let removed = pool.enforce_memory_budget(usage - tx_usage);
+
+		// then the future transaction with the lowest priority is removed first
+		assert_eq!(removed.iter().map(|tx| tx.hash).collect::<Vec<_>>(), vec![4]);
+
+		// when
+		let removed = pool.enforce_memory_budget(usage - 3 * tx_usage);
+
+		// then the remaining future transaction is removed before the worst ready one
+		assert_eq!(removed.iter().map(|tx| tx.hash).collect::<Vec<_>>(), vec![3, 2]);
+		assert_eq!(pool.ready().map(|tx| tx.hash).collect::<Vec<_>>(), vec![1]);
+		assert!(pool.memory_usage() <= usage - 3 * tx_usage);
+	}
+
 	#[test]
 	fn should_clear_future_queue() {
 		// given
@@ -234,6 +234,11 @@ impl<Hash: hash::Hash + Eq + Clone, Ex> FutureTransactions<Hash, Ex> {
 		self.waiting.values().map(|waiting| &*waiting.transaction)
 	}
 
+	/// Returns all future transactions.
+	pub fn transactions(&self) -> Vec<Arc<Transaction<Hash, Ex>>> {
+		self.waiting.values().map(|waiting| waiting.transaction.clone()).collect()
+	}
+
 	/// Removes and returns all future transactions.
 	pub fn clear(&mut self) -> Vec<Arc<Transaction<Hash, Ex>>> {
 		self.wanted_tags.clear();
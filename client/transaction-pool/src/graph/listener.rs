@@ -19,8 +19,10 @@
 use std::{collections::HashMap, fmt::Debug, hash};
 
 use crate::LOG_TARGET;
+use futures::channel::mpsc::{channel, Sender};
 use linked_hash_map::LinkedHashMap;
 use log::{debug, trace};
+use sc_transaction_pool_api::{DropReason, PoolEvent, PoolEventStream};
 use serde::Serialize;
 use sp_runtime::traits;
 
@@ -30,14 +32,22 @@ use super::{watcher, BlockHash, ChainApi, ExtrinsicHash};
 pub struct Listener<H: hash::Hash + Eq, C: ChainApi> {
 	watchers: HashMap<H, watcher::Sender<H, ExtrinsicHash<C>>>,
 	finality_watchers: LinkedHashMap<ExtrinsicHash<C>, Vec<H>>,
+	pool_event_sinks: Vec<Sender<PoolEvent<H, BlockHash<C>>>>,
 }
 
 /// Maximum number of blocks awaiting finality at any time.
 const MAX_FINALITY_WATCHERS: usize = 512;
 
+/// The size of the buffer of the pool event streams.
+const POOL_EVENTS_BUFFER_SIZE: usize = 1024;
+
 impl<H: hash::Hash + Eq + Debug, C: ChainApi> Default for Listener<H, C> {
 	fn default() -> Self {
-		Self { watchers: Default::default(), finality_watchers: Default::default() }
+		Self {
+			watchers: Default::default(),
+			finality_watchers: Default::default(),
+			pool_event_sinks: Default::default(),
+		}
 	}
 }
 
@@ -58,6 +68,27 @@ impl<H: hash::Hash + traits::Member + Serialize, C: ChainApi> Listener<H, C> {
 		}
 	}
 
+	/// Send the pool event to the subscribers of the pool events.
+	///
+	/// The event is dropped for the subscribers whose buffer is full.
+	fn pool_event(&mut self, event: PoolEvent<H, BlockHash<C>>) {
+		self.pool_event_sinks.retain_mut(|sink| match sink.try_send(event.clone()) {
+			Ok(()) => true,
+			Err(e) if e.is_full() => {
+				log::warn!(target: LOG_TARGET, "Dropping a pool event since the channel is full");
+				true
+			},
+			Err(_) => false,
+		});
+	}
+
+	/// Returns a stream of the events of all the transactions of the pool.
+	pub fn pool_events(&mut self) -> PoolEventStream<H, BlockHash<C>> {
+		let (sink, stream) = channel(POOL_EVENTS_BUFFER_SIZE);
+		self.pool_event_sinks.push(sink);
+		stream
+	}
+
 	/// Creates a new watcher for given verified extrinsic.
 	///
 	/// The watcher can be used to subscribe to life-cycle events of that extrinsic.
@@ -76,15 +107,25 @@ impl<H: hash::Hash + traits::Member + Serialize, C: ChainApi> Listener<H, C> {
 	pub fn ready(&mut self, tx: &H, old: Option<&H>) {
 		trace!(target: LOG_TARGET, "[{:?}] Ready (replaced with {:?})", tx, old);
 		self.fire(tx, |watcher| watcher.ready());
+		self.pool_event(PoolEvent::Ready { hash: tx.clone() });
 		if let Some(old) = old {
 			self.fire(old, |watcher| watcher.usurped(tx.clone()));
+			self.pool_event(PoolEvent::Usurped { hash: old.clone(), by: tx.clone() });
 		}
 	}
 
+	/// Transaction was promoted from the future pool to the ready pool.
+	pub fn promoted(&mut self, tx: &H) {
+		trace!(target: LOG_TARGET, "[{:?}] Promoted", tx);
+		self.fire(tx, |watcher| watcher.ready());
+		self.pool_event(PoolEvent::Promoted { hash: tx.clone() });
+	}
+
 	/// New transaction was added to the future pool.
 	pub fn future(&mut self, tx: &H) {
 		trace!(target: LOG_TARGET, "[{:?}] Future", tx);
 		self.fire(tx, |watcher| watcher.future());
+		self.pool_event(PoolEvent::Future { hash: tx.clone() });
 	}
 
 	/// Transaction was dropped from the pool because of the limit.
@@ -93,13 +134,25 @@ impl<H: hash::Hash + traits::Member + Serialize, C: ChainApi> Listener<H, C> {
 		self.fire(tx, |watcher| match by {
 			Some(t) => watcher.usurped(t.clone()),
 			None => watcher.dropped(),
-		})
+		});
+		self.pool_event(match by {
+			Some(by) => PoolEvent::Usurped { hash: tx.clone(), by: by.clone() },
+			None => PoolEvent::Dropped { hash: tx.clone(), reason: DropReason::Limit },
+		});
 	}
 
 	/// Transaction was removed as invalid.
 	pub fn invalid(&mut self, tx: &H) {
 		debug!(target: LOG_TARGET, "[{:?}] Extrinsic invalid", tx);
 		self.fire(tx, |watcher| watcher.invalid());
+		self.pool_event(PoolEvent::Dropped { hash: tx.clone(), reason: DropReason::Invalid });
+	}
+
+	/// Transaction was removed since it, or its sender, has been banned.
+	pub fn banned(&mut self, tx: &H) {
+		debug!(target: LOG_TARGET, "[{:?}] Extrinsic banned", tx);
+		self.fire(tx, |watcher| watcher.invalid());
+		self.pool_event(PoolEvent::Dropped { hash: tx.clone(), reason: DropReason::Banned });
 	}
 
 	/// Transaction was pruned from the pool.
@@ -112,6 +165,7 @@ impl<H: hash::Hash + traits::Member + Serialize, C: ChainApi> Listener<H, C> {
 		let tx_index = txs.len() - 1;
 
 		self.fire(tx, |watcher| watcher.in_block(block_hash, tx_index));
+		self.pool_event(PoolEvent::Pruned { hash: tx.clone(), block: block_hash });
 
 		while self.finality_watchers.len() > MAX_FINALITY_WATCHERS {
 			if let Some((hash, txs)) = self.finality_watchers.pop_front() {
@@ -16,7 +16,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Duration};
 
 use crate::LOG_TARGET;
 use futures::{channel::mpsc::Receiver, Future};
@@ -28,6 +28,7 @@ use sp_runtime::{
 	transaction_validity::{
 		TransactionSource, TransactionTag as Tag, TransactionValidity, TransactionValidityError,
 	},
+	Percent,
 };
 use std::time::Instant;
 
@@ -117,8 +118,54 @@ pub struct Options {
 	pub future: base::Limit,
 	/// Reject future transactions.
 	pub reject_future_transactions: bool,
+	/// The maximum number of future transactions of a sender, not limited if `None`.
+	///
+	/// The sender of a future transaction is identified by the first tag it provides, see
+	/// [`Options::nonce_len`].
+	pub future_limit_per_sender: Option<usize>,
+	/// The length of the encoded nonces ending the `(sender, nonce)` tags of the `CheckNonce`
+	/// signed extension. The rest of the tags identifies the sender of the transactions, for
+	/// the senders banned from the pool and the `future_limit_per_sender`.
+	pub nonce_len: usize,
 	/// How long the extrinsic is banned for.
 	pub ban_time: Duration,
+	/// The minimum priority increase, relative to the priority of the replaced transactions,
+	/// of a transaction replacing the ready transactions that provide the same tags, e.g.
+	/// the transaction of the same sender with the same nonce.
+	pub replacement_priority_bump: Percent,
+	/// The memory budget of the pool, in bytes, not limited if `None`.
+	///
+	/// The memory used by the transactions is estimated after each import, and the future
+	/// transactions with the lowest priority, then the worst ready transactions, are evicted
+	/// until the pool fits in the budget.
+	pub memory_budget: Option<usize>,
+	/// The file the transactions of the pool are persisted to, such that they are restored
+	/// when the node restarts. The pool is not persisted if `None`.
+	pub persistence_path: Option<PathBuf>,
+	/// Revalidate only the ready transactions affected by the new blocks. All the ready
+	/// transactions are revalidated at every block if `None`.
+	pub selective_revalidation: Option<SelectiveRevalidation>,
+}
+
+/// Revalidation of the ready transactions sharing a sender with the transactions of the new
+/// blocks only.
+///
+/// All the ready transactions are still revalidated periodically, as well as at the blocks
+/// retracting blocks or updating the runtime.
+#[derive(Debug, Clone)]
+pub struct SelectiveRevalidation {
+	/// The number of blocks between the revalidations of all the ready transactions.
+	pub full_revalidation_period: u32,
+	/// The length of the encoded nonces ending the `(sender, nonce)` tags of the `CheckNonce`
+	/// signed extension, which identify the sender of the transactions.
+	pub nonce_len: usize,
+}
+
+impl Default for SelectiveRevalidation {
+	fn default() -> Self {
+		// The `u32` nonces of the node runtime.
+		Self { full_revalidation_period: 20, nonce_len: 4 }
+	}
 }
 
 impl Default for Options {
@@ -127,7 +174,14 @@ impl Default for Options {
 			ready: base::Limit { count: 8192, total_bytes: 20 * 1024 * 1024 },
 			future: base::Limit { count: 512, total_bytes: 1 * 1024 * 1024 },
 			reject_future_transactions: false,
+			future_limit_per_sender: None,
+			// The `u32` nonces of the node runtime.
+			nonce_len: 4,
 			ban_time: Duration::from_secs(60 * 30),
+			replacement_priority_bump: Percent::zero(),
+			memory_budget: None,
+			persistence_path: None,
+			selective_revalidation: None,
 		}
 	}
 }
@@ -244,12 +298,14 @@ impl<B: ChainApi> Pool<B> {
 	/// To perform pruning we need the tags that each extrinsic provides and to avoid calling
 	/// into runtime too often we first lookup all extrinsics that are in the pool and get
 	/// their provided tags from there. Otherwise we query the runtime at the `parent` block.
+	///
+	/// Returns the tags provided by the extrinsics.
 	pub async fn prune(
 		&self,
 		at: &BlockId<B::Block>,
 		parent: &BlockId<B::Block>,
 		extrinsics: &[ExtrinsicFor<B>],
-	) -> Result<(), B::Error> {
+	) -> Result<Vec<Tag>, B::Error> {
 		log::debug!(
 			target: LOG_TARGET,
 			"Starting pruning of block {:?} (extrinsics: {})",
@@ -298,7 +354,8 @@ impl<B: ChainApi> Pool<B> {
 			}
 		}
 
-		self.prune_tags(at, future_tags, in_pool_hashes).await
+		self.prune_tags(at, future_tags.clone(), in_pool_hashes).await?;
+		Ok(future_tags)
 	}
 
 	/// Prunes ready transactions that provide given list of tags.
@@ -423,6 +480,7 @@ impl<B: ChainApi> Pool<B> {
 				if validity.provides.is_empty() {
 					ValidatedTransaction::Invalid(hash, error::Error::NoTagsProvided.into())
 				} else {
+					let validity = self.validated_pool.adjust_priority(source, &xt, validity);
 					ValidatedTransaction::valid_at(
 						block_number.saturated_into::<u64>(),
 						hash,
@@ -461,7 +519,7 @@ mod tests {
 	use futures::executor::block_on;
 	use parking_lot::Mutex;
 	use sc_transaction_pool_api::TransactionStatus;
-	use sp_runtime::transaction_validity::TransactionSource;
+	use sp_runtime::transaction_validity::{TransactionPriority, TransactionSource};
 	use std::{collections::HashMap, time::Instant};
 	use substrate_test_runtime::{AccountId, ExtrinsicBuilder, Transfer, H256};
 	use substrate_test_runtime_client::AccountKeyring::{Alice, Bob};
@@ -490,6 +548,42 @@ mod tests {
 		assert_eq!(pool.validated_pool().ready().map(|v| v.hash).collect::<Vec<_>>(), vec![hash]);
 	}
 
+	#[test]
+	fn should_adjust_priority_with_the_hook() {
+		struct Boost;
+		impl crate::PriorityHook<substrate_test_runtime::Block> for Boost {
+			fn priority(
+				&self,
+				source: TransactionSource,
+				_xt: &ExtrinsicFor<TestApi>,
+				priority: TransactionPriority,
+			) -> TransactionPriority {
+				match source {
+					TransactionSource::External => priority.saturating_add(100),
+					_ => priority,
+				}
+			}
+		}
+
+		// given
+		let pool = pool();
+		pool.validated_pool().set_priority_hook(Arc::new(Boost));
+		let uxt = uxt(Transfer {
+			from: Alice.into(),
+			to: AccountId::from_h256(H256::from_low_u64_be(2)),
+			amount: 5,
+			nonce: 0,
+		});
+
+		// when
+		block_on(pool.submit_one(&BlockId::Number(0), SOURCE, uxt)).unwrap();
+
+		// then
+		// The test api validates the transactions with the priority 4.
+		let ready = pool.validated_pool().ready().next().unwrap();
+		assert_eq!(ready.priority, 104);
+	}
+
 	#[test]
 	fn should_reject_if_temporarily_banned() {
 		// given
@@ -20,6 +20,7 @@
 //!
 //! Keeps only recent extrinsic and discard the ones kept for a significant amount of time.
 //! Discarded extrinsics are banned so that they don't get re-imported again.
+//! The senders may also be banned, such that none of their extrinsics enter the pool.
 
 use parking_lot::RwLock;
 use std::{
@@ -28,7 +29,9 @@ use std::{
 	time::{Duration, Instant},
 };
 
-use super::base_pool::Transaction;
+use sp_runtime::transaction_validity::TransactionTag as Tag;
+
+use super::base_pool::{tag_sender, Transaction};
 
 /// Expected size of the banned extrinsics cache.
 const EXPECTED_SIZE: usize = 2048;
@@ -42,18 +45,34 @@ pub struct PoolRotator<Hash> {
 	ban_time: Duration,
 	/// Currently banned extrinsics.
 	banned_until: RwLock<HashMap<Hash, Instant>>,
+	/// Currently banned senders, identified by their encoding.
+	banned_senders_until: RwLock<HashMap<Vec<u8>, Instant>>,
+	/// The length of the encoded nonces ending the tags identifying the senders.
+	nonce_len: usize,
 }
 
 impl<Hash: hash::Hash + Eq> Default for PoolRotator<Hash> {
 	fn default() -> Self {
-		Self { ban_time: Duration::from_secs(60 * 30), banned_until: Default::default() }
+		Self {
+			ban_time: Duration::from_secs(60 * 30),
+			banned_until: Default::default(),
+			banned_senders_until: Default::default(),
+			// The `u32` nonces of the node runtime.
+			nonce_len: 4,
+		}
 	}
 }
 
 impl<Hash: hash::Hash + Eq + Clone> PoolRotator<Hash> {
 	/// New rotator instance with specified ban time.
 	pub fn new(ban_time: Duration) -> Self {
-		Self { ban_time, banned_until: Default::default() }
+		Self { ban_time, ..Default::default() }
+	}
+
+	/// Sets the length of the encoded nonces ending the tags identifying the senders.
+	pub fn with_nonce_len(mut self, nonce_len: usize) -> Self {
+		self.nonce_len = nonce_len;
+		self
 	}
 
 	/// Returns `true` if extrinsic hash is currently banned.
@@ -69,13 +88,32 @@ impl<Hash: hash::Hash + Eq + Clone> PoolRotator<Hash> {
 			banned.insert(hash, *now + self.ban_time);
 		}
 
-		if banned.len() > 2 * EXPECTED_SIZE {
-			while banned.len() > EXPECTED_SIZE {
-				if let Some(key) = banned.keys().next().cloned() {
-					banned.remove(&key);
-				}
-			}
+		trim(&mut banned);
+	}
+
+	/// Bans given set of senders.
+	pub fn ban_senders(&self, now: &Instant, senders: impl IntoIterator<Item = Vec<u8>>) {
+		let mut banned = self.banned_senders_until.write();
+
+		for sender in senders {
+			banned.insert(sender, *now + self.ban_time);
 		}
+
+		trim(&mut banned);
+	}
+
+	/// Returns `true` if the sender of the extrinsic providing the given tags is currently
+	/// banned.
+	///
+	/// The extrinsics of a sender provide the `(sender, nonce)` tags of the `CheckNonce`
+	/// signed extension, whose sender is the tag without its nonce.
+	pub fn is_sender_banned(&self, provides: &[Tag]) -> bool {
+		let banned = self.banned_senders_until.read();
+		!banned.is_empty() &&
+			provides
+				.iter()
+				.filter_map(|tag| tag_sender(tag, self.nonce_len))
+				.any(|sender| banned.contains_key(sender))
 	}
 
 	/// Bans extrinsic if it's stale.
@@ -100,6 +138,18 @@ impl<Hash: hash::Hash + Eq + Clone> PoolRotator<Hash> {
 		let mut banned = self.banned_until.write();
 
 		banned.retain(|_, &mut v| v >= *now);
+		self.banned_senders_until.write().retain(|_, &mut v| v >= *now);
+	}
+}
+
+/// Removes arbitrary bans once there are more than twice the expected number of them.
+fn trim<K: hash::Hash + Eq + Clone>(banned: &mut HashMap<K, Instant>) {
+	if banned.len() > 2 * EXPECTED_SIZE {
+		while banned.len() > EXPECTED_SIZE {
+			if let Some(key) = banned.keys().next().cloned() {
+				banned.remove(&key);
+			}
+		}
 	}
 }
 
@@ -178,6 +228,32 @@ mod tests {
 		assert!(!rotator.is_banned(&hash));
 	}
 
+	#[test]
+	fn should_ban_senders() {
+		// given
+		let rotator = rotator();
+		let sender = vec![1u8; 32];
+		let tag = |sender: &[u8], nonce: u32| [sender, &nonce.to_le_bytes()[..]].concat();
+		assert!(!rotator.is_sender_banned(&[tag(&sender, 0)]));
+
+		// when
+		let now = Instant::now();
+		rotator.ban_senders(&now, std::iter::once(sender.clone()));
+
+		// then
+		assert!(rotator.is_sender_banned(&[tag(&sender, 0)]));
+		assert!(!rotator.is_sender_banned(&[tag(&[2u8; 32], 0)]));
+		assert!(!rotator.is_sender_banned(&[sender]));
+		// The sender whose encoding starts with the banned one is not banned.
+		assert!(!rotator.is_sender_banned(&[tag(&[1u8; 33], 0)]));
+
+		// when
+		rotator.clear_timeouts(&(now + rotator.ban_time + rotator.ban_time));
+
+		// then
+		assert!(!rotator.is_sender_banned(&[tag(&[1u8; 32], 0)]));
+	}
+
 	#[test]
 	fn should_garbage_collect() {
 		// given
@@ -214,4 +290,20 @@ mod tests {
 		assert!(rotator.ban_if_stale(&now, past_block, &tx));
 		assert_eq!(rotator.banned_until.read().len(), EXPECTED_SIZE);
 	}
+
+	#[test]
+	fn should_garbage_collect_senders() {
+		// given
+		let rotator = rotator();
+		let now = Instant::now();
+
+		// when
+		let senders = (0..2 * EXPECTED_SIZE as u32).map(|i| i.to_le_bytes().to_vec());
+		rotator.ban_senders(&now, senders);
+		assert_eq!(rotator.banned_senders_until.read().len(), 2 * EXPECTED_SIZE);
+
+		// then
+		rotator.ban_senders(&now, std::iter::once(vec![0xff; 32]));
+		assert_eq!(rotator.banned_senders_until.read().len(), EXPECTED_SIZE);
+	}
 }
@@ -22,10 +22,10 @@ use std::{
 	sync::Arc,
 };
 
-use crate::LOG_TARGET;
+use crate::{metrics::MetricsLink, PriorityHook, LOG_TARGET};
 use futures::channel::mpsc::{channel, Sender};
 use parking_lot::{Mutex, RwLock};
-use sc_transaction_pool_api::{error, PoolStatus, ReadyTransactions};
+use sc_transaction_pool_api::{error, PoolEventStream, PoolStatus, ReadyTransactions};
 use serde::Serialize;
 use sp_runtime::{
 	generic::BlockId,
@@ -109,13 +109,18 @@ pub struct ValidatedPool<B: ChainApi> {
 	pool: RwLock<base::BasePool<ExtrinsicHash<B>, ExtrinsicFor<B>>>,
 	import_notification_sinks: Mutex<Vec<Sender<ExtrinsicHash<B>>>>,
 	rotator: PoolRotator<ExtrinsicHash<B>>,
+	priority_hook: RwLock<Option<Arc<dyn PriorityHook<B::Block>>>>,
+	metrics: RwLock<MetricsLink>,
 }
 
 impl<B: ChainApi> ValidatedPool<B> {
 	/// Create a new transaction pool.
 	pub fn new(options: Options, is_validator: IsValidator, api: Arc<B>) -> Self {
-		let base_pool = base::BasePool::new(options.reject_future_transactions);
-		let ban_time = options.ban_time;
+		let base_pool = base::BasePool::new(options.reject_future_transactions)
+			.with_replacement_priority_bump(options.replacement_priority_bump)
+			.with_future_limit_per_sender(options.future_limit_per_sender)
+			.with_nonce_len(options.nonce_len);
+		let rotator = PoolRotator::new(options.ban_time).with_nonce_len(options.nonce_len);
 		Self {
 			is_validator,
 			options,
@@ -123,15 +128,67 @@ impl<B: ChainApi> ValidatedPool<B> {
 			api,
 			pool: RwLock::new(base_pool),
 			import_notification_sinks: Default::default(),
-			rotator: PoolRotator::new(ban_time),
+			rotator,
+			priority_hook: Default::default(),
+			metrics: Default::default(),
 		}
 	}
 
+	/// Sets the metrics the pool reports to.
+	pub fn set_metrics(&self, metrics: MetricsLink) {
+		*self.metrics.write() = metrics;
+	}
+
+	/// Sets the hook adjusting the priority of the validated transactions.
+	pub fn set_priority_hook(&self, hook: Arc<dyn PriorityHook<B::Block>>) {
+		*self.priority_hook.write() = Some(hook);
+	}
+
+	/// Adjusts the priority of the validated transaction with the priority hook, if any.
+	pub fn adjust_priority(
+		&self,
+		source: TransactionSource,
+		xt: &ExtrinsicFor<B>,
+		mut validity: ValidTransaction,
+	) -> ValidTransaction {
+		if let Some(hook) = &*self.priority_hook.read() {
+			validity.priority = hook.priority(source, xt, validity.priority);
+		}
+		validity
+	}
+
 	/// Bans given set of hashes.
 	pub fn ban(&self, now: &Instant, hashes: impl IntoIterator<Item = ExtrinsicHash<B>>) {
 		self.rotator.ban(now, hashes)
 	}
 
+	/// Bans the given senders and removes their transactions from the pool.
+	///
+	/// The senders are identified by their encoding, which is followed by the nonce in the tags
+	/// provided by their transactions, see [`Options::nonce_len`].
+	pub fn ban_senders(&self, senders: Vec<Vec<u8>>) -> Vec<TransactionFor<B>> {
+		self.rotator.ban_senders(&Instant::now(), senders);
+
+		let hashes = {
+			let pool = self.pool.read();
+			pool.ready()
+				.filter(|tx| self.rotator.is_sender_banned(&tx.provides))
+				.map(|tx| tx.hash)
+				.chain(
+					pool.futures()
+						.filter(|tx| self.rotator.is_sender_banned(&tx.provides))
+						.map(|tx| tx.hash),
+				)
+				.collect::<Vec<_>>()
+		};
+		self.ban_transactions(&hashes)
+	}
+
+	/// Bans the given transactions and removes them (and dependent transactions) from the pool.
+	pub fn ban_transactions(&self, hashes: &[ExtrinsicHash<B>]) -> Vec<TransactionFor<B>> {
+		self.remove_subtree(hashes, true)
+	}
+
 	/// Returns true if transaction with given hash is currently banned from the pool.
 	pub fn is_banned(&self, hash: &ExtrinsicHash<B>) -> bool {
 		self.rotator.is_banned(hash)
@@ -191,6 +248,10 @@ impl<B: ChainApi> ValidatedPool<B> {
 					return Err(error::Error::Unactionable.into())
 				}
 
+				if self.rotator.is_sender_banned(&tx.provides) {
+					return Err(error::Error::TemporarilyBanned.into())
+				}
+
 				let imported = self.pool.write().import(tx)?;
 
 				if let base::Imported::Ready { ref hash, .. } = imported {
@@ -230,28 +291,45 @@ impl<B: ChainApi> ValidatedPool<B> {
 		let status = self.pool.read().status();
 		let ready_limit = &self.options.ready;
 		let future_limit = &self.options.future;
+		let limits_exceeded = ready_limit.is_exceeded(status.ready, status.ready_bytes) ||
+			future_limit.is_exceeded(status.future, status.future_bytes);
 
 		log::debug!(target: LOG_TARGET, "Pool Status: {:?}", status);
-		if ready_limit.is_exceeded(status.ready, status.ready_bytes) ||
-			future_limit.is_exceeded(status.future, status.future_bytes)
-		{
-			log::debug!(
-				target: LOG_TARGET,
-				"Enforcing limits ({}/{}kB ready, {}/{}kB future",
-				ready_limit.count,
-				ready_limit.total_bytes / 1024,
-				future_limit.count,
-				future_limit.total_bytes / 1024,
-			);
+		if limits_exceeded || self.options.memory_budget.is_some() {
+			if limits_exceeded {
+				log::debug!(
+					target: LOG_TARGET,
+					"Enforcing limits ({}/{}kB ready, {}/{}kB future",
+					ready_limit.count,
+					ready_limit.total_bytes / 1024,
+					future_limit.count,
+					future_limit.total_bytes / 1024,
+				);
+			}
 
 			// clean up the pool
 			let removed = {
 				let mut pool = self.pool.write();
-				let removed = pool
+				let mut removed = pool
 					.enforce_limits(ready_limit, future_limit)
 					.into_iter()
 					.map(|x| x.hash)
 					.collect::<HashSet<_>>();
+				if let Some(budget) = self.options.memory_budget {
+					let evicted = pool.enforce_memory_budget(budget);
+					if !evicted.is_empty() {
+						log::debug!(
+							target: LOG_TARGET,
+							"Enforcing memory budget of {}kB: {} evicted",
+							budget / 1024,
+							evicted.len(),
+						);
+						self.metrics.read().report(|metrics| {
+							metrics.memory_evictions.inc_by(evicted.len() as u64)
+						});
+					}
+					removed.extend(evicted.into_iter().map(|x| x.hash));
+				}
 				// ban all removed transactions
 				self.rotator.ban(&Instant::now(), removed.iter().copied());
 				removed
@@ -578,6 +656,11 @@ impl<B: ChainApi> ValidatedPool<B> {
 		stream
 	}
 
+	/// Return a stream of the events of all the transactions of the pool.
+	pub fn pool_events(&self) -> PoolEventStream<ExtrinsicHash<B>, BlockHash<B>> {
+		self.listener.write().pool_events()
+	}
+
 	/// Invoked when extrinsics are broadcasted.
 	pub fn on_broadcasted(&self, propagated: HashMap<ExtrinsicHash<B>, Vec<String>>) {
 		let mut listener = self.listener.write();
@@ -593,6 +676,12 @@ impl<B: ChainApi> ValidatedPool<B> {
 	/// Note this is not the case for the dependent transactions - those may
 	/// still be valid so we want to be able to re-import them.
 	pub fn remove_invalid(&self, hashes: &[ExtrinsicHash<B>]) -> Vec<TransactionFor<B>> {
+		self.remove_subtree(hashes, false)
+	}
+
+	/// Remove a subtree of transactions from the pool and ban the transactions passed as an
+	/// argument, reporting them as `banned` or as invalid.
+	fn remove_subtree(&self, hashes: &[ExtrinsicHash<B>], banned: bool) -> Vec<TransactionFor<B>> {
 		// early exit in case there is no invalid transactions.
 		if hashes.is_empty() {
 			return vec![]
@@ -609,7 +698,11 @@ impl<B: ChainApi> ValidatedPool<B> {
 
 		let mut listener = self.listener.write();
 		for tx in &invalid {
-			listener.invalid(&tx.hash);
+			if banned && hashes.contains(&tx.hash) {
+				listener.banned(&tx.hash);
+			} else {
+				listener.invalid(&tx.hash);
+			}
 		}
 
 		invalid
@@ -625,6 +718,11 @@ impl<B: ChainApi> ValidatedPool<B> {
 		self.pool.read().futures().map(|tx| (tx.hash, tx.data.clone())).collect()
 	}
 
+	/// Returns the transactions in the future pool.
+	pub fn future_transactions(&self) -> Vec<TransactionFor<B>> {
+		self.pool.read().future_transactions()
+	}
+
 	/// Returns pool status.
 	pub fn status(&self) -> PoolStatus {
 		self.pool.read().status()
@@ -657,7 +755,7 @@ where
 			listener.ready(hash, None);
 			failed.iter().for_each(|f| listener.invalid(f));
 			removed.iter().for_each(|r| listener.dropped(&r.hash, Some(hash)));
-			promoted.iter().for_each(|p| listener.ready(p, None));
+			promoted.iter().for_each(|p| listener.promoted(p));
 		},
 		base::Imported::Future { ref hash } => listener.future(hash),
 	}
@@ -27,6 +27,8 @@ mod enactment_state;
 pub mod error;
 mod graph;
 mod metrics;
+mod persistence;
+mod priority;
 mod revalidation;
 #[cfg(test)]
 mod tests;
@@ -40,25 +42,28 @@ use futures::{
 	prelude::*,
 };
 pub use graph::{
-	base_pool::Limit as PoolLimit, ChainApi, Options, Pool, Transaction, ValidatedTransaction,
+	base_pool::Limit as PoolLimit, ChainApi, Options, Pool, SelectiveRevalidation, Transaction,
+	ValidatedTransaction,
 };
 use parking_lot::Mutex;
+pub use priority::PriorityHook;
 use std::{
 	collections::{HashMap, HashSet},
 	pin::Pin,
 	sync::Arc,
 };
 
-use graph::{ExtrinsicHash, IsValidator};
+use graph::{base_pool::tag_sender, ExtrinsicHash, IsValidator};
 use sc_transaction_pool_api::{
-	error::Error as TxPoolError, ChainEvent, ImportNotificationStream, MaintainedTransactionPool,
-	PoolFuture, PoolStatus, ReadyTransactions, TransactionFor, TransactionPool, TransactionSource,
-	TransactionStatusStreamFor, TxHash,
+	error::Error as TxPoolError, BlockHash, ChainEvent, ImportNotificationStream,
+	MaintainedTransactionPool, PoolEventStream, PoolFuture, PoolStatus, ReadyTransactions,
+	TransactionFor, TransactionPool, TransactionSource, TransactionStatusStreamFor, TxHash,
 };
 use sp_core::traits::SpawnEssentialNamed;
 use sp_runtime::{
-	generic::BlockId,
+	generic::{BlockId, DigestItem},
 	traits::{AtLeast32Bit, Block as BlockT, Extrinsic, Header as HeaderT, NumberFor, Zero},
+	transaction_validity::TransactionTag,
 };
 use std::time::Instant;
 
@@ -90,6 +95,7 @@ where
 	api: Arc<PoolApi>,
 	revalidation_strategy: Arc<Mutex<RevalidationStrategy<NumberFor<Block>>>>,
 	revalidation_queue: Arc<revalidation::RevalidationQueue<PoolApi>>,
+	selective_revalidation: Option<graph::SelectiveRevalidation>,
 	ready_poll: Arc<Mutex<ReadyPoll<ReadyIteratorFor<PoolApi>, Block>>>,
 	metrics: PrometheusMetrics,
 	enactment_state: Arc<Mutex<EnactmentState<Block>>>,
@@ -173,7 +179,8 @@ where
 				api: pool_api,
 				pool,
 				revalidation_queue: Arc::new(revalidation_queue),
-				revalidation_strategy: Arc::new(Mutex::new(RevalidationStrategy::Always)),
+				revalidation_strategy: Arc::new(Mutex::new(RevalidationStrategy::Always(None))),
+				selective_revalidation: None,
 				ready_poll: Default::default(),
 				metrics: Default::default(),
 				enactment_state: Arc::new(Mutex::new(EnactmentState::new(
@@ -198,7 +205,11 @@ where
 		best_block_hash: Block::Hash,
 		finalized_hash: Block::Hash,
 	) -> Self {
+		let persistence_path = options.persistence_path.clone();
+		let selective_revalidation = options.selective_revalidation.clone();
 		let pool = Arc::new(graph::Pool::new(options, is_validator, pool_api.clone()));
+		let metrics = PrometheusMetrics::new(prometheus);
+		pool.validated_pool().set_metrics(metrics.clone());
 		let (revalidation_queue, background_task) = match revalidation_type {
 			RevalidationType::Light =>
 				(revalidation::RevalidationQueue::new(pool_api.clone(), pool.clone()), None),
@@ -213,6 +224,15 @@ where
 			spawner.spawn_essential("txpool-background", Some("transaction-pool"), background_task);
 		}
 
+		if let Some(path) = persistence_path {
+			// The pool is written to the disk synchronously, on a dedicated blocking thread.
+			spawner.spawn_essential_blocking(
+				"txpool-persistence",
+				Some("transaction-pool"),
+				persistence::persistence_task(pool.clone(), path, best_block_hash).boxed(),
+			);
+		}
+
 		Self {
 			api: pool_api,
 			pool,
@@ -220,10 +240,11 @@ where
 			revalidation_strategy: Arc::new(Mutex::new(match revalidation_type {
 				RevalidationType::Light =>
 					RevalidationStrategy::Light(RevalidationStatus::NotScheduled),
-				RevalidationType::Full => RevalidationStrategy::Always,
+				RevalidationType::Full => RevalidationStrategy::Always(None),
 			})),
+			selective_revalidation,
 			ready_poll: Arc::new(Mutex::new(ReadyPoll::new(best_block_number))),
-			metrics: PrometheusMetrics::new(prometheus),
+			metrics,
 			enactment_state: Arc::new(Mutex::new(EnactmentState::new(
 				best_block_hash,
 				finalized_hash,
@@ -240,6 +261,12 @@ where
 	pub fn api(&self) -> &PoolApi {
 		&self.api
 	}
+
+	/// Sets the hook adjusting the priority of the transactions validated by the runtime,
+	/// before they enter the pool.
+	pub fn set_priority_hook(&self, hook: Arc<dyn PriorityHook<Block>>) {
+		self.pool.validated_pool().set_priority_hook(hook)
+	}
 }
 
 impl<PoolApi, Block> TransactionPool for BasicPool<PoolApi, Block>
@@ -315,6 +342,10 @@ where
 		self.pool.validated_pool().import_notification_stream()
 	}
 
+	fn pool_events(&self) -> PoolEventStream<TxHash<Self>, BlockHash<Self>> {
+		self.pool.validated_pool().pool_events()
+	}
+
 	fn hash_of(&self, xt: &TransactionFor<Self>) -> TxHash<Self> {
 		self.pool.hash_of(xt)
 	}
@@ -327,6 +358,18 @@ where
 		self.pool.validated_pool().ready_by_hash(hash)
 	}
 
+	fn futures(&self) -> Vec<Arc<Self::InPoolTransaction>> {
+		self.pool.validated_pool().future_transactions()
+	}
+
+	fn ban_transactions(&self, hashes: &[TxHash<Self>]) -> Vec<Arc<Self::InPoolTransaction>> {
+		self.pool.validated_pool().ban_transactions(hashes)
+	}
+
+	fn ban_senders(&self, senders: Vec<Vec<u8>>) -> Vec<Arc<Self::InPoolTransaction>> {
+		self.pool.validated_pool().ban_senders(senders)
+	}
+
 	fn ready_at(&self, at: NumberFor<Self::Block>) -> PolledIterator<PoolApi> {
 		let status = self.status();
 		// If there are no transactions in the pool, it is fine to return early.
@@ -439,6 +482,10 @@ where
 				})
 			})?;
 
+		let validity =
+			self.pool
+				.validated_pool()
+				.adjust_priority(TransactionSource::Local, &xt, validity);
 		let (hash, bytes) = self.pool.validated_pool().api().hash_and_length(&xt);
 		let block_number = self
 			.api
@@ -469,12 +516,17 @@ enum RevalidationStatus<N> {
 }
 
 enum RevalidationStrategy<N> {
-	Always,
+	/// Revalidate the transactions at every block, all of them the last time at the given
+	/// block, see [`graph::SelectiveRevalidation`].
+	Always(Option<N>),
 	Light(RevalidationStatus<N>),
 }
 
 struct RevalidationAction {
 	revalidate: bool,
+	/// Whether all the transactions are revalidated, rather than the ones affected by the
+	/// enacted blocks.
+	revalidate_all: bool,
 	resubmit: bool,
 }
 
@@ -490,17 +542,24 @@ impl<N: Clone + Copy + AtLeast32Bit> RevalidationStrategy<N> {
 		block: N,
 		revalidate_time_period: Option<std::time::Duration>,
 		revalidate_block_period: Option<N>,
+		full_revalidation_period: Option<N>,
 	) -> RevalidationAction {
 		match self {
-			Self::Light(status) => RevalidationAction {
-				revalidate: status.next_required(
-					block,
-					revalidate_time_period,
-					revalidate_block_period,
-				),
-				resubmit: false,
+			Self::Light(status) => {
+				let revalidate =
+					status.next_required(block, revalidate_time_period, revalidate_block_period);
+				RevalidationAction { revalidate, revalidate_all: revalidate, resubmit: false }
+			},
+			Self::Always(last_full_revalidation) => {
+				let revalidate_all = match (*last_full_revalidation, full_revalidation_period) {
+					(Some(last), Some(period)) => block < last || block >= last + period,
+					_ => true,
+				};
+				if revalidate_all {
+					*last_full_revalidation = Some(block);
+				}
+				RevalidationAction { revalidate: true, revalidate_all, resubmit: true }
 			},
-			Self::Always => RevalidationAction { revalidate: true, resubmit: true },
 		}
 	}
 }
@@ -540,12 +599,36 @@ impl<N: Clone + Copy + AtLeast32Bit> RevalidationStatus<N> {
 	}
 }
 
+/// Returns `true` if the validity of a transaction with the given tags could have been
+/// changed by the transactions of the enacted blocks, i.e. if one of its tags has the same
+/// sender as one of the tags provided by the enacted transactions.
+fn is_affected<'a>(
+	mut tags: impl Iterator<Item = &'a TransactionTag>,
+	enacted_senders: &HashSet<&[u8]>,
+	nonce_len: usize,
+) -> bool {
+	tags.any(|tag| {
+		tag_sender(tag, nonce_len).map_or(false, |sender| enacted_senders.contains(sender))
+	})
+}
+
+/// The transactions of a block, pruned from the pool.
+struct PrunedBlock<Api: graph::ChainApi> {
+	/// The hashes of the transactions.
+	hashes: Vec<ExtrinsicHash<Api>>,
+	/// The tags provided by the transactions.
+	tags: Vec<TransactionTag>,
+	/// Whether the block may have updated the runtime, which may change the validity of any
+	/// transaction.
+	runtime_updated: bool,
+}
+
 /// Prune the known txs for the given block.
 async fn prune_known_txs_for_block<Block: BlockT, Api: graph::ChainApi<Block = Block>>(
 	block_hash: Block::Hash,
 	api: &Api,
 	pool: &graph::Pool<Api>,
-) -> Vec<ExtrinsicHash<Api>> {
+) -> PrunedBlock<Api> {
 	let extrinsics = api
 		.block_body(block_hash)
 		.await
@@ -563,22 +646,29 @@ async fn prune_known_txs_for_block<Block: BlockT, Api: graph::ChainApi<Block = B
 		Ok(Some(h)) => h,
 		Ok(None) => {
 			log::debug!(target: LOG_TARGET, "Could not find header for {:?}.", block_hash);
-			return hashes
+			return PrunedBlock { hashes, tags: Vec::new(), runtime_updated: true }
 		},
 		Err(e) => {
 			log::debug!(target: LOG_TARGET, "Error retrieving header for {:?}: {}", block_hash, e);
-			return hashes
+			return PrunedBlock { hashes, tags: Vec::new(), runtime_updated: true }
 		},
 	};
-
-	if let Err(e) = pool
+	// The runtime code, the heap pages or the runtime version change along with this digest.
+	let runtime_updated = header
+		.digest()
+		.logs()
+		.iter()
+		.any(|log| matches!(log, DigestItem::RuntimeEnvironmentUpdated));
+
+	let tags = pool
 		.prune(&BlockId::Hash(block_hash), &BlockId::hash(*header.parent_hash()), &extrinsics)
 		.await
-	{
-		log::error!("Cannot prune known in the pool: {}", e);
-	}
+		.unwrap_or_else(|e| {
+			log::error!("Cannot prune known in the pool: {}", e);
+			Vec::new()
+		});
 
-	hashes
+	PrunedBlock { hashes, tags, runtime_updated }
 }
 
 impl<PoolApi, Block> BasicPool<PoolApi, Block>
@@ -610,11 +700,18 @@ where
 			*block_number,
 			Some(std::time::Duration::from_secs(60)),
 			Some(20u32.into()),
+			self.selective_revalidation
+				.as_ref()
+				.map(|selective| selective.full_revalidation_period.into()),
 		);
 
 		// We keep track of everything we prune so that later we won't add
 		// transactions with those hashes from the retracted blocks.
 		let mut pruned_log = HashSet::<ExtrinsicHash<PoolApi>>::new();
+		// The tags provided by the enacted transactions, telling which transactions of the
+		// pool are affected by the enacted blocks.
+		let mut enacted_tags = Vec::new();
+		let mut runtime_updated = false;
 
 		// If there is a tree route, we use this to prune known tx based on the enacted
 		// blocks. Before pruning enacted transactions, we inform the listeners about
@@ -634,8 +731,10 @@ where
 		)
 		.await
 		.into_iter()
-		.for_each(|enacted_log| {
-			pruned_log.extend(enacted_log);
+		.for_each(|pruned| {
+			pruned_log.extend(pruned.hashes);
+			enacted_tags.extend(pruned.tags);
+			runtime_updated |= pruned.runtime_updated;
 		});
 
 		self.metrics
@@ -710,7 +809,37 @@ where
 			.trigger(*block_number, move || Box::new(extra_pool.validated_pool().ready()));
 
 		if next_action.revalidate {
-			let hashes = pool.validated_pool().ready().map(|tx| tx.hash).collect();
+			// The state of the retracted blocks is reverted and the runtime updates change the
+			// validation, which may affect any transaction.
+			let nonce_len =
+				self.selective_revalidation.as_ref().map(|selective| selective.nonce_len);
+			let revalidate_all =
+				next_action.revalidate_all || !tree_route.retracted().is_empty() || runtime_updated;
+			let enacted_senders: HashSet<_> = enacted_tags
+				.iter()
+				.filter_map(|tag| nonce_len.and_then(|nonce_len| tag_sender(tag, nonce_len)))
+				.collect();
+			let hashes = pool
+				.validated_pool()
+				.ready()
+				.filter(|tx| {
+					revalidate_all ||
+						nonce_len.map_or(true, |nonce_len| {
+							is_affected(
+								tx.provides.iter().chain(&tx.requires),
+								&enacted_senders,
+								nonce_len,
+							)
+						})
+				})
+				.map(|tx| tx.hash)
+				.collect::<Vec<_>>();
+			log::debug!(
+				target: LOG_TARGET,
+				"Revalidating {} transactions (all: {})",
+				hashes.len(),
+				revalidate_all,
+			);
 			self.revalidation_queue.revalidate_later(*block_number, hashes).await;
 
 			self.revalidation_strategy.lock().clear();
@@ -49,6 +49,7 @@ pub struct Metrics {
 	pub validations_invalid: Counter<U64>,
 	pub block_transactions_pruned: Counter<U64>,
 	pub block_transactions_resubmitted: Counter<U64>,
+	pub memory_evictions: Counter<U64>,
 }
 
 impl Metrics {
@@ -82,6 +83,13 @@ impl Metrics {
 				)?,
 				registry,
 			)?,
+			memory_evictions: register(
+				Counter::new(
+					"substrate_sub_txpool_memory_evictions",
+					"Total number of transactions evicted to keep the pool within its memory budget",
+				)?,
+				registry,
+			)?,
 		})
 	}
 }
@@ -0,0 +1,187 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! On-disk persistence of the pool.
+//!
+//! The ready and the future transactions of the pool are periodically written to a file, as
+//! a SCALE-encoded list of extrinsics along with their source. When the node restarts, the
+//! transactions of the file are resubmitted to the pool from their original source and
+//! validated again at the best block, such that the pending transactions are not lost.
+//!
+//! The file is read and written synchronously, the task is expected to run on a blocking
+//! thread.
+
+use std::{
+	collections::HashSet,
+	path::{Path, PathBuf},
+	sync::Arc,
+	time::Duration,
+};
+
+use crate::{
+	graph::{BlockHash, ChainApi, ExtrinsicFor, ExtrinsicHash, Pool},
+	LOG_TARGET,
+};
+use codec::{Decode, Encode};
+use sp_runtime::{generic::BlockId, transaction_validity::TransactionSource};
+
+/// The interval between two writes of the pool to the disk.
+const PERSISTENCE_INTERVAL: Duration = Duration::from_secs(6);
+
+/// Resubmit the transactions persisted at `path` to the pool, then periodically write the
+/// transactions of the pool to `path`.
+///
+/// The pool is only written when its transactions changed since the last write.
+pub async fn persistence_task<Api: ChainApi>(
+	pool: Arc<Pool<Api>>,
+	path: PathBuf,
+	at: BlockHash<Api>,
+) {
+	let mut persisted = load::<(TransactionSource, ExtrinsicFor<Api>)>(&path);
+	let count = persisted.len();
+	let mut restored = 0;
+	for source in
+		[TransactionSource::InBlock, TransactionSource::Local, TransactionSource::External]
+	{
+		let (transactions, others): (Vec<_>, Vec<_>) =
+			persisted.into_iter().partition(|(tx_source, _)| *tx_source == source);
+		persisted = others;
+		if transactions.is_empty() {
+			continue
+		}
+
+		let transactions = transactions.into_iter().map(|(_, xt)| xt).collect();
+		match pool.submit_at(&BlockId::Hash(at), source, transactions).await {
+			Ok(results) => restored += results.iter().filter(|result| result.is_ok()).count(),
+			Err(e) => log::warn!(
+				target: LOG_TARGET,
+				"Failed to restore the persisted {:?} transactions: {:?}",
+				source,
+				e,
+			),
+		}
+	}
+	if count > 0 {
+		log::info!(
+			target: LOG_TARGET,
+			"Restored {} of the {} persisted transactions",
+			restored,
+			count,
+		);
+	}
+
+	let mut persisted = HashSet::new();
+	loop {
+		futures_timer::Delay::new(PERSISTENCE_INTERVAL).await;
+
+		let (hashes, transactions) = pool_transactions(&pool);
+		if hashes == persisted {
+			continue
+		}
+
+		match save(&path, &transactions) {
+			Ok(()) => persisted = hashes,
+			Err(e) => log::warn!(
+				target: LOG_TARGET,
+				"Failed to persist the transactions to {}: {}",
+				path.display(),
+				e,
+			),
+		}
+	}
+}
+
+/// Returns the hashes, the sources and the extrinsics of the ready and the future transactions
+/// of the pool.
+fn pool_transactions<Api: ChainApi>(
+	pool: &Pool<Api>,
+) -> (HashSet<ExtrinsicHash<Api>>, Vec<(TransactionSource, ExtrinsicFor<Api>)>) {
+	let validated_pool = pool.validated_pool();
+	validated_pool
+		.ready()
+		.chain(validated_pool.future_transactions())
+		.map(|tx| (tx.hash, (tx.source, tx.data.clone())))
+		.unzip()
+}
+
+/// Read the extrinsics persisted at `path`.
+///
+/// A missing or corrupted file is treated as an empty one.
+fn load<Ex: Decode>(path: &Path) -> Vec<Ex> {
+	let bytes = match std::fs::read(path) {
+		Ok(bytes) => bytes,
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+		Err(e) => {
+			log::warn!(target: LOG_TARGET, "Failed to read {}: {}", path.display(), e);
+			return Vec::new()
+		},
+	};
+
+	Vec::<Ex>::decode(&mut &bytes[..]).unwrap_or_else(|e| {
+		log::warn!(target: LOG_TARGET, "Failed to decode the persisted transactions: {}", e);
+		Vec::new()
+	})
+}
+
+/// Write the extrinsics to `path`.
+///
+/// The extrinsics are first written to a temporary file which then replaces the previous
+/// file, such that an interrupted write does not corrupt it.
+fn save<Ex: Encode>(path: &Path, transactions: &[Ex]) -> std::io::Result<()> {
+	if let Some(parent) = path.parent() {
+		std::fs::create_dir_all(parent)?;
+	}
+	let temporary = path.with_extension("tmp");
+	std::fs::write(&temporary, transactions.encode())?;
+	std::fs::rename(&temporary, path)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn save_and_load_round_trip() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("txpool").join("transactions");
+
+		assert_eq!(load::<Vec<u8>>(&path), Vec::<Vec<u8>>::new());
+
+		let transactions = vec![vec![1u8, 2, 3], vec![4u8]];
+		save(&path, &transactions).unwrap();
+		assert_eq!(load::<Vec<u8>>(&path), transactions);
+		assert!(!path.with_extension("tmp").exists());
+
+		std::fs::write(&path, [0xffu8]).unwrap();
+		assert_eq!(load::<Vec<u8>>(&path), Vec::<Vec<u8>>::new());
+	}
+
+	#[test]
+	fn sources_are_persisted() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("transactions");
+
+		let transactions = vec![
+			(TransactionSource::Local, vec![1u8]),
+			(TransactionSource::External, vec![2u8]),
+			(TransactionSource::InBlock, vec![3u8]),
+		];
+		save(&path, &transactions).unwrap();
+		assert_eq!(load::<(TransactionSource, Vec<u8>)>(&path), transactions);
+	}
+}
@@ -0,0 +1,39 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Adjustment of the priority of the transactions.
+
+use sc_transaction_pool_api::TransactionSource;
+use sp_runtime::{traits::Block as BlockT, transaction_validity::TransactionPriority};
+
+/// Adjusts the priority of the transactions validated by the runtime, before they enter the
+/// pool.
+///
+/// The hook is called every time a transaction is validated, including its revalidations,
+/// such that the adjusted priority is kept. It may e.g. favor the transactions of an allowlist
+/// of senders, or implement a local fee market.
+pub trait PriorityHook<Block: BlockT>: Send + Sync {
+	/// Returns the priority of the transaction, given the priority returned by the
+	/// `validate_transaction` runtime api.
+	fn priority(
+		&self,
+		source: TransactionSource,
+		xt: &Block::Extrinsic,
+		priority: TransactionPriority,
+	) -> TransactionPriority;
+}
@@ -104,6 +104,8 @@ async fn batch_revalidate<Api: ChainApi>(
 				);
 			},
 			Ok(Ok(validity)) => {
+				let validity =
+					pool.validated_pool().adjust_priority(ext.source, &ext.data, validity);
 				revalidated.insert(
 					ext_hash,
 					ValidatedTransaction::valid_at(
@@ -33,7 +33,7 @@ use sc_transaction_pool_api::{
 use sp_blockchain::HeaderBackend;
 use sp_consensus::BlockOrigin;
 use sp_runtime::{
-	generic::BlockId,
+	generic::{BlockId, Digest, DigestItem},
 	traits::Block as _,
 	transaction_validity::{TransactionSource, ValidTransaction},
 };
@@ -60,6 +60,31 @@ fn maintained_pool() -> (BasicPool<TestApi, Block>, Arc<TestApi>, futures::execu
 	(pool, api, thread_pool)
 }
 
+fn selective_revalidation_pool() -> (BasicPool<TestApi, Block>, Arc<TestApi>) {
+	let api = Arc::new(TestApi::with_alice_nonce(209));
+	let genesis_hash = api.chain().read().block_by_number[&0][0].0.header.hash();
+	let options = Options {
+		selective_revalidation: Some(SelectiveRevalidation {
+			full_revalidation_period: 20,
+			nonce_len: 4,
+		}),
+		..Default::default()
+	};
+
+	let pool = BasicPool::with_revalidation_type(
+		options,
+		true.into(),
+		api.clone(),
+		None,
+		RevalidationType::Full,
+		sp_core::testing::TaskExecutor::new(),
+		0,
+		genesis_hash,
+		genesis_hash,
+	);
+	(pool, api)
+}
+
 fn create_basic_pool_with_genesis(
 	test_api: Arc<TestApi>,
 ) -> (BasicPool<TestApi, Block>, Pin<Box<dyn Future<Output = ()> + Send>>) {
@@ -305,6 +330,57 @@ fn should_revalidate_during_maintenance() {
 	);
 }
 
+#[test]
+fn should_only_revalidate_affected_transactions_between_full_revalidations() {
+	let xt = uxt(Alice, 209);
+
+	let (pool, api) = selective_revalidation_pool();
+	block_on(pool.submit_one(&BlockId::number(0), SOURCE, xt.clone())).expect("1. Imported");
+
+	// the first revalidation covers all the transactions
+	let header = api.push_block(1, vec![], true);
+	block_on(pool.maintain(block_event(header)));
+	assert_eq!(pool.status().ready, 1);
+
+	// the transaction is not affected by the following blocks
+	api.add_invalid(&xt);
+	for number in 2..21 {
+		let header = api.push_block(number, vec![], true);
+		block_on(pool.maintain(block_event(header)));
+		assert_eq!(pool.status().ready, 1);
+	}
+
+	// until the next revalidation of all the transactions
+	let header = api.push_block(21, vec![], true);
+	block_on(pool.maintain(block_event(header)));
+	assert_eq!(pool.status().ready, 0);
+}
+
+#[test]
+fn should_revalidate_all_transactions_after_runtime_update() {
+	let xt = uxt(Alice, 209);
+
+	let (pool, api) = selective_revalidation_pool();
+	block_on(pool.submit_one(&BlockId::number(0), SOURCE, xt.clone())).expect("1. Imported");
+
+	let header = api.push_block(1, vec![], true);
+	block_on(pool.maintain(block_event(header.clone())));
+	assert_eq!(pool.status().ready, 1);
+
+	// the block updating the runtime is followed by the revalidation of all the transactions
+	api.add_invalid(&xt);
+	let header = Header {
+		number: 2,
+		digest: Digest { logs: vec![DigestItem::RuntimeEnvironmentUpdated] },
+		extrinsics_root: Hash::random(),
+		parent_hash: header.hash(),
+		state_root: Default::default(),
+	};
+	api.add_block(Block::new(header.clone(), vec![]), true);
+	block_on(pool.maintain(block_event(header)));
+	assert_eq!(pool.status().ready, 0);
+}
+
 #[test]
 fn should_resubmit_from_retracted_during_maintenance() {
 	let xt = uxt(Alice, 209);
@@ -175,3 +175,45 @@ where
 		})
 	}
 }
+
+/// Computes the fee information of the extrinsics watched with `author_submitAndWatchExtrinsic`
+/// with the [`TransactionPaymentRuntimeApi`].
+pub struct ExtrinsicFeeProvider<C, Block, Balance> {
+	/// Shared reference to the client.
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<fn() -> (Block, Balance)>,
+}
+
+impl<C, Block, Balance> ExtrinsicFeeProvider<C, Block, Balance> {
+	/// Creates a new fee provider.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+impl<C, Block, Balance> sc_rpc_api::author::ExtrinsicFeeProvider<Block>
+	for ExtrinsicFeeProvider<C, Block, Balance>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + Send + Sync + 'static,
+	C::Api: TransactionPaymentRuntimeApi<Block, Balance>,
+	Balance: Codec + MaybeDisplay + Copy + TryInto<NumberOrHex> + Send + Sync + 'static,
+{
+	fn fee_info(
+		&self,
+		at: Block::Hash,
+		extrinsic: &Block::Extrinsic,
+		len: u32,
+	) -> Result<sc_rpc_api::author::ExtrinsicFeeInfo, String> {
+		let info = self
+			.client
+			.runtime_api()
+			.query_info(at, extrinsic.clone(), len)
+			.map_err(|e| e.to_string())?;
+		let partial_fee = info.partial_fee.try_into().map_err(|_| {
+			format!("{} doesn't fit in NumberOrHex representation", info.partial_fee)
+		})?;
+
+		Ok(sc_rpc_api::author::ExtrinsicFeeInfo { partial_fee, weight: info.weight })
+	}
+}
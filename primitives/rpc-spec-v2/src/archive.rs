@@ -0,0 +1,129 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The parameters and results of the archive methods as json compatible objects.
+
+use crate::chain_head::{StorageQueryType, StorageResult};
+use serde::{Deserialize, Serialize};
+use sp_std::{alloc::string::String, prelude::*};
+
+/// The storage item of the `archive_unstable_storage` method.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaginatedStorageQuery<Key> {
+	/// The provided key.
+	pub key: Key,
+	/// The type of the storage query.
+	#[serde(flatten)]
+	pub query_type: StorageQueryType,
+	/// The descendants queries report the keys that follow this key.
+	#[serde(skip_serializing_if = "Option::is_none", default)]
+	pub pagination_start_key: Option<Key>,
+}
+
+/// The result of the `archive_unstable_storage` method.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveStorageResult {
+	/// The results of the queried items.
+	pub result: Vec<StorageResult>,
+	/// The number of queried items that were not processed.
+	pub discarded_items: usize,
+	/// The token resuming the descendants query truncated by the limit of the server.
+	///
+	/// The scan is resumed by `archive_unstable_storageContinue`, while the items that
+	/// follow the truncated query are discarded.
+	#[serde(skip_serializing_if = "Option::is_none", default)]
+	pub continuation_token: Option<String>,
+}
+
+/// The result of the `archive_unstable_call` method.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MethodResult {
+	/// True if the runtime call succeeded.
+	pub success: bool,
+	/// The hex-encoded output of the successful runtime call.
+	#[serde(skip_serializing_if = "Option::is_none", default)]
+	pub value: Option<String>,
+	/// The reason of the failed runtime call.
+	#[serde(skip_serializing_if = "Option::is_none", default)]
+	pub error: Option<String>,
+}
+
+impl MethodResult {
+	/// The result of a successful runtime call.
+	pub fn ok(value: impl Into<String>) -> Self {
+		MethodResult { success: true, value: Some(value.into()), error: None }
+	}
+
+	/// The result of a failed runtime call.
+	pub fn err(error: impl Into<String>) -> Self {
+		MethodResult { success: false, value: None, error: Some(error.into()) }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::chain_head::StorageResultType;
+
+	#[test]
+	fn paginated_storage_query() {
+		let query = PaginatedStorageQuery {
+			key: "0x1",
+			query_type: StorageQueryType::DescendantsValues,
+			pagination_start_key: Some("0x12"),
+		};
+		let ser = serde_json::to_string(&query).unwrap();
+		let exp = r#"{"key":"0x1","type":"descendantsValues","paginationStartKey":"0x12"}"#;
+		assert_eq!(ser, exp);
+
+		// The pagination start key is optional.
+		let query_dec: PaginatedStorageQuery<String> =
+			serde_json::from_str(r#"{"key":"0x1","type":"value"}"#).unwrap();
+		assert_eq!(query_dec.query_type, StorageQueryType::Value);
+		assert_eq!(query_dec.pagination_start_key, None);
+	}
+
+	#[test]
+	fn archive_storage_result() {
+		let result = ArchiveStorageResult {
+			result: vec![StorageResult {
+				key: "0x1".into(),
+				result: StorageResultType::Value("0x2".into()),
+			}],
+			discarded_items: 1,
+			continuation_token: Some("token".into()),
+		};
+		let ser = serde_json::to_string(&result).unwrap();
+		let exp = r#"{"result":[{"key":"0x1","value":"0x2"}],"discardedItems":1,"continuationToken":"token"}"#;
+		assert_eq!(ser, exp);
+
+		let result_dec: ArchiveStorageResult = serde_json::from_str(exp).unwrap();
+		assert_eq!(result_dec, result);
+	}
+
+	#[test]
+	fn method_result() {
+		let ser = serde_json::to_string(&MethodResult::ok("0x1")).unwrap();
+		assert_eq!(ser, r#"{"success":true,"value":"0x1"}"#);
+
+		let ser = serde_json::to_string(&MethodResult::err("Execution failed")).unwrap();
+		assert_eq!(ser, r#"{"success":false,"error":"Execution failed"}"#);
+	}
+}
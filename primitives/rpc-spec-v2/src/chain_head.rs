@@ -0,0 +1,1235 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The chain head's event returned as json compatible object.
+
+use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
+use sp_std::{alloc::string::String, prelude::*};
+use sp_version::RuntimeVersion;
+
+/// The operation could not be processed due to an error.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorEvent {
+	/// Reason of the error.
+	pub error: String,
+}
+
+/// The runtime specification of the current block.
+///
+/// This event is generated for:
+///   - the first announced block by the follow subscription
+///   - blocks that suffered a change in runtime compared with their parents
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeVersionEvent {
+	/// The runtime version.
+	pub spec: RuntimeVersion,
+}
+
+/// The runtime event generated if the `follow` subscription
+/// has set the `with_runtime` flag.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "type")]
+pub enum RuntimeEvent {
+	/// The runtime version of this block.
+	Valid(RuntimeVersionEvent),
+	/// The runtime could not be obtained due to an error.
+	Invalid(ErrorEvent),
+}
+
+/// Contain information about the latest finalized block and its finalized ancestors.
+///
+/// # Note
+///
+/// This is the first event generated by the `follow` subscription
+/// and is submitted only once.
+///
+/// If the `with_runtime` flag is set, then this event contains
+/// the `RuntimeEvent`, otherwise the `RuntimeEvent` is not present.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Initialized<Hash> {
+	/// The hashes of the finalized blocks, ordered from the oldest to the latest finalized
+	/// block.
+	///
+	/// The blocks preceding the latest finalized block are its finalized ancestors.
+	pub finalized_block_hashes: Vec<Hash>,
+	/// The runtime version of the latest finalized block.
+	///
+	/// # Note
+	///
+	/// This is present only if the `with_runtime` flag is set for
+	/// the `follow` subscription.
+	pub finalized_block_runtime: Option<RuntimeEvent>,
+	/// Keep track if the `finalized_block_runtime` should be serialized.
+	#[serde(default)]
+	pub with_runtime: bool,
+	/// The token resuming the subscription after the client disconnects.
+	///
+	/// # Note
+	///
+	/// This is present only if the server keeps the pinned blocks of the
+	/// disconnected subscriptions.
+	#[serde(default)]
+	pub resumption_token: Option<String>,
+}
+
+impl<Hash: Serialize> Serialize for Initialized<Hash> {
+	/// Custom serialize implementation to include the `RuntimeEvent` depending
+	/// on the internal `with_runtime` flag, and the resumption token if present.
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		let len = 1 + usize::from(self.with_runtime) + usize::from(self.resumption_token.is_some());
+
+		let mut state = serializer.serialize_struct("Initialized", len)?;
+		state.serialize_field("finalizedBlockHashes", &self.finalized_block_hashes)?;
+		if self.with_runtime {
+			state.serialize_field("finalizedBlockRuntime", &self.finalized_block_runtime)?;
+		}
+		if let Some(resumption_token) = &self.resumption_token {
+			state.serialize_field("resumptionToken", resumption_token)?;
+		}
+		state.end()
+	}
+}
+
+/// Indicate a new non-finalized block.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewBlock<Hash> {
+	/// The hash of the new block.
+	pub block_hash: Hash,
+	/// The parent hash of the new block.
+	pub parent_block_hash: Hash,
+	/// The runtime version of the new block.
+	///
+	/// # Note
+	///
+	/// This is present only if the `with_runtime` flag is set for
+	/// the `follow` subscription.
+	pub new_runtime: Option<RuntimeEvent>,
+	/// Keep track if the `finalized_block_runtime` should be serialized.
+	#[serde(default)]
+	pub with_runtime: bool,
+	/// The SCALE-encoded header of the new block.
+	///
+	/// # Note
+	///
+	/// This is present only if the `with_header` flag is set for
+	/// the `follow` subscription.
+	#[serde(default)]
+	pub header: Option<String>,
+	/// The number of extrinsics in the body of the new block.
+	///
+	/// # Note
+	///
+	/// This is present only if the `with_header` flag is set for
+	/// the `follow` subscription.
+	#[serde(default)]
+	pub extrinsics_count: Option<u32>,
+}
+
+impl<Hash: Serialize> Serialize for NewBlock<Hash> {
+	/// Custom serialize implementation to include the `RuntimeEvent` depending
+	/// on the internal `with_runtime` flag, and the block details if present.
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		let len = 2 +
+			usize::from(self.with_runtime) +
+			usize::from(self.header.is_some()) +
+			usize::from(self.extrinsics_count.is_some());
+
+		let mut state = serializer.serialize_struct("NewBlock", len)?;
+		state.serialize_field("blockHash", &self.block_hash)?;
+		state.serialize_field("parentBlockHash", &self.parent_block_hash)?;
+		if self.with_runtime {
+			state.serialize_field("newRuntime", &self.new_runtime)?;
+		}
+		if let Some(header) = &self.header {
+			state.serialize_field("header", header)?;
+		}
+		if let Some(extrinsics_count) = &self.extrinsics_count {
+			state.serialize_field("extrinsicsCount", extrinsics_count)?;
+		}
+		state.end()
+	}
+}
+
+/// Indicate the block hash of the new best block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BestBlockChanged<Hash> {
+	/// The block hash of the new best block.
+	pub best_block_hash: Hash,
+}
+
+/// Indicate the finalized and pruned block hashes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Finalized<Hash> {
+	/// Block hashes that are finalized.
+	pub finalized_block_hashes: Vec<Hash>,
+	/// Block hashes that are pruned (removed).
+	pub pruned_block_hashes: Vec<Hash>,
+}
+
+/// Summarize the blocks finalized while the events were paused during a major sync.
+///
+/// The reported blocks that are ancestors of the finalized block are finalized.
+/// The non-finalized descendants of the finalized block are reported by the
+/// `NewBlock` events that follow.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatchUp<Hash> {
+	/// The hash of the latest finalized block.
+	pub finalized_block_hash: Hash,
+	/// The runtime version of the finalized block.
+	///
+	/// This is present only if the `with_runtime` flag is set for the `follow` subscription.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	#[serde(default)]
+	pub finalized_block_runtime: Option<RuntimeEvent>,
+	/// The reported block hashes that are pruned (removed).
+	pub pruned_block_hashes: Vec<Hash>,
+}
+
+/// Indicate the operation id of the event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationId {
+	/// The operation id of the event.
+	pub operation_id: String,
+}
+
+/// The response of the `chainHead_body` method.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationBodyDone {
+	/// The operation id of the event.
+	pub operation_id: String,
+	/// Array of hexadecimal-encoded scale-encoded extrinsics found in the block.
+	pub value: Vec<String>,
+}
+
+/// A chunk of the extrinsics produced by the `chainHead_body` method.
+///
+/// The extrinsics of the last chunk are reported by the `OperationBodyDone` event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationBodyItems {
+	/// The operation id of the event.
+	pub operation_id: String,
+	/// Array of hexadecimal-encoded scale-encoded extrinsics found in the block.
+	pub value: Vec<String>,
+}
+
+/// The response of the `chainHead_call` method.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationCallDone {
+	/// The operation id of the event.
+	pub operation_id: String,
+	/// Hexadecimal-encoded output of the runtime function call.
+	pub output: String,
+	/// The execution statistics of the runtime function call.
+	///
+	/// # Note
+	///
+	/// This is present only if the `withStats` call option is set.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	#[serde(default)]
+	pub stats: Option<CallStats>,
+	/// The output of the runtime function call decoded as JSON.
+	///
+	/// # Note
+	///
+	/// This is present only if the `decode` call option is set.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	#[serde(default)]
+	pub decoded: Option<serde_json::Value>,
+}
+
+/// The execution statistics of a `chainHead_call` method.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallStats {
+	/// The time in microseconds spent executing the runtime function call.
+	///
+	/// This includes the overhead of recording the storage proof.
+	pub execution_time_micros: u64,
+	/// The encoded size in bytes of the storage proof recorded while executing the call.
+	pub proof_size: u64,
+	/// The benchmarked weight of the extrinsic applied by the call.
+	///
+	/// # Note
+	///
+	/// This is present only for the `BlockBuilder_apply_extrinsic` calls, if the runtime
+	/// implements the version 3 or later of `TransactionPaymentApi`.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	#[serde(default)]
+	pub weight: Option<CallWeight>,
+}
+
+/// The benchmarked weight of an extrinsic, as reported by the runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallWeight {
+	/// The computational time in picoseconds.
+	pub ref_time: u64,
+	/// The size in bytes of the storage proof.
+	pub proof_size: u64,
+}
+
+/// The context in which the runtime call of the `chainHead_call` method is executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CallContextType {
+	/// The call is executed in an offchain context.
+	Offchain,
+	/// The call is executed in an on-chain context, like importing a block.
+	Onchain,
+}
+
+/// The options of the `chainHead_call` method.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallOptions {
+	/// Report the execution statistics of the call in the `operationCallDone` event.
+	#[serde(default)]
+	pub with_stats: bool,
+	/// The context of the call, the context configured by the node if `None`.
+	#[serde(default)]
+	pub call_context: Option<CallContextType>,
+	/// Decode the output of the call as JSON using the runtime metadata of the block.
+	#[serde(default)]
+	pub decode: bool,
+}
+
+/// The options of the `chainHead_storage` method.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageOptions {
+	/// Report the read proof of the items in the `operationStorageItems` events.
+	#[serde(default)]
+	pub include_proof: bool,
+}
+
+/// The response of the `chainHead_storage` method.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationStorageItems {
+	/// The operation id of the event.
+	pub operation_id: String,
+	/// The resulting items.
+	pub items: Vec<StorageResult>,
+	/// Hexadecimal-encoded trie nodes proving the keys of the items against the state root
+	/// of the block.
+	///
+	/// # Note
+	///
+	/// This is present only if the `includeProof` storage option is set.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	#[serde(default)]
+	pub proof: Option<Vec<String>>,
+}
+
+/// Indicate a problem during the operation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationError {
+	/// The operation id of the event.
+	pub operation_id: String,
+	/// The reason of the error.
+	pub error: String,
+}
+
+/// The event generated by the `follow` method.
+///
+/// The block events are generated in the following order:
+/// 1. Initialized - generated only once to signal the latest finalized block
+/// 2. NewBlock - a new block was added.
+/// 3. BestBlockChanged - indicate that the best block is now the one from this event. The block was
+///    announced priorly with the `NewBlock` event.
+/// 4. Finalized - State the finalized and pruned blocks.
+///
+/// The following events are related to operations:
+/// - OperationBodyItems: Extrinsics produced by the `chainHead_body` before the
+///   OperationBodyDone
+/// - OperationBodyDone: The response of the `chianHead_body`
+/// - OperationCallDone: The response of the `chianHead_call`
+/// - OperationStorageItems: Items produced by the `chianHead_storage`
+/// - OperationWaitingForContinue: Generated after OperationStorageItems and requires the user to
+///   call `chainHead_continue`
+/// - OperationStorageDone: The `chianHead_storage` method has produced all the results
+/// - OperationInaccessible: The server was unable to provide the result, retries might succeed in
+///   the future
+/// - OperationTimeout: The operation did not complete in the time allowed by the server, retries
+///   might succeed in the future
+/// - OperationError: The server encountered an error, retries will not succeed
+///
+/// The stop event indicates that the JSON-RPC server was unable to provide a consistent list of
+/// the blocks at the head of the chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "event")]
+pub enum FollowEvent<Hash> {
+	/// The latest finalized block.
+	///
+	/// This event is generated only once.
+	Initialized(Initialized<Hash>),
+	/// A new non-finalized block was added.
+	NewBlock(NewBlock<Hash>),
+	/// The best block of the chain.
+	BestBlockChanged(BestBlockChanged<Hash>),
+	/// A list of finalized and pruned blocks.
+	Finalized(Finalized<Hash>),
+	/// The summary of the blocks finalized during a major sync.
+	CatchUp(CatchUp<Hash>),
+	/// Yield a chunk of the extrinsics of the `chainHead_body` method.
+	OperationBodyItems(OperationBodyItems),
+	/// The response of the `chainHead_body` method.
+	OperationBodyDone(OperationBodyDone),
+	/// The response of the `chainHead_call` method.
+	OperationCallDone(OperationCallDone),
+	/// Yield one or more items found in the storage.
+	OperationStorageItems(OperationStorageItems),
+	/// Ask the user to call `chainHead_continue` to produce more events
+	/// regarding the operation id.
+	OperationWaitingForContinue(OperationId),
+	/// The responses of the `chainHead_storage` method have been produced.
+	OperationStorageDone(OperationId),
+	/// The RPC server was unable to provide the response of the following operation id.
+	///
+	/// Repeating the same operation in the future might succeed.
+	OperationInaccessible(OperationId),
+	/// The operation did not complete within the time allowed by the RPC server.
+	///
+	/// Repeating the same operation in the future might succeed.
+	OperationTimeout(OperationId),
+	/// The RPC server encountered an error while processing an operation id.
+	///
+	/// Repeating the same operation in the future will not succeed.
+	OperationError(OperationError),
+	/// The subscription is dropped and no further events
+	/// will be generated.
+	Stop,
+}
+
+/// The storage item received as paramter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageQuery<Key> {
+	/// The provided key.
+	pub key: Key,
+	/// The type of the storage query.
+	#[serde(flatten)]
+	pub query_type: StorageQueryType,
+}
+
+/// The type of the storage query.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "type")]
+pub enum StorageQueryType {
+	/// Fetch the value of the provided key.
+	Value,
+	/// Fetch the hash of the value of the provided key.
+	Hash,
+	/// Fetch the closest descendant merkle value.
+	ClosestDescendantMerkleValue,
+	/// Fetch the values of all descendants of they provided key.
+	DescendantsValues,
+	/// Fetch the hashes of the values of all descendants of they provided key.
+	DescendantsHashes,
+	/// Check if the provided key has a value, without fetching the value.
+	Exists,
+	/// Fetch the length of the value of the provided key, without fetching the value.
+	ValueLength,
+	/// Fetch the values of the provided key at the block and at up to `ancestors`
+	/// of its ancestors.
+	AncestorValues {
+		/// The number of ancestors queried.
+		ancestors: u32,
+	},
+}
+
+/// The storage result.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageResult {
+	/// The hex-encoded key of the result.
+	pub key: String,
+	/// The result of the query.
+	#[serde(flatten)]
+	pub result: StorageResultType,
+}
+
+/// The type of the storage query.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageResultType {
+	/// Fetch the value of the provided key.
+	Value(String),
+	/// Fetch the hash of the value of the provided key.
+	Hash(String),
+	/// Fetch the closest descendant merkle value.
+	ClosestDescendantMerkleValue(String),
+	/// Check if the provided key has a value.
+	Exists(bool),
+	/// Fetch the length of the value of the provided key.
+	ValueLength(u32),
+	/// Fetch the values of the provided key, from the queried block to its oldest ancestor.
+	///
+	/// The value is `None` for the blocks where the key is absent.
+	AncestorValues(Vec<Option<String>>),
+}
+
+/// The method respose of `chainHead_body`, `chainHead_call` and `chainHead_storage`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "result")]
+pub enum MethodResponse {
+	/// The method has started.
+	Started(MethodResponseStarted),
+	/// The RPC server cannot handle the request at the moment.
+	LimitReached,
+}
+
+/// The `started` result of a method.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MethodResponseStarted {
+	/// The operation id of the response.
+	pub operation_id: String,
+	/// The number of items from the back of the `chainHead_storage` that have been discarded.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	#[serde(default)]
+	pub discarded_items: Option<usize>,
+}
+
+/// A block pinned by a `chainHead_follow` subscription.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedBlock<Hash> {
+	/// The hash of the pinned block.
+	pub hash: Hash,
+	/// The time in milliseconds since the block was pinned.
+	pub pin_age_millis: u64,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn follow_initialized_event_no_updates() {
+		// Runtime flag is false.
+		let event: FollowEvent<String> = FollowEvent::Initialized(Initialized {
+			finalized_block_hashes: vec!["0x1".into(), "0x2".into()],
+			finalized_block_runtime: None,
+			with_runtime: false,
+			resumption_token: None,
+		});
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"event":"initialized","finalizedBlockHashes":["0x1","0x2"]}"#;
+		assert_eq!(ser, exp);
+
+		let event_dec: FollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+	}
+
+	#[test]
+	fn follow_initialized_event_with_updates() {
+		// Runtime flag is true, block runtime must always be reported for this event.
+		let runtime = RuntimeVersion {
+			spec_name: "ABC".into(),
+			impl_name: "Impl".into(),
+			spec_version: 1,
+			..Default::default()
+		};
+
+		let runtime_event = RuntimeEvent::Valid(RuntimeVersionEvent { spec: runtime });
+		let mut initialized = Initialized {
+			finalized_block_hashes: vec!["0x1".into()],
+			finalized_block_runtime: Some(runtime_event),
+			with_runtime: true,
+			resumption_token: None,
+		};
+		let event: FollowEvent<String> = FollowEvent::Initialized(initialized.clone());
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = concat!(
+			r#"{"event":"initialized","finalizedBlockHashes":["0x1"],"#,
+			r#""finalizedBlockRuntime":{"type":"valid","spec":{"specName":"ABC","implName":"Impl","authoringVersion":0,"#,
+			r#""specVersion":1,"implVersion":0,"apis":[],"transactionVersion":0,"stateVersion":0}}}"#,
+		);
+		assert_eq!(ser, exp);
+
+		let event_dec: FollowEvent<String> = serde_json::from_str(exp).unwrap();
+		// The `with_runtime` field is used for serialization purposes.
+		initialized.with_runtime = false;
+		assert!(matches!(
+			event_dec, FollowEvent::Initialized(ref dec) if dec == &initialized
+		));
+	}
+
+	#[test]
+	fn follow_initialized_event_with_resumption_token() {
+		let event: FollowEvent<String> = FollowEvent::Initialized(Initialized {
+			finalized_block_hashes: vec!["0x1".into()],
+			finalized_block_runtime: None,
+			with_runtime: false,
+			resumption_token: Some("0xab".into()),
+		});
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp =
+			r#"{"event":"initialized","finalizedBlockHashes":["0x1"],"resumptionToken":"0xab"}"#;
+		assert_eq!(ser, exp);
+
+		let event_dec: FollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+	}
+
+	#[test]
+	fn follow_new_block_event_no_updates() {
+		// Runtime flag is false.
+		let event: FollowEvent<String> = FollowEvent::NewBlock(NewBlock {
+			block_hash: "0x1".into(),
+			parent_block_hash: "0x2".into(),
+			new_runtime: None,
+			with_runtime: false,
+			header: None,
+			extrinsics_count: None,
+		});
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"event":"newBlock","blockHash":"0x1","parentBlockHash":"0x2"}"#;
+		assert_eq!(ser, exp);
+
+		let event_dec: FollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+	}
+
+	#[test]
+	fn follow_new_block_event_with_updates() {
+		// Runtime flag is true, block runtime must always be reported for this event.
+		let runtime = RuntimeVersion {
+			spec_name: "ABC".into(),
+			impl_name: "Impl".into(),
+			spec_version: 1,
+			..Default::default()
+		};
+
+		let runtime_event = RuntimeEvent::Valid(RuntimeVersionEvent { spec: runtime });
+		let mut new_block = NewBlock {
+			block_hash: "0x1".into(),
+			parent_block_hash: "0x2".into(),
+			new_runtime: Some(runtime_event),
+			with_runtime: true,
+			header: None,
+			extrinsics_count: None,
+		};
+
+		let event: FollowEvent<String> = FollowEvent::NewBlock(new_block.clone());
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = concat!(
+			r#"{"event":"newBlock","blockHash":"0x1","parentBlockHash":"0x2","#,
+			r#""newRuntime":{"type":"valid","spec":{"specName":"ABC","implName":"Impl","authoringVersion":0,"#,
+			r#""specVersion":1,"implVersion":0,"apis":[],"transactionVersion":0,"stateVersion":0}}}"#,
+		);
+		assert_eq!(ser, exp);
+
+		let event_dec: FollowEvent<String> = serde_json::from_str(exp).unwrap();
+		// The `with_runtime` field is used for serialization purposes.
+		new_block.with_runtime = false;
+		assert!(matches!(
+			event_dec, FollowEvent::NewBlock(ref dec) if dec == &new_block
+		));
+
+		// Runtime flag is true, runtime didn't change compared to parent.
+		let mut new_block = NewBlock {
+			block_hash: "0x1".into(),
+			parent_block_hash: "0x2".into(),
+			new_runtime: None,
+			with_runtime: true,
+			header: None,
+			extrinsics_count: None,
+		};
+		let event: FollowEvent<String> = FollowEvent::NewBlock(new_block.clone());
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp =
+			r#"{"event":"newBlock","blockHash":"0x1","parentBlockHash":"0x2","newRuntime":null}"#;
+		assert_eq!(ser, exp);
+		new_block.with_runtime = false;
+		let event_dec: FollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert!(matches!(
+			event_dec, FollowEvent::NewBlock(ref dec) if dec == &new_block
+		));
+	}
+
+	#[test]
+	fn follow_new_block_event_with_header() {
+		let event: FollowEvent<String> = FollowEvent::NewBlock(NewBlock {
+			block_hash: "0x1".into(),
+			parent_block_hash: "0x2".into(),
+			new_runtime: None,
+			with_runtime: false,
+			header: Some("0x3".into()),
+			extrinsics_count: Some(4),
+		});
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = concat!(
+			r#"{"event":"newBlock","blockHash":"0x1","parentBlockHash":"0x2","#,
+			r#""header":"0x3","extrinsicsCount":4}"#,
+		);
+		assert_eq!(ser, exp);
+
+		let event_dec: FollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+	}
+
+	#[test]
+	fn follow_best_block_changed_event() {
+		let event: FollowEvent<String> =
+			FollowEvent::BestBlockChanged(BestBlockChanged { best_block_hash: "0x1".into() });
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"event":"bestBlockChanged","bestBlockHash":"0x1"}"#;
+		assert_eq!(ser, exp);
+
+		let event_dec: FollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+	}
+
+	#[test]
+	fn follow_finalized_event() {
+		let event: FollowEvent<String> = FollowEvent::Finalized(Finalized {
+			finalized_block_hashes: vec!["0x1".into()],
+			pruned_block_hashes: vec!["0x2".into()],
+		});
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp =
+			r#"{"event":"finalized","finalizedBlockHashes":["0x1"],"prunedBlockHashes":["0x2"]}"#;
+		assert_eq!(ser, exp);
+
+		let event_dec: FollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+	}
+
+	#[test]
+	fn follow_catch_up_event() {
+		let event: FollowEvent<String> = FollowEvent::CatchUp(CatchUp {
+			finalized_block_hash: "0x1".into(),
+			finalized_block_runtime: None,
+			pruned_block_hashes: vec!["0x2".into()],
+		});
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"event":"catchUp","finalizedBlockHash":"0x1","prunedBlockHashes":["0x2"]}"#;
+		assert_eq!(ser, exp);
+
+		let event_dec: FollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+	}
+
+	#[test]
+	fn follow_op_body_event() {
+		let event: FollowEvent<String> = FollowEvent::OperationBodyDone(OperationBodyDone {
+			operation_id: "123".into(),
+			value: vec!["0x1".into()],
+		});
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"event":"operationBodyDone","operationId":"123","value":["0x1"]}"#;
+		assert_eq!(ser, exp);
+
+		let event_dec: FollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+
+		// Each extrinsic of the body is a separate array item.
+		let event: FollowEvent<String> = FollowEvent::OperationBodyDone(OperationBodyDone {
+			operation_id: "123".into(),
+			value: vec!["0x1".into(), "0x0203".into()],
+		});
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"event":"operationBodyDone","operationId":"123","value":["0x1","0x0203"]}"#;
+		assert_eq!(ser, exp);
+
+		let event_dec: FollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+	}
+
+	#[test]
+	fn follow_op_body_items_event() {
+		let event: FollowEvent<String> = FollowEvent::OperationBodyItems(OperationBodyItems {
+			operation_id: "123".into(),
+			value: vec!["0x1".into()],
+		});
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"event":"operationBodyItems","operationId":"123","value":["0x1"]}"#;
+		assert_eq!(ser, exp);
+
+		let event_dec: FollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+	}
+
+	#[test]
+	fn follow_op_call_event() {
+		let event: FollowEvent<String> = FollowEvent::OperationCallDone(OperationCallDone {
+			operation_id: "123".into(),
+			output: "0x1".into(),
+			stats: None,
+			decoded: None,
+		});
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"event":"operationCallDone","operationId":"123","output":"0x1"}"#;
+		assert_eq!(ser, exp);
+
+		let event_dec: FollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+
+		// Call with execution statistics.
+		let event: FollowEvent<String> = FollowEvent::OperationCallDone(OperationCallDone {
+			operation_id: "123".into(),
+			output: "0x1".into(),
+			stats: Some(CallStats { execution_time_micros: 10, proof_size: 20, weight: None }),
+			decoded: None,
+		});
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = concat!(
+			r#"{"event":"operationCallDone","operationId":"123","output":"0x1","#,
+			r#""stats":{"executionTimeMicros":10,"proofSize":20}}"#,
+		);
+		assert_eq!(ser, exp);
+
+		let event_dec: FollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+
+		// Call with the benchmarked weight.
+		let event: FollowEvent<String> = FollowEvent::OperationCallDone(OperationCallDone {
+			operation_id: "123".into(),
+			output: "0x1".into(),
+			stats: Some(CallStats {
+				execution_time_micros: 10,
+				proof_size: 20,
+				weight: Some(CallWeight { ref_time: 30, proof_size: 40 }),
+			}),
+			decoded: None,
+		});
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = concat!(
+			r#"{"event":"operationCallDone","operationId":"123","output":"0x1","#,
+			r#""stats":{"executionTimeMicros":10,"proofSize":20,"#,
+			r#""weight":{"refTime":30,"proofSize":40}}}"#,
+		);
+		assert_eq!(ser, exp);
+
+		let event_dec: FollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+
+		// Call with the decoded output.
+		let event: FollowEvent<String> = FollowEvent::OperationCallDone(OperationCallDone {
+			operation_id: "123".into(),
+			output: "0x0100".into(),
+			stats: None,
+			decoded: Some(serde_json::json!({ "Some": 0 })),
+		});
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = concat!(
+			r#"{"event":"operationCallDone","operationId":"123","output":"0x0100","#,
+			r#""decoded":{"Some":0}}"#,
+		);
+		assert_eq!(ser, exp);
+
+		let event_dec: FollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+	}
+
+	#[test]
+	fn chain_head_call_options() {
+		let options: CallOptions = serde_json::from_str(r#"{"withStats":true}"#).unwrap();
+		assert_eq!(options, CallOptions { with_stats: true, call_context: None, decode: false });
+
+		let options: CallOptions = serde_json::from_str(r#"{"callContext":"onchain"}"#).unwrap();
+		assert_eq!(
+			options,
+			CallOptions {
+				with_stats: false,
+				call_context: Some(CallContextType::Onchain),
+				decode: false
+			}
+		);
+
+		let options: CallOptions = serde_json::from_str(r#"{"decode":true}"#).unwrap();
+		assert_eq!(options, CallOptions { with_stats: false, call_context: None, decode: true });
+
+		let options: CallOptions = serde_json::from_str("{}").unwrap();
+		assert_eq!(options, CallOptions::default());
+	}
+
+	#[test]
+	fn follow_op_storage_items_event() {
+		let event: FollowEvent<String> =
+			FollowEvent::OperationStorageItems(OperationStorageItems {
+				operation_id: "123".into(),
+				items: vec![StorageResult {
+					key: "0x1".into(),
+					result: StorageResultType::Value("0x123".to_string()),
+				}],
+				proof: None,
+			});
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"event":"operationStorageItems","operationId":"123","items":[{"key":"0x1","value":"0x123"}]}"#;
+		assert_eq!(ser, exp);
+
+		let event_dec: FollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+	}
+
+	#[test]
+	fn follow_op_storage_items_event_with_proof() {
+		let event: FollowEvent<String> =
+			FollowEvent::OperationStorageItems(OperationStorageItems {
+				operation_id: "123".into(),
+				items: vec![StorageResult {
+					key: "0x1".into(),
+					result: StorageResultType::Value("0x123".to_string()),
+				}],
+				proof: Some(vec!["0x80".into()]),
+			});
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = concat!(
+			r#"{"event":"operationStorageItems","operationId":"123","#,
+			r#""items":[{"key":"0x1","value":"0x123"}],"proof":["0x80"]}"#,
+		);
+		assert_eq!(ser, exp);
+
+		let event_dec: FollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+
+		let options: StorageOptions = serde_json::from_str(r#"{"includeProof":true}"#).unwrap();
+		assert_eq!(options, StorageOptions { include_proof: true });
+	}
+
+	#[test]
+	fn follow_op_wait_event() {
+		let event: FollowEvent<String> =
+			FollowEvent::OperationWaitingForContinue(OperationId { operation_id: "123".into() });
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"event":"operationWaitingForContinue","operationId":"123"}"#;
+		assert_eq!(ser, exp);
+
+		let event_dec: FollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+	}
+
+	#[test]
+	fn follow_op_storage_done_event() {
+		let event: FollowEvent<String> =
+			FollowEvent::OperationStorageDone(OperationId { operation_id: "123".into() });
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"event":"operationStorageDone","operationId":"123"}"#;
+		assert_eq!(ser, exp);
+
+		let event_dec: FollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+	}
+
+	#[test]
+	fn follow_op_inaccessible_event() {
+		let event: FollowEvent<String> =
+			FollowEvent::OperationInaccessible(OperationId { operation_id: "123".into() });
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"event":"operationInaccessible","operationId":"123"}"#;
+		assert_eq!(ser, exp);
+
+		let event_dec: FollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+	}
+
+	#[test]
+	fn follow_op_timeout_event() {
+		let event: FollowEvent<String> =
+			FollowEvent::OperationTimeout(OperationId { operation_id: "123".into() });
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"event":"operationTimeout","operationId":"123"}"#;
+		assert_eq!(ser, exp);
+
+		let event_dec: FollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+	}
+
+	#[test]
+	fn follow_op_error_event() {
+		let event: FollowEvent<String> = FollowEvent::OperationError(OperationError {
+			operation_id: "123".into(),
+			error: "reason".into(),
+		});
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"event":"operationError","operationId":"123","error":"reason"}"#;
+		assert_eq!(ser, exp);
+
+		let event_dec: FollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+	}
+
+	#[test]
+	fn follow_stop_event() {
+		let event: FollowEvent<String> = FollowEvent::Stop;
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"event":"stop"}"#;
+		assert_eq!(ser, exp);
+
+		let event_dec: FollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+	}
+
+	#[test]
+	fn method_response() {
+		// Response of `call` and `body`
+		let event = MethodResponse::Started(MethodResponseStarted {
+			operation_id: "123".into(),
+			discarded_items: None,
+		});
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"result":"started","operationId":"123"}"#;
+		assert_eq!(ser, exp);
+
+		let event_dec: MethodResponse = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+
+		// Response of `storage`
+		let event = MethodResponse::Started(MethodResponseStarted {
+			operation_id: "123".into(),
+			discarded_items: Some(1),
+		});
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"result":"started","operationId":"123","discardedItems":1}"#;
+		assert_eq!(ser, exp);
+
+		let event_dec: MethodResponse = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+
+		// Limit reached.
+		let event = MethodResponse::LimitReached;
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"result":"limitReached"}"#;
+		assert_eq!(ser, exp);
+
+		let event_dec: MethodResponse = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+	}
+
+	#[test]
+	fn pinned_block() {
+		let block = PinnedBlock { hash: "0x1".to_string(), pin_age_millis: 42 };
+
+		let ser = serde_json::to_string(&block).unwrap();
+		let exp = r#"{"hash":"0x1","pinAgeMillis":42}"#;
+		assert_eq!(ser, exp);
+
+		let block_dec: PinnedBlock<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(block_dec, block);
+	}
+
+	#[test]
+	fn chain_head_storage_query() {
+		// Item with Value.
+		let item = StorageQuery { key: "0x1", query_type: StorageQueryType::Value };
+		// Encode
+		let ser = serde_json::to_string(&item).unwrap();
+		let exp = r#"{"key":"0x1","type":"value"}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: StorageQuery<&str> = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, item);
+
+		// Item with Hash.
+		let item = StorageQuery { key: "0x1", query_type: StorageQueryType::Hash };
+		// Encode
+		let ser = serde_json::to_string(&item).unwrap();
+		let exp = r#"{"key":"0x1","type":"hash"}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: StorageQuery<&str> = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, item);
+
+		// Item with DescendantsValues.
+		let item = StorageQuery { key: "0x1", query_type: StorageQueryType::DescendantsValues };
+		// Encode
+		let ser = serde_json::to_string(&item).unwrap();
+		let exp = r#"{"key":"0x1","type":"descendantsValues"}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: StorageQuery<&str> = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, item);
+
+		// Item with DescendantsHashes.
+		let item = StorageQuery { key: "0x1", query_type: StorageQueryType::DescendantsHashes };
+		// Encode
+		let ser = serde_json::to_string(&item).unwrap();
+		let exp = r#"{"key":"0x1","type":"descendantsHashes"}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: StorageQuery<&str> = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, item);
+
+		// Item with Merkle.
+		let item =
+			StorageQuery { key: "0x1", query_type: StorageQueryType::ClosestDescendantMerkleValue };
+		// Encode
+		let ser = serde_json::to_string(&item).unwrap();
+		let exp = r#"{"key":"0x1","type":"closestDescendantMerkleValue"}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: StorageQuery<&str> = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, item);
+
+		// Item with Exists.
+		let item = StorageQuery { key: "0x1", query_type: StorageQueryType::Exists };
+		// Encode
+		let ser = serde_json::to_string(&item).unwrap();
+		let exp = r#"{"key":"0x1","type":"exists"}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: StorageQuery<&str> = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, item);
+
+		// Item with ValueLength.
+		let item = StorageQuery { key: "0x1", query_type: StorageQueryType::ValueLength };
+		// Encode
+		let ser = serde_json::to_string(&item).unwrap();
+		let exp = r#"{"key":"0x1","type":"valueLength"}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: StorageQuery<&str> = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, item);
+
+		// Item with AncestorValues.
+		let item = StorageQuery {
+			key: "0x1",
+			query_type: StorageQueryType::AncestorValues { ancestors: 2 },
+		};
+		// Encode
+		let ser = serde_json::to_string(&item).unwrap();
+		let exp = r#"{"key":"0x1","type":"ancestorValues","ancestors":2}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: StorageQuery<&str> = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, item);
+	}
+
+	#[test]
+	fn chain_head_storage_result() {
+		// Item with Value.
+		let item =
+			StorageResult { key: "0x1".into(), result: StorageResultType::Value("res".into()) };
+		// Encode
+		let ser = serde_json::to_string(&item).unwrap();
+		let exp = r#"{"key":"0x1","value":"res"}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: StorageResult = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, item);
+
+		// Item with Hash.
+		let item =
+			StorageResult { key: "0x1".into(), result: StorageResultType::Hash("res".into()) };
+		// Encode
+		let ser = serde_json::to_string(&item).unwrap();
+		let exp = r#"{"key":"0x1","hash":"res"}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: StorageResult = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, item);
+
+		// Item with DescendantsValues.
+		let item = StorageResult {
+			key: "0x1".into(),
+			result: StorageResultType::ClosestDescendantMerkleValue("res".into()),
+		};
+		// Encode
+		let ser = serde_json::to_string(&item).unwrap();
+		let exp = r#"{"key":"0x1","closestDescendantMerkleValue":"res"}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: StorageResult = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, item);
+
+		// Item with Exists.
+		let item = StorageResult { key: "0x1".into(), result: StorageResultType::Exists(true) };
+		// Encode
+		let ser = serde_json::to_string(&item).unwrap();
+		let exp = r#"{"key":"0x1","exists":true}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: StorageResult = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, item);
+
+		// Item with ValueLength.
+		let item = StorageResult { key: "0x1".into(), result: StorageResultType::ValueLength(4) };
+		// Encode
+		let ser = serde_json::to_string(&item).unwrap();
+		let exp = r#"{"key":"0x1","valueLength":4}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: StorageResult = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, item);
+
+		// Item with AncestorValues.
+		let item = StorageResult {
+			key: "0x1".into(),
+			result: StorageResultType::AncestorValues(vec![Some("res".into()), None]),
+		};
+		// Encode
+		let ser = serde_json::to_string(&item).unwrap();
+		let exp = r#"{"key":"0x1","ancestorValues":["res",null]}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: StorageResult = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, item);
+	}
+}
@@ -0,0 +1,138 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The decoded system events returned as json compatible objects.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sp_std::{alloc::string::String, prelude::*};
+
+/// Select the events of a pallet, or a single event of a pallet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventFilter {
+	/// The name of the pallet, as declared by the runtime.
+	pub pallet: String,
+	/// The name of the event, all the events of the pallet are selected if `None`.
+	#[serde(skip_serializing_if = "Option::is_none", default)]
+	pub event: Option<String>,
+}
+
+impl EventFilter {
+	/// Returns true if the event of the pallet is selected by the filter.
+	pub fn matches(&self, pallet: &str, event: &str) -> bool {
+		self.pallet == pallet && self.event.as_ref().map_or(true, |name| name == event)
+	}
+}
+
+/// An event deposited by the runtime, decoded using the runtime metadata.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemEvent {
+	/// The name of the pallet that deposited the event.
+	pub pallet: String,
+	/// The name of the event.
+	pub event: String,
+	/// The fields of the event, null if the event has no fields.
+	pub fields: Value,
+	/// The phase of the block execution in which the event was deposited.
+	pub phase: Value,
+	/// The topics of the event.
+	pub topics: Value,
+}
+
+/// The events deposited by a block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockEvents<Hash> {
+	/// The hash of the block.
+	pub hash: Hash,
+	/// The events deposited by the block, in order, that were selected by the filters.
+	pub events: Vec<SystemEvent>,
+}
+
+/// The events of a block could not be decoded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockEventsError<Hash> {
+	/// The hash of the block.
+	pub hash: Hash,
+	/// Reason of the error.
+	pub error: String,
+}
+
+/// The event generated by the `events_unstable_follow` subscription for each block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "event")]
+pub enum SystemEventsEvent<Hash> {
+	/// The decoded events of a block.
+	Block(BlockEvents<Hash>),
+	/// The events of a block could not be decoded.
+	///
+	/// The subscription continues with the following blocks.
+	Error(BlockEventsError<Hash>),
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn event_filter() {
+		let filter: EventFilter = serde_json::from_str(r#"{"pallet":"Balances"}"#).unwrap();
+		assert!(filter.matches("Balances", "Transfer"));
+		assert!(!filter.matches("System", "Transfer"));
+
+		let filter: EventFilter =
+			serde_json::from_str(r#"{"pallet":"Balances","event":"Transfer"}"#).unwrap();
+		assert!(filter.matches("Balances", "Transfer"));
+		assert!(!filter.matches("Balances", "Deposit"));
+	}
+
+	#[test]
+	fn system_events_event() {
+		let event = SystemEventsEvent::Block(BlockEvents {
+			hash: "0x1",
+			events: vec![SystemEvent {
+				pallet: "System".into(),
+				event: "CodeUpdated".into(),
+				fields: Value::Null,
+				phase: "Finalization".into(),
+				topics: Value::Array(vec![]),
+			}],
+		});
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"event":"block","hash":"0x1","events":[{"pallet":"System","event":"CodeUpdated","fields":null,"phase":"Finalization","topics":[]}]}"#;
+		assert_eq!(ser, exp);
+
+		let event =
+			SystemEventsEvent::Error(BlockEventsError { hash: "0x1", error: "Failed".into() });
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"event":"error","hash":"0x1","error":"Failed"}"#;
+		assert_eq!(ser, exp);
+
+		let event_dec: SystemEventsEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(
+			event_dec,
+			SystemEventsEvent::Error(BlockEventsError {
+				hash: "0x1".into(),
+				error: "Failed".into()
+			})
+		);
+	}
+}
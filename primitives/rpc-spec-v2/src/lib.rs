@@ -0,0 +1,32 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Types of the Substrate JSON-RPC interface v2.
+//!
+//! The event and error payloads of the `archive`, `chainHead`, `events` and `transaction`
+//! methods, shared by the RPC server and its clients. The crate supports `no_std`
+//! environments by disabling the default `std` feature.
+//!
+//! Specification [document](https://paritytech.github.io/json-rpc-interface-spec/).
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![warn(missing_docs)]
+
+pub mod archive;
+pub mod chain_head;
+pub mod events;
+pub mod transaction;
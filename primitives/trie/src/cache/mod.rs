@@ -285,6 +285,29 @@ impl std::fmt::Display for HitStats {
 	}
 }
 
+impl HitStats {
+	/// Add the given stats to these stats.
+	fn accumulate(&self, other: &HitStats) {
+		for (this, other) in [
+			(&self.shared_hits, &other.shared_hits),
+			(&self.shared_fetch_attempts, &other.shared_fetch_attempts),
+			(&self.local_hits, &other.local_hits),
+			(&self.local_fetch_attempts, &other.local_fetch_attempts),
+		] {
+			this.fetch_add(other.load(Ordering::Relaxed), Ordering::Relaxed);
+		}
+	}
+
+	/// Returns the number of hits and misses gathered so far.
+	fn snapshot(&self) -> CacheHitStats {
+		let hits =
+			self.local_hits.load(Ordering::Relaxed) + self.shared_hits.load(Ordering::Relaxed);
+		let fetch_attempts = self.local_fetch_attempts.load(Ordering::Relaxed);
+
+		CacheHitStats { hits, misses: fetch_attempts.saturating_sub(hits) }
+	}
+}
+
 /// A struct to gather hit/miss stats for the node cache and the value cache.
 #[derive(Default)]
 struct TrieHitStats {
@@ -292,6 +315,40 @@ struct TrieHitStats {
 	value_cache: HitStats,
 }
 
+impl TrieHitStats {
+	/// Add the given stats to these stats.
+	fn accumulate(&self, other: &TrieHitStats) {
+		self.node_cache.accumulate(&other.node_cache);
+		self.value_cache.accumulate(&other.value_cache);
+	}
+
+	/// Returns the number of hits and misses gathered so far.
+	fn snapshot(&self) -> TrieCacheHitStats {
+		TrieCacheHitStats {
+			node_cache: self.node_cache.snapshot(),
+			value_cache: self.value_cache.snapshot(),
+		}
+	}
+}
+
+/// The number of hits and misses of a cache.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CacheHitStats {
+	/// Number of lookups served by either the local or the shared cache.
+	pub hits: u64,
+	/// Number of lookups that had to be served by the database.
+	pub misses: u64,
+}
+
+/// The number of hits and misses of the node cache and the value cache.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TrieCacheHitStats {
+	/// Hits and misses of the node cache.
+	pub node_cache: CacheHitStats,
+	/// Hits and misses of the value cache.
+	pub value_cache: CacheHitStats,
+}
+
 /// An internal struct to store the cached trie nodes.
 pub(crate) struct NodeCached<H> {
 	/// The cached node.
@@ -403,6 +460,8 @@ impl<H: Hasher> Drop for LocalTrieCache<H> {
 			self.stats.value_cache
 		);
 
+		self.shared.accumulate_hit_stats(&self.stats);
+
 		let mut shared_inner = match self.shared.write_lock_inner() {
 			Some(inner) => inner,
 			None => {
@@ -727,6 +786,43 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn hit_stats_are_accumulated_on_drop() {
+		let (db, root) = create_trie();
+
+		let shared_cache = Cache::new(CACHE_SIZE);
+		let local_cache = shared_cache.local_cache();
+
+		{
+			let mut cache = local_cache.as_trie_db_cache(root);
+			let trie = TrieDBBuilder::<Layout>::new(&db, &root).with_cache(&mut cache).build();
+			assert_eq!(TEST_DATA[0].1.to_vec(), trie.get(TEST_DATA[0].0).unwrap().unwrap());
+		}
+
+		// The stats of the local cache are only taken into account once it is dropped.
+		assert_eq!(shared_cache.hit_stats(), Default::default());
+
+		drop(local_cache);
+
+		let stats = shared_cache.hit_stats();
+		assert_eq!(stats.value_cache, CacheHitStats { hits: 0, misses: 1 });
+		assert!(stats.node_cache.misses > 0);
+
+		let local_cache = shared_cache.local_cache();
+
+		{
+			let mut cache = local_cache.as_trie_db_cache(root);
+			let trie = TrieDBBuilder::<Layout>::new(&db, &root).with_cache(&mut cache).build();
+			assert_eq!(TEST_DATA[0].1.to_vec(), trie.get(TEST_DATA[0].0).unwrap().unwrap());
+		}
+
+		drop(local_cache);
+
+		// The value is now served by the shared cache.
+		let stats = shared_cache.hit_stats();
+		assert_eq!(stats.value_cache, CacheHitStats { hits: 1, misses: 1 });
+	}
+
 	#[test]
 	fn trie_db_mut_cache_works() {
 		let (mut db, root) = create_trie();
@@ -602,11 +602,13 @@ impl<H: Hasher> SharedTrieCacheInner<H> {
 /// The instance of this object can be shared between multiple threads.
 pub struct SharedTrieCache<H: Hasher> {
 	inner: Arc<RwLock<SharedTrieCacheInner<H>>>,
+	/// The hit/miss stats of all the dropped local caches.
+	stats: Arc<super::TrieHitStats>,
 }
 
 impl<H: Hasher> Clone for SharedTrieCache<H> {
 	fn clone(&self) -> Self {
-		Self { inner: self.inner.clone() }
+		Self { inner: self.inner.clone(), stats: self.stats.clone() }
 	}
 }
 
@@ -658,6 +660,7 @@ impl<H: Hasher> SharedTrieCache<H> {
 					value_cache_max_heap_size,
 				),
 			})),
+			stats: Default::default(),
 		}
 	}
 
@@ -715,6 +718,19 @@ impl<H: Hasher> SharedTrieCache<H> {
 		node_cache_size + value_cache_size
 	}
 
+	/// Returns the hits and misses of all the local caches dropped so far.
+	///
+	/// The lookups of a [`LocalTrieCache`](super::LocalTrieCache) are only taken into account
+	/// once it is dropped.
+	pub fn hit_stats(&self) -> super::TrieCacheHitStats {
+		self.stats.snapshot()
+	}
+
+	/// Add the hit/miss stats of a dropped local cache.
+	pub(super) fn accumulate_hit_stats(&self, stats: &super::TrieHitStats) {
+		self.stats.accumulate(stats);
+	}
+
 	/// Reset the node cache.
 	pub fn reset_node_cache(&self) {
 		self.inner.write().node_cache.reset();
@@ -156,6 +156,11 @@ impl<H: Hasher> Recorder<H> {
 		self.encoded_size_estimation.load(Ordering::Relaxed)
 	}
 
+	/// Returns the number of distinct keys accessed until now, across all the tries.
+	pub fn recorded_keys_count(&self) -> usize {
+		self.inner.lock().recorded_keys.values().map(|keys| keys.len()).sum()
+	}
+
 	/// Reset the state.
 	///
 	/// This discards all recorded data.
@@ -450,11 +455,9 @@ mod tests {
 
 	impl RecorderStats {
 		fn extract(recorder: &Recorder) -> Self {
+			let recorded_keys = recorder.recorded_keys_count();
 			let inner = recorder.inner.lock();
 
-			let recorded_keys =
-				inner.recorded_keys.iter().flat_map(|(_, keys)| keys.keys()).count();
-
 			Self {
 				recorded_keys,
 				accessed_nodes: inner.accessed_nodes.len(),
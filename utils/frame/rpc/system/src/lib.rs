@@ -17,7 +17,7 @@
 
 //! System FRAME specific RPC methods.
 
-use std::{fmt::Display, sync::Arc};
+use std::{collections::HashSet, fmt::Display, sync::Arc};
 
 use codec::{self, Codec, Decode, Encode};
 use jsonrpsee::{
@@ -27,12 +27,19 @@ use jsonrpsee::{
 };
 
 use sc_rpc_api::DenyUnsafe;
-use sc_transaction_pool_api::{InPoolTransaction, TransactionPool};
+use sc_transaction_pool_api::{
+	InPoolTransaction, TransactionLongevity, TransactionPool, TransactionPriority,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use sp_api::ApiExt;
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::HeaderBackend;
 use sp_core::{hexdisplay::HexDisplay, Bytes};
-use sp_runtime::{legacy, traits};
+use sp_runtime::{
+	generic::Era,
+	legacy,
+	traits::{self, UniqueSaturatedInto},
+};
 
 pub use frame_system_rpc_runtime_api::AccountNonceApi;
 
@@ -50,6 +57,117 @@ pub trait SystemApi<BlockHash, AccountId, Nonce> {
 	/// Dry run an extrinsic at a given block. Return SCALE encoded ApplyExtrinsicResult.
 	#[method(name = "system_dryRun", aliases = ["system_dryRunAt"])]
 	async fn dry_run(&self, extrinsic: Bytes, at: Option<BlockHash>) -> RpcResult<Bytes>;
+
+	/// Returns the era, the birth block hash and the next valid index (aka nonce) of a mortal
+	/// transaction of the given account, valid for about the given number of blocks from the
+	/// best block.
+	///
+	/// The lifetime defaults to [`DEFAULT_TRANSACTION_LIFETIME`] blocks, and is rounded up to
+	/// a power of two between 4 and 65536. It must not exceed the `BlockHashCount` of the
+	/// runtime, or the transaction is immediately invalid.
+	#[method(name = "system_transactionMortality")]
+	async fn mortality(
+		&self,
+		account: AccountId,
+		lifetime: Option<u64>,
+	) -> RpcResult<TransactionMortality<BlockHash, Nonce>>;
+}
+
+/// The default lifetime of the transactions, in blocks.
+pub const DEFAULT_TRANSACTION_LIFETIME: u64 = 64;
+
+/// The parameters of a mortal transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionMortality<BlockHash, Nonce> {
+	/// The era of the transaction.
+	pub era: Era,
+	/// The SCALE encoded era, as included in the transaction.
+	pub encoded_era: Bytes,
+	/// The number of the birth block of the era.
+	pub birth_number: u64,
+	/// The hash of the birth block of the era, included in the signed payload.
+	pub birth_hash: BlockHash,
+	/// The number of the first block at which the transaction is no longer valid.
+	pub death_number: u64,
+	/// The next valid index of the account, see `system_accountNextIndex`.
+	pub nonce: Nonce,
+}
+
+/// Transaction pool RPC methods.
+#[rpc(client, server)]
+pub trait TxPoolApi<Hash, AccountId, Nonce> {
+	/// Returns the ready and the future transactions of the pool, and the statistics of the
+	/// pool.
+	///
+	/// The sender and the nonce of a transaction are decoded from the tag provided by the
+	/// `CheckNonce` signed extension, and are not reported for the unsigned transactions.
+	#[method(name = "txpool_inspect")]
+	fn inspect(&self) -> RpcResult<PoolInspection<Hash, AccountId, Nonce>>;
+
+	/// Temporarily bans the given transactions from entering the pool, and removes them from
+	/// the pool along with their dependent transactions.
+	///
+	/// Returns the hashes of the removed transactions.
+	#[method(name = "txpool_banTransactions")]
+	fn ban_transactions(&self, hashes: Vec<Hash>) -> RpcResult<Vec<Hash>>;
+
+	/// Temporarily bans the given senders from submitting transactions to the pool, and
+	/// removes their transactions from the pool along with the dependent transactions.
+	///
+	/// Returns the hashes of the removed transactions.
+	#[method(name = "txpool_banSenders")]
+	fn ban_senders(&self, senders: Vec<AccountId>) -> RpcResult<Vec<Hash>>;
+}
+
+/// A transaction of the pool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolTransactionInfo<Hash, AccountId, Nonce> {
+	/// The hash of the transaction.
+	pub hash: Hash,
+	/// The sender of the transaction, if signed.
+	#[serde(skip_serializing_if = "Option::is_none", default)]
+	pub sender: Option<AccountId>,
+	/// The nonce of the transaction, if signed.
+	#[serde(skip_serializing_if = "Option::is_none", default)]
+	pub nonce: Option<Nonce>,
+	/// The priority of the transaction.
+	pub priority: TransactionPriority,
+	/// The longevity of the transaction, i.e. the number of the block until which it is valid.
+	pub longevity: TransactionLongevity,
+	/// Whether the transaction is propagated to the peers.
+	pub propagate: bool,
+	/// The size of the encoded transaction, in bytes.
+	pub bytes: usize,
+}
+
+/// The statistics of the pool.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolStatistics {
+	/// Number of transactions in the ready queue.
+	pub ready: usize,
+	/// Sum of the sizes of the ready transactions, in bytes.
+	pub ready_bytes: usize,
+	/// Number of transactions in the future queue.
+	pub future: usize,
+	/// Sum of the sizes of the future transactions, in bytes.
+	pub future_bytes: usize,
+	/// Number of distinct senders of the signed transactions.
+	pub senders: usize,
+}
+
+/// The transactions and the statistics of the pool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolInspection<Hash, AccountId, Nonce> {
+	/// The ready transactions, in the order they would be included in a block.
+	pub ready: Vec<PoolTransactionInfo<Hash, AccountId, Nonce>>,
+	/// The future transactions, waiting for the tags they require.
+	pub future: Vec<PoolTransactionInfo<Hash, AccountId, Nonce>>,
+	/// The statistics of the pool.
+	pub statistics: PoolStatistics,
 }
 
 /// Error type of this RPC api.
@@ -58,6 +176,8 @@ pub enum Error {
 	DecodeError,
 	/// The call to runtime failed.
 	RuntimeError,
+	/// The block was not found.
+	UnknownBlock,
 }
 
 impl From<Error> for i32 {
@@ -65,6 +185,7 @@ impl From<Error> for i32 {
 		match e {
 			Error::RuntimeError => 1,
 			Error::DecodeError => 2,
+			Error::UnknownBlock => 3,
 		}
 	}
 }
@@ -172,6 +293,152 @@ where
 
 		Ok(Encode::encode(&result).into())
 	}
+
+	async fn mortality(
+		&self,
+		account: AccountId,
+		lifetime: Option<u64>,
+	) -> RpcResult<TransactionMortality<<Block as traits::Block>::Hash, Nonce>> {
+		let best_number = self.client.info().best_number.unique_saturated_into();
+		let lifetime = lifetime.unwrap_or(DEFAULT_TRANSACTION_LIFETIME);
+		let (era, birth_hash) = mortal_era(&*self.client, lifetime).map_err(|e| {
+			CallError::Custom(ErrorObject::owned(
+				Error::UnknownBlock.into(),
+				"Unable to find the birth block.",
+				Some(e.to_string()),
+			))
+		})?;
+		let nonce = self.nonce(account).await?;
+
+		Ok(TransactionMortality {
+			era,
+			encoded_era: era.encode().into(),
+			birth_number: era.birth(best_number),
+			birth_hash,
+			death_number: era.death(best_number),
+			nonce,
+		})
+	}
+}
+
+/// Returns the era of a mortal transaction valid for about `lifetime` blocks from the best
+/// block, and the hash of the birth block of the era which is included in the signed payload.
+///
+/// The lifetime is rounded up to a power of two between 4 and 65536, and the birth block may
+/// precede the best block for the long lifetimes, see [`Era::mortal`].
+pub fn mortal_era<Block, C>(client: &C, lifetime: u64) -> sp_blockchain::Result<(Era, Block::Hash)>
+where
+	Block: traits::Block,
+	C: HeaderBackend<Block>,
+{
+	let best_number = client.info().best_number.unique_saturated_into();
+	let era = Era::mortal(lifetime, best_number);
+	let birth_number = era.birth(best_number);
+	let birth_hash = client.hash(birth_number.unique_saturated_into())?.ok_or_else(|| {
+		sp_blockchain::Error::UnknownBlock(format!("Block number {}", birth_number))
+	})?;
+
+	Ok((era, birth_hash))
+}
+
+/// An implementation of the transaction pool RPC methods.
+pub struct TxPool<P, AccountId, Nonce> {
+	pool: Arc<P>,
+	deny_unsafe: DenyUnsafe,
+	_marker: std::marker::PhantomData<(AccountId, Nonce)>,
+}
+
+impl<P, AccountId, Nonce> TxPool<P, AccountId, Nonce> {
+	/// Create new `TxPool` given the transaction pool.
+	pub fn new(pool: Arc<P>, deny_unsafe: DenyUnsafe) -> Self {
+		Self { pool, deny_unsafe, _marker: Default::default() }
+	}
+}
+
+impl<P, AccountId, Nonce> TxPoolApiServer<sc_transaction_pool_api::TxHash<P>, AccountId, Nonce>
+	for TxPool<P, AccountId, Nonce>
+where
+	P: TransactionPool + 'static,
+	AccountId: Clone + Codec + Serialize + DeserializeOwned + Send + Sync + 'static,
+	Nonce: Codec + Serialize + Send + Sync + 'static,
+{
+	fn inspect(
+		&self,
+	) -> RpcResult<PoolInspection<sc_transaction_pool_api::TxHash<P>, AccountId, Nonce>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let ready = self.pool.ready().map(|tx| transaction_info(&*tx)).collect::<Vec<_>>();
+		let future =
+			self.pool.futures().iter().map(|tx| transaction_info(&**tx)).collect::<Vec<_>>();
+
+		let senders = ready
+			.iter()
+			.chain(future.iter())
+			.filter_map(|tx| tx.sender.as_ref().map(Encode::encode))
+			.collect::<HashSet<_>>()
+			.len();
+		let status = self.pool.status();
+		let statistics = PoolStatistics {
+			ready: status.ready,
+			ready_bytes: status.ready_bytes,
+			future: status.future,
+			future_bytes: status.future_bytes,
+			senders,
+		};
+
+		Ok(PoolInspection { ready, future, statistics })
+	}
+
+	fn ban_transactions(
+		&self,
+		hashes: Vec<sc_transaction_pool_api::TxHash<P>>,
+	) -> RpcResult<Vec<sc_transaction_pool_api::TxHash<P>>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		Ok(self.pool.ban_transactions(&hashes).iter().map(|tx| tx.hash().clone()).collect())
+	}
+
+	fn ban_senders(
+		&self,
+		senders: Vec<AccountId>,
+	) -> RpcResult<Vec<sc_transaction_pool_api::TxHash<P>>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let senders = senders.iter().map(Encode::encode).collect();
+		Ok(self.pool.ban_senders(senders).iter().map(|tx| tx.hash().clone()).collect())
+	}
+}
+
+/// Describe the transaction of the pool.
+///
+/// The sender and the nonce are decoded from the first tag provided by the transaction, which
+/// is the `(sender, nonce)` tag of the `CheckNonce` signed extension.
+fn transaction_info<T, AccountId, Nonce>(tx: &T) -> PoolTransactionInfo<T::Hash, AccountId, Nonce>
+where
+	T: InPoolTransaction,
+	T::Hash: Clone,
+	T::Transaction: Encode,
+	AccountId: Decode,
+	Nonce: Decode,
+{
+	let sender_and_nonce = tx.provides().get(0).and_then(|tag| {
+		let mut input = &tag[..];
+		<(AccountId, Nonce)>::decode(&mut input).ok().filter(|_| input.is_empty())
+	});
+	let (sender, nonce) = match sender_and_nonce {
+		Some((sender, nonce)) => (Some(sender), Some(nonce)),
+		None => (None, None),
+	};
+
+	PoolTransactionInfo {
+		hash: tx.hash().clone(),
+		sender,
+		nonce,
+		priority: *tx.priority(),
+		longevity: *tx.longevity(),
+		propagate: tx.is_propagable(),
+		bytes: tx.data().encoded_size(),
+	}
 }
 
 /// Adjust account nonce from state, so that tx with the nonce will be
@@ -223,7 +490,10 @@ mod tests {
 		transaction_validity::{InvalidTransaction, TransactionValidityError},
 		ApplyExtrinsicResult,
 	};
-	use substrate_test_runtime_client::{runtime::Transfer, AccountKeyring};
+	use substrate_test_runtime_client::{
+		runtime::{AccountId, Transfer},
+		AccountKeyring,
+	};
 
 	#[tokio::test]
 	async fn should_return_next_nonce_for_some_account() {
@@ -260,6 +530,36 @@ mod tests {
 		assert_eq!(nonce.unwrap(), 2);
 	}
 
+	#[tokio::test]
+	async fn should_return_mortality_for_some_account() {
+		sp_tracing::try_init_simple();
+
+		// given
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let pool =
+			BasicPool::new_full(Default::default(), true.into(), None, spawner, client.clone());
+		let genesis_hash = client.info().genesis_hash;
+
+		let accounts = System::new(client, pool, DenyUnsafe::Yes);
+
+		// when
+		let mortality = accounts.mortality(AccountKeyring::Alice.into(), Some(50)).await.unwrap();
+
+		// then
+		assert_eq!(
+			mortality,
+			TransactionMortality {
+				era: Era::Mortal(64, 0),
+				encoded_era: Era::Mortal(64, 0).encode().into(),
+				birth_number: 0,
+				birth_hash: genesis_hash,
+				death_number: 64,
+				nonce: 0u64,
+			}
+		);
+	}
+
 	#[tokio::test]
 	async fn dry_run_should_deny_unsafe() {
 		sp_tracing::try_init_simple();
@@ -334,4 +634,116 @@ mod tests {
 		let apply_res: ApplyExtrinsicResult = Decode::decode(&mut bytes.as_ref()).unwrap();
 		assert_eq!(apply_res, Err(TransactionValidityError::Invalid(InvalidTransaction::Future)));
 	}
+
+	#[tokio::test]
+	async fn inspect_should_list_ready_and_future_transactions() {
+		sp_tracing::try_init_simple();
+
+		// given
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let pool =
+			BasicPool::new_full(Default::default(), true.into(), None, spawner, client.clone());
+
+		let source = sp_runtime::transaction_validity::TransactionSource::External;
+		let new_transaction = |nonce: u64| {
+			let t = Transfer {
+				from: AccountKeyring::Alice.into(),
+				to: AccountKeyring::Bob.into(),
+				amount: 5,
+				nonce,
+			};
+			t.into_unchecked_extrinsic()
+		};
+		for nonce in [0, 1, 5] {
+			block_on(pool.submit_one(&BlockId::number(0), source, new_transaction(nonce))).unwrap();
+		}
+
+		let txpool = TxPool::<_, AccountId, u64>::new(pool.clone(), DenyUnsafe::No);
+
+		// when
+		let inspection = txpool.inspect().unwrap();
+
+		// then
+		let alice: AccountId = AccountKeyring::Alice.into();
+		let ready = inspection.ready.iter().map(|tx| (tx.sender, tx.nonce)).collect::<Vec<_>>();
+		assert_eq!(ready, vec![(Some(alice), Some(0)), (Some(alice), Some(1))]);
+		let future = inspection.future.iter().map(|tx| (tx.sender, tx.nonce)).collect::<Vec<_>>();
+		assert_eq!(future, vec![(Some(alice), Some(5))]);
+		assert_eq!(inspection.ready[0].bytes, new_transaction(0).encoded_size());
+		assert_eq!(
+			inspection.statistics,
+			PoolStatistics {
+				ready: 2,
+				ready_bytes: pool.status().ready_bytes,
+				future: 1,
+				future_bytes: pool.status().future_bytes,
+				senders: 1,
+			}
+		);
+	}
+
+	#[tokio::test]
+	async fn ban_senders_should_remove_and_reject_their_transactions() {
+		sp_tracing::try_init_simple();
+
+		// given
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let pool =
+			BasicPool::new_full(Default::default(), true.into(), None, spawner, client.clone());
+
+		let source = sp_runtime::transaction_validity::TransactionSource::External;
+		let new_transaction = |from: AccountKeyring, nonce: u64| {
+			let t =
+				Transfer { from: from.into(), to: AccountKeyring::Bob.into(), amount: 5, nonce };
+			t.into_unchecked_extrinsic()
+		};
+		block_on(pool.submit_one(
+			&BlockId::number(0),
+			source,
+			new_transaction(AccountKeyring::Alice, 0),
+		))
+		.unwrap();
+		let charlie = block_on(pool.submit_one(
+			&BlockId::number(0),
+			source,
+			new_transaction(AccountKeyring::Charlie, 0),
+		))
+		.unwrap();
+
+		let txpool = TxPool::<_, AccountId, u64>::new(pool.clone(), DenyUnsafe::No);
+
+		// when
+		let removed = txpool.ban_senders(vec![AccountKeyring::Alice.into()]).unwrap();
+
+		// then
+		assert_eq!(removed.len(), 1);
+		assert_eq!(pool.ready().map(|tx| tx.hash).collect::<Vec<_>>(), vec![charlie]);
+		let res = block_on(pool.submit_one(
+			&BlockId::number(0),
+			source,
+			new_transaction(AccountKeyring::Alice, 1),
+		));
+		assert!(res.is_err());
+	}
+
+	#[tokio::test]
+	async fn inspect_should_deny_unsafe() {
+		sp_tracing::try_init_simple();
+
+		// given
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let pool =
+			BasicPool::new_full(Default::default(), true.into(), None, spawner, client.clone());
+
+		let txpool = TxPool::<_, AccountId, u64>::new(pool, DenyUnsafe::Yes);
+
+		// when
+		let res = txpool.inspect();
+		assert_matches!(res, Err(JsonRpseeError::Call(CallError::Custom(e))) => {
+			assert!(e.message().contains("RPC call is unsafe to be called externally"));
+		});
+	}
 }